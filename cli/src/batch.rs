@@ -0,0 +1,205 @@
+use options::conventions::{VegaConvention, lattice_vega};
+use options::lattice::binomial::BinomialTree;
+use options::lattice::trinomial::TrinomialTree;
+use options::lattice::{ExerciseStyle, LatticeEngine};
+use options::report::GreeksRecord;
+use options::{OptionType, Options};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+// Rate move of the central difference giving American rho
+const RATE_BUMP: f64 = 1e-4;
+
+/// One contract per input line; keys mirror the command line flags.
+#[derive(Debug, Deserialize)]
+struct BatchContract {
+    #[serde(default)]
+    id: Option<Value>,
+    #[serde(rename = "type")]
+    option_type: OptionType,
+    strike: f64,
+    spot: f64,
+    vol: f64,
+    rate: f64,
+    expiry: f64,
+    #[serde(default)]
+    div: Option<f64>,
+    #[serde(default)]
+    american: bool,
+}
+
+/// One output line per input line, carrying either the results or the reason the line failed.
+#[derive(Debug, Serialize)]
+struct BatchResult {
+    /// One-based line number in the input
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<f64>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    greeks: Option<GreeksRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// Early-exercise Greeks off a trinomial tree, whose price is smooth enough in spot, volatility
+// and rate to difference; delta, gamma and theta come from its first step
+fn american_greeks(option: &Options) -> GreeksRecord {
+    let tree = TrinomialTree::default();
+    let style = ExerciseStyle::American;
+    let (spot, vol, rate) = (
+        option.spot_price(),
+        option.volatility(),
+        option.risk_free_rate(),
+    );
+    let on_tree = tree.greeks(option, style);
+    let price_at = |rate: f64| tree.price(&option.with_market(spot, vol, rate), style);
+    GreeksRecord {
+        delta: on_tree.delta,
+        gamma: on_tree.gamma,
+        vega: lattice_vega(&tree, option, style, VegaConvention::PerUnit),
+        theta: on_tree.theta,
+        rho: (price_at(rate + RATE_BUMP) - price_at(rate - RATE_BUMP)) / (2.0 * RATE_BUMP),
+    }
+}
+
+// Reason a parsed contract cannot be priced. JSON has no NaN or infinity, so the finiteness
+// checks only guard contracts built some other way
+fn invalid_field(contract: &BatchContract) -> Option<String> {
+    for (name, value) in [
+        ("strike", contract.strike),
+        ("spot", contract.spot),
+        ("vol", contract.vol),
+        ("expiry", contract.expiry),
+    ] {
+        if !(value.is_finite() && value > 0.0) {
+            return Some(format!("{} must be positive", name));
+        }
+    }
+    for (name, value) in [("rate", Some(contract.rate)), ("div", contract.div)] {
+        if value.is_some_and(|value| !value.is_finite()) {
+            return Some(format!("{} must be finite", name));
+        }
+    }
+    None
+}
+
+fn price_line(line: usize, text: &str) -> BatchResult {
+    let failed = |id, error: String| BatchResult {
+        line,
+        id,
+        price: None,
+        greeks: None,
+        error: Some(error),
+    };
+    let contract: BatchContract = match serde_json::from_str(text) {
+        Ok(contract) => contract,
+        Err(err) => return failed(None, err.to_string()),
+    };
+    if let Some(error) = invalid_field(&contract) {
+        return failed(contract.id, error);
+    }
+    let option = Options::new_with_type(
+        contract.option_type,
+        contract.strike,
+        contract.spot,
+        contract.vol,
+        contract.rate,
+        contract.expiry,
+        contract.div,
+    );
+    let (price, greeks) = if contract.american {
+        (
+            BinomialTree::default().american_price(&option),
+            american_greeks(&option),
+        )
+    } else {
+        (option.bs_pricing(), GreeksRecord::of(&option))
+    };
+    BatchResult {
+        line,
+        id: contract.id,
+        price: Some(price),
+        greeks: Some(greeks),
+        error: None,
+    }
+}
+
+/// Prices JSON lines one at a time, so inputs of any size run in constant memory.
+///
+/// Blank lines are skipped; malformed lines produce an error record and do not stop the batch.
+/// Returns the number of lines that failed
+pub fn run<R: BufRead, W: Write>(input: R, mut output: W) -> io::Result<usize> {
+    let mut failures = 0;
+    for (index, text) in input.lines().enumerate() {
+        let text = text?;
+        if text.trim().is_empty() {
+            continue;
+        }
+        let result = price_line(index + 1, &text);
+        failures += usize::from(result.error.is_some());
+        serde_json::to_writer(&mut output, &result)?;
+        output.write_all(b"\n")?;
+    }
+    output.flush()?;
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_lines() {
+        let input = r#"{"id": "a", "type": "call", "strike": 100, "spot": 105, "vol": 0.2, "rate": 0.05, "expiry": 1}
+
+{"type": "put", "strike": 100, "spot": 105, "vol": -0.2, "rate": 0.05, "expiry": 1}
+not json
+{"id": 7, "type": "put", "strike": 100, "spot": 95, "vol": 0.2, "rate": 0.05, "expiry": 1, "american": true}
+"#;
+        let mut out = Vec::new();
+        assert_eq!(run(input.as_bytes(), &mut out).unwrap(), 2);
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["id"], "a");
+        assert!((lines[0]["price"].as_f64().unwrap() - 13.8579).abs() < 1e-4);
+        assert!(lines[0]["delta"].as_f64().unwrap() > 0.0);
+        assert_eq!(lines[1]["line"], 3);
+        assert_eq!(lines[1]["error"], "vol must be positive");
+        assert!(lines[2]["error"].is_string());
+        assert_eq!(lines[3]["id"], 7);
+        assert!(lines[3]["price"].as_f64().unwrap() > 7.6338);
+        // early exercise pulls the put's delta further from zero than the European's
+        let european = Options::new_put(100.0, 95.0, 0.2, 0.05, 1.0, None);
+        assert!(lines[3]["delta"].as_f64().unwrap() < european.delta());
+        assert!(lines[3]["gamma"].as_f64().unwrap() > 0.0);
+        assert!(lines[3]["vega"].as_f64().unwrap() > 0.0);
+        assert!(lines[3]["rho"].as_f64().unwrap() < 0.0);
+    }
+
+    #[test]
+    fn test_rejects_non_finite_carry() {
+        let line = |rate: f64, div: Option<f64>| {
+            let text = r#"{"type": "call", "strike": 100, "spot": 105, "vol": 0.2, "rate": 0.05, "expiry": 1}"#;
+            let mut contract: BatchContract = serde_json::from_str(text).unwrap();
+            contract.rate = rate;
+            contract.div = div;
+            contract
+        };
+        assert!(invalid_field(&line(0.05, Some(0.01))).is_none());
+        assert_eq!(
+            invalid_field(&line(f64::NAN, None)).unwrap(),
+            "rate must be finite"
+        );
+        assert_eq!(
+            invalid_field(&line(0.05, Some(f64::INFINITY))).unwrap(),
+            "div must be finite"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Overnight risk runs queued in the job database an options-server works through. sled lets only
+// one process open the database, so these commands are for a server that is stopped, or a
+// scripted run that submits its jobs and works the queue itself with `jobs work`.
+use crate::{Format, emit};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use server::jobs::{JobStatus, JobStore, RISK_JOB, RiskJob, run_job};
+use server::validation::RequestLimits;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct JobsArgs {
+    /// Job database directory, the server's OPTIONS_SERVER_JOBS
+    #[arg(long, default_value = "jobs.db")]
+    store: PathBuf,
+    #[command(subcommand)]
+    command: JobsCommand,
+}
+
+#[derive(Subcommand)]
+enum JobsCommand {
+    /// Queues the risk run described by a JSON file, `-` for stdin, and prints its id
+    Submit { input: String },
+    /// Whether a job is queued, running, completed or failed
+    Status { id: u64 },
+    /// Risk report of a completed job as JSON
+    Result { id: u64 },
+    /// Runs queued jobs until the queue is empty
+    Work,
+}
+
+#[derive(Serialize)]
+struct SubmitOutput {
+    id: u64,
+}
+
+#[derive(Serialize)]
+struct StatusOutput {
+    id: u64,
+    status: JobStatus,
+}
+
+#[derive(Serialize)]
+struct WorkOutput {
+    completed: usize,
+    failed: usize,
+}
+
+pub fn run(args: JobsArgs, format: Format) -> Result<(), Box<dyn Error>> {
+    let store = JobStore::open(&args.store)?;
+    match args.command {
+        JobsCommand::Submit { input } => {
+            let text = match input.as_str() {
+                "-" => {
+                    let mut text = String::new();
+                    io::stdin().read_to_string(&mut text)?;
+                    text
+                }
+                path => fs::read_to_string(path)?,
+            };
+            let id = submit(&store, &text)?;
+            emit(format, &SubmitOutput { id })?;
+        }
+        JobsCommand::Status { id } => {
+            let status = store.status(id)?;
+            emit(format, &StatusOutput { id, status })?;
+        }
+        JobsCommand::Result { id } => println!("{}", result(&store, id)?),
+        JobsCommand::Work => emit(format, &work(&store)?)?,
+    }
+    Ok(())
+}
+
+// Queues a risk run after the checks the server applies to submitted jobs
+fn submit(store: &JobStore, text: &str) -> Result<u64, Box<dyn Error>> {
+    let job: RiskJob = serde_json::from_str(text)?;
+    job.check(&RequestLimits::default())?;
+    Ok(store.submit(RISK_JOB, serde_json::to_value(job)?)?)
+}
+
+fn result(store: &JobStore, id: u64) -> Result<String, Box<dyn Error>> {
+    let job = store.get(id)?;
+    match (job.status, job.result, job.error) {
+        (JobStatus::Completed, Some(result), _) => Ok(serde_json::to_string_pretty(&result)?),
+        (JobStatus::Failed, _, Some(error)) => Err(format!("job {} failed: {}", id, error).into()),
+        (status, _, _) => Err(format!("job {} has not finished ({:?})", id, status).into()),
+    }
+}
+
+fn work(store: &JobStore) -> Result<WorkOutput, Box<dyn Error>> {
+    let mut output = WorkOutput {
+        completed: 0,
+        failed: 0,
+    };
+    while let Some(id) = store.run_next(run_job)? {
+        match store.status(id)? {
+            JobStatus::Failed => output.failed += 1,
+            _ => output.completed += 1,
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_and_work_the_queue() {
+        let store = JobStore::temporary().unwrap();
+        let job = r#"{
+            "positions": [{
+                "underlying": "SPX", "option_type": "call", "strike": 100.0, "spot": 100.0,
+                "volatility": 0.2, "risk_free_rate": 0.05, "expiry": 1.0, "quantity": 10.0
+            }],
+            "factor_names": ["SPX"],
+            "covariance": [[0.0004]],
+            "confidence": 0.99,
+            "methods": ["parametric"]
+        }"#;
+        let id = submit(&store, job).unwrap();
+        assert_eq!(store.status(id).unwrap(), JobStatus::Queued);
+        assert!(result(&store, id).is_err());
+        // refused before reaching the queue
+        assert!(submit(&store, &job.replace("0.2,", "-0.2,")).is_err());
+        assert!(submit(&store, "{}").is_err());
+
+        let done = work(&store).unwrap();
+        assert_eq!((done.completed, done.failed), (1, 0));
+        let report: serde_json::Value = serde_json::from_str(&result(&store, id).unwrap()).unwrap();
+        assert!(report["var"][0]["var"].as_f64().unwrap() > 0.0);
+        assert_eq!(work(&store).unwrap().completed, 0);
+    }
+}
@@ -1,33 +1,579 @@
-use options::{black_scholes::black_scholes_price, Options, exotics::ConvertibleBond};
-
-fn main() {
-    println!("Black-Scholes Option Pricing CLI");
-    // create a dummy call option
-    let call_option = Options::new_call(
-        100.0,
-        105.0,
-        0.2,
-        0.05,
-        1.0,
-        None
-    );
-    let price = black_scholes_price(call_option);
-    println!("Call Option Price: {:.4}", price);
-
-    // create a dummy convertible bond
-    let convertible_bond = ConvertibleBond {
-        face_value: 100.0,
-        coupon_rate: 0.05,
-        maturity: 5.0,
-        payment_frequency: 2,
-        risk_free_rate: 0.03,
-        credit_spread: 0.02,
-        conversion_price: 50.0,
-        stock_price: 55.0,
-        volatility: 0.25,
-        time_to_maturity: 5.0,
-        dividend_yield: None
-    };
-    let cb_price = convertible_bond.bs_pricing();
-    println!("Convertible Bond Price: {:.4}", cb_price);
+mod batch;
+#[cfg(feature = "jobs")]
+mod jobs;
+#[cfg(feature = "tui")]
+mod tui;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use options::bachelier::PricingModel;
+use options::calendar::{Date, DateTime};
+use options::clock::{CalendarTime, Timezone, TradingCalendar};
+use options::conventions::{GreekUnits, ThetaConvention, VegaConvention};
+use options::lattice::binomial::BinomialTree;
+use options::report::{GreeksRecord, Table, TableStyle};
+use options::{OptionType, Options};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+#[derive(Parser)]
+#[command(
+    name = "options",
+    about = "Price vanilla options and compute their risk"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output layout of the results
+    #[arg(long, value_enum, global = true, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Premium of a contract
+    Price(PriceArgs),
+    /// Delta, gamma, vega, theta and rho of a European contract
+    Greeks(GreeksArgs),
+    /// Volatility implied by a quoted premium
+    Iv(IvArgs),
+    /// Prices one JSON contract per stdin line, streaming one JSON result per line to stdout
+    Batch,
+    /// Prices every contract of a CSV file and writes prices and Greeks as CSV
+    PriceFile {
+        input: String,
+        /// Destination file, stdout when omitted
+        output: Option<String>,
+    },
+    /// Interactive explorer of price, Greeks and payoff as the inputs move
+    #[cfg(feature = "tui")]
+    Tui(ContractArgs),
+    /// Submits, inspects and works overnight risk runs in a job database
+    #[cfg(feature = "jobs")]
+    Jobs(jobs::JobsArgs),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Markdown,
+    Json,
+}
+
+impl Format {
+    // Layout of the table formats, None for JSON
+    fn table_style(self) -> Option<TableStyle> {
+        match self {
+            Format::Table => Some(TableStyle::Text),
+            Format::Markdown => Some(TableStyle::Markdown),
+            Format::Json => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Kind {
+    Call,
+    Put,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Model {
+    BlackScholes,
+    /// Normal model, with --vol in price units
+    Bachelier,
+}
+
+impl From<Model> for PricingModel {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::BlackScholes => PricingModel::BlackScholes,
+            Model::Bachelier => PricingModel::Bachelier,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Theta {
+    /// Annualized
+    Year,
+    /// Per calendar day, a 365th of the annual decay
+    CalendarDay,
+    /// Per trading day, a 252nd of the annual decay
+    TradingDay,
+}
+
+impl From<Theta> for ThetaConvention {
+    fn from(theta: Theta) -> Self {
+        match theta {
+            Theta::Year => ThetaConvention::PerYear,
+            Theta::CalendarDay => ThetaConvention::PerCalendarDay,
+            Theta::TradingDay => ThetaConvention::PerTradingDay,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Vega {
+    /// Per 1.00 of volatility
+    Unit,
+    /// Per vol point, a 1% move in volatility
+    Point,
+}
+
+impl From<Vega> for VegaConvention {
+    fn from(vega: Vega) -> Self {
+        match vega {
+            Vega::Unit => VegaConvention::PerUnit,
+            Vega::Point => VegaConvention::PerVolPoint,
+        }
+    }
+}
+
+impl From<Kind> for OptionType {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Call => OptionType::Call,
+            Kind::Put => OptionType::Put,
+        }
+    }
+}
+
+#[derive(Args)]
+struct MarketArgs {
+    #[arg(value_enum)]
+    kind: Kind,
+    #[arg(long)]
+    strike: f64,
+    #[arg(long)]
+    spot: f64,
+    /// Continuously compounded risk-free rate
+    #[arg(long, allow_negative_numbers = true)]
+    rate: f64,
+    /// Time to expiry in years
+    #[arg(long)]
+    expiry: f64,
+    /// Continuous dividend yield
+    #[arg(long, allow_negative_numbers = true)]
+    div: Option<f64>,
+}
+
+#[derive(Args)]
+struct ContractArgs {
+    #[command(flatten)]
+    market: MarketArgs,
+    /// Annualized volatility, e.g. 0.2
+    #[arg(long)]
+    vol: f64,
+}
+
+#[derive(Args)]
+struct PriceArgs {
+    #[command(flatten)]
+    contract: ContractArgs,
+    /// Allow early exercise, priced on a binomial tree
+    #[arg(long, conflicts_with = "model")]
+    american: bool,
+    /// Model pricing the contract
+    #[arg(long, value_enum, default_value_t = Model::BlackScholes)]
+    model: Model,
+}
+
+#[derive(Args)]
+struct GreeksArgs {
+    #[command(flatten)]
+    contract: ContractArgs,
+    /// Model the Greeks are taken under, vega per unit of its volatility
+    #[arg(long, value_enum, default_value_t = Model::BlackScholes)]
+    model: Model,
+    /// Time unit theta is quoted in
+    #[arg(long, value_enum, default_value_t = Theta::Year)]
+    theta: Theta,
+    /// Volatility move vega is quoted per
+    #[arg(long, value_enum, default_value_t = Vega::Unit)]
+    vega: Vega,
+}
+
+#[derive(Args)]
+struct IvArgs {
+    #[arg(value_enum, default_value_t = Kind::Call)]
+    kind: Kind,
+    #[arg(long)]
+    strike: f64,
+    /// Spot price, the previous close when --symbol is given
+    #[arg(long, required_unless_present = "symbol")]
+    spot: Option<f64>,
+    /// Continuously compounded risk-free rate, zero when omitted with --symbol
+    #[arg(
+        long,
+        allow_negative_numbers = true,
+        required_unless_present = "symbol"
+    )]
+    rate: Option<f64>,
+    /// Time to expiry in years, the expiry date as YYYY-MM-DD, or its New York time as
+    /// YYYY-MM-DDTHH:MM
+    #[arg(long)]
+    expiry: Expiry,
+    /// Continuous dividend yield
+    #[arg(long, allow_negative_numbers = true)]
+    div: Option<f64>,
+    /// Quoted premium to invert, the live mid (or last trade) when --symbol is given
+    #[arg(long, required_unless_present = "symbol")]
+    price: Option<f64>,
+    /// Treat the premium as an American quote
+    #[arg(long, conflicts_with = "model")]
+    american: bool,
+    /// Model whose volatility is implied, a normal vol in price units for bachelier
+    #[arg(long, value_enum, default_value_t = Model::BlackScholes)]
+    model: Model,
+    /// Underlying ticker to fetch spot and the option quote for (`live-data` builds, reads
+    /// POLYGON_API_KEY)
+    #[arg(long)]
+    symbol: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Expiry {
+    Years(f64),
+    Date(Date),
+    DateTime(DateTime),
+}
+
+impl FromStr for Expiry {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.parse::<f64>() {
+            Ok(years) => Ok(Expiry::Years(years)),
+            Err(_) => text
+                .parse()
+                .map(Expiry::Date)
+                .or_else(|_| text.parse().map(Expiry::DateTime))
+                .map_err(|_| {
+                    format!(
+                        "{} is neither years nor a YYYY-MM-DD date or YYYY-MM-DDTHH:MM time",
+                        text
+                    )
+                }),
+        }
+    }
+}
+
+impl Expiry {
+    fn years(self) -> f64 {
+        match self {
+            Expiry::Years(years) => years,
+            Expiry::Date(date) => Date::today().year_fraction(date),
+            Expiry::DateTime(expiry) => {
+                CalendarTime.year_fraction(Timezone::NewYork.to_local(DateTime::now()), expiry)
+            }
+        }
+    }
+}
+
+impl MarketArgs {
+    fn to_option(&self, vol: f64) -> Options {
+        Options::new_with_type(
+            self.kind.into(),
+            self.strike,
+            self.spot,
+            vol,
+            self.rate,
+            self.expiry,
+            self.div,
+        )
+    }
+    fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("strike", self.strike),
+            ("spot", self.spot),
+            ("expiry", self.expiry),
+        ] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("--{} must be positive", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IvArgs {
+    // The contract to invert for and the premium to invert, from the flags alone
+    fn quoted(&self) -> Result<(Options, f64), Box<dyn std::error::Error>> {
+        let market = MarketArgs {
+            kind: self.kind,
+            strike: self.strike,
+            spot: self.spot.ok_or("--spot is required without --symbol")?,
+            rate: self.rate.ok_or("--rate is required without --symbol")?,
+            expiry: self.expiry.years(),
+            div: self.div,
+        };
+        market.validate()?;
+        let price = self.price.ok_or("--price is required without --symbol")?;
+        Ok((market.to_option(0.2), price))
+    }
+
+    #[cfg(not(feature = "live-data"))]
+    fn live(&self, _symbol: &str) -> Result<(Options, f64), Box<dyn std::error::Error>> {
+        Err("--symbol needs a build with the live-data feature".into())
+    }
+
+    // Spot and, unless --price is given, the premium fetched for the listed contract
+    #[cfg(feature = "live-data")]
+    fn live(&self, symbol: &str) -> Result<(Options, f64), Box<dyn std::error::Error>> {
+        use options::curves::RateCurve;
+        use options::market_data::MarketDataProvider;
+        use options::market_data::http::HttpMarketData;
+
+        let Expiry::Date(expiry) = self.expiry else {
+            return Err("--symbol needs --expiry as a YYYY-MM-DD date".into());
+        };
+        let mut market =
+            HttpMarketData::from_env()?.with_rate_curve(RateCurve::flat(self.rate.unwrap_or(0.0)));
+        if let Some(div) = self.div {
+            market = market.with_dividend_yield(symbol, div);
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let quote = runtime.block_on(async {
+            market.fetch_spot(symbol).await?;
+            market
+                .fetch_option(symbol, self.kind.into(), self.strike, expiry)
+                .await
+        })?;
+        let price = match self.price {
+            Some(price) => price,
+            None => quote
+                .premium()
+                .ok_or_else(|| format!("no bid, ask or last trade for {} {}", symbol, expiry))?,
+        };
+        let years = market.expiry_years(expiry);
+        let option = MarketArgs {
+            kind: self.kind,
+            strike: self.strike,
+            spot: self.spot.unwrap_or(market.spot(symbol)?),
+            rate: market.rate_curve(symbol)?.rate(years),
+            expiry: years,
+            div: market.dividend_yield(symbol)?,
+        };
+        option.validate()?;
+        Ok((option.to_option(0.2), price))
+    }
+}
+
+impl ContractArgs {
+    fn to_option(&self) -> Result<Options, String> {
+        self.market.validate()?;
+        if !(self.vol.is_finite() && self.vol > 0.0) {
+            return Err("--vol must be positive".to_string());
+        }
+        Ok(self.market.to_option(self.vol))
+    }
+}
+
+// Prints named values either as an aligned two-column table or as one JSON object
+fn emit<T: Serialize>(format: Format, value: &T) -> Result<(), String> {
+    let json = serde_json::to_value(value).map_err(|err| err.to_string())?;
+    match format.table_style() {
+        None => println!("{}", json),
+        Some(style) => {
+            let fields = json.as_object().ok_or("result is not a record")?;
+            let mut table = Table::new(["field", "value"]);
+            for (name, value) in fields {
+                // integers such as job ids print as they are
+                let cell = match value.as_f64() {
+                    Some(number) if value.is_f64() => format!("{:.6}", number),
+                    _ => value.to_string(),
+                };
+                table.push_row([name.clone(), cell]);
+            }
+            print!("{}", table.render(style));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct PriceOutput {
+    price: f64,
+}
+
+#[derive(Serialize)]
+struct IvOutput {
+    implied_volatility: f64,
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    match cli.command {
+        Command::Price(args) => {
+            let option = args.contract.to_option()?;
+            let price = if args.american {
+                BinomialTree::default().american_price(&option)
+            } else {
+                option.model_price(args.model.into())
+            };
+            emit(cli.format, &PriceOutput { price })?;
+        }
+        Command::Greeks(args) => {
+            let option = args.contract.to_option()?;
+            let model = args.model.into();
+            let units = GreekUnits {
+                theta: args.theta.into(),
+                vega: args.vega.into(),
+            };
+            let greeks = GreeksRecord::of_model(&option, model);
+            match cli.format.table_style() {
+                // one row labelled with the contract, its premium beside the Greeks
+                Some(style) => {
+                    let row = (option.to_string(), option.model_price(model), greeks);
+                    print!("{}", Table::greeks(&[row], units).render(style));
+                }
+                None => emit(cli.format, &greeks.in_units(units))?,
+            }
+        }
+        Command::Iv(args) => {
+            let (option, price) = match &args.symbol {
+                Some(symbol) => args.live(symbol)?,
+                None => args.quoted()?,
+            };
+            let implied_volatility = if args.american {
+                option.american_implied_volatility(price)?
+            } else {
+                option.model_implied_volatility(args.model.into(), price)?
+            };
+            emit(cli.format, &IvOutput { implied_volatility })?;
+        }
+        Command::Batch => {
+            let failures = batch::run(io::stdin().lock(), BufWriter::new(io::stdout().lock()))?;
+            if failures > 0 {
+                return Err(format!("{} lines failed", failures).into());
+            }
+        }
+        Command::PriceFile { input, output } => {
+            let portfolio = options::io::read_portfolio(File::open(input)?)?;
+            match output {
+                Some(path) => {
+                    options::io::write_results(&portfolio, BufWriter::new(File::create(path)?))?
+                }
+                None => options::io::write_results(&portfolio, io::stdout().lock())?,
+            }
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui(args) => tui::run(args.to_option()?)?,
+        #[cfg(feature = "jobs")]
+        Command::Jobs(args) => jobs::run(args, cli.format)?,
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli = Cli::try_parse_from([
+            "options", "price", "put", "--strike", "100", "--spot", "95", "--vol", "0.2", "--rate",
+            "-0.005", "--expiry", "0.5", "--format", "json",
+        ])
+        .unwrap();
+        let Command::Price(args) = cli.command else {
+            panic!("expected the price subcommand");
+        };
+        let option = args.contract.to_option().unwrap();
+        assert_eq!(option.option_type(), OptionType::Put);
+        assert_eq!(option.risk_free_rate(), -0.005);
+        assert!(matches!(cli.format, Format::Json));
+        // a normal vol in price units under the Bachelier model, which has no American tree
+        let normal = [
+            "options",
+            "price",
+            "call",
+            "--strike",
+            "100",
+            "--spot",
+            "100",
+            "--vol",
+            "20",
+            "--rate",
+            "0",
+            "--expiry",
+            "1",
+            "--model",
+            "bachelier",
+        ];
+        let Command::Price(args) = Cli::try_parse_from(normal).unwrap().command else {
+            panic!("expected the price subcommand");
+        };
+        let price = args
+            .contract
+            .to_option()
+            .unwrap()
+            .model_price(args.model.into());
+        assert!((price - 20.0 / (2.0 * std::f64::consts::PI).sqrt()).abs() < 1e-9);
+        assert!(Cli::try_parse_from(normal.iter().chain(&["--american"])).is_err());
+
+        // iv takes a premium instead of a volatility
+        assert!(
+            Cli::try_parse_from([
+                "options", "iv", "call", "--strike", "100", "--spot", "105", "--rate", "0.05",
+                "--expiry", "1", "--vol", "0.2",
+            ])
+            .is_err()
+        );
+        let cli = Cli::try_parse_from([
+            "options",
+            "iv",
+            "put",
+            "--strike",
+            "100",
+            "--spot",
+            "105",
+            "--rate",
+            "0.05",
+            "--expiry",
+            "2030-06-21",
+            "--price",
+            "4.2",
+        ])
+        .unwrap();
+        let Command::Iv(args) = cli.command else {
+            panic!("expected the iv subcommand");
+        };
+        assert!(matches!(args.expiry, Expiry::Date(date) if date.to_string() == "2030-06-21"));
+        let close: Expiry = "2030-06-21T16:00".parse().unwrap();
+        assert!(matches!(close, Expiry::DateTime(time) if time.to_string() == "2030-06-21T16:00"));
+        assert_eq!(args.quoted().unwrap().1, 4.2);
+        // spot, rate and premium may only be left out when they are fetched live
+        assert!(
+            Cli::try_parse_from(["options", "iv", "--strike", "200", "--expiry", "2030-06-21"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "options",
+                "iv",
+                "--symbol",
+                "AAPL",
+                "--expiry",
+                "2030-06-21",
+                "--strike",
+                "200",
+            ])
+            .is_ok()
+        );
+    }
 }
@@ -0,0 +1,260 @@
+// Interactive explorer: arrow keys move the market inputs and every panel reprices live.
+use options::{OptionType, Options};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+
+const CHART_POINTS: usize = 121;
+
+/// Inputs adjustable from the keyboard, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Spot,
+    Volatility,
+    Rate,
+    Expiry,
+}
+
+impl Field {
+    const ALL: [Field; 4] = [Field::Spot, Field::Volatility, Field::Rate, Field::Expiry];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Field::Spot => "spot",
+            Field::Volatility => "vol",
+            Field::Rate => "rate",
+            Field::Expiry => "expiry",
+        }
+    }
+}
+
+/// Explorer state: the contract under study and the input being edited.
+#[derive(Debug, Clone)]
+struct App {
+    option: Options,
+    selected: usize,
+    quit: bool,
+}
+
+impl App {
+    fn new(option: Options) -> Self {
+        App {
+            option,
+            selected: 0,
+            quit: false,
+        }
+    }
+    fn field(&self) -> Field {
+        Field::ALL[self.selected]
+    }
+    fn value(&self, field: Field) -> f64 {
+        match field {
+            Field::Spot => self.option.spot_price(),
+            Field::Volatility => self.option.volatility(),
+            Field::Rate => self.option.risk_free_rate(),
+            Field::Expiry => self.option.time_to_maturity(),
+        }
+    }
+    // Moves the selected input by `steps` increments, keeping it in its valid range
+    fn nudge(&mut self, steps: f64) {
+        let option = &self.option;
+        let (mut spot, mut vol, mut rate, mut expiry) = (
+            option.spot_price(),
+            option.volatility(),
+            option.risk_free_rate(),
+            option.time_to_maturity(),
+        );
+        match self.field() {
+            Field::Spot => spot = (spot + steps * 0.01 * option.strike_price()).max(0.01),
+            Field::Volatility => vol = (vol + steps * 0.01).max(0.01),
+            Field::Rate => rate += steps * 0.0025,
+            Field::Expiry => expiry = (expiry + steps / 52.0).max(1.0 / 365.0),
+        }
+        self.option = Options::new_with_type(
+            option.option_type(),
+            option.strike_price(),
+            spot,
+            vol,
+            rate,
+            expiry,
+            option.dividend_yield(),
+        );
+    }
+    fn toggle_type(&mut self) {
+        let option = &self.option;
+        let option_type = match option.option_type() {
+            OptionType::Call => OptionType::Put,
+            OptionType::Put => OptionType::Call,
+        };
+        self.option = Options::new_with_type(
+            option_type,
+            option.strike_price(),
+            option.spot_price(),
+            option.volatility(),
+            option.risk_free_rate(),
+            option.time_to_maturity(),
+            option.dividend_yield(),
+        );
+    }
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => {
+                self.selected = (self.selected + Field::ALL.len() - 1) % Field::ALL.len()
+            }
+            KeyCode::Down => self.selected = (self.selected + 1) % Field::ALL.len(),
+            KeyCode::Right => self.nudge(1.0),
+            KeyCode::Left => self.nudge(-1.0),
+            KeyCode::Tab => self.toggle_type(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [left, chart] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(30)]).areas(frame.area());
+        let [inputs, greeks] =
+            Layout::vertical([Constraint::Length(8), Constraint::Min(9)]).areas(left);
+
+        let mut lines = vec![Line::from(format!(
+            "{:?} K={:.2}",
+            self.option.option_type(),
+            self.option.strike_price()
+        ))];
+        for (i, field) in Field::ALL.iter().enumerate() {
+            let line = Line::from(format!("{:<8}{:>12.4}", field.label(), self.value(*field)));
+            lines.push(if i == self.selected {
+                line.reversed()
+            } else {
+                line
+            });
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" inputs ")),
+            inputs,
+        );
+
+        let option = &self.option;
+        let greeks_text: Vec<Line> = [
+            ("price", option.bs_pricing()),
+            ("delta", option.delta()),
+            ("gamma", option.gamma()),
+            ("vega", option.vega()),
+            ("theta", option.theta()),
+            ("rho", option.rho()),
+        ]
+        .iter()
+        .map(|(name, value)| Line::from(format!("{:<8}{:>12.6}", name, value)))
+        .collect();
+        frame.render_widget(
+            Paragraph::new(greeks_text).block(Block::bordered().title(" greeks ")),
+            greeks,
+        );
+
+        let strike = option.strike_price();
+        let (low, high) = (0.5 * strike, 1.5 * strike);
+        let payoff = option.payoff_curve(low..=high, CHART_POINTS);
+        let value: Vec<(f64, f64)> = payoff
+            .iter()
+            .map(|&(spot, _)| {
+                let repriced =
+                    option.with_market(spot, option.volatility(), option.risk_free_rate());
+                (spot, repriced.bs_pricing())
+            })
+            .collect();
+        let top = payoff
+            .iter()
+            .chain(&value)
+            .map(|&(_, y)| y)
+            .fold(0.0, f64::max);
+        let spot_marker = [(option.spot_price(), option.bs_pricing())];
+        let datasets = vec![
+            Dataset::default()
+                .name("payoff")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&payoff),
+            Dataset::default()
+                .name("value")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&value),
+            Dataset::default()
+                .name("spot")
+                .marker(Marker::Block)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(Color::Red))
+                .data(&spot_marker),
+        ];
+        let chart_widget = Chart::new(datasets)
+            .block(
+                Block::bordered()
+                    .title(" payoff at expiry vs value today ")
+                    .title_bottom(" ↑↓ select  ←→ adjust  tab call/put  q quit "),
+            )
+            .x_axis(Axis::default().bounds([low, high]).labels([
+                format!("{:.0}", low),
+                format!("{:.0}", strike),
+                format!("{:.0}", high),
+            ]))
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, top])
+                    .labels(["0".to_string(), format!("{:.1}", top)]),
+            );
+        frame.render_widget(chart_widget, chart);
+    }
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key.code);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the explorer on the alternate screen until the user quits
+pub fn run(option: Options) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, App::new(option));
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_adjust_inputs() {
+        let mut app = App::new(Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None));
+        let price = app.option.bs_pricing();
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.option.spot_price(), 101.0);
+        assert!(app.option.bs_pricing() > price);
+
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.field(), Field::Expiry);
+        for _ in 0..100 {
+            app.handle_key(KeyCode::Left);
+        }
+        assert_eq!(app.option.time_to_maturity(), 1.0 / 365.0);
+
+        app.handle_key(KeyCode::Tab);
+        assert_eq!(app.option.option_type(), OptionType::Put);
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+}
@@ -0,0 +1,233 @@
+// Trade analytics under a real-world view. Pricing answers what a trade is worth to a hedger;
+// someone taking the trade outright wants its expected P&L, how often it wins and how much of a
+// bankroll to put at risk, all under their own distribution of the terminal spot rather than
+// the risk-neutral one. The distribution is a set of weighted scenarios, either supplied or
+// drawn from a lognormal at a chosen drift, and the Kelly fraction maximizes the expected log
+// growth of the bankroll with the trade's worst scenario as the unit of risk.
+use crate::Options;
+use crate::error::PricingError;
+use crate::math::float::{exp, sqrt};
+use crate::math::normal::Normal;
+use crate::solver::{DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE, brent};
+use crate::strategy::Strategy;
+
+// Quantiles a lognormal view is discretized into
+const LOGNORMAL_POINTS: usize = 2000;
+// Kelly fractions are searched strictly below betting the whole bankroll on the worst scenario
+const MAX_KELLY: f64 = 1.0 - 1e-12;
+
+/// Real-world distribution of the terminal spot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalDistribution {
+    /// Lognormal terminal spot with E[S_T] = S e^(drift T)
+    Lognormal {
+        spot: f64,
+        drift: f64,
+        volatility: f64,
+        maturity: f64,
+    },
+    /// (terminal spot, probability weight) scenarios; weights need not sum to one
+    Scenarios(Vec<(f64, f64)>),
+}
+
+impl TerminalDistribution {
+    /// Lognormal at the contract's spot, volatility and expiry with the real-world `drift`
+    /// replacing the risk-neutral r - q
+    pub fn lognormal(option: &Options, drift: f64) -> Self {
+        TerminalDistribution::Lognormal {
+            spot: option.spot_price(),
+            drift,
+            volatility: option.volatility(),
+            maturity: option.time_to_maturity(),
+        }
+    }
+    /// Scenarios with weights summing to one. A lognormal is cut into equally likely slices,
+    /// each represented by its mid quantile
+    pub fn scenarios(&self) -> Result<Vec<(f64, f64)>, PricingError> {
+        match self {
+            TerminalDistribution::Lognormal {
+                spot,
+                drift,
+                volatility,
+                maturity,
+            } => {
+                let sd = volatility * sqrt(*maturity);
+                let mean = (drift - 0.5 * volatility * volatility) * maturity;
+                let normal = Normal::new(mean, sd)?;
+                let weight = 1.0 / LOGNORMAL_POINTS as f64;
+                Ok((0..LOGNORMAL_POINTS)
+                    .map(|i| {
+                        let quantile = (i as f64 + 0.5) * weight;
+                        (spot * exp(normal.inverse_cdf(quantile)), weight)
+                    })
+                    .collect())
+            }
+            TerminalDistribution::Scenarios(scenarios) => {
+                let total: f64 = scenarios.iter().map(|&(_, weight)| weight).sum();
+                let valid = scenarios.iter().all(|&(_, weight)| weight >= 0.0);
+                if !(valid && total > 0.0 && total.is_finite()) {
+                    return Err(PricingError::InvalidInput(
+                        "scenario weights must be non-negative with a positive sum".to_string(),
+                    ));
+                }
+                Ok(scenarios
+                    .iter()
+                    .map(|&(spot, weight)| (spot, weight / total))
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Expiry P&L statistics of a trade under a real-world distribution, undiscounted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeAnalytics {
+    pub expected_value: f64,
+    /// Probability the P&L ends positive
+    pub win_probability: f64,
+    /// Loss in the worst scenario, positive, or zero for a trade that cannot lose
+    pub max_loss: f64,
+    /// Fraction of the bankroll to risk, the worst scenario losing exactly that fraction; zero
+    /// when the trade has no edge
+    ///
+    /// Formula: f* = argmax_f E[ln(1 + f P&L / max_loss)]
+    pub kelly_fraction: f64,
+}
+
+impl TradeAnalytics {
+    /// Units of the trade a `bankroll` supports at a multiple `fraction` of Kelly, e.g. 0.5 for
+    /// half Kelly
+    pub fn position_size(&self, bankroll: f64, fraction: f64) -> f64 {
+        fraction * self.kelly_fraction * bankroll / self.max_loss
+    }
+}
+
+/// Analytics of a trade whose expiry P&L at terminal spot S is `pnl(S)`. Fails on an invalid
+/// distribution, or when the trade has an edge but cannot lose, making the Kelly stake unbounded
+pub fn analytics<P: Fn(f64) -> f64>(
+    pnl: P,
+    distribution: &TerminalDistribution,
+) -> Result<TradeAnalytics, PricingError> {
+    let outcomes: Vec<(f64, f64)> = distribution
+        .scenarios()?
+        .into_iter()
+        .map(|(spot, weight)| (pnl(spot), weight))
+        .collect();
+    let expected_value = outcomes.iter().map(|&(pnl, weight)| weight * pnl).sum();
+    let win_probability = outcomes
+        .iter()
+        .filter(|&&(pnl, _)| pnl > 0.0)
+        .map(|&(_, weight)| weight)
+        .sum();
+    let max_loss = outcomes
+        .iter()
+        .filter(|&&(_, weight)| weight > 0.0)
+        .map(|&(pnl, _)| -pnl)
+        .fold(0.0, f64::max);
+
+    let kelly_fraction = if expected_value <= 0.0 {
+        0.0
+    } else if max_loss <= 0.0 {
+        return Err(PricingError::InvalidInput(
+            "a trade that cannot lose has an unbounded Kelly fraction".to_string(),
+        ));
+    } else {
+        // slope of the expected log growth, decreasing in f from the expected return at 0
+        let growth_slope = |f: f64| -> f64 {
+            outcomes
+                .iter()
+                .map(|&(pnl, weight)| {
+                    let r = pnl / max_loss;
+                    weight * r / (1.0 + f * r)
+                })
+                .sum()
+        };
+        if growth_slope(MAX_KELLY) >= 0.0 {
+            MAX_KELLY
+        } else {
+            brent(
+                growth_slope,
+                0.0,
+                MAX_KELLY,
+                DEFAULT_TOLERANCE,
+                DEFAULT_MAX_ITERATIONS,
+            )?
+        }
+    };
+    Ok(TradeAnalytics {
+        expected_value,
+        win_probability,
+        max_loss,
+        kelly_fraction,
+    })
+}
+
+impl Options {
+    /// Analytics of one long contract bought for `premium`
+    pub fn analytics(
+        &self,
+        premium: f64,
+        distribution: &TerminalDistribution,
+    ) -> Result<TradeAnalytics, PricingError> {
+        analytics(|spot| self.payout(spot) - premium, distribution)
+    }
+}
+
+impl Strategy {
+    /// Analytics of the strategy entered for `premium`, positive for a net debit
+    pub fn analytics(
+        &self,
+        premium: f64,
+        distribution: &TerminalDistribution,
+    ) -> Result<TradeAnalytics, PricingError> {
+        analytics(|spot| self.payoff(spot) - premium, distribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_bet_kelly() {
+        // a bet paying 1 for 1 risked that wins 60% of the time: f* = p - q
+        let bet = TerminalDistribution::Scenarios(vec![(110.0, 3.0), (90.0, 2.0)]);
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.0, 1.0, None);
+        let stats = call.analytics(5.0, &bet).unwrap();
+        assert!((stats.expected_value - 1.0).abs() < 1e-12);
+        assert!((stats.win_probability - 0.6).abs() < 1e-12);
+        assert_eq!(stats.max_loss, 5.0);
+        assert!((stats.kelly_fraction - 0.2).abs() < 1e-9);
+        assert!((stats.position_size(1000.0, 0.5) - 20.0).abs() < 1e-6);
+        // overpaying removes the edge
+        assert_eq!(call.analytics(7.0, &bet).unwrap().kelly_fraction, 0.0);
+        assert!(call.analytics(-1.0, &bet).is_err());
+        assert!(analytics(|s| s, &TerminalDistribution::Scenarios(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_lognormal_view() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.03, 0.5, None);
+        let premium = call.bs_pricing();
+        // at the risk-neutral drift a fairly priced option has no expected edge
+        let neutral = call
+            .analytics(premium, &TerminalDistribution::lognormal(&call, 0.03))
+            .unwrap();
+        assert!(neutral.expected_value.abs() < 0.02 * premium);
+        let view = TerminalDistribution::lognormal(&call, 0.15);
+        let bullish = call.analytics(premium, &view).unwrap();
+        assert!(bullish.expected_value > 0.0 && bullish.kelly_fraction > 0.0);
+        assert!(bullish.kelly_fraction < 1.0);
+        assert!((bullish.max_loss - premium).abs() < 1e-12);
+        // the call wins when spot finishes beyond the break-even
+        let d = ((100.0f64 / call.break_even(premium)).ln() + (0.15 - 0.02) * 0.5)
+            / (0.2 * 0.5f64.sqrt());
+        let expected = Normal::standard().cdf(d);
+        assert!((bullish.win_probability - expected).abs() < 1e-3);
+
+        let spread =
+            Strategy::vertical(call, Options::new_call(110.0, 100.0, 0.2, 0.03, 0.5, None));
+        let stats = spread.analytics(spread.premium(), &view).unwrap();
+        assert!(stats.max_loss <= spread.premium() + 1e-12);
+    }
+}
@@ -0,0 +1,241 @@
+// Normal (Bachelier) model: the forward moves arithmetically, so `volatility` is an absolute vol
+// in price units and forwards or strikes at or below zero are priced like any other.
+use crate::error::PricingError;
+use crate::math::float::{exp, sqrt};
+use crate::math::normal::Normal;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+use crate::{OptionType, Options};
+use alloc::format;
+
+// Implied normal vols are searched from here up to a multiple of the forward and strike sizes
+const MIN_NORMAL_VOL: f64 = 1e-8;
+const MAX_NORMAL_VOL_MULTIPLE: f64 = 10.0;
+
+/// Model that turns a contract's volatility into a price.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PricingModel {
+    /// Lognormal spot, volatility as a fraction of spot
+    #[default]
+    BlackScholes,
+    /// Normal forward, volatility in price units
+    Bachelier,
+    /// Lognormal forward displaced by the shift, volatility as a fraction of F + shift
+    ShiftedLognormal(f64),
+}
+
+/// Undiscounted Bachelier value of an option on a forward with normal vol σ.
+///
+/// Formula: φ(F - K) N(φd) + σ√T N'(d), d = (F - K) / σ√T
+pub fn forward_price(option_type: OptionType, forward: f64, strike: f64, vol: f64, t: f64) -> f64 {
+    let phi = sign(option_type);
+    let sd = vol * sqrt(t.max(0.0));
+    if sd <= 0.0 {
+        return (phi * (forward - strike)).max(0.0);
+    }
+    let std_norm = Normal::standard();
+    let d = (forward - strike) / sd;
+    phi * (forward - strike) * std_norm.cdf(phi * d) + sd * std_norm.pdf(d)
+}
+
+fn sign(option_type: OptionType) -> f64 {
+    match option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    }
+}
+
+/// A vanilla contract priced under the normal model, with the forward F = S e^((r - q)T).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bachelier {
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    /// Normal vol of the forward, in price units
+    pub volatility: f64,
+    pub rate: f64,
+    pub expiry: f64,
+    /// Continuous dividend yield, zero for none
+    pub dividend_yield: f64,
+}
+
+impl Bachelier {
+    /// Reads the contract's volatility as a normal vol
+    pub fn from_options(option: &Options) -> Self {
+        Bachelier {
+            option_type: option.option_type(),
+            strike: option.strike_price(),
+            spot: option.spot_price(),
+            volatility: option.volatility(),
+            rate: option.risk_free_rate(),
+            expiry: option.time_to_maturity(),
+            dividend_yield: option.dividend_yield().unwrap_or(0.0),
+        }
+    }
+    pub fn forward(&self) -> f64 {
+        self.spot * exp((self.rate - self.dividend_yield) * self.expiry)
+    }
+    // (d, N(φd), N'(d), σ√T)
+    fn terms(&self) -> (f64, f64, f64, f64) {
+        let std_norm = Normal::standard();
+        let sd = self.volatility * sqrt(self.expiry);
+        let d = (self.forward() - self.strike) / sd;
+        let phi = sign(self.option_type);
+        (d, std_norm.cdf(phi * d), std_norm.pdf(d), sd)
+    }
+    /// Formula: V = e^(-rT) [φ(F - K) N(φd) + σ√T N'(d)]
+    pub fn price(&self) -> f64 {
+        let t = self.expiry.max(0.0);
+        exp(-self.rate * t)
+            * forward_price(
+                self.option_type,
+                self.forward(),
+                self.strike,
+                self.volatility,
+                t,
+            )
+    }
+    /// Formula: Δ = φ e^(-qT) N(φd)
+    pub fn delta(&self) -> f64 {
+        let (_, cdf, _, _) = self.terms();
+        sign(self.option_type) * exp(-self.dividend_yield * self.expiry) * cdf
+    }
+    /// Formula: Γ = e^((r - 2q)T) N'(d) / σ√T
+    pub fn gamma(&self) -> f64 {
+        let (_, _, pdf, sd) = self.terms();
+        exp((self.rate - 2.0 * self.dividend_yield) * self.expiry) * pdf / sd
+    }
+    /// Sensitivity to the normal vol
+    ///
+    /// Formula: ν = e^(-rT) √T N'(d)
+    pub fn vega(&self) -> f64 {
+        let (_, _, pdf, _) = self.terms();
+        exp(-self.rate * self.expiry) * sqrt(self.expiry) * pdf
+    }
+    /// Annualized theta
+    ///
+    /// Formula: Θ = rV - e^(-rT) [φ(r - q) F N(φd) + σ N'(d) / 2√T]
+    pub fn theta(&self) -> f64 {
+        let (_, cdf, pdf, _) = self.terms();
+        let phi = sign(self.option_type);
+        let drift = phi * (self.rate - self.dividend_yield) * self.forward() * cdf;
+        let decay = self.volatility * pdf / (2.0 * sqrt(self.expiry));
+        self.rate * self.price() - exp(-self.rate * self.expiry) * (drift + decay)
+    }
+    /// Formula: ρ = T (e^(-rT) φ F N(φd) - V)
+    pub fn rho(&self) -> f64 {
+        let (_, cdf, _, _) = self.terms();
+        let phi = sign(self.option_type);
+        let discounted_forward = exp(-self.rate * self.expiry) * self.forward();
+        self.expiry * (phi * discounted_forward * cdf - self.price())
+    }
+}
+
+/// Normal vol at which the Bachelier price of `option` matches `price`.
+///
+/// Fails outside the no-arbitrage bounds of the normal model
+pub fn implied_volatility(option: &Options, price: f64) -> Result<f64, PricingError> {
+    implied_volatility_with(option, price, &NumericsConfig::default())
+}
+
+/// Implied normal vol solved to the tolerance and iteration cap of `numerics`
+pub fn implied_volatility_with(
+    option: &Options,
+    price: f64,
+    numerics: &NumericsConfig,
+) -> Result<f64, PricingError> {
+    let model = Bachelier::from_options(option);
+    let (forward, strike) = (model.forward(), model.strike);
+    let at = |volatility: f64| {
+        Bachelier {
+            volatility,
+            ..model
+        }
+        .price()
+            - price
+    };
+    let max_vol = MAX_NORMAL_VOL_MULTIPLE * (forward.abs() + strike.abs()).max(1.0);
+    if model.expiry <= 0.0 || !(at(MIN_NORMAL_VOL) < 0.0 && at(max_vol) > 0.0) {
+        return Err(PricingError::InvalidInput(format!(
+            "premium {} outside the normal model bounds",
+            price
+        )));
+    }
+    brent(
+        at,
+        MIN_NORMAL_VOL,
+        max_vol,
+        numerics.tolerance,
+        numerics.max_iterations,
+    )
+}
+
+impl Options {
+    /// The contract under the normal model, its volatility read in price units
+    pub fn bachelier(&self) -> Bachelier {
+        Bachelier::from_options(self)
+    }
+    /// Premium under the chosen model
+    pub fn model_price(&self, model: PricingModel) -> f64 {
+        match model {
+            PricingModel::BlackScholes => self.bs_pricing(),
+            PricingModel::Bachelier => self.bachelier().price(),
+            PricingModel::ShiftedLognormal(shift) => self.shifted_bs_pricing(shift),
+        }
+    }
+    /// Volatility, in the chosen model's units, reproducing a quoted premium
+    pub fn model_implied_volatility(
+        &self,
+        model: PricingModel,
+        price: f64,
+    ) -> Result<f64, PricingError> {
+        match model {
+            PricingModel::BlackScholes => self.implied_volatility(price),
+            PricingModel::Bachelier => implied_volatility(self, price),
+            PricingModel::ShiftedLognormal(shift) => self.shifted_implied_volatility(shift, price),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bachelier_price_and_greeks() {
+        let put = Options::new_put(100.0, 98.0, 20.0, 0.03, 0.75, Some(0.01));
+        let model = put.bachelier();
+        let call = Options::new_call(100.0, 98.0, 20.0, 0.03, 0.75, Some(0.01)).bachelier();
+        // put-call parity: C - P = e^(-rT)(F - K)
+        let parity = call.price() - model.price();
+        assert!((parity - (-0.03f64 * 0.75).exp() * (model.forward() - 100.0)).abs() < 1e-10);
+
+        let bumped = |f: &dyn Fn(&mut Bachelier, f64), h: f64| {
+            let (mut up, mut down) = (model, model);
+            f(&mut up, h);
+            f(&mut down, -h);
+            (up.price() - down.price()) / (2.0 * h)
+        };
+        assert!((model.delta() - bumped(&|m, h| m.spot += h, 1e-4)).abs() < 1e-7);
+        assert!((model.vega() - bumped(&|m, h| m.volatility += h, 1e-4)).abs() < 1e-7);
+        assert!((model.rho() - bumped(&|m, h| m.rate += h, 1e-6)).abs() < 1e-5);
+        assert!((model.theta() + bumped(&|m, h| m.expiry += h, 1e-6)).abs() < 1e-5);
+        let at = |spot: f64| Bachelier { spot, ..model }.price();
+        let gamma = (at(98.001) - 2.0 * at(98.0) + at(97.999)) / 1e-6;
+        assert!((model.gamma() - gamma).abs() < 1e-5);
+
+        let premium = put.model_price(PricingModel::Bachelier);
+        let vol = put
+            .model_implied_volatility(PricingModel::Bachelier, premium)
+            .unwrap();
+        assert!((vol - 20.0).abs() < 1e-7);
+        // a spread underlying trading below zero
+        let spread = Options::new_call(0.5, -1.0, 2.0, 0.0, 1.0, None);
+        assert!(spread.bachelier().price() > 0.0 && spread.bachelier().delta() > 0.0);
+    }
+}
@@ -1,76 +1,144 @@
 use crate::Options;
-use statrs::distribution::{ContinuousCDF, Normal};
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, sqrt};
+use crate::math::normal::Normal;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+use alloc::format;
 
 // calculate d1 for the Black-Scholes formula
 pub fn d_plus(t: f64, r: f64, q: Option<f64>, sigma: f64, spot: f64, strike: f64) -> f64 {
-    let numerator = (spot / strike).ln() + (r - q.unwrap_or(0.0) + 0.5 * sigma * sigma) * t;
-    let denominator = sigma * t.sqrt();
+    let numerator = ln(spot / strike) + (r - q.unwrap_or(0.0) + 0.5 * sigma * sigma) * t;
+    let denominator = sigma * sqrt(t);
     numerator / denominator
 }
 
 // calculate d2 for the Black-Scholes formula
 pub fn d_minus(t: f64, r: f64, q: Option<f64>, sigma: f64, spot: f64, strike: f64) -> f64 {
-    let numerator = (spot / strike).ln() + (r - q.unwrap_or(0.0) - 0.5 * sigma * sigma) * t;
-    let denominator = sigma * t.sqrt();
+    let numerator = ln(spot / strike) + (r - q.unwrap_or(0.0) - 0.5 * sigma * sigma) * t;
+    let denominator = sigma * sqrt(t);
     numerator / denominator
 }
 /// Calculate the Black-Scholes price for a given option, either Call or Put.
 ///
 /// Needs the option parameters encapsulated in the Options enum, handles dividend yields if they are present
 pub fn black_scholes_price(option: Options) -> f64 {
+    option.vanilla().price()
+}
+
+// Volatility bracket searched by the implied volatility solver
+const MIN_IMPLIED_VOL: f64 = 1e-6;
+const MAX_IMPLIED_VOL: f64 = 10.0;
+
+/// Solve for the Black-Scholes volatility that reproduces a quoted premium.
+///
+/// The volatility stored in the option is ignored. Fails if the premium breaks the no-arbitrage bounds
+pub fn implied_volatility(option: Options, price: f64) -> Result<f64, PricingError> {
+    implied_volatility_with(option, price, &NumericsConfig::default())
+}
+
+/// Implied volatility solved to the tolerance and iteration cap of `numerics`
+pub fn implied_volatility_with(
+    option: Options,
+    price: f64,
+    numerics: &NumericsConfig,
+) -> Result<f64, PricingError> {
+    let t = option.time_to_maturity();
+    let spot_pv = option.spot_price() * exp(-option.dividend_yield().unwrap_or(0.0) * t);
+    let strike_pv = option.strike_price() * exp(-option.risk_free_rate() * t);
+    let (lower, upper) = match option {
+        Options::Call(_) => ((spot_pv - strike_pv).max(0.0), spot_pv),
+        Options::Put(_) => ((strike_pv - spot_pv).max(0.0), strike_pv),
+    };
+    if !(price > lower && price < upper) || t <= 0.0 {
+        return Err(PricingError::InvalidInput(format!(
+            "premium {} outside no-arbitrage bounds ({}, {})",
+            price, lower, upper
+        )));
+    }
+    let spot = option.spot_price();
+    let rate = option.risk_free_rate();
+    brent(
+        |vol| option.with_market(spot, vol, rate).bs_pricing() - price,
+        MIN_IMPLIED_VOL,
+        MAX_IMPLIED_VOL,
+        numerics.tolerance,
+        numerics.max_iterations,
+    )
+}
+/// Risk-neutral probability that spot touches a barrier before `t`, from the reflection principle
+/// on the log-price with drift ν = r - q - σ²/2.
+///
+/// The barrier is approached from whichever side spot is on
+pub fn probability_of_touch(
+    spot: f64,
+    barrier: f64,
+    t: f64,
+    r: f64,
+    q: Option<f64>,
+    sigma: f64,
+) -> f64 {
+    if spot == barrier {
+        return 1.0;
+    }
+    if t <= 0.0 || sigma <= 0.0 {
+        return 0.0;
+    }
     let std_norm = Normal::new(0.0, 1.0).unwrap();
-    if let Options::Call(call) = option {
-        let d1 = d_plus(
-            call.time_to_maturity,
-            call.risk_free_rate,
-            call.dividend_yield,
-            call.volatility,
-            call.spot_price,
-            call.strike_price,
-        );
-        let d2 = d_minus(
-            call.time_to_maturity,
-            call.risk_free_rate,
-            call.dividend_yield,
-            call.volatility,
-            call.spot_price,
-            call.strike_price,
-        );
-        let nd1: f64 = std_norm.cdf(d1);
-        let nd2: f64 = std_norm.cdf(d2);
-        call.spot_price * (-call.dividend_yield.unwrap_or(0.0) * call.time_to_maturity).exp() * nd1
-            - call.strike_price * (-call.risk_free_rate * call.time_to_maturity).exp() * nd2
-    } else if let Options::Put(put) = option {
-        let d1 = d_plus(
-            put.time_to_maturity,
-            put.risk_free_rate,
-            put.dividend_yield,
-            put.volatility,
-            put.spot_price,
-            put.strike_price,
-        );
-        let d2 = d_minus(
-            put.time_to_maturity,
-            put.risk_free_rate,
-            put.dividend_yield,
-            put.volatility,
-            put.spot_price,
-            put.strike_price,
-        );
-        let n_neg_d1 = std_norm.cdf(-d1);
-        let n_neg_d2 = std_norm.cdf(-d2);
-        put.strike_price * (-put.risk_free_rate * put.time_to_maturity).exp() * n_neg_d2
-            - put.spot_price
-                * (-put.dividend_yield.unwrap_or(0.0) * put.time_to_maturity).exp()
-                * n_neg_d1
+    let nu = r - q.unwrap_or(0.0) - 0.5 * sigma * sigma;
+    let sd = sigma * sqrt(t);
+    // distance to the barrier, with the drift measured towards it
+    let (distance, drift) = if barrier > spot {
+        (ln(barrier / spot), nu)
     } else {
-        0.0
+        (ln(spot / barrier), -nu)
+    };
+    (std_norm.cdf((-distance + drift * t) / sd)
+        + exp(2.0 * drift * distance / (sigma * sigma))
+            * std_norm.cdf((-distance - drift * t) / sd))
+    .min(1.0)
+}
+
+impl Options {
+    /// The plain contract a displaced diffusion reduces to: lognormal in F + shift, which is
+    /// Black-Scholes on spot S + shift e^(-(r-q)T) and strike K + shift at the same volatility.
+    ///
+    /// Delta, gamma and vega carry over unchanged; theta and rho do not, as the equivalent spot
+    /// moves with time and rate
+    pub fn shifted(&self, shift: f64) -> Options {
+        let t = self.time_to_maturity();
+        let carry = self.risk_free_rate() - self.dividend_yield().unwrap_or(0.0);
+        Options::new_with_type(
+            self.option_type(),
+            self.strike_price() + shift,
+            self.spot_price() + shift * exp(-carry * t),
+            self.volatility(),
+            self.risk_free_rate(),
+            t,
+            self.dividend_yield(),
+        )
+    }
+    /// Shifted lognormal premium, for underlyings that can sit at or below zero
+    pub fn shifted_bs_pricing(&self, shift: f64) -> f64 {
+        self.shifted(shift).bs_pricing()
+    }
+    /// Volatility of the shifted forward reproducing a quoted premium
+    pub fn shifted_implied_volatility(&self, shift: f64, price: f64) -> Result<f64, PricingError> {
+        let shifted = self.shifted(shift);
+        if shifted.strike_price() <= 0.0 || shifted.spot_price() <= 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "shift {} leaves the strike or spot at or below zero",
+                shift
+            )));
+        }
+        shifted.implied_volatility(price)
     }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Call, Options, Put};
+    use crate::{Call, OptionType, Options, Put, Vanilla};
     #[test]
     fn test_black_scholes_pricing() {
         // Setup test options - ITM Call (spot > strike)
@@ -172,17 +240,17 @@ mod tests {
         };
 
         // Calculate all Greeks
-        let call_delta = call.delta(0.2, 100.0);
-        let call_gamma = call.gamma(0.2, 100.0);
-        let call_vega = call.vega(100.0);
-        let call_theta = call.theta(0.2, 100.0);
-        let call_rho = call.rho(0.2, 100.0, 0.05);
-
-        let put_delta = put.delta(0.2, 100.0);
-        let put_gamma = put.gamma(0.2, 100.0);
-        let put_vega = put.vega(100.0);
-        let put_theta = put.theta(0.2, 100.0);
-        let put_rho = put.rho(0.2, 100.0, 0.05);
+        let call_delta = call.delta();
+        let call_gamma = call.gamma();
+        let call_vega = call.vega();
+        let call_theta = call.theta();
+        let call_rho = call.rho();
+
+        let put_delta = put.delta();
+        let put_gamma = put.gamma();
+        let put_vega = put.vega();
+        let put_theta = put.theta();
+        let put_rho = put.rho();
 
         // Assert all Greeks
         assert!(
@@ -230,6 +298,14 @@ mod tests {
         assert!(put_theta < 0.0, "Put theta should be negative");
 
         assert!(put_rho < 0.0, "Put rho should be negative");
+
+        // overrides bump spot and volatility without touching the contract
+        assert_eq!(call.delta_at(100.0, 0.2), call_delta);
+        assert!(call.delta_at(101.0, 0.2) > call_delta);
+        assert!(put.vega_at(130.0, 0.3) > put.vega_at(130.0, 0.2));
+        let bumped = Options::Put(put).theta_at(110.0, 0.25);
+        let moved = Options::new_put(100.0, 110.0, 0.25, 0.05, 1.0, None);
+        assert_eq!(bumped, moved.theta());
     }
 
     #[test]
@@ -242,7 +318,7 @@ mod tests {
             time_to_maturity: 1.0,
             dividend_yield: Some(0.03),
         };
-        let delta = call.delta(0.2, 100.0);
+        let delta = call.delta();
         println!("Call delta with dividend: {}", delta);
         assert!(delta > 0.0 && delta < 1.0, "Delta with div incorrect");
 
@@ -254,11 +330,108 @@ mod tests {
             time_to_maturity: 1.0,
             dividend_yield: Some(0.03),
         };
-        let gamma = put.gamma(0.2, 100.0);
+        let gamma = put.gamma();
         assert!(gamma > 0.0, "Gamma with div incorrect");
         assert!(
             (gamma - 0.01897).abs() < 0.00001,
             "Gamma with div incorrect"
         );
     }
+
+    #[test]
+    fn test_implied_volatility() {
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        let vol = implied_volatility(call, 13.8579).unwrap();
+        assert!((vol - 0.2).abs() < 1e-4, "Call implied vol incorrect");
+
+        let put = Options::new_put(100.0, 95.0, 0.35, 0.05, 0.5, Some(0.02));
+        let vol = implied_volatility(put, put.bs_pricing()).unwrap();
+        assert!((vol - 0.35).abs() < 1e-8, "Put implied vol incorrect");
+
+        assert!(
+            implied_volatility(call, 1.0).is_err(),
+            "Premium below intrinsic should fail"
+        );
+    }
+
+    #[test]
+    fn test_probability_analytics() {
+        let call = Options::new_call(110.0, 100.0, 0.25, 0.03, 0.5, Some(0.01));
+        let put = Options::new_put(110.0, 100.0, 0.25, 0.03, 0.5, Some(0.01));
+        assert!((call.probability_itm() + put.probability_itm() - 1.0).abs() < 1e-12);
+        // driftless touch probability is twice the terminal probability
+        let zero_drift = Options::new_call(110.0, 100.0, 0.2, 0.02, 1.0, None);
+        assert!(
+            (zero_drift.probability_of_touch() - 2.0 * zero_drift.probability_itm()).abs() < 1e-12
+        );
+        assert!(call.probability_of_touch() > call.probability_itm());
+        assert_eq!(put.probability_of_touch(), 1.0);
+
+        // discounted conditional payoff times the exercise probability is the price
+        for option in [call, put] {
+            let df = (-0.03f64 * 0.5).exp();
+            let price = df * option.probability_itm() * option.expected_payoff_given_exercise();
+            assert!((price - option.bs_pricing()).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_vanilla_carries_both_sides() {
+        let put = Options::new_put(100.0, 95.0, 0.25, 0.05, 0.75, Some(0.02));
+        let vanilla = put.vanilla();
+        assert_eq!(vanilla.kind(), OptionType::Put);
+        assert_eq!(Options::from(vanilla).bs_pricing(), put.bs_pricing());
+        // its analytic Greeks agree with finite differences of the price, and with the Put's
+        let at = |spot: f64, vol: f64| put.with_market(spot, vol, 0.05).bs_pricing();
+        let h = 1e-4;
+        let delta = (at(95.0 + h, 0.25) - at(95.0 - h, 0.25)) / (2.0 * h);
+        let vega = (at(95.0, 0.25 + h) - at(95.0, 0.25 - h)) / (2.0 * h);
+        assert!((vanilla.delta() - delta).abs() < 1e-7);
+        assert!((vanilla.vega() - vega).abs() < 1e-5);
+        assert!((vanilla.theta_at(90.0, 0.3) - put.theta_at(90.0, 0.3)).abs() < 1e-12);
+
+        // put-call parity from the same contract on the other side
+        let call = Vanilla::new(OptionType::Call, 100.0, 95.0, 0.25, 0.05, 0.75, Some(0.02));
+        let forward_pv = 95.0 * (-0.02f64 * 0.75).exp() - 100.0 * (-0.05f64 * 0.75).exp();
+        assert!((call.price() - vanilla.price() - forward_pv).abs() < 1e-10);
+        assert!((call.delta() - vanilla.delta() - (-0.02f64 * 0.75).exp()).abs() < 1e-12);
+        assert_eq!(call.gamma(), vanilla.gamma());
+        assert_eq!(call.payout(110.0), 10.0);
+        assert_eq!(vanilla.payout(110.0), 0.0);
+    }
+
+    #[test]
+    fn test_shifted_lognormal() {
+        // a 0.5% shift carries a -0.2% rate and strike into lognormal territory
+        let option = Options::new_put(0.001, -0.002, 0.3, 0.02, 1.5, None);
+        let premium = option.shifted_bs_pricing(0.005);
+        let df = (-0.02f64 * 1.5).exp();
+        let forward = -0.002 * (0.02f64 * 1.5).exp();
+        // Black-76 on the shifted forward and strike
+        let (f, k, sd) = (forward + 0.005, 0.006, 0.3 * 1.5f64.sqrt());
+        let d1 = ((f / k).ln() + 0.5 * sd * sd) / sd;
+        let n = Normal::standard();
+        let black = k * n.cdf(-(d1 - sd)) - f * n.cdf(-d1);
+        assert!((premium - df * black).abs() < 1e-14);
+
+        let vol = option.shifted_implied_volatility(0.005, premium).unwrap();
+        assert!((vol - 0.3).abs() < 1e-8);
+        assert!(option.shifted_implied_volatility(0.001, premium).is_err());
+        // no shift is plain Black-Scholes
+        let call = Options::new_call(100.0, 95.0, 0.2, 0.03, 1.0, Some(0.01));
+        assert_eq!(call.shifted_bs_pricing(0.0), call.bs_pricing());
+    }
+
+    // Reference bit patterns for regression control; any platform built with `strict_math` must
+    // reproduce them exactly
+    #[cfg(feature = "strict_math")]
+    #[test]
+    fn test_strict_math_reference_bits() {
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, Some(0.01));
+        let put = Options::new_put(95.0, 100.0, 0.35, 0.03, 0.5, None);
+        assert_eq!(call.bs_pricing().to_bits(), 0x402a389e94628d44);
+        assert_eq!(call.delta().to_bits(), 0x3fe664300ab9e1f9);
+        assert_eq!(put.bs_pricing().to_bits(), 0x401aa720aec92198);
+        assert_eq!(put.vega().to_bits(), 0x403a209427eb4a52);
+    }
 }
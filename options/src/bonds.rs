@@ -0,0 +1,160 @@
+// Fixed-income building blocks: cash flows of coupon bonds and their yield risk measures, quoted
+// on the bond's own compounding frequency.
+use crate::error::PricingError;
+use crate::math::sum::KahanSum;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Lowest yield searched when solving a yield from a price
+pub const MIN_YIELD: f64 = -0.5;
+/// Highest yield searched when solving a yield from a price
+pub const MAX_YIELD: f64 = 2.0;
+
+// One basis point, the yield move a DV01 quotes
+const BASIS_POINT: f64 = 1e-4;
+
+/// Bullet bond paying `coupon_rate * face_value / frequency` every period, with yields compounded
+/// `frequency` times a year.
+///
+/// The number of coupons is the maturity in whole periods
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FixedCouponBond {
+    pub face_value: f64,
+    pub coupon_rate: f64,
+    pub maturity: f64,
+    pub frequency: u32,
+}
+
+impl FixedCouponBond {
+    pub fn new(face_value: f64, coupon_rate: f64, maturity: f64, frequency: u32) -> Self {
+        FixedCouponBond {
+            face_value,
+            coupon_rate,
+            maturity,
+            frequency: frequency.max(1),
+        }
+    }
+    pub fn periods(&self) -> u32 {
+        (self.maturity * self.frequency as f64) as u32
+    }
+    /// Cash paid each period
+    pub fn coupon(&self) -> f64 {
+        self.face_value * self.coupon_rate / self.frequency as f64
+    }
+    /// (time in years, amount) of every payment, the face value included in the last one
+    pub fn cash_flows(&self) -> Vec<(f64, f64)> {
+        let periods = self.periods();
+        (1..=periods)
+            .map(|k| {
+                let redemption = if k == periods { self.face_value } else { 0.0 };
+                (k as f64 / self.frequency as f64, self.coupon() + redemption)
+            })
+            .collect()
+    }
+    // Sum over the cash flows of weight(t) times their value discounted at `yield_rate`:
+    // Σ w(t_k) CF_k / (1 + y/f)^k
+    fn discounted(&self, yield_rate: f64, weight: impl Fn(f64) -> f64) -> f64 {
+        let growth = 1.0 + yield_rate / self.frequency as f64;
+        let mut total = KahanSum::new();
+        let mut discounter = 1.0;
+        let periods = self.periods();
+        for k in 1..=periods {
+            discounter *= growth;
+            let t = k as f64 / self.frequency as f64;
+            total.add(weight(t) * self.coupon() / discounter);
+        }
+        total.add(weight(periods as f64 / self.frequency as f64) * self.face_value / discounter);
+        total.value()
+    }
+    /// Dirty price at a yield to maturity.
+    ///
+    /// Formula: P = Σ C / (1 + y/f)^k + F / (1 + y/f)^N
+    pub fn price(&self, yield_rate: f64) -> f64 {
+        self.discounted(yield_rate, |_| 1.0)
+    }
+    /// Yield to maturity reproducing `price`.
+    ///
+    /// Fails if the price needs a yield outside [`MIN_YIELD`], [`MAX_YIELD`]
+    pub fn yield_to_maturity(&self, price: f64) -> Result<f64, PricingError> {
+        self.yield_to_maturity_with(price, &NumericsConfig::default())
+    }
+    /// Yield to maturity solved to the tolerance and iteration cap of `numerics`
+    pub fn yield_to_maturity_with(
+        &self,
+        price: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<f64, PricingError> {
+        let error = |yield_rate: f64| self.price(yield_rate) - price;
+        if !(error(MIN_YIELD) > 0.0 && error(MAX_YIELD) < 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "price {} implies a yield outside [{}, {}]",
+                price, MIN_YIELD, MAX_YIELD
+            )));
+        }
+        brent(
+            error,
+            MIN_YIELD,
+            MAX_YIELD,
+            numerics.tolerance,
+            numerics.max_iterations,
+        )
+    }
+    /// Present-value weighted average time to the cash flows, in years.
+    ///
+    /// Formula: D_mac = Σ t_k PV_k / P
+    pub fn macaulay_duration(&self, yield_rate: f64) -> f64 {
+        self.discounted(yield_rate, |t| t) / self.price(yield_rate)
+    }
+    /// Relative price sensitivity to the yield.
+    ///
+    /// Formula: D_mod = -dP/dy / P = D_mac / (1 + y/f)
+    pub fn modified_duration(&self, yield_rate: f64) -> f64 {
+        self.macaulay_duration(yield_rate) / (1.0 + yield_rate / self.frequency as f64)
+    }
+    /// Second-order relative price sensitivity to the yield.
+    ///
+    /// Formula: C = d²P/dy² / P = Σ t_k (t_k + 1/f) PV_k / (P (1 + y/f)²)
+    pub fn convexity(&self, yield_rate: f64) -> f64 {
+        let f = self.frequency as f64;
+        let growth = 1.0 + yield_rate / f;
+        self.discounted(yield_rate, |t| t * (t + 1.0 / f))
+            / (self.price(yield_rate) * growth * growth)
+    }
+    /// Price change for a one basis point fall in yield.
+    ///
+    /// Formula: DV01 = D_mod P / 10 000
+    pub fn dv01(&self, yield_rate: f64) -> f64 {
+        self.modified_duration(yield_rate) * self.price(yield_rate) * BASIS_POINT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bond_price_yield_and_risk() {
+        let bond = FixedCouponBond::new(100.0, 0.05, 10.0, 2);
+        // a bond yielding its coupon prices at par
+        assert!((bond.price(0.05) - 100.0).abs() < 1e-9);
+        let yield_rate = bond.yield_to_maturity(92.0).unwrap();
+        assert!((bond.price(yield_rate) - 92.0).abs() < 1e-8);
+        assert!(bond.yield_to_maturity(1e6).is_err());
+
+        // duration and convexity against finite differences of the price
+        let (y, h) = (0.06, 1e-5);
+        let (p, up, down) = (bond.price(y), bond.price(y + h), bond.price(y - h));
+        assert!((bond.modified_duration(y) - (down - up) / (2.0 * h * p)).abs() < 1e-6);
+        assert!((bond.convexity(y) - (up - 2.0 * p + down) / (h * h * p)).abs() < 1e-3);
+        let bp_move = (bond.price(y - 1e-4) - bond.price(y + 1e-4)) / 2.0;
+        assert!((bond.dv01(y) - bp_move).abs() < 1e-6);
+
+        // a zero-coupon bond's Macaulay duration is its maturity
+        let zero = FixedCouponBond::new(100.0, 0.0, 7.0, 1);
+        assert!((zero.macaulay_duration(0.04) - 7.0).abs() < 1e-12);
+        assert_eq!(bond.cash_flows().last(), Some(&(10.0, 102.5)));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::Options;
+use crate::math::sum::compensated_sum;
+use crate::portfolio::Portfolio;
+use std::ops::Add;
+
+/// Closed range of possible values of an uncertain quantity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+}
+
+impl Interval {
+    /// Interval spanning both values, in either order
+    pub fn new(a: f64, b: f64) -> Self {
+        Interval {
+            lo: a.min(b),
+            hi: a.max(b),
+        }
+    }
+    pub fn point(value: f64) -> Self {
+        Interval {
+            lo: value,
+            hi: value,
+        }
+    }
+    /// value ± half_width
+    pub fn around(value: f64, half_width: f64) -> Self {
+        Interval::new(value - half_width.abs(), value + half_width.abs())
+    }
+    pub fn mid(&self) -> f64 {
+        0.5 * (self.lo + self.hi)
+    }
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+    /// Interval scaled by a signed factor, flipping the ends when it is negative
+    pub fn scale(&self, factor: f64) -> Self {
+        Interval::new(self.lo * factor, self.hi * factor)
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+    fn add(self, other: Interval) -> Interval {
+        Interval {
+            lo: self.lo + other.lo,
+            hi: self.hi + other.hi,
+        }
+    }
+}
+
+/// Uncertainty applied to every position's market inputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputBands {
+    /// Relative half-width of the spot interval, e.g. 0.02 for ±2%
+    pub spot: f64,
+    /// Absolute half-width of the volatility interval, e.g. 0.01 for ±1 vol point
+    pub volatility: f64,
+}
+
+impl Options {
+    /// Range of Black-Scholes prices over a box of spots and volatilities.
+    ///
+    /// Vanilla prices are monotone in each input (delta has the sign of the option, vega is
+    /// positive), so the extremes sit at opposite corners and the bounds are exact
+    pub fn price_bounds(&self, spot: Interval, volatility: Interval) -> Interval {
+        let rate = self.risk_free_rate();
+        let vol_lo = volatility.lo.max(f64::MIN_POSITIVE);
+        let (cheap_spot, rich_spot) = match self {
+            Options::Call(_) => (spot.lo, spot.hi),
+            Options::Put(_) => (spot.hi, spot.lo),
+        };
+        Interval::new(
+            self.with_market(cheap_spot, vol_lo, rate).bs_pricing(),
+            self.with_market(rich_spot, volatility.hi, rate)
+                .bs_pricing(),
+        )
+    }
+}
+
+impl Portfolio {
+    /// Rigorous enclosure of the portfolio value, summing each position's exact price range.
+    ///
+    /// Offsetting positions on the same underlying are bounded independently, so the range is
+    /// conservative for hedged books
+    pub fn value_bounds(&self, bands: InputBands) -> Interval {
+        let ranges: Vec<Interval> = self
+            .positions()
+            .iter()
+            .map(|position| {
+                let option = &position.option;
+                let spot = option.spot_price();
+                option
+                    .price_bounds(
+                        Interval::around(spot, spot * bands.spot),
+                        Interval::around(option.volatility(), bands.volatility),
+                    )
+                    .scale(position.units())
+            })
+            .chain(self.stocks().iter().map(|stock| {
+                Interval::around(stock.spot, stock.spot * bands.spot).scale(stock.quantity)
+            }))
+            .chain(self.cash().iter().map(|cash| Interval::point(cash.value())))
+            .collect();
+        Interval {
+            lo: compensated_sum(ranges.iter().map(|range| range.lo)),
+            hi: compensated_sum(ranges.iter().map(|range| range.hi)),
+        }
+    }
+    /// Approximate value range from Greeks netted per underlying.
+    ///
+    /// ΔV ≈ Δ dS + ½ Γ dS² + ν dσ, with each underlying's spot and vol moving independently
+    /// inside the bands. Tighter than `value_bounds` on hedged books, but ignores higher orders
+    pub fn greek_bounds(&self, bands: InputBands) -> Interval {
+        let base = self.value();
+        self.underlyings()
+            .into_iter()
+            .map(|underlying| {
+                let (mut delta, mut gamma, mut vega, mut spot) = (0.0, 0.0, 0.0, 0.0);
+                for position in self.positions() {
+                    if position.underlying == underlying {
+                        let option = &position.option;
+                        delta += position.units() * option.delta();
+                        gamma += position.units() * option.gamma();
+                        vega += position.units() * option.vega();
+                        spot = option.spot_price();
+                    }
+                }
+                for stock in self.stocks() {
+                    if stock.underlying == underlying {
+                        delta += stock.quantity;
+                        spot = stock.spot;
+                    }
+                }
+                let h = spot * bands.spot;
+                // extremes of the spot quadratic are at the ends or its vertex
+                let quadratic = |ds: f64| delta * ds + 0.5 * gamma * ds * ds;
+                let mut spot_range = Interval::new(quadratic(-h), quadratic(h));
+                if gamma != 0.0 && (-delta / gamma).abs() <= h {
+                    let vertex = quadratic(-delta / gamma);
+                    spot_range =
+                        Interval::new(spot_range.lo.min(vertex), spot_range.hi.max(vertex));
+                }
+                spot_range + Interval::around(0.0, vega * bands.volatility)
+            })
+            .fold(Interval::point(base), |total, range| total + range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::Position;
+
+    #[test]
+    fn test_price_bounds_enclose_box() {
+        let put = Options::new_put(100.0, 98.0, 0.25, 0.03, 0.5, Some(0.01));
+        let spot = Interval::around(98.0, 3.0);
+        let vol = Interval::around(0.25, 0.02);
+        let bounds = put.price_bounds(spot, vol);
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let s = spot.lo + spot.width() * i as f64 / 10.0;
+                let v = vol.lo + vol.width() * j as f64 / 10.0;
+                let price = put.with_market(s, v, 0.03).bs_pricing();
+                assert!(bounds.contains(price), "{} outside {:?}", price, bounds);
+            }
+        }
+        let corner = put.with_market(spot.lo, vol.hi, 0.03).bs_pricing();
+        assert_eq!(bounds.hi, corner);
+    }
+
+    #[test]
+    fn test_portfolio_bounds() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let book: Portfolio = vec![
+            Position::new("SPX", call, 1.0),
+            Position::new("SPX", put, -1.0),
+        ]
+        .into_iter()
+        .collect();
+        let bands = InputBands {
+            spot: 0.01,
+            volatility: 0.01,
+        };
+        let rigorous = book.value_bounds(bands);
+        let greeks = book.greek_bounds(bands);
+        // long call, short put is a synthetic forward: worth ± spot move, vol neutral
+        assert!((greeks.width() - 2.0).abs() < 1e-6);
+        assert!(rigorous.contains(greeks.lo) && rigorous.contains(greeks.hi));
+        assert!(rigorous.width() > greeks.width());
+    }
+}
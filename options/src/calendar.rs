@@ -0,0 +1,420 @@
+// Calendar dates for listed expiries. Pricing works in year fractions; dates only appear where
+// contracts are identified by their expiry day, and are converted with Act/365. Short-dated
+// contracts, where a day is a large share of the remaining life, are timed in trading minutes
+// instead.
+use crate::error::PricingError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// A proleptic Gregorian calendar day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, PricingError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(PricingError::InvalidInput(format!(
+                "{:04}-{:02}-{:02} is not a calendar date",
+                year, month, day
+            )));
+        }
+        Ok(Date { year, month, day })
+    }
+    /// Current UTC date
+    pub fn today() -> Self {
+        DateTime::now().date
+    }
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+    /// Days since 1970-01-01, negative before it
+    pub fn days_since_epoch(&self) -> i64 {
+        // Shifted so the year starts in March and the leap day falls last
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = i64::from(self.month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+    /// Calendar days from `self` to `end`, negative if `end` is earlier
+    pub fn days_until(&self, end: Date) -> i64 {
+        end.days_since_epoch() - self.days_since_epoch()
+    }
+    /// Act/365 year fraction from `self` to `end`, the time to expiry the pricers take
+    pub fn year_fraction(&self, end: Date) -> f64 {
+        self.days_until(end) as f64 / DAYS_PER_YEAR
+    }
+    /// ISO day of the week, 1 for Monday to 7 for Sunday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        ((self.days_since_epoch() + 3).rem_euclid(7) + 1) as u32
+    }
+    pub fn is_weekend(&self) -> bool {
+        self.weekday() >= 6
+    }
+}
+
+/// Wall-clock time of day to the minute, in the exchange's local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct TimeOfDay {
+    hour: u32,
+    minute: u32,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u32, minute: u32) -> Result<Self, PricingError> {
+        if hour > 23 || minute > 59 {
+            return Err(PricingError::InvalidInput(format!(
+                "{:02}:{:02} is not a time of day",
+                hour, minute
+            )));
+        }
+        Ok(TimeOfDay { hour, minute })
+    }
+    pub fn hour(&self) -> u32 {
+        self.hour
+    }
+    pub fn minute(&self) -> u32 {
+        self.minute
+    }
+    /// Minutes since midnight
+    pub fn minutes(&self) -> u32 {
+        self.hour * 60 + self.minute
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+/// Parses 24-hour `HH:MM`
+impl FromStr for TimeOfDay {
+    type Err = PricingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || PricingError::InvalidInput(format!("{} is not a HH:MM time", text));
+        let (hour, minute) = text.trim().split_once(':').ok_or_else(invalid)?;
+        if hour.len() != 2 || minute.len() != 2 {
+            return Err(invalid());
+        }
+        TimeOfDay::new(
+            hour.parse().map_err(|_| invalid())?,
+            minute.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = PricingError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+/// Calendar day and wall-clock time to the minute, in UTC or an exchange's local time as the
+/// context says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: TimeOfDay,
+}
+
+impl DateTime {
+    pub fn new(date: Date, time: TimeOfDay) -> Self {
+        DateTime { date, time }
+    }
+    /// Current UTC time, truncated to the minute
+    pub fn now() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        DateTime::from_minutes_since_epoch((seconds / 60) as i64)
+    }
+    /// Minutes since 1970-01-01T00:00, negative before it
+    pub fn minutes_since_epoch(&self) -> i64 {
+        self.date.days_since_epoch() * MINUTES_PER_DAY + i64::from(self.time.minutes())
+    }
+    pub fn from_minutes_since_epoch(minutes: i64) -> Self {
+        let minute_of_day = minutes.rem_euclid(MINUTES_PER_DAY) as u32;
+        DateTime {
+            date: Date::from_days_since_epoch(minutes.div_euclid(MINUTES_PER_DAY)),
+            time: TimeOfDay {
+                hour: minute_of_day / 60,
+                minute: minute_of_day % 60,
+            },
+        }
+    }
+    /// The time `minutes` later, earlier if negative
+    pub fn add_minutes(&self, minutes: i64) -> Self {
+        DateTime::from_minutes_since_epoch(self.minutes_since_epoch() + minutes)
+    }
+    /// Minutes from `self` to `end`, negative if `end` is earlier
+    pub fn minutes_until(&self, end: DateTime) -> i64 {
+        end.minutes_since_epoch() - self.minutes_since_epoch()
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}", self.date, self.time)
+    }
+}
+
+/// Parses ISO `YYYY-MM-DDTHH:MM`, or with a space for the `T`
+impl FromStr for DateTime {
+    type Err = PricingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (date, time) = text
+            .trim()
+            .split_once(['T', ' '])
+            .ok_or_else(|| PricingError::InvalidInput(format!("{} has no time of day", text)))?;
+        Ok(DateTime::new(date.parse()?, time.parse()?))
+    }
+}
+
+/// Regular session of an exchange, open on weekdays. Holidays are not modelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradingHours {
+    pub open: TimeOfDay,
+    pub close: TimeOfDay,
+}
+
+impl Default for TradingHours {
+    /// US equity and index options, 09:30 to 16:00
+    fn default() -> Self {
+        TradingHours {
+            open: TimeOfDay {
+                hour: 9,
+                minute: 30,
+            },
+            close: TimeOfDay {
+                hour: 16,
+                minute: 0,
+            },
+        }
+    }
+}
+
+impl TradingHours {
+    pub fn new(open: TimeOfDay, close: TimeOfDay) -> Result<Self, PricingError> {
+        if close <= open {
+            return Err(PricingError::InvalidInput(format!(
+                "session closing at {} must close after its {} open",
+                close, open
+            )));
+        }
+        Ok(TradingHours { open, close })
+    }
+    /// Length of a full session in minutes
+    pub fn session_minutes(&self) -> u32 {
+        self.close.minutes() - self.open.minutes()
+    }
+    pub fn is_trading_day(&self, date: Date) -> bool {
+        !date.is_weekend()
+    }
+    /// Minutes the market is open from `start` to `end`, zero if `end` is not later
+    pub fn trading_minutes(&self, start: DateTime, end: DateTime) -> u32 {
+        if end <= start {
+            return 0;
+        }
+        let (open, close) = (self.open.minutes(), self.close.minutes());
+        let (first, last) = (start.date.days_since_epoch(), end.date.days_since_epoch());
+        (first..=last)
+            .map(Date::from_days_since_epoch)
+            .filter(|&date| self.is_trading_day(date))
+            .map(|date| {
+                let from = if date == start.date {
+                    start.time.minutes()
+                } else {
+                    0
+                };
+                let to = if date == end.date {
+                    end.time.minutes()
+                } else {
+                    MINUTES_PER_DAY as u32
+                };
+                to.min(close).saturating_sub(from.max(open))
+            })
+            .sum()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parses ISO `YYYY-MM-DD`
+impl FromStr for Date {
+    type Err = PricingError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || PricingError::InvalidInput(format!("{} is not a YYYY-MM-DD date", text));
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+        Date::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = PricingError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> Self {
+        date.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates_and_year_fractions() {
+        let expiry: Date = "2025-06-20".parse().unwrap();
+        assert_eq!(expiry.to_string(), "2025-06-20");
+        assert_eq!(Date::new(1970, 1, 1).unwrap().days_since_epoch(), 0);
+        assert_eq!(expiry.days_since_epoch(), 20_259);
+        for days in [-800_000, -1, 0, 59, 11_016, 20_259, 2_932_896] {
+            assert_eq!(Date::from_days_since_epoch(days).days_since_epoch(), days);
+        }
+        let start = Date::new(2024, 2, 28).unwrap();
+        assert_eq!(start.days_until(Date::new(2024, 3, 1).unwrap()), 2);
+        assert_eq!(start.year_fraction(Date::new(2025, 2, 27).unwrap()), 1.0);
+
+        assert!(Date::new(2023, 2, 29).is_err());
+        assert!(Date::new(2000, 2, 29).is_ok());
+        for text in ["2025-6-20", "2025-13-01", "20250620", "2025-06-20x"] {
+            assert!(text.parse::<Date>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_trading_minutes() {
+        let friday = Date::new(2025, 6, 20).unwrap();
+        assert_eq!(friday.weekday(), 5);
+        assert!(Date::new(2025, 6, 22).unwrap().is_weekend());
+        let at = |text: &str| text.parse::<TimeOfDay>().unwrap();
+        assert_eq!(at("09:30").minutes(), 570);
+        assert!("9:30".parse::<TimeOfDay>().is_err() && "24:00".parse::<TimeOfDay>().is_err());
+
+        let hours = TradingHours::default();
+        assert_eq!(hours.session_minutes(), 390);
+        let on = |date: Date, time: &str| DateTime::new(date, at(time));
+        // 0DTE at 14:00: two hours left, not a whole day
+        let minutes = hours.trading_minutes(on(friday, "14:00"), on(friday, "16:00"));
+        assert_eq!(minutes, 120);
+        // Thursday after the close to Monday's open spans Friday's session alone
+        let thursday = Date::new(2025, 6, 19).unwrap();
+        let monday = Date::new(2025, 6, 23).unwrap();
+        assert_eq!(
+            hours.trading_minutes(on(thursday, "17:00"), on(monday, "09:30")),
+            390
+        );
+        assert_eq!(
+            hours.trading_minutes(on(friday, "16:00"), on(monday, "09:30")),
+            0
+        );
+        assert_eq!(
+            hours.trading_minutes(on(monday, "10:00"), on(friday, "10:00")),
+            0
+        );
+
+        let close: DateTime = "2025-06-20T16:00".parse().unwrap();
+        assert_eq!(close, on(friday, "16:00"));
+        assert_eq!(close.to_string(), "2025-06-20T16:00");
+        assert_eq!("2025-06-20 16:00".parse::<DateTime>().unwrap(), close);
+        assert!("2025-06-20".parse::<DateTime>().is_err());
+        assert_eq!(
+            close.add_minutes(8 * 60 + 1),
+            on(Date::new(2025, 6, 21).unwrap(), "00:01")
+        );
+        assert_eq!(close.add_minutes(-1).minutes_until(close), 1);
+        assert!(TradingHours::new(at("16:00"), at("09:30")).is_err());
+    }
+}
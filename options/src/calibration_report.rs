@@ -0,0 +1,380 @@
+// What a least-squares calibration leaves behind for an audit: the fitted parameters with their
+// standard errors, the residual of every quote, and warnings where the fit cannot be trusted.
+// Standard errors come from the Jacobian of the fitted values at the optimum, the Gauss-Newton
+// approximation s²(JᵀJ)⁻¹ of the estimates' covariance. A parameter pinned against its bound is
+// flagged, since the quadratic approximation behind its error says nothing there.
+use crate::math::float::{exp, ln, sqrt};
+use crate::math::linalg::{Matrix, cholesky, cholesky_solve};
+use crate::numerics::DEFAULT_RELATIVE_BUMP;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Distance to a bound, relative to the bound and at least absolute, that counts as reaching it
+pub const BOUNDARY_TOLERANCE: f64 = 1e-4;
+
+/// A calibrated parameter and the open interval it lives in, infinite ends for none.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Parameter {
+    pub const fn new(name: &'static str, lower: f64, upper: f64) -> Self {
+        Parameter { name, lower, upper }
+    }
+    pub const fn unbounded(name: &'static str) -> Self {
+        Parameter::new(name, f64::NEG_INFINITY, f64::INFINITY)
+    }
+    pub const fn positive(name: &'static str) -> Self {
+        Parameter::new(name, 0.0, f64::INFINITY)
+    }
+    /// Coordinate on the whole real line for unconstrained minimizers: a logit between two
+    /// bounds, a logarithm of the distance to a single one
+    pub fn to_unconstrained(&self, value: f64) -> f64 {
+        match (self.lower.is_finite(), self.upper.is_finite()) {
+            (true, true) => ln((value - self.lower) / (self.upper - value)),
+            (true, false) => ln(value - self.lower),
+            (false, true) => ln(self.upper - value),
+            (false, false) => value,
+        }
+    }
+    /// Inverse of `to_unconstrained`
+    pub fn from_unconstrained(&self, x: f64) -> f64 {
+        match (self.lower.is_finite(), self.upper.is_finite()) {
+            (true, true) => self.lower + (self.upper - self.lower) / (1.0 + exp(-x)),
+            (true, false) => self.lower + exp(x),
+            (false, true) => self.upper - exp(x),
+            (false, false) => x,
+        }
+    }
+    // The bound `value` has reached, if any
+    fn reached_bound(&self, value: f64) -> Option<f64> {
+        [self.lower, self.upper].into_iter().find(|bound| {
+            bound.is_finite() && (value - bound).abs() <= BOUNDARY_TOLERANCE * bound.abs().max(1.0)
+        })
+    }
+}
+
+/// Fitted parameter value with its standard error.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParameterEstimate {
+    pub name: String,
+    pub value: f64,
+    /// None when the covariance could not be estimated
+    pub std_error: Option<f64>,
+}
+
+/// Quote the calibration targeted and the model's value for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuoteResidual {
+    pub target: f64,
+    pub fitted: f64,
+    /// Fitted less target
+    pub residual: f64,
+}
+
+/// Reason to distrust part of a calibration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum CalibrationWarning {
+    /// The parameter ended on its bound, where its standard error means nothing
+    AtBound {
+        parameter: String,
+        value: f64,
+        bound: f64,
+    },
+    /// No more quotes than parameters, leaving no degrees of freedom for the error variance
+    Underdetermined,
+    /// JᵀJ is singular or not finite: the quotes do not pin down every parameter
+    Unidentified,
+}
+
+/// Structured outcome of a least-squares calibration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalibrationReport {
+    pub parameters: Vec<ParameterEstimate>,
+    /// One per quote, in the order of the targets
+    pub residuals: Vec<QuoteResidual>,
+    /// Root-mean-square residual
+    pub rmse: f64,
+    /// Covariance of the parameter estimates, in parameter order
+    pub covariance: Option<Matrix>,
+    pub warnings: Vec<CalibrationWarning>,
+}
+
+impl CalibrationReport {
+    /// Report of the fit `values` of `parameters` to `targets`, where `fitted` maps parameter
+    /// values to the model's value for each target. The Jacobian is taken by central
+    /// differences, one-sided where a bump would leave the parameter's interval
+    ///
+    /// Formula: Cov = s²(JᵀJ)⁻¹, s² = Σ residual² / (quotes - parameters)
+    pub fn from_fit<F>(parameters: &[Parameter], values: &[f64], targets: &[f64], fitted: F) -> Self
+    where
+        F: Fn(&[f64]) -> Vec<f64>,
+    {
+        let weights = vec![1.0; targets.len()];
+        CalibrationReport::from_weighted_fit(parameters, values, targets, &weights, fitted)
+    }
+    /// Report of a weighted least-squares fit, each squared residual scaled by its target's
+    /// weight in the objective. Residuals and `rmse` stay unweighted
+    ///
+    /// Formula: Cov = s²(JᵀWJ)⁻¹, s² = Σ w residual² / (quotes - parameters)
+    pub fn from_weighted_fit<F>(
+        parameters: &[Parameter],
+        values: &[f64],
+        targets: &[f64],
+        weights: &[f64],
+        fitted: F,
+    ) -> Self
+    where
+        F: Fn(&[f64]) -> Vec<f64>,
+    {
+        let at_optimum = fitted(values);
+        let residuals: Vec<QuoteResidual> = targets
+            .iter()
+            .zip(&at_optimum)
+            .map(|(&target, &fitted)| QuoteResidual {
+                target,
+                fitted,
+                residual: fitted - target,
+            })
+            .collect();
+        let squares: f64 = residuals.iter().map(|r| r.residual * r.residual).sum();
+        let rmse = sqrt(squares / residuals.len().max(1) as f64);
+        let weighted_squares: f64 = residuals
+            .iter()
+            .zip(weights)
+            .map(|(r, w)| w * r.residual * r.residual)
+            .sum();
+        let mut warnings: Vec<CalibrationWarning> = parameters
+            .iter()
+            .zip(values)
+            .filter_map(|(parameter, &value)| {
+                parameter
+                    .reached_bound(value)
+                    .map(|bound| CalibrationWarning::AtBound {
+                        parameter: parameter.name.to_string(),
+                        value,
+                        bound,
+                    })
+            })
+            .collect();
+        let covariance = if residuals.len() <= values.len() {
+            warnings.push(CalibrationWarning::Underdetermined);
+            None
+        } else {
+            let error_variance = weighted_squares / (residuals.len() - values.len()) as f64;
+            // rows scaled by √w turn the weighted problem into an ordinary one
+            let mut jacobian = jacobian(parameters, values, &fitted);
+            for (row, w) in jacobian.iter_mut().zip(weights) {
+                row.iter_mut().for_each(|x| *x *= sqrt(*w));
+            }
+            let covariance = information_inverse(&jacobian, values.len()).map(|inverse| {
+                inverse
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|c| c * error_variance).collect())
+                    .collect::<Matrix>()
+            });
+            if covariance.is_none() {
+                warnings.push(CalibrationWarning::Unidentified);
+            }
+            covariance
+        };
+        let parameters = parameters
+            .iter()
+            .zip(values)
+            .enumerate()
+            .map(|(i, (parameter, &value))| ParameterEstimate {
+                name: parameter.name.to_string(),
+                value,
+                std_error: covariance.as_ref().map(|c| sqrt(c[i][i].max(0.0))),
+            })
+            .collect();
+        CalibrationReport {
+            parameters,
+            residuals,
+            rmse,
+            covariance,
+            warnings,
+        }
+    }
+    /// Fitted value of the parameter named `name`
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.parameters
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value)
+    }
+    /// Largest absolute residual
+    pub fn max_abs_residual(&self) -> f64 {
+        self.residuals
+            .iter()
+            .map(|r| r.residual.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(feature = "report")]
+impl CalibrationReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("calibration report is always serializable")
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+// ∂fitted/∂value, one row per target
+fn jacobian<F>(parameters: &[Parameter], values: &[f64], fitted: &F) -> Matrix
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let rows = fitted(values).len();
+    let mut jacobian = vec![vec![0.0; values.len()]; rows];
+    for (j, parameter) in parameters.iter().enumerate() {
+        let h = DEFAULT_RELATIVE_BUMP * values[j].abs().max(1.0);
+        let inside = |v: f64| v > parameter.lower && v < parameter.upper;
+        let (down, up) = match (inside(values[j] - h), inside(values[j] + h)) {
+            (true, true) | (false, false) => (values[j] - h, values[j] + h),
+            (false, true) => (values[j], values[j] + h),
+            (true, false) => (values[j] - h, values[j]),
+        };
+        let bumped = |v: f64| {
+            let mut shifted = values.to_vec();
+            shifted[j] = v;
+            fitted(&shifted)
+        };
+        let (low, high) = (bumped(down), bumped(up));
+        for (row, (l, u)) in jacobian.iter_mut().zip(low.iter().zip(&high)) {
+            row[j] = (u - l) / (up - down);
+        }
+    }
+    jacobian
+}
+
+// (JᵀJ)⁻¹, None when it is not finite and positive definite
+fn information_inverse(jacobian: &[Vec<f64>], n: usize) -> Option<Matrix> {
+    let information: Matrix = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| jacobian.iter().map(|row| row[i] * row[j]).sum())
+                .collect()
+        })
+        .collect();
+    if information.iter().flatten().any(|x| !x.is_finite()) {
+        return None;
+    }
+    let factor = cholesky(&information)?;
+    let columns: Vec<Vec<f64>> = (0..n)
+        .map(|j| {
+            let unit: Vec<f64> = (0..n).map(|i| if i == j { 1.0 } else { 0.0 }).collect();
+            cholesky_solve(&factor, &unit)
+        })
+        .collect();
+    Some(
+        (0..n)
+            .map(|i| (0..n).map(|j| columns[j][i]).collect())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_fit_standard_errors() {
+        // y = 1 + 2x with alternating noise, fitted by ordinary least squares
+        let xs: Vec<f64> = (0..8).map(|i| i as f64).collect();
+        let ys: Vec<f64> = xs
+            .iter()
+            .enumerate()
+            .map(|(i, x)| 1.0 + 2.0 * x + if i % 2 == 0 { 0.1 } else { -0.1 })
+            .collect();
+        let n = xs.len() as f64;
+        let (mean_x, mean_y) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+        let sxx: f64 = xs.iter().map(|x| (x - mean_x) * (x - mean_x)).sum();
+        let sxy: f64 = xs
+            .iter()
+            .zip(&ys)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let parameters = [
+            Parameter::unbounded("intercept"),
+            Parameter::positive("slope"),
+        ];
+        let line = |v: &[f64]| xs.iter().map(|x| v[0] + v[1] * x).collect::<Vec<f64>>();
+        let report = CalibrationReport::from_fit(&parameters, &[intercept, slope], &ys, line);
+        // textbook OLS errors: s²/Sxx for the slope, s²(1/n + x̄²/Sxx) for the intercept
+        let s2 = report
+            .residuals
+            .iter()
+            .map(|r| r.residual.powi(2))
+            .sum::<f64>()
+            / (n - 2.0);
+        let slope_error = report.parameters[1].std_error.unwrap();
+        assert!((slope_error - (s2 / sxx).sqrt()).abs() < 1e-9);
+        let intercept_error = report.parameters[0].std_error.unwrap();
+        let expected = (s2 * (1.0 / n + mean_x * mean_x / sxx)).sqrt();
+        assert!((intercept_error - expected).abs() < 1e-9);
+        assert_eq!(report.value("slope"), Some(slope));
+        assert!(report.warnings.is_empty());
+        assert!((report.max_abs_residual() - 0.1).abs() < 0.05);
+
+        // a slope pinned at zero, and a parameter the targets never see
+        let flat = CalibrationReport::from_fit(&parameters, &[intercept, 0.0], &ys, line);
+        assert!(matches!(
+            flat.warnings[0],
+            CalibrationWarning::AtBound { bound: 0.0, .. }
+        ));
+        #[cfg(feature = "report")]
+        assert_eq!(CalibrationReport::from_json(&flat.to_json()).unwrap(), flat);
+        let blind = |v: &[f64]| vec![v[0]; xs.len()];
+        let unidentified = CalibrationReport::from_fit(&parameters, &[1.0, 2.0], &ys, blind);
+        assert_eq!(
+            unidentified.warnings,
+            vec![CalibrationWarning::Unidentified]
+        );
+        assert!(unidentified.parameters[0].std_error.is_none());
+        let few = CalibrationReport::from_fit(&parameters, &[1.0, 2.0], &ys[..2], line);
+        assert_eq!(few.warnings, vec![CalibrationWarning::Underdetermined]);
+        // uniform weights of any size leave the estimated errors alone
+        let weights = vec![4.0; ys.len()];
+        let weighted = CalibrationReport::from_weighted_fit(
+            &parameters,
+            &[intercept, slope],
+            &ys,
+            &weights,
+            line,
+        );
+        assert!((weighted.parameters[1].std_error.unwrap() - slope_error).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unconstrained_coordinates() {
+        for (parameter, value) in [
+            (Parameter::new("rho", -1.0, 1.0), -0.7),
+            (Parameter::positive("vol"), 0.2),
+            (Parameter::new("cap", f64::NEG_INFINITY, 2.0), 1.5),
+            (Parameter::unbounded("drift"), -3.0),
+        ] {
+            let x = parameter.to_unconstrained(value);
+            assert!((parameter.from_unconstrained(x) - value).abs() < 1e-14);
+        }
+        assert_eq!(Parameter::positive("vol").from_unconstrained(-800.0), 0.0);
+    }
+}
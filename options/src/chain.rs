@@ -0,0 +1,276 @@
+// Synthetic option chains: every strike and expiry of an underlying priced off one vol rule, for
+// tests, demos and seeding the surface fitter with a known answer.
+use crate::error::PricingError;
+use crate::math::float::ln;
+use crate::moneyness;
+use crate::quote::OptionQuote;
+use crate::{OptionType, Options};
+
+/// Vol floor of the skew rule, so deep wings never go negative
+const MIN_CHAIN_VOL: f64 = 0.01;
+
+/// How the strikes of every expiry are laid out around spot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrikeRule {
+    /// `count` strikes each side of spot, `spacing` apart as a fraction of spot (0.05 = 5%)
+    Relative { spacing: f64, count: usize },
+    /// `count` strikes each side of the listed strike nearest spot, `increment` apart
+    Increment { increment: f64, count: usize },
+    /// Explicit strikes
+    List(Vec<f64>),
+}
+
+impl StrikeRule {
+    /// Positive strikes in increasing order
+    pub fn strikes(&self, spot: f64) -> Vec<f64> {
+        let around = |centre: f64, step: f64, count: usize| -> Vec<f64> {
+            let count = count as i64;
+            (-count..=count)
+                .map(|i| centre + i as f64 * step)
+                .filter(|strike| *strike > 0.0)
+                .collect()
+        };
+        let mut strikes = match self {
+            StrikeRule::Relative { spacing, count } => around(spot, spot * spacing, *count),
+            StrikeRule::Increment { increment, count } => {
+                around((spot / increment).round() * increment, *increment, *count)
+            }
+            StrikeRule::List(strikes) => strikes.iter().copied().filter(|k| *k > 0.0).collect(),
+        };
+        strikes.sort_by(f64::total_cmp);
+        strikes.dedup();
+        strikes
+    }
+}
+
+/// Implied vol assigned to each strike and expiry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChainVol {
+    Flat(f64),
+    /// Quadratic smile in log-moneyness k = ln(K/F): σ = atm + skew k + curvature k²
+    Skew {
+        atm: f64,
+        skew: f64,
+        curvature: f64,
+    },
+}
+
+impl ChainVol {
+    pub fn vol(&self, strike: f64, forward: f64) -> f64 {
+        match *self {
+            ChainVol::Flat(vol) => vol,
+            ChainVol::Skew {
+                atm,
+                skew,
+                curvature,
+            } => {
+                let k = ln(strike / forward);
+                (atm + skew * k + curvature * k * k).max(MIN_CHAIN_VOL)
+            }
+        }
+    }
+}
+
+/// Underlying inputs and layout of a generated chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainSpec {
+    pub spot: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: Option<f64>,
+    /// Expiries in years
+    pub expiries: Vec<f64>,
+    pub strikes: StrikeRule,
+    pub vol: ChainVol,
+    /// Bid-ask width as a fraction of the price, split evenly around it
+    pub spread: f64,
+}
+
+impl ChainSpec {
+    /// Eleven strikes 5% apart around spot at a flat 20% vol, quoted at mid
+    pub fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: Option<f64>,
+        expiries: Vec<f64>,
+    ) -> Self {
+        ChainSpec {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            expiries,
+            strikes: StrikeRule::Relative {
+                spacing: 0.05,
+                count: 5,
+            },
+            vol: ChainVol::Flat(0.2),
+            spread: 0.0,
+        }
+    }
+    fn forward(&self, expiry: f64) -> f64 {
+        moneyness::forward(self.spot, self.risk_free_rate, self.dividend_yield, expiry)
+    }
+}
+
+/// One listed contract of a chain with its price, Greeks and implied vol.
+#[derive(Debug, Clone, Copy)]
+pub struct ChainRow {
+    pub option: Options,
+    pub price: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+    /// Vol recovered from the price, `None` where the premium is too small to invert
+    pub implied_vol: Option<f64>,
+}
+
+/// Calls and puts at every strike and expiry of a `ChainSpec`, sorted by expiry, strike, type.
+#[derive(Debug, Clone)]
+pub struct OptionChain {
+    rows: Vec<ChainRow>,
+}
+
+impl OptionChain {
+    pub fn generate(spec: &ChainSpec) -> Result<Self, PricingError> {
+        if !(spec.spot > 0.0 && spec.spread >= 0.0 && spec.spread < 2.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "chain needs a positive spot and a spread in [0, 2), got {} and {}",
+                spec.spot, spec.spread
+            )));
+        }
+        if let Some(expiry) = spec.expiries.iter().find(|t| t.is_nan() || **t <= 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "chain expiry {} must be positive",
+                expiry
+            )));
+        }
+        let mut expiries = spec.expiries.clone();
+        expiries.sort_by(f64::total_cmp);
+        let strikes = spec.strikes.strikes(spec.spot);
+        let half_spread = 0.5 * spec.spread;
+        let mut rows = Vec::with_capacity(expiries.len() * strikes.len() * 2);
+        for &expiry in &expiries {
+            let forward = spec.forward(expiry);
+            for &strike in &strikes {
+                let vol = spec.vol.vol(strike, forward);
+                for option_type in [OptionType::Call, OptionType::Put] {
+                    let option = Options::new_with_type(
+                        option_type,
+                        strike,
+                        spec.spot,
+                        vol,
+                        spec.risk_free_rate,
+                        expiry,
+                        spec.dividend_yield,
+                    );
+                    let price = option.bs_pricing();
+                    rows.push(ChainRow {
+                        option,
+                        price,
+                        bid: price * (1.0 - half_spread),
+                        ask: price * (1.0 + half_spread),
+                        delta: option.delta(),
+                        gamma: option.gamma(),
+                        vega: option.vega(),
+                        theta: option.theta(),
+                        rho: option.rho(),
+                        implied_vol: option.implied_volatility(price).ok(),
+                    });
+                }
+            }
+        }
+        Ok(OptionChain { rows })
+    }
+    pub fn rows(&self) -> &[ChainRow] {
+        &self.rows
+    }
+    /// Rows of the expiry closest to `expiry`
+    pub fn expiry(&self, expiry: f64) -> Vec<&ChainRow> {
+        let nearest = self
+            .rows
+            .iter()
+            .map(|row| row.option.time_to_maturity())
+            .min_by(|a, b| (a - expiry).abs().total_cmp(&(b - expiry).abs()));
+        self.rows
+            .iter()
+            .filter(|row| Some(row.option.time_to_maturity()) == nearest)
+            .collect()
+    }
+    /// The chain as bid/ask quotes, e.g. for `VolSurface::from_chain`
+    pub fn to_quotes(&self) -> Vec<OptionQuote> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let option = &row.option;
+                OptionQuote::new(
+                    option.option_type(),
+                    option.strike_price(),
+                    option.time_to_maturity(),
+                    row.bid,
+                    row.ask,
+                    option.spot_price(),
+                    option.risk_free_rate(),
+                    option.dividend_yield(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_chain() {
+        let mut spec = ChainSpec::new(100.0, 0.03, Some(0.01), vec![1.0, 0.25]);
+        spec.vol = ChainVol::Skew {
+            atm: 0.2,
+            skew: -0.1,
+            curvature: 0.3,
+        };
+        spec.spread = 0.02;
+        let chain = OptionChain::generate(&spec).unwrap();
+        assert_eq!(chain.rows().len(), 2 * 11 * 2);
+        let front = chain.expiry(0.2);
+        assert_eq!(front.len(), 22);
+        assert_eq!(front[0].option.time_to_maturity(), 0.25);
+        assert_eq!(front[0].option.strike_price(), 75.0);
+        for row in chain.rows() {
+            let vol = row.option.volatility();
+            assert!((row.implied_vol.unwrap() - vol).abs() < 1e-8);
+            assert!(row.bid < row.price && row.price < row.ask);
+        }
+        // the downside wing carries the skew
+        assert!(front[0].option.volatility() > front[21].option.volatility());
+
+        let strikes = StrikeRule::Increment {
+            increment: 2.5,
+            count: 2,
+        };
+        assert_eq!(
+            strikes.strikes(101.4),
+            vec![97.5, 100.0, 102.5, 105.0, 107.5]
+        );
+        spec.expiries.push(0.0);
+        assert!(OptionChain::generate(&spec).is_err());
+    }
+
+    #[cfg(feature = "calibration")]
+    #[test]
+    fn test_chain_seeds_surface_fit() {
+        let mut spec = ChainSpec::new(100.0, 0.02, None, vec![0.5, 1.0]);
+        spec.vol = ChainVol::Skew {
+            atm: 0.25,
+            skew: -0.15,
+            curvature: 0.2,
+        };
+        let chain = OptionChain::generate(&spec).unwrap();
+        let surface = crate::vol::surface::VolSurface::from_chain(&chain.to_quotes()).unwrap();
+        let forward = spec.forward(1.0);
+        assert!((surface.vol(forward, 1.0).vol - 0.25).abs() < 5e-3);
+    }
+}
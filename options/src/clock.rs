@@ -0,0 +1,249 @@
+// Intraday times to expiry. An expiry is fixed in the exchange's local time, the valuation
+// instant usually in UTC; both are brought onto the exchange clock through its timezone and the
+// time between them measured by a trading calendar. Counted in whole days a 0DTE contract has
+// either a full day left or none, so the calendar works to the minute.
+use crate::Options;
+use crate::calendar::{Date, DateTime, TimeOfDay, TradingHours};
+use crate::conventions::{CALENDAR_DAYS_PER_YEAR, TRADING_DAYS_PER_YEAR};
+use crate::error::PricingError;
+
+/// Timezone of an exchange, with the daylight saving rules of its region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timezone {
+    /// A constant offset from UTC in minutes, e.g. 540 for Tokyo
+    Fixed { offset_minutes: i32 },
+    /// US Eastern time (NYSE, Cboe)
+    NewYork,
+    /// US Central time (CME)
+    Chicago,
+    /// UK time (ICE Europe, LSE)
+    London,
+    /// Central European time (Eurex)
+    Frankfurt,
+}
+
+impl Timezone {
+    /// Offset from UTC in minutes in force at the UTC instant `utc`
+    pub fn offset_at(&self, utc: DateTime) -> i32 {
+        match *self {
+            Timezone::Fixed { offset_minutes } => offset_minutes,
+            Timezone::NewYork => us_offset(-300, utc),
+            Timezone::Chicago => us_offset(-360, utc),
+            Timezone::London => eu_offset(0, utc),
+            Timezone::Frankfurt => eu_offset(60, utc),
+        }
+    }
+    /// Local wall-clock time at the UTC instant `utc`
+    pub fn to_local(&self, utc: DateTime) -> DateTime {
+        utc.add_minutes(i64::from(self.offset_at(utc)))
+    }
+    /// UTC instant of the local wall-clock time `local`, exact away from the hour the clocks
+    /// change in; a time repeated when they go back maps to its first occurrence
+    pub fn to_utc(&self, local: DateTime) -> DateTime {
+        // `local` read as UTC is within a day of the instant, so its offset is the right one
+        // but for the hours around a change, which the second lookup settles
+        let guess = local.add_minutes(-i64::from(self.offset_at(local)));
+        local.add_minutes(-i64::from(self.offset_at(guess)))
+    }
+}
+
+// The `nth` Sunday of a month, the last one for `nth == 0`
+fn sunday(year: i32, month: u32, nth: u32) -> Date {
+    let first = Date::new(year, month, 1).expect("the first is a calendar day");
+    if nth == 0 {
+        let next_month = if month == 12 {
+            Date::new(year + 1, 1, 1)
+        } else {
+            Date::new(year, month + 1, 1)
+        }
+        .expect("the first is a calendar day");
+        let last = Date::from_days_since_epoch(next_month.days_since_epoch() - 1);
+        return Date::from_days_since_epoch(
+            last.days_since_epoch() - i64::from(last.weekday() % 7),
+        );
+    }
+    let first_sunday = (7 - first.weekday()) % 7;
+    Date::from_days_since_epoch(first.days_since_epoch() + i64::from(first_sunday + 7 * (nth - 1)))
+}
+
+// US rule: daylight time from 02:00 local on the second Sunday of March to 02:00 local on the
+// first Sunday of November
+fn us_offset(standard: i32, utc: DateTime) -> i32 {
+    let year = utc.date.year();
+    let two_am = TimeOfDay::new(2, 0).expect("02:00 is a time of day");
+    let start = DateTime::new(sunday(year, 3, 2), two_am).add_minutes(-i64::from(standard));
+    let end = DateTime::new(sunday(year, 11, 1), two_am).add_minutes(-i64::from(standard + 60));
+    if start <= utc && utc < end {
+        standard + 60
+    } else {
+        standard
+    }
+}
+
+// EU rule: summer time from 01:00 UTC on the last Sunday of March to 01:00 UTC on the last
+// Sunday of October
+fn eu_offset(standard: i32, utc: DateTime) -> i32 {
+    let year = utc.date.year();
+    let one_am = TimeOfDay::new(1, 0).expect("01:00 is a time of day");
+    let start = DateTime::new(sunday(year, 3, 0), one_am);
+    let end = DateTime::new(sunday(year, 10, 0), one_am);
+    if start <= utc && utc < end {
+        standard + 60
+    } else {
+        standard
+    }
+}
+
+/// Measure of the time between two exchange-local times, in years.
+pub trait TradingCalendar {
+    /// Years from `start` to `end`, zero if `end` is not later
+    fn year_fraction(&self, start: DateTime, end: DateTime) -> f64;
+}
+
+/// Trading time: open minutes over those of `TRADING_DAYS_PER_YEAR` full sessions, so nights
+/// and weekends carry no variance. Pair it with a vol annualized over trading days
+impl TradingCalendar for TradingHours {
+    fn year_fraction(&self, start: DateTime, end: DateTime) -> f64 {
+        f64::from(self.trading_minutes(start, end))
+            / (f64::from(self.session_minutes()) * TRADING_DAYS_PER_YEAR)
+    }
+}
+
+/// Act/365 with intraday time, every minute of the day and week counting the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CalendarTime;
+
+impl TradingCalendar for CalendarTime {
+    fn year_fraction(&self, start: DateTime, end: DateTime) -> f64 {
+        start.minutes_until(end).max(0) as f64 / (CALENDAR_DAYS_PER_YEAR * 24.0 * 60.0)
+    }
+}
+
+/// An exchange's timezone and the calendar its contracts' time to expiry is measured on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpiryClock<C = TradingHours> {
+    pub timezone: Timezone,
+    pub calendar: C,
+}
+
+impl<C: TradingCalendar> ExpiryClock<C> {
+    pub fn new(timezone: Timezone, calendar: C) -> Self {
+        ExpiryClock { timezone, calendar }
+    }
+    /// Years from the UTC instant `now` to `expiry` in exchange-local time. Fails once expired
+    pub fn time_to_expiry(&self, now: DateTime, expiry: DateTime) -> Result<f64, PricingError> {
+        let local_now = self.timezone.to_local(now);
+        if expiry <= local_now {
+            return Err(PricingError::InvalidInput(format!(
+                "contract expired at {} local time, it is now {}",
+                expiry, local_now
+            )));
+        }
+        Ok(self.calendar.year_fraction(local_now, expiry))
+    }
+}
+
+impl ExpiryClock {
+    /// US listed options: New York time on the 09:30 to 16:00 session
+    pub fn us_options() -> Self {
+        ExpiryClock::new(Timezone::NewYork, TradingHours::default())
+    }
+}
+
+impl Options {
+    /// The contract with its time to maturity running from the UTC instant `now` to `expiry`
+    /// in exchange-local time on `clock`. Fails once expired
+    pub fn with_expiry<C: TradingCalendar>(
+        &self,
+        clock: &ExpiryClock<C>,
+        now: DateTime,
+        expiry: DateTime,
+    ) -> Result<Self, PricingError> {
+        Ok(self.with_time_to_maturity(clock.time_to_expiry(now, expiry)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_timezones() {
+        // US clocks went forward on 2025-03-09 and back on 2025-11-02
+        let new_york = Timezone::NewYork;
+        assert_eq!(new_york.offset_at(at("2025-03-09T06:59")), -300);
+        assert_eq!(new_york.offset_at(at("2025-03-09T07:00")), -240);
+        assert_eq!(new_york.offset_at(at("2025-11-02T05:59")), -240);
+        assert_eq!(new_york.offset_at(at("2025-11-02T06:00")), -300);
+        assert_eq!(
+            new_york.to_local(at("2025-06-20T20:00")),
+            at("2025-06-20T16:00")
+        );
+        assert_eq!(
+            new_york.to_utc(at("2025-06-20T16:00")),
+            at("2025-06-20T20:00")
+        );
+        assert_eq!(
+            new_york.to_utc(at("2025-12-19T16:00")),
+            at("2025-12-19T21:00")
+        );
+        assert_eq!(
+            Timezone::Chicago.to_utc(at("2025-06-20T15:00")),
+            at("2025-06-20T20:00")
+        );
+        // Europe changed on 2025-03-30 and 2025-10-26, weeks apart from the US
+        assert_eq!(
+            Timezone::London.to_utc(at("2025-03-28T16:30")),
+            at("2025-03-28T16:30")
+        );
+        assert_eq!(
+            Timezone::London.to_utc(at("2025-03-31T16:30")),
+            at("2025-03-31T15:30")
+        );
+        assert_eq!(
+            Timezone::Frankfurt.to_utc(at("2025-10-27T17:30")),
+            at("2025-10-27T16:30")
+        );
+        let tokyo = Timezone::Fixed {
+            offset_minutes: 540,
+        };
+        assert_eq!(
+            tokyo.to_local(at("2025-06-20T23:00")),
+            at("2025-06-21T08:00")
+        );
+    }
+
+    #[test]
+    fn test_zero_dte_from_the_clock() {
+        let clock = ExpiryClock::us_options();
+        let expiry = at("2025-06-20T16:00");
+        // 18:30 UTC is 14:30 in New York, an hour and a half before the close
+        let t = clock
+            .time_to_expiry(at("2025-06-20T18:30"), expiry)
+            .unwrap();
+        assert!((t - 90.0 / (390.0 * 252.0)).abs() < 1e-15);
+        assert!(
+            clock
+                .time_to_expiry(at("2025-06-20T20:00"), expiry)
+                .is_err()
+        );
+        let calendar = ExpiryClock::new(Timezone::NewYork, CalendarTime);
+        let t = calendar
+            .time_to_expiry(at("2025-06-19T20:00"), expiry)
+            .unwrap();
+        assert!((t - 1.0 / 365.0).abs() < 1e-15);
+
+        // whole days would give this call either a day or nothing; the clock gives its last
+        // ninety minutes
+        let call = Options::new_call(6000.0, 6000.0, 0.15, 0.0, 1.0 / 365.0, None);
+        let intraday = call
+            .with_expiry(&clock, at("2025-06-20T18:30"), expiry)
+            .unwrap();
+        assert!(intraday.bs_pricing() < 0.6 * call.bs_pricing());
+        assert!(intraday.theta() < 1.7 * call.theta());
+    }
+}
@@ -0,0 +1,225 @@
+// Commodity underlyings. A physical commodity costs money to store and pays its holder a
+// convenience yield for having it on hand, so its forwards carry at r + u - y rather than at a
+// dividend yield; gas, power and crops also price delivery months seasonally, winter gas over
+// summer gas whatever the carry. Options on commodity futures are then Black-76 on the forward
+// of their delivery month.
+use crate::OptionType;
+use crate::calendar::Date;
+use crate::conventions::CALENDAR_DAYS_PER_YEAR;
+use crate::curves::{RateCurve, require_positive};
+use crate::error::PricingError;
+use crate::futures::FuturesOption;
+use crate::math::float::{exp, ln};
+
+/// Multiplicative price factor of each delivery month, January first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Seasonality {
+    factors: [f64; 12],
+}
+
+impl Default for Seasonality {
+    /// Every month priced alike
+    fn default() -> Self {
+        Seasonality { factors: [1.0; 12] }
+    }
+}
+
+impl Seasonality {
+    /// Fails unless every factor is positive and finite
+    pub fn new(factors: [f64; 12]) -> Result<Self, PricingError> {
+        if let Some(month) = factors
+            .iter()
+            .position(|&factor| !(factor > 0.0 && factor.is_finite()))
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "seasonal factor {} of month {} must be positive and finite",
+                factors[month],
+                month + 1
+            )));
+        }
+        Ok(Seasonality { factors })
+    }
+    /// Factor of calendar month `month`, 1 to 12
+    pub fn factor(&self, month: u32) -> f64 {
+        self.factors[(month as usize).clamp(1, 12) - 1]
+    }
+}
+
+/// Carry model of a commodity: funding, storage cost and convenience yield curves with the
+/// seasonal shape of its delivery months, as of a valuation date.
+///
+/// Forward: F(T) = S e^((r(T) + u(T) - y(T)) T) s(m_T) / s(m_0)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommodityCarry {
+    pub as_of: Date,
+    pub funding: RateCurve,
+    pub storage: RateCurve,
+    pub convenience: RateCurve,
+    pub seasonality: Seasonality,
+}
+
+impl CommodityCarry {
+    /// Carry without seasonality
+    pub fn new(
+        as_of: Date,
+        funding: RateCurve,
+        storage: RateCurve,
+        convenience: RateCurve,
+    ) -> Self {
+        CommodityCarry {
+            as_of,
+            funding,
+            storage,
+            convenience,
+            seasonality: Seasonality::default(),
+        }
+    }
+    pub fn with_seasonality(self, seasonality: Seasonality) -> Self {
+        CommodityCarry {
+            seasonality,
+            ..self
+        }
+    }
+    /// Cost of carry r + u - y to time t
+    pub fn carry_rate(&self, t: f64) -> f64 {
+        self.funding.rate(t) + self.storage.rate(t) - self.convenience.rate(t)
+    }
+    /// Calendar month of delivery `t` years after the valuation date
+    pub fn delivery_month(&self, t: f64) -> u32 {
+        let days = (t * CALENDAR_DAYS_PER_YEAR).round() as i64;
+        Date::from_days_since_epoch(self.as_of.days_since_epoch() + days).month()
+    }
+    // Seasonal factor of delivery at t relative to today's month, which spot already prices
+    fn seasonal_ratio(&self, t: f64) -> f64 {
+        self.seasonality.factor(self.delivery_month(t))
+            / self.seasonality.factor(self.as_of.month())
+    }
+    pub fn forward(&self, spot: f64, t: f64) -> f64 {
+        spot * exp(self.carry_rate(t) * t) * self.seasonal_ratio(t)
+    }
+    /// Calibrates the convenience yield curve so forwards reprice the given (delivery, futures
+    /// price) quotes, the seasonal shape taken as known. Fails unless the spot and every delivery
+    /// and price are positive and finite
+    pub fn from_futures(
+        as_of: Date,
+        spot: f64,
+        futures: &[(f64, f64)],
+        funding: RateCurve,
+        storage: RateCurve,
+        seasonality: Seasonality,
+    ) -> Result<Self, PricingError> {
+        require_positive("spot", spot)?;
+        let mut carry = CommodityCarry::new(as_of, funding, storage, RateCurve::flat(0.0))
+            .with_seasonality(seasonality);
+        let pillars = futures
+            .iter()
+            .map(|&(t, price)| {
+                require_positive("futures delivery", t)?;
+                require_positive("futures price", price)?;
+                let deseasonalized = price / carry.seasonal_ratio(t);
+                let yield_t =
+                    carry.funding.rate(t) + carry.storage.rate(t) - ln(deseasonalized / spot) / t;
+                Ok((t, yield_t))
+            })
+            .collect::<Result<_, PricingError>>()?;
+        carry.convenience = RateCurve::new(pillars);
+        Ok(carry)
+    }
+    /// Black-76 option expiring at `expiry` on the future delivering at `delivery`, discounted
+    /// on the funding curve
+    pub fn futures_option(
+        &self,
+        option_type: OptionType,
+        strike: f64,
+        spot: f64,
+        volatility: f64,
+        expiry: f64,
+        delivery: f64,
+    ) -> FuturesOption {
+        FuturesOption::new(
+            option_type,
+            strike,
+            self.forward(spot, delivery),
+            volatility,
+            self.funding.rate(expiry),
+            expiry,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seasonal_gas_forwards() {
+        let as_of = Date::new(2025, 6, 2).unwrap();
+        // winter gas a third over summer gas
+        let mut factors = [1.0; 12];
+        for month in [1, 2, 11, 12] {
+            factors[month - 1] = 4.0 / 3.0;
+        }
+        let seasonality = Seasonality::new(factors).unwrap();
+        assert!(Seasonality::new([0.0; 12]).is_err());
+        let carry = CommodityCarry::new(
+            as_of,
+            RateCurve::flat(0.04),
+            RateCurve::flat(0.03),
+            RateCurve::flat(0.02),
+        );
+        let spot = 3.0;
+        assert!((carry.carry_rate(1.0) - 0.05).abs() < 1e-15);
+        assert!((carry.forward(spot, 0.5) - spot * (0.05f64 * 0.5).exp()).abs() < 1e-12);
+        let seasonal = carry.with_seasonality(seasonality);
+        // 0.6y from June delivers in January
+        assert_eq!(seasonal.delivery_month(0.6), 1);
+        let winter = seasonal.forward(spot, 0.6);
+        assert!((winter - spot * (0.05f64 * 0.6).exp() * 4.0 / 3.0).abs() < 1e-12);
+
+        // calibration recovers the convenience yield behind quoted futures
+        let quotes = [(0.25, seasonal.forward(spot, 0.25)), (0.6, winter)];
+        let fitted = CommodityCarry::from_futures(
+            as_of,
+            spot,
+            &quotes,
+            RateCurve::flat(0.04),
+            RateCurve::flat(0.03),
+            seasonality,
+        )
+        .unwrap();
+        assert!((fitted.convenience.rate(0.6) - 0.02).abs() < 1e-12);
+        for (t, price) in quotes {
+            assert!((fitted.forward(spot, t) - price).abs() < 1e-12);
+        }
+
+        // a December option on the January future is Black-76 on the winter forward
+        let option = fitted.futures_option(OptionType::Call, 4.0, spot, 0.5, 0.5, 0.6);
+        assert!((option.futures - winter).abs() < 1e-12);
+        assert_eq!(option.rate, 0.04);
+        assert!(option.price() > 0.0);
+    }
+
+    #[test]
+    fn test_calibration_rejects_degenerate_quotes() {
+        let as_of = Date::new(2025, 6, 2).unwrap();
+        let fit = |spot, quote| {
+            CommodityCarry::from_futures(
+                as_of,
+                spot,
+                &[quote],
+                RateCurve::flat(0.04),
+                RateCurve::flat(0.03),
+                Seasonality::default(),
+            )
+        };
+        assert!(fit(3.0, (0.5, 3.1)).is_ok());
+        assert!(fit(0.0, (0.5, 3.1)).is_err());
+        assert!(fit(-3.0, (0.5, 3.1)).is_err());
+        assert!(fit(f64::NAN, (0.5, 3.1)).is_err());
+        assert!(fit(3.0, (0.0, 3.1)).is_err());
+        assert!(fit(3.0, (-0.5, 3.1)).is_err());
+        assert!(fit(3.0, (0.5, 0.0)).is_err());
+        assert!(fit(3.0, (0.5, -3.1)).is_err());
+        assert!(fit(3.0, (0.5, f64::INFINITY)).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+// Units Greeks are quoted in. The pricers return theta per year of time decay and vega per unit
+// of volatility; desks quote theta per calendar day (Act/365) or per trading day (252 a year) and
+// vega per vol point, and the choice is made explicit here.
+use crate::Options;
+use crate::generic::BlackScholes;
+use crate::lattice::{ExerciseStyle, LatticeEngine};
+use crate::math::real::Real;
+#[cfg(feature = "std")]
+use crate::portfolio::Portfolio;
+
+/// Calendar days in a year, matching the Act/365 times to expiry
+pub const CALENDAR_DAYS_PER_YEAR: f64 = 365.0;
+/// Trading days in a year
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+/// One vol point, a 1% move in volatility
+pub const VOL_POINT: f64 = 0.01;
+
+// Volatility bump of the numerical vegas, relative to the volatility
+const VOL_BUMP: f64 = 1e-4;
+
+/// Time unit of a quoted theta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ThetaConvention {
+    /// Annualized, as the pricers return it
+    #[default]
+    PerYear,
+    /// Value lost over one calendar day
+    PerCalendarDay,
+    /// Value lost over one trading day
+    PerTradingDay,
+}
+
+impl ThetaConvention {
+    /// Number of the convention's periods in a year
+    pub fn periods_per_year(&self) -> f64 {
+        match self {
+            ThetaConvention::PerYear => 1.0,
+            ThetaConvention::PerCalendarDay => CALENDAR_DAYS_PER_YEAR,
+            ThetaConvention::PerTradingDay => TRADING_DAYS_PER_YEAR,
+        }
+    }
+    /// The period a theta is quoted over, e.g. for column headers
+    pub fn unit(&self) -> &'static str {
+        match self {
+            ThetaConvention::PerYear => "year",
+            ThetaConvention::PerCalendarDay => "calendar day",
+            ThetaConvention::PerTradingDay => "trading day",
+        }
+    }
+    /// An annualized theta restated in this convention
+    pub fn scale(&self, theta: f64) -> f64 {
+        theta / self.periods_per_year()
+    }
+    /// A theta quoted in this convention restated in `target`
+    pub fn convert(&self, theta: f64, target: ThetaConvention) -> f64 {
+        target.scale(theta * self.periods_per_year())
+    }
+}
+
+/// Volatility move a quoted vega is per.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum VegaConvention {
+    /// Per 1.00 of volatility, as the pricers return it
+    #[default]
+    PerUnit,
+    /// Per vol point, the market quote: the value change for a 1% move in volatility
+    PerVolPoint,
+}
+
+impl VegaConvention {
+    /// Volatility move the convention quotes vega per
+    pub fn vol_move(&self) -> f64 {
+        match self {
+            VegaConvention::PerUnit => 1.0,
+            VegaConvention::PerVolPoint => VOL_POINT,
+        }
+    }
+    /// The move a vega is quoted per, e.g. for column headers
+    pub fn unit(&self) -> &'static str {
+        match self {
+            VegaConvention::PerUnit => "vol unit",
+            VegaConvention::PerVolPoint => "vol point",
+        }
+    }
+    /// A vega per unit of volatility restated in this convention
+    pub fn scale(&self, vega: f64) -> f64 {
+        vega * self.vol_move()
+    }
+    /// A vega quoted in this convention restated in `target`
+    pub fn convert(&self, vega: f64, target: VegaConvention) -> f64 {
+        target.scale(vega / self.vol_move())
+    }
+}
+
+/// Units of the theta and vega columns of a table or report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GreekUnits {
+    pub theta: ThetaConvention,
+    pub vega: VegaConvention,
+}
+
+impl GreekUnits {
+    /// Daily theta and vega per vol point, as most desks quote them
+    pub fn market() -> Self {
+        GreekUnits {
+            theta: ThetaConvention::PerCalendarDay,
+            vega: VegaConvention::PerVolPoint,
+        }
+    }
+    pub fn with_theta(self, theta: ThetaConvention) -> Self {
+        GreekUnits { theta, ..self }
+    }
+    pub fn with_vega(self, vega: VegaConvention) -> Self {
+        GreekUnits { vega, ..self }
+    }
+}
+
+/// Vega of any pricer by central differences in the volatility, quoted in `convention`
+pub fn bumped_vega(
+    price_at: impl Fn(f64) -> f64,
+    volatility: f64,
+    convention: VegaConvention,
+) -> f64 {
+    let h = VOL_BUMP * volatility.abs().max(VOL_POINT);
+    convention.scale((price_at(volatility + h) - price_at(volatility - h)) / (2.0 * h))
+}
+
+impl Options {
+    /// Theta at the stored spot and volatility, in the chosen time unit
+    pub fn theta_in(&self, convention: ThetaConvention) -> f64 {
+        convention.scale(self.theta())
+    }
+    /// Vega at the stored spot and volatility, per the chosen volatility move
+    pub fn vega_in(&self, convention: VegaConvention) -> f64 {
+        convention.scale(self.vega())
+    }
+}
+
+impl<T: Real> BlackScholes<T> {
+    /// Vega in `T`, per the chosen volatility move
+    pub fn vega_in(&self, convention: VegaConvention) -> T {
+        self.vega() * T::from_f64(convention.vol_move())
+    }
+}
+
+/// Vega of a tree price with the chosen exercise, by bumping the volatility of `option`
+pub fn lattice_vega(
+    tree: &impl LatticeEngine,
+    option: &Options,
+    style: ExerciseStyle,
+    convention: VegaConvention,
+) -> f64 {
+    let (spot, rate) = (option.spot_price(), option.risk_free_rate());
+    bumped_vega(
+        |vol| tree.price(&option.with_market(spot, vol, rate), style),
+        option.volatility(),
+        convention,
+    )
+}
+
+#[cfg(feature = "std")]
+impl Portfolio {
+    /// Quantity-weighted theta of the book, in the chosen time unit
+    pub fn theta_in(&self, convention: ThetaConvention) -> f64 {
+        convention.scale(self.theta())
+    }
+    /// Quantity-weighted vega of the book, per the chosen volatility move
+    pub fn vega_in(&self, convention: VegaConvention) -> f64 {
+        convention.scale(self.vega())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theta_conventions() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        assert_eq!(call.theta_in(ThetaConvention::PerYear), call.theta());
+        let daily = call.theta_in(ThetaConvention::PerCalendarDay);
+        assert!((daily - call.theta() / 365.0).abs() < 1e-15);
+        // a trading day carries more of the year's decay than a calendar day
+        let trading = call.theta_in(ThetaConvention::PerTradingDay);
+        assert!(trading < daily && daily < 0.0);
+        let restated =
+            ThetaConvention::PerTradingDay.convert(trading, ThetaConvention::PerCalendarDay);
+        assert!((restated - daily).abs() < 1e-15);
+        // a one-day bump of the expiry is close to the calendar-day theta
+        let bumped = call.with_time_to_maturity(0.5 - 1.0 / 365.0).bs_pricing() - call.bs_pricing();
+        assert!((bumped - daily).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_vega_conventions() {
+        let put = Options::new_put(100.0, 95.0, 0.25, 0.03, 1.0, Some(0.01));
+        let point = put.vega_in(VegaConvention::PerVolPoint);
+        assert!((point - put.vega() / 100.0).abs() < 1e-14);
+        // the quote per vol point is close to a one-point repricing
+        let repriced = put.with_market(95.0, 0.26, 0.03).bs_pricing() - put.bs_pricing();
+        assert!((point - repriced).abs() < 1e-3);
+        // analytic, generic and numerical vegas agree in the same convention
+        let generic = put.generic::<f64>().vega_in(VegaConvention::PerVolPoint);
+        assert!((generic - point).abs() < 1e-12);
+        let price_at = |vol: f64| put.with_market(95.0, vol, 0.03).bs_pricing();
+        let numerical = bumped_vega(price_at, 0.25, VegaConvention::PerVolPoint);
+        assert!((numerical - point).abs() < 1e-8);
+        let tree = crate::lattice::trinomial::TrinomialTree::new(400);
+        let on_tree = lattice_vega(
+            &tree,
+            &put,
+            ExerciseStyle::European,
+            VegaConvention::PerVolPoint,
+        );
+        assert!((on_tree - point).abs() < 1e-3);
+        assert!(
+            (VegaConvention::PerVolPoint.convert(point, VegaConvention::PerUnit) - put.vega())
+                .abs()
+                < 1e-12
+        );
+    }
+}
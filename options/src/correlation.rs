@@ -0,0 +1,282 @@
+// Covariance and correlation estimated from multi-asset return series. With few observations per
+// asset the sample correlation is noisy and often nearly singular, so it can be shrunk towards
+// the identity with the Ledoit-Wolf intensity, which minimizes the expected squared error of the
+// blend. Variances are left as estimated; only the correlations are pulled towards zero.
+use crate::error::PricingError;
+use crate::math::float::{powi, sqrt};
+use crate::math::linalg::Matrix;
+use crate::var::RiskFactors;
+use crate::vol::historical::Weighting;
+
+/// How far the sample correlations are pulled towards zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shrinkage {
+    None,
+    /// Intensity estimated from the data by Ledoit and Wolf's formula
+    LedoitWolf,
+    /// Fixed intensity in [0, 1], one giving uncorrelated assets
+    Fixed(f64),
+}
+
+/// Estimator of the covariance of return series, one column per asset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovarianceEstimator {
+    pub weighting: Weighting,
+    pub shrinkage: Shrinkage,
+}
+
+impl Default for CovarianceEstimator {
+    fn default() -> Self {
+        CovarianceEstimator {
+            weighting: Weighting::Equal,
+            shrinkage: Shrinkage::None,
+        }
+    }
+}
+
+/// Estimated covariance per observation period, with the correlation it implies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CovarianceEstimate {
+    pub covariance: Matrix,
+    pub correlation: Matrix,
+    /// Intensity the correlations were shrunk with, zero for the sample estimate
+    pub shrinkage: f64,
+}
+
+impl CovarianceEstimator {
+    pub fn with_ewma(self, decay: f64) -> Self {
+        CovarianceEstimator {
+            weighting: Weighting::Ewma { decay },
+            ..self
+        }
+    }
+    pub fn with_shrinkage(self, shrinkage: Shrinkage) -> Self {
+        CovarianceEstimator { shrinkage, ..self }
+    }
+    pub fn with_ledoit_wolf(self) -> Self {
+        self.with_shrinkage(Shrinkage::LedoitWolf)
+    }
+    /// Estimates the covariance of `returns`, one row per observation, oldest first, and one
+    /// column per asset. Unshrunk and equally weighted it is the sample covariance of
+    /// `RiskFactors::from_returns`.
+    ///
+    /// The Ledoit-Wolf intensity generalizes to weighted observations as
+    /// δ = Σₖ wₖ² Σᵢ≠ⱼ (zₖᵢ zₖⱼ - ρᵢⱼ)² / Σᵢ≠ⱼ ρᵢⱼ², capped at one, with z the standardized
+    /// returns. Fails with fewer than two observations, ragged rows or a constant series
+    pub fn estimate(&self, returns: &[Vec<f64>]) -> Result<CovarianceEstimate, PricingError> {
+        self.weighting.validate()?;
+        let Some(first) = returns.first() else {
+            return Err(PricingError::InvalidInput(
+                "no return observations".to_string(),
+            ));
+        };
+        let assets = first.len();
+        if returns.len() < 2 || returns.iter().any(|row| row.len() != assets) {
+            return Err(PricingError::InvalidInput(
+                "need at least two observations with one return per asset".to_string(),
+            ));
+        }
+        let weights = self.weighting.weights(returns.len());
+        let means: Vec<f64> = (0..assets)
+            .map(|j| {
+                returns
+                    .iter()
+                    .zip(&weights)
+                    .map(|(row, w)| w * row[j])
+                    .sum()
+            })
+            .collect();
+        let mut vols = vec![0.0; assets];
+        for (j, vol) in vols.iter_mut().enumerate() {
+            let variance: f64 = returns
+                .iter()
+                .zip(&weights)
+                .map(|(row, w)| w * powi(row[j] - means[j], 2))
+                .sum();
+            if !(variance > 0.0 && variance.is_finite()) {
+                return Err(PricingError::InvalidInput(format!(
+                    "returns of asset {} do not vary",
+                    j
+                )));
+            }
+            *vol = sqrt(variance);
+        }
+        let standardized: Matrix = returns
+            .iter()
+            .map(|row| (0..assets).map(|j| (row[j] - means[j]) / vols[j]).collect())
+            .collect();
+        let mut correlation: Matrix = (0..assets)
+            .map(|i| {
+                (0..assets)
+                    .map(|j| {
+                        let rho: f64 = standardized
+                            .iter()
+                            .zip(&weights)
+                            .map(|(z, w)| w * z[i] * z[j])
+                            .sum();
+                        if i == j { 1.0 } else { rho }
+                    })
+                    .collect()
+            })
+            .collect();
+        let shrinkage = match self.shrinkage {
+            Shrinkage::None => 0.0,
+            Shrinkage::Fixed(intensity) if (0.0..=1.0).contains(&intensity) => intensity,
+            Shrinkage::Fixed(intensity) => {
+                return Err(PricingError::InvalidInput(format!(
+                    "shrinkage intensity {} is outside [0, 1]",
+                    intensity
+                )));
+            }
+            Shrinkage::LedoitWolf => ledoit_wolf(&standardized, &weights, &correlation),
+        };
+        for (i, row) in correlation.iter_mut().enumerate() {
+            for (j, rho) in row.iter_mut().enumerate() {
+                if i != j {
+                    *rho *= 1.0 - shrinkage;
+                }
+            }
+        }
+        // Bessel's correction for the effective number of observations 1 / Σw²
+        let concentration: f64 = weights.iter().map(|w| w * w).sum();
+        let covariance = (0..assets)
+            .map(|i| {
+                (0..assets)
+                    .map(|j| vols[i] * vols[j] * correlation[i][j] / (1.0 - concentration))
+                    .collect()
+            })
+            .collect();
+        Ok(CovarianceEstimate {
+            covariance,
+            correlation,
+            shrinkage,
+        })
+    }
+}
+
+// Ledoit-Wolf intensity towards the identity of the correlation of standardized returns
+fn ledoit_wolf(standardized: &[Vec<f64>], weights: &[f64], correlation: &[Vec<f64>]) -> f64 {
+    let assets = correlation.len();
+    let (mut dispersion, mut noise) = (0.0, 0.0);
+    for i in 0..assets {
+        for j in (0..assets).filter(|&j| j != i) {
+            dispersion += correlation[i][j] * correlation[i][j];
+            noise += standardized
+                .iter()
+                .zip(weights)
+                .map(|(z, w)| w * w * powi(z[i] * z[j] - correlation[i][j], 2))
+                .sum::<f64>();
+        }
+    }
+    if dispersion > 0.0 {
+        (noise / dispersion).min(1.0)
+    } else {
+        0.0
+    }
+}
+
+impl CovarianceEstimate {
+    /// Volatility of each asset, annualized over `periods_per_year` observation periods
+    pub fn volatilities(&self, periods_per_year: f64) -> Vec<f64> {
+        (0..self.covariance.len())
+            .map(|i| sqrt(self.covariance[i][i] * periods_per_year))
+            .collect()
+    }
+    /// VaR risk factors with this covariance, one name per asset, for returns observed over the
+    /// VaR horizon
+    pub fn risk_factors(&self, names: Vec<String>) -> Result<RiskFactors, PricingError> {
+        RiskFactors::new(names, self.covariance.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::normal::Normal;
+
+    // Returns of `assets` independent standard normal series, drawn by inverting a Weyl sequence
+    fn independent(observations: usize, assets: usize) -> Matrix {
+        let normal = Normal::standard();
+        (0..observations)
+            .map(|k| {
+                (0..assets)
+                    .map(|j| {
+                        let u = ((k * assets + j + 1) as f64 * 0.618_033_988_749_895).fract();
+                        normal.inverse_cdf(u)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_covariance() {
+        let returns = vec![
+            vec![0.01, 0.02],
+            vec![-0.02, -0.01],
+            vec![0.015, 0.03],
+            vec![0.0, -0.02],
+        ];
+        let estimate = CovarianceEstimator::default().estimate(&returns).unwrap();
+        let names = vec!["AAA".to_string(), "BBB".to_string()];
+        let sample = RiskFactors::from_returns(names.clone(), returns.clone()).unwrap();
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((estimate.covariance[i][j] - sample.covariance[i][j]).abs() < 1e-15);
+            }
+        }
+        let vols = estimate.volatilities(1.0);
+        let rho = estimate.correlation[0][1];
+        assert!((rho * vols[0] * vols[1] - estimate.covariance[0][1]).abs() < 1e-15);
+        assert_eq!(estimate.correlation[1][1], 1.0);
+        assert_eq!(
+            estimate.risk_factors(names).unwrap().covariance,
+            estimate.covariance
+        );
+
+        // full shrinkage keeps the variances and drops the covariance
+        let diagonal = CovarianceEstimator::default()
+            .with_shrinkage(Shrinkage::Fixed(1.0))
+            .estimate(&returns)
+            .unwrap();
+        assert_eq!(diagonal.covariance[0][1], 0.0);
+        assert_eq!(diagonal.covariance[1][1], estimate.covariance[1][1]);
+        assert!(
+            CovarianceEstimator::default()
+                .estimate(&returns[..1])
+                .is_err()
+        );
+        let constant = vec![vec![0.01, 0.0], vec![0.02, 0.0]];
+        assert!(CovarianceEstimator::default().estimate(&constant).is_err());
+    }
+
+    #[test]
+    fn test_ledoit_wolf_shrinks_spurious_correlation() {
+        // 30 observations of 10 independent assets show sizeable spurious correlations
+        let returns = independent(30, 10);
+        let sample = CovarianceEstimator::default().estimate(&returns).unwrap();
+        let shrunk = CovarianceEstimator::default()
+            .with_ledoit_wolf()
+            .estimate(&returns)
+            .unwrap();
+        assert!(shrunk.shrinkage > 0.3 && shrunk.shrinkage <= 1.0);
+        // squared distance from the true, identity, correlation
+        let error = |estimate: &CovarianceEstimate| -> f64 {
+            let squares = estimate.correlation.iter().flatten().map(|rho| rho * rho);
+            squares.sum::<f64>() - 10.0
+        };
+        assert!(error(&shrunk) < error(&sample));
+        // moving together for 20 observations then opposite for 10: exponential weighting
+        // follows the latest regime
+        let regimes: Matrix = independent(30, 1)
+            .iter()
+            .enumerate()
+            .map(|(k, z)| vec![z[0], if k < 20 { z[0] } else { -z[0] }])
+            .collect();
+        let equal = CovarianceEstimator::default().estimate(&regimes).unwrap();
+        let ewma = CovarianceEstimator::default().with_ewma(0.8);
+        assert!(equal.correlation[0][1] > 0.0);
+        assert!(ewma.estimate(&regimes).unwrap().correlation[0][1] < 0.0);
+        assert!(ewma.with_ewma(0.0).estimate(&regimes).is_err());
+    }
+}
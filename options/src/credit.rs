@@ -0,0 +1,216 @@
+// Issuer default risk: hazard-rate term structures, survival-weighted discounting, and bonds
+// whose cash flows stop at default.
+use crate::bonds::FixedCouponBond;
+use crate::curves::RateCurve;
+use crate::error::PricingError;
+use crate::math::float::exp;
+use crate::math::sum::KahanSum;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+
+/// Highest hazard rate searched when bootstrapping CDS quotes
+pub const MAX_HAZARD_RATE: f64 = 10.0;
+/// Premium payments per year of the CDS contracts bootstrapped
+pub const CDS_PAYMENTS_PER_YEAR: u32 = 4;
+
+/// Piecewise-constant default intensity.
+///
+/// Each (time, hazard) pillar holds its rate from the previous pillar up to its time; the last
+/// rate is held flat beyond it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HazardCurve {
+    pillars: Vec<(f64, f64)>,
+}
+
+impl HazardCurve {
+    /// Builds a curve from (time, hazard) pillars; pillars are sorted by time
+    pub fn new(mut pillars: Vec<(f64, f64)>) -> Self {
+        pillars.sort_by(|a, b| a.0.total_cmp(&b.0));
+        HazardCurve { pillars }
+    }
+    pub fn flat(hazard: f64) -> Self {
+        HazardCurve {
+            pillars: vec![(0.0, hazard)],
+        }
+    }
+    pub fn pillars(&self) -> &[(f64, f64)] {
+        &self.pillars
+    }
+    /// Default intensity at time t
+    pub fn hazard(&self, t: f64) -> f64 {
+        self.pillars
+            .iter()
+            .find(|&&(end, _)| t <= end)
+            .or(self.pillars.last())
+            .map_or(0.0, |&(_, hazard)| hazard)
+    }
+    /// Integrated intensity up to time t.
+    ///
+    /// Formula: Λ(t) = Σ λ_i (min(t, t_i) - t_(i-1))
+    pub fn cumulative_hazard(&self, t: f64) -> f64 {
+        let mut total = 0.0;
+        let mut start = 0.0;
+        for &(end, hazard) in &self.pillars {
+            if t <= end {
+                return total + hazard * (t - start).max(0.0);
+            }
+            total += hazard * (end - start);
+            start = end;
+        }
+        let last = self.pillars.last().map_or(0.0, |&(_, hazard)| hazard);
+        total + last * (t - start).max(0.0)
+    }
+    /// Probability that the issuer survives to time t, Q(t) = e^(-Λ(t))
+    pub fn survival_probability(&self, t: f64) -> f64 {
+        exp(-self.cumulative_hazard(t))
+    }
+    pub fn default_probability(&self, t: f64) -> f64 {
+        1.0 - self.survival_probability(t)
+    }
+    /// Value today of one unit paid at t only if the issuer has not defaulted, P(t) Q(t)
+    pub fn risky_discount_factor(&self, rates: &RateCurve, t: f64) -> f64 {
+        rates.discount_factor(t) * self.survival_probability(t)
+    }
+    /// Running spread that prices a CDS to zero, with quarterly premiums, accrual paid on
+    /// default and protection paid at the end of the period of default.
+    ///
+    /// Formula: s = (1 - R) Σ P(t_k)(Q(t_(k-1)) - Q(t_k)) / Σ Δ_k P(t_k)(Q(t_k) + (Q(t_(k-1)) - Q(t_k))/2)
+    pub fn cds_par_spread(&self, maturity: f64, recovery: f64, rates: &RateCurve) -> f64 {
+        let mut protection = KahanSum::new();
+        let mut annuity = KahanSum::new();
+        let mut previous = (0.0, 1.0);
+        for t in payment_dates(maturity, CDS_PAYMENTS_PER_YEAR) {
+            let (survival, discount) = (self.survival_probability(t), rates.discount_factor(t));
+            let defaulted = previous.1 - survival;
+            protection.add((1.0 - recovery) * discount * defaulted);
+            annuity.add((t - previous.0) * discount * (survival + 0.5 * defaulted));
+            previous = (t, survival);
+        }
+        protection.value() / annuity.value()
+    }
+    /// Hazard curve repricing (maturity, par spread) CDS quotes, one pillar per quote.
+    ///
+    /// Pillars are solved in maturity order, each holding the earlier ones fixed
+    pub fn from_cds_spreads(
+        quotes: &[(f64, f64)],
+        recovery: f64,
+        rates: &RateCurve,
+    ) -> Result<Self, PricingError> {
+        Self::from_cds_spreads_with(quotes, recovery, rates, &NumericsConfig::default())
+    }
+    /// CDS bootstrap solved to the tolerance and iteration cap of `numerics`
+    pub fn from_cds_spreads_with(
+        quotes: &[(f64, f64)],
+        recovery: f64,
+        rates: &RateCurve,
+        numerics: &NumericsConfig,
+    ) -> Result<Self, PricingError> {
+        if !(0.0..1.0).contains(&recovery) {
+            return Err(PricingError::InvalidInput(format!(
+                "recovery rate {} must lie in [0, 1)",
+                recovery
+            )));
+        }
+        let mut sorted = quotes.to_vec();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut curve = HazardCurve { pillars: vec![] };
+        for (maturity, spread) in sorted {
+            let error = |hazard: f64| {
+                let mut trial = curve.clone();
+                trial.pillars.push((maturity, hazard));
+                trial.cds_par_spread(maturity, recovery, rates) - spread
+            };
+            if !(error(0.0) <= 0.0 && error(MAX_HAZARD_RATE) > 0.0) {
+                return Err(PricingError::InvalidInput(format!(
+                    "CDS spread {} at {} implies a hazard rate outside [0, {}]",
+                    spread, maturity, MAX_HAZARD_RATE
+                )));
+            }
+            let hazard = brent(
+                error,
+                0.0,
+                MAX_HAZARD_RATE,
+                numerics.tolerance,
+                numerics.max_iterations,
+            )?;
+            curve.pillars.push((maturity, hazard));
+        }
+        Ok(curve)
+    }
+}
+
+// Payment times every 1/frequency years up to maturity, the last period a short stub if needed
+fn payment_dates(maturity: f64, frequency: u32) -> Vec<f64> {
+    let period = 1.0 / frequency as f64;
+    let full = (maturity / period - 1e-9).floor().max(0.0) as usize;
+    (1..=full)
+        .map(|k| k as f64 * period)
+        .chain(core::iter::once(maturity))
+        .collect()
+}
+
+/// Fixed coupon bond of an issuer that can default, recovering a fraction of face value at the
+/// end of the coupon period it defaults in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DefaultableBond {
+    pub bond: FixedCouponBond,
+    pub recovery: f64,
+}
+
+impl DefaultableBond {
+    pub fn new(bond: FixedCouponBond, recovery: f64) -> Self {
+        DefaultableBond { bond, recovery }
+    }
+    /// Cash flows discounted with survival, plus the recovery on default.
+    ///
+    /// Formula: V = Σ CF_k P(t_k) Q(t_k) + R F Σ P(t_k)(Q(t_(k-1)) - Q(t_k))
+    pub fn price(&self, rates: &RateCurve, hazard: &HazardCurve) -> f64 {
+        let mut value = KahanSum::new();
+        let mut previous = 1.0;
+        for (t, amount) in self.bond.cash_flows() {
+            let survival = hazard.survival_probability(t);
+            let discount = rates.discount_factor(t);
+            value.add(amount * discount * survival);
+            value.add(self.recovery * self.bond.face_value * discount * (previous - survival));
+            previous = survival;
+        }
+        value.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hazard_curve_bootstrap_and_risky_bond() {
+        let curve = HazardCurve::new(vec![(3.0, 0.03), (1.0, 0.01)]);
+        assert!((curve.cumulative_hazard(2.0) - 0.04).abs() < 1e-12);
+        assert!((curve.cumulative_hazard(5.0) - 0.13).abs() < 1e-12);
+        assert_eq!(curve.hazard(0.5), 0.01);
+
+        // the credit triangle holds closely for a flat curve
+        let rates = RateCurve::flat(0.03);
+        let flat = HazardCurve::flat(0.02);
+        assert!((flat.cds_par_spread(5.0, 0.4, &rates) - 0.012).abs() < 1e-4);
+
+        let quotes = [(1.0, 0.006), (3.0, 0.009), (5.0, 0.012)];
+        let bootstrapped = HazardCurve::from_cds_spreads(&quotes, 0.4, &rates).unwrap();
+        for (maturity, spread) in quotes {
+            let par = bootstrapped.cds_par_spread(maturity, 0.4, &rates);
+            assert!((par - spread).abs() < 1e-9);
+        }
+        assert!(HazardCurve::from_cds_spreads(&[(1.0, -0.01)], 0.4, &rates).is_err());
+
+        // without default risk the bond is its risk-free value, and default costs value
+        let bond = FixedCouponBond::new(100.0, 0.05, 5.0, 2);
+        let riskless = DefaultableBond::new(bond, 0.4).price(&rates, &HazardCurve::flat(0.0));
+        let risk_free: f64 = bond
+            .cash_flows()
+            .iter()
+            .map(|&(t, cf)| cf * rates.discount_factor(t))
+            .sum();
+        assert!((riskless - risk_free).abs() < 1e-9);
+        assert!(DefaultableBond::new(bond, 0.4).price(&rates, &bootstrapped) < riskless);
+    }
+}
@@ -0,0 +1,155 @@
+// Coin-margined crypto conventions. Inverse options, as listed on Deribit, are struck and quoted
+// in USD per coin but pay and are premium-settled in the coin itself: a call pays
+// max(S - K, 0) / S coins. Received in coins that are worth S each, the payoff is the ordinary
+// USD payoff, so an inverse option is the vanilla Black-Scholes contract converted at spot; what
+// changes is the unit the premium and the Greeks are held in. Inverse perpetuals and futures
+// likewise settle a USD notional in coins.
+use crate::Options;
+use crate::error::PricingError;
+
+/// USD amount worth `coins` at `spot` USD per coin
+pub fn coin_to_usd(coins: f64, spot: f64) -> f64 {
+    coins * spot
+}
+
+/// Coins worth `usd` at `spot` USD per coin
+pub fn usd_to_coin(usd: f64, spot: f64) -> f64 {
+    usd / spot
+}
+
+/// A coin-settled option on a USD strike. The wrapped contract's spot is the USD index price,
+/// its rate the USD rate and its dividend yield the coin's own interest rate, so the forward is
+/// the matching futures price.
+#[derive(Debug, Clone, Copy)]
+pub struct InverseOption {
+    pub option: Options,
+}
+
+impl InverseOption {
+    pub fn new(option: Options) -> Self {
+        InverseOption { option }
+    }
+    fn spot(&self) -> f64 {
+        self.option.spot_price()
+    }
+    /// Premium in coins
+    ///
+    /// Formula: V = V_BS / S
+    pub fn price(&self) -> f64 {
+        usd_to_coin(self.option.bs_pricing(), self.spot())
+    }
+    /// Premium converted back to USD at spot, the vanilla Black-Scholes price
+    pub fn usd_price(&self) -> f64 {
+        self.option.bs_pricing()
+    }
+    /// Formula: max(φ(S - K), 0) / S coins
+    pub fn payout(&self, spot: f64) -> f64 {
+        usd_to_coin(self.option.payout(spot), spot)
+    }
+    /// Premium-adjusted delta: coins of the underlying the position is long once the coin
+    /// premium it is worth is netted off, the Black-Scholes delta less the coin price. A coin-
+    /// margined book hedges this, not the plain delta
+    ///
+    /// Formula: Δ = Δ_BS - V
+    pub fn delta(&self) -> f64 {
+        self.option.delta() - self.price()
+    }
+    /// Change in the premium-adjusted delta per USD move in spot
+    ///
+    /// Formula: Γ = Γ_BS - (Δ_BS - V) / S
+    pub fn gamma(&self) -> f64 {
+        self.option.gamma() - self.delta() / self.spot()
+    }
+    /// Coin value change per unit of volatility
+    ///
+    /// Formula: ν = ν_BS / S
+    pub fn vega(&self) -> f64 {
+        usd_to_coin(self.option.vega(), self.spot())
+    }
+    /// Annualized coin value change with spot held
+    ///
+    /// Formula: Θ = Θ_BS / S
+    pub fn theta(&self) -> f64 {
+        usd_to_coin(self.option.theta(), self.spot())
+    }
+    /// Coin value change per unit of the USD rate
+    ///
+    /// Formula: ρ = ρ_BS / S
+    pub fn rho(&self) -> f64 {
+        usd_to_coin(self.option.rho(), self.spot())
+    }
+    /// Volatility reproducing a premium quoted in coins
+    pub fn implied_volatility(&self, coin_price: f64) -> Result<f64, PricingError> {
+        self.option
+            .implied_volatility(coin_to_usd(coin_price, self.spot()))
+    }
+}
+
+/// An inverse perpetual or future: a USD notional, long when positive, settled in coins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InversePerpetual {
+    /// Contract value in USD, negative for a short
+    pub notional: f64,
+    /// Entry price in USD per coin
+    pub entry_price: f64,
+}
+
+impl InversePerpetual {
+    /// Coin profit at mark price `mark`
+    ///
+    /// Formula: PnL = N (1/entry - 1/mark)
+    pub fn pnl(&self, mark: f64) -> f64 {
+        self.notional * (1.0 / self.entry_price - 1.0 / mark)
+    }
+    /// Coin profit per USD move in the mark
+    ///
+    /// Formula: ∂PnL/∂S = N / mark²
+    pub fn delta(&self, mark: f64) -> f64 {
+        self.notional / (mark * mark)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse_option_in_coins() {
+        let (strike, spot) = (60_000.0, 55_000.0);
+        let vanilla = Options::new_call(strike, spot, 0.6, 0.05, 0.25, Some(0.01));
+        let inverse = InverseOption::new(vanilla);
+        assert!((inverse.price() * spot - vanilla.bs_pricing()).abs() < 1e-9);
+        assert!((inverse.payout(75_000.0) - 0.2).abs() < 1e-15);
+        assert_eq!(inverse.payout(50_000.0), 0.0);
+
+        // the premium-adjusted delta is the slope of the coin value scaled by spot
+        let coin_value = |s: f64| InverseOption::new(vanilla.with_market(s, 0.6, 0.05)).price();
+        let h = 1.0;
+        let slope = (coin_value(spot + h) - coin_value(spot - h)) / (2.0 * h);
+        assert!((inverse.delta() - spot * slope).abs() < 1e-6);
+        assert!(inverse.delta() < vanilla.delta());
+        let delta = |s: f64| InverseOption::new(vanilla.with_market(s, 0.6, 0.05)).delta();
+        let gamma = (delta(spot + h) - delta(spot - h)) / (2.0 * h);
+        assert!((inverse.gamma() - gamma).abs() < 1e-9);
+        assert!((inverse.vega() - vanilla.vega() / spot).abs() < 1e-15);
+
+        let vol = inverse.implied_volatility(inverse.price()).unwrap();
+        assert!((vol - 0.6).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_inverse_perpetual() {
+        let long = InversePerpetual {
+            notional: 10_000.0,
+            entry_price: 50_000.0,
+        };
+        let pnl = long.pnl(55_000.0);
+        assert!((pnl - 10_000.0 * (1.0 / 50_000.0 - 1.0 / 55_000.0)).abs() < 1e-15);
+        // a 10% rally earns less than the 10% fall loses, in coins
+        assert!(pnl < -long.pnl(45_000.0));
+        let h = 1.0;
+        let slope = (long.pnl(55_000.0 + h) - long.pnl(55_000.0 - h)) / (2.0 * h);
+        assert!((long.delta(55_000.0) - slope).abs() < 1e-12);
+        assert_eq!(usd_to_coin(coin_to_usd(0.5, 55_000.0), 55_000.0), 0.5);
+    }
+}
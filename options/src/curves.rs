@@ -0,0 +1,267 @@
+use crate::error::PricingError;
+use crate::math::float::{exp, ln};
+use crate::math::interp::{Interpolation, Spline};
+
+// Quote inputs a calibrated pillar takes a log or a ratio of, which would otherwise turn into a
+// NaN or infinite rate
+pub(crate) fn require_positive(name: &str, value: f64) -> Result<(), PricingError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(PricingError::InvalidInput(format!(
+            "{} {} must be positive and finite",
+            name, value
+        )))
+    }
+}
+
+/// Continuously compounded zero-rate term structure.
+///
+/// Rates are linearly interpolated between pillars unless a cubic interpolation is chosen, and
+/// held flat beyond the first and last pillar
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateCurve {
+    pillars: Vec<(f64, f64)>,
+    // Cubic interpolant of the rates, None for linear interpolation
+    spline: Option<Spline>,
+}
+
+impl RateCurve {
+    /// Builds a curve from (time, rate) pillars; pillars are sorted by time
+    pub fn new(mut pillars: Vec<(f64, f64)>) -> Self {
+        pillars.sort_by(|a, b| a.0.total_cmp(&b.0));
+        RateCurve {
+            pillars,
+            spline: None,
+        }
+    }
+    pub fn flat(rate: f64) -> Self {
+        RateCurve::new(vec![(0.0, rate)])
+    }
+    /// Interpolates the rates with `interpolation`; a monotone cubic keeps a monotone curve
+    /// monotone with smoother forwards than linear. Fails on pillars sharing a time
+    pub fn with_interpolation(self, interpolation: Interpolation) -> Result<Self, PricingError> {
+        let spline = match interpolation {
+            Interpolation::Linear => None,
+            _ => Some(Spline::new(
+                self.pillars.iter().map(|p| p.0).collect(),
+                self.pillars.iter().map(|p| p.1).collect(),
+                interpolation,
+            )?),
+        };
+        Ok(RateCurve { spline, ..self })
+    }
+    pub fn interpolation(&self) -> Interpolation {
+        self.spline
+            .as_ref()
+            .map_or(Interpolation::Linear, Spline::method)
+    }
+    pub fn pillars(&self) -> &[(f64, f64)] {
+        &self.pillars
+    }
+    /// Zero rate at time t
+    pub fn rate(&self, t: f64) -> f64 {
+        if let Some(spline) = &self.spline {
+            return spline.value(t);
+        }
+        let Some(&(first_t, first_rate)) = self.pillars.first() else {
+            return 0.0;
+        };
+        if t <= first_t {
+            return first_rate;
+        }
+        for window in self.pillars.windows(2) {
+            let (t0, r0) = window[0];
+            let (t1, r1) = window[1];
+            if t <= t1 {
+                return r0 + (r1 - r0) * (t - t0) / (t1 - t0);
+            }
+        }
+        self.pillars[self.pillars.len() - 1].1
+    }
+    pub fn discount_factor(&self, t: f64) -> f64 {
+        exp(-self.rate(t) * t)
+    }
+    /// Simply compounded forward rate between two times, as fixed by a floating coupon
+    ///
+    /// Formula: F = (P(t₁) / P(t₂) - 1) / (t₂ - t₁)
+    pub fn forward_rate(&self, start: f64, end: f64) -> f64 {
+        (self.discount_factor(start) / self.discount_factor(end) - 1.0) / (end - start)
+    }
+    /// Implied financing curve from box spread prices
+    ///
+    /// A box paying (K_high - K_low) at expiry trades at (K_high - K_low) * e^(-rT), so each quote
+    /// (expiry, k_low, k_high, box_price) gives the option-implied rate for its expiry. Fails
+    /// unless every expiry, price and strike width is positive and finite
+    pub fn from_box_spreads(quotes: &[(f64, f64, f64, f64)]) -> Result<Self, PricingError> {
+        let pillars = quotes
+            .iter()
+            .map(|&(t, k_low, k_high, price)| {
+                require_positive("box expiry", t)?;
+                require_positive("box strike width", k_high - k_low)?;
+                require_positive("box price", price)?;
+                Ok((t, -ln(price / (k_high - k_low)) / t))
+            })
+            .collect::<Result<_, PricingError>>()?;
+        Ok(RateCurve::new(pillars))
+    }
+}
+
+/// Carry model of an equity underlying: funding curve, borrow (repo spread) curve and dividend yield.
+///
+/// Forward: F(T) = S * e^((r(T) - b(T) - q) * T)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityCarry {
+    pub funding: RateCurve,
+    pub borrow: RateCurve,
+    pub dividend_yield: Option<f64>,
+}
+
+impl EquityCarry {
+    pub fn new(funding: RateCurve, borrow: RateCurve, dividend_yield: Option<f64>) -> Self {
+        EquityCarry {
+            funding,
+            borrow,
+            dividend_yield,
+        }
+    }
+    pub fn forward(&self, spot: f64, t: f64) -> f64 {
+        spot * exp((self.funding.rate(t) - self.carry_yield(t)) * t)
+    }
+    /// Dividend yield plus borrow cost at time t, usable wherever a single dividend yield is expected
+    pub fn carry_yield(&self, t: f64) -> f64 {
+        self.dividend_yield.unwrap_or(0.0) + self.borrow.rate(t)
+    }
+    /// Calibrates the borrow curve so forwards reprice the given (expiry, futures price) quotes.
+    /// Fails unless the spot and every expiry and price are positive and finite
+    pub fn from_futures(
+        spot: f64,
+        futures: &[(f64, f64)],
+        funding: RateCurve,
+        dividend_yield: Option<f64>,
+    ) -> Result<Self, PricingError> {
+        require_positive("spot", spot)?;
+        let q = dividend_yield.unwrap_or(0.0);
+        if !q.is_finite() {
+            return Err(PricingError::InvalidInput(format!(
+                "dividend yield {} must be finite",
+                q
+            )));
+        }
+        let pillars = futures
+            .iter()
+            .map(|&(t, price)| {
+                require_positive("futures expiry", t)?;
+                require_positive("futures price", price)?;
+                Ok((t, funding.rate(t) - q - ln(price / spot) / t))
+            })
+            .collect::<Result<_, PricingError>>()?;
+        Ok(EquityCarry::new(
+            funding,
+            RateCurve::new(pillars),
+            dividend_yield,
+        ))
+    }
+    /// Calibrates the borrow curve as the spread between a reference rate curve and the
+    /// box-spread implied financing curve of the underlying's listed options. Fails on the quotes
+    /// `RateCurve::from_box_spreads` rejects
+    pub fn from_box_spreads(
+        reference: RateCurve,
+        quotes: &[(f64, f64, f64, f64)],
+        dividend_yield: Option<f64>,
+    ) -> Result<Self, PricingError> {
+        let implied = RateCurve::from_box_spreads(quotes)?;
+        let borrow = RateCurve::new(
+            implied
+                .pillars()
+                .iter()
+                .map(|&(t, rate)| (t, reference.rate(t) - rate))
+                .collect(),
+        );
+        Ok(EquityCarry::new(reference, borrow, dividend_yield))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_curve_interpolation() {
+        let curve = RateCurve::new(vec![(2.0, 0.04), (1.0, 0.02)]);
+        assert_eq!(curve.rate(0.5), 0.02);
+        assert!((curve.rate(1.5) - 0.03).abs() < 1e-12);
+        assert_eq!(curve.rate(5.0), 0.04);
+        assert!((curve.discount_factor(2.0) - (-0.08f64).exp()).abs() < 1e-12);
+
+        let boxes =
+            RateCurve::from_box_spreads(&[(1.0, 90.0, 110.0, 20.0 * (-0.045f64).exp())]).unwrap();
+        assert!((boxes.rate(1.0) - 0.045).abs() < 1e-12);
+
+        // a monotone cubic still passes through the pillars but has no kinks between them
+        let pillars = vec![(0.5, 0.02), (1.0, 0.025), (2.0, 0.032), (5.0, 0.04)];
+        let cubic = RateCurve::new(pillars.clone())
+            .with_interpolation(Interpolation::MonotoneCubic)
+            .unwrap();
+        assert_eq!(cubic.interpolation(), Interpolation::MonotoneCubic);
+        for (t, rate) in pillars {
+            assert!((cubic.rate(t) - rate).abs() < 1e-15);
+        }
+        let left = (cubic.rate(1.0) - cubic.rate(1.0 - 1e-6)) / 1e-6;
+        let right = (cubic.rate(1.0 + 1e-6) - cubic.rate(1.0)) / 1e-6;
+        assert!((left - right).abs() < 1e-5 && right > 0.0);
+        let duplicated = RateCurve::new(vec![(1.0, 0.02), (1.0, 0.03)]);
+        assert!(
+            duplicated
+                .with_interpolation(Interpolation::NaturalCubic)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_borrow_calibration_from_futures() {
+        let spot = 100.0;
+        let funding = RateCurve::flat(0.05);
+        // futures priced with 1% dividend and a borrow term structure of 0.5% then 1%
+        let futures = [
+            (0.5, spot * ((0.05 - 0.01 - 0.005) * 0.5f64).exp()),
+            (1.0, spot * ((0.05 - 0.01 - 0.01) * 1.0f64).exp()),
+        ];
+        let carry = EquityCarry::from_futures(spot, &futures, funding, Some(0.01)).unwrap();
+        assert!((carry.borrow.rate(0.5) - 0.005).abs() < 1e-12);
+        assert!((carry.borrow.rate(1.0) - 0.01).abs() < 1e-12);
+        for (t, price) in futures {
+            assert!((carry.forward(spot, t) - price).abs() < 1e-9);
+        }
+        assert!((carry.carry_yield(1.0) - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_calibration_rejects_degenerate_quotes() {
+        let boxes = |quote| RateCurve::from_box_spreads(&[quote]);
+        assert!(boxes((1.0, 90.0, 110.0, 19.0)).is_ok());
+        assert!(boxes((0.0, 90.0, 110.0, 19.0)).is_err());
+        assert!(boxes((-1.0, 90.0, 110.0, 19.0)).is_err());
+        assert!(boxes((1.0, 90.0, 110.0, 0.0)).is_err());
+        assert!(boxes((1.0, 110.0, 90.0, 19.0)).is_err());
+        assert!(boxes((1.0, 90.0, 90.0, 19.0)).is_err());
+        assert!(boxes((1.0, 90.0, f64::INFINITY, 19.0)).is_err());
+        assert!(boxes((f64::NAN, 90.0, 110.0, 19.0)).is_err());
+
+        let futures =
+            |spot, quote, q| EquityCarry::from_futures(spot, &[quote], RateCurve::flat(0.05), q);
+        assert!(futures(100.0, (1.0, 102.0), None).is_ok());
+        assert!(futures(0.0, (1.0, 102.0), None).is_err());
+        assert!(futures(-100.0, (1.0, 102.0), None).is_err());
+        assert!(futures(f64::NAN, (1.0, 102.0), None).is_err());
+        assert!(futures(100.0, (0.0, 102.0), None).is_err());
+        assert!(futures(100.0, (1.0, 0.0), None).is_err());
+        assert!(futures(100.0, (1.0, f64::INFINITY), None).is_err());
+        assert!(futures(100.0, (1.0, 102.0), Some(f64::NAN)).is_err());
+
+        let reference = RateCurve::flat(0.05);
+        assert!(
+            EquityCarry::from_box_spreads(reference, &[(1.0, 90.0, 110.0, -1.0)], None).is_err()
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// Time-decay projections: a position rolled forward one calendar day at a time with vols and
+// rates held, valued each day until its last leg expires. The path shows how theta accelerates
+// into expiry, which a single theta number hides.
+use crate::Options;
+use crate::conventions::CALENDAR_DAYS_PER_YEAR;
+use crate::math::float::exp;
+use crate::strategy::{Leg, Strategy};
+
+// Fraction of a day an expiry may run over a whole day and still count as falling on it
+const DAY_TOLERANCE: f64 = 1e-9;
+
+/// Where spot sits while the position is rolled forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpotPath {
+    /// Spot held at today's level, isolating pure time decay
+    #[default]
+    Fixed,
+    /// Spot rolling along its forward at the carry r - q of each leg, so only the decay the
+    /// market does not already price in as drift remains
+    Forward,
+}
+
+/// Value of the position at the end of one day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecayPoint {
+    /// Calendar days from today, zero for today
+    pub day: usize,
+    pub value: f64,
+    /// Value change over the day, zero on day zero
+    pub daily_change: f64,
+    /// Value change since today
+    pub cumulative_change: f64,
+}
+
+/// Day-by-day value of a position until its last expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayProjection {
+    pub points: Vec<DecayPoint>,
+}
+
+impl DecayProjection {
+    /// Rolls legs forward a calendar day at a time under `path`. A leg that expires on the way is
+    /// worth its payout at the spot of its expiry from then on
+    pub fn of_legs(legs: &[Leg], path: SpotPath) -> Self {
+        let last_expiry = legs
+            .iter()
+            .map(|leg| leg.option.time_to_maturity())
+            .fold(0.0, f64::max);
+        let days = (last_expiry * CALENDAR_DAYS_PER_YEAR - DAY_TOLERANCE)
+            .ceil()
+            .max(0.0) as usize;
+        let value_on = |day: usize| -> f64 {
+            let elapsed = day as f64 / CALENDAR_DAYS_PER_YEAR;
+            legs.iter()
+                .map(|leg| leg.quantity * leg_value(&leg.option, elapsed, path))
+                .sum()
+        };
+        let today = value_on(0);
+        let mut previous = today;
+        let points = (0..=days)
+            .map(|day| {
+                let value = value_on(day);
+                let point = DecayPoint {
+                    day,
+                    value,
+                    daily_change: value - previous,
+                    cumulative_change: value - today,
+                };
+                previous = value;
+                point
+            })
+            .collect();
+        DecayProjection { points }
+    }
+    /// Value lost from today to the last expiry, positive for a long premium position
+    pub fn total_decay(&self) -> f64 {
+        self.points
+            .last()
+            .map_or(0.0, |last| -last.cumulative_change)
+    }
+    /// Day losing the most value, None for an empty projection
+    pub fn steepest_day(&self) -> Option<&DecayPoint> {
+        self.points
+            .iter()
+            .skip(1)
+            .min_by(|a, b| a.daily_change.total_cmp(&b.daily_change))
+    }
+}
+
+// Value of one contract `elapsed` years from today, its payout once expired
+fn leg_value(option: &Options, elapsed: f64, path: SpotPath) -> f64 {
+    let maturity = option.time_to_maturity();
+    let carry = option.risk_free_rate() - option.dividend_yield().unwrap_or(0.0);
+    let spot_at = |t: f64| match path {
+        SpotPath::Fixed => option.spot_price(),
+        SpotPath::Forward => option.spot_price() * exp(carry * t),
+    };
+    if elapsed >= maturity {
+        return option.payout(spot_at(maturity));
+    }
+    option
+        .with_market(
+            spot_at(elapsed),
+            option.volatility(),
+            option.risk_free_rate(),
+        )
+        .with_time_to_maturity(maturity - elapsed)
+        .bs_pricing()
+}
+
+impl Options {
+    /// Day-by-day value of one long contract until expiry
+    pub fn decay_projection(&self, path: SpotPath) -> DecayProjection {
+        DecayProjection::of_legs(
+            &[Leg {
+                option: *self,
+                quantity: 1.0,
+            }],
+            path,
+        )
+    }
+}
+
+impl Strategy {
+    /// Day-by-day value of the strategy until its last leg expires
+    pub fn decay_projection(&self, path: SpotPath) -> DecayProjection {
+        DecayProjection::of_legs(self.legs(), path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conventions::ThetaConvention;
+
+    #[test]
+    fn test_decay_of_a_straddle() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.0, 30.0 / 365.0, None);
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.0, 30.0 / 365.0, None);
+        let projection = Strategy::straddle(call, put).decay_projection(SpotPath::Fixed);
+        assert_eq!(projection.points.len(), 31);
+        // an at-the-money straddle decays to nothing, faster each day
+        let total = call.bs_pricing() + put.bs_pricing();
+        assert!((projection.total_decay() - total).abs() < 1e-12);
+        assert_eq!(projection.steepest_day().unwrap().day, 30);
+        let changes: Vec<f64> = projection.points[1..]
+            .iter()
+            .map(|p| p.daily_change)
+            .collect();
+        assert!(changes.windows(2).all(|w| w[1] < w[0]));
+        // the first day's loss is the quoted daily theta
+        let theta = ThetaConvention::PerCalendarDay.scale(call.theta() + put.theta());
+        assert!((changes[0] - theta).abs() < 0.01 * theta.abs());
+    }
+
+    #[test]
+    fn test_decay_along_the_forward() {
+        // deep in the money, a call rolled along a carrying forward gains more than it bleeds
+        let call = Options::new_call(50.0, 100.0, 0.2, 0.05, 1.0, None);
+        let fixed = call.decay_projection(SpotPath::Fixed);
+        let forward = call.decay_projection(SpotPath::Forward);
+        let last = |p: &DecayProjection| p.points.last().unwrap().value;
+        assert!((last(&fixed) - 50.0).abs() < 1e-12);
+        assert!((last(&forward) - (100.0 * 0.05f64.exp() - 50.0)).abs() < 1e-9);
+        assert!(forward.total_decay() < 0.0 && fixed.total_decay() > 0.0);
+    }
+}
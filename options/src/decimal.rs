@@ -0,0 +1,140 @@
+// Decimal output layer for reporting. Results are computed in f64, converted exactly and then
+// rounded once under an explicit policy, so identical f64 inputs print identically everywhere;
+// combine with `strict_math` to make the f64 results themselves platform-independent.
+use crate::Options;
+use crate::error::PricingError;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Largest number of decimal places a `Decimal` can hold
+pub const MAX_DECIMAL_PLACES: u32 = 28;
+
+/// How the exact decimal value of an f64 is cut to the reported precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Rounding {
+    /// Ties to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Ties away from zero
+    HalfUp,
+    /// Towards zero
+    Truncate,
+    /// Away from zero
+    Up,
+}
+
+impl Rounding {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            Rounding::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Rounding::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Rounding::Truncate => RoundingStrategy::ToZero,
+            Rounding::Up => RoundingStrategy::AwayFromZero,
+        }
+    }
+}
+
+/// Precision and rounding applied to every reported number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecimalPolicy {
+    /// Decimal places kept, at most `MAX_DECIMAL_PLACES`
+    pub decimal_places: u32,
+    pub rounding: Rounding,
+}
+
+impl Default for DecimalPolicy {
+    fn default() -> Self {
+        DecimalPolicy {
+            decimal_places: 8,
+            rounding: Rounding::HalfEven,
+        }
+    }
+}
+
+impl DecimalPolicy {
+    pub fn new(decimal_places: u32, rounding: Rounding) -> Result<Self, PricingError> {
+        if decimal_places > MAX_DECIMAL_PLACES {
+            return Err(PricingError::InvalidInput(format!(
+                "{} decimal places exceed the maximum of {}",
+                decimal_places, MAX_DECIMAL_PLACES
+            )));
+        }
+        Ok(DecimalPolicy {
+            decimal_places,
+            rounding,
+        })
+    }
+    /// Rounds the exact binary value of `value`, so the result never depends on how the float
+    /// would have been printed
+    pub fn to_decimal(&self, value: f64) -> Result<Decimal, PricingError> {
+        let exact = Decimal::from_f64_retain(value).ok_or_else(|| {
+            PricingError::InvalidInput(format!("{} has no decimal representation", value))
+        })?;
+        Ok(exact.round_dp_with_strategy(self.decimal_places, self.rounding.strategy()))
+    }
+    /// Re-rounds a decimal that came from elsewhere to this policy
+    pub fn round(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.decimal_places, self.rounding.strategy())
+    }
+}
+
+/// Nearest f64 to a decimal, for feeding reported figures back into the f64 engines
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Price and Greeks of one contract, rounded under a `DecimalPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecimalPricing {
+    pub price: Decimal,
+    pub delta: Decimal,
+    pub gamma: Decimal,
+    pub vega: Decimal,
+    pub theta: Decimal,
+    pub rho: Decimal,
+}
+
+impl Options {
+    /// Black-Scholes price and Greeks as decimals; fails if any of them is not finite
+    pub fn price_decimal(&self, policy: &DecimalPolicy) -> Result<DecimalPricing, PricingError> {
+        Ok(DecimalPricing {
+            price: policy.to_decimal(self.bs_pricing())?,
+            delta: policy.to_decimal(self.delta())?,
+            gamma: policy.to_decimal(self.gamma())?,
+            vega: policy.to_decimal(self.vega())?,
+            theta: policy.to_decimal(self.theta())?,
+            rho: policy.to_decimal(self.rho())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_rounding_policy() {
+        let option = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let report = option.price_decimal(&DecimalPolicy::default()).unwrap();
+        assert_eq!(report.price.to_string(), "10.45058357");
+        assert!((to_f64(report.delta) - option.delta()).abs() < 1e-8);
+
+        let cents = DecimalPolicy::new(0, Rounding::HalfEven).unwrap();
+        assert_eq!(cents.to_decimal(2.5).unwrap(), Decimal::from(2));
+        assert_eq!(cents.to_decimal(3.5).unwrap(), Decimal::from(4));
+        let half_up = DecimalPolicy::new(0, Rounding::HalfUp).unwrap();
+        assert_eq!(half_up.to_decimal(2.5).unwrap(), Decimal::from(3));
+        // 1.005 is stored just below the midpoint, so it rounds down under either rule
+        let two = DecimalPolicy::new(2, Rounding::HalfUp).unwrap();
+        assert_eq!(two.to_decimal(1.005).unwrap().to_string(), "1.00");
+
+        assert!(cents.to_decimal(f64::NAN).is_err());
+        assert!(DecimalPolicy::new(29, Rounding::Truncate).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// One-line descriptions of contracts for logs, tables and terminals, e.g.
+// "CALL 100 @ 1.0y, σ=20%, r=5%". Amounts drop trailing zeros, rates and vols print as percent.
+#[cfg(feature = "std")]
+use crate::bonds::FixedCouponBond;
+#[cfg(feature = "std")]
+use crate::credit::DefaultableBond;
+#[cfg(feature = "std")]
+use crate::exotics::{Autocallable, ConvertibleBond, ExoticOptions};
+#[cfg(feature = "std")]
+use crate::structured::{NoteLeg, NoteOption};
+use crate::{Call, OptionType, Options, Put, Vanilla};
+use alloc::format;
+use core::fmt;
+
+// Decimal places kept before trailing zeros are dropped
+const AMOUNT_DECIMALS: usize = 6;
+const YEAR_DECIMALS: usize = 4;
+const PERCENT_DECIMALS: usize = 4;
+
+// A number with at most `decimals` places and no trailing zeros, keeping one place for years
+fn trimmed(
+    f: &mut fmt::Formatter<'_>,
+    value: f64,
+    decimals: usize,
+    keep_point: bool,
+) -> fmt::Result {
+    let text = format!("{:.*}", decimals, value);
+    let text = text.trim_end_matches('0');
+    match text.strip_suffix('.') {
+        Some(whole) if keep_point => write!(f, "{}.0", whole),
+        Some(whole) => f.write_str(whole),
+        None => f.write_str(text),
+    }
+}
+
+/// Amount such as a strike or face value, without trailing zeros
+pub struct Amount(pub f64);
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        trimmed(f, self.0, AMOUNT_DECIMALS, false)
+    }
+}
+
+/// Time in years with a `y` suffix, e.g. 0.25y
+pub struct Years(pub f64);
+
+impl fmt::Display for Years {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        trimmed(f, self.0, YEAR_DECIMALS, true)?;
+        f.write_str("y")
+    }
+}
+
+/// Fraction printed as a percentage, e.g. 0.025 as 2.5%
+pub struct Percent(pub f64);
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        trimmed(f, self.0 * 100.0, PERCENT_DECIMALS, false)?;
+        f.write_str("%")
+    }
+}
+
+impl fmt::Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OptionType::Call => "CALL",
+            OptionType::Put => "PUT",
+        })
+    }
+}
+
+/// Side, strike, expiry, volatility and rate, and the dividend yield when there is one
+impl fmt::Display for Vanilla {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} @ {}, σ={}, r={}",
+            self.kind,
+            Amount(self.strike_price),
+            Years(self.time_to_maturity),
+            Percent(self.volatility),
+            Percent(self.risk_free_rate)
+        )?;
+        match self.dividend_yield {
+            Some(yield_val) => write!(f, ", q={}", Percent(yield_val)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.vanilla().fmt(f)
+    }
+}
+
+impl fmt::Display for Put {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.vanilla().fmt(f)
+    }
+}
+
+impl fmt::Display for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.vanilla().fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for FixedCouponBond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BOND {} {} @ {}, {}/y",
+            Amount(self.face_value),
+            Percent(self.coupon_rate),
+            Years(self.maturity),
+            self.frequency
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for DefaultableBond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, recovery {}", self.bond, Percent(self.recovery))
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ConvertibleBond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CONVERTIBLE {} {} @ {}, conversion {}, S={}, σ={}, spread {}",
+            Amount(self.face_value),
+            Percent(self.coupon_rate),
+            Years(self.maturity),
+            Amount(self.conversion_price),
+            Amount(self.stock_price),
+            Percent(self.volatility),
+            Percent(self.credit_spread)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ExoticOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExoticOptions::ConvertibleBond(bond) => bond.fmt(f),
+        }
+    }
+}
+
+/// Notional, observation count and maturity, coupon, barriers as a fraction of the initial
+/// level, and the knock-in when there is one
+#[cfg(feature = "std")]
+impl fmt::Display for Autocallable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AUTOCALLABLE {} x{} @ {}, coupon {}, autocall {}, coupon barrier {}",
+            Amount(self.notional),
+            self.observations.len(),
+            Years(self.maturity()),
+            Percent(self.coupon),
+            Percent(self.autocall_barrier),
+            Percent(self.coupon_barrier)
+        )?;
+        if self.memory {
+            f.write_str(" with memory")?;
+        }
+        match self.knock_in_barrier {
+            Some(barrier) => write!(f, ", knock-in {}", Percent(barrier)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Participation and the option on performance, strikes and levels as percent of the initial
+/// level, e.g. "150% x CALL 100%, cap 130%"
+#[cfg(feature = "std")]
+impl fmt::Display for NoteLeg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} x ", Percent(self.participation))?;
+        match self.option {
+            NoteOption::Call { strike } => write!(f, "CALL {}", Percent(strike)),
+            NoteOption::CappedCall { strike, cap } => {
+                write!(f, "CALL {}, cap {}", Percent(strike), Percent(cap))
+            }
+            NoteOption::KnockOutCall { strike, barrier } => {
+                write!(
+                    f,
+                    "CALL {}, up-and-out {}",
+                    Percent(strike),
+                    Percent(barrier)
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_contract_descriptions() {
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        assert_eq!(call.to_string(), "CALL 100 @ 1.0y, σ=20%, r=5%");
+        let put = Options::new_put(102.5, 100.0, 0.35, 0.07, 0.25, Some(0.015));
+        assert_eq!(put.to_string(), "PUT 102.5 @ 0.25y, σ=35%, r=7%, q=1.5%");
+        assert_eq!(Percent(-0.005).to_string(), "-0.5%");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bond_descriptions() {
+        let bond = FixedCouponBond::new(100.0, 0.04, 5.0, 2);
+        assert_eq!(bond.to_string(), "BOND 100 4% @ 5.0y, 2/y");
+        assert_eq!(
+            DefaultableBond::new(bond, 0.4).to_string(),
+            "BOND 100 4% @ 5.0y, 2/y, recovery 40%"
+        );
+        let note =
+            Autocallable::new(100.0, vec![0.25, 0.5, 0.75, 1.0], 0.02).with_knock_in(0.6, 1.0);
+        assert_eq!(
+            note.to_string(),
+            "AUTOCALLABLE 100 x4 @ 1.0y, coupon 2%, autocall 100%, coupon barrier 100%, knock-in 60%"
+        );
+    }
+}
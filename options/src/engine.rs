@@ -0,0 +1,460 @@
+// Separates what a contract is priced under (a `Model`) from how the number is produced (an
+// engine). Engines implement `PricingEngine<M>` for every model they support, refined by the
+// closed-form, simulation and finite-difference traits, so new models or methods plug in from
+// outside the crate; `EngineRegistry` picks one by name at run time.
+use crate::error::PricingError;
+use crate::lattice::binomial::BinomialTree;
+use crate::lattice::trinomial::TrinomialTree;
+use crate::lattice::{ExerciseStyle, LatticeEngine};
+use crate::math::float::{exp, ln, sqrt};
+use crate::math::linalg::solve_tridiagonal;
+#[cfg(feature = "mc")]
+use crate::mc::{McConfig, McResult, MonteCarloEngine};
+#[cfg(feature = "mc")]
+use crate::processes::{GeometricBrownianMotion, Heston};
+use crate::{OptionType, Options};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+// Half-width of the finite-difference grid in standard deviations of log spot at expiry
+const PDE_STD_DEVS: f64 = 6.0;
+// Fully implicit steps that damp the payoff kink before Crank-Nicolson takes over (Rannacher)
+const IMPLICIT_STEPS: usize = 2;
+#[cfg(feature = "mc")]
+const DEFAULT_MC_PATHS: usize = 50_000;
+#[cfg(feature = "mc")]
+const DEFAULT_MC_STEPS: usize = 100;
+
+/// Dynamics of the underlying, independent of the numerical method pricing under them.
+pub trait Model: Send + Sync + 'static {
+    /// Name used in engine listings and errors
+    fn name(&self) -> &'static str;
+}
+
+/// Lognormal spot at the contract's own volatility.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BlackScholesModel;
+
+/// Normal forward, the contract's volatility read in price units.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BachelierModel;
+
+/// Heston stochastic variance; the contract supplies spot, rates and expiry and its
+/// volatility is ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HestonModel {
+    /// Initial variance v₀
+    pub variance: f64,
+    pub kappa: f64,
+    /// Long-run variance θ
+    pub theta: f64,
+    /// Volatility of variance ξ
+    pub vol_of_vol: f64,
+    /// Spot-variance correlation ρ
+    pub rho: f64,
+}
+
+impl Model for BlackScholesModel {
+    fn name(&self) -> &'static str {
+        "black_scholes"
+    }
+}
+
+impl Model for BachelierModel {
+    fn name(&self) -> &'static str {
+        "bachelier"
+    }
+}
+
+impl Model for HestonModel {
+    fn name(&self) -> &'static str {
+        "heston"
+    }
+}
+
+/// A numerical method producing the premium of a European contract under model `M`.
+pub trait PricingEngine<M: Model>: Send + Sync {
+    fn price(&self, model: &M, option: &Options) -> Result<f64, PricingError>;
+}
+
+/// Engines with a closed form, which also give an exact delta.
+pub trait AnalyticEngine<M: Model>: PricingEngine<M> {
+    fn delta(&self, model: &M, option: &Options) -> f64;
+}
+
+/// Simulation engines, whose estimate comes with a standard error.
+#[cfg(feature = "mc")]
+pub trait McEngine<M: Model>: PricingEngine<M> {
+    fn simulate(&self, model: &M, option: &Options) -> Result<McResult, PricingError>;
+}
+
+/// Finite-difference engines, which value the contract across a whole spot grid at once.
+pub trait PdeEngine<M: Model>: PricingEngine<M> {
+    /// (spot, value) today at every grid node, in increasing spot
+    fn value_grid(&self, model: &M, option: &Options) -> Result<Vec<(f64, f64)>, PricingError>;
+}
+
+/// Closed-form prices: Black-Scholes and Bachelier formulas.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Analytic;
+
+impl PricingEngine<BlackScholesModel> for Analytic {
+    fn price(&self, _model: &BlackScholesModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(option.bs_pricing())
+    }
+}
+
+impl AnalyticEngine<BlackScholesModel> for Analytic {
+    fn delta(&self, _model: &BlackScholesModel, option: &Options) -> f64 {
+        option.delta()
+    }
+}
+
+impl PricingEngine<BachelierModel> for Analytic {
+    fn price(&self, _model: &BachelierModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(option.bachelier().price())
+    }
+}
+
+impl AnalyticEngine<BachelierModel> for Analytic {
+    fn delta(&self, _model: &BachelierModel, option: &Options) -> f64 {
+        option.bachelier().delta()
+    }
+}
+
+impl PricingEngine<BlackScholesModel> for BinomialTree {
+    fn price(&self, _model: &BlackScholesModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(LatticeEngine::price(self, option, ExerciseStyle::European))
+    }
+}
+
+impl PricingEngine<BlackScholesModel> for TrinomialTree {
+    fn price(&self, _model: &BlackScholesModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(LatticeEngine::price(self, option, ExerciseStyle::European))
+    }
+}
+
+#[cfg(feature = "mc")]
+impl PricingEngine<BlackScholesModel> for MonteCarloEngine {
+    fn price(&self, model: &BlackScholesModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(self.simulate(model, option)?.price)
+    }
+}
+
+#[cfg(feature = "mc")]
+impl McEngine<BlackScholesModel> for MonteCarloEngine {
+    fn simulate(
+        &self,
+        _model: &BlackScholesModel,
+        option: &Options,
+    ) -> Result<McResult, PricingError> {
+        let process = GeometricBrownianMotion {
+            spot: option.spot_price(),
+            drift: option.risk_free_rate() - option.dividend_yield().unwrap_or(0.0),
+            volatility: option.volatility(),
+        };
+        Ok(self.price_process(
+            &process,
+            option.risk_free_rate(),
+            option.time_to_maturity(),
+            terminal_payout(option),
+        ))
+    }
+}
+
+#[cfg(feature = "mc")]
+impl PricingEngine<HestonModel> for MonteCarloEngine {
+    fn price(&self, model: &HestonModel, option: &Options) -> Result<f64, PricingError> {
+        Ok(self.simulate(model, option)?.price)
+    }
+}
+
+#[cfg(feature = "mc")]
+impl McEngine<HestonModel> for MonteCarloEngine {
+    fn simulate(&self, model: &HestonModel, option: &Options) -> Result<McResult, PricingError> {
+        if model.variance < 0.0 || model.theta < 0.0 || model.rho.abs() > 1.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "Heston variance {} / {} or correlation {} out of range",
+                model.variance, model.theta, model.rho
+            )));
+        }
+        let process = Heston {
+            spot: option.spot_price(),
+            drift: option.risk_free_rate() - option.dividend_yield().unwrap_or(0.0),
+            variance: model.variance,
+            kappa: model.kappa,
+            theta: model.theta,
+            vol_of_vol: model.vol_of_vol,
+            rho: model.rho,
+        };
+        Ok(self.price_process(
+            &process,
+            option.risk_free_rate(),
+            option.time_to_maturity(),
+            terminal_payout(option),
+        ))
+    }
+}
+
+#[cfg(feature = "mc")]
+fn terminal_payout(option: &Options) -> impl Fn(&[f64]) -> f64 + Sync + use<> {
+    let contract = *option;
+    move |path: &[f64]| contract.payout(path[path.len() - 1])
+}
+
+/// Crank-Nicolson solver of the Black-Scholes PDE in log spot, started with a few fully
+/// implicit steps so the payoff kink does not ring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrankNicolson {
+    /// Intervals of the log-spot grid
+    pub space_steps: usize,
+    pub time_steps: usize,
+}
+
+impl Default for CrankNicolson {
+    fn default() -> Self {
+        CrankNicolson {
+            space_steps: 400,
+            time_steps: 200,
+        }
+    }
+}
+
+impl PricingEngine<BlackScholesModel> for CrankNicolson {
+    fn price(&self, model: &BlackScholesModel, option: &Options) -> Result<f64, PricingError> {
+        let grid = self.value_grid(model, option)?;
+        let spot = option.spot_price();
+        let i = grid
+            .partition_point(|&(s, _)| s < spot)
+            .clamp(1, grid.len() - 1);
+        let ((s0, v0), (s1, v1)) = (grid[i - 1], grid[i]);
+        Ok(v0 + (v1 - v0) * (spot - s0) / (s1 - s0))
+    }
+}
+
+impl PdeEngine<BlackScholesModel> for CrankNicolson {
+    /// Formula: V_τ = σ²/2 V_xx + (r - q - σ²/2) V_x - rV on x = ln S, boundaries at the
+    /// discounted forward intrinsic
+    fn value_grid(
+        &self,
+        _model: &BlackScholesModel,
+        option: &Options,
+    ) -> Result<Vec<(f64, f64)>, PricingError> {
+        let (sigma, t) = (option.volatility(), option.time_to_maturity());
+        let (r, q) = (
+            option.risk_free_rate(),
+            option.dividend_yield().unwrap_or(0.0),
+        );
+        let (strike, spot) = (option.strike_price(), option.spot_price());
+        if self.space_steps < 2 || self.time_steps == 0 {
+            return Err(PricingError::InvalidInput(format!(
+                "grid of {} space and {} time steps is too coarse",
+                self.space_steps, self.time_steps
+            )));
+        }
+        if sigma <= 0.0 || t <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "PDE needs positive spot, strike, volatility and expiry, got {} {} {} {}",
+                spot, strike, sigma, t
+            )));
+        }
+        let n = self.space_steps;
+        let half_width = PDE_STD_DEVS * sigma * sqrt(t);
+        let dx = 2.0 * half_width / n as f64;
+        let spots: Vec<f64> = (0..=n)
+            .map(|i| exp(ln(spot) - half_width + i as f64 * dx))
+            .collect();
+        let phi = match option.option_type() {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let boundary =
+            |s: f64, tau: f64| (phi * (s * exp(-q * tau) - strike * exp(-r * tau))).max(0.0);
+        let mut values: Vec<f64> = spots.iter().map(|&s| option.payout(s)).collect();
+
+        // L V_i = a_l V_{i-1} + a_d V_i + a_u V_{i+1}
+        let diffusion = 0.5 * sigma * sigma / (dx * dx);
+        let drift = (r - q - 0.5 * sigma * sigma) / (2.0 * dx);
+        let (a_l, a_d, a_u) = (diffusion - drift, -2.0 * diffusion - r, diffusion + drift);
+        let dt = t / self.time_steps as f64;
+        for step in 0..self.time_steps {
+            let theta = if step < IMPLICIT_STEPS { 1.0 } else { 0.5 };
+            let tau = (step + 1) as f64 * dt;
+            let (explicit, implicit) = ((1.0 - theta) * dt, theta * dt);
+            let mut rhs = vec![0.0; n + 1];
+            for i in 1..n {
+                rhs[i] = values[i]
+                    + explicit * (a_l * values[i - 1] + a_d * values[i] + a_u * values[i + 1]);
+            }
+            let (mut lower, mut diag, mut upper) = (
+                vec![-implicit * a_l; n + 1],
+                vec![1.0 - implicit * a_d; n + 1],
+                vec![-implicit * a_u; n + 1],
+            );
+            // Dirichlet rows pin the edges
+            (diag[0], upper[0], rhs[0]) = (1.0, 0.0, boundary(spots[0], tau));
+            (diag[n], lower[n], rhs[n]) = (1.0, 0.0, boundary(spots[n], tau));
+            values = solve_tridiagonal(&lower, &diag, &upper, &rhs);
+        }
+        Ok(spots.into_iter().zip(values).collect())
+    }
+}
+
+type Pricer = Box<dyn Fn(&dyn Any, &Options) -> Result<f64, PricingError> + Send + Sync>;
+
+/// Engines filed by model type and name, so callers choose the method at run time and add
+/// their own models or engines without touching the crate.
+pub struct EngineRegistry {
+    engines: HashMap<(TypeId, String), Pricer>,
+}
+
+impl EngineRegistry {
+    /// A registry with no engines
+    pub fn new() -> Self {
+        EngineRegistry {
+            engines: HashMap::new(),
+        }
+    }
+    /// Files `engine` under `name` for model `M`, replacing any engine already there
+    pub fn register<M: Model, E: PricingEngine<M> + 'static>(&mut self, name: &str, engine: E) {
+        let pricer = move |model: &dyn Any, option: &Options| {
+            // keyed by the model's TypeId, so the downcast cannot fail
+            let model = model
+                .downcast_ref::<M>()
+                .expect("engine filed under another model");
+            engine.price(model, option)
+        };
+        self.engines
+            .insert((TypeId::of::<M>(), name.to_string()), Box::new(pricer));
+    }
+    pub fn with_engine<M: Model, E: PricingEngine<M> + 'static>(
+        mut self,
+        name: &str,
+        engine: E,
+    ) -> Self {
+        self.register::<M, E>(name, engine);
+        self
+    }
+    /// Names of the engines available for model `M`, sorted
+    pub fn engines<M: Model>(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .engines
+            .keys()
+            .filter(|(model, _)| *model == TypeId::of::<M>())
+            .map(|(_, name)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+    /// Prices `option` under `model` with the engine filed as `engine`
+    pub fn price<M: Model>(
+        &self,
+        engine: &str,
+        model: &M,
+        option: &Options,
+    ) -> Result<f64, PricingError> {
+        let pricer = self
+            .engines
+            .get(&(TypeId::of::<M>(), engine.to_string()))
+            .ok_or_else(|| {
+                PricingError::InvalidInput(format!(
+                    "no engine {} registered for the {} model",
+                    engine,
+                    model.name()
+                ))
+            })?;
+        pricer(model, option)
+    }
+}
+
+impl Default for EngineRegistry {
+    /// The crate's own engines: analytic, binomial, trinomial and pde for Black-Scholes,
+    /// analytic for Bachelier, and mc for Black-Scholes and Heston when simulation is built
+    fn default() -> Self {
+        let registry = EngineRegistry::new()
+            .with_engine::<BlackScholesModel, _>("analytic", Analytic)
+            .with_engine::<BlackScholesModel, _>("binomial", BinomialTree::default())
+            .with_engine::<BlackScholesModel, _>("trinomial", TrinomialTree::default())
+            .with_engine::<BlackScholesModel, _>("pde", CrankNicolson::default())
+            .with_engine::<BachelierModel, _>("analytic", Analytic);
+        #[cfg(feature = "mc")]
+        let registry = {
+            let mc = MonteCarloEngine::new(McConfig::new(DEFAULT_MC_PATHS, DEFAULT_MC_STEPS, 0));
+            registry
+                .with_engine::<BlackScholesModel, _>("mc", mc)
+                .with_engine::<HestonModel, _>("mc", mc)
+        };
+        registry
+    }
+}
+
+impl Options {
+    /// Premium under `model` by `engine`, e.g. `option.price_with(&heston, &mc)`
+    pub fn price_with<M: Model, E: PricingEngine<M>>(
+        &self,
+        model: &M,
+        engine: &E,
+    ) -> Result<f64, PricingError> {
+        engine.price(model, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engines_agree_on_black_scholes() {
+        let put = Options::new_put(105.0, 100.0, 0.25, 0.04, 1.0, Some(0.01));
+        let exact = put.price_with(&BlackScholesModel, &Analytic).unwrap();
+        let pde = put
+            .price_with(&BlackScholesModel, &CrankNicolson::default())
+            .unwrap();
+        assert!((pde - exact).abs() < 5e-3, "{} vs {}", pde, exact);
+
+        let registry = EngineRegistry::default();
+        for engine in ["binomial", "trinomial", "pde"] {
+            let price = registry.price(engine, &BlackScholesModel, &put).unwrap();
+            assert!((price - exact).abs() < 2e-2, "{}: {}", engine, price);
+        }
+        assert!(registry.price("pde", &BachelierModel, &put).is_err());
+        assert!(registry.engines::<BachelierModel>() == ["analytic"]);
+
+        // variance pinned at σ² with no vol of vol is Black-Scholes
+        #[cfg(feature = "mc")]
+        {
+            let heston = HestonModel {
+                variance: 0.0625,
+                kappa: 1.0,
+                theta: 0.0625,
+                vol_of_vol: 0.0,
+                rho: -0.5,
+            };
+            let mc = MonteCarloEngine::new(McConfig::new(20_000, 20, 11));
+            let result = mc.simulate(&heston, &put).unwrap();
+            assert!((result.price - exact).abs() < 3.0 * result.std_error + 1e-3);
+            assert_eq!(put.price_with(&heston, &mc).unwrap(), result.price);
+        }
+    }
+
+    #[test]
+    fn test_custom_model_registers_from_outside() {
+        // a toy model whose engine quotes intrinsic value only
+        struct Intrinsic;
+        impl Model for Intrinsic {
+            fn name(&self) -> &'static str {
+                "intrinsic"
+            }
+        }
+        struct Payout;
+        impl PricingEngine<Intrinsic> for Payout {
+            fn price(&self, _: &Intrinsic, option: &Options) -> Result<f64, PricingError> {
+                Ok(option.payout(option.spot_price()))
+            }
+        }
+        let registry = EngineRegistry::new().with_engine::<Intrinsic, _>("payout", Payout);
+        let call = Options::new_call(90.0, 100.0, 0.2, 0.03, 1.0, None);
+        assert_eq!(registry.price("payout", &Intrinsic, &call).unwrap(), 10.0);
+        assert!(registry.price("payout", &BlackScholesModel, &call).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Errors surfaced by pricing, calibration and risk routines.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingError {
+    /// Inputs are inconsistent or outside the domain of the routine
+    InvalidInput(String),
+    /// An iterative solver stopped before reaching its tolerance
+    NoConvergence { iterations: usize },
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PricingError::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+            PricingError::NoConvergence { iterations } => {
+                write!(f, "no convergence after {} iterations", iterations)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PricingError {}
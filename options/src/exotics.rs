@@ -1,10 +1,27 @@
 use crate::Call;
+use crate::bonds::FixedCouponBond;
+use crate::credit::{DefaultableBond, HazardCurve};
+use crate::curves::RateCurve;
+use crate::error::PricingError;
+use crate::math::float::exp;
+#[cfg(feature = "mc")]
+use crate::mc::{McResult, MonteCarloEngine};
+#[cfg(feature = "mc")]
+use crate::multi_asset::MultiAssetSimulator;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 pub enum ExoticOptions {
     ConvertibleBond(ConvertibleBond),
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Highest credit spread searched when backing spreads out of convertible prices
+pub const MAX_CREDIT_SPREAD: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ConvertibleBond {
     // Bond parameters
     pub face_value: f64,
@@ -23,19 +40,18 @@ pub struct ConvertibleBond {
 }
 
 impl ConvertibleBond {
+    /// The straight bond left once the conversion right is stripped off
+    pub fn bond(&self) -> FixedCouponBond {
+        FixedCouponBond::new(
+            self.face_value,
+            self.coupon_rate,
+            self.maturity,
+            self.payment_frequency,
+        )
+    }
+    // Bond floor discounted at the risk-free rate plus the credit spread
     fn npv(&self) -> f64 {
-        // NPV calculation logic
-        let periods = (self.maturity * self.payment_frequency as f64) as u32;
-        let coupon_payment = self.face_value * self.coupon_rate / self.payment_frequency as f64;
-        let mut npv = 0.0;
-        let mut discounter = 1.0;
-        for _ in 1..=periods {
-            discounter *=
-                1.0 + (self.risk_free_rate + self.credit_spread) / self.payment_frequency as f64;
-            npv += coupon_payment / discounter;
-        }
-        npv += self.face_value / discounter;
-        npv
+        self.bond().price(self.risk_free_rate + self.credit_spread)
     }
     fn conversion_option_price(&self) -> f64 {
         // Use Black-Scholes to price the conversion option
@@ -49,10 +65,225 @@ impl ConvertibleBond {
         };
         underlying_call.bs_pricing()
     }
+    /// Price with the bond floor discounted at the convertible's risk-free rate and the survival
+    /// probabilities of `hazard`, instead of the flat credit spread
+    pub fn credit_pricing(&self, hazard: &HazardCurve, recovery: f64) -> f64 {
+        let rates = RateCurve::flat(self.risk_free_rate);
+        let floor = DefaultableBond::new(self.bond(), recovery).price(&rates, hazard);
+        floor + self.face_value / self.conversion_price * self.conversion_option_price()
+    }
     /// Calculate the total price of the convertible bond using Black-Scholes for the conversion option and NPV for the bond component
     pub fn bs_pricing(&self) -> f64 {
         self.npv() + self.face_value / self.conversion_price * self.conversion_option_price()
     }
+    /// Credit spread at which the model price matches `market_price`.
+    ///
+    /// The equity conversion option is valued first, so the spread is backed out of the remaining
+    /// bond floor: market_price - (F / conversion_price) C = NPV(spread)
+    pub fn implied_credit_spread(&self, market_price: f64) -> Result<f64, PricingError> {
+        self.implied_credit_spread_with(market_price, &NumericsConfig::default())
+    }
+    /// Implied credit spread solved to the tolerance and iteration cap of `numerics`
+    pub fn implied_credit_spread_with(
+        &self,
+        market_price: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<f64, PricingError> {
+        let bond_floor =
+            market_price - self.face_value / self.conversion_price * self.conversion_option_price();
+        let floor_error = |spread: f64| {
+            ConvertibleBond {
+                credit_spread: spread,
+                ..*self
+            }
+            .npv()
+                - bond_floor
+        };
+        if floor_error(0.0) < 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "price {} implies a negative credit spread",
+                market_price
+            )));
+        }
+        if floor_error(MAX_CREDIT_SPREAD) > 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "price {} implies a credit spread above {}",
+                market_price, MAX_CREDIT_SPREAD
+            )));
+        }
+        brent(
+            floor_error,
+            0.0,
+            MAX_CREDIT_SPREAD,
+            numerics.tolerance,
+            numerics.max_iterations,
+        )
+    }
+    /// Constant hazard rate implied by `market_price` for a given recovery rate
+    ///
+    /// Formula: λ = s / (1 - R), the credit triangle
+    pub fn implied_hazard_rate(
+        &self,
+        market_price: f64,
+        recovery: f64,
+    ) -> Result<f64, PricingError> {
+        if !(0.0..1.0).contains(&recovery) {
+            return Err(PricingError::InvalidInput(format!(
+                "recovery rate {} must lie in [0, 1)",
+                recovery
+            )));
+        }
+        Ok(self.implied_credit_spread(market_price)? / (1.0 - recovery))
+    }
+    /// Risk-neutral probability that the issuer defaults within `horizon` years
+    ///
+    /// Formula: 1 - exp(-λ t)
+    pub fn implied_default_probability(
+        &self,
+        market_price: f64,
+        recovery: f64,
+        horizon: f64,
+    ) -> Result<f64, PricingError> {
+        let hazard = self.implied_hazard_rate(market_price, recovery)?;
+        Ok(1.0 - exp(-hazard * horizon))
+    }
+}
+
+/// Autocallable (Phoenix) note on the worst performer of its underlyings, performance being
+/// each level over its value at the start.
+///
+/// On every observation the note pays its coupon if the worst performance is at or above the
+/// coupon barrier, catching up missed coupons when `memory` is set, and redeems at par when it
+/// is at or above the autocall barrier. Reaching maturity it redeems at par, less a short put
+/// struck at `put_strike` if the worst performance ends below the knock-in barrier
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Autocallable {
+    pub notional: f64,
+    /// Observation times in years, increasing, the last one at maturity
+    pub observations: Vec<f64>,
+    /// Coupon per observation as a fraction of notional
+    pub coupon: f64,
+    pub autocall_barrier: f64,
+    pub coupon_barrier: f64,
+    pub memory: bool,
+    /// Final worst performance below which the put is live; None for a capital protected note
+    pub knock_in_barrier: Option<f64>,
+    pub put_strike: f64,
+}
+
+impl Autocallable {
+    /// Note calling and paying its coupon at or above the initial level, without memory or
+    /// knock-in
+    pub fn new(notional: f64, observations: Vec<f64>, coupon: f64) -> Self {
+        Autocallable {
+            notional,
+            observations,
+            coupon,
+            autocall_barrier: 1.0,
+            coupon_barrier: 1.0,
+            memory: false,
+            knock_in_barrier: None,
+            put_strike: 1.0,
+        }
+    }
+    pub fn with_autocall_barrier(self, autocall_barrier: f64) -> Self {
+        Autocallable {
+            autocall_barrier,
+            ..self
+        }
+    }
+    pub fn with_coupon_barrier(self, coupon_barrier: f64, memory: bool) -> Self {
+        Autocallable {
+            coupon_barrier,
+            memory,
+            ..self
+        }
+    }
+    pub fn with_knock_in(self, barrier: f64, put_strike: f64) -> Self {
+        Autocallable {
+            knock_in_barrier: Some(barrier),
+            put_strike,
+            ..self
+        }
+    }
+    pub fn maturity(&self) -> f64 {
+        self.observations.last().copied().unwrap_or(0.0)
+    }
+    /// (time, amount) paid given the worst performance on each observation, up to the call
+    pub fn cash_flows(&self, worst: &[f64]) -> Vec<(f64, f64)> {
+        let mut flows = Vec::new();
+        let mut missed = 0.0;
+        let last = self.observations.len().saturating_sub(1);
+        for (k, (&t, &performance)) in self.observations.iter().zip(worst).enumerate() {
+            let mut amount = 0.0;
+            if performance >= self.coupon_barrier {
+                amount += self.notional * self.coupon * (1.0 + missed);
+                missed = 0.0;
+            } else if self.memory {
+                missed += 1.0;
+            }
+            let called = performance >= self.autocall_barrier && k < last;
+            if called || k == last {
+                let knocked_in = self.knock_in_barrier.is_some_and(|b| performance < b);
+                let put = if knocked_in {
+                    (self.put_strike - performance).max(0.0) / self.put_strike
+                } else {
+                    0.0
+                };
+                amount += self.notional * (1.0 - put);
+            }
+            if amount != 0.0 {
+                flows.push((t, amount));
+            }
+            if called {
+                break;
+            }
+        }
+        flows
+    }
+    /// Present value on correlated paths of the underlyings, discounted at a flat `rate`.
+    ///
+    /// Observations are read at the nearest simulation step, so the engine's step count should
+    /// put a step on each of them
+    #[cfg(feature = "mc")]
+    pub fn price(
+        &self,
+        simulator: &MultiAssetSimulator,
+        engine: &MonteCarloEngine,
+        rate: f64,
+    ) -> Result<McResult, PricingError> {
+        let maturity = self.maturity();
+        let increasing = self.observations.windows(2).all(|w| w[0] < w[1]);
+        if self.observations.first().is_none_or(|&t| t <= 0.0) || !increasing {
+            return Err(PricingError::InvalidInput(
+                "autocall observations must be positive and increasing".to_string(),
+            ));
+        }
+        let steps = engine.config.steps.max(1);
+        let indices: Vec<usize> = self
+            .observations
+            .iter()
+            .map(|t| ((t / maturity * steps as f64).round() as usize).min(steps))
+            .collect();
+        let payoff = |paths: &[Vec<f64>]| {
+            let worst: Vec<f64> = indices
+                .iter()
+                .map(|&k| {
+                    paths
+                        .iter()
+                        .map(|path| path[k] / path[0])
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect();
+            // compounded to maturity, where the simulator discounts every path
+            self.cash_flows(&worst)
+                .iter()
+                .map(|&(t, amount)| amount * exp(rate * (maturity - t)))
+                .sum()
+        };
+        Ok(simulator.price(engine, rate, maturity, payoff))
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +309,107 @@ mod tests {
         println!("Convertible Bond Price: {:.4}", price);
         assert!((price - 1_318.0).abs() < 1e-1); // expected value
     }
+
+    #[test]
+    fn test_implied_default_probability() {
+        let cb = ConvertibleBond {
+            face_value: 1000.0,
+            coupon_rate: 0.04,
+            maturity: 5.0,
+            payment_frequency: 2,
+            credit_spread: 0.03,
+            risk_free_rate: 0.03,
+            conversion_price: 50.0,
+            stock_price: 45.0,
+            volatility: 0.3,
+            time_to_maturity: 5.0,
+            dividend_yield: Some(0.01),
+        };
+        let quoted = ConvertibleBond {
+            credit_spread: 0.0,
+            ..cb
+        };
+        let price = cb.bs_pricing();
+        assert!((quoted.implied_credit_spread(price).unwrap() - 0.03).abs() < 1e-9);
+        let hazard = quoted.implied_hazard_rate(price, 0.4).unwrap();
+        assert!((hazard - 0.05).abs() < 1e-8);
+        let pd = quoted.implied_default_probability(price, 0.4, 5.0).unwrap();
+        assert!((pd - (1.0 - (-0.25f64).exp())).abs() < 1e-8);
+        // a price above the risk-free bond plus option cannot be explained by credit risk
+        assert!(quoted.implied_credit_spread(price + 500.0).is_err());
+
+        // with no default risk the hazard-curve price only differs by continuous compounding
+        let riskless = quoted.credit_pricing(&HazardCurve::flat(0.0), 0.4);
+        assert!((riskless - quoted.bs_pricing()).abs() < 0.01 * riskless);
+        assert!(quoted.credit_pricing(&HazardCurve::flat(0.05), 0.4) < riskless);
+    }
+
+    #[test]
+    fn test_autocallable_cash_flows() {
+        let note = Autocallable::new(100.0, vec![0.5, 1.0, 1.5, 2.0], 0.04)
+            .with_coupon_barrier(0.7, true)
+            .with_knock_in(0.6, 1.0);
+        // missed coupons are caught up, then the note calls
+        assert_eq!(
+            note.cash_flows(&[0.65, 0.8, 1.05, 1.2]),
+            vec![(1.0, 8.0), (1.5, 104.0)]
+        );
+        // knocked in at maturity: the holder takes the loss below the strike
+        assert_eq!(
+            note.cash_flows(&[0.9, 0.5, 0.5, 0.5]),
+            vec![(0.5, 4.0), (2.0, 50.0)]
+        );
+        // below the knock-in barrier on earlier dates only does not matter
+        assert_eq!(
+            note.cash_flows(&[0.5, 0.5, 0.5, 0.95]).last(),
+            Some(&(2.0, 116.0))
+        );
+    }
+
+    #[cfg(feature = "mc")]
+    #[test]
+    fn test_worst_of_autocallable_pricing() {
+        use crate::mc::McConfig;
+        use crate::multi_asset::{Decomposition, MultiAssetSimulator};
+        use crate::processes::{GeometricBrownianMotion, StochasticProcess};
+
+        let gbm = |volatility| -> Box<dyn StochasticProcess> {
+            Box::new(GeometricBrownianMotion {
+                spot: 100.0,
+                drift: 0.03,
+                volatility,
+            })
+        };
+        let engine = MonteCarloEngine::new(McConfig::new(8_000, 8, 21));
+        let single =
+            MultiAssetSimulator::new(vec![gbm(0.25)], &[vec![1.0]], Decomposition::Cholesky)
+                .unwrap();
+        let correlation = vec![vec![1.0, 0.5], vec![0.5, 1.0]];
+        let pair = MultiAssetSimulator::new(
+            vec![gbm(0.25), gbm(0.25)],
+            &correlation,
+            Decomposition::Cholesky,
+        )
+        .unwrap();
+        let quarterly: Vec<f64> = (1..=8).map(|k| k as f64 * 0.25).collect();
+
+        // never calling, no coupon and no knock-in is a zero-coupon bond
+        let zero = Autocallable::new(100.0, quarterly.clone(), 0.0).with_autocall_barrier(1e9);
+        let bond = zero.price(&single, &engine, 0.03).unwrap();
+        assert!((bond.price - 100.0 * (-0.06f64).exp()).abs() < 1e-9);
+
+        let phoenix = Autocallable::new(100.0, quarterly, 0.02)
+            .with_coupon_barrier(0.7, true)
+            .with_knock_in(0.6, 1.0);
+        let one = phoenix.price(&single, &engine, 0.03).unwrap();
+        let worst_of = phoenix.price(&pair, &engine, 0.03).unwrap();
+        // a second underlying only adds ways to miss coupons and knock in
+        assert!(worst_of.price < one.price - 3.0 * (one.std_error + worst_of.std_error));
+        assert!(one.price < 100.0 + 16.0);
+        assert!(
+            Autocallable::new(100.0, vec![1.0, 0.5], 0.01)
+                .price(&single, &engine, 0.03)
+                .is_err()
+        );
+    }
 }
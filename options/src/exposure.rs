@@ -0,0 +1,172 @@
+// Notional and leverage reporting against account equity, for risk-limit and suitability checks.
+use crate::error::PricingError;
+use crate::math::sum::compensated_sum;
+use crate::portfolio::Portfolio;
+
+/// Notionals of the positions held on one underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingExposure {
+    pub underlying: String,
+    /// Σ |quantity| * spot over options and stock
+    pub gross_notional: f64,
+    /// Σ quantity * spot, longs minus shorts
+    pub net_notional: f64,
+    /// Σ quantity * delta * spot, with stock at delta one
+    pub delta_notional: f64,
+}
+
+/// Exposure of a portfolio relative to the equity of the account holding it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExposureReport {
+    pub account_equity: f64,
+    pub gross_notional: f64,
+    pub net_notional: f64,
+    /// Net delta-adjusted notional across underlyings
+    pub delta_notional: f64,
+    /// Σ |delta notional| per underlying, so hedges only offset within an underlying
+    pub gross_delta_notional: f64,
+    /// Premium paid for long options, the most those legs can lose
+    pub premium_at_risk: f64,
+    /// gross delta notional / equity
+    pub leverage: f64,
+    /// net delta notional / equity, negative for net short books
+    pub net_leverage: f64,
+    pub per_underlying: Vec<UnderlyingExposure>,
+}
+
+/// Thresholds checked against an exposure report; unset limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExposureLimits {
+    pub max_leverage: Option<f64>,
+    /// Largest premium at risk as a fraction of account equity
+    pub max_premium_at_risk: Option<f64>,
+}
+
+/// A limit exceeded by a portfolio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitBreach {
+    Leverage { actual: f64, limit: f64 },
+    PremiumAtRisk { actual: f64, limit: f64 },
+}
+
+impl ExposureReport {
+    /// Every limit the report exceeds, empty when the portfolio is within limits
+    pub fn breaches(&self, limits: &ExposureLimits) -> Vec<LimitBreach> {
+        let mut breaches = Vec::new();
+        if let Some(limit) = limits.max_leverage
+            && self.leverage > limit
+        {
+            breaches.push(LimitBreach::Leverage {
+                actual: self.leverage,
+                limit,
+            });
+        }
+        let premium_fraction = self.premium_at_risk / self.account_equity;
+        if let Some(limit) = limits.max_premium_at_risk
+            && premium_fraction > limit
+        {
+            breaches.push(LimitBreach::PremiumAtRisk {
+                actual: premium_fraction,
+                limit,
+            });
+        }
+        breaches
+    }
+}
+
+impl Portfolio {
+    /// Gross and net notional, delta-adjusted notional and leverage against `account_equity`
+    pub fn exposure(&self, account_equity: f64) -> Result<ExposureReport, PricingError> {
+        if !(account_equity.is_finite() && account_equity > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "account equity {} must be positive",
+                account_equity
+            )));
+        }
+        let per_underlying: Vec<UnderlyingExposure> = self
+            .underlyings()
+            .into_iter()
+            .map(|underlying| {
+                // (quantity, spot, delta) of every holding on the underlying
+                let holdings: Vec<(f64, f64, f64)> = self
+                    .positions()
+                    .iter()
+                    .filter(|position| position.underlying == underlying)
+                    .map(|p| (p.units(), p.option.spot_price(), p.option.delta()))
+                    .chain(
+                        self.stocks()
+                            .iter()
+                            .filter(|stock| stock.underlying == underlying)
+                            .map(|stock| (stock.quantity, stock.spot, 1.0)),
+                    )
+                    .collect();
+                UnderlyingExposure {
+                    underlying: underlying.to_string(),
+                    gross_notional: compensated_sum(holdings.iter().map(|(q, s, _)| q.abs() * s)),
+                    net_notional: compensated_sum(holdings.iter().map(|(q, s, _)| q * s)),
+                    delta_notional: compensated_sum(holdings.iter().map(|(q, s, d)| q * d * s)),
+                }
+            })
+            .collect();
+        let total = |field: fn(&UnderlyingExposure) -> f64| {
+            compensated_sum(per_underlying.iter().map(field))
+        };
+        let delta_notional = total(|u| u.delta_notional);
+        let gross_delta_notional = total(|u| u.delta_notional.abs());
+        let premium_at_risk = compensated_sum(
+            self.positions()
+                .iter()
+                .filter(|position| position.quantity > 0.0)
+                .map(|position| position.value()),
+        );
+        Ok(ExposureReport {
+            account_equity,
+            gross_notional: total(|u| u.gross_notional),
+            net_notional: total(|u| u.net_notional),
+            delta_notional,
+            gross_delta_notional,
+            premium_at_risk,
+            leverage: gross_delta_notional / account_equity,
+            net_leverage: delta_notional / account_equity,
+            per_underlying,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+    use crate::portfolio::{Position, StockPosition};
+
+    #[test]
+    fn test_exposure_and_limits() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let put = Options::new_put(50.0, 50.0, 0.3, 0.05, 1.0, None);
+        let mut book = Portfolio::covered_call("AAA", 100.0, call);
+        book.add(Position::new("BBB", put, 200.0));
+        book.add_stock(StockPosition::new("BBB", 50.0, -100.0));
+
+        let report = book.exposure(20_000.0).unwrap();
+        assert_eq!(
+            report.gross_notional,
+            10_000.0 + 10_000.0 + 10_000.0 + 5_000.0
+        );
+        assert_eq!(report.net_notional, 0.0 + 10_000.0 - 5_000.0);
+        let aaa = 100.0 * 100.0 * (1.0 - call.delta());
+        let bbb = 200.0 * 50.0 * put.delta() - 5_000.0;
+        assert!((report.delta_notional - (aaa + bbb)).abs() < 1e-9);
+        assert!((report.gross_delta_notional - (aaa.abs() + bbb.abs())).abs() < 1e-9);
+        assert!((report.premium_at_risk - 200.0 * put.bs_pricing()).abs() < 1e-9);
+
+        let limits = ExposureLimits {
+            max_leverage: Some(report.leverage / 2.0),
+            max_premium_at_risk: Some(1.0),
+        };
+        assert!(matches!(
+            report.breaches(&limits)[..],
+            [LimitBreach::Leverage { .. }]
+        ));
+        assert!(book.exposure(0.0).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// Pure-jump Lévy models, whose fat tails and skew fit short-dated equity smiles that diffusions
+// cannot. Variance gamma is Brownian motion with drift run on a gamma clock; CGMY generalizes its
+// Lévy density with a fine-structure exponent Y between finite (Y < 0) and infinite variation.
+use crate::calibration_report::Parameter;
+use crate::error::PricingError;
+use crate::fourier::{Calibratable, CharacteristicFunction};
+use crate::math::complex::{Complex, I};
+use crate::math::float::{gamma, ln, powf};
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Variance gamma of Madan, Carr and Chang: X = θG + σW(G) with G a gamma process of unit mean
+/// rate and variance rate ν, compensated so the forward is unchanged.
+///
+/// Formula: φ(u) = e^(iuωt) (1 - iuθν + ½σ²νu²)^(-t/ν), ω = ln(1 - θν - ½σ²ν)/ν
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarianceGammaModel {
+    /// Volatility σ of the Brownian motion
+    pub volatility: f64,
+    /// Variance rate ν of the gamma clock, the kurtosis of the returns
+    pub nu: f64,
+    /// Drift θ of the Brownian motion, negative for a left-skewed smile
+    pub theta: f64,
+}
+
+impl VarianceGammaModel {
+    /// Drift ω making exp(X) a martingale
+    pub fn compensator(&self) -> f64 {
+        ln(1.0 - self.theta * self.nu - 0.5 * self.volatility * self.volatility * self.nu) / self.nu
+    }
+}
+
+impl CharacteristicFunction for VarianceGammaModel {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let clock = 1.0 - I * u * (self.theta * self.nu)
+            + u * u * (0.5 * self.volatility * self.volatility * self.nu);
+        (I * u * (self.compensator() * t) - clock.ln() * (t / self.nu)).exp()
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        if !(self.volatility > 0.0 && self.nu > 0.0 && self.theta.is_finite()) {
+            return Err(PricingError::InvalidInput(format!(
+                "variance gamma volatility {} and variance rate {} must be positive",
+                self.volatility, self.nu
+            )));
+        }
+        if 1.0 - self.theta * self.nu - 0.5 * self.volatility * self.volatility * self.nu <= 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "variance gamma {:?} has no finite forward",
+                self
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Calibratable for VarianceGammaModel {
+    const PARAMETERS: &'static [Parameter] = &[
+        Parameter::positive("volatility"),
+        Parameter::positive("nu"),
+        Parameter::unbounded("theta"),
+    ];
+    fn values(&self) -> Vec<f64> {
+        vec![self.volatility, self.nu, self.theta]
+    }
+    fn from_values(values: &[f64]) -> Self {
+        VarianceGammaModel {
+            volatility: values[0],
+            nu: values[1],
+            theta: values[2],
+        }
+    }
+}
+
+/// CGMY of Carr, Geman, Madan and Yor, with Lévy density C e^(-G|x|)/|x|^(1+Y) for negative
+/// jumps and C e^(-Mx)/x^(1+Y) for positive ones, compensated so the forward is unchanged.
+///
+/// Formula: φ(u) = exp(t(CΓ(-Y)((M - iu)^Y - M^Y + (G + iu)^Y - G^Y) + iuω)),
+/// ω = -CΓ(-Y)((M - 1)^Y - M^Y + (G + 1)^Y - G^Y)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CgmyModel {
+    /// Overall activity C
+    pub c: f64,
+    /// Exponential decay G of the negative jumps
+    pub g: f64,
+    /// Exponential decay M of the positive jumps, above one for a finite forward
+    pub m: f64,
+    /// Fine-structure exponent Y in (0, 2), one excluded
+    pub y: f64,
+}
+
+impl CgmyModel {
+    // CΓ(-Y)((M - iu)^Y - M^Y + (G + iu)^Y - G^Y), the Lévy exponent without compensation
+    fn exponent(&self, iu: Complex) -> Complex {
+        let power = |z: Complex| (z.ln() * self.y).exp();
+        let scale = self.c * gamma(-self.y);
+        ((power(self.m - iu) + power(self.g + iu)) - powf(self.m, self.y) - powf(self.g, self.y))
+            * scale
+    }
+}
+
+impl CharacteristicFunction for CgmyModel {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let iu = I * u;
+        let compensator = -self.exponent(Complex::real(1.0)).re;
+        ((self.exponent(iu) + iu * compensator) * t).exp()
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        if !(self.c > 0.0 && self.g > 0.0 && self.m > 1.0 && self.y > 0.0 && self.y < 2.0)
+            || self.y == 1.0
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "CGMY needs C, G > 0, M > 1 and Y in (0, 2) other than 1, got {:?}",
+                self
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Calibratable for CgmyModel {
+    const PARAMETERS: &'static [Parameter] = &[
+        Parameter::positive("c"),
+        Parameter::positive("g"),
+        Parameter::new("m", 1.0, f64::INFINITY),
+        Parameter::new("y", 0.0, 2.0),
+    ];
+    fn values(&self) -> Vec<f64> {
+        vec![self.c, self.g, self.m, self.y]
+    }
+    fn from_values(values: &[f64]) -> Self {
+        CgmyModel {
+            c: values[0],
+            g: values[1],
+            m: values[2],
+            y: values[3],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+    use crate::fourier::{calibrate, cos_price};
+
+    #[test]
+    fn test_reference_prices() {
+        // Fang and Oosterlee's test cases and reference values
+        let vg = VarianceGammaModel {
+            volatility: 0.12,
+            nu: 0.2,
+            theta: -0.14,
+        };
+        for (maturity, reference) in [(0.1, 10.993703187), (1.0, 19.099354724)] {
+            let call = Options::new_call(90.0, 100.0, 0.2, 0.1, maturity, None);
+            assert!((cos_price(&vg, &call).unwrap() - reference).abs() < 1e-7);
+        }
+        for (y, reference) in [(0.5, 19.812948843), (1.5, 49.790905469)] {
+            let cgmy = CgmyModel {
+                c: 1.0,
+                g: 5.0,
+                m: 5.0,
+                y,
+            };
+            let call = Options::new_call(100.0, 100.0, 0.2, 0.1, 1.0, None);
+            assert!((cos_price(&cgmy, &call).unwrap() - reference).abs() < 1e-7);
+        }
+        let explosive = VarianceGammaModel { nu: 20.0, ..vg };
+        let call = Options::new_call(90.0, 100.0, 0.2, 0.1, 1.0, None);
+        assert!(cos_price(&explosive, &call).is_err());
+        assert!(cos_price(&CgmyModel::from_values(&[1.0, 5.0, 5.0, 1.0]), &call).is_err());
+    }
+
+    #[test]
+    fn test_calibration_recovers_parameters() {
+        let truth = VarianceGammaModel {
+            volatility: 0.15,
+            nu: 0.3,
+            theta: -0.2,
+        };
+        let quotes: Vec<(Options, f64)> = [80.0, 90.0, 100.0, 110.0, 120.0]
+            .into_iter()
+            .map(|strike| {
+                let option = Options::new_put(strike, 100.0, 0.2, 0.02, 0.25, None);
+                (option, cos_price(&truth, &option).unwrap())
+            })
+            .collect();
+        let start = VarianceGammaModel {
+            volatility: 0.25,
+            nu: 0.1,
+            theta: 0.0,
+        };
+        let fit = calibrate(&start, &quotes).unwrap();
+        assert!(fit.report.rmse < 1e-5);
+        assert_eq!(fit.report.residuals.len(), 5);
+        // five exact quotes pin down three parameters, none against a bound
+        let nu = &fit.report.parameters[1];
+        assert!(nu.name == "nu" && nu.std_error.unwrap() < 1e-2);
+        assert!(fit.report.warnings.is_empty());
+        assert!((fit.model.volatility - truth.volatility).abs() < 1e-3);
+        assert!((fit.model.nu - truth.nu).abs() < 1e-2);
+        assert!((fit.model.theta - truth.theta).abs() < 1e-2);
+        assert!(calibrate(&start, &[]).is_err());
+    }
+}
@@ -0,0 +1,507 @@
+// European prices from characteristic functions with the COS method (Fang and Oosterlee, 2008):
+// the density of the log-price is expanded in a cosine series on a range set by its cumulants,
+// and the payoff's cosine coefficients are known in closed form. Any model with a characteristic
+// function prices through `cos_price`, so a new model only needs `CharacteristicFunction`.
+pub mod levy;
+
+use crate::Options;
+use crate::calibration_report::{CalibrationReport, Parameter};
+use crate::error::PricingError;
+use crate::math::complex::{Complex, I};
+use crate::math::float::{cos, exp, ln, sin, sqrt};
+use crate::numerics::NumericsConfig;
+use crate::solver::nelder_mead;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+/// Half-width of the truncation range in units of √(c₂ + √|c₄|), wide enough for the heavier
+/// tails of stochastic-volatility and jump models
+pub const TRUNCATION_WIDTH: f64 = 12.0;
+/// Terms of the cosine series added between two convergence checks
+pub const COS_BLOCK: usize = 64;
+/// Cap on the terms of the cosine series
+pub const MAX_COS_TERMS: usize = 1 << 16;
+
+// Step of the finite differences estimating cumulants from the characteristic function
+const CUMULANT_STEP: f64 = 0.05;
+// Initial simplex edge, in unconstrained coordinates, and iteration cap of model calibration
+const CALIBRATION_STEP: f64 = 0.2;
+const CALIBRATION_ITERATIONS: usize = 2_000;
+
+/// Model of the log-price through its characteristic function.
+pub trait CharacteristicFunction {
+    /// E[exp(iuX)] of X = ln(S_t / F_t), the log of the spot over its forward at time `t`, so
+    /// that E[exp(X)] = 1 at u = -i
+    fn characteristic(&self, u: Complex, t: f64) -> Complex;
+    /// First, second and fourth cumulants of X, which set the truncation range. The default
+    /// differentiates the cumulant generating function ln E[exp(sX)] numerically at zero
+    fn cumulants(&self, t: f64) -> [f64; 3] {
+        let h = CUMULANT_STEP;
+        let k = |s: f64| ln(self.characteristic(Complex::new(0.0, -s), t).re);
+        let (k0, k1, k2, km1, km2) = (k(0.0), k(h), k(2.0 * h), k(-h), k(-2.0 * h));
+        [
+            (km2 - 8.0 * km1 + 8.0 * k1 - k2) / (12.0 * h),
+            (-km2 + 16.0 * km1 - 30.0 * k0 + 16.0 * k1 - k2) / (12.0 * h * h),
+            (km2 - 4.0 * km1 + 6.0 * k0 - 4.0 * k1 + k2) / (h * h * h * h),
+        ]
+    }
+    /// Rejects parameters the characteristic function is not defined for
+    fn validate(&self) -> Result<(), PricingError> {
+        Ok(())
+    }
+}
+
+/// Lognormal model with constant volatility, the reference the other models reduce to.
+///
+/// Formula: φ(u) = exp(-½σ²t(iu + u²))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackScholesModel {
+    pub volatility: f64,
+}
+
+impl CharacteristicFunction for BlackScholesModel {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        (-(I * u + u * u) * (0.5 * self.volatility * self.volatility * t)).exp()
+    }
+    fn cumulants(&self, t: f64) -> [f64; 3] {
+        let variance = self.volatility * self.volatility * t;
+        [-0.5 * variance, variance, 0.0]
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        if !(self.volatility > 0.0 && self.volatility.is_finite()) {
+            return Err(PricingError::InvalidInput(format!(
+                "volatility {} is not positive",
+                self.volatility
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Heston stochastic variance, dv = κ(θ - v)dt + ξ√v dW with correlation ρ to the spot, in
+/// Albrecher et al.'s formulation, which stays on the principal branch of the logarithm.
+///
+/// Formula: φ(u) = exp(C + Dv₀), d = √((κ - ρξiu)² + ξ²(iu + u²)), g = (κ - ρξiu - d)/(κ - ρξiu + d),
+/// C = κθ/ξ² ((κ - ρξiu - d)t - 2 ln((1 - ge^(-dt))/(1 - g))),
+/// D = (κ - ρξiu - d)/ξ² (1 - e^(-dt))/(1 - ge^(-dt))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HestonModel {
+    /// Initial variance v₀
+    pub variance: f64,
+    /// Mean reversion speed κ
+    pub kappa: f64,
+    /// Long-run variance θ
+    pub theta: f64,
+    /// Volatility of variance ξ
+    pub vol_of_vol: f64,
+    /// Correlation ρ of spot and variance
+    pub rho: f64,
+}
+
+impl CharacteristicFunction for HestonModel {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let xi2 = self.vol_of_vol * self.vol_of_vol;
+        let iu = I * u;
+        let a = self.kappa - iu * (self.rho * self.vol_of_vol);
+        let d = (a * a + (iu + u * u) * xi2).sqrt();
+        let g = (a - d) / (a + d);
+        let decay = (-d * t).exp();
+        let c = ((a - d) * t - ((1.0 - g * decay) / (1.0 - g)).ln() * 2.0)
+            * (self.kappa * self.theta / xi2);
+        let dv = (a - d) / xi2 * (1.0 - decay) / (1.0 - g * decay);
+        (c + dv * self.variance).exp()
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        let positive = [
+            ("initial variance", self.variance),
+            ("mean reversion", self.kappa),
+            ("long-run variance", self.theta),
+            ("vol of vol", self.vol_of_vol),
+        ];
+        for (name, value) in positive {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(PricingError::InvalidInput(format!(
+                    "Heston {} {} is not positive",
+                    name, value
+                )));
+            }
+        }
+        if !(-1.0..=1.0).contains(&self.rho) {
+            return Err(PricingError::InvalidInput(format!(
+                "Heston correlation {} is outside [-1, 1]",
+                self.rho
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Calibratable for HestonModel {
+    const PARAMETERS: &'static [Parameter] = &[
+        Parameter::positive("variance"),
+        Parameter::positive("kappa"),
+        Parameter::positive("theta"),
+        Parameter::positive("vol_of_vol"),
+        Parameter::new("rho", -1.0, 1.0),
+    ];
+    fn values(&self) -> Vec<f64> {
+        vec![
+            self.variance,
+            self.kappa,
+            self.theta,
+            self.vol_of_vol,
+            self.rho,
+        ]
+    }
+    fn from_values(values: &[f64]) -> Self {
+        HestonModel {
+            variance: values[0],
+            kappa: values[1],
+            theta: values[2],
+            vol_of_vol: values[3],
+            rho: values[4],
+        }
+    }
+}
+
+/// Merton jump diffusion: lognormal diffusion plus Poisson jumps of normally distributed
+/// log-size, compensated so the forward is unchanged.
+///
+/// Formula: φ(u) = exp(t(-½σ²(iu + u²) + λ(e^(iuμ - ½δ²u²) - 1) - iuλ(e^(μ + ½δ²) - 1)))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MertonJumpDiffusion {
+    pub volatility: f64,
+    /// Jumps per year λ
+    pub intensity: f64,
+    /// Mean μ of the log jump size
+    pub jump_mean: f64,
+    /// Standard deviation δ of the log jump size
+    pub jump_vol: f64,
+}
+
+impl CharacteristicFunction for MertonJumpDiffusion {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let iu = I * u;
+        let diffusion = -(iu + u * u) * (0.5 * self.volatility * self.volatility);
+        let jump =
+            (iu * self.jump_mean - u * u * (0.5 * self.jump_vol * self.jump_vol)).exp() - 1.0;
+        let compensator = exp(self.jump_mean + 0.5 * self.jump_vol * self.jump_vol) - 1.0;
+        ((diffusion + (jump - iu * compensator) * self.intensity) * t).exp()
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        if !(self.volatility > 0.0
+            && self.volatility.is_finite()
+            && self.intensity >= 0.0
+            && self.jump_vol >= 0.0)
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "Merton volatility {} must be positive, intensity {} and jump vol {} non-negative",
+                self.volatility, self.intensity, self.jump_vol
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Calibratable for MertonJumpDiffusion {
+    const PARAMETERS: &'static [Parameter] = &[
+        Parameter::positive("volatility"),
+        Parameter::positive("intensity"),
+        Parameter::unbounded("jump_mean"),
+        Parameter::positive("jump_vol"),
+    ];
+    fn values(&self) -> Vec<f64> {
+        vec![
+            self.volatility,
+            self.intensity,
+            self.jump_mean,
+            self.jump_vol,
+        ]
+    }
+    fn from_values(values: &[f64]) -> Self {
+        MertonJumpDiffusion {
+            volatility: values[0],
+            intensity: values[1],
+            jump_mean: values[2],
+            jump_vol: values[3],
+        }
+    }
+}
+
+/// European price of `option` under `model`; the option's own volatility is ignored.
+pub fn cos_price<M: CharacteristicFunction + ?Sized>(
+    model: &M,
+    option: &Options,
+) -> Result<f64, PricingError> {
+    cos_price_with(model, option, &NumericsConfig::default())
+}
+
+/// `cos_price` with the series summed until a block of `COS_BLOCK` terms moves the price by
+/// less than `numerics.tolerance`. Puts are priced from the series and calls by put-call parity,
+/// which keeps deep in-the-money calls from losing the exponential payoff to truncation.
+///
+/// Formula: P = K e^(-rT) Σ' Re[φ(uₖ) e^(iuₖ(x - a))] (2/(b - a))(ψₖ - χₖ), uₖ = kπ/(b - a),
+/// x = ln(F/K), over the range [a, b] = x + c₁ ± L√(c₂ + √|c₄|). Fails on invalid parameters or
+/// when the series has not converged within `MAX_COS_TERMS` terms
+pub fn cos_price_with<M: CharacteristicFunction + ?Sized>(
+    model: &M,
+    option: &Options,
+    numerics: &NumericsConfig,
+) -> Result<f64, PricingError> {
+    model.validate()?;
+    let (spot, strike, t) = (
+        option.spot_price(),
+        option.strike_price(),
+        option.time_to_maturity(),
+    );
+    if !(spot > 0.0 && strike > 0.0 && t > 0.0) {
+        return Err(PricingError::InvalidInput(format!(
+            "spot {}, strike {} and maturity {} must be positive",
+            spot, strike, t
+        )));
+    }
+    let rate = option.risk_free_rate();
+    let dividend_yield = option.dividend_yield().unwrap_or(0.0);
+    let x = ln(spot / strike) + (rate - dividend_yield) * t;
+    let [c1, c2, c4] = model.cumulants(t);
+    if !(c2 > 0.0 && c2.is_finite() && c4.is_finite()) {
+        return Err(PricingError::InvalidInput(format!(
+            "log-price variance {} is not positive",
+            c2
+        )));
+    }
+    let width = TRUNCATION_WIDTH * sqrt(c2 + sqrt(c4.abs()));
+    let (a, b) = (x + c1 - width, x + c1 + width);
+    // the put pays K(1 - e^y) for y = ln(S_T/K) in [a, min(b, 0)]
+    let d = b.min(0.0);
+    let discount = strike * exp(-rate * t);
+    let mut put = 0.0;
+    if d > a {
+        let scale = PI / (b - a);
+        let term = |k: usize| {
+            let u = k as f64 * scale;
+            let (chi, psi) = if k == 0 {
+                (exp(d) - exp(a), d - a)
+            } else {
+                let angle = u * (d - a);
+                (
+                    (cos(angle) * exp(d) - exp(a) + u * sin(angle) * exp(d)) / (1.0 + u * u),
+                    sin(angle) / u,
+                )
+            };
+            let density = model.characteristic(Complex::real(u), t) * (I * (u * (x - a))).exp();
+            let weight = if k == 0 { 0.5 } else { 1.0 };
+            weight * density.re * 2.0 / (b - a) * (psi - chi) * discount
+        };
+        let mut terms = 0;
+        loop {
+            let block: f64 = (terms..terms + COS_BLOCK).map(term).sum();
+            if !block.is_finite() {
+                return Err(PricingError::InvalidInput(
+                    "characteristic function is not finite".into(),
+                ));
+            }
+            put += block;
+            terms += COS_BLOCK;
+            if block.abs() < numerics.tolerance {
+                break;
+            }
+            if terms >= MAX_COS_TERMS {
+                return Err(PricingError::InvalidInput(format!(
+                    "COS series has not converged within {} terms",
+                    MAX_COS_TERMS
+                )));
+            }
+        }
+    }
+    let put = put.max(0.0);
+    Ok(match option {
+        Options::Put(_) => put,
+        Options::Call(_) => put + spot * exp(-dividend_yield * t) - discount,
+    })
+}
+
+/// Model whose parameters `calibrate` can fit, each within the open interval it declares.
+pub trait Calibratable: CharacteristicFunction + Sized {
+    /// Names and bounds of the parameters, in the order of `values`
+    const PARAMETERS: &'static [Parameter];
+    fn values(&self) -> Vec<f64>;
+    fn from_values(values: &[f64]) -> Self;
+}
+
+/// Model fitted to option prices, with the report of the fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration<M> {
+    pub model: M,
+    pub report: CalibrationReport,
+}
+
+/// Fits `initial`'s parameters to `(option, price)` quotes by least squares on COS prices,
+/// with the Nelder-Mead simplex in coordinates mapping each parameter's interval onto the
+/// real line. Parameters the model rejects count as an infinite error. The report's residuals
+/// are in price. Fails without quotes or when no valid fit is found
+pub fn calibrate<M: Calibratable>(
+    initial: &M,
+    quotes: &[(Options, f64)],
+) -> Result<Calibration<M>, PricingError> {
+    if quotes.is_empty() {
+        return Err(PricingError::InvalidInput(
+            "no quotes to calibrate to".into(),
+        ));
+    }
+    let from_unconstrained = |x: &[f64]| -> Vec<f64> {
+        M::PARAMETERS
+            .iter()
+            .zip(x)
+            .map(|(parameter, &x)| parameter.from_unconstrained(x))
+            .collect()
+    };
+    let prices = |values: &[f64]| -> Vec<f64> {
+        let model = M::from_values(values);
+        quotes
+            .iter()
+            .map(|(option, _)| cos_price(&model, option).unwrap_or(f64::NAN))
+            .collect()
+    };
+    let objective = |x: &[f64]| {
+        let squares: f64 = prices(&from_unconstrained(x))
+            .iter()
+            .zip(quotes)
+            .map(|(model_price, (_, price))| (model_price - price) * (model_price - price))
+            .sum();
+        if squares.is_nan() {
+            f64::INFINITY
+        } else {
+            squares
+        }
+    };
+    let start: Vec<f64> = M::PARAMETERS
+        .iter()
+        .zip(initial.values())
+        .map(|(parameter, value)| parameter.to_unconstrained(value))
+        .collect();
+    let (best, error) = nelder_mead(
+        objective,
+        &start,
+        CALIBRATION_STEP,
+        1e-16,
+        CALIBRATION_ITERATIONS,
+    );
+    if !error.is_finite() {
+        return Err(PricingError::InvalidInput(
+            "calibration found no parameters the model accepts".into(),
+        ));
+    }
+    let values = from_unconstrained(&best);
+    let targets: Vec<f64> = quotes.iter().map(|(_, price)| *price).collect();
+    let report = CalibrationReport::from_fit(M::PARAMETERS, &values, &targets, prices);
+    Ok(Calibration {
+        model: M::from_values(&values),
+        report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptionType;
+
+    #[test]
+    fn test_black_scholes_recovered() {
+        let model = BlackScholesModel { volatility: 0.25 };
+        for option_type in [OptionType::Call, OptionType::Put] {
+            for (strike, maturity, dividend) in [
+                (60.0, 0.1, None),
+                (100.0, 1.0, Some(0.02)),
+                (150.0, 3.0, None),
+            ] {
+                let option = Options::new_with_type(
+                    option_type,
+                    strike,
+                    100.0,
+                    0.25,
+                    0.05,
+                    maturity,
+                    dividend,
+                );
+                let price = cos_price(&model, &option).unwrap();
+                assert!((price - option.bs_pricing()).abs() < 1e-9);
+            }
+        }
+        // numerical cumulants agree with the closed form
+        let numerical = HestonModel {
+            variance: 0.0625,
+            kappa: 1.0,
+            theta: 0.0625,
+            vol_of_vol: 1e-3,
+            rho: 0.0,
+        };
+        let [c1, c2, c4] = numerical.cumulants(2.0);
+        assert!((c1 + 0.0625).abs() < 1e-6 && (c2 - 0.125).abs() < 1e-6 && c4.abs() < 1e-4);
+        let expired = Options::new_call(100.0, 100.0, 0.25, 0.05, 0.0, None);
+        assert!(cos_price(&model, &expired).is_err());
+        assert!(cos_price(&BlackScholesModel { volatility: 0.0 }, &expired).is_err());
+    }
+
+    #[test]
+    fn test_heston_reference_price() {
+        // Fang and Oosterlee's Heston test case, reference value 5.785155450
+        let model = HestonModel {
+            variance: 0.0175,
+            kappa: 1.5768,
+            theta: 0.0398,
+            vol_of_vol: 0.5751,
+            rho: -0.5711,
+        };
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.0, 1.0, None);
+        assert!((cos_price(&model, &call).unwrap() - 5.785155450).abs() < 1e-7);
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.0, 1.0, None);
+        assert!((cos_price(&model, &put).unwrap() - 5.785155450).abs() < 1e-7);
+        // without vol of vol the variance is deterministic and the price Black-Scholes
+        let flat = HestonModel {
+            variance: 0.04,
+            theta: 0.04,
+            vol_of_vol: 1e-3,
+            rho: 0.0,
+            ..model
+        };
+        let option = Options::new_put(90.0, 100.0, 0.2, 0.03, 0.5, Some(0.01));
+        assert!((cos_price(&flat, &option).unwrap() - option.bs_pricing()).abs() < 1e-6);
+        assert!(cos_price(&HestonModel { rho: 1.5, ..model }, &option).is_err());
+    }
+
+    #[test]
+    fn test_merton_series() {
+        let model = MertonJumpDiffusion {
+            volatility: 0.2,
+            intensity: 0.5,
+            jump_mean: -0.1,
+            jump_vol: 0.15,
+        };
+        let (spot, strike, rate, t) = (100.0, 95.0, 0.04, 0.75);
+        // Merton's series of Black-Scholes prices conditional on n jumps
+        let k = exp(model.jump_mean + 0.5 * model.jump_vol * model.jump_vol) - 1.0;
+        let mean_jumps = model.intensity * (1.0 + k) * t;
+        let mut weight = exp(-mean_jumps);
+        let mut series = 0.0;
+        for n in 0..40 {
+            let n = n as f64;
+            if n > 0.0 {
+                weight *= mean_jumps / n;
+            }
+            let vol = sqrt(model.volatility.powi(2) + n * model.jump_vol.powi(2) / t);
+            let drift = rate - model.intensity * k + n * ln(1.0 + k) / t;
+            series += weight * Options::new_call(strike, spot, vol, drift, t, None).bs_pricing();
+        }
+        let call = Options::new_call(strike, spot, 0.2, rate, t, None);
+        assert!((cos_price(&model, &call).unwrap() - series).abs() < 1e-8);
+        // no jumps is Black-Scholes
+        let diffusion = MertonJumpDiffusion {
+            intensity: 0.0,
+            ..model
+        };
+        assert!((cos_price(&diffusion, &call).unwrap() - call.bs_pricing()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,198 @@
+// Options on futures under Black-76. Listed futures options come in two flavours: premium paid
+// upfront, as on CME, and futures-style, as on Eurex, ICE Europe and ASX, where the option itself
+// is marked to market daily against margin and no premium changes hands until it is closed or
+// exercised. Nothing is financed in the futures-style case, so the value carries no discounting
+// and, like the future, no sensitivity to rates.
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, sqrt};
+use crate::math::normal::Normal;
+use crate::{OptionType, Options};
+
+/// How the option premium is settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PremiumStyle {
+    /// Paid in full at the trade and discounted from expiry
+    #[default]
+    Upfront,
+    /// Margined daily like the future, worth the undiscounted Black-76 value
+    FuturesStyle,
+}
+
+/// A European option on a futures price, priced with Black-76.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FuturesOption {
+    pub option_type: OptionType,
+    pub strike: f64,
+    /// Futures price for the option's delivery month
+    pub futures: f64,
+    pub volatility: f64,
+    pub rate: f64,
+    pub expiry: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub premium: PremiumStyle,
+}
+
+impl FuturesOption {
+    /// Upfront-premium option; see `with_premium` for futures-style margining
+    pub fn new(
+        option_type: OptionType,
+        strike: f64,
+        futures: f64,
+        volatility: f64,
+        rate: f64,
+        expiry: f64,
+    ) -> Self {
+        FuturesOption {
+            option_type,
+            strike,
+            futures,
+            volatility,
+            rate,
+            expiry,
+            premium: PremiumStyle::Upfront,
+        }
+    }
+    pub fn with_premium(self, premium: PremiumStyle) -> Self {
+        FuturesOption { premium, ..self }
+    }
+    /// Factor applied to the undiscounted value: e^(-rT) upfront, 1 futures-style
+    pub fn discount(&self) -> f64 {
+        match self.premium {
+            PremiumStyle::Upfront => exp(-self.rate * self.expiry),
+            PremiumStyle::FuturesStyle => 1.0,
+        }
+    }
+    fn phi(&self) -> f64 {
+        match self.option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        }
+    }
+    // (d₁, d₂, σ√T)
+    fn d(&self) -> (f64, f64, f64) {
+        let sd = self.volatility * sqrt(self.expiry);
+        let d1 = (ln(self.futures / self.strike) + 0.5 * sd * sd) / sd;
+        (d1, d1 - sd, sd)
+    }
+    /// Formula: V = D φ[F N(φd₁) - K N(φd₂)], d₁,₂ = (ln(F/K) ± σ²T/2) / σ√T
+    pub fn price(&self) -> f64 {
+        let phi = self.phi();
+        if self.expiry <= 0.0 {
+            return (phi * (self.futures - self.strike)).max(0.0);
+        }
+        let (d1, d2, _) = self.d();
+        let std_norm = Normal::standard();
+        self.discount()
+            * phi
+            * (self.futures * std_norm.cdf(phi * d1) - self.strike * std_norm.cdf(phi * d2))
+    }
+    /// Formula: max(φ(F - K), 0)
+    pub fn payout(&self, futures: f64) -> f64 {
+        (self.phi() * (futures - self.strike)).max(0.0)
+    }
+    /// Sensitivity to the futures price
+    ///
+    /// Formula: Δ = D φ N(φd₁)
+    pub fn delta(&self) -> f64 {
+        let phi = self.phi();
+        let (d1, _, _) = self.d();
+        self.discount() * phi * Normal::standard().cdf(phi * d1)
+    }
+    /// Formula: Γ = D N'(d₁) / (F σ√T)
+    pub fn gamma(&self) -> f64 {
+        let (d1, _, sd) = self.d();
+        self.discount() * Normal::standard().pdf(d1) / (self.futures * sd)
+    }
+    /// Formula: ν = D F √T N'(d₁)
+    pub fn vega(&self) -> f64 {
+        let (d1, _, _) = self.d();
+        self.discount() * self.futures * sqrt(self.expiry) * Normal::standard().pdf(d1)
+    }
+    /// Annualized theta with the futures price held; the carry term rV only accrues on an
+    /// upfront premium
+    ///
+    /// Formula: Θ = -D F N'(d₁) σ / 2√T + rV upfront, -F N'(d₁) σ / 2√T futures-style
+    pub fn theta(&self) -> f64 {
+        let (d1, _, _) = self.d();
+        let decay = -self.discount() * self.futures * Normal::standard().pdf(d1) * self.volatility
+            / (2.0 * sqrt(self.expiry));
+        match self.premium {
+            PremiumStyle::Upfront => decay + self.rate * self.price(),
+            PremiumStyle::FuturesStyle => decay,
+        }
+    }
+    /// Rate sensitivity with the futures price held
+    ///
+    /// Formula: ρ = -TV upfront, 0 futures-style
+    pub fn rho(&self) -> f64 {
+        match self.premium {
+            PremiumStyle::Upfront => -self.expiry * self.price(),
+            PremiumStyle::FuturesStyle => 0.0,
+        }
+    }
+    /// Black-76 volatility reproducing a premium quoted in this contract's premium style
+    pub fn implied_volatility(&self, price: f64) -> Result<f64, PricingError> {
+        // an upfront premium is the same Black-Scholes contract with spot F and yield r
+        let upfront = price * exp(-self.rate * self.expiry) / self.discount();
+        self.to_options().implied_volatility(upfront)
+    }
+    /// The upfront-premium contract as Black-Scholes on spot F with dividend yield r
+    pub fn to_options(&self) -> Options {
+        Options::new_with_type(
+            self.option_type,
+            self.strike,
+            self.futures,
+            self.volatility,
+            self.rate,
+            self.expiry,
+            Some(self.rate),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upfront_and_futures_style_premiums() {
+        let upfront = FuturesOption::new(OptionType::Call, 95.0, 100.0, 0.25, 0.04, 0.5);
+        let margined = upfront.with_premium(PremiumStyle::FuturesStyle);
+        let black = upfront.to_options();
+        assert!((upfront.price() - black.bs_pricing()).abs() < 1e-12);
+        assert!((upfront.delta() - black.delta()).abs() < 1e-12);
+        assert!((upfront.gamma() - black.gamma()).abs() < 1e-12);
+        assert!((upfront.vega() - black.vega()).abs() < 1e-10);
+        assert!((upfront.theta() - black.theta()).abs() < 1e-10);
+
+        // margining drops the discount from the value and every Greek
+        let df = (-0.04f64 * 0.5).exp();
+        assert!((margined.price() * df - upfront.price()).abs() < 1e-12);
+        assert!((margined.delta() * df - upfront.delta()).abs() < 1e-12);
+        assert!((margined.vega() * df - upfront.vega()).abs() < 1e-10);
+        assert_eq!(margined.rho(), 0.0);
+        let h = 1e-5;
+        let bumped = |rate: f64| FuturesOption { rate, ..upfront }.price();
+        assert!((upfront.rho() - (bumped(0.04 + h) - bumped(0.04 - h)) / (2.0 * h)).abs() < 1e-6);
+        let later = |expiry: f64| FuturesOption { expiry, ..margined }.price();
+        assert!((margined.theta() + (later(0.5 + h) - later(0.5 - h)) / (2.0 * h)).abs() < 1e-6);
+
+        // undiscounted put-call parity C - P = F - K
+        let put = FuturesOption {
+            option_type: OptionType::Put,
+            ..margined
+        };
+        assert!((margined.price() - put.price() - 5.0).abs() < 1e-12);
+
+        for option in [upfront, margined] {
+            let vol = option.implied_volatility(option.price()).unwrap();
+            assert!((vol - 0.25).abs() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// Black-Scholes prices and Greeks over any `Real` scalar. These are the only copy of the closed
+// forms: the f64 API on `Options` and `Vanilla` evaluates them at `T = f64`, and f32 workloads
+// run the same code in single precision.
+use crate::math::real::{Real, norm_pdf};
+use crate::{OptionType, Options};
+
+/// A vanilla contract with every input in the scalar type `T`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackScholes<T> {
+    pub option_type: OptionType,
+    pub strike: T,
+    pub spot: T,
+    pub volatility: T,
+    pub rate: T,
+    pub expiry: T,
+    /// Continuous dividend yield, zero for none
+    pub dividend_yield: T,
+}
+
+impl<T: Real> BlackScholes<T> {
+    /// Converts an f64 contract into `T`
+    pub fn from_options(option: &Options) -> Self {
+        BlackScholes {
+            option_type: option.option_type(),
+            strike: T::from_f64(option.strike_price()),
+            spot: T::from_f64(option.spot_price()),
+            volatility: T::from_f64(option.volatility()),
+            rate: T::from_f64(option.risk_free_rate()),
+            expiry: T::from_f64(option.time_to_maturity()),
+            dividend_yield: T::from_f64(option.dividend_yield().unwrap_or(0.0)),
+        }
+    }
+    // +1 for calls and -1 for puts, so each formula is written once
+    fn sign(&self) -> T {
+        match self.option_type {
+            OptionType::Call => T::one(),
+            OptionType::Put => -T::one(),
+        }
+    }
+    // (d₁, d₂)
+    fn d(&self) -> (T, T) {
+        let half = T::from_f64(0.5);
+        let sd = self.volatility * self.expiry.sqrt();
+        let d1 = ((self.spot / self.strike).ln()
+            + (self.rate - self.dividend_yield + half * self.volatility * self.volatility)
+                * self.expiry)
+            / sd;
+        (d1, d1 - sd)
+    }
+    fn spot_discount(&self) -> T {
+        (-self.dividend_yield * self.expiry).exp()
+    }
+    fn strike_discount(&self) -> T {
+        (-self.rate * self.expiry).exp()
+    }
+    /// Black-Scholes price, or intrinsic value once the contract has expired
+    ///
+    /// Formula: φ[S e^(-qT) N(φd₁) - K e^(-rT) N(φd₂)], φ = +1 for calls and -1 for puts
+    pub fn price(&self) -> T {
+        let phi = self.sign();
+        if self.expiry <= T::zero() {
+            return (phi * (self.spot - self.strike)).max(T::zero());
+        }
+        let (d1, d2) = self.d();
+        phi * (self.spot * self.spot_discount() * (phi * d1).normal_cdf()
+            - self.strike * self.strike_discount() * (phi * d2).normal_cdf())
+    }
+    /// Formula: Δ = φ e^(-qT) N(φd₁)
+    pub fn delta(&self) -> T {
+        let phi = self.sign();
+        let (d1, _) = self.d();
+        phi * self.spot_discount() * (phi * d1).normal_cdf()
+    }
+    /// Formula: Γ = N'(d₁) e^(-qT) / (S σ √T)
+    pub fn gamma(&self) -> T {
+        let (d1, _) = self.d();
+        norm_pdf(d1) * self.spot_discount() / (self.spot * self.volatility * self.expiry.sqrt())
+    }
+    /// Formula: ν = S N'(d₁) √T e^(-qT)
+    pub fn vega(&self) -> T {
+        let (d1, _) = self.d();
+        self.spot * norm_pdf(d1) * self.expiry.sqrt() * self.spot_discount()
+    }
+    /// Annualized theta
+    ///
+    /// Formula: Θ = -S N'(d₁) σ e^(-qT) / (2√T) + φ[q S e^(-qT) N(φd₁) - r K e^(-rT) N(φd₂)]
+    pub fn theta(&self) -> T {
+        let phi = self.sign();
+        let (d1, d2) = self.d();
+        let two = T::from_f64(2.0);
+        let spot_pv = self.spot * self.spot_discount();
+        -spot_pv * norm_pdf(d1) * self.volatility / (two * self.expiry.sqrt())
+            + phi
+                * (self.dividend_yield * spot_pv * (phi * d1).normal_cdf()
+                    - self.rate * self.strike * self.strike_discount() * (phi * d2).normal_cdf())
+    }
+    /// Formula: ρ = φ K T e^(-rT) N(φd₂)
+    pub fn rho(&self) -> T {
+        let phi = self.sign();
+        let (_, d2) = self.d();
+        phi * self.strike * self.expiry * self.strike_discount() * (phi * d2).normal_cdf()
+    }
+}
+
+impl Options {
+    /// The contract with its inputs converted to `T`, e.g. `option.generic::<f32>().price()`
+    pub fn generic<T: Real>(&self) -> BlackScholes<T> {
+        BlackScholes::from_options(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generic_matches_reference_values() {
+        // closed-form values at S = K = 100, σ = 20%, r = 5%, T = 1, computed independently
+        let cases = [
+            (
+                Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None),
+                [
+                    10.450584, 0.636831, 0.018762, 37.524035, -6.414028, 53.232482,
+                ],
+            ),
+            (
+                Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None),
+                [
+                    5.573526, -0.363169, 0.018762, 37.524035, -1.657880, -41.890461,
+                ],
+            ),
+        ];
+        for (option, expected) in cases {
+            let generic = option.generic::<f64>();
+            let actual = [
+                generic.price(),
+                generic.delta(),
+                generic.gamma(),
+                generic.vega(),
+                generic.theta(),
+                generic.rho(),
+            ];
+            for (actual, expected) in actual.into_iter().zip(expected) {
+                assert!((actual - expected).abs() < 1e-6, "{actual} vs {expected}");
+            }
+            let single = option.generic::<f32>();
+            assert!((single.price() as f64 - generic.price()).abs() < 1e-3);
+            assert!((single.delta() as f64 - generic.delta()).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+// Hedge suggestions: trades from a universe of listed instruments that flatten a book's delta,
+// and optionally gamma and vega, underlying by underlying and in whole lots.
+use crate::Options;
+use crate::error::PricingError;
+use crate::math::float::powi;
+use crate::math::linalg::{cholesky, cholesky_solve};
+use crate::math::sum::compensated_sum;
+use crate::portfolio::{Portfolio, Position, StockPosition};
+
+const DELTA: usize = 0;
+const GAMMA: usize = 1;
+const VEGA: usize = 2;
+/// Bound on the one-lot improvement passes after rounding
+const MAX_SEARCH_PASSES: usize = 1000;
+
+/// What a hedge instrument trades.
+#[derive(Debug, Clone, Copy)]
+pub enum HedgeAsset {
+    /// Shares at `spot`, delta one each
+    Stock {
+        spot: f64,
+    },
+    Option(Options),
+}
+
+/// A listed hedge on a named underlying, traded in multiples of `lot_size` units.
+#[derive(Debug, Clone)]
+pub struct HedgeInstrument {
+    pub label: String,
+    pub underlying: String,
+    pub asset: HedgeAsset,
+    /// Units per lot, e.g. 100 for a standard equity option contract
+    pub lot_size: f64,
+    /// Largest number of lots bought or sold, unbounded when `None`
+    pub max_lots: Option<u64>,
+}
+
+impl HedgeInstrument {
+    pub fn stock(underlying: &str, spot: f64, lot_size: f64) -> Self {
+        HedgeInstrument {
+            label: underlying.to_string(),
+            underlying: underlying.to_string(),
+            asset: HedgeAsset::Stock { spot },
+            lot_size,
+            max_lots: None,
+        }
+    }
+    pub fn option(label: &str, underlying: &str, option: Options, lot_size: f64) -> Self {
+        HedgeInstrument {
+            label: label.to_string(),
+            underlying: underlying.to_string(),
+            asset: HedgeAsset::Option(option),
+            lot_size,
+            max_lots: None,
+        }
+    }
+    pub fn with_max_lots(mut self, max_lots: u64) -> Self {
+        self.max_lots = Some(max_lots);
+        self
+    }
+    // Delta, gamma and vega of one lot
+    fn lot_greeks(&self) -> [f64; 3] {
+        let unit = match &self.asset {
+            HedgeAsset::Stock { .. } => [1.0, 0.0, 0.0],
+            HedgeAsset::Option(option) => [option.delta(), option.gamma(), option.vega()],
+        };
+        unit.map(|greek| greek * self.lot_size)
+    }
+    fn is_stock(&self) -> bool {
+        matches!(self.asset, HedgeAsset::Stock { .. })
+    }
+}
+
+/// Which Greeks a hedge neutralizes besides delta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HedgeTargets {
+    pub gamma: bool,
+    pub vega: bool,
+}
+
+impl HedgeTargets {
+    pub fn delta() -> Self {
+        HedgeTargets::default()
+    }
+    pub fn delta_gamma() -> Self {
+        HedgeTargets {
+            gamma: true,
+            vega: false,
+        }
+    }
+    pub fn delta_gamma_vega() -> Self {
+        HedgeTargets {
+            gamma: true,
+            vega: true,
+        }
+    }
+    fn rows(&self) -> Vec<usize> {
+        let mut rows = vec![DELTA];
+        if self.gamma {
+            rows.push(GAMMA);
+        }
+        if self.vega {
+            rows.push(VEGA);
+        }
+        rows
+    }
+}
+
+/// Delta, gamma and vega of the book on one underlying.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HedgeGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+}
+
+impl HedgeGreeks {
+    fn from_array(greeks: [f64; 3]) -> Self {
+        HedgeGreeks {
+            delta: greeks[DELTA],
+            gamma: greeks[GAMMA],
+            vega: greeks[VEGA],
+        }
+    }
+}
+
+/// A suggested buy (positive lots) or sell of one hedge instrument.
+#[derive(Debug, Clone)]
+pub struct HedgeTrade {
+    pub label: String,
+    pub underlying: String,
+    pub asset: HedgeAsset,
+    pub lots: i64,
+    /// Signed units, `lots * lot_size`
+    pub quantity: f64,
+}
+
+/// Exposure on one underlying before and after the suggested trades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingHedge {
+    pub underlying: String,
+    pub before: HedgeGreeks,
+    pub after: HedgeGreeks,
+}
+
+/// Suggested trades with the exposures they leave. Underlyings without a usable instrument
+/// keep their exposure.
+#[derive(Debug, Clone)]
+pub struct HedgePlan {
+    pub trades: Vec<HedgeTrade>,
+    pub exposures: Vec<UnderlyingHedge>,
+}
+
+impl HedgePlan {
+    /// The portfolio with every suggested trade added
+    pub fn apply(&self, portfolio: &Portfolio) -> Portfolio {
+        let mut hedged = portfolio.clone();
+        for trade in &self.trades {
+            match trade.asset {
+                HedgeAsset::Stock { spot } => {
+                    hedged.add_stock(StockPosition::new(&trade.underlying, spot, trade.quantity))
+                }
+                HedgeAsset::Option(option) => {
+                    hedged.add(Position::new(&trade.underlying, option, trade.quantity))
+                }
+            }
+        }
+        hedged
+    }
+}
+
+// Book Greeks on one underlying; shares only carry delta
+fn exposure(portfolio: &Portfolio, underlying: &str) -> [f64; 3] {
+    let held: Vec<&Position> = portfolio
+        .positions()
+        .iter()
+        .filter(|position| position.underlying == underlying)
+        .collect();
+    let greek = |f: fn(&Options) -> f64| {
+        compensated_sum(
+            held.iter()
+                .map(|position| position.units() * f(&position.option)),
+        )
+    };
+    [
+        portfolio.underlying_delta(underlying),
+        greek(Options::gamma),
+        greek(Options::vega),
+    ]
+}
+
+// Lots of each instrument on one underlying and what they must offset
+struct Problem<'a> {
+    instruments: Vec<&'a HedgeInstrument>,
+    greeks: Vec<[f64; 3]>,
+    exposure: [f64; 3],
+    rows: Vec<usize>,
+    // Largest one-lot move of each row, so rows in different units weigh alike
+    scales: [f64; 3],
+}
+
+impl Problem<'_> {
+    fn residual(&self, lots: &[i64]) -> [f64; 3] {
+        let mut residual = self.exposure;
+        for (greeks, lots) in self.greeks.iter().zip(lots) {
+            for row in 0..3 {
+                residual[row] += *lots as f64 * greeks[row];
+            }
+        }
+        residual
+    }
+    fn objective(&self, lots: &[i64]) -> f64 {
+        let residual = self.residual(lots);
+        self.rows
+            .iter()
+            .map(|&row| powi(residual[row] / self.scales[row], 2))
+            .sum()
+    }
+    fn bound(&self, j: usize) -> i64 {
+        self.instruments[j]
+            .max_lots
+            .map_or(i64::MAX, |max| max.min(i64::MAX as u64) as i64)
+    }
+    // Minimum-norm continuous lots of the `active` instruments offsetting `rows`, rounded
+    fn solve(&self, lots: &mut [i64], active: &[usize], rows: &[usize]) {
+        let residual = self.residual(lots);
+        let a: Vec<Vec<f64>> = rows
+            .iter()
+            .map(|&row| {
+                active
+                    .iter()
+                    .map(|&j| self.greeks[j][row] / self.scales[row])
+                    .collect()
+            })
+            .collect();
+        let b: Vec<f64> = rows
+            .iter()
+            .map(|&row| -residual[row] / self.scales[row])
+            .collect();
+        // A Aᵀ with a small ridge, so rows no instrument can separate still factor
+        let mut normal: Vec<Vec<f64>> = a
+            .iter()
+            .map(|ri| {
+                a.iter()
+                    .map(|rk| ri.iter().zip(rk).map(|(x, y)| x * y).sum())
+                    .collect()
+            })
+            .collect();
+        let ridge = 1e-10 * (1.0 + (0..rows.len()).map(|i| normal[i][i]).sum::<f64>());
+        for (i, row) in normal.iter_mut().enumerate() {
+            row[i] += ridge;
+        }
+        let Some(l) = cholesky(&normal) else {
+            return;
+        };
+        let y = cholesky_solve(&l, &b);
+        for (column, &j) in active.iter().enumerate() {
+            let x: f64 = a.iter().zip(&y).map(|(row, y)| row[column] * y).sum();
+            let bound = self.bound(j);
+            lots[j] = (lots[j] as f64 + x)
+                .round()
+                .clamp(-(bound as f64), bound as f64) as i64;
+        }
+    }
+    // One lot at a time, take the move that lowers the residual most until none does
+    fn improve(&self, lots: &mut [i64]) {
+        let mut best = self.objective(lots);
+        for _ in 0..MAX_SEARCH_PASSES {
+            let mut step = None;
+            for j in 0..lots.len() {
+                for delta in [-1, 1] {
+                    let moved = lots[j] + delta;
+                    if moved.abs() > self.bound(j) {
+                        continue;
+                    }
+                    lots[j] = moved;
+                    let objective = self.objective(lots);
+                    lots[j] -= delta;
+                    if objective < best * (1.0 - 1e-12) {
+                        best = objective;
+                        step = Some((j, delta));
+                    }
+                }
+            }
+            match step {
+                Some((j, delta)) => lots[j] += delta,
+                None => break,
+            }
+        }
+    }
+}
+
+impl Portfolio {
+    /// Trades neutralizing delta, plus gamma and vega when targeted, on every underlying of the
+    /// book. Options carry the gamma and vega hedge and the underlying's stock then takes the
+    /// remaining delta; without a stock instrument the options hedge delta too. Quantities are
+    /// whole lots, rounded from the minimum-size continuous hedge and then refined lot by lot
+    pub fn hedge(
+        &self,
+        universe: &[HedgeInstrument],
+        targets: HedgeTargets,
+    ) -> Result<HedgePlan, PricingError> {
+        if let Some(bad) = universe
+            .iter()
+            .find(|instrument| !(instrument.lot_size.is_finite() && instrument.lot_size > 0.0))
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "hedge {} lot size {} must be positive",
+                bad.label, bad.lot_size
+            )));
+        }
+        let mut plan = HedgePlan {
+            trades: Vec::new(),
+            exposures: Vec::new(),
+        };
+        for underlying in self.underlyings() {
+            let instruments: Vec<&HedgeInstrument> = universe
+                .iter()
+                .filter(|instrument| instrument.underlying == underlying)
+                .collect();
+            let greeks: Vec<[f64; 3]> = instruments.iter().map(|i| i.lot_greeks()).collect();
+            let mut scales = [0.0; 3];
+            for row in 0..3 {
+                scales[row] = greeks.iter().map(|g| g[row].abs()).fold(0.0, f64::max);
+            }
+            let problem = Problem {
+                rows: targets
+                    .rows()
+                    .into_iter()
+                    .filter(|&row| scales[row] > 0.0)
+                    .collect(),
+                exposure: exposure(self, underlying),
+                instruments,
+                greeks,
+                scales,
+            };
+            let mut lots = vec![0; problem.instruments.len()];
+            let stocks: Vec<usize> = (0..lots.len())
+                .filter(|&j| problem.instruments[j].is_stock())
+                .collect();
+            let options: Vec<usize> = (0..lots.len())
+                .filter(|&j| !problem.instruments[j].is_stock())
+                .collect();
+            let option_rows: Vec<usize> = problem
+                .rows
+                .iter()
+                .copied()
+                .filter(|&row| row != DELTA || stocks.is_empty())
+                .collect();
+            if !options.is_empty() && !option_rows.is_empty() {
+                problem.solve(&mut lots, &options, &option_rows);
+            }
+            if !stocks.is_empty() {
+                problem.solve(&mut lots, &stocks, &[DELTA]);
+            }
+            problem.improve(&mut lots);
+
+            for (j, &lots) in lots.iter().enumerate() {
+                if lots != 0 {
+                    let instrument = problem.instruments[j];
+                    plan.trades.push(HedgeTrade {
+                        label: instrument.label.clone(),
+                        underlying: instrument.underlying.clone(),
+                        asset: instrument.asset,
+                        lots,
+                        quantity: lots as f64 * instrument.lot_size,
+                    });
+                }
+            }
+            plan.exposures.push(UnderlyingHedge {
+                underlying: underlying.to_string(),
+                before: HedgeGreeks::from_array(problem.exposure),
+                after: HedgeGreeks::from_array(problem.residual(&lots)),
+            });
+        }
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_gamma_vega_hedge_in_lots() {
+        let mut book = Portfolio::new();
+        let short = Options::new_call(105.0, 100.0, 0.25, 0.03, 0.5, None);
+        book.add(Position::new("XYZ", short, -1_250.0));
+        book.add(Position::new(
+            "XYZ",
+            Options::new_put(90.0, 100.0, 0.3, 0.03, 0.25, None),
+            400.0,
+        ));
+        let universe = [
+            HedgeInstrument::stock("XYZ", 100.0, 1.0),
+            HedgeInstrument::option(
+                "XYZ 100C 3M",
+                "XYZ",
+                Options::new_call(100.0, 100.0, 0.25, 0.03, 0.25, None),
+                100.0,
+            ),
+            HedgeInstrument::option(
+                "XYZ 100C 1Y",
+                "XYZ",
+                Options::new_call(100.0, 100.0, 0.25, 0.03, 1.0, None),
+                100.0,
+            ),
+        ];
+
+        let delta_only = book.hedge(&universe, HedgeTargets::delta()).unwrap();
+        assert_eq!(delta_only.trades.len(), 1);
+        assert_eq!(delta_only.trades[0].label, "XYZ");
+        assert!(delta_only.exposures[0].after.delta.abs() <= 0.5);
+
+        let plan = book
+            .hedge(&universe, HedgeTargets::delta_gamma_vega())
+            .unwrap();
+        let exposure = &plan.exposures[0];
+        // each Greek is left below what one more option lot would move it by
+        let lot = universe[1].lot_greeks();
+        assert!(exposure.after.delta.abs() <= 0.5);
+        assert!(exposure.after.gamma.abs() < lot[GAMMA]);
+        assert!(exposure.after.vega.abs() < universe[2].lot_greeks()[VEGA]);
+        assert!(exposure.after.vega.abs() < 0.1 * exposure.before.vega.abs());
+        for trade in &plan.trades {
+            assert_eq!(
+                trade.quantity,
+                trade.lots as f64 * if trade.label == "XYZ" { 1.0 } else { 100.0 }
+            );
+        }
+        let hedged = plan.apply(&book);
+        assert!((hedged.delta() - exposure.after.delta).abs() < 1e-6);
+        assert!((hedged.vega() - exposure.after.vega).abs() < 1e-6);
+
+        // capped lots leave the gamma the cap cannot reach
+        let capped = [universe[0].clone(), universe[1].clone().with_max_lots(1)];
+        let plan = book.hedge(&capped, HedgeTargets::delta_gamma()).unwrap();
+        assert!(
+            plan.trades
+                .iter()
+                .all(|trade| trade.lots.abs() <= 1 || trade.label == "XYZ")
+        );
+        assert!(plan.exposures[0].after.delta.abs() <= 0.5);
+        assert!(plan.exposures[0].after.gamma < -15.0);
+        let mut bad = universe[0].clone();
+        bad.lot_size = 0.0;
+        assert!(book.hedge(&[bad], HedgeTargets::delta()).is_err());
+    }
+}
@@ -0,0 +1,812 @@
+// One pricing interface for every contract type, so books can hold `Box<dyn Instrument>` and
+// value vanillas, convertibles, bonds and rate options against the same market without per-type
+// match arms.
+use crate::bonds::FixedCouponBond;
+#[cfg(feature = "mc")]
+use crate::conventions::CALENDAR_DAYS_PER_YEAR;
+use crate::credit::{DefaultableBond, HazardCurve};
+use crate::crypto::{InverseOption, usd_to_coin};
+use crate::curves::RateCurve;
+use crate::error::PricingError;
+#[cfg(feature = "mc")]
+use crate::exotics::Autocallable;
+use crate::exotics::{ConvertibleBond, ExoticOptions};
+use crate::futures::FuturesOption;
+use crate::market::MarketQuote;
+use crate::math::float::exp;
+#[cfg(feature = "mc")]
+use crate::mc::MonteCarloEngine;
+#[cfg(feature = "mc")]
+use crate::multi_asset::MultiAssetSimulator;
+use crate::perpetual::PerpetualAmerican;
+use crate::rates::callable::CallableBond;
+use crate::rates::caps::{CapFloor, CapFloorType};
+use crate::rates::hull_white::HullWhite;
+use crate::rates::swaptions::{Swaption, SwaptionType};
+use crate::rates::vol::RateVolatility;
+use crate::structured::{NoteOption, StructuredNote};
+use crate::swing::SwingOption;
+#[cfg(feature = "mc")]
+use crate::weather::{WeatherMarket, WeatherOption};
+use crate::{Call, OptionType, Options, Put};
+
+// Time steps per year of the short-rate tree callable bonds are priced on
+const TREE_STEPS_PER_YEAR: f64 = 24.0;
+// Swing trees take this many steps across the closest pair of exercise dates
+const SWING_STEPS_PER_GAP: f64 = 100.0;
+
+/// Market an instrument is valued against: the discount curve, and the issuer's default risk
+/// for instruments that carry it.
+pub trait PricingContext {
+    fn rate_curve(&self) -> &RateCurve;
+    /// Issuer hazard curve with the recovery rate on default; None leaves credit to each
+    /// instrument's own spread
+    fn credit(&self) -> Option<(&HazardCurve, f64)> {
+        None
+    }
+    /// Quoted volatility of caps, floors and swaptions; None when the context cannot value them
+    fn rate_volatility(&self) -> Option<RateVolatility> {
+        None
+    }
+    /// Short-rate model for bonds with call or put schedules
+    fn short_rate_model(&self) -> Option<HullWhite> {
+        None
+    }
+    /// Spot, volatility and dividend yield of the single underlying of equity-linked notes, as
+    /// a contract whose strike, type, rate and maturity are ignored
+    fn underlying(&self) -> Option<&Options> {
+        None
+    }
+    /// Correlated paths of a basket's underlyings with the engine simulating them, for
+    /// contexts valuing worst-of notes
+    #[cfg(feature = "mc")]
+    fn basket(&self) -> Option<(&MultiAssetSimulator, &MonteCarloEngine)> {
+        None
+    }
+    /// Station weather with the engine simulating it, for contexts valuing weather options
+    #[cfg(feature = "mc")]
+    fn weather(&self) -> Option<(&WeatherMarket, &MonteCarloEngine)> {
+        None
+    }
+}
+
+impl PricingContext for RateCurve {
+    fn rate_curve(&self) -> &RateCurve {
+        self
+    }
+}
+
+/// A discount curve with an optional issuer credit curve, rate option models and underlying.
+#[derive(Debug, Clone)]
+pub struct MarketContext {
+    pub rates: RateCurve,
+    pub credit: Option<(HazardCurve, f64)>,
+    pub rate_volatility: Option<RateVolatility>,
+    pub short_rate: Option<HullWhite>,
+    pub underlying: Option<Options>,
+}
+
+impl MarketContext {
+    pub fn new(rates: RateCurve) -> Self {
+        MarketContext {
+            rates,
+            credit: None,
+            rate_volatility: None,
+            short_rate: None,
+            underlying: None,
+        }
+    }
+    pub fn with_credit(self, hazard: HazardCurve, recovery: f64) -> Self {
+        MarketContext {
+            credit: Some((hazard, recovery)),
+            ..self
+        }
+    }
+    pub fn with_rate_volatility(self, volatility: RateVolatility) -> Self {
+        MarketContext {
+            rate_volatility: Some(volatility),
+            ..self
+        }
+    }
+    pub fn with_short_rate(self, model: HullWhite) -> Self {
+        MarketContext {
+            short_rate: Some(model),
+            ..self
+        }
+    }
+    pub fn with_underlying(self, market: Options) -> Self {
+        MarketContext {
+            underlying: Some(market),
+            ..self
+        }
+    }
+}
+
+impl PricingContext for MarketContext {
+    fn rate_curve(&self) -> &RateCurve {
+        &self.rates
+    }
+    fn credit(&self) -> Option<(&HazardCurve, f64)> {
+        self.credit
+            .as_ref()
+            .map(|(hazard, recovery)| (hazard, *recovery))
+    }
+    fn rate_volatility(&self) -> Option<RateVolatility> {
+        self.rate_volatility
+    }
+    fn short_rate_model(&self) -> Option<HullWhite> {
+        self.short_rate
+    }
+    fn underlying(&self) -> Option<&Options> {
+        self.underlying.as_ref()
+    }
+}
+
+// Error for an instrument valued against a context lacking the market data it needs
+fn missing(instrument: &str, data: &str) -> PricingError {
+    PricingError::InvalidInput(format!("pricing a {} needs a {}", instrument, data))
+}
+
+/// A contract that can be valued against a `PricingContext`.
+pub trait Instrument {
+    /// Amount paid at maturity for a final underlying level; bonds ignore the level
+    fn payoff(&self, underlying: f64) -> f64;
+    /// Years to the last cash flow
+    fn maturity(&self) -> f64;
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError>;
+}
+
+/// Options keep their spot and volatility and take the zero rate to expiry from the context
+impl Instrument for Call {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.time_to_maturity
+    }
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let risk_free_rate = context.rate_curve().rate(self.time_to_maturity);
+        Ok(Call {
+            risk_free_rate,
+            ..*self
+        }
+        .bs_pricing())
+    }
+}
+
+impl Instrument for Put {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.time_to_maturity
+    }
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let risk_free_rate = context.rate_curve().rate(self.time_to_maturity);
+        Ok(Put {
+            risk_free_rate,
+            ..*self
+        }
+        .bs_pricing())
+    }
+}
+
+impl Instrument for Options {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.time_to_maturity()
+    }
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        match self {
+            Options::Call(call) => call.price(context),
+            Options::Put(put) => put.price(context),
+        }
+    }
+}
+
+/// Futures options keep their futures price and volatility and take the zero rate to expiry from
+/// the context, which a futures-style premium then leaves undiscounted
+impl Instrument for FuturesOption {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.expiry
+    }
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let rate = context.rate_curve().rate(self.expiry);
+        Ok(FuturesOption { rate, ..*self }.price())
+    }
+}
+
+/// Inverse options pay, and are valued, in coins
+impl Instrument for InverseOption {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.option.time_to_maturity()
+    }
+    /// The vanilla USD value at the curve rate, converted at spot
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let usd = Instrument::price(&self.option, context)?;
+        Ok(usd_to_coin(usd, self.option.spot_price()))
+    }
+}
+
+impl Instrument for FixedCouponBond {
+    fn payoff(&self, _underlying: f64) -> f64 {
+        self.face_value + self.coupon()
+    }
+    fn maturity(&self) -> f64 {
+        self.maturity
+    }
+    /// Cash flows discounted on the curve, treated as default-free
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let curve = context.rate_curve();
+        Ok(self
+            .cash_flows()
+            .iter()
+            .map(|&(t, amount)| amount * curve.discount_factor(t))
+            .sum())
+    }
+}
+
+impl Instrument for DefaultableBond {
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.bond.payoff(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.bond.maturity
+    }
+    /// Survival-weighted under the context's hazard curve with the bond's own recovery;
+    /// default-free when the context carries no credit
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        match context.credit() {
+            Some((hazard, _)) => Ok(DefaultableBond::price(self, context.rate_curve(), hazard)),
+            None => Instrument::price(&self.bond, context),
+        }
+    }
+}
+
+impl Instrument for ConvertibleBond {
+    /// Redemption at face or conversion into shares, whichever is worth more
+    fn payoff(&self, underlying: f64) -> f64 {
+        (self.face_value / self.conversion_price * underlying).max(self.face_value)
+    }
+    fn maturity(&self) -> f64 {
+        self.maturity
+    }
+    /// Bond floor on the curve, survival-weighted when the context carries credit and otherwise
+    /// at the bond's credit spread over it, plus the conversion option at the curve rate
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let curve = context.rate_curve();
+        let floor = match context.credit() {
+            Some((hazard, recovery)) => {
+                DefaultableBond::new(self.bond(), recovery).price(curve, hazard)
+            }
+            None => self
+                .bond()
+                .cash_flows()
+                .iter()
+                .map(|&(t, amount)| {
+                    amount * curve.discount_factor(t) * exp(-self.credit_spread * t)
+                })
+                .sum(),
+        };
+        let conversion = Call {
+            strike_price: self.conversion_price,
+            spot_price: self.stock_price,
+            volatility: self.volatility,
+            risk_free_rate: self.risk_free_rate,
+            time_to_maturity: self.time_to_maturity,
+            dividend_yield: self.dividend_yield,
+        };
+        Ok(floor + self.face_value / self.conversion_price * conversion.price(context)?)
+    }
+}
+
+impl Instrument for ExoticOptions {
+    fn payoff(&self, underlying: f64) -> f64 {
+        match self {
+            ExoticOptions::ConvertibleBond(bond) => bond.payoff(underlying),
+        }
+    }
+    fn maturity(&self) -> f64 {
+        match self {
+            ExoticOptions::ConvertibleBond(bond) => Instrument::maturity(bond),
+        }
+    }
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        match self {
+            ExoticOptions::ConvertibleBond(bond) => Instrument::price(bond, context),
+        }
+    }
+}
+
+impl Instrument for CallableBond {
+    /// Final redemption when the bond is never called or put
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.bond.payoff(underlying)
+    }
+    fn maturity(&self) -> f64 {
+        self.bond.maturity
+    }
+    /// On a tree of the context's short-rate model fitted to its curve, treated as default-free
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let model = context
+            .short_rate_model()
+            .ok_or_else(|| missing("callable bond", "short-rate model"))?;
+        let maturity = self.bond.maturity;
+        let steps = (maturity * TREE_STEPS_PER_YEAR).ceil().max(1.0) as usize;
+        let tree = model.tree(context.rate_curve(), maturity, steps);
+        Ok(CallableBond::price(self, &tree))
+    }
+}
+
+impl Instrument for CapFloor {
+    /// Last period's payment for a final rate fixing
+    fn payoff(&self, underlying: f64) -> f64 {
+        let phi = match self.kind {
+            CapFloorType::Cap => 1.0,
+            CapFloorType::Floor => -1.0,
+        };
+        self.notional / self.frequency as f64 * (phi * (underlying - self.strike)).max(0.0)
+    }
+    fn maturity(&self) -> f64 {
+        self.maturity
+    }
+    /// Caplets on the context's curve at its quoted rate volatility
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let vol = context
+            .rate_volatility()
+            .ok_or_else(|| missing("cap or floor", "rate volatility"))?;
+        Ok(CapFloor::price(self, context.rate_curve(), vol))
+    }
+}
+
+impl Instrument for Swaption {
+    /// Exercise value for a swap rate fixing at expiry, the annuity left undiscounted
+    fn payoff(&self, underlying: f64) -> f64 {
+        let phi = match self.kind {
+            SwaptionType::Payer => 1.0,
+            SwaptionType::Receiver => -1.0,
+        };
+        self.notional * self.tenor * (phi * (underlying - self.strike)).max(0.0)
+    }
+    fn maturity(&self) -> f64 {
+        self.expiry + self.tenor
+    }
+    /// On the context's curve at its quoted rate volatility
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let vol = context
+            .rate_volatility()
+            .ok_or_else(|| missing("swaption", "rate volatility"))?;
+        Ok(Swaption::price(self, context.rate_curve(), vol))
+    }
+}
+
+impl Instrument for PerpetualAmerican {
+    fn payoff(&self, underlying: f64) -> f64 {
+        match self.option_type {
+            OptionType::Call => (underlying - self.strike).max(0.0),
+            OptionType::Put => (self.strike - underlying).max(0.0),
+        }
+    }
+    /// Never expires
+    fn maturity(&self) -> f64 {
+        f64::INFINITY
+    }
+    /// Keeps its spot and volatility and takes the zero rate at the curve's last pillar, the
+    /// nearest the curve gets to a perpetual rate
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let curve = context.rate_curve();
+        let rate = curve.pillars().last().map_or(0.0, |&(t, _)| curve.rate(t));
+        Ok(PerpetualAmerican { rate, ..*self }.price())
+    }
+}
+
+impl Instrument for StructuredNote {
+    /// Redemption for a final performance S(T) / S(0), assuming knock-out barriers were only
+    /// touched if the underlying finishes beyond them
+    fn payoff(&self, underlying: f64) -> f64 {
+        let legs: f64 = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let unit = match leg.option {
+                    NoteOption::Call { strike } => (underlying - strike).max(0.0),
+                    NoteOption::CappedCall { strike, cap } => {
+                        (underlying.min(cap) - strike).max(0.0)
+                    }
+                    NoteOption::KnockOutCall { strike, barrier } if underlying < barrier => {
+                        (underlying - strike).max(0.0)
+                    }
+                    NoteOption::KnockOutCall { .. } => 0.0,
+                };
+                leg.participation * unit
+            })
+            .sum();
+        self.notional * (self.protection + legs)
+    }
+    fn maturity(&self) -> f64 {
+        self.maturity
+    }
+    /// On the context's underlying at the curve's zero rate to maturity
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let market = context
+            .underlying()
+            .ok_or_else(|| missing("structured note", "underlying market"))?;
+        let rate = context.rate_curve().rate(self.maturity);
+        let market = market.with_market(market.spot_price(), market.volatility(), rate);
+        Ok(StructuredNote::price(self, &market)?.value)
+    }
+}
+
+impl Instrument for SwingOption {
+    /// Value of the last exercise right at a final level
+    fn payoff(&self, underlying: f64) -> f64 {
+        let intrinsic = match self.option_type {
+            OptionType::Call => underlying - self.strike,
+            OptionType::Put => self.strike - underlying,
+        };
+        self.volume * intrinsic.max(0.0)
+    }
+    fn maturity(&self) -> f64 {
+        self.exercise_dates.last().copied().unwrap_or(0.0)
+    }
+    /// On the context's underlying at the curve's zero rate to the last date, its dividend
+    /// yield as the carry, on a tree resolving the closest exercise dates
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let underlying = context
+            .underlying()
+            .ok_or_else(|| missing("swing option", "underlying market"))?;
+        let maturity = Instrument::maturity(self);
+        let market = MarketQuote {
+            spot: underlying.spot_price(),
+            volatility: underlying.volatility(),
+            risk_free_rate: context.rate_curve().rate(maturity),
+        };
+        let closest = core::iter::once(0.0)
+            .chain(self.exercise_dates.iter().copied())
+            .collect::<Vec<f64>>()
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .fold(f64::INFINITY, f64::min);
+        // badly ordered dates are rejected by the tree itself
+        let steps = if closest > 0.0 && closest.is_finite() {
+            (maturity / closest * SWING_STEPS_PER_GAP).round() as usize
+        } else {
+            1
+        };
+        let carry = underlying.dividend_yield().unwrap_or(0.0);
+        SwingOption::price(self, &market, carry, steps)
+    }
+}
+
+#[cfg(feature = "mc")]
+impl Instrument for Autocallable {
+    /// Final payment for a final worst performance, the note having never called or paid a
+    /// coupon before
+    fn payoff(&self, underlying: f64) -> f64 {
+        let mut worst = vec![0.0; self.observations.len()];
+        if let Some(last) = worst.last_mut() {
+            *last = underlying;
+        }
+        self.cash_flows(&worst)
+            .last()
+            .filter(|&&(t, _)| t == Autocallable::maturity(self))
+            .map_or(0.0, |&(_, amount)| amount)
+    }
+    fn maturity(&self) -> f64 {
+        Autocallable::maturity(self)
+    }
+    /// On the context's basket simulation, discounted at the curve's zero rate to maturity
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let (simulator, engine) = context
+            .basket()
+            .ok_or_else(|| missing("autocallable", "basket simulation"))?;
+        let rate = context.rate_curve().rate(Autocallable::maturity(self));
+        Ok(Autocallable::price(self, simulator, engine, rate)?.price)
+    }
+}
+
+#[cfg(feature = "mc")]
+impl Instrument for WeatherOption {
+    /// Payout for the season's accumulated degree days
+    fn payoff(&self, underlying: f64) -> f64 {
+        self.payout(underlying)
+    }
+    /// End of the season, zero for a contract without one
+    fn maturity(&self) -> f64 {
+        self.season_days
+            .map_or(0.0, |days| days as f64 / CALENDAR_DAYS_PER_YEAR)
+    }
+    /// Simulated on the context's temperature model, discounted at the curve's zero rate to the
+    /// season's end. Fails for a contract without a season
+    fn price(&self, context: &dyn PricingContext) -> Result<f64, PricingError> {
+        let days = self.season_days.ok_or_else(|| {
+            PricingError::InvalidInput("a weather option needs a season to be priced".to_string())
+        })?;
+        let (weather, engine) = context
+            .weather()
+            .ok_or_else(|| missing("weather option", "temperature model"))?;
+        let rate = context.rate_curve().rate(Instrument::maturity(self));
+        Ok(weather
+            .model
+            .price(self, engine, weather.today, weather.anomaly, days, rate)
+            .price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::futures::PremiumStyle;
+
+    #[test]
+    fn test_heterogeneous_book() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let bond = FixedCouponBond::new(100.0, 0.04, 5.0, 2);
+        let convertible = ConvertibleBond {
+            face_value: 1000.0,
+            coupon_rate: 0.03,
+            maturity: 5.0,
+            payment_frequency: 2,
+            credit_spread: 0.02,
+            conversion_price: 50.0,
+            stock_price: 45.0,
+            volatility: 0.3,
+            time_to_maturity: 5.0,
+            risk_free_rate: 0.05,
+            dividend_yield: None,
+        };
+        let book: Vec<Box<dyn Instrument>> = vec![
+            Box::new(call),
+            Box::new(bond),
+            Box::new(ExoticOptions::ConvertibleBond(convertible)),
+        ];
+        // on a flat curve each matches its existing pricer, the bond at the equivalent
+        // semiannual yield
+        let flat = RateCurve::flat(0.05);
+        let semiannual = 2.0 * ((0.025f64).exp() - 1.0);
+        assert!((book[0].price(&flat).unwrap() - call.bs_pricing()).abs() < 1e-12);
+        assert!((book[1].price(&flat).unwrap() - bond.price(semiannual)).abs() < 1e-9);
+        assert_eq!(book[2].payoff(60.0), 1200.0);
+        assert!(book.iter().all(|instrument| instrument.maturity() > 0.0));
+
+        // issuer credit in the context replaces the convertible's own spread
+        let risky = MarketContext::new(flat.clone()).with_credit(HazardCurve::flat(0.03), 0.4);
+        let credit = convertible.credit_pricing(&HazardCurve::flat(0.03), 0.4);
+        assert!((book[2].price(&risky).unwrap() - credit).abs() < 1e-9);
+        // the default-free bond ignores it
+        assert_eq!(
+            book[1].price(&risky).unwrap(),
+            book[1].price(&flat).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rate_products() {
+        let curve = RateCurve::new(vec![(1.0, 0.03), (10.0, 0.04)]);
+        let black = RateVolatility::Black(0.2);
+        let model = HullWhite::new(0.05, 0.012);
+        let market = MarketContext::new(curve.clone())
+            .with_rate_volatility(black)
+            .with_short_rate(model);
+        let bond = FixedCouponBond::new(100.0, 0.05, 10.0, 2);
+        let callable = (3..10).fold(CallableBond::new(bond), |b, t| b.with_call(t as f64, 100.0));
+        let cap = CapFloor::cap(0.035, 5.0, 4).with_notional(1e6);
+        let payer = Swaption::new(SwaptionType::Payer, 2.0, 5.0, 0.04, 2).with_notional(1e7);
+        let book: Vec<Box<dyn Instrument>> =
+            vec![Box::new(callable.clone()), Box::new(cap), Box::new(payer)];
+
+        let tree = model.tree(&curve, 10.0, 240);
+        assert!((book[0].price(&market).unwrap() - callable.price(&tree)).abs() < 1e-12);
+        assert_eq!(book[1].price(&market).unwrap(), cap.price(&curve, black));
+        assert_eq!(book[2].price(&market).unwrap(), payer.price(&curve, black));
+        assert!((book[1].payoff(0.045) - 1e6 / 4.0 * 0.01).abs() < 1e-9);
+        assert_eq!(book[2].payoff(0.03), 0.0);
+        assert_eq!(book[2].maturity(), 7.0);
+        // a bare curve has no model for any of them
+        assert!(
+            book.iter()
+                .all(|instrument| instrument.price(&curve).is_err())
+        );
+    }
+
+    #[cfg(feature = "mc")]
+    #[test]
+    fn test_autocallable_on_a_basket() {
+        use crate::mc::McConfig;
+        use crate::multi_asset::Decomposition;
+        use crate::processes::{GeometricBrownianMotion, StochasticProcess};
+
+        // a desk's market carrying the simulation of its basket
+        struct BasketMarket {
+            curve: RateCurve,
+            simulator: MultiAssetSimulator,
+            engine: MonteCarloEngine,
+        }
+        impl PricingContext for BasketMarket {
+            fn rate_curve(&self) -> &RateCurve {
+                &self.curve
+            }
+            fn basket(&self) -> Option<(&MultiAssetSimulator, &MonteCarloEngine)> {
+                Some((&self.simulator, &self.engine))
+            }
+        }
+        let gbm = || -> Box<dyn StochasticProcess> {
+            Box::new(GeometricBrownianMotion {
+                spot: 100.0,
+                drift: 0.03,
+                volatility: 0.25,
+            })
+        };
+        let correlation = vec![vec![1.0, 0.5], vec![0.5, 1.0]];
+        let market = BasketMarket {
+            curve: RateCurve::flat(0.03),
+            simulator: MultiAssetSimulator::new(
+                vec![gbm(), gbm()],
+                &correlation,
+                Decomposition::Cholesky,
+            )
+            .unwrap(),
+            engine: MonteCarloEngine::new(McConfig::new(4_000, 4, 7)),
+        };
+        let note = Autocallable::new(100.0, vec![0.5, 1.0, 1.5, 2.0], 0.04)
+            .with_coupon_barrier(0.7, true)
+            .with_knock_in(0.6, 1.0);
+        let direct = note
+            .price(&market.simulator, &market.engine, 0.03)
+            .unwrap()
+            .price;
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(note)];
+        assert_eq!(book[0].price(&market).unwrap(), direct);
+        assert_eq!(book[0].maturity(), 2.0);
+        // all missed coupons caught up at maturity, then knocked in below 60%
+        assert_eq!(book[0].payoff(0.95), 116.0);
+        assert_eq!(book[0].payoff(0.5), 50.0);
+        assert!(book[0].price(&RateCurve::flat(0.03)).is_err());
+    }
+
+    #[test]
+    fn test_perpetual_takes_the_long_rate() {
+        let put = Options::new_put(100.0, 100.0, 0.25, 0.0, 1.0, None).perpetual_american();
+        let curve = RateCurve::new(vec![(1.0, 0.02), (30.0, 0.05)]);
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(put)];
+        let expected = PerpetualAmerican { rate: 0.05, ..put }.price();
+        assert_eq!(book[0].price(&curve).unwrap(), expected);
+        assert_eq!(book[0].maturity(), f64::INFINITY);
+        assert_eq!(book[0].payoff(80.0), 20.0);
+        assert_eq!(book[0].payoff(120.0), 0.0);
+    }
+
+    #[test]
+    fn test_futures_option_takes_the_curve_rate() {
+        let curve = RateCurve::new(vec![(0.25, 0.02), (1.0, 0.04)]);
+        let option = FuturesOption::new(OptionType::Put, 80.0, 75.0, 0.3, 0.0, 0.5);
+        let margined = option.with_premium(PremiumStyle::FuturesStyle);
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(option), Box::new(margined)];
+        let rate = curve.rate(0.5);
+        let upfront = FuturesOption { rate, ..option }.price();
+        assert_eq!(book[0].price(&curve).unwrap(), upfront);
+        // nothing is financed futures-style, so the curve drops out
+        assert_eq!(book[1].price(&curve).unwrap(), margined.price());
+        assert!((book[1].price(&curve).unwrap() * (-rate * 0.5).exp() - upfront).abs() < 1e-12);
+        assert_eq!(book[0].maturity(), 0.5);
+        assert_eq!(book[0].payoff(70.0), 10.0);
+        assert_eq!(book[0].payoff(90.0), 0.0);
+    }
+
+    #[test]
+    fn test_inverse_option_prices_in_coins() {
+        let curve = RateCurve::new(vec![(0.1, 0.03), (1.0, 0.05)]);
+        let vanilla = Options::new_call(60_000.0, 55_000.0, 0.6, 0.0, 0.25, Some(0.01));
+        let inverse = InverseOption::new(vanilla);
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(inverse)];
+        let expected = InverseOption::new(vanilla.with_market(55_000.0, 0.6, curve.rate(0.25)));
+        assert_eq!(book[0].price(&curve).unwrap(), expected.price());
+        assert_eq!(book[0].maturity(), 0.25);
+        assert!((book[0].payoff(75_000.0) - 0.2).abs() < 1e-15);
+        assert_eq!(book[0].payoff(50_000.0), 0.0);
+    }
+
+    #[test]
+    fn test_structured_note_on_the_underlying() {
+        let note = StructuredNote::new(1000.0, 2.0)
+            .with_protection(0.9)
+            .with_capped_call(1.0, 1.3, 0.8);
+        let curve = RateCurve::new(vec![(1.0, 0.02), (3.0, 0.04)]);
+        let underlying = Options::new_call(50.0, 100.0, 0.2, 0.0, 0.5, Some(0.01));
+        let market = MarketContext::new(curve.clone()).with_underlying(underlying);
+        let direct = note
+            .price(&underlying.with_market(100.0, 0.2, curve.rate(2.0)))
+            .unwrap()
+            .value;
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(note)];
+        assert_eq!(book[0].price(&market).unwrap(), direct);
+        assert!(book[0].price(&curve).is_err());
+        assert_eq!(book[0].maturity(), 2.0);
+        assert_eq!(book[0].payoff(0.7), 900.0);
+        assert!((book[0].payoff(1.5) - 1140.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_swing_on_the_underlying() {
+        let swing = SwingOption {
+            option_type: OptionType::Call,
+            strike: 32.0,
+            exercise_dates: (1..=12).map(|m| m as f64 / 12.0).collect(),
+            volume: 10.0,
+            min_volume: 20.0,
+            max_volume: 60.0,
+        };
+        let curve = RateCurve::new(vec![(0.5, 0.02), (1.0, 0.03)]);
+        let underlying = Options::new_call(30.0, 30.0, 0.5, 0.0, 1.0, Some(0.01));
+        let market = MarketContext::new(curve).with_underlying(underlying);
+        let quote = MarketQuote {
+            spot: 30.0,
+            volatility: 0.5,
+            risk_free_rate: 0.03,
+        };
+        let direct = swing.price(&quote, 0.01, 1200).unwrap();
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(swing)];
+        assert!((book[0].price(&market).unwrap() - direct).abs() < 1e-9);
+        assert!(book[0].price(&RateCurve::flat(0.03)).is_err());
+        assert_eq!(book[0].maturity(), 1.0);
+        assert_eq!(book[0].payoff(35.0), 30.0);
+        assert_eq!(book[0].payoff(30.0), 0.0);
+    }
+
+    #[cfg(feature = "mc")]
+    #[test]
+    fn test_weather_option_on_a_station() {
+        use crate::mc::McConfig;
+        use crate::weather::{DegreeDayIndex, TemperatureModel};
+
+        struct Station {
+            curve: RateCurve,
+            weather: WeatherMarket,
+            engine: MonteCarloEngine,
+        }
+        impl PricingContext for Station {
+            fn rate_curve(&self) -> &RateCurve {
+                &self.curve
+            }
+            fn weather(&self) -> Option<(&WeatherMarket, &MonteCarloEngine)> {
+                Some((&self.weather, &self.engine))
+            }
+        }
+        let station = Station {
+            curve: RateCurve::flat(0.04),
+            weather: WeatherMarket {
+                model: TemperatureModel {
+                    level: 55.0,
+                    trend: 0.0,
+                    sine: 5.0,
+                    cosine: -20.0,
+                    kappa: 60.0,
+                    sigma: 60.0,
+                },
+                today: 364,
+                anomaly: -2.0,
+            },
+            engine: MonteCarloEngine::new(McConfig::new(2_000, 1, 11)),
+        };
+        let hdd = WeatherOption::new(DegreeDayIndex::Heating, 65.0, OptionType::Call, 900.0, 1.0);
+        let direct = station
+            .weather
+            .model
+            .price(&hdd, &station.engine, 364, -2.0, 31, 0.04)
+            .price;
+        let book: Vec<Box<dyn Instrument>> = vec![Box::new(hdd.with_season(31)), Box::new(hdd)];
+        assert_eq!(book[0].price(&station).unwrap(), direct);
+        assert_eq!(book[0].maturity(), 31.0 / 365.0);
+        assert_eq!(book[0].payoff(950.0), 50.0);
+        assert!(book[0].price(&RateCurve::flat(0.04)).is_err());
+        assert!(book[1].price(&station).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! CSV exchange of contracts and results.
+//!
+//! Input columns, one contract per row, header required:
+//!
+//! | column             | meaning                                   |
+//! |--------------------|-------------------------------------------|
+//! | `underlying`       | underlying identifier                     |
+//! | `type`             | `call` or `put`                           |
+//! | `strike`           | strike price                              |
+//! | `spot`             | spot price of the underlying              |
+//! | `volatility`       | annualized volatility, e.g. `0.2`         |
+//! | `rate`             | continuously compounded risk-free rate    |
+//! | `expiry`           | time to maturity in years                 |
+//! | `dividend_yield`   | optional, empty for none                  |
+//! | `quantity`         | optional signed contract count, default 1 |
+//! | `multiplier`       | optional shares per contract, default 1   |
+//!
+//! Output rows repeat the input columns and append `price`, `value`, `delta`, `gamma`, `vega`,
+//! `theta` and `rho`, with Greeks per contract.
+use crate::portfolio::{Portfolio, Position};
+use crate::{OptionType, Options};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{Read, Write};
+
+fn default_quantity() -> f64 {
+    1.0
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+/// One input row of a contracts file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractRow {
+    pub underlying: String,
+    #[serde(rename = "type")]
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    #[serde(rename = "rate")]
+    pub risk_free_rate: f64,
+    #[serde(rename = "expiry")]
+    pub time_to_maturity: f64,
+    #[serde(default)]
+    pub dividend_yield: Option<f64>,
+    #[serde(default = "default_quantity")]
+    pub quantity: f64,
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+}
+
+impl ContractRow {
+    pub fn to_position(&self) -> Position {
+        let option = Options::new_with_type(
+            self.option_type,
+            self.strike,
+            self.spot,
+            self.volatility,
+            self.risk_free_rate,
+            self.time_to_maturity,
+            self.dividend_yield,
+        );
+        Position::new(&self.underlying, option, self.quantity).with_multiplier(self.multiplier)
+    }
+    fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("strike", self.strike),
+            ("spot", self.spot),
+            ("volatility", self.volatility),
+            ("expiry", self.time_to_maturity),
+            ("multiplier", self.multiplier),
+        ];
+        for (column, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("{} must be positive, got {}", column, value));
+            }
+        }
+        if !self.risk_free_rate.is_finite() || !self.quantity.is_finite() {
+            return Err("rate and quantity must be finite".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// One output row: the contract followed by its price, position value and Greeks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultRow {
+    pub underlying: String,
+    #[serde(rename = "type")]
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    #[serde(rename = "rate")]
+    pub risk_free_rate: f64,
+    #[serde(rename = "expiry")]
+    pub time_to_maturity: f64,
+    pub dividend_yield: Option<f64>,
+    pub quantity: f64,
+    pub multiplier: f64,
+    pub price: f64,
+    pub value: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+impl ResultRow {
+    pub fn of(position: &Position) -> Self {
+        let option = &position.option;
+        let price = option.bs_pricing();
+        ResultRow {
+            underlying: position.underlying.clone(),
+            option_type: option.option_type(),
+            strike: option.strike_price(),
+            spot: option.spot_price(),
+            volatility: option.volatility(),
+            risk_free_rate: option.risk_free_rate(),
+            time_to_maturity: option.time_to_maturity(),
+            dividend_yield: option.dividend_yield(),
+            quantity: position.quantity,
+            multiplier: position.multiplier,
+            price,
+            value: position.units() * price,
+            delta: option.delta(),
+            gamma: option.gamma(),
+            vega: option.vega(),
+            theta: option.theta(),
+            rho: option.rho(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IoError {
+    Csv(csv::Error),
+    Json(serde_json::Error),
+    File(std::io::Error),
+    /// A row parsed but describes an invalid contract; lines count the header as line 1
+    InvalidRow {
+        line: u64,
+        reason: String,
+    },
+    /// The input parsed but its contents are unusable
+    Invalid(String),
+    /// A document parsed but breaks a rule of its schema at the field `path`
+    Schema {
+        path: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Csv(err) => write!(f, "csv error: {}", err),
+            IoError::Json(err) => write!(f, "json error: {}", err),
+            IoError::File(err) => write!(f, "file error: {}", err),
+            IoError::InvalidRow { line, reason } => write!(f, "line {}: {}", line, reason),
+            IoError::Invalid(reason) => f.write_str(reason),
+            IoError::Schema { path, reason } => write!(f, "{}: {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for IoError {}
+
+impl From<csv::Error> for IoError {
+    fn from(err: csv::Error) -> Self {
+        IoError::Csv(err)
+    }
+}
+
+impl From<serde_json::Error> for IoError {
+    fn from(err: serde_json::Error) -> Self {
+        IoError::Json(err)
+    }
+}
+
+impl From<std::io::Error> for IoError {
+    fn from(err: std::io::Error) -> Self {
+        IoError::File(err)
+    }
+}
+
+/// Reads and validates every contract row
+pub fn read_contracts<R: Read>(reader: R) -> Result<Vec<ContractRow>, IoError> {
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = csv.headers()?.clone();
+    let mut rows = Vec::new();
+    for record in csv.records() {
+        let record = record?;
+        let row: ContractRow = record.deserialize(Some(&headers))?;
+        if let Err(reason) = row.validate() {
+            let line = record.position().map_or(0, |position| position.line());
+            return Err(IoError::InvalidRow { line, reason });
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+pub fn read_portfolio<R: Read>(reader: R) -> Result<Portfolio, IoError> {
+    Ok(read_contracts(reader)?
+        .iter()
+        .map(ContractRow::to_position)
+        .collect())
+}
+
+/// Writes one priced row per position, in portfolio order
+pub fn write_results<W: Write>(portfolio: &Portfolio, writer: W) -> Result<(), IoError> {
+    let mut csv = csv::Writer::from_writer(writer);
+    for position in portfolio.positions() {
+        csv.serialize(ResultRow::of(position))?;
+    }
+    csv.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let input = "underlying,type,strike,spot,volatility,rate,expiry,dividend_yield,quantity
+SPX, call ,100,105,0.2,0.05,1,,10
+AAPL,put,100,95,0.2,0.05,1,0.02,-5
+";
+        let portfolio = read_portfolio(input.as_bytes()).unwrap();
+        assert_eq!(portfolio.positions().len(), 2);
+        let mut out = Vec::new();
+        write_results(&portfolio, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with(
+            "underlying,type,strike,spot,volatility,rate,expiry,dividend_yield,quantity,multiplier,"
+        ));
+        let rows: Vec<ResultRow> = csv::Reader::from_reader(text.as_bytes())
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!((rows[0].price - 13.8579).abs() < 1e-4);
+        assert!((rows[0].value - 138.579).abs() < 1e-3);
+        assert_eq!(rows[1].dividend_yield, Some(0.02));
+
+        // quantity column is optional
+        let minimal =
+            "underlying,type,strike,spot,volatility,rate,expiry\nSPX,put,100,100,0.2,0.05,1\n";
+        assert_eq!(read_contracts(minimal.as_bytes()).unwrap()[0].quantity, 1.0);
+        // listed equity options carry 100 shares per contract
+        let listed = "underlying,type,strike,spot,volatility,rate,expiry,quantity,multiplier
+SPX,put,100,100,0.2,0.05,1,2,100
+";
+        let portfolio = read_portfolio(listed.as_bytes()).unwrap();
+        let put = &portfolio.positions()[0];
+        assert!((portfolio.value() - 200.0 * put.option.bs_pricing()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_rows() {
+        let input = "underlying,type,strike,spot,volatility,rate,expiry
+SPX,call,100,105,0.2,0.05,1
+SPX,call,100,105,-0.2,0.05,1
+";
+        match read_contracts(input.as_bytes()) {
+            Err(IoError::InvalidRow { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected invalid row, got {:?}", other),
+        }
+        let bad_type =
+            "underlying,type,strike,spot,volatility,rate,expiry\nSPX,straddle,100,105,0.2,0.05,1\n";
+        assert!(matches!(
+            read_contracts(bad_type.as_bytes()),
+            Err(IoError::Csv(_))
+        ));
+    }
+}
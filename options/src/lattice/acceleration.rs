@@ -0,0 +1,105 @@
+// Convergence acceleration for tree prices. Engines here wrap a lattice and cancel its leading
+// discretization error, by extrapolating across step counts or by smoothing the terminal payoff,
+// so a few hundred steps reach the accuracy of thousands.
+use super::binomial::BinomialTree;
+use super::{ExerciseStyle, LatticeEngine};
+use crate::Options;
+
+/// Engine pricing on `n` and `2n` steps of the wrapped lattice and extrapolating the
+/// first-order error away.
+///
+/// Formula: V ≈ 2 V(2n) - V(n)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Richardson<E> {
+    pub engine: E,
+}
+
+impl<E: LatticeEngine> Richardson<E> {
+    pub fn new(engine: E) -> Self {
+        Richardson { engine }
+    }
+}
+
+impl<E: LatticeEngine> LatticeEngine for Richardson<E> {
+    fn steps(&self) -> usize {
+        self.engine.steps()
+    }
+    fn with_steps(&self, steps: usize) -> Self {
+        Richardson::new(self.engine.with_steps(steps))
+    }
+    fn price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        self.engine.extrapolated_price(option, style)
+    }
+}
+
+/// Binomial tree valuing its last step in closed form (Broadie-Detemple's BBS). Wrapped in
+/// [`Richardson`] it is the BBSR method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothedBinomialTree {
+    pub steps: usize,
+}
+
+impl SmoothedBinomialTree {
+    pub fn new(steps: usize) -> Self {
+        SmoothedBinomialTree {
+            steps: steps.max(1),
+        }
+    }
+}
+
+impl LatticeEngine for SmoothedBinomialTree {
+    fn steps(&self) -> usize {
+        self.steps
+    }
+    fn with_steps(&self, steps: usize) -> Self {
+        SmoothedBinomialTree::new(steps)
+    }
+    fn price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        BinomialTree::new(self.steps).smoothed_price(option, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // American put at S = K = 100, σ = 20%, r = 5%, one year, from a 20 000 step BBSR tree
+    const AMERICAN_PUT: f64 = 6.090372;
+
+    #[test]
+    fn test_smoothed_tree_error_decays_smoothly() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let error = |engine: &dyn Fn(usize) -> f64, n: usize| (engine(n) - AMERICAN_PUT).abs();
+        let crr = |n: usize| BinomialTree::new(n).price(&put, ExerciseStyle::American);
+        let bbs = |n: usize| SmoothedBinomialTree::new(n).price(&put, ExerciseStyle::American);
+        // first order without oscillation: doubling the steps roughly halves the error
+        for n in [50, 100, 200] {
+            let ratio = error(&bbs, n) / error(&bbs, 2 * n);
+            assert!(ratio > 1.4 && ratio < 2.6, "{} steps: {}", n, ratio);
+        }
+        // the plain tree oscillates, gaining little from 200 to 400 steps
+        assert!(error(&crr, 400) > 0.8 * error(&crr, 200));
+        // so BBSR on 200 steps beats the plain tree on 800
+        let bbsr = |n: usize| {
+            Richardson::new(SmoothedBinomialTree::new(n)).price(&put, ExerciseStyle::American)
+        };
+        assert!(error(&bbsr, 200) < 5e-4);
+        assert!(error(&bbsr, 200) < 0.2 * error(&crr, 800));
+    }
+
+    #[test]
+    fn test_richardson_wraps_any_lattice() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let exact = call.bs_pricing();
+        let bbsr = Richardson::new(SmoothedBinomialTree::new(50));
+        let crr = BinomialTree::new(50);
+        let bbsr_error = (bbsr.price(&call, ExerciseStyle::European) - exact).abs();
+        let crr_error = (crr.price(&call, ExerciseStyle::European) - exact).abs();
+        assert!(bbsr_error < 1e-4 && crr_error > 50.0 * bbsr_error);
+        assert_eq!(bbsr.with_steps(100).steps(), 100);
+        // the wrapper is itself a lattice engine, so control variates compose with it
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let american = Richardson::new(SmoothedBinomialTree::new(100)).american_price(&put);
+        assert!((american - AMERICAN_PUT).abs() < 1e-3);
+    }
+}
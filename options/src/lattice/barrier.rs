@@ -0,0 +1,151 @@
+use super::{ExerciseStyle, LatticeEngine};
+use crate::Options;
+use crate::error::PricingError;
+use alloc::format;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Side of spot the barrier sits on and whether touching it kills or activates the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum BarrierType {
+    UpAndOut,
+    DownAndOut,
+    UpAndIn,
+    DownAndIn,
+}
+
+impl BarrierType {
+    pub fn is_up(&self) -> bool {
+        matches!(self, BarrierType::UpAndOut | BarrierType::UpAndIn)
+    }
+    pub fn is_knock_out(&self) -> bool {
+        matches!(self, BarrierType::UpAndOut | BarrierType::DownAndOut)
+    }
+}
+
+/// A barrier monitored at every step of the tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Barrier {
+    pub kind: BarrierType,
+    pub level: f64,
+}
+
+impl Barrier {
+    pub fn new(kind: BarrierType, level: f64) -> Self {
+        Barrier { kind, level }
+    }
+    /// Whether `spot` is at or beyond the barrier
+    pub fn is_breached(&self, spot: f64) -> bool {
+        if self.kind.is_up() {
+            spot >= self.level
+        } else {
+            spot <= self.level
+        }
+    }
+}
+
+/// A lattice that prices contracts extinguished or activated by a barrier.
+pub trait BarrierEngine: LatticeEngine {
+    /// Value of the option knocked out the first time a node reaches the barrier, with the knock
+    /// direction taken from `barrier` and spot on the live side
+    fn knock_out_price(&self, option: &Options, barrier: &Barrier, style: ExerciseStyle) -> f64;
+    /// Value of a knock-out or knock-in option.
+    ///
+    /// Knock-ins come from in-out parity, so they are only priced European; spot already
+    /// beyond the barrier settles the contract as dead or as the plain option
+    fn barrier_price(
+        &self,
+        option: &Options,
+        barrier: &Barrier,
+        style: ExerciseStyle,
+    ) -> Result<f64, PricingError> {
+        if !(barrier.level.is_finite() && barrier.level > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "barrier level {} is not positive",
+                barrier.level
+            )));
+        }
+        let knock_out = barrier.kind.is_knock_out();
+        if !knock_out && style == ExerciseStyle::American {
+            return Err(PricingError::InvalidInput(format!(
+                "{:?} barriers are only priced European",
+                barrier.kind
+            )));
+        }
+        if barrier.is_breached(option.spot_price()) {
+            return Ok(if knock_out {
+                0.0
+            } else {
+                self.price(option, style)
+            });
+        }
+        let out = self.knock_out_price(option, barrier, style);
+        Ok(if knock_out {
+            out
+        } else {
+            // Formula: V_in = V - V_out
+            self.price(option, style) - out
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::binomial::BinomialTree;
+    use crate::lattice::trinomial::TrinomialTree;
+
+    // Reiner-Rubinstein down-and-out call with the barrier below the strike and no dividends
+    fn down_and_out_call(option: &Options, level: f64) -> f64 {
+        let (spot, sigma) = (option.spot_price(), option.volatility());
+        let lambda = (option.risk_free_rate() + 0.5 * sigma * sigma) / (sigma * sigma);
+        let image = option.with_market(level * level / spot, sigma, option.risk_free_rate());
+        option.bs_pricing() - (level / spot).powf(2.0 * lambda - 2.0) * image.bs_pricing()
+    }
+
+    #[test]
+    fn test_barrier_trees_converge_smoothly() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let barrier = Barrier::new(BarrierType::DownAndOut, 90.0);
+        let exact = down_and_out_call(&call, 90.0);
+        let european = ExerciseStyle::European;
+        let spread = |prices: &[f64]| {
+            let max = prices.iter().cloned().fold(f64::MIN, f64::max);
+            max - prices.iter().cloned().fold(f64::MAX, f64::min)
+        };
+        let steps = [100, 110, 120, 130, 140, 150];
+        let trinomial = steps.map(|n| {
+            let tree = TrinomialTree::new(n);
+            tree.barrier_price(&call, &barrier, european).unwrap()
+        });
+        let binomial =
+            steps.map(|n| BinomialTree::new(n).knock_out_price(&call, &barrier, european));
+        // aligned levels leave the trinomial tree almost exact, and the Derman-Kani interpolation
+        // keeps the binomial one from jumping as levels cross the barrier
+        for price in trinomial {
+            assert!((price - exact).abs() < 0.005, "{} vs {}", price, exact);
+        }
+        for price in binomial {
+            assert!((price - exact).abs() < 0.03, "{} vs {}", price, exact);
+        }
+        assert!(spread(&trinomial) < 0.005 && spread(&binomial) < 0.02);
+
+        // knock-in plus knock-out is the plain option, and spot past the barrier is already dead
+        let knock_in = Barrier::new(BarrierType::DownAndIn, 90.0);
+        let tree = TrinomialTree::new(200);
+        let total = tree.barrier_price(&call, &knock_in, european).unwrap()
+            + tree.barrier_price(&call, &barrier, european).unwrap();
+        assert!((total - tree.price(&call, european)).abs() < 1e-9);
+        let knocked = Barrier::new(BarrierType::UpAndOut, 95.0);
+        assert_eq!(tree.barrier_price(&call, &knocked, european), Ok(0.0));
+        assert!(
+            tree.barrier_price(&call, &knock_in, ExerciseStyle::American)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use super::barrier::{Barrier, BarrierEngine};
+use super::dividends::{DiscreteDividends, DividendEngine, DividendModel, pay_dividend};
+use super::{ExerciseStyle, LatticeEngine, exercise_edge};
+use crate::Options;
+use crate::error::PricingError;
+use crate::math::float::{exp, powi, sqrt};
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Number of time steps used when none is given
+pub const DEFAULT_STEPS: usize = 200;
+
+// Volatility bracket searched by the American implied volatility solver
+const MIN_IMPLIED_VOL: f64 = 1e-4;
+const MAX_IMPLIED_VOL: f64 = 5.0;
+
+/// Cox-Ross-Rubinstein tree with the up and down moves centred on the forward drift.
+///
+/// Centring keeps the branching probability inside (0, 1) for any volatility, which the
+/// implied volatility solver relies on at the low end of its bracket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinomialTree {
+    pub steps: usize,
+}
+
+impl Default for BinomialTree {
+    fn default() -> Self {
+        BinomialTree::new(DEFAULT_STEPS)
+    }
+}
+
+impl BinomialTree {
+    pub fn new(steps: usize) -> Self {
+        BinomialTree {
+            steps: steps.max(1),
+        }
+    }
+    /// Tree with the step count of `numerics`
+    pub fn from_config(numerics: &NumericsConfig) -> Self {
+        BinomialTree::new(numerics.tree_steps)
+    }
+    /// Raw tree value of the option for the given exercise style
+    pub fn price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        self.roll_back(
+            option,
+            &|s| option.payout(s),
+            style,
+            None,
+            None,
+            false,
+            None,
+        )
+    }
+    /// Tree value with the last step replaced by the Black-Scholes value of the European over
+    /// it (Broadie-Detemple), exercised where intrinsic is worth more. Smoothing the kinked
+    /// terminal payoff removes the odd-even oscillation, so the error decays smoothly in the
+    /// step count and extrapolates well
+    pub fn smoothed_price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        self.roll_back(option, &|s| option.payout(s), style, None, None, true, None)
+    }
+    /// American tree value with the early-exercise boundary: (time from today, critical spot)
+    /// at every step where some node is exercised, in increasing time. Puts are exercised at or
+    /// below the critical spot and calls at or above it
+    pub fn price_with_boundary(&self, option: &Options) -> (f64, Vec<(f64, f64)>) {
+        let mut boundary = Vec::new();
+        let price = self.roll_back(
+            option,
+            &|s| option.payout(s),
+            ExerciseStyle::American,
+            None,
+            None,
+            false,
+            Some(&mut boundary),
+        );
+        (price, boundary)
+    }
+    /// Tree value of any exercise value on the market of `option`
+    pub(crate) fn roll_back_payout(
+        &self,
+        option: &Options,
+        payout: &dyn Fn(f64) -> f64,
+        style: ExerciseStyle,
+    ) -> f64 {
+        self.roll_back(option, payout, style, None, None, false, None)
+    }
+    // Backward induction, knocking out nodes at or beyond `barrier`. The node just inside the
+    // barrier is re-interpolated between zero at the true barrier and the next node in
+    // (Derman-Kani), so the effective barrier no longer jumps between lattice levels.
+    // Escrowed dividends shift the tree to net spot; spot-adjusted ones are paid on their step.
+    // `option` supplies the market and `payout` the exercise value; `smoothed` values the final
+    // step in closed form, which only holds for the vanilla payout of `option`. `boundary`
+    // collects each step's critical spot
+    #[allow(clippy::too_many_arguments)]
+    fn roll_back(
+        &self,
+        option: &Options,
+        payout: &dyn Fn(f64) -> f64,
+        style: ExerciseStyle,
+        barrier: Option<&Barrier>,
+        dividends: Option<&DiscreteDividends>,
+        smoothed: bool,
+        mut boundary: Option<&mut Vec<(f64, f64)>>,
+    ) -> f64 {
+        let n = self.steps;
+        let t = option.time_to_maturity();
+        if t <= 0.0 {
+            return payout(option.spot_price());
+        }
+        let dt = t / n as f64;
+        let r = option.risk_free_rate();
+        let q = option.dividend_yield().unwrap_or(0.0);
+        let jump = option.volatility() * sqrt(dt);
+        let drift = (r - q) * dt;
+        let up = exp(drift + jump);
+        let down = exp(drift - jump);
+        let p = (exp(drift) - down) / (up - down);
+        let discount = exp(-r * dt);
+        let escrow = |step: usize| match dividends {
+            Some(d) if d.model == DividendModel::Escrowed => {
+                d.present_value(r, step as f64 * dt, t)
+            }
+            _ => 0.0,
+        };
+        let cash = dividends
+            .filter(|d| d.model == DividendModel::SpotAdjusted)
+            .map(|d| d.per_step(t, n));
+        let spot = option.spot_price() - escrow(0);
+        let node =
+            |step: usize, j: usize| spot * powi(up, j as i32) * powi(down, (step - j) as i32);
+        let pay = |values: &mut [f64], step: usize| {
+            if let Some(amount) = cash.as_ref().map(|c| c[step]).filter(|&a| a > 0.0) {
+                let spots: Vec<f64> = (0..=step).map(|j| node(step, j)).collect();
+                pay_dividend(values, &spots, amount, option, style);
+            }
+        };
+
+        // terminal node j has j up moves
+        let last = if smoothed { n - 1 } else { n };
+        let mut values: Vec<f64> = (0..=last)
+            .map(|j| {
+                let s = node(last, j);
+                if !smoothed {
+                    return payout(s);
+                }
+                let european = Options::new_with_type(
+                    option.option_type(),
+                    option.strike_price(),
+                    s,
+                    option.volatility(),
+                    r,
+                    dt,
+                    option.dividend_yield(),
+                )
+                .bs_pricing();
+                match style {
+                    ExerciseStyle::European => european,
+                    ExerciseStyle::American => european.max(payout(s)),
+                }
+            })
+            .collect();
+        if let Some(barrier) = barrier {
+            knock_out(&mut values, barrier, |j| node(last, j));
+        }
+        pay(&mut values, last);
+        for step in (0..last).rev() {
+            let held = escrow(step);
+            let mut edge = None;
+            for j in 0..=step {
+                let cont = discount * (p * values[j + 1] + (1.0 - p) * values[j]);
+                values[j] = match style {
+                    ExerciseStyle::European => cont,
+                    ExerciseStyle::American => {
+                        let exercise = payout(node(step, j) + held);
+                        if exercise > cont && exercise > 0.0 {
+                            exercise_edge(&mut edge, option.option_type(), node(step, j) + held);
+                        }
+                        cont.max(exercise)
+                    }
+                };
+            }
+            if let (Some(boundary), Some(edge)) = (boundary.as_mut(), edge) {
+                boundary.push((step as f64 * dt, edge));
+            }
+            if let Some(barrier) = barrier {
+                knock_out(&mut values[..=step], barrier, |j| node(step, j));
+            }
+            pay(&mut values[..=step], step);
+        }
+        if let Some(boundary) = boundary {
+            boundary.reverse();
+        }
+        values[0]
+    }
+    /// Early exercise premium measured on the tree, American minus European
+    pub fn early_exercise_premium(&self, option: &Options) -> f64 {
+        self.price(option, ExerciseStyle::American) - self.price(option, ExerciseStyle::European)
+    }
+    /// American value with the European tree error removed by the Black-Scholes control variate.
+    ///
+    /// V = V_am_tree - V_eu_tree + V_bs
+    pub fn american_price(&self, option: &Options) -> f64 {
+        self.early_exercise_premium(option) + option.bs_pricing()
+    }
+    /// Volatility at which the American value reproduces a quoted premium.
+    ///
+    /// Fails if the premium is at or below intrinsic value or above the no-arbitrage cap
+    pub fn implied_volatility(&self, option: &Options, price: f64) -> Result<f64, PricingError> {
+        self.implied_volatility_with(option, price, &NumericsConfig::default())
+    }
+    /// American implied volatility solved to the tolerance and iteration cap of `numerics`
+    pub fn implied_volatility_with(
+        &self,
+        option: &Options,
+        price: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<f64, PricingError> {
+        let spot = option.spot_price();
+        let strike = option.strike_price();
+        let (intrinsic, cap) = match option {
+            Options::Call(_) => ((spot - strike).max(0.0), spot),
+            Options::Put(_) => ((strike - spot).max(0.0), strike),
+        };
+        if !(price > intrinsic && price < cap) || option.time_to_maturity() <= 0.0 {
+            return Err(PricingError::InvalidInput(format!(
+                "American premium {} outside bounds ({}, {})",
+                price, intrinsic, cap
+            )));
+        }
+        let rate = option.risk_free_rate();
+        brent(
+            |vol| self.american_price(&option.with_market(spot, vol, rate)) - price,
+            MIN_IMPLIED_VOL,
+            MAX_IMPLIED_VOL,
+            numerics.tolerance,
+            numerics.max_iterations,
+        )
+    }
+    /// European-equivalent premium of an American quote.
+    ///
+    /// Solves the American implied volatility, then reprices the contract as a European at it,
+    /// so the result can be fed to European tooling such as surface fitting
+    pub fn de_americanize(&self, option: &Options, price: f64) -> Result<f64, PricingError> {
+        let vol = self.implied_volatility(option, price)?;
+        let spot = option.spot_price();
+        let rate = option.risk_free_rate();
+        Ok(option.with_market(spot, vol, rate).bs_pricing())
+    }
+}
+
+// Zeroes the nodes of one step at or beyond the barrier and interpolates the last live node
+// linearly between the barrier and its inner neighbour
+fn knock_out(values: &mut [f64], barrier: &Barrier, node: impl Fn(usize) -> f64) {
+    // live nodes are contiguous, with the dead ones above them for an up barrier
+    let mut live: Option<(usize, usize)> = None;
+    for (j, value) in values.iter_mut().enumerate() {
+        if barrier.is_breached(node(j)) {
+            *value = 0.0;
+        } else {
+            live = Some((live.map_or(j, |(first, _)| first), j));
+        }
+    }
+    let Some((first, last)) = live else {
+        return;
+    };
+    // only a step that reaches past the barrier has a node straddling it
+    let (inside, next) = if barrier.kind.is_up() {
+        (
+            last,
+            last.checked_sub(1)
+                .filter(|&j| j >= first && last + 1 < values.len()),
+        )
+    } else {
+        (first, Some(first + 1).filter(|&j| j <= last && first > 0))
+    };
+    if let Some(next) = next {
+        let (s_in, s_next) = (node(inside), node(next));
+        // Formula: V_in = V_next (S_in - B) / (S_next - B)
+        values[inside] = values[next] * (s_in - barrier.level) / (s_next - barrier.level);
+    }
+}
+
+impl LatticeEngine for BinomialTree {
+    fn steps(&self) -> usize {
+        self.steps
+    }
+    fn with_steps(&self, steps: usize) -> Self {
+        BinomialTree::new(steps)
+    }
+    fn price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        BinomialTree::price(self, option, style)
+    }
+}
+
+impl BarrierEngine for BinomialTree {
+    fn knock_out_price(&self, option: &Options, barrier: &Barrier, style: ExerciseStyle) -> f64 {
+        self.roll_back(
+            option,
+            &|s| option.payout(s),
+            style,
+            Some(barrier),
+            None,
+            false,
+            None,
+        )
+    }
+}
+
+impl DividendEngine for BinomialTree {
+    fn price_with_dividends(
+        &self,
+        option: &Options,
+        dividends: &DiscreteDividends,
+        style: ExerciseStyle,
+    ) -> f64 {
+        self.roll_back(
+            option,
+            &|s| option.payout(s),
+            style,
+            None,
+            Some(dividends),
+            false,
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binomial_converges_to_black_scholes() {
+        let tree = BinomialTree::new(500);
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        let european = tree.price(&call, ExerciseStyle::European);
+        assert!((european - call.bs_pricing()).abs() < 0.01);
+        // without dividends early exercise of a call is never optimal
+        assert!(tree.early_exercise_premium(&call).abs() < 1e-8);
+
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let american = tree.american_price(&put);
+        // reference value from a 10 000 step tree
+        assert!((american - 6.0903).abs() < 0.005, "{}", american);
+        assert!(american > put.bs_pricing());
+    }
+
+    #[test]
+    fn test_american_implied_volatility() {
+        let tree = BinomialTree::default();
+        let put = Options::new_put(110.0, 100.0, 0.3, 0.05, 0.75, Some(0.01));
+        let premium = tree.american_price(&put);
+        let vol = tree.implied_volatility(&put, premium).unwrap();
+        assert!((vol - 0.3).abs() < 1e-8);
+
+        // the European solver over-states vol on an American quote
+        let european_vol = put.implied_volatility(premium).unwrap();
+        assert!(european_vol > 0.3 + 1e-3);
+        let european_premium = tree.de_americanize(&put, premium).unwrap();
+        assert!((put.implied_volatility(european_premium).unwrap() - 0.3).abs() < 1e-6);
+
+        assert!(tree.implied_volatility(&put, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_exercise_boundary() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let tree = BinomialTree::new(400);
+        let (price, boundary) = tree.price_with_boundary(&put);
+        assert_eq!(price, tree.price(&put, ExerciseStyle::American));
+        assert!(boundary.windows(2).all(|w| w[0].0 < w[1].0));
+        // the critical spot rises towards the strike as expiry nears
+        let (first, last) = (boundary[0], boundary[boundary.len() - 1]);
+        assert!(first.1 > 80.0 && first.1 < 85.0);
+        assert!(last.1 > 95.0 && last.1 < 100.0 && last.0 < 1.0);
+        // with a dividend yield, calls are exercised early above the strike; the boundary ends
+        // at the larger of K and rK/q at expiry, here 125 with the yield below the rate
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, Some(0.04));
+        let calls = tree.price_with_boundary(&call).1;
+        assert!(calls.iter().all(|&(_, s)| s > 125.0));
+        // and never without dividends
+        let undivided = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        assert!(tree.price_with_boundary(&undivided).1.is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+// Tree pricers for contracts without closed forms, such as early exercise.
+pub mod acceleration;
+pub mod barrier;
+pub mod binomial;
+pub mod dividends;
+pub mod trinomial;
+
+use crate::{OptionType, Options};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// When the holder may exercise a contract priced on a lattice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExerciseStyle {
+    European,
+    American,
+}
+
+// Moves a step's critical spot to an exercised node, visited in increasing spot: puts keep the
+// highest exercised node and calls the lowest
+fn exercise_edge(edge: &mut Option<f64>, option_type: OptionType, spot: f64) {
+    match option_type {
+        OptionType::Call => {
+            edge.get_or_insert(spot);
+        }
+        OptionType::Put => *edge = Some(spot),
+    }
+}
+
+/// A recombining tree over the underlying, refined by its step count.
+pub trait LatticeEngine {
+    fn steps(&self) -> usize;
+    /// The same tree with another step count
+    fn with_steps(&self, steps: usize) -> Self
+    where
+        Self: Sized;
+    /// Raw tree value of the option for the given exercise style
+    fn price(&self, option: &Options, style: ExerciseStyle) -> f64;
+    /// Early exercise premium measured on the tree, American minus European
+    fn early_exercise_premium(&self, option: &Options) -> f64 {
+        self.price(option, ExerciseStyle::American) - self.price(option, ExerciseStyle::European)
+    }
+    /// American value with the European tree error removed by the Black-Scholes control variate.
+    ///
+    /// V = V_am_tree - V_eu_tree + V_bs
+    fn american_price(&self, option: &Options) -> f64 {
+        self.early_exercise_premium(option) + option.bs_pricing()
+    }
+    /// Richardson extrapolation of the first-order tree error from this tree and one with twice
+    /// the steps. Only pays off when the error decays smoothly, as on trinomial and smoothed trees
+    ///
+    /// Formula: V ≈ 2 V(2n) - V(n)
+    fn extrapolated_price(&self, option: &Options, style: ExerciseStyle) -> f64
+    where
+        Self: Sized,
+    {
+        let fine = self.with_steps(2 * self.steps()).price(option, style);
+        2.0 * fine - self.price(option, style)
+    }
+}
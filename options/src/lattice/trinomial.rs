@@ -0,0 +1,290 @@
+use super::barrier::{Barrier, BarrierEngine};
+use super::dividends::{DiscreteDividends, DividendEngine, DividendModel, pay_dividend};
+use super::{ExerciseStyle, LatticeEngine, exercise_edge};
+use crate::Options;
+use crate::lattice::binomial::DEFAULT_STEPS;
+use crate::math::float::{exp, ln, sqrt};
+use alloc::vec::Vec;
+
+/// Stretch λ of Kamrad-Ritchken giving the middle branch a probability of 1/3
+pub const DEFAULT_STRETCH: f64 = 1.224_744_871_391_589; // √(3/2)
+
+/// Kamrad-Ritchken trinomial tree: up, flat and down moves of e^(±λσ√dt) and 1.
+///
+/// The flat branch makes the price converge smoothly in the step count instead of oscillating
+/// between odd and even trees, so Richardson extrapolation and Greeks read off the tree are usable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrinomialTree {
+    pub steps: usize,
+    /// Stretch λ of the moves, at least 1 so the flat probability 1 - 1/λ² is not negative
+    pub stretch: f64,
+}
+
+impl Default for TrinomialTree {
+    fn default() -> Self {
+        TrinomialTree::new(DEFAULT_STEPS)
+    }
+}
+
+/// Spot Greeks and theta read off the first step of a tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    /// Annualized, from the flat branch
+    pub theta: f64,
+}
+
+impl TrinomialTree {
+    pub fn new(steps: usize) -> Self {
+        TrinomialTree {
+            steps: steps.max(1),
+            stretch: DEFAULT_STRETCH,
+        }
+    }
+    // Backward induction to the three nodes after the first step, then to the root:
+    // (root value, [down, flat, up] values at step one, up move, dt). Nodes at or beyond
+    // `barrier` net up moves from spot, negative for a down barrier, are knocked out.
+    // Escrowed dividends shift the tree to net spot; spot-adjusted ones are paid on their step.
+    // `boundary` collects each step's critical spot
+    fn roll_back(
+        &self,
+        option: &Options,
+        style: ExerciseStyle,
+        barrier: Option<isize>,
+        dividends: Option<&DiscreteDividends>,
+        mut boundary: Option<&mut Vec<(f64, f64)>>,
+    ) -> (f64, [f64; 3], f64, f64) {
+        let n = self.steps;
+        let t = option.time_to_maturity();
+        let dt = t / n as f64;
+        let r = option.risk_free_rate();
+        let q = option.dividend_yield().unwrap_or(0.0);
+        let sigma = option.volatility();
+        let lambda = self.stretch.max(1.0);
+        let jump = lambda * sigma * sqrt(dt);
+        let up = exp(jump);
+        // Formula: p_u,d = 1/(2λ²) ± (r - q - σ²/2)√dt / (2λσ), p_m = 1 - 1/λ²
+        let bias = (r - q - 0.5 * sigma * sigma) * sqrt(dt) / (2.0 * lambda * sigma);
+        let edge = 0.5 / (lambda * lambda);
+        let (p_up, p_down) = ((edge + bias).clamp(0.0, 1.0), (edge - bias).clamp(0.0, 1.0));
+        let p_mid = (1.0 - p_up - p_down).max(0.0);
+        let discount = exp(-r * dt);
+        let escrow = |step: usize| match dividends {
+            Some(d) if d.model == DividendModel::Escrowed => {
+                d.present_value(r, step as f64 * dt, t)
+            }
+            _ => 0.0,
+        };
+        let cash = dividends
+            .filter(|d| d.model == DividendModel::SpotAdjusted)
+            .map(|d| d.per_step(t, n));
+        let spot = option.spot_price() - escrow(0);
+
+        // node j of a step holds j - step net up moves; spots are reused across steps
+        let spots: Vec<f64> = (0..=2 * n)
+            .map(|j| spot * exp((j as f64 - n as f64) * jump))
+            .collect();
+        let node = |step: usize, j: usize| spots[n - step + j];
+        let alive = |step: usize, j: usize| {
+            let net = j as isize - step as isize;
+            match barrier {
+                Some(level) if level > 0 => net < level,
+                Some(level) => net > level,
+                None => true,
+            }
+        };
+        let mut values: Vec<f64> = (0..=2 * n)
+            .map(|j| {
+                if alive(n, j) {
+                    option.payout(node(n, j))
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let pay = |values: &mut [f64], step: usize| {
+            if let Some(amount) = cash.as_ref().map(|c| c[step]).filter(|&a| a > 0.0) {
+                pay_dividend(values, &spots[n - step..=n + step], amount, option, style);
+            }
+        };
+        pay(&mut values, n);
+        let mut first = [0.0; 3];
+        for step in (0..n).rev() {
+            let held = escrow(step);
+            let mut edge = None;
+            for j in 0..=2 * step {
+                let cont =
+                    discount * (p_up * values[j + 2] + p_mid * values[j + 1] + p_down * values[j]);
+                values[j] = match style {
+                    _ if !alive(step, j) => 0.0,
+                    ExerciseStyle::European => cont,
+                    ExerciseStyle::American => {
+                        let exercise = option.payout(node(step, j) + held);
+                        if exercise > cont && exercise > 0.0 {
+                            exercise_edge(&mut edge, option.option_type(), node(step, j) + held);
+                        }
+                        cont.max(exercise)
+                    }
+                };
+            }
+            if let (Some(boundary), Some(edge)) = (boundary.as_mut(), edge) {
+                boundary.push((step as f64 * dt, edge));
+            }
+            pay(&mut values[..=2 * step], step);
+            if step == 1 {
+                first = [values[0], values[1], values[2]];
+            }
+        }
+        if let Some(boundary) = boundary {
+            boundary.reverse();
+        }
+        (values[0], first, up, dt)
+    }
+    /// Delta, gamma and theta from the nodes one step in, where the tree values the option at
+    /// three spots for the same time
+    pub fn greeks(&self, option: &Options, style: ExerciseStyle) -> TreeGreeks {
+        let tree = TrinomialTree {
+            steps: self.steps.max(2),
+            ..*self
+        };
+        let (root, [down, mid, up_value], up, dt) = tree.roll_back(option, style, None, None, None);
+        let spot = option.spot_price();
+        let (s_up, s_down) = (spot * up, spot / up);
+        let slope_up = (up_value - mid) / (s_up - spot);
+        let slope_down = (mid - down) / (spot - s_down);
+        TreeGreeks {
+            delta: (up_value - down) / (s_up - s_down),
+            gamma: (slope_up - slope_down) / (0.5 * (s_up - s_down)),
+            theta: (mid - root) / dt,
+        }
+    }
+    /// American tree value with the early-exercise boundary: (time from today, critical spot)
+    /// at every step where some node is exercised, in increasing time
+    pub fn price_with_boundary(&self, option: &Options) -> (f64, Vec<(f64, f64)>) {
+        if option.time_to_maturity() <= 0.0 {
+            return (option.payout(option.spot_price()), Vec::new());
+        }
+        let mut boundary = Vec::new();
+        let (price, ..) = self.roll_back(
+            option,
+            ExerciseStyle::American,
+            None,
+            None,
+            Some(&mut boundary),
+        );
+        (price, boundary)
+    }
+}
+
+impl LatticeEngine for TrinomialTree {
+    fn steps(&self) -> usize {
+        self.steps
+    }
+    fn with_steps(&self, steps: usize) -> Self {
+        TrinomialTree {
+            steps: steps.max(1),
+            ..*self
+        }
+    }
+    fn price(&self, option: &Options, style: ExerciseStyle) -> f64 {
+        if option.time_to_maturity() <= 0.0 {
+            return option.payout(option.spot_price());
+        }
+        self.roll_back(option, style, None, None, None).0
+    }
+}
+
+impl BarrierEngine for TrinomialTree {
+    /// Stretches the moves so that a whole number of them lands exactly on the barrier, which
+    /// removes the oscillation from the barrier falling between lattice levels.
+    ///
+    /// A barrier closer to spot than one default move keeps the unaligned tree
+    fn knock_out_price(&self, option: &Options, barrier: &Barrier, style: ExerciseStyle) -> f64 {
+        if option.time_to_maturity() <= 0.0 {
+            return option.payout(option.spot_price());
+        }
+        let dt = option.time_to_maturity() / self.steps as f64;
+        let step = option.volatility() * sqrt(dt);
+        let distance = ln(barrier.level / option.spot_price()).abs();
+        // Formula: λ = d / (k σ√dt), k = ⌊d / (λ₀ σ√dt)⌋, so λ ≥ λ₀
+        let moves = (distance / (self.stretch.max(1.0) * step)) as isize;
+        let tree = if moves > 0 {
+            TrinomialTree {
+                stretch: distance / (moves as f64 * step),
+                ..*self
+            }
+        } else {
+            *self
+        };
+        // an unaligned barrier lies inside the first move, so the first level beyond spot is dead
+        let level = moves.max(1);
+        let level = if barrier.kind.is_up() { level } else { -level };
+        tree.roll_back(option, style, Some(level), None, None).0
+    }
+}
+
+impl DividendEngine for TrinomialTree {
+    fn price_with_dividends(
+        &self,
+        option: &Options,
+        dividends: &DiscreteDividends,
+        style: ExerciseStyle,
+    ) -> f64 {
+        if option.time_to_maturity() <= 0.0 {
+            return option.payout(option.spot_price());
+        }
+        self.roll_back(option, style, None, Some(dividends), None).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::binomial::BinomialTree;
+
+    #[test]
+    fn test_trinomial_convergence_and_greeks() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let tree = TrinomialTree::new(200);
+        assert!((tree.price(&put, ExerciseStyle::European) - put.bs_pricing()).abs() < 0.005);
+        // reference value from a 10 000 step binomial tree
+        let american = tree.extrapolated_price(&put, ExerciseStyle::American);
+        assert!((american - 6.0903).abs() < 0.002, "{}", american);
+
+        // off the money the binomial error flips sign with the parity of the step count, while
+        // the trinomial one barely moves
+        let off = Options::new_put(97.0, 100.0, 0.2, 0.05, 1.0, None);
+        let parity_jump = |steps: [f64; 2]| (steps[0] - steps[1]).abs();
+        let binomial =
+            [100, 101].map(|n| BinomialTree::new(n).price(&off, ExerciseStyle::European));
+        let trinomial =
+            [100, 101].map(|n| TrinomialTree::new(n).price(&off, ExerciseStyle::European));
+        assert!(parity_jump(trinomial) < 0.01 * parity_jump(binomial));
+
+        let greeks = tree.greeks(&put, ExerciseStyle::European);
+        assert!((greeks.delta - put.delta()).abs() < 2e-3);
+        assert!((greeks.gamma - put.gamma()).abs() < 2e-4);
+        assert!((greeks.theta - put.theta()).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_exercise_boundary_matches_binomial() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let (price, boundary) = TrinomialTree::new(400).price_with_boundary(&put);
+        assert_eq!(
+            price,
+            TrinomialTree::new(400).price(&put, ExerciseStyle::American)
+        );
+        let binomial = BinomialTree::new(400).price_with_boundary(&put).1;
+        let at = |boundary: &[(f64, f64)], t: f64| {
+            boundary[boundary.partition_point(|&(time, _)| time < t)].1
+        };
+        assert!((at(&boundary, 0.5) - at(&binomial, 0.5)).abs() < 1.0);
+        // far from expiry the boundary flattens out at the perpetual one
+        let long = Options::new_put(100.0, 100.0, 0.2, 0.05, 50.0, None);
+        let perpetual = long.perpetual_american().exercise_boundary().unwrap();
+        let (_, boundary) = TrinomialTree::new(2000).price_with_boundary(&long);
+        assert!((boundary[0].1 - perpetual).abs() < 1.5);
+    }
+}
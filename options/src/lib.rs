@@ -1,11 +1,155 @@
+// Without the `std` feature only the analytic kernel (Black-Scholes prices and Greeks, implied
+// vols, trees and the solvers) is built, on `core` + `alloc`
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analytics;
+pub mod bachelier;
 pub mod black_scholes;
+#[cfg(feature = "std")]
+pub mod bonds;
+#[cfg(feature = "std")]
+pub mod bounds;
+#[cfg(feature = "std")]
+pub mod calendar;
+pub mod calibration_report;
+#[cfg(feature = "std")]
+pub mod chain;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod commodity;
+pub mod conventions;
+#[cfg(feature = "std")]
+pub mod correlation;
+#[cfg(feature = "std")]
+pub mod credit;
+pub mod crypto;
+#[cfg(feature = "std")]
+pub mod curves;
+#[cfg(feature = "std")]
+pub mod decay;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod display;
+#[cfg(feature = "std")]
+pub mod engine;
+pub mod error;
+#[cfg(feature = "std")]
 pub mod exotics;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod exposure;
+pub mod fourier;
+#[cfg(feature = "io")]
+pub mod fpml;
+pub mod futures;
+pub mod generic;
+#[cfg(feature = "std")]
+pub mod hedging;
+#[cfg(feature = "std")]
+pub mod instrument;
+#[cfg(feature = "io")]
+pub mod io;
+pub mod lattice;
+// Experimental models, public only with the `unstable` feature; their API may change in any release
+#[cfg(all(feature = "unstable", feature = "mc", feature = "calibration"))]
+pub mod local_vol;
+#[cfg(all(not(feature = "unstable"), feature = "mc", feature = "calibration"))]
+#[allow(dead_code)]
+mod local_vol;
+#[cfg(feature = "std")]
+pub mod margin;
+#[cfg(feature = "std")]
+pub mod market;
+#[cfg(feature = "std")]
+pub mod market_data;
+pub mod math;
+#[cfg(feature = "mc")]
+pub mod mc;
+#[cfg(feature = "mc")]
+pub mod model_risk;
+pub mod moneyness;
+#[cfg(feature = "mc")]
+pub mod multi_asset;
+pub mod numerics;
+#[cfg(feature = "std")]
+pub mod parity;
+pub mod payoff;
+pub mod perpetual;
+#[cfg(feature = "std")]
+pub mod portfolio;
+pub mod prelude;
+#[cfg(feature = "mc")]
+pub mod processes;
+#[cfg(feature = "std")]
+pub mod quote;
+#[cfg(feature = "std")]
+pub mod rates;
+#[cfg(feature = "report")]
+pub mod report;
+#[cfg(feature = "std")]
+pub mod risk_slide;
+#[cfg(feature = "mc")]
+pub mod rng;
+// Experimental rough volatility Monte Carlo; its API may change in any release
+#[cfg(feature = "rough_vol")]
+pub mod rough_vol;
+#[cfg(feature = "std")]
+pub mod scenario;
+pub mod selection;
+#[cfg(feature = "std")]
+pub mod settlement;
+pub mod solver;
+#[cfg(feature = "std")]
+pub mod stale;
+#[cfg(feature = "std")]
+pub mod strategy;
+#[cfg(feature = "std")]
+pub mod structured;
+#[cfg(feature = "std")]
+pub mod swing;
+#[cfg(feature = "serde")]
+pub mod termsheet;
+#[cfg(feature = "std")]
+pub mod validation;
+#[cfg(feature = "std")]
+pub mod var;
+#[cfg(feature = "std")]
+pub mod vol;
+#[cfg(feature = "mc")]
+pub mod weather;
 
 use black_scholes::*;
-use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use error::PricingError;
+use generic::BlackScholes;
+use math::float::exp;
+use math::normal::Normal;
+use numerics::NumericsConfig;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Call or put side of a vanilla contract, used wherever the contract itself is not at hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum OptionType {
+    Call,
+    Put,
+}
 
 // Core option contract types shared across pricing engines and front-ends.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "option_type", rename_all = "snake_case")
+)]
 pub enum Options {
     Call(Call),
     Put(Put),
@@ -49,23 +193,9 @@ impl Options {
             dividend_yield,
         ))
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct Call {
-    strike_price: f64,
-    spot_price: f64,
-    volatility: f64,
-    risk_free_rate: f64,
-    time_to_maturity: f64,
-    dividend_yield: Option<f64>,
-}
-
-impl Call {
-    pub fn bs_pricing(&self) -> f64 {
-        black_scholes_price(Options::Call(*self))
-    }
-    pub fn new(
+    /// Builds a call or put from the same inputs
+    pub fn new_with_type(
+        option_type: OptionType,
         strike_price: f64,
         spot_price: f64,
         volatility: f64,
@@ -73,150 +203,206 @@ impl Call {
         time_to_maturity: f64,
         dividend_yield: Option<f64>,
     ) -> Self {
-        Call {
-            strike_price,
-            spot_price,
-            volatility,
-            risk_free_rate,
-            time_to_maturity,
-            dividend_yield,
+        match option_type {
+            OptionType::Call => Options::new_call(
+                strike_price,
+                spot_price,
+                volatility,
+                risk_free_rate,
+                time_to_maturity,
+                dividend_yield,
+            ),
+            OptionType::Put => Options::new_put(
+                strike_price,
+                spot_price,
+                volatility,
+                risk_free_rate,
+                time_to_maturity,
+                dividend_yield,
+            ),
+        }
+    }
+    pub fn option_type(&self) -> OptionType {
+        match self {
+            Options::Call(_) => OptionType::Call,
+            Options::Put(_) => OptionType::Put,
+        }
+    }
+    /// Black-Scholes implied volatility reproducing the given premium
+    pub fn implied_volatility(&self, price: f64) -> Result<f64, PricingError> {
+        implied_volatility(*self, price)
+    }
+    /// Implied volatility of an American premium, inverted through the binomial pricer
+    pub fn american_implied_volatility(&self, price: f64) -> Result<f64, PricingError> {
+        lattice::binomial::BinomialTree::default().implied_volatility(self, price)
+    }
+    /// Implied volatility under a house numerical policy
+    pub fn implied_volatility_with(
+        &self,
+        price: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<f64, PricingError> {
+        black_scholes::implied_volatility_with(*self, price, numerics)
+    }
+    /// American implied volatility on a tree sized and solved by `numerics`
+    pub fn american_implied_volatility_with(
+        &self,
+        price: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<f64, PricingError> {
+        lattice::binomial::BinomialTree::from_config(numerics)
+            .implied_volatility_with(self, price, numerics)
+    }
+    pub fn strike_price(&self) -> f64 {
+        match self {
+            Options::Call(call) => call.strike_price,
+            Options::Put(put) => put.strike_price,
+        }
+    }
+    pub fn spot_price(&self) -> f64 {
+        match self {
+            Options::Call(call) => call.spot_price,
+            Options::Put(put) => put.spot_price,
+        }
+    }
+    pub fn volatility(&self) -> f64 {
+        match self {
+            Options::Call(call) => call.volatility,
+            Options::Put(put) => put.volatility,
+        }
+    }
+    pub fn risk_free_rate(&self) -> f64 {
+        match self {
+            Options::Call(call) => call.risk_free_rate,
+            Options::Put(put) => put.risk_free_rate,
+        }
+    }
+    pub fn time_to_maturity(&self) -> f64 {
+        match self {
+            Options::Call(call) => call.time_to_maturity,
+            Options::Put(put) => put.time_to_maturity,
+        }
+    }
+    pub fn dividend_yield(&self) -> Option<f64> {
+        match self {
+            Options::Call(call) => call.dividend_yield,
+            Options::Put(put) => put.dividend_yield,
+        }
+    }
+    /// Returns a copy of the option with its market inputs replaced, keeping the contract terms.
+    ///
+    /// Used to revalue a contract under shocked markets
+    pub fn with_market(&self, spot_price: f64, volatility: f64, risk_free_rate: f64) -> Self {
+        match *self {
+            Options::Call(call) => Options::Call(Call {
+                spot_price,
+                volatility,
+                risk_free_rate,
+                ..call
+            }),
+            Options::Put(put) => Options::Put(Put {
+                spot_price,
+                volatility,
+                risk_free_rate,
+                ..put
+            }),
+        }
+    }
+    /// Returns a copy of the option with a different remaining time to maturity
+    pub fn with_time_to_maturity(&self, time_to_maturity: f64) -> Self {
+        match *self {
+            Options::Call(call) => Options::Call(Call {
+                time_to_maturity,
+                ..call
+            }),
+            Options::Put(put) => Options::Put(Put {
+                time_to_maturity,
+                ..put
+            }),
         }
     }
     pub fn payout(&self, spot: f64) -> f64 {
-        f64::max(0.0, spot - self.strike_price)
+        self.vanilla().payout(spot)
     }
-    /// Calculates Delta (Δ) - the rate of change of option price with respect to spot price.
-    /// For calls, delta ranges from 0 to 1. Higher values indicate deeper in-the-money positions
-    /// 
-    /// Formula: Δ = e^(-qT) * N(d₁)
-    pub fn delta(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        std_norm.cdf(d1) * dividend_correction
-    }
-    /// Calculates Theta (Θ) - time decay of option value.
-    /// Typically negative for calls. Divide by 365 for daily theta
-    /// 
-    /// Formula: Θ = -[S*N'(d₁)*σ*e^(-qT)] / [2√T] + qS*N(d₁)*e^(-qT) - rK*e^(-rT)*N(d₂)
-    pub fn theta(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let d2 = d_minus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let n_d1 = std_norm.pdf(d1);
-        let n_d2 = std_norm.cdf(d2);
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        let dividend_npv = self.dividend_yield.map_or(0.0, |yield_val| {
-            yield_val * self.spot_price * dividend_correction * std_norm.cdf(d1)
-        });
-        -(spot_price * n_d1 * imply_vol * dividend_correction)
-            / (2.0 * self.time_to_maturity.sqrt())
-            + dividend_npv
-            - self.risk_free_rate
-                * self.strike_price
-                * (-self.risk_free_rate * self.time_to_maturity).exp()
-                * n_d2
+    /// The contract as a `Vanilla`, carrying its side as data
+    pub fn vanilla(&self) -> Vanilla {
+        match *self {
+            Options::Call(call) => call.into(),
+            Options::Put(put) => put.into(),
+        }
     }
-    /// Calculates Gamma (Γ) - the rate of change of delta with respect to spot price.
-    /// Always positive. Highest for at-the-money options near expiration
-    /// 
-    /// Formula: Γ = N'(d₁) * e^(-qT) / (S * σ * √T)
-    pub fn gamma(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        std_norm.pdf(d1) * dividend_correction
-            / (spot_price * imply_vol * self.time_to_maturity.sqrt())
+    /// Delta evaluated at the stored spot and volatility
+    pub fn delta(&self) -> f64 {
+        self.vanilla().delta()
     }
-    /// Calculates Vega (ν) - sensitivity to volatility changes.
-    /// Always positive. Highest for at-the-money options
-    /// 
-    /// Formula: ν = S * N'(d₁) * √T * e^(-qT)
-    pub fn vega(&self, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            self.volatility,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        spot_price * std_norm.pdf(d1) * self.time_to_maturity.sqrt() * dividend_correction
+    /// Gamma evaluated at the stored spot and volatility
+    pub fn gamma(&self) -> f64 {
+        self.vanilla().gamma()
     }
-    /// Calculates Rho (ρ) - sensitivity to interest rate changes.
-    /// Positive for calls. Larger for longer-dated and in-the-money options
-    /// 
-    /// Formula: ρ = K * T * e^(-rT) * N(d₂)
-    pub fn rho(&self, imply_vol: f64, spot_price: f64, interest_rate: f64) -> f64 {
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let d2 = d_minus(
-            self.time_to_maturity,
-            interest_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        self.strike_price
-            * self.time_to_maturity
-            * std_norm.cdf(d2)
-            * (-interest_rate * self.time_to_maturity).exp()
+    /// Vega evaluated at the stored spot and volatility
+    pub fn vega(&self) -> f64 {
+        self.vanilla().vega()
+    }
+    /// Annualized theta evaluated at the stored spot and volatility
+    pub fn theta(&self) -> f64 {
+        self.vanilla().theta()
+    }
+    /// Risk-neutral probability of expiring in the money
+    pub fn probability_itm(&self) -> f64 {
+        self.vanilla().probability_itm()
+    }
+    /// Risk-neutral probability of spot touching the strike before expiry
+    pub fn probability_of_touch(&self) -> f64 {
+        self.vanilla().probability_of_touch()
+    }
+    /// Expected undiscounted payoff conditional on finishing in the money
+    pub fn expected_payoff_given_exercise(&self) -> f64 {
+        self.vanilla().expected_payoff_given_exercise()
+    }
+    /// Rho evaluated at the stored spot, volatility and rate
+    pub fn rho(&self) -> f64 {
+        self.vanilla().rho()
+    }
+    /// Delta at a bumped spot and volatility, for scenarios
+    pub fn delta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.vanilla().delta_at(spot_price, volatility)
+    }
+    /// Gamma at a bumped spot and volatility, for scenarios
+    pub fn gamma_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.vanilla().gamma_at(spot_price, volatility)
+    }
+    /// Vega at a bumped spot and volatility, for scenarios
+    pub fn vega_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.vanilla().vega_at(spot_price, volatility)
+    }
+    /// Annualized theta at a bumped spot and volatility, for scenarios
+    pub fn theta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.vanilla().theta_at(spot_price, volatility)
+    }
+    /// Rho at a bumped spot and volatility, for scenarios
+    pub fn rho_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.vanilla().rho_at(spot_price, volatility)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Put {
+/// A European contract with its side held as data, so the Black-Scholes formulas are written
+/// once with the sign φ = +1 for calls and -1 for puts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Vanilla {
+    kind: OptionType,
     strike_price: f64,
     spot_price: f64,
     volatility: f64,
     risk_free_rate: f64,
     time_to_maturity: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
     dividend_yield: Option<f64>,
 }
 
-impl Put {
-    pub fn bs_pricing(&self) -> f64 {
-        black_scholes_price(Options::Put(*self))
-    }
+impl Vanilla {
     pub fn new(
+        kind: OptionType,
         strike_price: f64,
         spot_price: f64,
         volatility: f64,
@@ -224,7 +410,8 @@ impl Put {
         time_to_maturity: f64,
         dividend_yield: Option<f64>,
     ) -> Self {
-        Put {
+        Vanilla {
+            kind,
             strike_price,
             spot_price,
             volatility,
@@ -233,121 +420,267 @@ impl Put {
             dividend_yield,
         }
     }
-    pub fn payout(&self, spot: f64) -> f64 {
-        f64::max(0.0, self.strike_price - spot)
+    pub fn kind(&self) -> OptionType {
+        self.kind
     }
-    /// Calculates Delta (Δ) - the rate of change of option price with respect to spot price.
-    /// For puts, delta ranges from -1 to 0. More negative values indicate deeper in-the-money positions
-    /// 
-    /// Formula: Δ = e^(-qT) * [N(d₁) - 1]
-    pub fn delta(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        (std_norm.cdf(d1) - 1.0) * dividend_correction
-    }
-    /// Calculates Theta (Θ) - time decay of option value.
-    /// Can be positive or negative for puts depending on moneyness. Divide by 365 for daily theta
-    /// 
-    /// Formula: Θ = -[S*N'(d₁)*σ*e^(-qT)] / [2√T] - qS*N(-d₁)*e^(-qT) + rK*e^(-rT)*N(-d₂)
-    pub fn theta(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let d2 = d_minus(
+    fn phi(&self) -> f64 {
+        match self.kind {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        }
+    }
+    // (d₁, d₂) at the given spot and volatility
+    fn d(&self, spot_price: f64, volatility: f64) -> (f64, f64) {
+        let (t, r, q, k) = (
             self.time_to_maturity,
             self.risk_free_rate,
             self.dividend_yield,
-            imply_vol,
-            spot_price,
             self.strike_price,
         );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let n_d1 = std_norm.pdf(d1);
-        let n_d2 = std_norm.cdf(-d2);
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        let dividend_npv = self.dividend_yield.map_or(0.0, |yield_val| {
-            yield_val * self.spot_price * dividend_correction * std_norm.cdf(-d1)
-        });
-        -(spot_price * n_d1 * imply_vol * dividend_correction)
-            / (2.0 * self.time_to_maturity.sqrt())
-            - dividend_npv
-            + self.risk_free_rate
-                * self.strike_price
-                * (-self.risk_free_rate * self.time_to_maturity).exp()
-                * n_d2
+        (
+            d_plus(t, r, q, volatility, spot_price, k),
+            d_minus(t, r, q, volatility, spot_price, k),
+        )
+    }
+    // The closed forms at the given spot and volatility, evaluated by the generic pricer
+    fn model(&self, spot_price: f64, volatility: f64) -> BlackScholes<f64> {
+        BlackScholes {
+            option_type: self.kind,
+            strike: self.strike_price,
+            spot: spot_price,
+            volatility,
+            rate: self.risk_free_rate,
+            expiry: self.time_to_maturity,
+            dividend_yield: self.dividend_yield.unwrap_or(0.0),
+        }
+    }
+    /// Black-Scholes price, handling the dividend yield if present
+    ///
+    /// Formula: V = φ[S*e^(-qT)*N(φd₁) - K*e^(-rT)*N(φd₂)]
+    pub fn price(&self) -> f64 {
+        self.model(self.spot_price, self.volatility).price()
+    }
+    /// Formula: max(φ(S - K), 0)
+    pub fn payout(&self, spot: f64) -> f64 {
+        f64::max(0.0, self.phi() * (spot - self.strike_price))
+    }
+    /// Calculates Delta (Δ) - the rate of change of option price with respect to spot price.
+    /// Ranges from 0 to 1 for calls and from -1 to 0 for puts
+    ///
+    /// Formula: Δ = φ * e^(-qT) * N(φd₁)
+    pub fn delta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.model(spot_price, volatility).delta()
+    }
+    /// Delta at the stored spot and volatility
+    pub fn delta(&self) -> f64 {
+        self.delta_at(self.spot_price, self.volatility)
+    }
+    /// Calculates Theta (Θ) - time decay of option value. Divide by 365 for daily theta
+    ///
+    /// Formula: Θ = -[S*N'(d₁)*σ*e^(-qT)] / [2√T] + φqS*N(φd₁)*e^(-qT) - φrK*e^(-rT)*N(φd₂)
+    pub fn theta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.model(spot_price, volatility).theta()
+    }
+    /// Theta at the stored spot and volatility
+    pub fn theta(&self) -> f64 {
+        self.theta_at(self.spot_price, self.volatility)
     }
     /// Calculates Gamma (Γ) - the rate of change of delta with respect to spot price.
-    /// Always positive. Highest for at-the-money options near expiration
-    /// 
+    /// Always positive and the same for both sides. Highest at the money near expiration
+    ///
     /// Formula: Γ = N'(d₁) * e^(-qT) / (S * σ * √T)
-    pub fn gamma(&self, imply_vol: f64, spot_price: f64) -> f64 {
-        let d1 = d_plus(
-            self.time_to_maturity,
-            self.risk_free_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        (std_norm.pdf(d1) * dividend_correction) / (spot_price * imply_vol * self.time_to_maturity.sqrt())
+    pub fn gamma_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.model(spot_price, volatility).gamma()
+    }
+    /// Gamma at the stored spot and volatility
+    pub fn gamma(&self) -> f64 {
+        self.gamma_at(self.spot_price, self.volatility)
     }
     /// Calculates Vega (ν) - sensitivity to volatility changes.
-    /// Always positive. Highest for at-the-money options
-    /// 
+    /// Always positive and the same for both sides. Highest at the money
+    ///
     /// Formula: ν = S * N'(d₁) * √T * e^(-qT)
-    pub fn vega(&self, spot_price: f64) -> f64 {
-        let d1 = d_plus(
+    pub fn vega_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.model(spot_price, volatility).vega()
+    }
+    /// Vega at the stored spot and volatility
+    pub fn vega(&self) -> f64 {
+        self.vega_at(self.spot_price, self.volatility)
+    }
+    /// Calculates Rho (ρ) - sensitivity to interest rate changes.
+    /// Positive for calls, negative for puts. Larger for longer-dated and in-the-money options
+    ///
+    /// Formula: ρ = φ * K * T * e^(-rT) * N(φd₂)
+    pub fn rho_at(&self, spot_price: f64, volatility: f64) -> f64 {
+        self.model(spot_price, volatility).rho()
+    }
+    /// Rho at the stored spot and volatility
+    pub fn rho(&self) -> f64 {
+        self.rho_at(self.spot_price, self.volatility)
+    }
+    /// Risk-neutral probability of expiring in the money
+    ///
+    /// Formula: N(φd₂)
+    pub fn probability_itm(&self) -> f64 {
+        let (_, d2) = self.d(self.spot_price, self.volatility);
+        Normal::new(0.0, 1.0).unwrap().cdf(self.phi() * d2)
+    }
+    /// Risk-neutral probability that spot trades to the strike before expiry; 1 once in the money
+    pub fn probability_of_touch(&self) -> f64 {
+        if self.phi() * (self.spot_price - self.strike_price) >= 0.0 {
+            return 1.0;
+        }
+        probability_of_touch(
+            self.spot_price,
+            self.strike_price,
             self.time_to_maturity,
             self.risk_free_rate,
             self.dividend_yield,
             self.volatility,
-            spot_price,
-            self.strike_price,
-        );
-        let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let dividend_correction = self
-            .dividend_yield
-            .map_or(1.0, |yield_val| (-yield_val * self.time_to_maturity).exp());
-        spot_price * std_norm.pdf(d1) * self.time_to_maturity.sqrt() * dividend_correction
+        )
     }
-    /// Calculates Rho (ρ) - sensitivity to interest rate changes.
-    /// Negative for puts. Larger absolute value for longer-dated and in-the-money options
-    /// 
-    /// Formula: ρ = -K * T * e^(-rT) * N(-d₂)
-    pub fn rho(&self, imply_vol: f64, spot_price: f64, interest_rate: f64) -> f64 {
+    /// Expected undiscounted payoff given the option expires in the money
+    ///
+    /// Formula: E[φ(S_T - K) | in the money] = φ[F*N(φd₁) - K*N(φd₂)] / N(φd₂)
+    pub fn expected_payoff_given_exercise(&self) -> f64 {
+        let p = self.probability_itm();
+        if p <= 0.0 {
+            return 0.0;
+        }
+        let phi = self.phi();
+        let carry = self.risk_free_rate - self.dividend_yield.unwrap_or(0.0);
+        let forward = self.spot_price * exp(carry * self.time_to_maturity);
+        let (d1, _) = self.d(self.spot_price, self.volatility);
         let std_norm = Normal::new(0.0, 1.0).unwrap();
-        let d2 = d_minus(
-            self.time_to_maturity,
-            interest_rate,
-            self.dividend_yield,
-            imply_vol,
-            spot_price,
-            self.strike_price,
-        );
-        -self.strike_price
-            * self.time_to_maturity
-            * std_norm.cdf(-d2)
-            * (-interest_rate * self.time_to_maturity).exp()
+        phi * (forward * std_norm.cdf(phi * d1) - self.strike_price * p) / p
     }
 }
+
+impl From<Vanilla> for Options {
+    fn from(vanilla: Vanilla) -> Self {
+        Options::new_with_type(
+            vanilla.kind,
+            vanilla.strike_price,
+            vanilla.spot_price,
+            vanilla.volatility,
+            vanilla.risk_free_rate,
+            vanilla.time_to_maturity,
+            vanilla.dividend_yield,
+        )
+    }
+}
+
+// Call and Put keep their own types and serialized form, delegating every formula to `Vanilla`
+macro_rules! vanilla_side {
+    ($name:ident, $kind:expr) => {
+        #[derive(Debug, Clone, Copy)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct $name {
+            strike_price: f64,
+            spot_price: f64,
+            volatility: f64,
+            risk_free_rate: f64,
+            time_to_maturity: f64,
+            #[cfg_attr(feature = "serde", serde(default))]
+            dividend_yield: Option<f64>,
+        }
+
+        impl From<$name> for Vanilla {
+            fn from(side: $name) -> Self {
+                Vanilla::new(
+                    $kind,
+                    side.strike_price,
+                    side.spot_price,
+                    side.volatility,
+                    side.risk_free_rate,
+                    side.time_to_maturity,
+                    side.dividend_yield,
+                )
+            }
+        }
+
+        impl $name {
+            pub fn bs_pricing(&self) -> f64 {
+                self.vanilla().price()
+            }
+            pub fn new(
+                strike_price: f64,
+                spot_price: f64,
+                volatility: f64,
+                risk_free_rate: f64,
+                time_to_maturity: f64,
+                dividend_yield: Option<f64>,
+            ) -> Self {
+                $name {
+                    strike_price,
+                    spot_price,
+                    volatility,
+                    risk_free_rate,
+                    time_to_maturity,
+                    dividend_yield,
+                }
+            }
+            pub fn vanilla(&self) -> Vanilla {
+                (*self).into()
+            }
+            pub fn payout(&self, spot: f64) -> f64 {
+                self.vanilla().payout(spot)
+            }
+            /// Delta at a given spot and volatility, see `Vanilla::delta_at`
+            pub fn delta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+                self.vanilla().delta_at(spot_price, volatility)
+            }
+            /// Delta at the stored spot and volatility
+            pub fn delta(&self) -> f64 {
+                self.vanilla().delta()
+            }
+            /// Annualized theta at a given spot and volatility, see `Vanilla::theta_at`
+            pub fn theta_at(&self, spot_price: f64, volatility: f64) -> f64 {
+                self.vanilla().theta_at(spot_price, volatility)
+            }
+            /// Theta at the stored spot and volatility
+            pub fn theta(&self) -> f64 {
+                self.vanilla().theta()
+            }
+            /// Gamma at a given spot and volatility, see `Vanilla::gamma_at`
+            pub fn gamma_at(&self, spot_price: f64, volatility: f64) -> f64 {
+                self.vanilla().gamma_at(spot_price, volatility)
+            }
+            /// Gamma at the stored spot and volatility
+            pub fn gamma(&self) -> f64 {
+                self.vanilla().gamma()
+            }
+            /// Vega at a given spot and volatility, see `Vanilla::vega_at`
+            pub fn vega_at(&self, spot_price: f64, volatility: f64) -> f64 {
+                self.vanilla().vega_at(spot_price, volatility)
+            }
+            /// Vega at the stored spot and volatility
+            pub fn vega(&self) -> f64 {
+                self.vanilla().vega()
+            }
+            /// Rho at a given spot and volatility, see `Vanilla::rho_at`
+            pub fn rho_at(&self, spot_price: f64, volatility: f64) -> f64 {
+                self.vanilla().rho_at(spot_price, volatility)
+            }
+            /// Rho at the stored spot and volatility
+            pub fn rho(&self) -> f64 {
+                self.vanilla().rho()
+            }
+            /// Risk-neutral probability of expiring in the money
+            pub fn probability_itm(&self) -> f64 {
+                self.vanilla().probability_itm()
+            }
+            /// Risk-neutral probability that spot trades to the strike before expiry; 1 once in the money
+            pub fn probability_of_touch(&self) -> f64 {
+                self.vanilla().probability_of_touch()
+            }
+            /// Expected undiscounted payoff given the option expires in the money
+            pub fn expected_payoff_given_exercise(&self) -> f64 {
+                self.vanilla().expected_payoff_given_exercise()
+            }
+        }
+    };
+}
+
+vanilla_side!(Call, OptionType::Call);
+vanilla_side!(Put, OptionType::Put);
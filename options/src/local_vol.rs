@@ -0,0 +1,219 @@
+use crate::math::float::{exp, ln};
+use crate::math::interp::bracket;
+use crate::mc::{McResult, MonteCarloEngine};
+use crate::vol::surface::VolSurface;
+
+/// Regularization of the Dupire extraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalVolParams {
+    /// Local vols are clamped into [min_vol, max_vol]
+    pub min_vol: f64,
+    pub max_vol: f64,
+    /// Floor on ∂w/∂T, so small calendar noise does not produce negative local variance
+    pub min_variance_slope: f64,
+    /// Half-width, in grid nodes, of the moving average applied across log-moneyness
+    pub smoothing: usize,
+}
+
+impl Default for LocalVolParams {
+    fn default() -> Self {
+        LocalVolParams {
+            min_vol: 0.01,
+            max_vol: 3.0,
+            min_variance_slope: 1e-6,
+            smoothing: 0,
+        }
+    }
+}
+
+/// Dupire local volatility on a (time, log-moneyness) grid, with k = ln(S / F(t)).
+///
+/// Looked up by bilinear interpolation and held flat outside the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalVolSurface {
+    times: Vec<f64>,
+    log_moneyness: Vec<f64>,
+    /// Local vols indexed [time][log-moneyness]
+    vols: Vec<Vec<f64>>,
+    forward_rate: f64,
+    spot: f64,
+}
+
+impl LocalVolSurface {
+    /// Extracts local vols from an implied surface with Gatheral's total-variance form of Dupire:
+    /// σ²_loc = ∂w/∂T / (1 - (k/w)∂w/∂k + ¼(-¼ - 1/w + k²/w²)(∂w/∂k)² + ½∂²w/∂k²),
+    /// using central finite differences on w(k, T) = σ²_imp T.
+    ///
+    /// `spot` and the continuously compounded `forward_rate` (r - q) map spots to log-moneyness
+    pub fn from_implied(
+        surface: &VolSurface,
+        spot: f64,
+        forward_rate: f64,
+        times: Vec<f64>,
+        log_moneyness: Vec<f64>,
+        params: LocalVolParams,
+    ) -> Self {
+        let forward = |t: f64| spot * exp(forward_rate * t);
+        let w = |k: f64, t: f64| {
+            let vol = surface.vol(forward(t) * exp(k), t).vol;
+            vol * vol * t
+        };
+        let vols = times
+            .iter()
+            .map(|&t| {
+                let h_t = (t * 1e-3).max(1e-5);
+                let h_k = 1e-3;
+                let raw: Vec<f64> = log_moneyness
+                    .iter()
+                    .map(|&k| {
+                        let w0 = w(k, t);
+                        let dw_dt = ((w(k, t + h_t) - w(k, (t - h_t).max(1e-8)))
+                            / (t + h_t - (t - h_t).max(1e-8)))
+                        .max(params.min_variance_slope);
+                        let (w_up, w_down) = (w(k + h_k, t), w(k - h_k, t));
+                        let dw_dk = (w_up - w_down) / (2.0 * h_k);
+                        let d2w_dk2 = (w_up - 2.0 * w0 + w_down) / (h_k * h_k);
+                        let denominator = 1.0 - k / w0 * dw_dk
+                            + 0.25 * (-0.25 - 1.0 / w0 + k * k / (w0 * w0)) * dw_dk * dw_dk
+                            + 0.5 * d2w_dk2;
+                        let variance = dw_dt / denominator.max(1e-8);
+                        variance.sqrt().clamp(params.min_vol, params.max_vol)
+                    })
+                    .collect();
+                smooth(&raw, params.smoothing)
+            })
+            .collect();
+        LocalVolSurface {
+            times,
+            log_moneyness,
+            vols,
+            forward_rate,
+            spot,
+        }
+    }
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+    pub fn log_moneyness(&self) -> &[f64] {
+        &self.log_moneyness
+    }
+    /// Local vol at an underlying level and time
+    pub fn local_vol(&self, spot: f64, t: f64) -> f64 {
+        let k = ln(spot / (self.spot * exp(self.forward_rate * t)));
+        let (i, wt) = bracket(&self.times, t);
+        let (j, wk) = bracket(&self.log_moneyness, k);
+        let i1 = (i + 1).min(self.times.len() - 1);
+        let j1 = (j + 1).min(self.log_moneyness.len() - 1);
+        let row = |i: usize| self.vols[i][j] + wk * (self.vols[i][j1] - self.vols[i][j]);
+        row(i) + wt * (row(i1) - row(i))
+    }
+    /// Prices a path-dependent payoff by simulating the underlying under this local vol surface,
+    /// so the price is consistent with the implied smile it was extracted from
+    pub fn mc_price<P>(
+        &self,
+        engine: &MonteCarloEngine,
+        rate: f64,
+        dividend_yield: Option<f64>,
+        maturity: f64,
+        payoff: P,
+    ) -> McResult
+    where
+        P: Fn(&[f64]) -> f64 + Sync,
+    {
+        engine.price(
+            self.spot,
+            rate,
+            dividend_yield,
+            maturity,
+            |s, t| self.local_vol(s, t),
+            payoff,
+        )
+    }
+}
+
+fn smooth(values: &[f64], half_width: usize) -> Vec<f64> {
+    if half_width == 0 {
+        return values.to_vec();
+    }
+    (0..values.len())
+        .map(|i| {
+            let window =
+                &values[i.saturating_sub(half_width)..(i + half_width + 1).min(values.len())];
+            window.iter().sum::<f64>() / window.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+    use crate::mc::McConfig;
+    use crate::vol::smile::{Smile, WingExtrapolation};
+    use crate::vol::surface::SurfaceSlice;
+
+    fn surface(skew: f64) -> VolSurface {
+        let spot: f64 = 100.0;
+        VolSurface::from_slices(
+            [0.25f64, 0.5, 1.0, 2.0]
+                .iter()
+                .map(|&expiry| {
+                    let forward = spot * (0.03 * expiry).exp();
+                    let quotes = (60..=150)
+                        .step_by(10)
+                        .map(|strike| {
+                            let k = (strike as f64 / forward).ln();
+                            (strike as f64, 0.2 + skew * k)
+                        })
+                        .collect();
+                    SurfaceSlice {
+                        expiry,
+                        forward,
+                        svi: None,
+                        fit: None,
+                        smile: Smile::new(expiry, forward, quotes, WingExtrapolation::Flat)
+                            .unwrap(),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn grid(surface: &VolSurface) -> LocalVolSurface {
+        LocalVolSurface::from_implied(
+            surface,
+            100.0,
+            0.03,
+            vec![0.25, 0.5, 0.75, 1.0],
+            (-8..=8).map(|i| i as f64 * 0.05).collect(),
+            LocalVolParams::default(),
+        )
+    }
+
+    #[test]
+    fn test_flat_surface_gives_flat_local_vol() {
+        let local = grid(&surface(0.0));
+        for t in [0.3, 0.8] {
+            for s in [80.0, 100.0, 120.0] {
+                assert!((local.local_vol(s, t) - 0.2).abs() < 1e-3);
+            }
+        }
+        let engine = MonteCarloEngine::new(McConfig::new(20_000, 50, 11));
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.03, 1.0, None);
+        let result = local.mc_price(&engine, 0.03, None, 1.0, |path: &[f64]| {
+            call.payout(path[path.len() - 1])
+        });
+        assert!((result.price - call.bs_pricing()).abs() < 3.0 * result.std_error + 0.02);
+    }
+
+    #[test]
+    fn test_skew_steepens_local_vol() {
+        let local = grid(&surface(-0.2));
+        // local vol skew is roughly twice the implied skew
+        let down = local.local_vol(90.0, 0.5);
+        let up = local.local_vol(110.0, 0.5);
+        assert!(down > up);
+        let implied_spread = 0.2 * (110.0f64 / 90.0).ln();
+        assert!(down - up > 1.5 * implied_spread);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::portfolio::{Portfolio, Position, StockPosition};
+use crate::scenario::Shock;
+
+/// Scan ranges of a SPAN-style margin grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginParams {
+    /// Relative spot scan range, e.g. 0.15 for ±15%
+    pub price_scan: f64,
+    /// Absolute vol scan range, e.g. 0.05 for ±5 vol points
+    pub vol_scan: f64,
+    /// Multiple of the price scan used for the two extreme-move scenarios
+    pub extreme_multiple: f64,
+    /// Fraction of the extreme-move loss that counts towards margin
+    pub extreme_coverage: f64,
+}
+
+impl Default for MarginParams {
+    fn default() -> Self {
+        MarginParams {
+            price_scan: 0.15,
+            vol_scan: 0.05,
+            extreme_multiple: 2.0,
+            extreme_coverage: 0.35,
+        }
+    }
+}
+
+/// One row of the margin grid: spot and vol shocks plus the weight applied to the resulting loss.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginScenario {
+    pub spot: Shock,
+    pub vol: Shock,
+    pub weight: f64,
+}
+
+impl MarginParams {
+    /// The standard 16 scenarios: spot unchanged and ±1/3, ±2/3, ±3/3 of the scan range, each with
+    /// vol up and down, then two extreme spot moves at partial coverage
+    pub fn scenarios(&self) -> Vec<MarginScenario> {
+        let mut scenarios = Vec::with_capacity(16);
+        for fraction in [0.0, 1.0, -1.0, 2.0, -2.0, 3.0, -3.0] {
+            for vol in [self.vol_scan, -self.vol_scan] {
+                scenarios.push(MarginScenario {
+                    spot: Shock::Relative(self.price_scan * fraction / 3.0),
+                    vol: Shock::Absolute(vol),
+                    weight: 1.0,
+                });
+            }
+        }
+        for direction in [1.0, -1.0] {
+            scenarios.push(MarginScenario {
+                spot: Shock::Relative(direction * self.extreme_multiple * self.price_scan),
+                vol: Shock::Absolute(0.0),
+                weight: self.extreme_coverage,
+            });
+        }
+        scenarios
+    }
+}
+
+/// Margin requirements from the worst weighted loss over the scenario grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginReport {
+    /// Stand-alone requirement of each position, in portfolio order
+    pub per_position: Vec<f64>,
+    /// Requirement of each underlying with its option and stock positions netted against each
+    /// other
+    pub per_underlying: Vec<(String, f64)>,
+    /// Sum of the netted underlying requirements
+    pub total: f64,
+}
+
+fn scenario_pnl(position: &Position, scenario: &MarginScenario) -> f64 {
+    let option = position.option;
+    let shocked = option.with_market(
+        scenario.spot.apply(option.spot_price()).max(1e-8),
+        scenario.vol.apply(option.volatility()).max(1e-8),
+        option.risk_free_rate(),
+    );
+    position.units() * (shocked.bs_pricing() - option.bs_pricing())
+}
+
+fn stock_pnl(stock: &StockPosition, scenario: &MarginScenario) -> f64 {
+    stock.quantity * (scenario.spot.apply(stock.spot) - stock.spot)
+}
+
+fn requirement(pnl: impl Iterator<Item = f64>, scenarios: &[MarginScenario]) -> f64 {
+    pnl.zip(scenarios)
+        .map(|(pnl, scenario)| -pnl * scenario.weight)
+        .fold(0.0, f64::max)
+}
+
+impl Portfolio {
+    /// Scenario-grid margin, netted within each underlying but not across underlyings. Shares
+    /// held count towards the netting, so a covered call is margined on its combined P&L
+    pub fn margin(&self, params: &MarginParams) -> MarginReport {
+        let scenarios = params.scenarios();
+        let pnl: Vec<Vec<f64>> = self
+            .positions()
+            .iter()
+            .map(|position| {
+                scenarios
+                    .iter()
+                    .map(|scenario| scenario_pnl(position, scenario))
+                    .collect()
+            })
+            .collect();
+        let per_position = pnl
+            .iter()
+            .map(|row| requirement(row.iter().copied(), &scenarios))
+            .collect();
+        let per_underlying: Vec<(String, f64)> = self
+            .underlyings()
+            .into_iter()
+            .map(|underlying| {
+                let netted = scenarios.iter().enumerate().map(|(s, scenario)| {
+                    let options = self
+                        .positions()
+                        .iter()
+                        .zip(&pnl)
+                        .filter(|(position, _)| position.underlying == underlying)
+                        .map(|(_, row)| row[s]);
+                    let shares = self
+                        .stocks()
+                        .iter()
+                        .filter(|stock| stock.underlying == underlying)
+                        .map(|stock| stock_pnl(stock, scenario));
+                    options.chain(shares).sum::<f64>()
+                });
+                (underlying.to_string(), requirement(netted, &scenarios))
+            })
+            .collect();
+        let total = per_underlying.iter().map(|(_, margin)| margin).sum();
+        MarginReport {
+            per_position,
+            per_underlying,
+            total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    #[test]
+    fn test_margin_grid_and_netting() {
+        let params = MarginParams::default();
+        assert_eq!(params.scenarios().len(), 16);
+
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.25, None);
+        let mut naked = Portfolio::new();
+        naked.add(Position::new("XYZ", call, -1.0));
+        let naked_margin = naked.margin(&params);
+        assert!(naked_margin.total > 0.0);
+
+        // a long call one strike up caps the loss of the short call, so the spread nets down
+        let mut spread = naked.clone();
+        let hedge = Options::new_call(105.0, 100.0, 0.2, 0.05, 0.25, None);
+        spread.add(Position::new("XYZ", hedge, 1.0));
+        let spread_margin = spread.margin(&params);
+        assert!(spread_margin.total < naked_margin.total);
+        assert!(spread_margin.total <= 5.0);
+        assert!(spread_margin.total < spread_margin.per_position.iter().sum::<f64>());
+
+        // long options can never lose more than their premium
+        let mut long = Portfolio::new();
+        long.add(Position::new("XYZ", call, 1.0));
+        assert!(long.margin(&params).total <= call.bs_pricing());
+    }
+
+    #[test]
+    fn test_margin_nets_stock_against_options() {
+        let params = MarginParams::default();
+        let call = Options::new_call(90.0, 100.0, 0.2, 0.05, 0.25, None);
+        let mut naked = Portfolio::new();
+        naked.add(Position::new("XYZ", call, -100.0));
+        let naked_margin = naked.margin(&params);
+
+        // the shares pay out what the in-the-money short call loses on a rally
+        let covered = Portfolio::covered_call("XYZ", 100.0, call);
+        let covered_margin = covered.margin(&params);
+        assert!(covered_margin.total < naked_margin.total);
+        assert_eq!(covered_margin.per_position, naked_margin.per_position);
+
+        // stock on its own is margined on the down moves
+        let mut shares = Portfolio::new();
+        shares.add_stock(StockPosition::new("ABC", 50.0, 10.0));
+        let stock_margin = shares.margin(&params);
+        let worst = 10.0 * 50.0 * params.price_scan;
+        assert!((stock_margin.total - worst).abs() < 1e-9);
+        assert_eq!(stock_margin.per_underlying[0].0, "ABC");
+    }
+}
@@ -0,0 +1,49 @@
+use crate::Options;
+use std::collections::HashMap;
+
+/// Market inputs observed for a single underlying.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketQuote {
+    pub spot: f64,
+    pub volatility: f64,
+    pub risk_free_rate: f64,
+}
+
+/// Market state of several underlyings at a point in time.
+///
+/// `time` is measured in years from the date the contracts' maturities are quoted against, so a
+/// snapshot taken a week later has time 7/365 and shortens every contract by that much
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketSnapshot {
+    pub time: f64,
+    quotes: HashMap<String, MarketQuote>,
+}
+
+impl MarketSnapshot {
+    pub fn new(time: f64) -> Self {
+        MarketSnapshot {
+            time,
+            quotes: HashMap::new(),
+        }
+    }
+    /// Adds or replaces the quote for an underlying
+    pub fn with_quote(mut self, underlying: &str, quote: MarketQuote) -> Self {
+        self.quotes.insert(underlying.to_string(), quote);
+        self
+    }
+    pub fn insert(&mut self, underlying: &str, quote: MarketQuote) {
+        self.quotes.insert(underlying.to_string(), quote);
+    }
+    pub fn quote(&self, underlying: &str) -> Option<&MarketQuote> {
+        self.quotes.get(underlying)
+    }
+    /// Reprices the contract inputs under this snapshot. Underlyings without a quote keep the
+    /// option's own spot, vol and rate; maturity always rolls down by `time`
+    pub fn apply(&self, underlying: &str, option: &Options) -> Options {
+        let moved = match self.quote(underlying) {
+            Some(quote) => option.with_market(quote.spot, quote.volatility, quote.risk_free_rate),
+            None => *option,
+        };
+        moved.with_time_to_maturity(option.time_to_maturity() - self.time)
+    }
+}
@@ -0,0 +1,145 @@
+//! Market snapshots loaded from CSV or JSON files.
+//!
+//! CSV holds one symbol per row with a flat rate and vol, header required:
+//! `symbol,spot,rate,dividend_yield,volatility` (`dividend_yield` may be empty).
+//!
+//! JSON maps symbols to their inputs and also takes term structures and vol grids:
+//!
+//! ```json
+//! {"AAPL": {"spot": 190.0, "rate_curve": [[0.5, 0.048], [1.0, 0.045]], "dividend_yield": 0.005,
+//!           "volatility": {"expiries": [0.5, 1.0], "strikes": [180, 200], "vols": [[0.3, 0.26], [0.28, 0.25]]}},
+//!  "SPX": {"spot": 5200.0, "rate": 0.05, "volatility": 0.16}}
+//! ```
+use super::{StaticMarketData, SymbolData, VolData};
+use crate::curves::RateCurve;
+use crate::io::IoError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// One row of a CSV market file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketRow {
+    pub symbol: String,
+    pub spot: f64,
+    pub rate: f64,
+    #[serde(default)]
+    pub dividend_yield: Option<f64>,
+    pub volatility: f64,
+}
+
+/// Inputs of one symbol in a JSON market file; `rate_curve` pillars take precedence over `rate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolRecord {
+    pub spot: f64,
+    #[serde(default)]
+    pub rate: Option<f64>,
+    #[serde(default)]
+    pub rate_curve: Option<Vec<(f64, f64)>>,
+    #[serde(default)]
+    pub dividend_yield: Option<f64>,
+    pub volatility: VolData,
+}
+
+impl SymbolRecord {
+    fn into_data(self) -> SymbolData {
+        let rate_curve = match self.rate_curve {
+            Some(pillars) => RateCurve::new(pillars),
+            None => RateCurve::flat(self.rate.unwrap_or(0.0)),
+        };
+        SymbolData {
+            spot: self.spot,
+            rate_curve,
+            dividend_yield: self.dividend_yield,
+            vol: self.volatility,
+        }
+    }
+}
+
+/// Reads a CSV snapshot, rejecting rows with a non-positive spot or vol
+pub fn read_csv<R: Read>(reader: R) -> Result<StaticMarketData, IoError> {
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = csv.headers()?.clone();
+    let mut market = StaticMarketData::new();
+    for record in csv.records() {
+        let record = record?;
+        let row: MarketRow = record.deserialize(Some(&headers))?;
+        let data = SymbolData {
+            spot: row.spot,
+            rate_curve: RateCurve::flat(row.rate),
+            dividend_yield: row.dividend_yield,
+            vol: VolData::Flat(row.volatility),
+        };
+        if let Err(err) = market.insert(&row.symbol, data) {
+            let line = record.position().map_or(0, |position| position.line());
+            return Err(IoError::InvalidRow {
+                line,
+                reason: err.to_string(),
+            });
+        }
+    }
+    Ok(market)
+}
+
+/// Reads a JSON snapshot keyed by symbol
+pub fn read_json<R: Read>(reader: R) -> Result<StaticMarketData, IoError> {
+    let records: BTreeMap<String, SymbolRecord> = serde_json::from_reader(reader)?;
+    let mut market = StaticMarketData::new();
+    for (symbol, record) in records {
+        market
+            .insert(&symbol, record.into_data())
+            .map_err(|err| IoError::Invalid(err.to_string()))?;
+    }
+    Ok(market)
+}
+
+/// Loads a `.csv` or `.json` snapshot, picking the format from the extension
+pub fn load(path: &Path) -> Result<StaticMarketData, IoError> {
+    let reader = BufReader::new(File::open(path)?);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => read_csv(reader),
+        Some(ext) if ext.eq_ignore_ascii_case("json") => read_json(reader),
+        _ => Err(IoError::Invalid(format!(
+            "{} is neither a .csv nor a .json file",
+            path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::MarketDataProvider;
+
+    #[test]
+    fn test_load_csv_and_json_snapshots() {
+        let csv = "symbol,spot,rate,dividend_yield,volatility
+SPX, 5200 ,0.05,,0.16
+AAPL,190,0.045,0.005,0.25";
+        let market = read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(market.symbols(), vec!["AAPL", "SPX"]);
+        assert_eq!(market.dividend_yield("AAPL").unwrap(), Some(0.005));
+        assert_eq!(market.volatility("SPX", 5000.0, 0.5).unwrap(), 0.16);
+        let bad = "symbol,spot,rate,dividend_yield,volatility\nSPX,-1,0.05,,0.16";
+        assert!(matches!(
+            read_csv(bad.as_bytes()),
+            Err(IoError::InvalidRow { line: 2, .. })
+        ));
+
+        let json = r#"{
+            "AAPL": {"spot": 190.0, "rate_curve": [[0.5, 0.048], [1.0, 0.045]],
+                     "volatility": {"expiries": [0.5, 1.0], "strikes": [180, 200],
+                                    "vols": [[0.3, 0.26], [0.28, 0.25]]}},
+            "SPX": {"spot": 5200.0, "rate": 0.05, "volatility": 0.16}
+        }"#;
+        let market = read_json(json.as_bytes()).unwrap();
+        assert_eq!(market.rate_curve("AAPL").unwrap().rate(0.75), 0.0465);
+        assert!((market.volatility("AAPL", 190.0, 1.0).unwrap() - 0.265).abs() < 1e-12);
+        assert_eq!(market.rate_curve("SPX").unwrap().rate(2.0), 0.05);
+        assert!(load(Path::new("market.txt")).is_err());
+    }
+}
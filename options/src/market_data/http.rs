@@ -0,0 +1,426 @@
+//! Live quotes from a Polygon-style REST API (`https://api.polygon.io` by default).
+//!
+//! Fetching is async and fills a cache: `fetch_spot` takes the underlying's previous close and
+//! `fetch_option` the snapshot of one listed contract. The `MarketDataProvider` methods read that
+//! cache, so pricing code stays synchronous and never blocks on the network. The API publishes
+//! neither rates nor dividend yields; set them on the provider.
+use super::{MarketDataProvider, unknown_symbol};
+use crate::calendar::Date;
+use crate::curves::RateCurve;
+use crate::error::PricingError;
+use crate::{OptionType, Options};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.polygon.io";
+/// Environment variable `from_env` reads the API key from
+pub const API_KEY_VAR: &str = "POLYGON_API_KEY";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum LiveDataError {
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    /// The API answered with an error status or without the requested data
+    Api(String),
+    /// The provider is missing its API key or the request is malformed
+    Config(String),
+}
+
+impl fmt::Display for LiveDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveDataError::Http(err) => write!(f, "http error: {}", err),
+            LiveDataError::Json(err) => write!(f, "unexpected response: {}", err),
+            LiveDataError::Api(reason) => write!(f, "market data api: {}", reason),
+            LiveDataError::Config(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for LiveDataError {}
+
+impl From<reqwest::Error> for LiveDataError {
+    fn from(err: reqwest::Error) -> Self {
+        LiveDataError::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for LiveDataError {
+    fn from(err: serde_json::Error) -> Self {
+        LiveDataError::Json(err)
+    }
+}
+
+/// OCC-style option ticker, e.g. `O:AAPL250620C00200000` for the 2025-06-20 200 call
+pub fn option_ticker(
+    underlying: &str,
+    option_type: OptionType,
+    strike: f64,
+    expiry: Date,
+) -> String {
+    let side = match option_type {
+        OptionType::Call => 'C',
+        OptionType::Put => 'P',
+    };
+    format!(
+        "O:{}{:02}{:02}{:02}{}{:08}",
+        underlying.to_ascii_uppercase(),
+        expiry.year().rem_euclid(100),
+        expiry.month(),
+        expiry.day(),
+        side,
+        (strike * 1000.0).round() as u64
+    )
+}
+
+/// Last known market of one listed contract.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiveQuote {
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub expiry: Date,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub last: Option<f64>,
+    /// Vol published with the snapshot, if the API computes one
+    pub implied_volatility: Option<f64>,
+}
+
+impl LiveQuote {
+    /// Mid of a two-sided market, else the last trade
+    pub fn premium(&self) -> Option<f64> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) if bid > 0.0 && ask >= bid => Some(0.5 * (bid + ask)),
+            _ => self.last.filter(|last| *last > 0.0),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Aggregates {
+    #[serde(default)]
+    results: Vec<Bar>,
+}
+
+#[derive(Deserialize)]
+struct Bar {
+    /// Close
+    c: f64,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    results: ContractSnapshot,
+}
+
+#[derive(Deserialize)]
+struct ContractSnapshot {
+    #[serde(default)]
+    implied_volatility: Option<f64>,
+    #[serde(default)]
+    last_quote: Option<SnapshotQuote>,
+    #[serde(default)]
+    last_trade: Option<SnapshotTrade>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotQuote {
+    #[serde(default)]
+    bid: Option<f64>,
+    #[serde(default)]
+    ask: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotTrade {
+    #[serde(default)]
+    price: Option<f64>,
+}
+
+fn parse_previous_close(symbol: &str, body: &str) -> Result<f64, LiveDataError> {
+    let aggregates: Aggregates = serde_json::from_str(body)?;
+    match aggregates.results.first() {
+        Some(bar) if bar.c.is_finite() && bar.c > 0.0 => Ok(bar.c),
+        _ => Err(LiveDataError::Api(format!(
+            "no previous close for {}",
+            symbol
+        ))),
+    }
+}
+
+fn parse_option_snapshot(
+    body: &str,
+    option_type: OptionType,
+    strike: f64,
+    expiry: Date,
+) -> Result<LiveQuote, LiveDataError> {
+    let snapshot: Snapshot = serde_json::from_str(body)?;
+    let contract = snapshot.results;
+    let (bid, ask) = contract
+        .last_quote
+        .map_or((None, None), |quote| (quote.bid, quote.ask));
+    Ok(LiveQuote {
+        option_type,
+        strike,
+        expiry,
+        bid,
+        ask,
+        last: contract.last_trade.and_then(|trade| trade.price),
+        implied_volatility: contract.implied_volatility.filter(|vol| *vol > 0.0),
+    })
+}
+
+/// Market data provider backed by the REST API, reading spots and option quotes from the
+/// values last fetched.
+#[derive(Clone)]
+pub struct HttpMarketData {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    rate_curve: RateCurve,
+    dividend_yields: HashMap<String, f64>,
+    today: Date,
+    spots: HashMap<String, f64>,
+    quotes: HashMap<String, Vec<LiveQuote>>,
+}
+
+// The API key stays out of logs
+impl fmt::Debug for HttpMarketData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpMarketData")
+            .field("base_url", &self.base_url)
+            .field("api_key", &"<redacted>")
+            .field("rate_curve", &self.rate_curve)
+            .field("dividend_yields", &self.dividend_yields)
+            .field("today", &self.today)
+            .field("spots", &self.spots)
+            .field("quotes", &self.quotes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpMarketData {
+    /// Zero rates, no dividends and expiries measured from today's UTC date. Fails if the HTTP
+    /// client cannot be built
+    pub fn new(api_key: &str) -> Result<Self, LiveDataError> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(HttpMarketData {
+            client,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key: api_key.to_string(),
+            rate_curve: RateCurve::flat(0.0),
+            dividend_yields: HashMap::new(),
+            today: Date::today(),
+            spots: HashMap::new(),
+            quotes: HashMap::new(),
+        })
+    }
+    /// Provider keyed by the `POLYGON_API_KEY` environment variable
+    pub fn from_env() -> Result<Self, LiveDataError> {
+        match std::env::var(API_KEY_VAR) {
+            Ok(key) if !key.trim().is_empty() => HttpMarketData::new(key.trim()),
+            _ => Err(LiveDataError::Config(format!(
+                "set {} to query live market data",
+                API_KEY_VAR
+            ))),
+        }
+    }
+    /// Another deployment of the same API, e.g. a proxy or a recorded mock
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+    /// Funding curve every symbol is discounted on
+    pub fn with_rate_curve(mut self, rate_curve: RateCurve) -> Self {
+        self.rate_curve = rate_curve;
+        self
+    }
+    pub fn with_dividend_yield(mut self, symbol: &str, dividend_yield: f64) -> Self {
+        self.dividend_yields
+            .insert(symbol.to_ascii_uppercase(), dividend_yield);
+        self
+    }
+    /// Valuation date that expiry dates are converted to year fractions from
+    pub fn with_today(mut self, today: Date) -> Self {
+        self.today = today;
+        self
+    }
+    pub fn today(&self) -> Date {
+        self.today
+    }
+    /// Time to `expiry` in years from the valuation date
+    pub fn expiry_years(&self, expiry: Date) -> f64 {
+        self.today.year_fraction(expiry)
+    }
+
+    async fn get(&self, path: &str) -> Result<String, LiveDataError> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .bearer_auth(&self.api_key)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(LiveDataError::Api(format!(
+                "{} from {}: {}",
+                status, path, body
+            )));
+        }
+        Ok(body)
+    }
+
+    /// Fetches and caches the previous close of `symbol` as its spot
+    pub async fn fetch_spot(&mut self, symbol: &str) -> Result<f64, LiveDataError> {
+        let symbol = symbol.to_ascii_uppercase();
+        let body = self
+            .get(&format!("/v2/aggs/ticker/{}/prev?adjusted=true", symbol))
+            .await?;
+        let spot = parse_previous_close(&symbol, &body)?;
+        self.spots.insert(symbol, spot);
+        Ok(spot)
+    }
+
+    /// Fetches and caches the current market of one listed contract; `volatility` then answers
+    /// for its strike and expiry
+    pub async fn fetch_option(
+        &mut self,
+        symbol: &str,
+        option_type: OptionType,
+        strike: f64,
+        expiry: Date,
+    ) -> Result<LiveQuote, LiveDataError> {
+        if !(strike.is_finite() && strike > 0.0) {
+            return Err(LiveDataError::Config(format!(
+                "strike {} must be positive",
+                strike
+            )));
+        }
+        let symbol = symbol.to_ascii_uppercase();
+        let ticker = option_ticker(&symbol, option_type, strike, expiry);
+        let body = self
+            .get(&format!("/v3/snapshot/options/{}/{}", symbol, ticker))
+            .await?;
+        let quote = parse_option_snapshot(&body, option_type, strike, expiry)?;
+        let quotes = self.quotes.entry(symbol).or_default();
+        quotes.retain(|cached| {
+            (cached.option_type, cached.strike, cached.expiry) != (option_type, strike, expiry)
+        });
+        quotes.push(quote);
+        Ok(quote)
+    }
+
+    /// Fetched quotes of `symbol`, in fetch order
+    pub fn quotes(&self, symbol: &str) -> &[LiveQuote] {
+        self.quotes
+            .get(&symbol.to_ascii_uppercase())
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+impl MarketDataProvider for HttpMarketData {
+    fn spot(&self, symbol: &str) -> Result<f64, PricingError> {
+        self.spots
+            .get(&symbol.to_ascii_uppercase())
+            .copied()
+            .ok_or_else(|| unknown_symbol(symbol))
+    }
+    fn rate_curve(&self, symbol: &str) -> Result<RateCurve, PricingError> {
+        self.spot(symbol)?;
+        Ok(self.rate_curve.clone())
+    }
+    fn dividend_yield(&self, symbol: &str) -> Result<Option<f64>, PricingError> {
+        self.spot(symbol)?;
+        Ok(self
+            .dividend_yields
+            .get(&symbol.to_ascii_uppercase())
+            .copied())
+    }
+    /// The published vol of the fetched contract at `strike` and `expiry` (within half a day),
+    /// else the vol implied by its premium
+    fn volatility(&self, symbol: &str, strike: f64, expiry: f64) -> Result<f64, PricingError> {
+        let spot = self.spot(symbol)?;
+        let quote = self
+            .quotes(symbol)
+            .iter()
+            .filter(|quote| (quote.strike - strike).abs() < 1e-9)
+            .find(|quote| (self.expiry_years(quote.expiry) - expiry).abs() < 0.5 / 365.0)
+            .ok_or_else(|| {
+                PricingError::InvalidInput(format!(
+                    "no fetched {} option at strike {} and expiry {}",
+                    symbol, strike, expiry
+                ))
+            })?;
+        if let Some(vol) = quote.implied_volatility {
+            return Ok(vol);
+        }
+        let premium = quote.premium().ok_or_else(|| {
+            PricingError::InvalidInput(format!("{} {} has no market", symbol, strike))
+        })?;
+        let option = Options::new_with_type(
+            quote.option_type,
+            strike,
+            spot,
+            0.2,
+            self.rate_curve.rate(expiry),
+            expiry,
+            self.dividend_yield(symbol)?,
+        );
+        option.implied_volatility(premium)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_responses_into_provider() {
+        let expiry = Date::new(2025, 6, 20).unwrap();
+        assert_eq!(
+            option_ticker("aapl", OptionType::Call, 200.0, expiry),
+            "O:AAPL250620C00200000"
+        );
+        assert_eq!(
+            option_ticker("SPY", OptionType::Put, 512.5, expiry),
+            "O:SPY250620P00512500"
+        );
+
+        let prev =
+            r#"{"ticker":"AAPL","status":"OK","results":[{"T":"AAPL","c":196.58,"o":195.0}]}"#;
+        assert_eq!(parse_previous_close("AAPL", prev).unwrap(), 196.58);
+        let empty = r#"{"ticker":"ZZZZ","status":"OK","resultsCount":0}"#;
+        assert!(matches!(
+            parse_previous_close("ZZZZ", empty),
+            Err(LiveDataError::Api(_))
+        ));
+
+        let snapshot = r#"{"status":"OK","results":{
+            "details":{"contract_type":"call","strike_price":200,"expiration_date":"2025-06-20"},
+            "last_quote":{"bid":8.1,"ask":8.3},"last_trade":{"price":8.25}}}"#;
+        let quote = parse_option_snapshot(snapshot, OptionType::Call, 200.0, expiry).unwrap();
+        assert!((quote.premium().unwrap() - 8.2).abs() < 1e-12);
+        assert_eq!(quote.implied_volatility, None);
+
+        // a provider seeded as if both requests had returned these bodies
+        let mut market = HttpMarketData::new("secret-key")
+            .unwrap()
+            .with_today(Date::new(2024, 12, 20).unwrap())
+            .with_rate_curve(RateCurve::flat(0.045));
+        market.spots.insert("AAPL".to_string(), 196.58);
+        market.quotes.insert("AAPL".to_string(), vec![quote]);
+        let years = market.expiry_years(expiry);
+        let vol = market.volatility("AAPL", 200.0, years).unwrap();
+        let option = Options::new_call(200.0, 196.58, vol, 0.045, years, None);
+        assert!((option.bs_pricing() - 8.2).abs() < 1e-6);
+        assert!(market.volatility("AAPL", 210.0, years).is_err());
+        assert!(market.spot("MSFT").is_err());
+        assert!(!format!("{:?}", market).contains("secret-key"));
+    }
+}
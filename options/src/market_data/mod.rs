@@ -0,0 +1,299 @@
+// Market inputs looked up by symbol, so pricing code does not care whether they come from a file,
+// a test fixture or a live feed.
+#[cfg(feature = "io")]
+pub mod file;
+#[cfg(feature = "live-data")]
+pub mod http;
+
+use crate::Options;
+use crate::curves::RateCurve;
+use crate::error::PricingError;
+use crate::math::interp::bracket;
+use crate::math::sum::compensated_sum;
+use crate::portfolio::Portfolio;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Source of the market inputs of an underlying. Lookups fail for unknown symbols.
+pub trait MarketDataProvider {
+    fn spot(&self, symbol: &str) -> Result<f64, PricingError>;
+    /// Funding curve the symbol's options are discounted on
+    fn rate_curve(&self, symbol: &str) -> Result<RateCurve, PricingError>;
+    fn dividend_yield(&self, symbol: &str) -> Result<Option<f64>, PricingError>;
+    /// Implied volatility of the symbol at a strike and expiry in years
+    fn volatility(&self, symbol: &str, strike: f64, expiry: f64) -> Result<f64, PricingError>;
+}
+
+pub(crate) fn unknown_symbol(symbol: &str) -> PricingError {
+    PricingError::InvalidInput(format!("no market data for {}", symbol))
+}
+
+/// Implied volatilities of one symbol.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum VolData {
+    Flat(f64),
+    /// Vols on an (expiry, strike) grid, interpolated bilinearly and held flat outside it
+    Grid {
+        expiries: Vec<f64>,
+        strikes: Vec<f64>,
+        /// Indexed [expiry][strike]
+        vols: Vec<Vec<f64>>,
+    },
+}
+
+impl VolData {
+    pub fn vol(&self, strike: f64, expiry: f64) -> f64 {
+        match self {
+            VolData::Flat(vol) => *vol,
+            VolData::Grid {
+                expiries,
+                strikes,
+                vols,
+            } => {
+                let (i, wt) = bracket(expiries, expiry);
+                let (j, wk) = bracket(strikes, strike);
+                let at = |i: usize, j: usize| {
+                    let row = &vols[i.min(vols.len() - 1)];
+                    row[j.min(row.len() - 1)]
+                };
+                let lower = at(i, j) * (1.0 - wk) + at(i, j + 1) * wk;
+                let upper = at(i + 1, j) * (1.0 - wk) + at(i + 1, j + 1) * wk;
+                lower * (1.0 - wt) + upper * wt
+            }
+        }
+    }
+    fn validate(&self, symbol: &str) -> Result<(), PricingError> {
+        let invalid = |reason: &str| {
+            Err(PricingError::InvalidInput(format!(
+                "{} volatility {}",
+                symbol, reason
+            )))
+        };
+        match self {
+            VolData::Flat(vol) if !(vol.is_finite() && *vol > 0.0) => invalid("must be positive"),
+            VolData::Grid {
+                expiries,
+                strikes,
+                vols,
+            } => {
+                if expiries.is_empty()
+                    || strikes.is_empty()
+                    || vols.len() != expiries.len()
+                    || vols.iter().any(|row| row.len() != strikes.len())
+                {
+                    return invalid("grid must be one row of strikes per expiry");
+                }
+                let sorted = |nodes: &[f64]| nodes.windows(2).all(|w| w[0] < w[1]);
+                if !sorted(expiries) || !sorted(strikes) {
+                    return invalid("grid nodes must increase");
+                }
+                if vols
+                    .iter()
+                    .flatten()
+                    .any(|vol| !(vol.is_finite() && *vol > 0.0))
+                {
+                    return invalid("grid entries must be positive");
+                }
+                Ok(())
+            }
+            VolData::Flat(_) => Ok(()),
+        }
+    }
+}
+
+/// Market inputs of one symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolData {
+    pub spot: f64,
+    pub rate_curve: RateCurve,
+    pub dividend_yield: Option<f64>,
+    pub vol: VolData,
+}
+
+/// In-memory market snapshot, filled by hand or loaded from a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StaticMarketData {
+    symbols: HashMap<String, SymbolData>,
+}
+
+impl StaticMarketData {
+    pub fn new() -> Self {
+        StaticMarketData::default()
+    }
+    /// Adds or replaces a symbol, rejecting non-positive spots and malformed vol grids
+    pub fn insert(&mut self, symbol: &str, data: SymbolData) -> Result<(), PricingError> {
+        if !(data.spot.is_finite() && data.spot > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "{} spot {} must be positive",
+                symbol, data.spot
+            )));
+        }
+        data.vol.validate(symbol)?;
+        self.symbols.insert(symbol.to_string(), data);
+        Ok(())
+    }
+    pub fn get(&self, symbol: &str) -> Option<&SymbolData> {
+        self.symbols.get(symbol)
+    }
+    /// Symbols in sorted order
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.symbols.keys().map(String::as_str).collect();
+        symbols.sort_unstable();
+        symbols
+    }
+    fn data(&self, symbol: &str) -> Result<&SymbolData, PricingError> {
+        self.get(symbol).ok_or_else(|| unknown_symbol(symbol))
+    }
+}
+
+impl MarketDataProvider for StaticMarketData {
+    fn spot(&self, symbol: &str) -> Result<f64, PricingError> {
+        Ok(self.data(symbol)?.spot)
+    }
+    fn rate_curve(&self, symbol: &str) -> Result<RateCurve, PricingError> {
+        Ok(self.data(symbol)?.rate_curve.clone())
+    }
+    fn dividend_yield(&self, symbol: &str) -> Result<Option<f64>, PricingError> {
+        Ok(self.data(symbol)?.dividend_yield)
+    }
+    fn volatility(&self, symbol: &str, strike: f64, expiry: f64) -> Result<f64, PricingError> {
+        Ok(self.data(symbol)?.vol.vol(strike, expiry))
+    }
+}
+
+impl Options {
+    /// The contract with spot, vol, rate and dividend yield taken from `provider`; strike, type
+    /// and maturity are kept
+    pub fn at_market(
+        &self,
+        provider: &dyn MarketDataProvider,
+        symbol: &str,
+    ) -> Result<Options, PricingError> {
+        let (strike, expiry) = (self.strike_price(), self.time_to_maturity());
+        Ok(Options::new_with_type(
+            self.option_type(),
+            strike,
+            provider.spot(symbol)?,
+            provider.volatility(symbol, strike, expiry)?,
+            provider.rate_curve(symbol)?.rate(expiry),
+            expiry,
+            provider.dividend_yield(symbol)?,
+        ))
+    }
+    /// Black-Scholes price at the provider's market
+    pub fn price_at_market(
+        &self,
+        provider: &dyn MarketDataProvider,
+        symbol: &str,
+    ) -> Result<f64, PricingError> {
+        Ok(self.at_market(provider, symbol)?.bs_pricing())
+    }
+}
+
+impl Portfolio {
+    /// The portfolio with every option and stock moved to the provider's market, each on its
+    /// own underlying; cash is unchanged
+    pub fn at_market(&self, provider: &dyn MarketDataProvider) -> Result<Portfolio, PricingError> {
+        let mut moved = Portfolio::new();
+        for position in self.positions() {
+            let mut position = position.clone();
+            position.option = position.option.at_market(provider, &position.underlying)?;
+            moved.add(position);
+        }
+        for stock in self.stocks() {
+            let mut stock = stock.clone();
+            stock.spot = provider.spot(&stock.underlying)?;
+            moved.add_stock(stock);
+        }
+        for cash in self.cash() {
+            moved.add_cash(*cash);
+        }
+        Ok(moved)
+    }
+    /// Unrealized P&L of the open option positions at the provider's market, before fees.
+    /// Fails if the provider lacks an underlying or a position has no entry price
+    pub fn unrealized_pnl(&self, provider: &dyn MarketDataProvider) -> Result<f64, PricingError> {
+        let pnl = self
+            .positions()
+            .iter()
+            .map(|position| {
+                let Some(entry) = position.entry_price else {
+                    return Err(PricingError::InvalidInput(format!(
+                        "position on {} has no entry price",
+                        position.underlying
+                    )));
+                };
+                let price = position
+                    .option
+                    .price_at_market(provider, &position.underlying)?;
+                Ok(position.units() * (price - entry))
+            })
+            .collect::<Result<Vec<f64>, PricingError>>()?;
+        Ok(compensated_sum(pnl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::{Position, StockPosition};
+
+    #[test]
+    fn test_pricing_from_provider() {
+        let mut market = StaticMarketData::new();
+        let grid = VolData::Grid {
+            expiries: vec![0.5, 1.0],
+            strikes: vec![90.0, 110.0],
+            vols: vec![vec![0.30, 0.20], vec![0.26, 0.22]],
+        };
+        market
+            .insert(
+                "AAA",
+                SymbolData {
+                    spot: 100.0,
+                    rate_curve: RateCurve::new(vec![(0.5, 0.04), (1.0, 0.05)]),
+                    dividend_yield: None,
+                    vol: grid.clone(),
+                },
+            )
+            .unwrap();
+        assert!((grid.vol(100.0, 0.75) - 0.245).abs() < 1e-12);
+        assert_eq!(grid.vol(50.0, 2.0), 0.26);
+
+        let stale = Options::new_call(100.0, 80.0, 0.5, 0.0, 1.0, None);
+        let live = stale.at_market(&market, "AAA").unwrap();
+        let expected = Options::new_call(100.0, 100.0, 0.24, 0.05, 1.0, None);
+        assert!((live.bs_pricing() - expected.bs_pricing()).abs() < 1e-12);
+        assert!(stale.price_at_market(&market, "BBB").is_err());
+
+        let mut book = Portfolio::new();
+        book.add(Position::new("AAA", stale, 2.0));
+        book.add_stock(StockPosition::new("AAA", 80.0, -50.0));
+        let moved = book.at_market(&market).unwrap();
+        let value = 2.0 * expected.bs_pricing() - 50.0 * 100.0;
+        assert!((moved.value() - value).abs() < 1e-9);
+        assert!(book.unrealized_pnl(&market).is_err());
+        let mut entered = Portfolio::new();
+        entered.add(
+            Position::new("AAA", stale, 2.0)
+                .with_multiplier(100.0)
+                .with_entry(5.0, 1.0),
+        );
+        let pnl = entered.unrealized_pnl(&market).unwrap();
+        assert!((pnl - 200.0 * (expected.bs_pricing() - 5.0)).abs() < 1e-9);
+        let bad_grid = SymbolData {
+            spot: 100.0,
+            rate_curve: RateCurve::flat(0.05),
+            dividend_yield: None,
+            vol: VolData::Grid {
+                expiries: vec![1.0],
+                strikes: vec![90.0, 110.0],
+                vols: vec![vec![0.2]],
+            },
+        };
+        assert!(market.insert("CCC", bad_grid).is_err());
+    }
+}
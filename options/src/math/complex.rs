@@ -0,0 +1,181 @@
+// Complex arithmetic for characteristic functions, which the Fourier pricers evaluate along the
+// real axis and, for cumulants, along the imaginary one. Only the operations those formulas use
+// are provided; logarithms and square roots take the principal branch.
+use crate::math::float::{atan2, cos, exp, ln, sin, sqrt};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Complex number `re + i im`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+/// The imaginary unit
+pub const I: Complex = Complex { re: 0.0, im: 1.0 };
+
+impl Complex {
+    pub const fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+    pub const fn real(re: f64) -> Self {
+        Complex { re, im: 0.0 }
+    }
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+    /// Modulus |z|
+    pub fn abs(self) -> f64 {
+        sqrt(self.re * self.re + self.im * self.im)
+    }
+    /// Argument in (-π, π]
+    pub fn arg(self) -> f64 {
+        atan2(self.im, self.re)
+    }
+    pub fn exp(self) -> Self {
+        let modulus = exp(self.re);
+        Complex::new(modulus * cos(self.im), modulus * sin(self.im))
+    }
+    /// Principal logarithm, ln|z| + i arg z
+    pub fn ln(self) -> Self {
+        Complex::new(ln(self.abs()), self.arg())
+    }
+    /// Principal square root, with a non-negative real part
+    pub fn sqrt(self) -> Self {
+        // the larger part from the modulus, the smaller from im = 2 re·im without cancellation
+        let modulus = self.abs();
+        if modulus == 0.0 {
+            return Complex::default();
+        }
+        if self.re >= 0.0 {
+            let re = sqrt(0.5 * (modulus + self.re));
+            Complex::new(re, self.im / (2.0 * re))
+        } else {
+            let im = sqrt(0.5 * (modulus - self.re));
+            let im = if self.im < 0.0 { -im } else { im };
+            Complex::new(self.im / (2.0 * im), im)
+        }
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Complex::real(re)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, other: Complex) -> Complex {
+        let norm = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / norm,
+            (self.im * other.re - self.re * other.im) / norm,
+        )
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+    fn neg(self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl Add<f64> for Complex {
+    type Output = Complex;
+    fn add(self, other: f64) -> Complex {
+        Complex::new(self.re + other, self.im)
+    }
+}
+
+impl Sub<f64> for Complex {
+    type Output = Complex;
+    fn sub(self, other: f64) -> Complex {
+        Complex::new(self.re - other, self.im)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+    fn mul(self, other: f64) -> Complex {
+        Complex::new(self.re * other, self.im * other)
+    }
+}
+
+impl Div<f64> for Complex {
+    type Output = Complex;
+    fn div(self, other: f64) -> Complex {
+        Complex::new(self.re / other, self.im / other)
+    }
+}
+
+impl Add<Complex> for f64 {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        other + self
+    }
+}
+
+impl Sub<Complex> for f64 {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self - other.re, -other.im)
+    }
+}
+
+impl Mul<Complex> for f64 {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        other * self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f64::consts::PI;
+
+    #[test]
+    fn test_complex_functions() {
+        let z = Complex::new(1.5, -2.0);
+        let close = |a: Complex, b: Complex| (a - b).abs() < 1e-14;
+        assert!(close(z * z.conj(), Complex::real(6.25)));
+        assert!(close(z / z, Complex::real(1.0)));
+        assert!(close(z.ln().exp(), z));
+        assert!(close(z.sqrt() * z.sqrt(), z));
+        assert!(z.sqrt().re >= 0.0);
+        // Euler's identity and the principal branch on the negative real axis
+        assert!(close((I * PI).exp(), Complex::real(-1.0)));
+        assert!(close(Complex::real(-4.0).sqrt(), I * 2.0));
+        assert!((Complex::real(-1.0).ln().im - PI).abs() < 1e-15);
+        // a small imaginary part survives next to a large real one
+        let root = Complex::new(4.0, 1e-12).sqrt();
+        assert!((root.im - 2.5e-13).abs() < 1e-27);
+        assert!(close(1.0 - I * I, Complex::real(2.0)));
+    }
+}
@@ -0,0 +1,128 @@
+// Elementary functions used throughout the pricers. With the `strict_math` feature they come from
+// the pure-Rust libm port instead of the platform's C library, so results are bit-for-bit
+// identical on every target. `no_std` builds have no platform functions and always use libm.
+// Square roots are correctly rounded everywhere and only need a wrapper for `no_std`.
+
+#[inline]
+pub fn exp(x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::exp(x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.exp()
+    }
+}
+
+#[inline]
+pub fn ln(x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::log(x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.ln()
+    }
+}
+
+#[inline]
+pub fn powf(x: f64, y: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::pow(x, y)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.powf(y)
+    }
+}
+
+#[inline]
+pub fn sin(x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::sin(x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.sin()
+    }
+}
+
+#[inline]
+pub fn asin(x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::asin(x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.asin()
+    }
+}
+
+#[inline]
+pub fn cos(x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::cos(x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.cos()
+    }
+}
+
+#[inline]
+pub fn atan2(y: f64, x: f64) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::atan2(y, x)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        y.atan2(x)
+    }
+}
+
+/// Integer power; strict mode routes it through `pow` since `powi` lowering differs by target
+#[inline]
+pub fn powi(x: f64, n: i32) -> f64 {
+    #[cfg(any(feature = "strict_math", not(feature = "std")))]
+    {
+        libm::pow(x, n as f64)
+    }
+    #[cfg(all(not(feature = "strict_math"), feature = "std"))]
+    {
+        x.powi(n)
+    }
+}
+
+#[inline]
+pub fn sqrt(x: f64) -> f64 {
+    #[cfg(not(feature = "std"))]
+    {
+        libm::sqrt(x)
+    }
+    #[cfg(feature = "std")]
+    {
+        x.sqrt()
+    }
+}
+
+/// Gamma function Γ(x); `std` has no stable one, so it always comes from libm
+#[inline]
+pub fn gamma(x: f64) -> f64 {
+    libm::tgamma(x)
+}
+
+/// ln |Γ(x)|, from libm like `gamma`
+#[inline]
+pub fn ln_gamma(x: f64) -> f64 {
+    libm::lgamma(x)
+}
+
+/// Whether the crate was built with platform-independent elementary functions
+pub const STRICT_MATH: bool = cfg!(any(feature = "strict_math", not(feature = "std")));
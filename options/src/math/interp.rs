@@ -0,0 +1,340 @@
+// One- and two-dimensional interpolation on sorted nodes, held flat beyond the outermost nodes.
+// Cubic splines are stored in Hermite form, a value and a slope per node: the natural spline's
+// slopes come from its tridiagonal system, and the monotone variant clips them with Hyman's
+// filter so that monotone data (discount factors, total variance across expiries) never
+// overshoots between nodes.
+use crate::error::PricingError;
+use crate::math::linalg::{Matrix, solve_tridiagonal};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Index of the node at or below `x` and the weight of the next node, clamped to the ends.
+pub fn bracket(nodes: &[f64], x: f64) -> (usize, f64) {
+    if nodes.len() < 2 || x <= nodes[0] {
+        return (0, 0.0);
+    }
+    let last = nodes.len() - 1;
+    if x >= nodes[last] {
+        return (last - 1, 1.0);
+    }
+    let i = nodes.partition_point(|node| *node <= x) - 1;
+    (i, (x - nodes[i]) / (nodes[i + 1] - nodes[i]))
+}
+
+/// Piecewise linear interpolation of `ys` at `x`, flat beyond the ends. Expects at least one node
+pub fn linear(xs: &[f64], ys: &[f64], x: f64) -> f64 {
+    let (i, weight) = bracket(xs, x);
+    if weight == 0.0 {
+        return ys[i];
+    }
+    ys[i] + weight * (ys[i + 1] - ys[i])
+}
+
+/// Shape of a one-dimensional interpolant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Twice differentiable cubic spline with zero curvature at both ends
+    NaturalCubic,
+    /// Natural cubic slopes clipped by Hyman's filter: monotone wherever the data is, at the
+    /// cost of curvature jumps at the nodes
+    MonotoneCubic,
+}
+
+/// Interpolant through (x, y) nodes with strictly increasing x.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spline {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    /// dy/dx at each node
+    slopes: Vec<f64>,
+    method: Interpolation,
+}
+
+impl Spline {
+    /// Fails without nodes, with unequal lengths or with x not strictly increasing
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, method: Interpolation) -> Result<Self, PricingError> {
+        validate_nodes(&xs, ys.len())?;
+        let secants: Vec<f64> = (1..xs.len())
+            .map(|i| (ys[i] - ys[i - 1]) / (xs[i] - xs[i - 1]))
+            .collect();
+        let slopes = match method {
+            Interpolation::Linear => secants.clone(),
+            Interpolation::NaturalCubic => natural_slopes(&xs, &secants),
+            Interpolation::MonotoneCubic => hyman_filter(natural_slopes(&xs, &secants), &secants),
+        };
+        Ok(Spline {
+            xs,
+            ys,
+            slopes,
+            method,
+        })
+    }
+    pub fn xs(&self) -> &[f64] {
+        &self.xs
+    }
+    pub fn ys(&self) -> &[f64] {
+        &self.ys
+    }
+    pub fn method(&self) -> Interpolation {
+        self.method
+    }
+    pub fn value(&self, x: f64) -> f64 {
+        if self.method == Interpolation::Linear {
+            return linear(&self.xs, &self.ys, x);
+        }
+        if self.xs.len() < 2 {
+            return self.ys[0];
+        }
+        let (i, t) = bracket(&self.xs, x);
+        let h = self.xs[i + 1] - self.xs[i];
+        let (t2, t3) = (t * t, t * t * t);
+        (2.0 * t3 - 3.0 * t2 + 1.0) * self.ys[i]
+            + (t3 - 2.0 * t2 + t) * h * self.slopes[i]
+            + (3.0 * t2 - 2.0 * t3) * self.ys[i + 1]
+            + (t3 - t2) * h * self.slopes[i + 1]
+    }
+    /// dy/dx at `x`, zero beyond the ends where the value is held flat
+    pub fn derivative(&self, x: f64) -> f64 {
+        let n = self.xs.len();
+        if n < 2 || x < self.xs[0] || x > self.xs[n - 1] {
+            return 0.0;
+        }
+        let (i, t) = bracket(&self.xs, x);
+        if self.method == Interpolation::Linear {
+            return self.slopes[i];
+        }
+        let h = self.xs[i + 1] - self.xs[i];
+        let t2 = t * t;
+        ((6.0 * t2 - 6.0 * t) * (self.ys[i] - self.ys[i + 1])) / h
+            + (3.0 * t2 - 4.0 * t + 1.0) * self.slopes[i]
+            + (3.0 * t2 - 2.0 * t) * self.slopes[i + 1]
+    }
+}
+
+fn validate_nodes(xs: &[f64], values: usize) -> Result<(), PricingError> {
+    if xs.is_empty() || xs.len() != values {
+        return Err(PricingError::InvalidInput(format!(
+            "interpolation needs one value per node, got {} nodes and {} values",
+            xs.len(),
+            values
+        )));
+    }
+    if !xs.windows(2).all(|w| w[1] > w[0]) {
+        return Err(PricingError::InvalidInput(
+            "interpolation nodes must be strictly increasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Node slopes of the natural cubic spline, from its second derivatives M with M₀ = Mₙ = 0:
+// hᵢ₋₁Mᵢ₋₁ + 2(hᵢ₋₁ + hᵢ)Mᵢ + hᵢMᵢ₊₁ = 6(Sᵢ - Sᵢ₋₁)
+fn natural_slopes(xs: &[f64], secants: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    if n < 3 {
+        return vec![secants.first().copied().unwrap_or(0.0); n];
+    }
+    let h: Vec<f64> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let interior = n - 2;
+    let lower: Vec<f64> = (0..interior).map(|i| h[i]).collect();
+    let diag: Vec<f64> = (0..interior).map(|i| 2.0 * (h[i] + h[i + 1])).collect();
+    let upper: Vec<f64> = (0..interior).map(|i| h[i + 1]).collect();
+    let rhs: Vec<f64> = (0..interior)
+        .map(|i| 6.0 * (secants[i + 1] - secants[i]))
+        .collect();
+    let mut curvature = vec![0.0; n];
+    curvature[1..n - 1].copy_from_slice(&solve_tridiagonal(&lower, &diag, &upper, &rhs));
+    let mut slopes: Vec<f64> = (0..n - 1)
+        .map(|i| secants[i] - h[i] * (2.0 * curvature[i] + curvature[i + 1]) / 6.0)
+        .collect();
+    slopes.push(secants[n - 2] + h[n - 2] * (curvature[n - 2] + 2.0 * curvature[n - 1]) / 6.0);
+    slopes
+}
+
+// Hyman (1983): each slope keeps the sign of the adjacent secants and at most three times the
+// smaller of them, and is zero at local extrema of the data
+fn hyman_filter(mut slopes: Vec<f64>, secants: &[f64]) -> Vec<f64> {
+    let clip = |slope: f64, bound: f64, sign: f64| {
+        if slope * sign > 0.0 {
+            sign * (slope.abs()).min(3.0 * bound)
+        } else {
+            0.0
+        }
+    };
+    let n = slopes.len();
+    if n < 2 {
+        return slopes;
+    }
+    slopes[0] = clip(slopes[0], secants[0].abs(), secants[0].signum());
+    slopes[n - 1] = clip(slopes[n - 1], secants[n - 2].abs(), secants[n - 2].signum());
+    for i in 1..n - 1 {
+        let (left, right) = (secants[i - 1], secants[i]);
+        slopes[i] = if left * right > 0.0 {
+            clip(slopes[i], left.abs().min(right.abs()), right.signum())
+        } else {
+            0.0
+        };
+    }
+    slopes
+}
+
+/// Shape of a two-dimensional interpolant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation2d {
+    #[default]
+    Bilinear,
+    /// Natural cubic splines along y on each row of the grid, then along x through the results
+    Bicubic,
+}
+
+/// Interpolant over a rectangular grid of values indexed [x][y], held flat outside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid2d {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    values: Matrix,
+    method: Interpolation2d,
+    // Splines of each row along y, for bicubic lookups
+    rows: Vec<Spline>,
+}
+
+impl Grid2d {
+    /// Fails unless both axes are strictly increasing and `values` has one row per x with one
+    /// value per y
+    pub fn new(
+        xs: Vec<f64>,
+        ys: Vec<f64>,
+        values: Matrix,
+        method: Interpolation2d,
+    ) -> Result<Self, PricingError> {
+        validate_nodes(&xs, values.len())?;
+        for row in &values {
+            validate_nodes(&ys, row.len())?;
+        }
+        let rows = match method {
+            Interpolation2d::Bilinear => Vec::new(),
+            Interpolation2d::Bicubic => values
+                .iter()
+                .map(|row| Spline::new(ys.clone(), row.clone(), Interpolation::NaturalCubic))
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Grid2d {
+            xs,
+            ys,
+            values,
+            method,
+            rows,
+        })
+    }
+    pub fn value(&self, x: f64, y: f64) -> f64 {
+        match self.method {
+            Interpolation2d::Bilinear => {
+                let (i, wx) = bracket(&self.xs, x);
+                let (j, wy) = bracket(&self.ys, y);
+                let i1 = (i + 1).min(self.xs.len() - 1);
+                let j1 = (j + 1).min(self.ys.len() - 1);
+                let row = |i: usize| {
+                    let values = &self.values[i];
+                    values[j] + wy * (values[j1] - values[j])
+                };
+                row(i) + wx * (row(i1) - row(i))
+            }
+            Interpolation2d::Bicubic => {
+                let column: Vec<f64> = self.rows.iter().map(|row| row.value(y)).collect();
+                Spline::new(self.xs.clone(), column, Interpolation::NaturalCubic)
+                    .map(|spline| spline.value(x))
+                    .unwrap_or(f64::NAN)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splines() {
+        let xs = vec![0.0, 0.5, 1.5, 2.0, 4.0];
+        // every method reproduces a straight line, and holds its ends flat beyond the nodes
+        let line: Vec<f64> = xs.iter().map(|x| 1.0 + 2.0 * x).collect();
+        for method in [
+            Interpolation::Linear,
+            Interpolation::NaturalCubic,
+            Interpolation::MonotoneCubic,
+        ] {
+            let spline = Spline::new(xs.clone(), line.clone(), method).unwrap();
+            for x in [0.2, 1.0, 3.3] {
+                assert!((spline.value(x) - (1.0 + 2.0 * x)).abs() < 1e-12);
+                assert!((spline.derivative(x) - 2.0).abs() < 1e-12);
+            }
+            assert_eq!(spline.value(-1.0), 1.0);
+            assert_eq!(spline.value(5.0), 9.0);
+        }
+        // the natural spline passes through its nodes with zero end curvature
+        let ys = vec![0.0, 0.8, 0.3, 0.9, 1.2];
+        let natural = Spline::new(xs.clone(), ys.clone(), Interpolation::NaturalCubic).unwrap();
+        for (x, y) in xs.iter().zip(&ys) {
+            assert!((natural.value(*x) - y).abs() < 1e-14);
+        }
+        let curvature = |x: f64| (natural.derivative(x + 1e-5) - natural.derivative(x)) / 1e-5;
+        assert!(curvature(0.0).abs() < 1e-3 && curvature(4.0 - 1e-5).abs() < 1e-3);
+        // slopes are continuous across interior nodes
+        assert!((natural.derivative(1.5 - 1e-9) - natural.derivative(1.5 + 1e-9)).abs() < 1e-6);
+
+        assert!(Spline::new(vec![0.0, 0.0], vec![1.0, 2.0], Interpolation::Linear).is_err());
+        assert!(Spline::new(vec![0.0, 1.0], vec![1.0], Interpolation::Linear).is_err());
+    }
+
+    #[test]
+    fn test_monotone_spline_does_not_overshoot() {
+        // a step: the natural spline rings around it, the monotone one stays within the data
+        let xs = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let ys = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let natural = Spline::new(xs.clone(), ys.clone(), Interpolation::NaturalCubic).unwrap();
+        let monotone = Spline::new(xs, ys, Interpolation::MonotoneCubic).unwrap();
+        let grid: Vec<f64> = (0..=500).map(|i| i as f64 / 100.0).collect();
+        assert!(grid.iter().any(|&x| natural.value(x) < -1e-3));
+        let values: Vec<f64> = grid.iter().map(|&x| monotone.value(x)).collect();
+        assert!(values.windows(2).all(|w| w[1] >= w[0] - 1e-15));
+        assert!(values.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn test_grid_interpolation() {
+        let xs = vec![0.0, 1.0, 2.0, 3.0];
+        let ys = vec![0.0, 0.5, 1.0, 2.0, 3.0];
+        let grid = |f: &dyn Fn(f64, f64) -> f64| -> Matrix {
+            xs.iter()
+                .map(|&x| ys.iter().map(|&y| f(x, y)).collect())
+                .collect()
+        };
+        // bilinear functions are reproduced by both methods
+        let bilinear = |x: f64, y: f64| 1.0 + x - 2.0 * y + 0.5 * x * y;
+        for method in [Interpolation2d::Bilinear, Interpolation2d::Bicubic] {
+            let surface = Grid2d::new(xs.clone(), ys.clone(), grid(&bilinear), method).unwrap();
+            assert!((surface.value(1.3, 0.7) - bilinear(1.3, 0.7)).abs() < 1e-12);
+            assert!((surface.value(-1.0, 4.0) - bilinear(0.0, 3.0)).abs() < 1e-12);
+        }
+        // bicubic is closer on a smooth curved function
+        let smooth = |x: f64, y: f64| (0.7 * x).sin() * (0.9 * y).cos();
+        let error = |method| {
+            let surface = Grid2d::new(xs.clone(), ys.clone(), grid(&smooth), method).unwrap();
+            (surface.value(1.4, 1.6) - smooth(1.4, 1.6)).abs()
+        };
+        assert!(error(Interpolation2d::Bicubic) < 0.5 * error(Interpolation2d::Bilinear));
+        assert!(
+            Grid2d::new(
+                xs.clone(),
+                ys.clone(),
+                vec![vec![0.0; 5]; 3],
+                Default::default()
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::math::float::sqrt;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Dense row-major matrix.
+pub type Matrix = Vec<Vec<f64>>;
+
+/// Cap on the Jacobi sweeps of `symmetric_eigen`; a handful suffice for small matrices
+const JACOBI_SWEEPS: usize = 100;
+
+pub fn identity(n: usize) -> Matrix {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Lower-triangular Cholesky factor L with A = L * Lᵀ.
+///
+/// Returns None if the matrix is not square or not positive definite
+pub fn cholesky(a: &[Vec<f64>]) -> Option<Matrix> {
+    let n = a.len();
+    if a.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let diag = a[i][i] - sum;
+                if diag <= 0.0 {
+                    return None;
+                }
+                l[i][j] = sqrt(diag);
+            } else {
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solves A x = b given the Cholesky factor L of A
+pub fn cholesky_solve(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let n = l.len();
+    let mut y = vec![0.0; n];
+    for i in 0..n {
+        let sum: f64 = (0..i).map(|k| l[i][k] * y[k]).sum();
+        y[i] = (b[i] - sum) / l[i][i];
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (y[i] - sum) / l[i][i];
+    }
+    x
+}
+
+/// Eigenvalues of a symmetric matrix in decreasing order, with the unit eigenvectors as the
+/// matching columns, by cyclic Jacobi rotations.
+///
+/// Returns None if the matrix is not square
+pub fn symmetric_eigen(a: &[Vec<f64>]) -> Option<(Vec<f64>, Matrix)> {
+    let n = a.len();
+    if a.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    let mut m = a.to_vec();
+    let mut v = identity(n);
+    let scale: f64 = a.iter().flatten().map(|x| x * x).sum();
+    for _ in 0..JACOBI_SWEEPS {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| m[i][j] * m[i][j])
+            .sum();
+        if off <= 1e-30 * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if m[p][q] == 0.0 {
+                    continue;
+                }
+                // Rotation zeroing m[p][q]: t = tan φ with cot 2φ = (m_qq - m_pp) / 2m_pq
+                let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+                let t = theta.signum() / (theta.abs() + sqrt(theta * theta + 1.0));
+                let c = 1.0 / sqrt(t * t + 1.0);
+                let s = t * c;
+                for row in m.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (upper, lower) = m.split_at_mut(q);
+                for (pk, qk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (a, b) = (*pk, *qk);
+                    *pk = c * a - s * b;
+                    *qk = s * a + c * b;
+                }
+                for row in v.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| m[j][j].total_cmp(&m[i][i]));
+    let values = order.iter().map(|&i| m[i][i]).collect();
+    let vectors = v
+        .iter()
+        .map(|row| order.iter().map(|&i| row[i]).collect())
+        .collect();
+    Some((values, vectors))
+}
+
+pub fn mat_vec(a: &[Vec<f64>], x: &[f64]) -> Vec<f64> {
+    a.iter()
+        .map(|row| row.iter().zip(x).map(|(a, x)| a * x).sum())
+        .collect()
+}
+
+pub fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+pub fn mat_mul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    let cols = b.first().map_or(0, Vec::len);
+    a.iter()
+        .map(|row| {
+            (0..cols)
+                .map(|j| row.iter().zip(b).map(|(a, b_row)| a * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Solves a tridiagonal system by the Thomas algorithm. `lower[i]` and `upper[i]` multiply
+/// x[i-1] and x[i+1] in row i; `lower[0]` and the last `upper` are ignored.
+///
+/// Needs a diagonally dominant system, as implicit finite-difference steps give
+pub fn solve_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let n = diag.len();
+    let (mut c, mut d) = (vec![0.0; n], vec![0.0; n]);
+    for i in 0..n {
+        let (a, b) = if i == 0 {
+            (0.0, diag[0])
+        } else {
+            (lower[i], diag[i] - lower[i] * c[i - 1])
+        };
+        c[i] = if i + 1 < n { upper[i] / b } else { 0.0 };
+        let previous = if i == 0 { 0.0 } else { d[i - 1] };
+        d[i] = (rhs[i] - a * previous) / b;
+    }
+    for i in (0..n.saturating_sub(1)).rev() {
+        d[i] -= c[i] * d[i + 1];
+    }
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cholesky_roundtrip() {
+        let a = vec![
+            vec![4.0, 2.0, 0.4],
+            vec![2.0, 2.0, 0.5],
+            vec![0.4, 0.5, 1.0],
+        ];
+        let l = cholesky(&a).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let value: f64 = (0..3).map(|k| l[i][k] * l[j][k]).sum();
+                assert!((value - a[i][j]).abs() < 1e-12);
+            }
+        }
+        assert!(cholesky(&[vec![1.0, 2.0], vec![2.0, 1.0]]).is_none());
+        assert_eq!(mat_vec(&a, &[1.0, 0.0, 0.0]), vec![4.0, 2.0, 0.4]);
+        let x = cholesky_solve(&l, &[1.0, -2.0, 0.5]);
+        for (lhs, rhs) in mat_vec(&a, &x).iter().zip([1.0, -2.0, 0.5]) {
+            assert!((lhs - rhs).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let a = vec![
+            vec![1.0, 0.9, 0.2],
+            vec![0.9, 1.0, -0.4],
+            vec![0.2, -0.4, 1.0],
+        ];
+        let (values, vectors) = symmetric_eigen(&a).unwrap();
+        // an indefinite "correlation" matrix, as noisy quotes produce
+        assert!(values[2] < 0.0);
+        assert!(values.windows(2).all(|w| w[0] >= w[1]));
+        for (k, value) in values.iter().enumerate() {
+            let column: Vec<f64> = vectors.iter().map(|row| row[k]).collect();
+            for (lhs, x) in mat_vec(&a, &column).iter().zip(&column) {
+                assert!((lhs - value * x).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_tridiagonal() {
+        let (lower, diag, upper) = ([0.0, -1.0, -1.0, -1.0], [4.0; 4], [-1.0, -1.0, -1.0, 0.0]);
+        let x = solve_tridiagonal(&lower, &diag, &upper, &[1.0, 2.0, 3.0, 4.0]);
+        let dense: Vec<Vec<f64>> = (0..4)
+            .map(|i| {
+                (0..4)
+                    .map(|j| match j as isize - i as isize {
+                        0 => 4.0,
+                        1 | -1 => -1.0,
+                        _ => 0.0,
+                    })
+                    .collect()
+            })
+            .collect();
+        for (lhs, rhs) in mat_vec(&dense, &x).iter().zip([1.0, 2.0, 3.0, 4.0]) {
+            assert!((lhs - rhs).abs() < 1e-12);
+        }
+    }
+}
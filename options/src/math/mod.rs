@@ -0,0 +1,9 @@
+// Numerical building blocks shared by the pricing and risk modules.
+pub mod complex;
+pub mod distributions;
+pub mod float;
+pub mod interp;
+pub mod linalg;
+pub mod normal;
+pub mod real;
+pub mod sum;
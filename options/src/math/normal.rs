@@ -0,0 +1,128 @@
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, sqrt};
+use alloc::format;
+use core::f64::consts::{PI, SQRT_2};
+
+// Acklam's rational approximation of the standard normal quantile
+const A: [f64; 6] = [
+    -3.969683028665376e1,
+    2.209460984245205e2,
+    -2.759285104469687e2,
+    1.38357751867269e2,
+    -3.066479806614716e1,
+    2.506628277459239,
+];
+const B: [f64; 5] = [
+    -5.447609879822406e1,
+    1.615858368580409e2,
+    -1.556989798598866e2,
+    6.680131188771972e1,
+    -1.328068155288572e1,
+];
+const C: [f64; 6] = [
+    -7.784894002430293e-3,
+    -3.223964580411365e-1,
+    -2.400758277161838,
+    -2.549732539343734,
+    4.374664141464968,
+    2.938163982698783,
+];
+const D: [f64; 4] = [
+    7.784695709041462e-3,
+    3.224671290700398e-1,
+    2.445134137142996,
+    3.754408661907416,
+];
+const P_LOW: f64 = 0.02425;
+
+/// Normal distribution evaluated in closed form through `erfc`, without a statistics dependency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    pub fn new(mean: f64, std_dev: f64) -> Result<Self, PricingError> {
+        if !(mean.is_finite() && std_dev.is_finite() && std_dev > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "normal distribution needs a finite mean and positive deviation, got ({}, {})",
+                mean, std_dev
+            )));
+        }
+        Ok(Normal { mean, std_dev })
+    }
+    pub fn standard() -> Self {
+        Normal {
+            mean: 0.0,
+            std_dev: 1.0,
+        }
+    }
+    pub fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.std_dev;
+        exp(-0.5 * z * z) / (self.std_dev * sqrt(2.0 * PI))
+    }
+    /// Distribution function, accurate in relative terms far into the lower tail
+    pub fn cdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.std_dev;
+        0.5 * libm::erfc(-z / SQRT_2)
+    }
+    /// Quantile function: Acklam's approximation polished with one Halley step
+    pub fn inverse_cdf(&self, p: f64) -> f64 {
+        if p <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if p >= 1.0 {
+            return f64::INFINITY;
+        }
+        let z = if p < P_LOW {
+            let q = sqrt(-2.0 * ln(p));
+            (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+                / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+        } else if p <= 1.0 - P_LOW {
+            let q = p - 0.5;
+            let r = q * q;
+            (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+                / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+        } else {
+            let q = sqrt(-2.0 * ln(1.0 - p));
+            -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+                / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+        };
+        let standard = Normal::standard();
+        let error = standard.cdf(z) - p;
+        let u = error * sqrt(2.0 * PI) * exp(0.5 * z * z);
+        let z = z - u / (1.0 + 0.5 * z * u);
+        self.mean + self.std_dev * z
+    }
+}
+
+#[cfg(feature = "mc")]
+impl rand::distributions::Distribution<f64> for Normal {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let z: f64 = rng.sample(rand_distr::StandardNormal);
+        self.mean + self.std_dev * z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_functions() {
+        let n = Normal::new(0.0, 1.0).unwrap();
+        assert!((n.cdf(0.0) - 0.5).abs() < 1e-16);
+        assert!((n.cdf(1.96) - 0.9750021048517795).abs() < 1e-15);
+        // lower tail keeps relative precision
+        assert!((n.cdf(-10.0) / 7.619853024160527e-24 - 1.0).abs() < 1e-12);
+        assert!((n.pdf(1.0) - 0.24197072451914337).abs() < 1e-16);
+        for p in [1e-10, 0.01, 0.3, 0.5, 0.975, 0.999999] {
+            let x = n.inverse_cdf(p);
+            assert!((n.cdf(x) / p - 1.0).abs() < 1e-13, "{}", p);
+        }
+        let shifted = Normal::new(2.0, 3.0).unwrap();
+        assert!((shifted.inverse_cdf(0.5) - 2.0).abs() < 1e-14);
+        assert!(Normal::new(0.0, 0.0).is_err());
+    }
+}
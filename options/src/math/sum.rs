@@ -0,0 +1,87 @@
+// Accurate summation for aggregation paths where many terms of mixed magnitude are added, such as
+// large books, Monte Carlo averages and discounted cash flows.
+use core::iter::Sum;
+
+// Below this length pairwise summation falls back to a plain loop
+const PAIRWISE_BLOCK: usize = 128;
+
+/// Running sum with Neumaier's compensation, carrying the low-order bits lost by each addition.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KahanSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl KahanSum {
+    pub fn new() -> Self {
+        KahanSum::default()
+    }
+    pub fn add(&mut self, value: f64) {
+        let total = self.sum + value;
+        // recover whichever operand lost bits in the rounded addition
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - total) + value;
+        } else {
+            self.compensation += (value - total) + self.sum;
+        }
+        self.sum = total;
+    }
+    /// Folds in another partial sum, e.g. one accumulated by a parallel worker
+    pub fn merge(&mut self, other: &KahanSum) {
+        self.add(other.sum);
+        self.add(other.compensation);
+    }
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+impl Sum<f64> for KahanSum {
+    fn sum<I: Iterator<Item = f64>>(iter: I) -> Self {
+        let mut total = KahanSum::new();
+        iter.for_each(|value| total.add(value));
+        total
+    }
+}
+
+/// Compensated sum of the values
+pub fn compensated_sum<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    values.into_iter().sum::<KahanSum>().value()
+}
+
+/// Pairwise (cascade) sum: error grows with log n instead of n, at the cost of needing a slice
+pub fn pairwise_sum(values: &[f64]) -> f64 {
+    if values.len() <= PAIRWISE_BLOCK {
+        return values.iter().sum();
+    }
+    let (left, right) = values.split_at(values.len() / 2);
+    pairwise_sum(left) + pairwise_sum(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_million_term_sums() {
+        let terms = vec![0.1; 1_000_000];
+        let naive: f64 = terms.iter().sum();
+        // the rounding error of 0.1 itself contributes 5.55e-12 to the exact total
+        let exact = 100_000.0 + 5.551115123125783e-12;
+        assert!((naive - exact).abs() > 1e-7);
+        assert!((compensated_sum(terms.iter().copied()) - exact).abs() < 1e-10);
+        assert!((pairwise_sum(&terms) - exact).abs() < 1e-9);
+
+        // a large position next to many small ones is lost entirely by naive summation
+        let mut book = vec![1e16];
+        book.extend(std::iter::repeat_n(1.0, 1_000_000));
+        book.push(-1e16);
+        assert_eq!(book.iter().sum::<f64>(), 0.0);
+        assert_eq!(compensated_sum(book.iter().copied()), 1_000_000.0);
+
+        let mut left: KahanSum = terms[..500_000].iter().copied().sum();
+        let right: KahanSum = terms[500_000..].iter().copied().sum();
+        left.merge(&right);
+        assert!((left.value() - exact).abs() < 1e-10);
+    }
+}
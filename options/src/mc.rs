@@ -0,0 +1,365 @@
+use crate::Options;
+use crate::math::float::exp;
+use crate::math::normal::Normal;
+use crate::math::sum::KahanSum;
+use crate::payoff::Payoff;
+use crate::processes::{LocalVolatility, StochasticProcess};
+use crate::rng::{RngAlgorithm, RngProvider, stream_id};
+use rayon::prelude::*;
+
+// Paths simulated per parallel task; each chunk gets its own seeded generator so results do not
+// depend on thread scheduling
+const CHUNK_PATHS: usize = 1024;
+
+/// Path counts and seeding of a Monte Carlo run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct McConfig {
+    pub paths: usize,
+    /// Time steps per path
+    pub steps: usize,
+    pub seed: u64,
+    /// Generator algorithm, pinned with the seed to reproduce a run across releases
+    pub rng: RngAlgorithm,
+    /// Stream of the run under its seed, chunks drawing from consecutive streams after it
+    pub stream: u64,
+    /// Pairs every normal draw with its negation to reduce variance
+    pub antithetic: bool,
+    /// Stops early once the estimate is precise enough, with `paths` as the budget
+    pub adaptive: Option<AdaptiveStopping>,
+}
+
+/// Batched simulation that stops once the standard error reaches a target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveStopping {
+    /// Standard error of the discounted price to stop at
+    pub target_std_error: f64,
+    /// Paths simulated between checks, rounded up to whole chunks of 1024
+    pub batch_paths: usize,
+}
+
+impl McConfig {
+    pub fn new(paths: usize, steps: usize, seed: u64) -> Self {
+        McConfig {
+            paths,
+            steps,
+            seed,
+            rng: RngAlgorithm::StdRng,
+            stream: 0,
+            antithetic: true,
+            adaptive: None,
+        }
+    }
+    pub fn with_rng(self, rng: RngAlgorithm) -> Self {
+        McConfig { rng, ..self }
+    }
+    /// Draws from the stream named `name`, so products sharing a seed get independent paths
+    pub fn with_stream(self, name: &str) -> Self {
+        McConfig {
+            stream: stream_id(name),
+            ..self
+        }
+    }
+    /// Simulates in batches of `batch_paths` until the standard error is at most `target`, or
+    /// the configured paths are spent
+    pub fn with_target_std_error(mut self, target: f64, batch_paths: usize) -> Self {
+        self.adaptive = Some(AdaptiveStopping {
+            target_std_error: target,
+            batch_paths,
+        });
+        self
+    }
+}
+
+/// Discounted Monte Carlo estimate with its standard error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McResult {
+    pub price: f64,
+    pub std_error: f64,
+    pub paths: usize,
+    /// Running estimate after each batch, the last one equal to the result; a run without
+    /// adaptive stopping is a single batch
+    pub history: Vec<McConvergence>,
+}
+
+impl McResult {
+    /// Whether the standard error reached the adaptive target, false for fixed-path runs
+    pub fn converged(&self, config: &McConfig) -> bool {
+        config
+            .adaptive
+            .is_some_and(|adaptive| self.std_error <= adaptive.target_std_error)
+    }
+}
+
+/// Estimate after a number of simulated paths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct McConvergence {
+    pub paths: usize,
+    pub price: f64,
+    pub std_error: f64,
+}
+
+/// Monte Carlo engine simulating a single underlying under a spot- and time-dependent volatility.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloEngine {
+    pub config: McConfig,
+}
+
+impl MonteCarloEngine {
+    pub fn new(config: McConfig) -> Self {
+        MonteCarloEngine { config }
+    }
+    /// Prices a path-dependent payoff with log-Euler steps
+    /// S(t+dt) = S(t) * exp((r - q - σ²/2)dt + σ√dt Z), where σ = vol(S(t), t).
+    ///
+    /// The payoff receives the full path including the initial spot and is discounted at `rate`
+    pub fn price<V, P>(
+        &self,
+        spot: f64,
+        rate: f64,
+        dividend_yield: Option<f64>,
+        maturity: f64,
+        vol: V,
+        payoff: P,
+    ) -> McResult
+    where
+        V: Fn(f64, f64) -> f64 + Sync,
+        P: Fn(&[f64]) -> f64 + Sync,
+    {
+        let process = LocalVolatility {
+            spot,
+            drift: rate - dividend_yield.unwrap_or(0.0),
+            vol,
+        };
+        self.price_process(&process, rate, maturity, payoff)
+    }
+    /// Prices a path-dependent payoff on any process. The payoff receives the observable at
+    /// every step including the start, and is discounted at `rate`
+    pub fn price_process<S, P>(&self, process: &S, rate: f64, maturity: f64, payoff: P) -> McResult
+    where
+        S: StochasticProcess + ?Sized,
+        P: Fn(&[f64]) -> f64 + Sync,
+    {
+        let steps = self.config.steps.max(1);
+        let dt = maturity / steps as f64;
+        let sqrt_dt = dt.sqrt();
+        let factors = process.factors();
+        let scratch = || {
+            (
+                Vec::with_capacity(process.dimension()),
+                vec![0.0; factors],
+                Vec::with_capacity(steps + 1),
+            )
+        };
+        let simulate = |buffers: &mut (Vec<f64>, Vec<f64>, Vec<f64>), z: &[f64]| {
+            let (state, dw, path) = buffers;
+            state.clear();
+            state.extend(process.initial_state());
+            path.clear();
+            path.push(process.observable(state));
+            for (i, z) in z.chunks_exact(factors).enumerate() {
+                for (dw, z) in dw.iter_mut().zip(z) {
+                    *dw = sqrt_dt * z;
+                }
+                process.evolve(state, i as f64 * dt, dt, dw);
+                path.push(process.observable(state));
+            }
+            payoff(path)
+        };
+        self.run(steps * factors, exp(-rate * maturity), scratch, simulate)
+    }
+    /// Averages `sample` over the configured paths and scales by `discount`. Each path gets
+    /// `draws` standard normal draws, negated for its antithetic twin, and reuses buffers made
+    /// by `scratch` once per chunk. Chunks are seeded by index, so an adaptive run that stops
+    /// after some batches matches a fixed run of the same paths
+    pub(crate) fn run<B, I, F>(
+        &self,
+        draws: usize,
+        discount: f64,
+        scratch: I,
+        sample: F,
+    ) -> McResult
+    where
+        I: Fn() -> B + Sync,
+        F: Fn(&mut B, &[f64]) -> f64 + Sync,
+    {
+        let config = self.config;
+        let chunks = config.paths.div_ceil(CHUNK_PATHS);
+        let batch_chunks = match config.adaptive {
+            Some(adaptive) => adaptive.batch_paths.div_ceil(CHUNK_PATHS).max(1),
+            None => chunks,
+        };
+        let simulate_chunk = |chunk: usize| {
+            let std_norm = Normal::new(0.0, 1.0).unwrap();
+            let mut rng = config
+                .rng
+                .stream(config.seed, config.stream.wrapping_add(chunk as u64));
+            let count = CHUNK_PATHS.min(config.paths - chunk * CHUNK_PATHS);
+            let mut z = vec![0.0; draws];
+            let mut buffers = scratch();
+            let (mut sum, mut sum_sq) = (KahanSum::new(), KahanSum::new());
+            for _ in 0..count {
+                for draw in z.iter_mut() {
+                    *draw = rand::distributions::Distribution::sample(&std_norm, &mut rng);
+                }
+                let mut value = sample(&mut buffers, &z);
+                if config.antithetic {
+                    z.iter_mut().for_each(|draw| *draw = -*draw);
+                    value = 0.5 * (value + sample(&mut buffers, &z));
+                }
+                sum.add(value);
+                sum_sq.add(value * value);
+            }
+            (sum, sum_sq, count)
+        };
+        let estimate = |sum: &KahanSum, sum_sq: &KahanSum, n: usize| {
+            let n_f = n as f64;
+            let mean = sum.value() / n_f;
+            let variance =
+                (sum_sq.value() / n_f - mean * mean).max(0.0) * n_f / (n_f - 1.0).max(1.0);
+            McConvergence {
+                paths: n,
+                price: discount * mean,
+                std_error: discount * (variance / n_f).sqrt(),
+            }
+        };
+        let (mut sum, mut sum_sq, mut n) = (KahanSum::new(), KahanSum::new(), 0);
+        let mut history = Vec::new();
+        let mut start = 0;
+        while start < chunks {
+            let end = (start + batch_chunks).min(chunks);
+            // (sum, sum of squares, samples) per chunk, reduced in chunk order for reproducibility
+            let partials: Vec<(KahanSum, KahanSum, usize)> =
+                (start..end).into_par_iter().map(simulate_chunk).collect();
+            for (chunk_sum, chunk_sum_sq, count) in &partials {
+                sum.merge(chunk_sum);
+                sum_sq.merge(chunk_sum_sq);
+                n += count;
+            }
+            let point = estimate(&sum, &sum_sq, n);
+            history.push(point);
+            start = end;
+            if config
+                .adaptive
+                .is_some_and(|adaptive| point.std_error <= adaptive.target_std_error)
+            {
+                break;
+            }
+        }
+        let last = history
+            .last()
+            .copied()
+            .unwrap_or_else(|| estimate(&sum, &sum_sq, n));
+        McResult {
+            price: last.price,
+            std_error: last.std_error,
+            paths: last.paths,
+            history,
+        }
+    }
+    /// Prices a vanilla option under constant volatility, mainly as a check against Black-Scholes
+    pub fn price_european(&self, option: &Options) -> McResult {
+        let sigma = option.volatility();
+        let contract = *option;
+        self.price(
+            option.spot_price(),
+            option.risk_free_rate(),
+            option.dividend_yield(),
+            option.time_to_maturity(),
+            |_, _| sigma,
+            |path: &[f64]| contract.payout(path[path.len() - 1]),
+        )
+    }
+    /// Prices a composed payoff over the simulated path at the market and constant volatility of
+    /// `option`, whose strike and type are ignored
+    pub fn price_payoff(&self, option: &Options, payoff: &Payoff) -> McResult {
+        let sigma = option.volatility();
+        self.price(
+            option.spot_price(),
+            option.risk_free_rate(),
+            option.dividend_yield(),
+            option.time_to_maturity(),
+            |_, _| sigma,
+            |path: &[f64]| payoff.evaluate(path),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mc_matches_black_scholes() {
+        let engine = MonteCarloEngine::new(McConfig::new(40_000, 1, 7));
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        let result = engine.price_european(&call);
+        assert!((result.price - call.bs_pricing()).abs() < 3.0 * result.std_error + 1e-3);
+        assert_eq!(result.paths, 40_000);
+        // same seed, same answer regardless of thread scheduling
+        assert_eq!(engine.price_european(&call), result);
+        // the same contract written as a payoff expression
+        let composed = engine.price_payoff(&call, &Payoff::call(100.0));
+        assert_eq!(composed.price, result.price);
+    }
+
+    #[test]
+    fn test_heston_without_vol_of_vol_is_black_scholes() {
+        use crate::processes::Heston;
+
+        let engine = MonteCarloEngine::new(McConfig::new(20_000, 50, 3));
+        let heston = Heston {
+            spot: 100.0,
+            drift: 0.05,
+            variance: 0.04,
+            kappa: 2.0,
+            theta: 0.04,
+            vol_of_vol: 0.0,
+            rho: -0.7,
+        };
+        let put = Options::new_put(95.0, 100.0, 0.2, 0.05, 1.0, None);
+        let result = engine.price_process(&heston, 0.05, 1.0, |path: &[f64]| {
+            put.payout(path[path.len() - 1])
+        });
+        assert!((result.price - put.bs_pricing()).abs() < 3.0 * result.std_error + 1e-3);
+    }
+
+    #[test]
+    fn test_adaptive_stopping() {
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        let config = McConfig::new(200_000, 1, 7).with_target_std_error(0.05, 4096);
+        let result = MonteCarloEngine::new(config).price_european(&call);
+        assert!(result.converged(&config) && result.paths < 200_000);
+        assert_eq!(result.paths % 4096, 0);
+        let step = |w: &[McConvergence]| w[1].paths - w[0].paths;
+        assert!(result.history.windows(2).all(|w| step(w) == 4096));
+        // checked after every batch, so the batch before last had not reached the target
+        let before = result.history[result.history.len() - 2];
+        assert!(before.std_error > 0.05);
+        // the stopped run is the fixed run of the same paths
+        let fixed = MonteCarloEngine::new(McConfig::new(result.paths, 1, 7)).price_european(&call);
+        assert_eq!(fixed.price, result.price);
+        assert_eq!(fixed.history.len(), 1);
+
+        // an unreachable target spends the whole budget
+        let budget = McConfig::new(10_000, 1, 7).with_target_std_error(1e-6, 4096);
+        let spent = MonteCarloEngine::new(budget).price_european(&call);
+        assert!(spent.paths == 10_000 && !spent.converged(&budget));
+        assert_eq!(spent.history.len(), 3);
+    }
+
+    #[test]
+    fn test_pinned_generators() {
+        let call = Options::new_call(100.0, 105.0, 0.2, 0.05, 1.0, None);
+        let default = MonteCarloEngine::new(McConfig::new(20_000, 1, 7)).price_european(&call);
+        for rng in [RngAlgorithm::Pcg64, RngAlgorithm::Philox] {
+            let config = McConfig::new(20_000, 1, 7).with_rng(rng);
+            let result = MonteCarloEngine::new(config).price_european(&call);
+            assert!((result.price - call.bs_pricing()).abs() < 3.0 * result.std_error);
+            assert_ne!(result.price, default.price);
+            // a named stream is a different, equally reproducible sample
+            let named = MonteCarloEngine::new(config.with_stream("desk-a"));
+            assert_eq!(named.price_european(&call), named.price_european(&call));
+            assert_ne!(named.price_european(&call).price, result.price);
+        }
+    }
+}
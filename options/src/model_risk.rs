@@ -0,0 +1,189 @@
+// Model risk of a trade: the same payoff priced under several models calibrated to one market,
+// typically Black-Scholes, Heston, local vol and a jump diffusion. Vanillas the models were fitted
+// to agree by construction; how far apart they land on an exotic measures what the smile alone
+// does not pin down. All models share the engine's seed, so the dispersion is not muddied by
+// independent Monte Carlo noise.
+use crate::error::PricingError;
+use crate::mc::MonteCarloEngine;
+use crate::processes::StochasticProcess;
+
+/// Price of the trade under one model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelPrice {
+    pub model: String,
+    pub price: f64,
+    pub std_error: f64,
+}
+
+/// Spread of a trade's price across models.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispersionReport {
+    /// One per model, in the order given
+    pub prices: Vec<ModelPrice>,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    /// Largest Monte Carlo standard error, the noise floor below which dispersion means nothing
+    pub max_std_error: f64,
+}
+
+impl DispersionReport {
+    /// Report over already computed model prices. Fails without prices
+    pub fn new(prices: Vec<ModelPrice>) -> Result<Self, PricingError> {
+        if prices.is_empty() {
+            return Err(PricingError::InvalidInput(
+                "no model prices to compare".to_string(),
+            ));
+        }
+        let mut sorted: Vec<f64> = prices.iter().map(|p| p.price).collect();
+        sorted.sort_by(f64::total_cmp);
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+        let max_std_error = prices.iter().map(|p| p.std_error).fold(0.0, f64::max);
+        let mut report = DispersionReport {
+            prices,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            median: 0.0,
+            max_std_error,
+        };
+        report.median = report.percentile(0.5);
+        Ok(report)
+    }
+    /// Price at fraction `p` of the way through the sorted model prices, interpolating
+    /// linearly between neighbours
+    pub fn percentile(&self, p: f64) -> f64 {
+        let mut sorted: Vec<f64> = self.prices.iter().map(|p| p.price).collect();
+        sorted.sort_by(f64::total_cmp);
+        let position = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+        let below = position.floor() as usize;
+        let above = (below + 1).min(sorted.len() - 1);
+        sorted[below] + (position - below as f64) * (sorted[above] - sorted[below])
+    }
+    /// max - min, the model reserve a desk would hold against the trade
+    pub fn range(&self) -> f64 {
+        self.max - self.min
+    }
+    /// Range relative to the mean price
+    pub fn relative_range(&self) -> f64 {
+        self.range() / self.mean.abs()
+    }
+    /// Model pricing the trade highest
+    pub fn richest(&self) -> &ModelPrice {
+        self.prices
+            .iter()
+            .max_by(|a, b| a.price.total_cmp(&b.price))
+            .expect("a report holds at least one price")
+    }
+    /// Model pricing the trade lowest
+    pub fn cheapest(&self) -> &ModelPrice {
+        self.prices
+            .iter()
+            .min_by(|a, b| a.price.total_cmp(&b.price))
+            .expect("a report holds at least one price")
+    }
+}
+
+/// Prices a path-dependent `payoff` under each named model process, discounting at `rate`, and
+/// reports how far the prices disperse. The payoff receives each process's observable at every
+/// step of `engine`'s grid including the start. Fails without models
+pub fn compare_models<P>(
+    engine: &MonteCarloEngine,
+    models: &[(&str, &dyn StochasticProcess)],
+    rate: f64,
+    maturity: f64,
+    payoff: P,
+) -> Result<DispersionReport, PricingError>
+where
+    P: Fn(&[f64]) -> f64 + Sync,
+{
+    let prices = models
+        .iter()
+        .map(|&(name, process)| {
+            let result = engine.price_process(process, rate, maturity, &payoff);
+            ModelPrice {
+                model: name.to_string(),
+                price: result.price,
+                std_error: result.std_error,
+            }
+        })
+        .collect();
+    DispersionReport::new(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fourier::{HestonModel, MertonJumpDiffusion};
+    use crate::mc::McConfig;
+    use crate::processes::{GeometricBrownianMotion, Heston, JumpDiffusion, LocalVolatility};
+
+    #[test]
+    fn test_barrier_dispersion_across_models() {
+        let (spot, rate) = (100.0, 0.03);
+        let engine = MonteCarloEngine::new(McConfig::new(20_000, 50, 5));
+        let black_scholes = GeometricBrownianMotion {
+            spot,
+            drift: rate,
+            volatility: 0.2,
+        };
+        let heston_model = HestonModel {
+            variance: 0.04,
+            kappa: 2.0,
+            theta: 0.04,
+            vol_of_vol: 0.6,
+            rho: -0.7,
+        };
+        let heston = Heston::from_model(spot, rate, &heston_model);
+        let local_vol = LocalVolatility {
+            spot,
+            drift: rate,
+            vol: |s: f64, _t: f64| (0.2 - 0.3 * (s / spot).ln()).max(0.05),
+        };
+        let jumps = JumpDiffusion {
+            spot,
+            drift: rate,
+            model: MertonJumpDiffusion {
+                volatility: 0.15,
+                intensity: 0.5,
+                jump_mean: -0.15,
+                jump_vol: 0.1,
+            },
+        };
+        let models: [(&str, &dyn StochasticProcess); 4] = [
+            ("black_scholes", &black_scholes),
+            ("heston", &heston),
+            ("local_vol", &local_vol),
+            ("jump_diffusion", &jumps),
+        ];
+        // down-and-out put, knocked out on any close below 80
+        let barrier = |path: &[f64]| {
+            if path.iter().any(|&s| s < 80.0) {
+                0.0
+            } else {
+                (100.0 - path[path.len() - 1]).max(0.0)
+            }
+        };
+        let report = compare_models(&engine, &models, rate, 1.0, barrier).unwrap();
+        assert_eq!(report.prices.len(), 4);
+        assert!(report.min <= report.median && report.median <= report.max);
+        assert_eq!(report.percentile(0.0), report.min);
+        assert_eq!(report.percentile(1.0), report.max);
+        assert!(report.range() > 10.0 * report.max_std_error);
+        assert_eq!(report.cheapest().price, report.min);
+        assert!(compare_models(&engine, &[], rate, 1.0, barrier).is_err());
+
+        let fixed = |name: &str, price: f64| ModelPrice {
+            model: name.to_string(),
+            price,
+            std_error: 0.0,
+        };
+        let three =
+            DispersionReport::new(vec![fixed("a", 3.0), fixed("b", 1.0), fixed("c", 2.0)]).unwrap();
+        assert_eq!(three.median, 2.0);
+        assert_eq!(three.percentile(0.25), 1.5);
+        assert_eq!(three.richest().model, "a");
+        assert!((three.relative_range() - 1.0).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,233 @@
+// Forward, moneyness and delta primitives shared by smile parameterizations and FX-style surfaces,
+// which quote strikes by delta. Strikes are recovered from a delta in closed form where the delta
+// is monotone in d₁, and by a root search in d₂ for premium-adjusted deltas.
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, sqrt};
+use crate::math::normal::Normal;
+use crate::solver::{DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE, brent};
+use crate::{OptionType, Options};
+use alloc::format;
+
+// Standardized moneyness searched for premium-adjusted strikes
+const MAX_D: f64 = 10.0;
+
+/// Forward of the underlying to `t`
+///
+/// Formula: F = S e^((r - q)T)
+pub fn forward(spot: f64, rate: f64, dividend_yield: Option<f64>, t: f64) -> f64 {
+    spot * exp((rate - dividend_yield.unwrap_or(0.0)) * t)
+}
+
+/// Formula: k = ln(K / F)
+pub fn log_moneyness(strike: f64, forward: f64) -> f64 {
+    ln(strike / forward)
+}
+
+/// Log-moneyness in standard deviations of the terminal log-price
+///
+/// Formula: ln(K / F) / σ√T
+pub fn standardized_moneyness(strike: f64, forward: f64, vol: f64, t: f64) -> f64 {
+    log_moneyness(strike, forward) / (vol * sqrt(t))
+}
+
+/// How a quoted delta is measured, following the FX market conventions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DeltaConvention {
+    /// Sensitivity to spot: φ e^(-qT) N(φd₁)
+    #[default]
+    Spot,
+    /// Sensitivity to the forward: φ N(φd₁)
+    Forward,
+    /// Spot delta less the premium paid in the underlying: φ e^(-qT) (K/F) N(φd₂)
+    PremiumAdjustedSpot,
+    /// Forward delta less the premium: φ (K/F) N(φd₂)
+    PremiumAdjustedForward,
+}
+
+/// Strike whose delta under `convention` equals `delta`, e.g. -0.25 for the 25-delta put.
+///
+/// `dividend_discount` is e^(-qT), the spot delta's discount from the forward one. Premium-adjusted
+/// call deltas peak below one; the strike returned is the one above the peak, where quotes live.
+/// Fails when no strike has the requested delta
+pub fn strike_from_delta(
+    option_type: OptionType,
+    delta: f64,
+    forward: f64,
+    vol: f64,
+    t: f64,
+    dividend_discount: f64,
+    convention: DeltaConvention,
+) -> Result<f64, PricingError> {
+    let phi = match option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    };
+    let sd = vol * sqrt(t);
+    let std_norm = Normal::standard();
+    let invalid = || {
+        PricingError::InvalidInput(format!(
+            "{:?} delta {} is out of reach for a {:?}",
+            convention, delta, option_type
+        ))
+    };
+    if !(sd > 0.0 && forward > 0.0) {
+        return Err(invalid());
+    }
+    let forward_delta = match convention {
+        DeltaConvention::Spot | DeltaConvention::PremiumAdjustedSpot => delta / dividend_discount,
+        DeltaConvention::Forward | DeltaConvention::PremiumAdjustedForward => delta,
+    };
+    // |forward delta| as a probability-like quantity in (0, 1)
+    let level = phi * forward_delta;
+    if !(level > 0.0 && level < 1.0) {
+        return Err(invalid());
+    }
+    match convention {
+        DeltaConvention::Spot | DeltaConvention::Forward => {
+            // φ N(φd₁) = Δ_F inverts to d₁, and K = F e^(-d₁σ√T + σ²T/2)
+            let d1 = phi * std_norm.inverse_cdf(level);
+            Ok(forward * exp(-d1 * sd + 0.5 * sd * sd))
+        }
+        DeltaConvention::PremiumAdjustedSpot | DeltaConvention::PremiumAdjustedForward => {
+            // with K/F = e^(-d₂σ√T - σ²T/2), solve (K/F) N(φd₂) = |Δ_F| in d₂
+            let adjusted = |d2: f64| exp(-d2 * sd - 0.5 * sd * sd) * std_norm.cdf(phi * d2) - level;
+            let upper = match option_type {
+                // the call delta peaks where σ√T N(d₂) = N'(d₂), and falls with the strike above
+                OptionType::Call => brent(
+                    |d2| sd * std_norm.cdf(d2) - std_norm.pdf(d2),
+                    -sd,
+                    MAX_D,
+                    DEFAULT_TOLERANCE,
+                    DEFAULT_MAX_ITERATIONS,
+                )?,
+                OptionType::Put => MAX_D,
+            };
+            if adjusted(-MAX_D) * adjusted(upper) >= 0.0 {
+                return Err(invalid());
+            }
+            let d2 = brent(
+                adjusted,
+                -MAX_D,
+                upper,
+                DEFAULT_TOLERANCE,
+                DEFAULT_MAX_ITERATIONS,
+            )?;
+            Ok(forward * exp(-d2 * sd - 0.5 * sd * sd))
+        }
+    }
+}
+
+impl Options {
+    /// Forward of the underlying to expiry
+    pub fn forward(&self) -> f64 {
+        forward(
+            self.spot_price(),
+            self.risk_free_rate(),
+            self.dividend_yield(),
+            self.time_to_maturity(),
+        )
+    }
+    /// Formula: ln(K / F)
+    pub fn log_moneyness(&self) -> f64 {
+        log_moneyness(self.strike_price(), self.forward())
+    }
+    /// Formula: ln(K / F) / σ√T
+    pub fn standardized_moneyness(&self) -> f64 {
+        standardized_moneyness(
+            self.strike_price(),
+            self.forward(),
+            self.volatility(),
+            self.time_to_maturity(),
+        )
+    }
+    /// Formula: d₁ = (ln(F / K) + σ²T/2) / σ√T
+    pub fn d1(&self) -> f64 {
+        let sd = self.volatility() * sqrt(self.time_to_maturity());
+        -self.log_moneyness() / sd + 0.5 * sd
+    }
+    /// Formula: d₂ = d₁ - σ√T
+    pub fn d2(&self) -> f64 {
+        self.d1() - self.volatility() * sqrt(self.time_to_maturity())
+    }
+    /// Strike of the same side, expiry and market whose delta is `delta` under `convention`,
+    /// at the contract's volatility; the stored strike is ignored
+    pub fn strike_from_delta(
+        &self,
+        delta: f64,
+        convention: DeltaConvention,
+    ) -> Result<f64, PricingError> {
+        let t = self.time_to_maturity();
+        strike_from_delta(
+            self.option_type(),
+            delta,
+            self.forward(),
+            self.volatility(),
+            t,
+            exp(-self.dividend_yield().unwrap_or(0.0) * t),
+            convention,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moneyness_measures() {
+        let call = Options::new_call(110.0, 100.0, 0.2, 0.05, 1.0, Some(0.02));
+        let f = call.forward();
+        assert!((f - 100.0 * (0.03f64).exp()).abs() < 1e-12);
+        assert!((call.log_moneyness() - (110.0 / f).ln()).abs() < 1e-15);
+        assert!((call.standardized_moneyness() - call.log_moneyness() / 0.2).abs() < 1e-15);
+        let d1 = crate::black_scholes::d_plus(1.0, 0.05, Some(0.02), 0.2, 100.0, 110.0);
+        assert!((call.d1() - d1).abs() < 1e-14);
+        assert!((call.d1() - call.d2() - 0.2).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_strike_from_delta() {
+        let put = Options::new_put(100.0, 100.0, 0.15, 0.03, 0.5, Some(0.01));
+        let with_strike =
+            |strike: f64| Options::new_put(strike, 100.0, 0.15, 0.03, 0.5, Some(0.01));
+        // the 25-delta put under spot delta reprices to -0.25
+        let strike = put.strike_from_delta(-0.25, DeltaConvention::Spot).unwrap();
+        assert!(strike < 100.0);
+        assert!((with_strike(strike).delta() + 0.25).abs() < 1e-10);
+        let forward_strike = put
+            .strike_from_delta(-0.25, DeltaConvention::Forward)
+            .unwrap();
+        let forward_delta = with_strike(forward_strike).delta() * (0.01f64 * 0.5).exp();
+        assert!((forward_delta + 0.25).abs() < 1e-10);
+
+        // premium-adjusted deltas net the premium, in units of the underlying
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let target = if option_type == OptionType::Call {
+                0.25
+            } else {
+                -0.25
+            };
+            let option =
+                Options::new_with_type(option_type, 100.0, 100.0, 0.15, 0.03, 0.5, Some(0.01));
+            let strike = option
+                .strike_from_delta(target, DeltaConvention::PremiumAdjustedSpot)
+                .unwrap();
+            let quoted =
+                Options::new_with_type(option_type, strike, 100.0, 0.15, 0.03, 0.5, Some(0.01));
+            let adjusted = quoted.delta() - quoted.bs_pricing() / 100.0;
+            assert!((adjusted - target).abs() < 1e-9, "{:?}", option_type);
+        }
+        // above the peak of the premium-adjusted call delta there is no strike
+        assert!(
+            put.strike_from_delta(-1.2, DeltaConvention::Spot).is_err()
+                && Options::new_call(100.0, 100.0, 0.5, 0.0, 4.0, None)
+                    .strike_from_delta(0.9, DeltaConvention::PremiumAdjustedForward)
+                    .is_err()
+        );
+    }
+}
@@ -0,0 +1,437 @@
+// Joint simulation of several underlyings whose spot drivers are correlated, for baskets,
+// spreads and rainbows. Correlation enters through a loading matrix L with L Lᵀ ≈ C, either
+// its Cholesky factor or a principal-component truncation to fewer factors.
+use crate::OptionType;
+use crate::error::PricingError;
+use crate::math::float::{exp, sqrt};
+use crate::math::linalg::{Matrix, cholesky, mat_mul, symmetric_eigen};
+use crate::mc::{McResult, MonteCarloEngine};
+use crate::numerics::NumericsConfig;
+use crate::processes::StochasticProcess;
+
+/// Slack on symmetry, the unit diagonal and the smallest eigenvalue of a correlation matrix
+const CORRELATION_TOLERANCE: f64 = 1e-10;
+/// Eigenvalue floor of a repaired matrix, so it still has a Cholesky factor
+const MIN_REPAIRED_EIGENVALUE: f64 = 1e-10;
+
+/// Checks that `correlation` is square, symmetric, has a unit diagonal, entries in [-1, 1] and
+/// no negative eigenvalue
+pub fn validate_correlation(correlation: &[Vec<f64>]) -> Result<(), PricingError> {
+    let n = correlation.len();
+    let invalid = |reason: String| Err(PricingError::InvalidInput(reason));
+    if correlation.iter().any(|row| row.len() != n) {
+        return invalid("correlation matrix must be square".to_string());
+    }
+    for (i, row) in correlation.iter().enumerate() {
+        if (row[i] - 1.0).abs() > CORRELATION_TOLERANCE {
+            return invalid(format!("correlation diagonal entry {} is not one", i));
+        }
+        for (j, &rho) in row.iter().enumerate().take(i) {
+            if rho.is_nan()
+                || rho.abs() > 1.0
+                || (rho - correlation[j][i]).abs() > CORRELATION_TOLERANCE
+            {
+                return invalid(format!(
+                    "correlation ({}, {}) must be symmetric and within [-1, 1]",
+                    i, j
+                ));
+            }
+        }
+    }
+    let (values, _) = symmetric_eigen(correlation).unwrap_or_default();
+    match values.last() {
+        Some(&smallest) if smallest < -CORRELATION_TOLERANCE => invalid(format!(
+            "correlation matrix is not positive semi-definite (eigenvalue {:.3e}); repair it with \
+             nearest_correlation",
+            smallest
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Symmetric matrix with its eigenvalues floored at `floor`
+fn clip_eigenvalues(a: &[Vec<f64>], floor: f64) -> Matrix {
+    let (values, vectors) = symmetric_eigen(a).unwrap_or_default();
+    let n = a.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    (0..n)
+                        .map(|k| vectors[i][k] * values[k].max(floor) * vectors[j][k])
+                        .sum()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Nearest valid correlation matrix to a noisy estimate in the Frobenius norm, by Higham's
+/// alternating projections (2002)
+pub fn nearest_correlation(correlation: &[Vec<f64>]) -> Result<Matrix, PricingError> {
+    nearest_correlation_with(correlation, &NumericsConfig::default())
+}
+
+/// Nearest correlation matrix, iterating to the tolerance and iteration cap of `numerics`
+pub fn nearest_correlation_with(
+    correlation: &[Vec<f64>],
+    numerics: &NumericsConfig,
+) -> Result<Matrix, PricingError> {
+    numerics.validate()?;
+    let n = correlation.len();
+    if correlation.iter().any(|row| row.len() != n) {
+        return Err(PricingError::InvalidInput(
+            "correlation matrix must be square".to_string(),
+        ));
+    }
+    // Symmetrized start; Dykstra's correction keeps the PSD projection from stalling
+    let mut y: Matrix = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| 0.5 * (correlation[i][j] + correlation[j][i]))
+                .collect()
+        })
+        .collect();
+    let mut correction = vec![vec![0.0; n]; n];
+    for _ in 0..numerics.max_iterations {
+        let r: Matrix = (0..n)
+            .map(|i| (0..n).map(|j| y[i][j] - correction[i][j]).collect())
+            .collect();
+        let x = clip_eigenvalues(&r, 0.0);
+        let mut change = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                correction[i][j] = x[i][j] - r[i][j];
+                let unit = if i == j { 1.0 } else { x[i][j] };
+                change += (unit - y[i][j]) * (unit - y[i][j]);
+                y[i][j] = unit;
+            }
+        }
+        if sqrt(change) < numerics.tolerance {
+            break;
+        }
+    }
+    // Floor the spectrum and rescale to a unit diagonal, so the result factors exactly
+    let x = clip_eigenvalues(&y, MIN_REPAIRED_EIGENVALUE);
+    Ok((0..n)
+        .map(|i| (0..n).map(|j| x[i][j] / sqrt(x[i][i] * x[j][j])).collect())
+        .collect())
+}
+
+/// How correlated drivers are built from independent factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decomposition {
+    /// Exact Cholesky factor, one factor per asset; needs a positive definite matrix
+    Cholesky,
+    /// The leading `factors` principal components, rescaled so every asset keeps unit variance
+    Pca { factors: usize },
+}
+
+/// Per-asset processes whose first Brownian drivers are correlated. Further drivers of a
+/// process (the variance of a Heston asset) stay independent.
+pub struct MultiAssetSimulator {
+    processes: Vec<Box<dyn StochasticProcess>>,
+    /// Assets × factors
+    loadings: Matrix,
+}
+
+impl MultiAssetSimulator {
+    pub fn new(
+        processes: Vec<Box<dyn StochasticProcess>>,
+        correlation: &[Vec<f64>],
+        decomposition: Decomposition,
+    ) -> Result<Self, PricingError> {
+        if correlation.len() != processes.len() {
+            return Err(PricingError::InvalidInput(format!(
+                "{} processes need a {0}x{0} correlation matrix",
+                processes.len()
+            )));
+        }
+        validate_correlation(correlation)?;
+        let loadings = match decomposition {
+            Decomposition::Cholesky => cholesky(correlation).ok_or_else(|| {
+                PricingError::InvalidInput(
+                    "correlation matrix is singular; use Pca or nearest_correlation".to_string(),
+                )
+            })?,
+            Decomposition::Pca { factors } => {
+                if factors == 0 || factors > processes.len() {
+                    return Err(PricingError::InvalidInput(format!(
+                        "PCA needs between 1 and {} factors, got {}",
+                        processes.len(),
+                        factors
+                    )));
+                }
+                let (values, vectors) = symmetric_eigen(correlation).unwrap_or_default();
+                vectors
+                    .iter()
+                    .map(|row| {
+                        let loading: Vec<f64> = (0..factors)
+                            .map(|k| row[k] * sqrt(values[k].max(0.0)))
+                            .collect();
+                        let norm = sqrt(loading.iter().map(|x| x * x).sum::<f64>());
+                        loading
+                            .iter()
+                            .map(|x| if norm > 0.0 { x / norm } else { 0.0 })
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+        Ok(MultiAssetSimulator {
+            processes,
+            loadings,
+        })
+    }
+    pub fn assets(&self) -> usize {
+        self.processes.len()
+    }
+    /// Independent factors behind the correlated drivers
+    pub fn factors(&self) -> usize {
+        self.loadings.first().map_or(0, Vec::len)
+    }
+    pub fn loadings(&self) -> &Matrix {
+        &self.loadings
+    }
+    /// Correlation the simulation reproduces, L Lᵀ; below the input under PCA truncation
+    pub fn simulated_correlation(&self) -> Matrix {
+        let transpose: Matrix = (0..self.factors())
+            .map(|k| self.loadings.iter().map(|row| row[k]).collect())
+            .collect();
+        mat_mul(&self.loadings, &transpose)
+    }
+    /// Discounted expectation of `payoff`, which receives every asset's observable path
+    /// (initial value included) indexed [asset][step]
+    pub fn price<P>(
+        &self,
+        engine: &MonteCarloEngine,
+        rate: f64,
+        maturity: f64,
+        payoff: P,
+    ) -> McResult
+    where
+        P: Fn(&[Vec<f64>]) -> f64 + Sync,
+    {
+        let steps = engine.config.steps.max(1);
+        let dt = maturity / steps as f64;
+        let sqrt_dt = sqrt(dt);
+        let factors = self.factors();
+        // Per step: the common factors, then each asset's own extra drivers
+        let extras: Vec<usize> = self
+            .processes
+            .iter()
+            .map(|process| process.factors().saturating_sub(1))
+            .collect();
+        let per_step = factors + extras.iter().sum::<usize>();
+        let widest = self
+            .processes
+            .iter()
+            .map(|p| p.factors())
+            .max()
+            .unwrap_or(1);
+        let scratch = || {
+            (
+                vec![Vec::new(); self.assets()],
+                vec![0.0; widest],
+                vec![Vec::with_capacity(steps + 1); self.assets()],
+            )
+        };
+        let simulate = |buffers: &mut (Vec<Vec<f64>>, Vec<f64>, Vec<Vec<f64>>), z: &[f64]| {
+            let (states, dw, paths) = buffers;
+            for ((process, state), path) in self
+                .processes
+                .iter()
+                .zip(states.iter_mut())
+                .zip(paths.iter_mut())
+            {
+                *state = process.initial_state();
+                path.clear();
+                path.push(process.observable(state));
+            }
+            for (step, z) in z.chunks_exact(per_step).enumerate() {
+                let (common, mut own) = z.split_at(factors);
+                for (i, process) in self.processes.iter().enumerate() {
+                    let correlated: f64 = self.loadings[i]
+                        .iter()
+                        .zip(common)
+                        .map(|(l, z)| l * z)
+                        .sum();
+                    dw[0] = sqrt_dt * correlated;
+                    let (extra, rest) = own.split_at(extras[i]);
+                    for (dw, z) in dw[1..].iter_mut().zip(extra) {
+                        *dw = sqrt_dt * z;
+                    }
+                    own = rest;
+                    process.evolve(
+                        &mut states[i],
+                        step as f64 * dt,
+                        dt,
+                        &dw[..process.factors()],
+                    );
+                    paths[i].push(process.observable(&states[i]));
+                }
+            }
+            payoff(paths)
+        };
+        engine.run(steps * per_step, exp(-rate * maturity), scratch, simulate)
+    }
+    /// Prices one of the standard multi-asset payoffs on the terminal values
+    pub fn price_payoff(
+        &self,
+        engine: &MonteCarloEngine,
+        rate: f64,
+        maturity: f64,
+        payoff: &MultiAssetPayoff,
+    ) -> McResult {
+        self.price(engine, rate, maturity, |paths: &[Vec<f64>]| {
+            let terminal: Vec<f64> = paths.iter().map(|path| path[path.len() - 1]).collect();
+            payoff.payout(&terminal)
+        })
+    }
+}
+
+/// European payoffs on several underlyings' terminal values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultiAssetPayoff {
+    /// On the weighted sum Σ wᵢ Sᵢ
+    Basket {
+        option_type: OptionType,
+        weights: Vec<f64>,
+        strike: f64,
+    },
+    /// On the first asset less the second, S₁ - S₂
+    Spread {
+        option_type: OptionType,
+        strike: f64,
+    },
+    /// On the best performer, max Sᵢ
+    BestOf {
+        option_type: OptionType,
+        strike: f64,
+    },
+    /// On the worst performer, min Sᵢ
+    WorstOf {
+        option_type: OptionType,
+        strike: f64,
+    },
+}
+
+impl MultiAssetPayoff {
+    pub fn payout(&self, terminal: &[f64]) -> f64 {
+        let (option_type, strike, underlying) = match self {
+            MultiAssetPayoff::Basket {
+                option_type,
+                weights,
+                strike,
+            } => (
+                option_type,
+                strike,
+                weights.iter().zip(terminal).map(|(w, s)| w * s).sum(),
+            ),
+            MultiAssetPayoff::Spread {
+                option_type,
+                strike,
+            } => (option_type, strike, terminal[0] - terminal[1]),
+            MultiAssetPayoff::BestOf {
+                option_type,
+                strike,
+            } => (
+                option_type,
+                strike,
+                terminal.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            ),
+            MultiAssetPayoff::WorstOf {
+                option_type,
+                strike,
+            } => (
+                option_type,
+                strike,
+                terminal.iter().copied().fold(f64::INFINITY, f64::min),
+            ),
+        };
+        match option_type {
+            OptionType::Call => (underlying - strike).max(0.0),
+            OptionType::Put => (strike - underlying).max(0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::McConfig;
+    use crate::processes::GeometricBrownianMotion;
+
+    fn gbm(spot: f64, volatility: f64) -> Box<dyn StochasticProcess> {
+        Box::new(GeometricBrownianMotion {
+            spot,
+            drift: 0.03,
+            volatility,
+        })
+    }
+
+    #[test]
+    fn test_correlation_checks_and_repair() {
+        let noisy = vec![
+            vec![1.0, 0.9, 0.2],
+            vec![0.9, 1.0, -0.4],
+            vec![0.2, -0.4, 1.0],
+        ];
+        assert!(validate_correlation(&noisy).is_err());
+        assert!(validate_correlation(&[vec![1.0, 1.2], vec![1.2, 1.0]]).is_err());
+        let repaired = nearest_correlation(&noisy).unwrap();
+        validate_correlation(&repaired).unwrap();
+        assert!(cholesky(&repaired).is_some());
+        // the repair moves the estimate as little as it must
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((repaired[i][j] - noisy[i][j]).abs() < 0.15);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spread_and_basket_pricing() {
+        let engine = MonteCarloEngine::new(McConfig::new(40_000, 1, 5));
+        let correlation = vec![vec![1.0, 0.6], vec![0.6, 1.0]];
+        let simulator = MultiAssetSimulator::new(
+            vec![gbm(100.0, 0.2), gbm(100.0, 0.3)],
+            &correlation,
+            Decomposition::Cholesky,
+        )
+        .unwrap();
+        // exchange option S₁ - S₂ at zero strike: Margrabe with σ² = σ₁² + σ₂² - 2ρσ₁σ₂
+        let exchange = simulator.price_payoff(
+            &engine,
+            0.03,
+            1.0,
+            &MultiAssetPayoff::Spread {
+                option_type: OptionType::Call,
+                strike: 0.0,
+            },
+        );
+        let sigma = (0.04f64 + 0.09 - 2.0 * 0.6 * 0.2 * 0.3).sqrt();
+        let margrabe = crate::Options::new_call(100.0, 100.0, sigma, 0.0, 1.0, None).bs_pricing();
+        assert!((exchange.price - margrabe).abs() < 3.0 * exchange.std_error + 0.02);
+
+        // one principal component of two assets drives them perfectly together
+        let pca = MultiAssetSimulator::new(
+            vec![gbm(100.0, 0.2), gbm(100.0, 0.2)],
+            &correlation,
+            Decomposition::Pca { factors: 1 },
+        )
+        .unwrap();
+        assert!((pca.simulated_correlation()[0][1] - 1.0).abs() < 1e-12);
+        let best = MultiAssetPayoff::BestOf {
+            option_type: OptionType::Call,
+            strike: 100.0,
+        };
+        let single = crate::Options::new_call(100.0, 100.0, 0.2, 0.03, 1.0, None).bs_pricing();
+        let result = pca.price_payoff(&engine, 0.03, 1.0, &best);
+        assert!((result.price - single).abs() < 3.0 * result.std_error + 0.02);
+        assert!(
+            MultiAssetSimulator::new(vec![gbm(100.0, 0.2)], &correlation, Decomposition::Cholesky)
+                .is_err()
+        );
+    }
+}
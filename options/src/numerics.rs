@@ -0,0 +1,110 @@
+// House numerical policy: one place to set the tolerances, iteration caps, bump sizes and
+// discretizations the engines otherwise default on their own.
+use crate::error::PricingError;
+use crate::lattice::binomial::DEFAULT_STEPS;
+use crate::solver::{DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE};
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// Default relative bump of central finite differences
+pub const DEFAULT_RELATIVE_BUMP: f64 = 1e-4;
+/// Default number of quadrature points of tail integrals
+pub const DEFAULT_INTEGRATION_POINTS: usize = 200;
+
+/// Numerical settings passed to the `*_with` entry points; the plain entry points use
+/// `NumericsConfig::default()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NumericsConfig {
+    /// Absolute tolerance of root finders
+    pub tolerance: f64,
+    /// Iteration cap of root finders
+    pub max_iterations: usize,
+    /// Bump of central differences, as a fraction of the bumped input
+    pub relative_bump: f64,
+    /// Time steps of binomial trees
+    pub tree_steps: usize,
+    /// Quadrature points of integrals such as the expected shortfall tail average
+    pub integration_points: usize,
+}
+
+impl Default for NumericsConfig {
+    fn default() -> Self {
+        NumericsConfig {
+            tolerance: DEFAULT_TOLERANCE,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            relative_bump: DEFAULT_RELATIVE_BUMP,
+            tree_steps: DEFAULT_STEPS,
+            integration_points: DEFAULT_INTEGRATION_POINTS,
+        }
+    }
+}
+
+impl NumericsConfig {
+    /// Rejects settings no engine can run with, so a bad policy fails where it is loaded
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let invalid = |field: &str, value: String| {
+            Err(PricingError::InvalidInput(format!(
+                "numerics {} {} must be positive",
+                field, value
+            )))
+        };
+        if !(self.tolerance.is_finite() && self.tolerance > 0.0) {
+            return invalid("tolerance", self.tolerance.to_string());
+        }
+        if !(self.relative_bump.is_finite() && self.relative_bump > 0.0) {
+            return invalid("relative_bump", self.relative_bump.to_string());
+        }
+        for (field, value) in [
+            ("max_iterations", self.max_iterations),
+            ("tree_steps", self.tree_steps),
+            ("integration_points", self.integration_points),
+        ] {
+            if value == 0 {
+                return invalid(field, value.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+    use crate::lattice::binomial::BinomialTree;
+
+    #[test]
+    fn test_config_reaches_engines() {
+        let option = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let defaults = NumericsConfig::default();
+        assert!(defaults.validate().is_ok());
+        let price = option.bs_pricing();
+        assert_eq!(
+            option.implied_volatility_with(price, &defaults),
+            option.implied_volatility(price)
+        );
+
+        let loose = NumericsConfig {
+            tolerance: 1e-3,
+            max_iterations: 2,
+            ..defaults
+        };
+        assert!(matches!(
+            option.implied_volatility_with(price, &loose),
+            Err(PricingError::NoConvergence { iterations: 2 })
+        ));
+
+        let coarse = NumericsConfig {
+            tree_steps: 25,
+            ..defaults
+        };
+        assert_eq!(BinomialTree::from_config(&coarse), BinomialTree::new(25));
+        let zero_steps = NumericsConfig {
+            tree_steps: 0,
+            ..defaults
+        };
+        assert!(zero_steps.validate().is_err());
+    }
+}
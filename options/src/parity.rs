@@ -0,0 +1,223 @@
+// Carry implied by listed quotes. Put-call parity C - P = D (F - K) is linear in the strike, so a
+// weighted fit of the call-put spread across strikes of one expiry gives the discount factor D and
+// the forward F; together they give the dividend yield (or borrow cost) the market is pricing,
+// without assuming a rate.
+use crate::OptionType;
+use crate::error::PricingError;
+use crate::math::float::{exp, ln};
+use crate::quote::OptionQuote;
+
+// Expiries, in years, and strikes this close are matched into one call-put pair
+const MATCH_TOLERANCE: f64 = 1e-9;
+// Floor on a pair's combined bid-ask spread, so mid-only quotes weigh equally
+const MIN_SPREAD: f64 = 1e-8;
+
+/// Forward and discounting implied by the call-put pairs of one expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpliedCarry {
+    pub expiry: f64,
+    pub spot: f64,
+    pub forward: f64,
+    pub discount_factor: f64,
+    /// Strikes quoted on both sides
+    pub pairs: usize,
+    /// Per pair, the strike and the mid call-put spread less its fitted value; large residuals
+    /// flag stale or crossed quotes
+    pub residuals: Vec<(f64, f64)>,
+}
+
+impl ImpliedCarry {
+    /// Fits parity across the call-put pairs of `quotes`, which must share one expiry.
+    ///
+    /// Pairs are weighted by the inverse square of their combined bid-ask spread. With a single
+    /// strike the discount factor is taken from the quoted rate and only the forward is implied.
+    /// Fails without any matched pair
+    pub fn from_quotes(quotes: &[OptionQuote]) -> Result<Self, PricingError> {
+        let Some(first) = quotes.first() else {
+            return Err(PricingError::InvalidInput("no quotes".to_string()));
+        };
+        if quotes
+            .iter()
+            .any(|quote| (quote.expiry - first.expiry).abs() > MATCH_TOLERANCE)
+        {
+            return Err(PricingError::InvalidInput(
+                "parity quotes must share one expiry".to_string(),
+            ));
+        }
+        // (strike, mid call - mid put, weight)
+        let pairs: Vec<(f64, f64, f64)> = quotes
+            .iter()
+            .filter(|quote| quote.option_type == OptionType::Call)
+            .filter_map(|call| {
+                let put = quotes.iter().find(|quote| {
+                    quote.option_type == OptionType::Put
+                        && (quote.strike - call.strike).abs() < MATCH_TOLERANCE
+                })?;
+                let spread = (call.spread() + put.spread()).max(MIN_SPREAD);
+                Some((call.strike, call.mid() - put.mid(), 1.0 / (spread * spread)))
+            })
+            .collect();
+        let (discount_factor, forward) = match pairs.as_slice() {
+            [] => {
+                return Err(PricingError::InvalidInput(format!(
+                    "no strike quoted on both sides at expiry {}",
+                    first.expiry
+                )));
+            }
+            [(strike, spread, _)] => {
+                // F = K + (C - P) / D at the quoted rate
+                let discount = exp(-first.risk_free_rate * first.expiry);
+                (discount, strike + spread / discount)
+            }
+            _ => {
+                // weighted least squares of C - P = a - b K, with D = b and F = a / b
+                let total: f64 = pairs.iter().map(|p| p.2).sum();
+                let mean_k = pairs.iter().map(|p| p.2 * p.0).sum::<f64>() / total;
+                let mean_y = pairs.iter().map(|p| p.2 * p.1).sum::<f64>() / total;
+                let (mut sxy, mut sxx) = (0.0, 0.0);
+                for &(k, y, w) in &pairs {
+                    sxy += w * (k - mean_k) * (y - mean_y);
+                    sxx += w * (k - mean_k) * (k - mean_k);
+                }
+                let discount = -sxy / sxx;
+                if !(discount > 0.0 && discount.is_finite()) {
+                    return Err(PricingError::InvalidInput(format!(
+                        "call-put spreads at expiry {} imply a discount factor of {}",
+                        first.expiry, discount
+                    )));
+                }
+                (discount, mean_k + mean_y / discount)
+            }
+        };
+        let residuals = pairs
+            .iter()
+            .map(|&(k, y, _)| (k, y - discount_factor * (forward - k)))
+            .collect();
+        Ok(ImpliedCarry {
+            expiry: first.expiry,
+            spot: first.spot,
+            forward,
+            discount_factor,
+            pairs: pairs.len(),
+            residuals,
+        })
+    }
+    /// Continuously compounded rate of the implied discount factor
+    pub fn rate(&self) -> f64 {
+        -ln(self.discount_factor) / self.expiry
+    }
+    /// Continuous yield the forward carries below the rate, dividends and borrow together
+    ///
+    /// Formula: q = -ln(F D / S) / T
+    pub fn dividend_yield(&self) -> f64 {
+        -ln(self.forward * self.discount_factor / self.spot) / self.expiry
+    }
+    /// Implied yield in excess of a known dividend yield, the cost of borrowing the stock
+    pub fn borrow_cost(&self, dividend_yield: f64) -> f64 {
+        self.dividend_yield() - dividend_yield
+    }
+    /// The quote restated with the implied rate and yield, so its forward is the implied one
+    pub fn apply(&self, quote: &OptionQuote) -> OptionQuote {
+        OptionQuote {
+            risk_free_rate: self.rate(),
+            dividend_yield: Some(self.dividend_yield()),
+            ..*quote
+        }
+    }
+}
+
+/// Implied carry of every expiry of a chain with at least one call-put pair, by expiry
+pub fn implied_carry_term_structure(
+    quotes: &[OptionQuote],
+) -> Result<Vec<ImpliedCarry>, PricingError> {
+    let mut sorted = quotes.to_vec();
+    sorted.sort_by(|a, b| a.expiry.total_cmp(&b.expiry));
+    let carries: Vec<ImpliedCarry> = sorted
+        .chunk_by(|a, b| (b.expiry - a.expiry).abs() < MATCH_TOLERANCE)
+        .filter_map(|slice| ImpliedCarry::from_quotes(slice).ok())
+        .collect();
+    if carries.is_empty() {
+        return Err(PricingError::InvalidInput(
+            "no expiry quoted on both sides".to_string(),
+        ));
+    }
+    Ok(carries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(expiry: f64, strikes: &[f64], spread: f64) -> Vec<OptionQuote> {
+        // priced at a 4% rate and a 1.5% dividend yield with a skew, quoted with no carry
+        let mut quotes = Vec::new();
+        for &strike in strikes {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let vol = 0.2 - 0.1 * (strike / 100.0 - 1.0);
+                let option = crate::Options::new_with_type(
+                    option_type,
+                    strike,
+                    100.0,
+                    vol,
+                    0.04,
+                    expiry,
+                    Some(0.015),
+                );
+                let mid = option.bs_pricing();
+                quotes.push(OptionQuote::new(
+                    option_type,
+                    strike,
+                    expiry,
+                    mid - 0.5 * spread,
+                    mid + 0.5 * spread,
+                    100.0,
+                    0.0,
+                    None,
+                ));
+            }
+        }
+        quotes
+    }
+
+    #[test]
+    fn test_parity_recovers_carry() {
+        let quotes = chain(0.5, &[90.0, 95.0, 100.0, 105.0, 110.0], 0.1);
+        let carry = ImpliedCarry::from_quotes(&quotes).unwrap();
+        assert_eq!(carry.pairs, 5);
+        assert!((carry.rate() - 0.04).abs() < 1e-10);
+        assert!((carry.dividend_yield() - 0.015).abs() < 1e-10);
+        assert!((carry.forward - 100.0 * (0.025f64 * 0.5).exp()).abs() < 1e-8);
+        assert!(carry.residuals.iter().all(|(_, r)| r.abs() < 1e-10));
+        assert!((carry.borrow_cost(0.01) - 0.005).abs() < 1e-10);
+        // restated quotes carry the implied forward
+        let restated = carry.apply(&quotes[0]);
+        assert!((restated.forward() - carry.forward).abs() < 1e-8);
+
+        // a single pair falls back to the quoted rate for discounting
+        let single = ImpliedCarry::from_quotes(&chain(0.5, &[100.0], 0.1)).unwrap();
+        assert_eq!(single.discount_factor, 1.0);
+        assert!(single.forward < carry.forward);
+        // a calls-only chain has nothing to match
+        let calls: Vec<_> = quotes
+            .iter()
+            .copied()
+            .filter(|q| q.option_type == OptionType::Call)
+            .collect();
+        assert!(ImpliedCarry::from_quotes(&calls).is_err());
+    }
+
+    #[test]
+    fn test_carry_term_structure_weights_tight_quotes() {
+        let mut quotes = chain(1.0, &[80.0, 90.0, 100.0, 110.0, 120.0], 0.05);
+        // a stale, wide pair far from parity barely moves the fit
+        quotes[0].bid += 1.5;
+        quotes[0].ask = quotes[0].bid + 5.0;
+        quotes.extend(chain(0.25, &[95.0, 100.0, 105.0], 0.05));
+        let term = implied_carry_term_structure(&quotes).unwrap();
+        assert_eq!(term.len(), 2);
+        assert!(term[0].expiry < term[1].expiry);
+        assert!((term[1].dividend_yield() - 0.015).abs() < 1e-3);
+        let stale = term[1].residuals[0].1;
+        assert!(stale.abs() > 1.0 && term[1].residuals[2].1.abs() < 0.1);
+    }
+}
@@ -0,0 +1,189 @@
+// Payoffs built from primitives into an expression tree, so bespoke structures such as capped
+// participation notes are priced by the existing engines without a dedicated contract type.
+// Expressions read the whole simulated path; those using only `Spot` and constants are also
+// path-independent and price on trees.
+use crate::Options;
+use crate::error::PricingError;
+use crate::lattice::ExerciseStyle;
+use crate::lattice::barrier::Barrier;
+use crate::lattice::binomial::BinomialTree;
+use alloc::boxed::Box;
+use alloc::format;
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// An expression evaluated on a path of underlying fixings, the first at the start and the
+/// last at maturity.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payoff {
+    /// Underlying level at maturity
+    Spot,
+    Const(f64),
+    /// Arithmetic average of every fixing on the path, the start included
+    Average,
+    /// One while the barrier leaves the contract alive: knock-outs never breached on the path,
+    /// knock-ins breached at least once; zero otherwise
+    Alive(Barrier),
+    Max(Box<Payoff>, Box<Payoff>),
+    Min(Box<Payoff>, Box<Payoff>),
+    Add(Box<Payoff>, Box<Payoff>),
+    Sub(Box<Payoff>, Box<Payoff>),
+    Mul(Box<Payoff>, Box<Payoff>),
+}
+
+impl Payoff {
+    pub fn max(self, other: Payoff) -> Payoff {
+        Payoff::Max(Box::new(self), Box::new(other))
+    }
+    pub fn min(self, other: Payoff) -> Payoff {
+        Payoff::Min(Box::new(self), Box::new(other))
+    }
+    /// Formula: max(S - K, 0)
+    pub fn call(strike: f64) -> Payoff {
+        (Payoff::Spot - Payoff::Const(strike)).max(Payoff::Const(0.0))
+    }
+    /// Formula: max(K - S, 0)
+    pub fn put(strike: f64) -> Payoff {
+        (Payoff::Const(strike) - Payoff::Spot).max(Payoff::Const(0.0))
+    }
+    /// Whether the value depends on fixings before maturity
+    pub fn is_path_dependent(&self) -> bool {
+        match self {
+            Payoff::Spot | Payoff::Const(_) => false,
+            Payoff::Average | Payoff::Alive(_) => true,
+            Payoff::Max(a, b)
+            | Payoff::Min(a, b)
+            | Payoff::Add(a, b)
+            | Payoff::Sub(a, b)
+            | Payoff::Mul(a, b) => a.is_path_dependent() || b.is_path_dependent(),
+        }
+    }
+    /// Value on a path holding at least one fixing
+    pub fn evaluate(&self, path: &[f64]) -> f64 {
+        match self {
+            Payoff::Spot => path[path.len() - 1],
+            Payoff::Const(value) => *value,
+            Payoff::Average => path.iter().sum::<f64>() / path.len() as f64,
+            Payoff::Alive(barrier) => {
+                let breached = path.iter().any(|&s| barrier.is_breached(s));
+                if breached != barrier.kind.is_knock_out() {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Payoff::Max(a, b) => a.evaluate(path).max(b.evaluate(path)),
+            Payoff::Min(a, b) => a.evaluate(path).min(b.evaluate(path)),
+            Payoff::Add(a, b) => a.evaluate(path) + b.evaluate(path),
+            Payoff::Sub(a, b) => a.evaluate(path) - b.evaluate(path),
+            Payoff::Mul(a, b) => a.evaluate(path) * b.evaluate(path),
+        }
+    }
+}
+
+impl From<f64> for Payoff {
+    fn from(value: f64) -> Self {
+        Payoff::Const(value)
+    }
+}
+
+impl Add for Payoff {
+    type Output = Payoff;
+    fn add(self, other: Payoff) -> Payoff {
+        Payoff::Add(Box::new(self), Box::new(other))
+    }
+}
+
+impl Sub for Payoff {
+    type Output = Payoff;
+    fn sub(self, other: Payoff) -> Payoff {
+        Payoff::Sub(Box::new(self), Box::new(other))
+    }
+}
+
+impl Mul for Payoff {
+    type Output = Payoff;
+    fn mul(self, other: Payoff) -> Payoff {
+        Payoff::Mul(Box::new(self), Box::new(other))
+    }
+}
+
+impl Mul<f64> for Payoff {
+    type Output = Payoff;
+    fn mul(self, scale: f64) -> Payoff {
+        self * Payoff::Const(scale)
+    }
+}
+
+impl Neg for Payoff {
+    type Output = Payoff;
+    fn neg(self) -> Payoff {
+        Payoff::Const(-1.0) * self
+    }
+}
+
+impl BinomialTree {
+    /// Tree value of a path-independent payoff, with the market of `option` and its strike and
+    /// type ignored. American exercise pays the payoff at the node's spot
+    pub fn price_payoff(
+        &self,
+        option: &Options,
+        payoff: &Payoff,
+        style: ExerciseStyle,
+    ) -> Result<f64, PricingError> {
+        if payoff.is_path_dependent() {
+            return Err(PricingError::InvalidInput(format!(
+                "{:?} depends on the path and cannot be rolled back on a tree",
+                payoff
+            )));
+        }
+        let payout = |spot: f64| payoff.evaluate(core::slice::from_ref(&spot));
+        Ok(self.roll_back_payout(option, &payout, style))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::barrier::BarrierType;
+
+    #[test]
+    fn test_payoff_expressions() {
+        let path = [100.0, 120.0, 90.0, 110.0];
+        assert_eq!(Payoff::call(100.0).evaluate(&path), 10.0);
+        assert_eq!(Payoff::put(100.0).evaluate(&path), 0.0);
+        assert_eq!(Payoff::Average.evaluate(&path), 105.0);
+        let up_out = Payoff::Alive(Barrier::new(BarrierType::UpAndOut, 115.0));
+        let down_in = Payoff::Alive(Barrier::new(BarrierType::DownAndIn, 95.0));
+        assert_eq!((up_out.clone() * Payoff::call(100.0)).evaluate(&path), 0.0);
+        assert_eq!((down_in * Payoff::call(100.0)).evaluate(&path), 10.0);
+        assert!(up_out.is_path_dependent() && !Payoff::call(100.0).is_path_dependent());
+
+        // capped participation note: par plus 150% of the upside, capped at 20%
+        let upside = (Payoff::Spot * 0.01 - 1.0.into()).max(0.0.into());
+        let note = Payoff::Const(100.0) + (upside * 1.5).min(0.2.into()) * 100.0;
+        assert!((note.evaluate(&path) - 115.0).abs() < 1e-12);
+        assert!((note.evaluate(&[100.0, 150.0]) - 120.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tree_prices_composed_payoff() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let tree = BinomialTree::new(400);
+        let composed = tree
+            .price_payoff(&call, &Payoff::call(100.0), ExerciseStyle::European)
+            .unwrap();
+        assert_eq!(composed, tree.price(&call, ExerciseStyle::European));
+        // a call spread as the difference of two calls
+        let spread = Payoff::call(95.0) - Payoff::call(105.0);
+        let value = tree
+            .price_payoff(&call, &spread, ExerciseStyle::European)
+            .unwrap();
+        let expected = Options::new_call(95.0, 100.0, 0.2, 0.05, 1.0, None).bs_pricing()
+            - Options::new_call(105.0, 100.0, 0.2, 0.05, 1.0, None).bs_pricing();
+        assert!((value - expected).abs() < 0.02);
+        assert!(
+            tree.price_payoff(&call, &Payoff::Average, ExerciseStyle::European)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,152 @@
+// Perpetual American options, which never expire and so have a flat exercise boundary and a
+// closed form (McKean, Merton). They bound finite-maturity American values from above and value
+// real options such as an open-ended right to invest or abandon.
+use crate::math::float::{powf, sqrt};
+use crate::{OptionType, Options};
+
+/// A call or put exercisable at any time, with no expiry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerpetualAmerican {
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    pub rate: f64,
+    /// Continuous dividend yield, zero for none
+    pub dividend_yield: f64,
+}
+
+impl PerpetualAmerican {
+    /// The contract's market with its maturity ignored
+    pub fn from_options(option: &Options) -> Self {
+        PerpetualAmerican {
+            option_type: option.option_type(),
+            strike: option.strike_price(),
+            spot: option.spot_price(),
+            volatility: option.volatility(),
+            rate: option.risk_free_rate(),
+            dividend_yield: option.dividend_yield().unwrap_or(0.0),
+        }
+    }
+    /// Root of ½σ²β(β - 1) + (r - q)β - r = 0 the value grows with, above one for the call and
+    /// negative for the put
+    ///
+    /// Formula: β = -m ± √(m² + 2r/σ²), m = (r - q)/σ² - ½
+    pub fn exponent(&self) -> f64 {
+        let variance = self.volatility * self.volatility;
+        let m = (self.rate - self.dividend_yield) / variance - 0.5;
+        let root = sqrt(m * m + 2.0 * self.rate / variance);
+        match self.option_type {
+            OptionType::Call => -m + root,
+            OptionType::Put => -m - root,
+        }
+    }
+    /// Spot at which exercise becomes optimal, the same at every date: calls exercise at or
+    /// above it, puts at or below. None when exercise is never optimal, for calls without a
+    /// positive dividend yield and puts without a positive rate
+    ///
+    /// Formula: S* = K β / (β - 1)
+    pub fn exercise_boundary(&self) -> Option<f64> {
+        let exercisable = match self.option_type {
+            OptionType::Call => self.dividend_yield > 0.0,
+            OptionType::Put => self.rate > 0.0,
+        };
+        if !exercisable {
+            return None;
+        }
+        let beta = self.exponent();
+        Some(self.strike * beta / (beta - 1.0))
+    }
+    fn exercised(&self, boundary: f64) -> bool {
+        match self.option_type {
+            OptionType::Call => self.spot >= boundary,
+            OptionType::Put => self.spot <= boundary,
+        }
+    }
+    /// Intrinsic value once exercised, otherwise the boundary's intrinsic value scaled by
+    /// (S / S*)^β. Never-exercised calls are worth the spot and puts the strike
+    ///
+    /// Formula: V = |S* - K| (S / S*)^β
+    pub fn price(&self) -> f64 {
+        let Some(boundary) = self.exercise_boundary() else {
+            return match self.option_type {
+                OptionType::Call => self.spot,
+                OptionType::Put => self.strike,
+            };
+        };
+        if self.exercised(boundary) {
+            return (self.spot - self.strike).abs();
+        }
+        (boundary - self.strike).abs() * powf(self.spot / boundary, self.exponent())
+    }
+    /// Formula: Δ = β V / S before exercise, ±1 after
+    pub fn delta(&self) -> f64 {
+        let Some(boundary) = self.exercise_boundary() else {
+            return match self.option_type {
+                OptionType::Call => 1.0,
+                OptionType::Put => 0.0,
+            };
+        };
+        if self.exercised(boundary) {
+            return match self.option_type {
+                OptionType::Call => 1.0,
+                OptionType::Put => -1.0,
+            };
+        }
+        self.exponent() * self.price() / self.spot
+    }
+}
+
+impl Options {
+    /// The contract as a perpetual American, its maturity ignored
+    pub fn perpetual_american(&self) -> PerpetualAmerican {
+        PerpetualAmerican::from_options(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::ExerciseStyle;
+    use crate::lattice::binomial::BinomialTree;
+
+    #[test]
+    fn test_perpetual_put() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None).perpetual_american();
+        // without dividends β = -2r/σ²
+        assert!((put.exponent() + 2.5).abs() < 1e-12);
+        let boundary = put.exercise_boundary().unwrap();
+        assert!((boundary - 100.0 * 2.5 / 3.5).abs() < 1e-10);
+        // value and delta paste smoothly onto intrinsic at the boundary
+        let at = |spot: f64| PerpetualAmerican { spot, ..put };
+        let inside = at(boundary * (1.0 + 1e-9));
+        assert!((inside.price() - (100.0 - boundary)).abs() < 1e-6);
+        assert!((inside.delta() + 1.0).abs() < 1e-6);
+        assert_eq!(at(60.0).price(), 40.0);
+        // a long-dated tree approaches the perpetual value from below
+        let long = Options::new_put(100.0, 100.0, 0.2, 0.05, 100.0, None);
+        let tree = BinomialTree::new(2000).price(&long, ExerciseStyle::American);
+        assert!(tree < put.price() && put.price() - tree < 0.05);
+    }
+
+    #[test]
+    fn test_perpetual_call_needs_dividends() {
+        let call =
+            Options::new_call(100.0, 100.0, 0.25, 0.04, 1.0, Some(0.03)).perpetual_american();
+        let boundary = call.exercise_boundary().unwrap();
+        assert!(boundary > 100.0 && call.exponent() > 1.0);
+        let finite = Options::new_call(100.0, 100.0, 0.25, 0.04, 1.0, Some(0.03));
+        assert!(call.price() > BinomialTree::new(500).american_price(&finite));
+        let beyond = PerpetualAmerican {
+            spot: boundary + 10.0,
+            ..call
+        };
+        assert!((beyond.price() - (beyond.spot - 100.0)).abs() < 1e-12);
+        // without dividends the call is never exercised and is worth the underlying
+        let undivided = PerpetualAmerican {
+            dividend_yield: 0.0,
+            ..call
+        };
+        assert!(undivided.exercise_boundary().is_none() && undivided.price() == 100.0);
+    }
+}
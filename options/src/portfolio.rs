@@ -0,0 +1,409 @@
+use crate::Options;
+use crate::error::PricingError;
+use crate::market::MarketQuote;
+use crate::math::float::exp;
+use crate::math::sum::compensated_sum;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Part of a position closed out at `price` per share.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CloseEvent {
+    /// Contracts closed, signed like the position they came from
+    pub quantity: f64,
+    pub price: f64,
+    pub commission: f64,
+}
+
+/// A signed holding of a single option contract on a named underlying, with the lifecycle
+/// needed for P&L: what was paid to open it and what has since been closed.
+///
+/// Negative quantities are short positions. Quantities count contracts and prices are per
+/// share, so every value and Greek is scaled by the multiplier, e.g. 100 shares per listed
+/// equity option
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    pub underlying: String,
+    pub option: Options,
+    /// Contracts still open
+    pub quantity: f64,
+    #[cfg_attr(feature = "serde", serde(default = "unit_multiplier"))]
+    pub multiplier: f64,
+    /// Premium per share the position was opened at
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub entry_price: Option<f64>,
+    /// Fees paid opening and closing
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub commissions: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub closes: Vec<CloseEvent>,
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn unit_multiplier() -> f64 {
+    1.0
+}
+
+impl Position {
+    /// Position with a multiplier of one and no entry recorded
+    pub fn new(underlying: &str, option: Options, quantity: f64) -> Self {
+        Position {
+            underlying: underlying.to_string(),
+            option,
+            quantity,
+            multiplier: 1.0,
+            entry_price: None,
+            commissions: 0.0,
+            closes: Vec::new(),
+        }
+    }
+    pub fn with_multiplier(self, multiplier: f64) -> Self {
+        Position { multiplier, ..self }
+    }
+    /// Records the premium per share paid, or received when short, and the opening commission
+    pub fn with_entry(self, price: f64, commission: f64) -> Self {
+        Position {
+            entry_price: Some(price),
+            commissions: self.commissions + commission,
+            ..self
+        }
+    }
+    /// Share-equivalent size: open contracts times the multiplier
+    pub fn units(&self) -> f64 {
+        self.quantity * self.multiplier
+    }
+    pub fn value(&self) -> f64 {
+        self.units() * self.option.bs_pricing()
+    }
+    /// Closes `quantity` contracts, a positive count whichever side the position is on, at
+    /// `price` per share. Fails without an entry price or when closing more than is open
+    pub fn close(
+        &mut self,
+        quantity: f64,
+        price: f64,
+        commission: f64,
+    ) -> Result<(), PricingError> {
+        if self.entry_price.is_none() {
+            return Err(PricingError::InvalidInput(
+                "cannot close a position without an entry price".to_string(),
+            ));
+        }
+        if !(quantity > 0.0 && quantity <= self.quantity.abs()) {
+            return Err(PricingError::InvalidInput(format!(
+                "cannot close {} of {} open contracts",
+                quantity, self.quantity
+            )));
+        }
+        let closed = quantity.copysign(self.quantity);
+        self.quantity -= closed;
+        self.commissions += commission;
+        self.closes.push(CloseEvent {
+            quantity: closed,
+            price,
+            commission,
+        });
+        Ok(())
+    }
+    /// P&L locked in by the closes, net of every commission paid
+    ///
+    /// Formula: Σ q_close m (P_close - P_entry) - commissions
+    pub fn realized_pnl(&self) -> f64 {
+        let entry = self.entry_price.unwrap_or(0.0);
+        compensated_sum(
+            self.closes
+                .iter()
+                .map(|close| close.quantity * self.multiplier * (close.price - entry)),
+        ) - self.commissions
+    }
+    /// P&L of the open contracts revalued at `market`, before fees. Fails without an entry price
+    ///
+    /// Formula: q m (V(market) - P_entry)
+    pub fn unrealized_pnl(&self, market: &MarketQuote) -> Result<f64, PricingError> {
+        let Some(entry) = self.entry_price else {
+            return Err(PricingError::InvalidInput(format!(
+                "position on {} has no entry price",
+                self.underlying
+            )));
+        };
+        let option = self
+            .option
+            .with_market(market.spot, market.volatility, market.risk_free_rate);
+        Ok(self.units() * (option.bs_pricing() - entry))
+    }
+}
+
+/// Shares of an underlying held outright, with delta one per share.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StockPosition {
+    pub underlying: String,
+    pub spot: f64,
+    pub quantity: f64,
+}
+
+impl StockPosition {
+    pub fn new(underlying: &str, spot: f64, quantity: f64) -> Self {
+        StockPosition {
+            underlying: underlying.to_string(),
+            spot,
+            quantity,
+        }
+    }
+    pub fn value(&self) -> f64 {
+        self.quantity * self.spot
+    }
+}
+
+/// Cash paying `amount` at `maturity` years, discounted at its own rate.
+///
+/// A zero maturity is a plain balance valued at par with no rate exposure
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CashPosition {
+    pub amount: f64,
+    pub risk_free_rate: f64,
+    pub maturity: f64,
+}
+
+impl CashPosition {
+    pub fn new(amount: f64, risk_free_rate: f64, maturity: f64) -> Self {
+        CashPosition {
+            amount,
+            risk_free_rate,
+            maturity,
+        }
+    }
+    /// Formula: A * e^(-rT)
+    pub fn value(&self) -> f64 {
+        self.amount * exp(-self.risk_free_rate * self.maturity)
+    }
+    /// Accrual as the payment date approaches
+    ///
+    /// Formula: Θ = r * A * e^(-rT)
+    pub fn theta(&self) -> f64 {
+        self.risk_free_rate * self.value()
+    }
+    /// Formula: ρ = -T * A * e^(-rT)
+    pub fn rho(&self) -> f64 {
+        -self.maturity * self.value()
+    }
+}
+
+/// A collection of option, stock and cash positions valued and risk-managed together.
+///
+/// Stock and cash let covered calls, collars and buy-writes report their net exposure
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Portfolio {
+    positions: Vec<Position>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    stocks: Vec<StockPosition>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cash: Vec<CashPosition>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Portfolio::default()
+    }
+    pub fn add(&mut self, position: Position) {
+        self.positions.push(position);
+    }
+    pub fn add_stock(&mut self, stock: StockPosition) {
+        self.stocks.push(stock);
+    }
+    pub fn add_cash(&mut self, cash: CashPosition) {
+        self.cash.push(cash);
+    }
+    /// Option positions only; stock and cash are held separately
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+    /// Option positions, e.g. to close part of one
+    pub fn positions_mut(&mut self) -> &mut [Position] {
+        &mut self.positions
+    }
+    pub fn stocks(&self) -> &[StockPosition] {
+        &self.stocks
+    }
+    pub fn cash(&self) -> &[CashPosition] {
+        &self.cash
+    }
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.stocks.is_empty() && self.cash.is_empty()
+    }
+    /// Long stock with a short call on it
+    pub fn covered_call(underlying: &str, shares: f64, call: Options) -> Self {
+        let mut portfolio = Portfolio::new();
+        portfolio.add_stock(StockPosition::new(underlying, call.spot_price(), shares));
+        portfolio.add(Position::new(underlying, call, -shares));
+        portfolio
+    }
+    /// Long stock with a long put on it
+    pub fn protective_put(underlying: &str, shares: f64, put: Options) -> Self {
+        let mut portfolio = Portfolio::new();
+        portfolio.add_stock(StockPosition::new(underlying, put.spot_price(), shares));
+        portfolio.add(Position::new(underlying, put, shares));
+        portfolio
+    }
+    /// Long stock, long a put below and short a call above
+    pub fn collar(underlying: &str, shares: f64, put: Options, call: Options) -> Self {
+        let mut portfolio = Portfolio::protective_put(underlying, shares, put);
+        portfolio.add(Position::new(underlying, call, -shares));
+        portfolio
+    }
+    /// Distinct underlyings held through options, then stock, in order of first appearance
+    pub fn underlyings(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        let held = self.positions.iter().map(|position| &position.underlying);
+        for name in held.chain(self.stocks.iter().map(|stock| &stock.underlying)) {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+    /// Total value of the portfolio (Black-Scholes for options), summed with compensation for
+    /// large books
+    pub fn value(&self) -> f64 {
+        compensated_sum(
+            self.positions
+                .iter()
+                .map(Position::value)
+                .chain(self.stocks.iter().map(StockPosition::value))
+                .chain(self.cash.iter().map(CashPosition::value)),
+        )
+    }
+    /// Option deltas plus one per share held
+    pub fn delta(&self) -> f64 {
+        self.aggregate(Options::delta)
+            + compensated_sum(self.stocks.iter().map(|stock| stock.quantity))
+    }
+    /// Net delta on one underlying
+    pub fn underlying_delta(&self, underlying: &str) -> f64 {
+        let options = self
+            .positions
+            .iter()
+            .filter(|position| position.underlying == underlying)
+            .map(|position| position.units() * position.option.delta());
+        let shares = self
+            .stocks
+            .iter()
+            .filter(|stock| stock.underlying == underlying)
+            .map(|stock| stock.quantity);
+        compensated_sum(options.chain(shares))
+    }
+    pub fn gamma(&self) -> f64 {
+        self.aggregate(Options::gamma)
+    }
+    pub fn vega(&self) -> f64 {
+        self.aggregate(Options::vega)
+    }
+    pub fn theta(&self) -> f64 {
+        self.aggregate(Options::theta) + compensated_sum(self.cash.iter().map(CashPosition::theta))
+    }
+    pub fn rho(&self) -> f64 {
+        self.aggregate(Options::rho) + compensated_sum(self.cash.iter().map(CashPosition::rho))
+    }
+    /// Realized P&L of every option position, net of commissions
+    pub fn realized_pnl(&self) -> f64 {
+        compensated_sum(self.positions.iter().map(Position::realized_pnl))
+    }
+    fn aggregate(&self, greek: fn(&Options) -> f64) -> f64 {
+        compensated_sum(
+            self.positions
+                .iter()
+                .map(|position| position.units() * greek(&position.option)),
+        )
+    }
+}
+
+impl FromIterator<Position> for Portfolio {
+    fn from_iter<I: IntoIterator<Item = Position>>(iter: I) -> Self {
+        Portfolio {
+            positions: iter.into_iter().collect(),
+            ..Portfolio::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portfolio_aggregation() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let mut portfolio = Portfolio::new();
+        portfolio.add(Position::new("SPX", call, 2.0));
+        portfolio.add(Position::new("SPX", put, -1.0));
+
+        let expected = 2.0 * call.bs_pricing() - put.bs_pricing();
+        assert!((portfolio.value() - expected).abs() < 1e-12);
+        // long two calls, short one put: net delta 2 * 0.6368 + 0.3632
+        assert!((portfolio.delta() - 1.6368).abs() < 0.001);
+        assert!((portfolio.gamma() - 0.01876).abs() < 0.0001);
+        assert_eq!(portfolio.underlyings(), vec!["SPX"]);
+    }
+
+    #[test]
+    fn test_stock_and_cash_positions() {
+        let call = Options::new_call(105.0, 100.0, 0.2, 0.05, 0.5, None);
+        let mut buy_write = Portfolio::covered_call("XYZ", 100.0, call);
+        buy_write.add_cash(CashPosition::new(-10_000.0, 0.05, 0.5));
+
+        let expected = 100.0 * (100.0 - call.bs_pricing()) - 10_000.0 * (-0.025f64).exp();
+        assert!((buy_write.value() - expected).abs() < 1e-9);
+        // shares carry delta one, the short call gives some back
+        assert!((buy_write.delta() - 100.0 * (1.0 - call.delta())).abs() < 1e-9);
+        assert_eq!(buy_write.underlying_delta("XYZ"), buy_write.delta());
+        assert_eq!(buy_write.gamma(), -100.0 * call.gamma());
+        // borrowed cash gains when rates rise
+        let cash_rho = 0.5 * 10_000.0 * (-0.025f64).exp();
+        assert!((buy_write.rho() - (cash_rho - 100.0 * call.rho())).abs() < 1e-9);
+        assert_eq!(buy_write.underlyings(), vec!["XYZ"]);
+    }
+
+    #[test]
+    fn test_position_lifecycle() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let mut position = Position::new("XYZ", call, 5.0)
+            .with_multiplier(100.0)
+            .with_entry(6.0, 3.25);
+        assert_eq!(position.units(), 500.0);
+        assert!((position.value() - 500.0 * call.bs_pricing()).abs() < 1e-9);
+        let rally = MarketQuote {
+            spot: 110.0,
+            volatility: 0.2,
+            risk_free_rate: 0.05,
+        };
+        let marked = call.with_market(110.0, 0.2, 0.05).bs_pricing();
+        let unrealized = position.unrealized_pnl(&rally).unwrap();
+        assert!((unrealized - 500.0 * (marked - 6.0)).abs() < 1e-9);
+
+        position.close(2.0, 9.5, 1.3).unwrap();
+        assert_eq!(position.quantity, 3.0);
+        assert!((position.realized_pnl() - (200.0 * 3.5 - 4.55)).abs() < 1e-9);
+        assert!(position.close(4.0, 9.5, 1.3).is_err());
+        assert!(
+            Position::new("XYZ", call, 1.0)
+                .close(1.0, 1.0, 0.0)
+                .is_err()
+        );
+
+        // a short closed in full keeps its realized P&L and no longer moves with the market
+        let mut short = Position::new("XYZ", call, -1.0)
+            .with_multiplier(100.0)
+            .with_entry(6.0, 0.0);
+        short.close(1.0, 2.0, 0.0).unwrap();
+        assert_eq!(short.quantity, 0.0);
+        assert!((short.realized_pnl() - 400.0).abs() < 1e-9);
+        assert_eq!(short.unrealized_pnl(&rally).unwrap(), 0.0);
+        let book: Portfolio = [position, short].into_iter().collect();
+        assert!((book.realized_pnl() - (695.45 + 400.0)).abs() < 1e-9);
+        assert!((book.delta() - 300.0 * call.delta()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,41 @@
+// Stable surface of the crate: the types most callers need, importable in one line.
+// Items here only change in a breaking release; anything reachable only through `unstable`
+// may change in any release.
+pub use crate::bachelier::PricingModel;
+pub use crate::conventions::{GreekUnits, ThetaConvention, VegaConvention};
+#[cfg(feature = "std")]
+pub use crate::curves::{EquityCarry, RateCurve};
+#[cfg(feature = "std")]
+pub use crate::engine::{EngineRegistry, Model, PricingEngine};
+pub use crate::error::PricingError;
+#[cfg(feature = "std")]
+pub use crate::instrument::{Instrument, MarketContext, PricingContext};
+pub use crate::lattice::binomial::BinomialTree;
+pub use crate::lattice::trinomial::TrinomialTree;
+pub use crate::lattice::{ExerciseStyle, LatticeEngine};
+#[cfg(feature = "std")]
+pub use crate::market::{MarketQuote, MarketSnapshot};
+#[cfg(feature = "mc")]
+pub use crate::mc::{McConfig, McResult, MonteCarloEngine};
+pub use crate::moneyness::DeltaConvention;
+pub use crate::numerics::NumericsConfig;
+#[cfg(feature = "std")]
+pub use crate::parity::ImpliedCarry;
+pub use crate::payoff::Payoff;
+#[cfg(feature = "std")]
+pub use crate::portfolio::{CashPosition, Portfolio, Position, StockPosition};
+#[cfg(feature = "std")]
+pub use crate::quote::OptionQuote;
+#[cfg(feature = "report")]
+pub use crate::report::{RiskReport, VarMethod};
+#[cfg(feature = "std")]
+pub use crate::scenario::{ScenarioCube, ScenarioGrid, Shock};
+#[cfg(feature = "std")]
+pub use crate::strategy::{Leg, Strategy};
+#[cfg(feature = "std")]
+pub use crate::var::{RiskFactors, VarReport};
+#[cfg(feature = "std")]
+pub use crate::vol::smile::{Smile, WingExtrapolation};
+#[cfg(feature = "calibration")]
+pub use crate::vol::surface::VolSurface;
+pub use crate::{Call, OptionType, Options, Put, Vanilla};
@@ -0,0 +1,395 @@
+// Dynamics the Monte Carlo engine simulates. A process owns its state vector and advances it one
+// step given the Brownian increments of that step, so custom models plug into the engine without
+// touching its seeding, antithetics or parallel reduction.
+use crate::fourier::levy::VarianceGammaModel;
+use crate::fourier::{HestonModel, MertonJumpDiffusion};
+use crate::math::distributions::gamma_inverse_cdf;
+use crate::math::float::{exp, sqrt};
+use crate::math::normal::Normal;
+
+/// A Markov process driven by independent Brownian motions.
+pub trait StochasticProcess: Sync {
+    /// Length of the state vector
+    fn dimension(&self) -> usize;
+    /// Independent Brownian drivers consumed per step
+    fn factors(&self) -> usize;
+    fn initial_state(&self) -> Vec<f64>;
+    /// Advances `state` from `t` to `t + dt`; `dw` holds one increment per factor, each N(0, dt)
+    fn evolve(&self, state: &mut [f64], t: f64, dt: f64, dw: &[f64]);
+    /// Value the payoff observes, the first state variable unless overridden
+    fn observable(&self, state: &[f64]) -> f64 {
+        state[0]
+    }
+}
+
+/// Geometric Brownian motion dS = μ S dt + σ S dW, stepped exactly in log space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometricBrownianMotion {
+    pub spot: f64,
+    /// Risk-neutral drift r - q
+    pub drift: f64,
+    pub volatility: f64,
+}
+
+impl StochasticProcess for GeometricBrownianMotion {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        1
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.spot]
+    }
+    /// Formula: S(t+dt) = S(t) exp((μ - σ²/2)dt + σ dW)
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let sigma = self.volatility;
+        state[0] *= exp((self.drift - 0.5 * sigma * sigma) * dt + sigma * dw[0]);
+    }
+}
+
+/// Spot under a volatility that depends on spot and time, stepped with log-Euler.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalVolatility<V> {
+    pub spot: f64,
+    /// Risk-neutral drift r - q
+    pub drift: f64,
+    /// σ(S, t)
+    pub vol: V,
+}
+
+impl<V: Fn(f64, f64) -> f64 + Sync> StochasticProcess for LocalVolatility<V> {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        1
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.spot]
+    }
+    /// Formula: S(t+dt) = S(t) exp((μ - σ²/2)dt + σ dW), σ = σ(S(t), t)
+    fn evolve(&self, state: &mut [f64], t: f64, dt: f64, dw: &[f64]) {
+        let sigma = (self.vol)(state[0], t);
+        state[0] *= exp((self.drift - 0.5 * sigma * sigma) * dt + sigma * dw[0]);
+    }
+}
+
+/// Mean-reverting Gaussian process dX = κ(θ - X)dt + σ dW, stepped with its exact transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrnsteinUhlenbeck {
+    pub initial: f64,
+    /// Speed of mean reversion κ
+    pub kappa: f64,
+    /// Long-run mean θ
+    pub theta: f64,
+    pub sigma: f64,
+}
+
+impl StochasticProcess for OrnsteinUhlenbeck {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        1
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.initial]
+    }
+    /// Formula: X(t+dt) = θ + (X(t) - θ)e^(-κdt) + σ √((1 - e^(-2κdt)) / 2κ) dW/√dt
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let decay = exp(-self.kappa * dt);
+        // Variance of the exact transition, tending to σ²dt as κ → 0
+        let variance = if self.kappa.abs() < 1e-12 {
+            dt
+        } else {
+            (1.0 - decay * decay) / (2.0 * self.kappa)
+        };
+        let z = if dt > 0.0 { dw[0] / sqrt(dt) } else { 0.0 };
+        state[0] = self.theta + (state[0] - self.theta) * decay + self.sigma * sqrt(variance) * z;
+    }
+}
+
+/// Cox-Ingersoll-Ross square-root process dX = κ(θ - X)dt + σ √X dW, stepped with full
+/// truncation Euler so the observed value never goes negative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoxIngersollRoss {
+    pub initial: f64,
+    pub kappa: f64,
+    pub theta: f64,
+    pub sigma: f64,
+}
+
+impl CoxIngersollRoss {
+    /// Whether 2κθ ≥ σ², under which the exact process stays positive
+    pub fn satisfies_feller(&self) -> bool {
+        2.0 * self.kappa * self.theta >= self.sigma * self.sigma
+    }
+}
+
+impl StochasticProcess for CoxIngersollRoss {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        1
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.initial]
+    }
+    /// Formula: X ← X + κ(θ - X⁺)dt + σ √X⁺ dW, X⁺ = max(X, 0)
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let x = state[0].max(0.0);
+        state[0] += self.kappa * (self.theta - x) * dt + self.sigma * sqrt(x) * dw[0];
+    }
+    fn observable(&self, state: &[f64]) -> f64 {
+        state[0].max(0.0)
+    }
+}
+
+/// Heston stochastic volatility: spot and variance driven by correlated Brownian motions, with
+/// log-Euler spot steps and full truncation of the variance. State is (S, v).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heston {
+    pub spot: f64,
+    /// Risk-neutral drift r - q
+    pub drift: f64,
+    /// Initial variance v₀
+    pub variance: f64,
+    pub kappa: f64,
+    /// Long-run variance θ
+    pub theta: f64,
+    /// Volatility of variance ξ
+    pub vol_of_vol: f64,
+    /// Spot-variance correlation ρ
+    pub rho: f64,
+}
+
+impl Heston {
+    /// Process of a Heston model, for instance one calibrated with `fourier::calibrate`
+    pub fn from_model(spot: f64, drift: f64, model: &HestonModel) -> Self {
+        Heston {
+            spot,
+            drift,
+            variance: model.variance,
+            kappa: model.kappa,
+            theta: model.theta,
+            vol_of_vol: model.vol_of_vol,
+            rho: model.rho,
+        }
+    }
+}
+
+impl StochasticProcess for Heston {
+    fn dimension(&self) -> usize {
+        2
+    }
+    fn factors(&self) -> usize {
+        2
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.spot, self.variance]
+    }
+    /// Formula: S ← S exp((μ - v⁺/2)dt + √v⁺ dW₁), v ← v + κ(θ - v⁺)dt + ξ √v⁺ (ρ dW₁ + √(1-ρ²) dW₂)
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let v = state[1].max(0.0);
+        let dw_v = self.rho * dw[0] + sqrt(1.0 - self.rho * self.rho) * dw[1];
+        state[0] *= exp((self.drift - 0.5 * v) * dt + sqrt(v) * dw[0]);
+        state[1] += self.kappa * (self.theta - v) * dt + self.vol_of_vol * sqrt(v) * dw_v;
+    }
+}
+
+/// Variance gamma spot: Brownian motion with drift θ and volatility σ run on a gamma clock of
+/// variance rate ν, the subordinated form of `VarianceGammaModel`. Each step draws the clock's
+/// increment from the second factor by inverting the gamma distribution, so steps are exact
+/// and the path is priced with the engine's seeding and antithetics unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarianceGamma {
+    pub spot: f64,
+    /// Risk-neutral drift r - q
+    pub drift: f64,
+    pub model: VarianceGammaModel,
+}
+
+impl StochasticProcess for VarianceGamma {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        2
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.spot]
+    }
+    /// Formula: S ← S exp((μ + ω)dt + θg + σ√g Z), g = ν Gamma(dt/ν) from Φ(dW₂/√dt), Z = dW₁/√dt
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let VarianceGammaModel {
+            volatility,
+            nu,
+            theta,
+        } = self.model;
+        let uniform = Normal::standard().cdf(dw[1] / sqrt(dt));
+        let clock = nu * gamma_inverse_cdf(dt / nu, uniform);
+        let z = dw[0] / sqrt(dt);
+        state[0] *= exp((self.drift + self.model.compensator()) * dt
+            + theta * clock
+            + volatility * sqrt(clock) * z);
+    }
+}
+
+/// Merton jump-diffusion spot: lognormal diffusion plus Poisson jumps of normally distributed
+/// log-size. Each step draws the number of jumps from the second factor by inverting the Poisson
+/// distribution and their total size from the third, so steps are exact.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JumpDiffusion {
+    pub spot: f64,
+    /// Risk-neutral drift r - q
+    pub drift: f64,
+    pub model: MertonJumpDiffusion,
+}
+
+impl StochasticProcess for JumpDiffusion {
+    fn dimension(&self) -> usize {
+        1
+    }
+    fn factors(&self) -> usize {
+        3
+    }
+    fn initial_state(&self) -> Vec<f64> {
+        vec![self.spot]
+    }
+    /// Formula: S ← S exp((μ - λk - σ²/2)dt + σdW₁ + nμ_J + δ√n Z), n = Poisson(λdt) from
+    /// Φ(dW₂/√dt), Z = dW₃/√dt, k = e^(μ_J + δ²/2) - 1
+    fn evolve(&self, state: &mut [f64], _t: f64, dt: f64, dw: &[f64]) {
+        let MertonJumpDiffusion {
+            volatility,
+            intensity,
+            jump_mean,
+            jump_vol,
+        } = self.model;
+        let compensator = intensity * (exp(jump_mean + 0.5 * jump_vol * jump_vol) - 1.0);
+        let uniform = Normal::standard().cdf(dw[1] / sqrt(dt));
+        let jumps = poisson_inverse_cdf(intensity * dt, uniform) as f64;
+        state[0] *= exp(
+            (self.drift - compensator - 0.5 * volatility * volatility) * dt
+                + volatility * dw[0]
+                + jumps * jump_mean
+                + jump_vol * sqrt(jumps) * dw[2] / sqrt(dt),
+        );
+    }
+}
+
+// Smallest n with P(N ≤ n) ≥ p for N Poisson of the given mean, by summing the mass function
+fn poisson_inverse_cdf(mean: f64, p: f64) -> usize {
+    let mut mass = exp(-mean);
+    let mut cumulative = mass;
+    let mut n = 0;
+    while cumulative < p && mass > 0.0 {
+        n += 1;
+        mass *= mean / n as f64;
+        cumulative += mass;
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_processes_step_deterministically() {
+        // with no noise each process follows its drift
+        let gbm = GeometricBrownianMotion {
+            spot: 100.0,
+            drift: 0.05,
+            volatility: 0.2,
+        };
+        let mut state = gbm.initial_state();
+        gbm.evolve(&mut state, 0.0, 1.0, &[0.0]);
+        assert!((state[0] - 100.0 * (0.03f64).exp()).abs() < 1e-12);
+
+        let ou = OrnsteinUhlenbeck {
+            initial: 1.0,
+            kappa: 2.0,
+            theta: 0.0,
+            sigma: 0.3,
+        };
+        let mut state = ou.initial_state();
+        ou.evolve(&mut state, 0.0, 0.5, &[0.0]);
+        assert!((state[0] - (-1.0f64).exp()).abs() < 1e-12);
+
+        let cir = CoxIngersollRoss {
+            initial: 0.01,
+            kappa: 1.0,
+            theta: 0.04,
+            sigma: 0.5,
+        };
+        assert!(!cir.satisfies_feller());
+        let mut state = cir.initial_state();
+        cir.evolve(&mut state, 0.0, 0.1, &[-0.5]);
+        assert!(state[0] < 0.0 && cir.observable(&state) == 0.0);
+
+        let heston = Heston {
+            spot: 100.0,
+            drift: 0.0,
+            variance: 0.04,
+            kappa: 1.5,
+            theta: 0.04,
+            vol_of_vol: 0.5,
+            rho: -0.7,
+        };
+        let mut state = heston.initial_state();
+        heston.evolve(&mut state, 0.0, 0.01, &[0.1, 0.0]);
+        // a spot rally lowers the variance under negative correlation
+        assert!(state[0] > 100.0 && state[1] < 0.04);
+    }
+
+    #[test]
+    fn test_jump_processes_match_fourier_prices() {
+        use crate::Options;
+        use crate::fourier::{CharacteristicFunction, cos_price};
+        use crate::mc::{McConfig, MonteCarloEngine};
+
+        let vg = VarianceGammaModel {
+            volatility: 0.12,
+            nu: 0.2,
+            theta: -0.14,
+        };
+        let merton = MertonJumpDiffusion {
+            volatility: 0.15,
+            intensity: 1.0,
+            jump_mean: -0.1,
+            jump_vol: 0.15,
+        };
+        let call = Options::new_call(95.0, 100.0, 0.2, 0.05, 0.5, None);
+        // Lévy processes are sampled exactly in one step; two give the same law
+        for steps in [1, 2] {
+            let engine = MonteCarloEngine::new(McConfig::new(40_000, steps, 11));
+            let check = |process: &dyn StochasticProcess, model: &dyn CharacteristicFunction| {
+                let result =
+                    engine.price_process(process, 0.05, 0.5, |path| (path[steps] - 95.0).max(0.0));
+                let exact = cos_price(model, &call).unwrap();
+                assert!((result.price - exact).abs() < 3.0 * result.std_error);
+            };
+            let drift = 0.05;
+            check(
+                &VarianceGamma {
+                    spot: 100.0,
+                    drift,
+                    model: vg,
+                },
+                &vg,
+            );
+            check(
+                &JumpDiffusion {
+                    spot: 100.0,
+                    drift,
+                    model: merton,
+                },
+                &merton,
+            );
+        }
+        assert_eq!(poisson_inverse_cdf(1.0, 0.3), 0);
+        assert_eq!(poisson_inverse_cdf(1.0, 0.5), 1);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::error::PricingError;
+use crate::math::float::exp;
+use crate::{OptionType, Options};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Side of a two-way quote a price is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QuoteSide {
+    Bid,
+    #[default]
+    Mid,
+    Ask,
+}
+
+/// A market quote on a listed option, together with the underlying inputs it was observed with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OptionQuote {
+    pub option_type: OptionType,
+    pub strike: f64,
+    /// Time to expiry in years
+    pub expiry: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub spot: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: Option<f64>,
+}
+
+impl OptionQuote {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        option_type: OptionType,
+        strike: f64,
+        expiry: f64,
+        bid: f64,
+        ask: f64,
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: Option<f64>,
+    ) -> Self {
+        OptionQuote {
+            option_type,
+            strike,
+            expiry,
+            bid,
+            ask,
+            spot,
+            risk_free_rate,
+            dividend_yield,
+        }
+    }
+    /// Quote carrying only a mid price, with bid and ask both set to it
+    pub fn from_mid(
+        option_type: OptionType,
+        strike: f64,
+        expiry: f64,
+        mid: f64,
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: Option<f64>,
+    ) -> Self {
+        OptionQuote::new(
+            option_type,
+            strike,
+            expiry,
+            mid,
+            mid,
+            spot,
+            risk_free_rate,
+            dividend_yield,
+        )
+    }
+    pub fn mid(&self) -> f64 {
+        0.5 * (self.bid + self.ask)
+    }
+    pub fn spread(&self) -> f64 {
+        self.ask - self.bid
+    }
+    /// Price on one side of the quote
+    pub fn price(&self, side: QuoteSide) -> f64 {
+        match side {
+            QuoteSide::Bid => self.bid,
+            QuoteSide::Mid => self.mid(),
+            QuoteSide::Ask => self.ask,
+        }
+    }
+    /// Forward of the underlying to the quote's expiry
+    pub fn forward(&self) -> f64 {
+        self.spot * exp((self.risk_free_rate - self.dividend_yield.unwrap_or(0.0)) * self.expiry)
+    }
+    /// The quoted contract priced at the given volatility
+    pub fn to_option(&self, volatility: f64) -> Options {
+        Options::new_with_type(
+            self.option_type,
+            self.strike,
+            self.spot,
+            volatility,
+            self.risk_free_rate,
+            self.expiry,
+            self.dividend_yield,
+        )
+    }
+    /// Black-Scholes implied volatility of the mid price
+    pub fn implied_volatility(&self) -> Result<f64, PricingError> {
+        self.side_implied_volatility(QuoteSide::Mid)
+    }
+    /// Black-Scholes implied volatility of one side of the quote
+    pub fn side_implied_volatility(&self, side: QuoteSide) -> Result<f64, PricingError> {
+        self.to_option(0.2).implied_volatility(self.price(side))
+    }
+    /// Implied volatility of the mid price for an American-style listing
+    pub fn american_implied_volatility(&self) -> Result<f64, PricingError> {
+        self.to_option(0.2).american_implied_volatility(self.mid())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::bonds::FixedCouponBond;
+use crate::rates::hull_white::ShortRateTree;
+
+/// Fixed coupon bond the issuer may redeem early at call prices, or the holder may sell back at
+/// put prices, on the dates of its schedules.
+///
+/// Exercise prices are clean: the coupon due on an exercise date is paid either way
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableBond {
+    pub bond: FixedCouponBond,
+    /// (time, call price) at which the issuer may redeem
+    pub calls: Vec<(f64, f64)>,
+    /// (time, put price) at which the holder may redeem
+    pub puts: Vec<(f64, f64)>,
+}
+
+impl CallableBond {
+    pub fn new(bond: FixedCouponBond) -> Self {
+        CallableBond {
+            bond,
+            calls: Vec::new(),
+            puts: Vec::new(),
+        }
+    }
+    pub fn with_call(mut self, time: f64, price: f64) -> Self {
+        self.calls.push((time, price));
+        self
+    }
+    pub fn with_put(mut self, time: f64, price: f64) -> Self {
+        self.puts.push((time, price));
+        self
+    }
+    /// Value on a short-rate tree spanning the bond, with cash flows and exercise dates moved to
+    /// the nearest step.
+    ///
+    /// At each exercise date V ← min(V, call), then V ← max(V, put)
+    pub fn price(&self, tree: &ShortRateTree) -> f64 {
+        let mut cash = vec![0.0; tree.steps() + 1];
+        for (t, amount) in self.bond.cash_flows() {
+            cash[tree.step_at(t)] += amount;
+        }
+        let schedule = |dates: &[(f64, f64)], step: usize| {
+            dates
+                .iter()
+                .find(|&&(t, _)| tree.step_at(t) == step)
+                .map(|&(_, price)| price)
+        };
+        tree.roll_back(&cash, |step, value| {
+            let called = schedule(&self.calls, step).map_or(value, |call| value.min(call));
+            schedule(&self.puts, step).map_or(called, |put| called.max(put))
+        })
+    }
+    /// Value of the bond without its call and put rights on the same tree
+    pub fn straight_price(&self, tree: &ShortRateTree) -> f64 {
+        CallableBond::new(self.bond).price(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::RateCurve;
+    use crate::rates::hull_white::HullWhite;
+
+    #[test]
+    fn test_callable_and_puttable_bonds() {
+        let curve = RateCurve::new(vec![(1.0, 0.04), (10.0, 0.05)]);
+        let tree = HullWhite::new(0.05, 0.012).tree(&curve, 10.0, 240);
+        let bond = FixedCouponBond::new(100.0, 0.05, 10.0, 2);
+
+        // the straight bond is its curve value
+        let curve_value: f64 = bond
+            .cash_flows()
+            .iter()
+            .map(|&(t, cf)| cf * curve.discount_factor(t))
+            .sum();
+        let straight = CallableBond::new(bond).price(&tree);
+        assert!((straight - curve_value).abs() < 1e-8);
+
+        // a call right belongs to the issuer and a put right to the holder
+        let callable = (3..10).fold(CallableBond::new(bond), |b, t| b.with_call(t as f64, 100.0));
+        let puttable = CallableBond::new(bond).with_put(5.0, 100.0);
+        let (called, put) = (callable.price(&tree), puttable.price(&tree));
+        assert!(called < straight - 0.5);
+        assert!(put > straight && puttable.straight_price(&tree) == straight);
+
+        // more rate volatility makes both rights worth more
+        let volatile = HullWhite::new(0.05, 0.02).tree(&curve, 10.0, 240);
+        assert!(callable.price(&volatile) < called && puttable.price(&volatile) > put);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::curves::RateCurve;
+use crate::math::float::{exp, ln, sqrt};
+
+// Hull and White's bound on the tree width, above which the branching turns inwards
+const BRANCHING_LIMIT: f64 = 0.184;
+
+/// Hull-White one-factor short rate dr = (θ(t) - a r) dt + σ dW, with θ fitted to a yield curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HullWhite {
+    /// Mean reversion speed a
+    pub mean_reversion: f64,
+    /// Normal volatility σ of the short rate
+    pub volatility: f64,
+}
+
+impl HullWhite {
+    pub fn new(mean_reversion: f64, volatility: f64) -> Self {
+        HullWhite {
+            mean_reversion,
+            volatility,
+        }
+    }
+    /// Trinomial tree over `maturity` in `steps` steps, shifted at each step by forward induction
+    /// so that it reprices every zero-coupon bond of `curve` on the step grid
+    pub fn tree(&self, curve: &RateCurve, maturity: f64, steps: usize) -> ShortRateTree {
+        let steps = steps.max(1);
+        let dt = maturity / steps as f64;
+        let a = self.mean_reversion;
+        let dx = self.volatility * sqrt(3.0 * dt);
+        // Formula: j_max = ⌈0.184 / (a dt)⌉, never reached within the tree when a = 0
+        let width = if a * dt > 0.0 {
+            ((BRANCHING_LIMIT / (a * dt)).ceil() as usize).min(steps)
+        } else {
+            steps
+        };
+        let mut tree = ShortRateTree {
+            dt,
+            dx,
+            mean_reversion: a,
+            width,
+            shifts: Vec::with_capacity(steps),
+        };
+
+        // Arrow-Debreu prices of the nodes of the current step
+        let mut prices = vec![0.0; 2 * width + 1];
+        prices[width] = 1.0;
+        for step in 0..steps {
+            let reach = tree.reach(step);
+            let nodes = -reach..=reach;
+            // Formula: α_m = (ln Σ_j Q_(m,j) e^(-j dx dt) - ln P(0, (m+1) dt)) / dt
+            let weighted: f64 = nodes
+                .clone()
+                .map(|j| prices[tree.index(j)] * exp(-(j as f64) * dx * dt))
+                .sum();
+            let shift = (ln(weighted) - ln(curve.discount_factor((step + 1) as f64 * dt))) / dt;
+            tree.shifts.push(shift);
+
+            let mut next = vec![0.0; 2 * width + 1];
+            for j in nodes {
+                let discounted = prices[tree.index(j)] * exp(-tree.short_rate(step, j) * dt);
+                for (k, p) in tree.branches(j) {
+                    next[tree.index(k)] += discounted * p;
+                }
+            }
+            prices = next;
+        }
+        tree
+    }
+}
+
+/// Recombining trinomial lattice of the short rate, r(m, j) = α_m + j dx.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortRateTree {
+    dt: f64,
+    dx: f64,
+    mean_reversion: f64,
+    // Highest |j|, where the branching turns back towards the centre
+    width: usize,
+    shifts: Vec<f64>,
+}
+
+impl ShortRateTree {
+    pub fn steps(&self) -> usize {
+        self.shifts.len()
+    }
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+    /// Step nearest to time t
+    pub fn step_at(&self, t: f64) -> usize {
+        ((t / self.dt + 0.5) as usize).min(self.steps())
+    }
+    /// One-period rate at node j of a step
+    pub fn short_rate(&self, step: usize, j: isize) -> f64 {
+        self.shifts[step] + j as f64 * self.dx
+    }
+    /// Highest |j| reached after `step` steps
+    fn reach(&self, step: usize) -> isize {
+        step.min(self.width) as isize
+    }
+    fn index(&self, j: isize) -> usize {
+        (j + self.width as isize) as usize
+    }
+    // Successor nodes and probabilities matching the mean reversion -a j dx dt and variance σ²dt
+    // of the step, from the middle successor k and the displacement d = j(1 - a dt) - k
+    fn branches(&self, j: isize) -> [(isize, f64); 3] {
+        let width = self.width as isize;
+        let k = j.clamp(1 - width, width - 1);
+        let d = j as f64 * (1.0 - self.mean_reversion * self.dt) - k as f64;
+        // Formula: p_u,d = (1/3 + d² ± d) / 2, p_m = 2/3 - d²
+        [
+            (k + 1, (1.0 / 3.0 + d * d + d) / 2.0),
+            (k, 2.0 / 3.0 - d * d),
+            (k - 1, (1.0 / 3.0 + d * d - d) / 2.0),
+        ]
+    }
+    /// Value today of `cash[m]` paid at every step m, with `exercise(m, value)` replacing the
+    /// value of the flows after step m before the last step, for callable and puttable rights
+    pub fn roll_back(&self, cash: &[f64], exercise: impl Fn(usize, f64) -> f64) -> f64 {
+        let n = self.steps();
+        let flow = |m: usize| cash.get(m).copied().unwrap_or(0.0);
+        // nothing is left to pay after the last step
+        let mut values = vec![0.0; 2 * self.width + 1];
+        for step in (0..n).rev() {
+            let mut earlier = vec![0.0; 2 * self.width + 1];
+            for j in -self.reach(step)..=self.reach(step) {
+                let expected: f64 = self
+                    .branches(j)
+                    .iter()
+                    .map(|&(k, p)| p * (values[self.index(k)] + flow(step + 1)))
+                    .sum();
+                let held = exp(-self.short_rate(step, j) * self.dt) * expected;
+                earlier[self.index(j)] = exercise(step, held);
+            }
+            values = earlier;
+        }
+        values[self.index(0)] + flow(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_reprices_the_curve() {
+        let curve = RateCurve::new(vec![(0.5, 0.03), (5.0, 0.045), (10.0, 0.05)]);
+        let tree = HullWhite::new(0.1, 0.01).tree(&curve, 10.0, 200);
+        for t in [1.0, 3.5, 10.0] {
+            let step = tree.step_at(t);
+            let mut cash = vec![0.0; step + 1];
+            cash[step] = 1.0;
+            let zero = tree.roll_back(&cash, |_, value| value);
+            assert!((zero - curve.discount_factor(t)).abs() < 1e-10, "{}", t);
+        }
+        // branching at the edges keeps probabilities inside [0, 1]
+        for j in -(tree.width as isize)..=tree.width as isize {
+            assert!(
+                tree.branches(j)
+                    .iter()
+                    .all(|&(_, p)| (0.0..=1.0).contains(&p))
+            );
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// Interest rate contracts and models: short-rate trees for callable bonds, and caps, floors and
+// swaptions quoted in Black or normal vols.
+pub mod callable;
+pub mod caps;
+pub mod hull_white;
+pub mod swaptions;
+pub mod vol;
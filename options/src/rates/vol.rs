@@ -0,0 +1,187 @@
+use crate::OptionType;
+use crate::bachelier;
+use crate::error::PricingError;
+use crate::math::float::{ln, sqrt};
+use crate::math::normal::Normal;
+use crate::numerics::NumericsConfig;
+use crate::solver::brent;
+
+// Vol brackets searched by the implied solvers: lognormal vols as fractions, normal vols in
+// rate units (0.01 is 100bp a year)
+const MIN_BLACK_VOL: f64 = 1e-6;
+const MAX_BLACK_VOL: f64 = 10.0;
+const MIN_NORMAL_VOL: f64 = 1e-8;
+const MAX_NORMAL_VOL: f64 = 1.0;
+
+/// A rate volatility together with the model it is quoted under.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateVolatility {
+    /// Lognormal vol of Black-76, which needs positive forwards and strikes
+    Black(f64),
+    /// Normal (Bachelier) vol in rate units, which allows negative rates
+    Normal(f64),
+    /// Lognormal vol of F + shift, which allows rates down to -shift
+    ShiftedBlack { vol: f64, shift: f64 },
+}
+
+impl RateVolatility {
+    /// Undiscounted option value on a forward under the quoted model
+    pub fn price(&self, option_type: OptionType, forward: f64, strike: f64, expiry: f64) -> f64 {
+        match *self {
+            RateVolatility::Black(vol) => black_price(option_type, forward, strike, vol, expiry),
+            RateVolatility::Normal(vol) => normal_price(option_type, forward, strike, vol, expiry),
+            RateVolatility::ShiftedBlack { vol, shift } => {
+                black_price(option_type, forward + shift, strike + shift, vol, expiry)
+            }
+        }
+    }
+    /// The normal vol giving the same price for this forward, strike and expiry
+    pub fn to_normal(&self, forward: f64, strike: f64, expiry: f64) -> Result<f64, PricingError> {
+        match *self {
+            RateVolatility::Normal(vol) => Ok(vol),
+            _ => {
+                // out-of-the-money side, where the time value is not swamped by intrinsic
+                let side = otm_side(forward, strike);
+                let price = self.price(side, forward, strike, expiry);
+                normal_implied_vol(side, forward, strike, expiry, price)
+            }
+        }
+    }
+    /// The lognormal vol giving the same price for this forward, strike and expiry
+    pub fn to_black(&self, forward: f64, strike: f64, expiry: f64) -> Result<f64, PricingError> {
+        match *self {
+            RateVolatility::Black(vol) => Ok(vol),
+            _ => {
+                let side = otm_side(forward, strike);
+                let price = self.price(side, forward, strike, expiry);
+                black_implied_vol(side, forward, strike, expiry, price)
+            }
+        }
+    }
+}
+
+fn otm_side(forward: f64, strike: f64) -> OptionType {
+    if strike >= forward {
+        OptionType::Call
+    } else {
+        OptionType::Put
+    }
+}
+
+fn intrinsic(option_type: OptionType, forward: f64, strike: f64) -> f64 {
+    match option_type {
+        OptionType::Call => (forward - strike).max(0.0),
+        OptionType::Put => (strike - forward).max(0.0),
+    }
+}
+
+/// Undiscounted Black-76 value of an option on a forward.
+///
+/// Formula: φ[F N(φd₁) - K N(φd₂)], d₁,₂ = (ln(F/K) ± σ²T/2) / σ√T
+pub fn black_price(option_type: OptionType, forward: f64, strike: f64, vol: f64, t: f64) -> f64 {
+    let sd = vol * sqrt(t.max(0.0));
+    if sd <= 0.0 || forward <= 0.0 || strike <= 0.0 {
+        return intrinsic(option_type, forward, strike);
+    }
+    let std_norm = Normal::standard();
+    let d1 = (ln(forward / strike) + 0.5 * sd * sd) / sd;
+    let phi = match option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    };
+    phi * (forward * std_norm.cdf(phi * d1) - strike * std_norm.cdf(phi * (d1 - sd)))
+}
+
+/// Undiscounted Bachelier value of an option on a forward with normal vol σ.
+///
+/// Formula: φ(F - K) N(φd) + σ√T N'(d), d = (F - K) / σ√T
+pub fn normal_price(option_type: OptionType, forward: f64, strike: f64, vol: f64, t: f64) -> f64 {
+    bachelier::forward_price(option_type, forward, strike, vol, t)
+}
+
+/// Lognormal vol at which the undiscounted Black-76 value matches `price`
+pub fn black_implied_vol(
+    option_type: OptionType,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    price: f64,
+) -> Result<f64, PricingError> {
+    implied_vol(
+        |vol| black_price(option_type, forward, strike, vol, t) - price,
+        (MIN_BLACK_VOL, MAX_BLACK_VOL),
+        price,
+    )
+}
+
+/// Normal vol at which the undiscounted Bachelier value matches `price`
+pub fn normal_implied_vol(
+    option_type: OptionType,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    price: f64,
+) -> Result<f64, PricingError> {
+    implied_vol(
+        |vol| normal_price(option_type, forward, strike, vol, t) - price,
+        (MIN_NORMAL_VOL, MAX_NORMAL_VOL),
+        price,
+    )
+}
+
+fn implied_vol(
+    error: impl Fn(f64) -> f64,
+    (lo, hi): (f64, f64),
+    price: f64,
+) -> Result<f64, PricingError> {
+    if !(error(lo) < 0.0 && error(hi) > 0.0) {
+        return Err(PricingError::InvalidInput(format!(
+            "premium {} outside the range of vols in [{}, {}]",
+            price, lo, hi
+        )));
+    }
+    let numerics = NumericsConfig::default();
+    brent(error, lo, hi, numerics.tolerance, numerics.max_iterations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_black_and_normal_quotes() {
+        let (forward, strike, t) = (0.03, 0.035, 2.0);
+        let black = RateVolatility::Black(0.25);
+        let call = black.price(OptionType::Call, forward, strike, t);
+        let put = black.price(OptionType::Put, forward, strike, t);
+        // put-call parity on the forward
+        assert!((call - put - (forward - strike)).abs() < 1e-14);
+        assert!(
+            (black_implied_vol(OptionType::Call, forward, strike, t, call).unwrap() - 0.25).abs()
+                < 1e-8
+        );
+
+        // a normal vol near σ_B F prices close to the lognormal quote, and converts back exactly
+        let normal_vol = black.to_normal(forward, strike, t).unwrap();
+        assert!((normal_vol - 0.25 * forward).abs() < 1e-3);
+        let normal = RateVolatility::Normal(normal_vol);
+        assert!((normal.price(OptionType::Call, forward, strike, t) - call).abs() < 1e-10);
+        assert!((normal.to_black(forward, strike, t).unwrap() - 0.25).abs() < 1e-7);
+
+        // the normal model prices negative forwards the lognormal one cannot
+        let negative = normal_price(OptionType::Put, -0.002, 0.0, 0.006, 1.0);
+        assert!(negative > 0.002);
+        assert!(normal_implied_vol(OptionType::Put, -0.002, 0.0, 1.0, negative).is_ok());
+        // and so does the shifted lognormal one, leaving the plain quote at zero shift
+        let shifted = RateVolatility::ShiftedBlack {
+            vol: 0.3,
+            shift: 0.01,
+        };
+        assert!(shifted.to_normal(-0.002, 0.0, 1.0).unwrap() > 0.0);
+        let unshifted = RateVolatility::ShiftedBlack {
+            vol: 0.25,
+            shift: 0.0,
+        };
+        assert_eq!(unshifted.price(OptionType::Call, forward, strike, t), call);
+    }
+}
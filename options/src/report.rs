@@ -0,0 +1,764 @@
+use crate::bachelier::PricingModel;
+use crate::conventions::{GreekUnits, ThetaConvention, VegaConvention};
+use crate::math::float::exp;
+use crate::portfolio::{CashPosition, Portfolio, StockPosition, unit_multiplier};
+use crate::scenario::{ScenarioCube, ScenarioGrid, Shock};
+use crate::var::VarReport;
+use crate::{OptionType, Options};
+use serde::{Deserialize, Serialize};
+
+/// Version of the risk report layout, bumped on any incompatible change to the JSON schema
+pub const SCHEMA_VERSION: &str = "1.2";
+
+/// JSON Schema describing the exported risk report
+pub const RISK_REPORT_SCHEMA: &str = include_str!("../schema/risk_report.v1.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GreeksRecord {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+impl GreeksRecord {
+    pub fn of(option: &Options) -> Self {
+        GreeksRecord {
+            delta: option.delta(),
+            gamma: option.gamma(),
+            vega: option.vega(),
+            theta: option.theta(),
+            rho: option.rho(),
+        }
+    }
+    /// Greeks under the chosen model, vega per unit of that model's volatility
+    pub fn of_model(option: &Options, model: PricingModel) -> Self {
+        match model {
+            PricingModel::BlackScholes => GreeksRecord::of(option),
+            PricingModel::Bachelier => {
+                let normal = option.bachelier();
+                GreeksRecord {
+                    delta: normal.delta(),
+                    gamma: normal.gamma(),
+                    vega: normal.vega(),
+                    theta: normal.theta(),
+                    rho: normal.rho(),
+                }
+            }
+            PricingModel::ShiftedLognormal(shift) => {
+                // the equivalent spot S + shift e^(-(r-q)T) drifts with time and rate
+                let shifted = option.shifted(shift);
+                let t = option.time_to_maturity();
+                let carry = option.risk_free_rate() - option.dividend_yield().unwrap_or(0.0);
+                let displacement = shift * exp(-carry * t);
+                GreeksRecord {
+                    theta: shifted.theta() + shifted.delta() * carry * displacement,
+                    rho: shifted.rho() - shifted.delta() * t * displacement,
+                    ..GreeksRecord::of(&shifted)
+                }
+            }
+        }
+    }
+    /// Greeks of one share: delta one and nothing else
+    pub fn of_share() -> Self {
+        GreeksRecord {
+            delta: 1.0,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: 0.0,
+            rho: 0.0,
+        }
+    }
+    /// Greeks of a cash holding, whose only exposures are accrual and rates
+    pub fn of_cash(cash: &CashPosition) -> Self {
+        GreeksRecord {
+            delta: 0.0,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: cash.theta(),
+            rho: cash.rho(),
+        }
+    }
+    /// The record with its annualized theta restated in `convention`
+    pub fn with_theta_convention(self, convention: ThetaConvention) -> Self {
+        GreeksRecord {
+            theta: convention.scale(self.theta),
+            ..self
+        }
+    }
+    /// The record with its vega per unit of volatility restated in `convention`
+    pub fn with_vega_convention(self, convention: VegaConvention) -> Self {
+        GreeksRecord {
+            vega: convention.scale(self.vega),
+            ..self
+        }
+    }
+    /// The record, in the pricers' annualized theta and per-unit vega, restated in `units`
+    pub fn in_units(self, units: GreekUnits) -> Self {
+        self.with_theta_convention(units.theta)
+            .with_vega_convention(units.vega)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub underlying: String,
+    pub kind: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    pub risk_free_rate: f64,
+    pub time_to_maturity: f64,
+    pub dividend_yield: Option<f64>,
+    pub quantity: f64,
+    /// Underlying units per contract; absent in 1.0 reports, where it was always one
+    #[serde(default = "unit_multiplier")]
+    pub multiplier: f64,
+    /// Per-share price; the position value is quantity x multiplier x price
+    pub price: f64,
+    pub value: f64,
+    /// Per-contract Greeks
+    pub greeks: GreeksRecord,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockRecord {
+    pub underlying: String,
+    pub spot: f64,
+    pub quantity: f64,
+    pub value: f64,
+    /// Per-share Greeks
+    pub greeks: GreeksRecord,
+}
+
+impl From<&StockPosition> for StockRecord {
+    fn from(stock: &StockPosition) -> Self {
+        StockRecord {
+            underlying: stock.underlying.clone(),
+            spot: stock.spot,
+            quantity: stock.quantity,
+            value: stock.value(),
+            greeks: GreeksRecord::of_share(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashRecord {
+    pub amount: f64,
+    pub risk_free_rate: f64,
+    pub maturity: f64,
+    pub value: f64,
+    /// Greeks of the whole holding
+    pub greeks: GreeksRecord,
+}
+
+impl From<&CashPosition> for CashRecord {
+    fn from(cash: &CashPosition) -> Self {
+        CashRecord {
+            amount: cash.amount,
+            risk_free_rate: cash.risk_free_rate,
+            maturity: cash.maturity,
+            value: cash.value(),
+            greeks: GreeksRecord::of_cash(cash),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TotalsRecord {
+    pub value: f64,
+    /// Quantity-weighted portfolio Greeks
+    pub greeks: GreeksRecord,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShockRecord {
+    pub kind: ShockKind,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShockKind {
+    Absolute,
+    Relative,
+    BasisPoints,
+}
+
+impl From<Shock> for ShockRecord {
+    fn from(shock: Shock) -> Self {
+        let (kind, value) = match shock {
+            Shock::Absolute(value) => (ShockKind::Absolute, value),
+            Shock::Relative(value) => (ShockKind::Relative, value),
+            Shock::BasisPoints(value) => (ShockKind::BasisPoints, value),
+        };
+        ShockRecord { kind, value }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioRecord {
+    pub spot_shocks: Vec<ShockRecord>,
+    pub vol_shocks: Vec<ShockRecord>,
+    pub rate_shocks: Vec<ShockRecord>,
+    pub base_value: f64,
+    /// P&L indexed [spot][vol][rate]
+    pub pnl: Vec<Vec<Vec<f64>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VarMethod {
+    Parametric,
+    MonteCarlo,
+    Historical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ComponentRecord {
+    pub marginal: f64,
+    pub component: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VarRecord {
+    pub method: VarMethod,
+    pub confidence: f64,
+    pub var: f64,
+    pub expected_shortfall: f64,
+    pub components: Vec<ComponentRecord>,
+}
+
+/// Full portfolio risk report in the versioned exchange layout described by `RISK_REPORT_SCHEMA`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskReport {
+    pub schema_version: String,
+    /// Time unit of every theta in the report
+    #[serde(default)]
+    pub theta_convention: ThetaConvention,
+    /// Volatility move every vega in the report is per
+    #[serde(default)]
+    pub vega_convention: VegaConvention,
+    pub positions: Vec<PositionRecord>,
+    /// Shares held outright; absent before 1.2
+    #[serde(default)]
+    pub stocks: Vec<StockRecord>,
+    /// Cash holdings; absent before 1.2
+    #[serde(default)]
+    pub cash: Vec<CashRecord>,
+    pub totals: TotalsRecord,
+    pub scenarios: Option<ScenarioRecord>,
+    #[serde(default)]
+    pub var: Vec<VarRecord>,
+}
+
+impl RiskReport {
+    /// Option, stock and cash records plus portfolio totals. The totals are the option Greeks
+    /// scaled by quantity and multiplier, plus the stock Greeks scaled by quantity, plus the
+    /// cash Greeks
+    pub fn from_portfolio(portfolio: &Portfolio) -> Self {
+        let positions = portfolio
+            .positions()
+            .iter()
+            .map(|position| {
+                let option = &position.option;
+                let price = option.bs_pricing();
+                PositionRecord {
+                    underlying: position.underlying.clone(),
+                    kind: option.option_type(),
+                    strike: option.strike_price(),
+                    spot: option.spot_price(),
+                    volatility: option.volatility(),
+                    risk_free_rate: option.risk_free_rate(),
+                    time_to_maturity: option.time_to_maturity(),
+                    dividend_yield: option.dividend_yield(),
+                    quantity: position.quantity,
+                    multiplier: position.multiplier,
+                    price,
+                    value: position.units() * price,
+                    greeks: GreeksRecord::of(option),
+                }
+            })
+            .collect();
+        RiskReport {
+            schema_version: SCHEMA_VERSION.to_string(),
+            theta_convention: ThetaConvention::PerYear,
+            vega_convention: VegaConvention::PerUnit,
+            positions,
+            stocks: portfolio.stocks().iter().map(StockRecord::from).collect(),
+            cash: portfolio.cash().iter().map(CashRecord::from).collect(),
+            totals: TotalsRecord {
+                value: portfolio.value(),
+                greeks: GreeksRecord {
+                    delta: portfolio.delta(),
+                    gamma: portfolio.gamma(),
+                    vega: portfolio.vega(),
+                    theta: portfolio.theta(),
+                    rho: portfolio.rho(),
+                },
+            },
+            scenarios: None,
+            var: Vec::new(),
+        }
+    }
+    pub fn with_scenarios(mut self, grid: &ScenarioGrid, cube: &ScenarioCube) -> Self {
+        let (n_spot, n_vol, n_rate) = cube.shape();
+        let pnl = (0..n_spot)
+            .map(|i| {
+                (0..n_vol)
+                    .map(|j| (0..n_rate).map(|k| cube.pnl(i, j, k)).collect())
+                    .collect()
+            })
+            .collect();
+        let records = |shocks: &[Shock]| shocks.iter().map(|&s| ShockRecord::from(s)).collect();
+        self.scenarios = Some(ScenarioRecord {
+            spot_shocks: records(&grid.spot_shocks),
+            vol_shocks: records(&grid.vol_shocks),
+            rate_shocks: records(&grid.rate_shocks),
+            base_value: cube.base_value,
+            pnl,
+        });
+        self
+    }
+    pub fn with_var(mut self, method: VarMethod, report: &VarReport) -> Self {
+        self.var.push(VarRecord {
+            method,
+            confidence: report.confidence,
+            var: report.var,
+            expected_shortfall: report.expected_shortfall,
+            components: report
+                .positions
+                .iter()
+                .map(|p| ComponentRecord {
+                    marginal: p.marginal,
+                    component: p.component,
+                })
+                .collect(),
+        });
+        self
+    }
+    /// Restates every record's and the total thetas in `convention`
+    pub fn with_theta_convention(mut self, convention: ThetaConvention) -> Self {
+        let from = self.theta_convention;
+        let restate =
+            |greeks: &mut GreeksRecord| greeks.theta = from.convert(greeks.theta, convention);
+        self.positions
+            .iter_mut()
+            .for_each(|position| restate(&mut position.greeks));
+        self.stocks
+            .iter_mut()
+            .for_each(|stock| restate(&mut stock.greeks));
+        self.cash
+            .iter_mut()
+            .for_each(|cash| restate(&mut cash.greeks));
+        restate(&mut self.totals.greeks);
+        self.theta_convention = convention;
+        self
+    }
+    /// Restates every record's and the total vegas in `convention`
+    pub fn with_vega_convention(mut self, convention: VegaConvention) -> Self {
+        let from = self.vega_convention;
+        let restate =
+            |greeks: &mut GreeksRecord| greeks.vega = from.convert(greeks.vega, convention);
+        self.positions
+            .iter_mut()
+            .for_each(|position| restate(&mut position.greeks));
+        self.stocks
+            .iter_mut()
+            .for_each(|stock| restate(&mut stock.greeks));
+        self.cash
+            .iter_mut()
+            .for_each(|cash| restate(&mut cash.greeks));
+        restate(&mut self.totals.greeks);
+        self.vega_convention = convention;
+        self
+    }
+    /// Restates every theta and vega in `units`
+    pub fn in_units(self, units: GreekUnits) -> Self {
+        self.with_theta_convention(units.theta)
+            .with_vega_convention(units.vega)
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("risk report is always serializable")
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Layout of a rendered table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// Columns padded with spaces under a dashed rule, for terminals
+    Text,
+    /// GitHub-flavoured markdown
+    Markdown,
+}
+
+/// Rows of text cells under a header, rendered with every column padded to its widest cell.
+///
+/// The first column is left-aligned and the others, which hold numbers, right-aligned
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(header: impl IntoIterator<Item = S>) -> Self {
+        Table {
+            header: header.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+    /// Appends a row; cells beyond the header are dropped and missing ones left blank
+    pub fn push_row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) {
+        let mut row: Vec<String> = cells.into_iter().map(Into::into).collect();
+        row.resize(self.header.len(), String::new());
+        self.rows.push(row);
+    }
+    pub fn with_row<S: Into<String>>(mut self, cells: impl IntoIterator<Item = S>) -> Self {
+        self.push_row(cells);
+        self
+    }
+    /// Price and Greeks of labelled contracts, one row each, to six decimals. The records'
+    /// annualized thetas and per-unit vegas are restated in `units`, named in the headers
+    pub fn greeks(rows: &[(String, f64, GreeksRecord)], units: GreekUnits) -> Self {
+        let vega_header = format!("vega/{}", units.vega.unit());
+        let theta_header = format!("theta/{}", units.theta.unit());
+        let mut table = Table::new([
+            "contract",
+            "price",
+            "delta",
+            "gamma",
+            &vega_header,
+            &theta_header,
+            "rho",
+        ]);
+        for (label, price, greeks) in rows {
+            let greeks = greeks.in_units(units);
+            let values = [
+                *price,
+                greeks.delta,
+                greeks.gamma,
+                greeks.vega,
+                greeks.theta,
+                greeks.rho,
+            ];
+            let cells = values.iter().map(|value| format!("{:.6}", value));
+            table.push_row(std::iter::once(label.clone()).chain(cells));
+        }
+        table
+    }
+    /// Black-Scholes price and Greeks of each contract, labelled by its description
+    pub fn of_contracts(options: &[Options], units: GreekUnits) -> Self {
+        let rows: Vec<_> = options
+            .iter()
+            .map(|option| {
+                (
+                    option.to_string(),
+                    option.bs_pricing(),
+                    GreeksRecord::of(option),
+                )
+            })
+            .collect();
+        Table::greeks(&rows, units)
+    }
+    pub fn render(&self, style: TableStyle) -> String {
+        let widths: Vec<usize> = (0..self.header.len())
+            .map(|column| {
+                std::iter::once(&self.header)
+                    .chain(&self.rows)
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, &width))| match column {
+                    0 => format!("{:<width$}", cell),
+                    _ => format!("{:>width$}", cell),
+                })
+                .collect();
+            match style {
+                TableStyle::Text => padded.join("  ").trim_end().to_string(),
+                TableStyle::Markdown => format!("| {} |", padded.join(" | ")),
+            }
+        };
+        let rule = match style {
+            TableStyle::Text => widths
+                .iter()
+                .map(|&width| "-".repeat(width))
+                .collect::<Vec<_>>()
+                .join("  "),
+            TableStyle::Markdown => {
+                let dashes: Vec<String> = widths
+                    .iter()
+                    .enumerate()
+                    .map(|(column, &width)| match column {
+                        0 => format!(":{}", "-".repeat(width.max(2) - 1)),
+                        _ => format!("{}:", "-".repeat(width.max(2) - 1)),
+                    })
+                    .collect();
+                format!("| {} |", dashes.join(" | "))
+            }
+        };
+        let mut rendered = line(&self.header) + "\n" + &rule + "\n";
+        for row in &self.rows {
+            rendered += &line(row);
+            rendered.push('\n');
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::{CashPosition, Position, StockPosition};
+    use crate::var::RiskFactors;
+    use serde_json::Value;
+
+    // Minimal JSON Schema checker covering the keywords used by the report schema
+    fn validate(schema: &Value, value: &Value, root: &Value, path: &str) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            return validate(&root["$defs"][name], value, root, path);
+        }
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            let actual = match value {
+                Value::Null => "null",
+                Value::Bool(_) => "boolean",
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Array(_) => "array",
+                Value::Object(_) => "object",
+            };
+            if !types.contains(&actual) {
+                return Err(format!("{}: expected {:?}, found {}", path, types, actual));
+            }
+        }
+        if let Some(constant) = schema.get("const")
+            && constant != value
+        {
+            return Err(format!("{}: expected constant {}", path, constant));
+        }
+        if let Some(Value::Array(options)) = schema.get("enum")
+            && !options.contains(value)
+        {
+            return Err(format!("{}: {} not in enum", path, value));
+        }
+        if let Value::Object(map) = value {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        return Err(format!("{}: missing required field {}", path, key));
+                    }
+                }
+            }
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (key, sub_schema) in properties {
+                    if let Some(sub_value) = map.get(key) {
+                        validate(sub_schema, sub_value, root, &format!("{}.{}", path, key))?;
+                    }
+                }
+            }
+        }
+        if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+            for (i, item) in items.iter().enumerate() {
+                validate(item_schema, item, root, &format!("{}[{}]", path, i))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn full_report() -> RiskReport {
+        let mut portfolio = Portfolio::new();
+        portfolio.add(Position::new(
+            "SPX",
+            Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None),
+            2.0,
+        ));
+        portfolio.add(Position::new(
+            "SPX",
+            Options::new_put(95.0, 100.0, 0.22, 0.05, 0.5, Some(0.01)),
+            -1.0,
+        ));
+        portfolio.add(
+            Position::new(
+                "SPX",
+                Options::new_call(110.0, 100.0, 0.2, 0.05, 0.25, None),
+                3.0,
+            )
+            .with_multiplier(100.0),
+        );
+        let grid = ScenarioGrid::new(
+            Shock::ladder(-0.1, 0.1, 3, Shock::Relative),
+            vec![Shock::Absolute(0.0), Shock::Absolute(0.05)],
+            vec![Shock::BasisPoints(0.0)],
+        );
+        let cube = grid.run(&portfolio);
+        let factors = RiskFactors::new(vec!["SPX".to_string()], vec![vec![0.0001]]).unwrap();
+        let var = portfolio.parametric_var(&factors, 0.99).unwrap();
+        RiskReport::from_portfolio(&portfolio)
+            .with_scenarios(&grid, &cube)
+            .with_var(VarMethod::Parametric, &var)
+    }
+
+    #[test]
+    fn test_report_matches_schema() {
+        let schema: Value = serde_json::from_str(RISK_REPORT_SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        let report = full_report();
+        let exported: Value = serde_json::from_str(&report.to_json()).unwrap();
+        validate(&schema, &exported, &schema, "$").unwrap();
+        // a 100x contract carries its multiplier, and its value follows from the record alone
+        let contract = &exported["positions"][2];
+        assert_eq!(contract["multiplier"], 100.0);
+        let value = contract["quantity"].as_f64().unwrap()
+            * contract["multiplier"].as_f64().unwrap()
+            * contract["price"].as_f64().unwrap();
+        assert!((contract["value"].as_f64().unwrap() - value).abs() < 1e-9);
+        assert_eq!(report.positions[0].multiplier, 1.0);
+
+        let mut broken = exported.clone();
+        broken["positions"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("greeks");
+        assert!(validate(&schema, &broken, &schema, "$").is_err());
+    }
+
+    #[test]
+    fn test_report_roundtrip() {
+        let report = full_report();
+        let parsed = RiskReport::from_json(&report.to_json()).unwrap();
+        assert_eq!(parsed, report);
+        assert_eq!(parsed.scenarios.unwrap().pnl.len(), 3);
+        // 1.0 reports predate the multiplier
+        let mut legacy: Value = serde_json::from_str(&report.to_json()).unwrap();
+        legacy["positions"][2]
+            .as_object_mut()
+            .unwrap()
+            .remove("multiplier");
+        let legacy: RiskReport = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.positions[2].multiplier, 1.0);
+
+        // thetas restated per trading day survive the round trip, and restate back exactly
+        let daily = report
+            .clone()
+            .with_theta_convention(ThetaConvention::PerTradingDay);
+        let parsed = RiskReport::from_json(&daily.to_json()).unwrap();
+        assert_eq!(parsed.theta_convention, ThetaConvention::PerTradingDay);
+        let theta = report.totals.greeks.theta;
+        assert!((parsed.totals.greeks.theta - theta / 252.0).abs() < 1e-12);
+        let annual = parsed.with_theta_convention(ThetaConvention::PerYear);
+        assert!(
+            (annual.positions[1].greeks.theta - report.positions[1].greeks.theta).abs() < 1e-12
+        );
+        // and so do vegas per vol point
+        let market = report.clone().in_units(GreekUnits::market());
+        assert_eq!(market.vega_convention, VegaConvention::PerVolPoint);
+        let vega = report.positions[0].greeks.vega;
+        assert!((market.positions[0].greeks.vega - vega / 100.0).abs() < 1e-12);
+        assert_eq!(RiskReport::from_json(&market.to_json()).unwrap(), market);
+    }
+
+    #[test]
+    fn test_records_reconcile_to_totals() {
+        let call = Options::new_call(105.0, 100.0, 0.2, 0.05, 0.5, None);
+        let put = Options::new_put(90.0, 100.0, 0.25, 0.05, 0.5, None);
+        let mut portfolio = Portfolio::collar("XYZ", 100.0, put, call);
+        portfolio.add_stock(StockPosition::new("ABC", 40.0, -50.0));
+        portfolio.add_cash(CashPosition::new(-10_000.0, 0.05, 0.5));
+        let report = RiskReport::from_portfolio(&portfolio);
+        assert_eq!(report.stocks.len(), 2);
+        assert_eq!(report.cash.len(), 1);
+        let schema: Value = serde_json::from_str(RISK_REPORT_SCHEMA).unwrap();
+        let exported: Value = serde_json::from_str(&report.to_json()).unwrap();
+        validate(&schema, &exported, &schema, "$").unwrap();
+
+        let options = report
+            .positions
+            .iter()
+            .map(|p| (p.quantity * p.multiplier, p.value, p.greeks));
+        let stocks = report
+            .stocks
+            .iter()
+            .map(|s| (s.quantity, s.value, s.greeks));
+        let cash = report.cash.iter().map(|c| (1.0, c.value, c.greeks));
+        let records: Vec<_> = options.chain(stocks).chain(cash).collect();
+        let sum = |greek: fn(&GreeksRecord) -> f64| {
+            records
+                .iter()
+                .map(|(scale, _, g)| scale * greek(g))
+                .sum::<f64>()
+        };
+        let totals = &report.totals;
+        let value: f64 = records.iter().map(|(_, value, _)| value).sum();
+        assert!((value - totals.value).abs() < 1e-9);
+        assert!((sum(|g| g.delta) - totals.greeks.delta).abs() < 1e-9);
+        assert!((sum(|g| g.gamma) - totals.greeks.gamma).abs() < 1e-9);
+        assert!((sum(|g| g.vega) - totals.greeks.vega).abs() < 1e-9);
+        assert!((sum(|g| g.theta) - totals.greeks.theta).abs() < 1e-9);
+        assert!((sum(|g| g.rho) - totals.greeks.rho).abs() < 1e-9);
+
+        // 1.1 reports carry no stock or cash records
+        let mut legacy: Value = serde_json::from_str(&report.to_json()).unwrap();
+        let object = legacy.as_object_mut().unwrap();
+        object.remove("stocks");
+        object.remove("cash");
+        let legacy: RiskReport = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.stocks.is_empty() && legacy.cash.is_empty());
+    }
+
+    #[test]
+    fn test_greeks_table() {
+        let contracts = [
+            Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None),
+            Options::new_put(95.0, 100.0, 0.22, 0.05, 0.5, Some(0.01)),
+        ];
+        let table = Table::of_contracts(&contracts, GreekUnits::market());
+        let text = table.render(TableStyle::Text);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("contract") && lines[0].ends_with("rho"));
+        assert!(lines[0].contains("vega/vol point") && lines[0].contains("theta/calendar day"));
+        let vega = format!("{:.6}", contracts[0].vega() / 100.0);
+        assert!(lines[2].contains(&vega));
+        assert!(lines[1].chars().all(|c| c == '-' || c == ' '));
+        assert!(lines[2].starts_with("CALL 100 @ 1.0y, σ=20%, r=5%"));
+        assert!(lines[2].contains(&format!("{:.6}", contracts[0].bs_pricing())));
+        // every line ends at the same column
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+
+        let markdown = table.render(TableStyle::Markdown);
+        assert!(markdown.lines().nth(1).unwrap().starts_with("| :---"));
+        assert!(
+            markdown
+                .lines()
+                .all(|line| line.starts_with("| ") && line.ends_with(" |"))
+        );
+
+        let short = Table::new(["field", "value"]).with_row(["delta"]);
+        assert_eq!(
+            short.render(TableStyle::Text),
+            "field  value\n-----  -----\ndelta\n"
+        );
+    }
+}
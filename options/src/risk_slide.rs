@@ -0,0 +1,321 @@
+// Risk slides: value and Greeks of a book revalued across a ladder of spot levels, every
+// underlying moved by the same percentage with vols, rates and time held. Traders read them to
+// see where gamma flips, how delta migrates and what a gap move costs. Greek grids extend the
+// ladder across vol shifts, giving the shadow gamma after a spot gap and the vega convexity a
+// vol spike brings, which today's flat Greeks miss.
+use crate::Options;
+use crate::error::PricingError;
+use crate::portfolio::{Portfolio, Position, StockPosition};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// Floor keeping shifted vols inside the domain of the pricing formulas
+const MIN_VOL: f64 = 1e-8;
+
+/// Book revalued at one spot level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SlideRow {
+    /// Spot as a fraction of today's, e.g. 0.9 for a 10% drop
+    pub level: f64,
+    pub value: f64,
+    /// Value less today's value
+    pub pnl: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+/// Price and Greeks across a spot ladder, one row per level in ladder order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RiskSlide {
+    pub base_value: f64,
+    pub rows: Vec<SlideRow>,
+}
+
+impl RiskSlide {
+    /// Row at the level closest to `level`
+    pub fn at(&self, level: f64) -> Option<&SlideRow> {
+        self.rows
+            .iter()
+            .min_by(|a, b| (a.level - level).abs().total_cmp(&(b.level - level).abs()))
+    }
+}
+
+#[cfg(feature = "report")]
+impl RiskSlide {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("risk slide is always serializable")
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(feature = "io")]
+impl RiskSlide {
+    /// Writes one CSV row per level under a `level,value,pnl,delta,gamma,vega,theta,rho` header
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> Result<(), crate::io::IoError> {
+        let mut csv = csv::Writer::from_writer(writer);
+        for row in &self.rows {
+            csv.serialize(row)?;
+        }
+        csv.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+/// Evenly spaced spot levels from `from` to `to` inclusive, e.g. `spot_levels(0.8, 1.2, 0.01)`
+/// for 80% to 120% in 1% steps. Empty unless `step` is positive and `to` not below `from`
+pub fn spot_levels(from: f64, to: f64, step: f64) -> Vec<f64> {
+    if !(step > 0.0 && to >= from) {
+        return Vec::new();
+    }
+    let steps = ((to - from) / step).round() as usize;
+    (0..=steps).map(|i| from + i as f64 * step).collect()
+}
+
+/// Revalues `portfolio` at every spot level, shares and options alike, with cash unchanged.
+/// Fails on a level that is not positive and finite
+pub fn risk_slide(portfolio: &Portfolio, levels: &[f64]) -> Result<RiskSlide, PricingError> {
+    check_levels(levels)?;
+    let base_value = portfolio.value();
+    let rows = levels
+        .iter()
+        .map(|&level| {
+            let moved = at_state(portfolio, level, 0.0);
+            let value = moved.value();
+            SlideRow {
+                level,
+                value,
+                pnl: value - base_value,
+                delta: moved.delta(),
+                gamma: moved.gamma(),
+                vega: moved.vega(),
+                theta: moved.theta(),
+                rho: moved.rho(),
+            }
+        })
+        .collect();
+    Ok(RiskSlide { base_value, rows })
+}
+
+/// Risk slide of one long contract
+pub fn option_risk_slide(option: &Options, levels: &[f64]) -> Result<RiskSlide, PricingError> {
+    let single: Portfolio = [Position::new("", *option, 1.0)].into_iter().collect();
+    risk_slide(&single, levels)
+}
+
+/// Greek reported on a [`GreekGrid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Greek {
+    Delta,
+    Gamma,
+    Vega,
+    Theta,
+    Rho,
+}
+
+impl Greek {
+    /// The Greek of a whole book, shares and cash included
+    pub fn of(&self, portfolio: &Portfolio) -> f64 {
+        match self {
+            Greek::Delta => portfolio.delta(),
+            Greek::Gamma => portfolio.gamma(),
+            Greek::Vega => portfolio.vega(),
+            Greek::Theta => portfolio.theta(),
+            Greek::Rho => portfolio.rho(),
+        }
+    }
+}
+
+/// One Greek of a book across a spot × vol grid of shifted market states.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GreekGrid {
+    pub greek: Greek,
+    /// Spot as a fraction of today's, one row each
+    pub spot_levels: Vec<f64>,
+    /// Absolute shifts added to every vol, e.g. 0.05 for five vol points, one column each
+    pub vol_shifts: Vec<f64>,
+    /// values[i][j]: the Greek at spot level i with vols shifted by vol shift j
+    pub values: Vec<Vec<f64>>,
+}
+
+impl GreekGrid {
+    /// Value at the grid point closest to (`level`, `vol_shift`), None for an empty grid
+    pub fn at(&self, level: f64, vol_shift: f64) -> Option<f64> {
+        let i = closest(&self.spot_levels, level)?;
+        let j = closest(&self.vol_shifts, vol_shift)?;
+        Some(self.values[i][j])
+    }
+}
+
+#[cfg(feature = "report")]
+impl GreekGrid {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("greek grid is always serializable")
+    }
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+fn closest(points: &[f64], target: f64) -> Option<usize> {
+    (0..points.len()).min_by(|&a, &b| {
+        (points[a] - target)
+            .abs()
+            .total_cmp(&(points[b] - target).abs())
+    })
+}
+
+/// `greek` of `portfolio` with every underlying moved to each spot level and every vol shifted
+/// by each vol shift, floored just above zero, rates and time held, e.g. gamma after a 10% drop
+/// at `levels` 0.9 and `vol_shifts` 0.0, or vega after a five point vol rise at 1.0 and 0.05.
+/// Fails on a level that is not positive and finite
+pub fn greek_grid(
+    portfolio: &Portfolio,
+    greek: Greek,
+    levels: &[f64],
+    vol_shifts: &[f64],
+) -> Result<GreekGrid, PricingError> {
+    check_levels(levels)?;
+    let values = levels
+        .iter()
+        .map(|&level| {
+            vol_shifts
+                .iter()
+                .map(|&shift| greek.of(&at_state(portfolio, level, shift)))
+                .collect()
+        })
+        .collect();
+    Ok(GreekGrid {
+        greek,
+        spot_levels: levels.to_vec(),
+        vol_shifts: vol_shifts.to_vec(),
+        values,
+    })
+}
+
+fn check_levels(levels: &[f64]) -> Result<(), PricingError> {
+    if let Some(level) = levels
+        .iter()
+        .find(|&&level| !(level > 0.0 && level.is_finite()))
+    {
+        return Err(PricingError::InvalidInput(format!(
+            "spot level {} must be positive and finite",
+            level
+        )));
+    }
+    Ok(())
+}
+
+// The book at spot `level` times today's with every option vol moved by `vol_shift`
+fn at_state(portfolio: &Portfolio, level: f64, vol_shift: f64) -> Portfolio {
+    let mut moved: Portfolio = portfolio
+        .positions()
+        .iter()
+        .map(|position| {
+            let option = position.option;
+            Position {
+                option: option.with_market(
+                    option.spot_price() * level,
+                    (option.volatility() + vol_shift).max(MIN_VOL),
+                    option.risk_free_rate(),
+                ),
+                ..position.clone()
+            }
+        })
+        .collect();
+    for stock in portfolio.stocks() {
+        moved.add_stock(StockPosition {
+            spot: stock.spot * level,
+            ..stock.clone()
+        });
+    }
+    for cash in portfolio.cash() {
+        moved.add_cash(*cash);
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_risk_slide_of_a_covered_call() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let levels = spot_levels(0.8, 1.2, 0.01);
+        assert_eq!(levels.len(), 41);
+        assert!((levels[40] - 1.2).abs() < 1e-12);
+        let slide = option_risk_slide(&call, &levels).unwrap();
+        let base = slide.at(1.0).unwrap();
+        assert!(base.pnl.abs() < 1e-12 && (base.delta - call.delta()).abs() < 1e-12);
+        let up = slide.at(1.1).unwrap();
+        let moved = call.with_market(110.0, 0.2, 0.05);
+        assert!((up.value - moved.bs_pricing()).abs() < 1e-12);
+        assert!((up.gamma - moved.gamma()).abs() < 1e-12);
+        assert!(slide.rows.windows(2).all(|w| w[1].delta > w[0].delta));
+
+        // short calls against stock cap the upside: delta falls as spot rallies
+        let book = Portfolio::covered_call("SPX", 100.0, call);
+        let covered = risk_slide(&book, &levels).unwrap();
+        let (low, high) = (covered.at(0.8).unwrap(), covered.at(1.2).unwrap());
+        assert!(low.delta > high.delta && high.delta > 0.0);
+        let call_gain = call.with_market(120.0, 0.2, 0.05).bs_pricing() - call.bs_pricing();
+        assert!((high.pnl - 100.0 * (20.0 - call_gain)).abs() < 1e-9);
+        assert!(risk_slide(&book, &[1.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_greeks_in_shifted_states() {
+        let put = Options::new_put(90.0, 100.0, 0.2, 0.05, 0.25, None);
+        let book: Portfolio = [Position::new("SPX", put, -10.0)].into_iter().collect();
+        let levels = [0.9, 1.0, 1.1];
+        let shifts = [-0.05, 0.0, 0.05];
+        let gamma = greek_grid(&book, Greek::Gamma, &levels, &shifts).unwrap();
+        assert_eq!((gamma.values.len(), gamma.values[0].len()), (3, 3));
+        assert!((gamma.at(1.0, 0.0).unwrap() - book.gamma()).abs() < 1e-12);
+        // the short put's shadow gamma after a 10% drop is far shorter than today's
+        let shadow = gamma.at(0.9, 0.0).unwrap();
+        let moved = put.with_market(90.0, 0.2, 0.05);
+        assert!((shadow + 10.0 * moved.gamma()).abs() < 1e-12);
+        assert!(shadow < 2.0 * book.gamma());
+
+        // out of the money vega rises with vol: the grid shows the vol convexity
+        let vega = greek_grid(&book, Greek::Vega, &levels, &shifts).unwrap();
+        let row = &vega.values[1];
+        assert!(row[0] > row[1] && row[1] > row[2]);
+        let spiked = put.with_market(100.0, 0.25, 0.05);
+        assert!((row[2] + 10.0 * spiked.vega()).abs() < 1e-12);
+        assert!(greek_grid(&book, Greek::Delta, &[-1.0], &shifts).is_err());
+        assert!(
+            greek_grid(&book, Greek::Delta, &[], &shifts)
+                .unwrap()
+                .at(1.0, 0.0)
+                .is_none()
+        );
+    }
+
+    #[cfg(all(feature = "io", feature = "report"))]
+    #[test]
+    fn test_risk_slide_exports() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let slide = option_risk_slide(&call, &spot_levels(0.9, 1.1, 0.1)).unwrap();
+        let mut out = Vec::new();
+        slide.write_csv(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("level,value,pnl,delta,gamma,vega,theta,rho\n"));
+        assert_eq!(text.lines().count(), 4);
+        assert_eq!(RiskSlide::from_json(&slide.to_json()).unwrap(), slide);
+        let book: Portfolio = [Position::new("", call, 1.0)].into_iter().collect();
+        let gamma = greek_grid(&book, Greek::Gamma, &[0.9, 1.0], &[0.0]).unwrap();
+        assert_eq!(GreekGrid::from_json(&gamma.to_json()).unwrap(), gamma);
+    }
+}
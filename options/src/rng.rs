@@ -0,0 +1,312 @@
+// Random number generators behind the Monte Carlo engines. A run is reproducible from its
+// algorithm, seed and stream, so pinning all three per product lets an audit replay a price
+// after an upgrade. `rand`'s StdRng may change algorithm between major versions; PCG64 and
+// Philox are implemented here and their sequences are fixed, checked against reference values.
+use rand::RngCore;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Source of independent, reproducible generators indexed by a seed and a stream.
+pub trait RngProvider {
+    type Rng: RngCore;
+    /// Generator of stream `stream` under `seed`; the same pair always gives the same sequence
+    fn stream(&self, seed: u64, stream: u64) -> Self::Rng;
+    /// Generator of the stream named `name`, see `stream_id`
+    fn named_stream(&self, seed: u64, name: &str) -> Self::Rng {
+        self.stream(seed, stream_id(name))
+    }
+}
+
+/// Stream index of a name, its 64-bit FNV-1a hash, stable across platforms and releases.
+pub fn stream_id(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `rand`'s StdRng seeded with seed + stream, as the Monte Carlo engine has always seeded its
+/// chunks. Streams of neighbouring seeds overlap, and the algorithm is only fixed within a
+/// major version of `rand`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StdRngProvider;
+
+impl RngProvider for StdRngProvider {
+    type Rng = StdRng;
+    fn stream(&self, seed: u64, stream: u64) -> StdRng {
+        StdRng::seed_from_u64(seed.wrapping_add(stream))
+    }
+}
+
+const PCG_MULTIPLIER: u128 = 0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645;
+
+/// PCG XSL RR 128/64: a 128-bit linear congruential generator whose odd increment selects the
+/// stream, with a permuted 64-bit output. Matches `rand_pcg::Pcg64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pcg64 {
+    state: u128,
+    increment: u128,
+}
+
+impl Pcg64 {
+    pub fn new(state: u128, stream: u128) -> Self {
+        let increment = (stream << 1) | 1;
+        let mut pcg = Pcg64 {
+            state: state.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG_MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl RngCore for Pcg64 {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        let rotation = (self.state >> 122) as u32;
+        (((self.state >> 64) as u64) ^ (self.state as u64)).rotate_right(rotation)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// PCG64 streams: the seed sets the state, the stream the increment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Pcg64Provider;
+
+impl RngProvider for Pcg64Provider {
+    type Rng = Pcg64;
+    fn stream(&self, seed: u64, stream: u64) -> Pcg64 {
+        Pcg64::new(u128::from(seed), u128::from(stream))
+    }
+}
+
+const PHILOX_M0: u32 = 0xD251_1F53;
+const PHILOX_M1: u32 = 0xCD9E_8D57;
+const PHILOX_W0: u32 = 0x9E37_79B9;
+const PHILOX_W1: u32 = 0xBB67_AE85;
+const PHILOX_ROUNDS: usize = 10;
+
+/// Philox4x32-10 (Salmon et al., Random123), counter-based: the n-th block of four words is a
+/// keyed bijection of n, so any position of a stream is reached without generating the ones
+/// before it. The key is the seed and the upper half of the counter the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Philox {
+    key: [u32; 2],
+    counter: [u32; 4],
+    block: [u32; 4],
+    // Words of `block` already handed out, 4 once it is spent
+    used: usize,
+}
+
+impl Philox {
+    pub fn new(seed: u64, stream: u64) -> Self {
+        Philox {
+            key: [seed as u32, (seed >> 32) as u32],
+            counter: [0, 0, stream as u32, (stream >> 32) as u32],
+            block: [0; 4],
+            used: 4,
+        }
+    }
+    /// Moves to the start of block `block` of the stream, four 32-bit words per block
+    pub fn seek(&mut self, block: u64) {
+        self.counter[0] = block as u32;
+        self.counter[1] = (block >> 32) as u32;
+        self.used = 4;
+    }
+    /// The keyed bijection of one counter value
+    pub fn generate(key: [u32; 2], counter: [u32; 4]) -> [u32; 4] {
+        let (mut key, mut x) = (key, counter);
+        for round in 0..PHILOX_ROUNDS {
+            if round > 0 {
+                key = [
+                    key[0].wrapping_add(PHILOX_W0),
+                    key[1].wrapping_add(PHILOX_W1),
+                ];
+            }
+            let product0 = u64::from(PHILOX_M0) * u64::from(x[0]);
+            let product1 = u64::from(PHILOX_M1) * u64::from(x[2]);
+            x = [
+                (product1 >> 32) as u32 ^ x[1] ^ key[0],
+                product1 as u32,
+                (product0 >> 32) as u32 ^ x[3] ^ key[1],
+                product0 as u32,
+            ];
+        }
+        x
+    }
+}
+
+impl RngCore for Philox {
+    fn next_u32(&mut self) -> u32 {
+        if self.used == 4 {
+            self.block = Philox::generate(self.key, self.counter);
+            let block =
+                (u64::from(self.counter[1]) << 32 | u64::from(self.counter[0])).wrapping_add(1);
+            self.counter[0] = block as u32;
+            self.counter[1] = (block >> 32) as u32;
+            self.used = 0;
+        }
+        self.used += 1;
+        self.block[self.used - 1]
+    }
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32();
+        u64::from(self.next_u32()) << 32 | u64::from(low)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Philox streams: the seed sets the key, the stream the upper half of the counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PhiloxProvider;
+
+impl RngProvider for PhiloxProvider {
+    type Rng = Philox;
+    fn stream(&self, seed: u64, stream: u64) -> Philox {
+        Philox::new(seed, stream)
+    }
+}
+
+fn fill_bytes<R: RngCore>(rng: &mut R, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+        let bytes = rng.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+/// Generator algorithm chosen at run time, as `McConfig` stores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RngAlgorithm {
+    #[default]
+    StdRng,
+    Pcg64,
+    Philox,
+}
+
+/// Generator of any `RngAlgorithm`.
+#[derive(Debug, Clone)]
+pub enum AnyRng {
+    StdRng(Box<StdRng>),
+    Pcg64(Pcg64),
+    Philox(Philox),
+}
+
+impl RngProvider for RngAlgorithm {
+    type Rng = AnyRng;
+    fn stream(&self, seed: u64, stream: u64) -> AnyRng {
+        match self {
+            RngAlgorithm::StdRng => AnyRng::StdRng(Box::new(StdRngProvider.stream(seed, stream))),
+            RngAlgorithm::Pcg64 => AnyRng::Pcg64(Pcg64Provider.stream(seed, stream)),
+            RngAlgorithm::Philox => AnyRng::Philox(PhiloxProvider.stream(seed, stream)),
+        }
+    }
+}
+
+impl RngCore for AnyRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            AnyRng::StdRng(rng) => rng.next_u32(),
+            AnyRng::Pcg64(rng) => rng.next_u32(),
+            AnyRng::Philox(rng) => rng.next_u32(),
+        }
+    }
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            AnyRng::StdRng(rng) => rng.next_u64(),
+            AnyRng::Pcg64(rng) => rng.next_u64(),
+            AnyRng::Philox(rng) => rng.next_u64(),
+        }
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_sequences() {
+        // rand_pcg's reference values for Pcg64::new(42, 54)
+        let mut pcg = Pcg64::new(42, 54);
+        let expected = [
+            0x86b1da1d72062b68,
+            0x1304aa46c9853d39,
+            0xa3670e9e0dd50358,
+            0xf9090e529a7dae00,
+            0xc85b9fd837996f2c,
+            0x606121f8e3919196,
+        ];
+        assert_eq!(expected.map(|_| pcg.next_u64()), expected);
+        // Random123 known-answer tests for Philox4x32-10
+        assert_eq!(
+            Philox::generate([0; 2], [0; 4]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+        );
+        assert_eq!(
+            Philox::generate([u32::MAX; 2], [u32::MAX; 4]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+        );
+        assert_eq!(
+            Philox::generate(
+                [0xa4093822, 0x299f31d0],
+                [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344]
+            ),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+        );
+    }
+
+    #[test]
+    fn test_streams() {
+        assert_eq!(stream_id(""), 0xcbf29ce484222325);
+        assert_eq!(stream_id("a"), 0xaf63dc4c8601ec8c);
+        for algorithm in [
+            RngAlgorithm::StdRng,
+            RngAlgorithm::Pcg64,
+            RngAlgorithm::Philox,
+        ] {
+            let draws = |stream: u64| {
+                let mut rng = algorithm.stream(7, stream);
+                [0; 8].map(|_| rng.next_u64())
+            };
+            assert_eq!(draws(3), draws(3));
+            assert_ne!(draws(3), draws(4));
+            let mut named = algorithm.named_stream(7, "autocall");
+            assert_eq!(named.next_u64(), draws(stream_id("autocall"))[0]);
+        }
+        // seeking a Philox stream skips whole blocks without generating them
+        let mut philox = Philox::new(7, 1);
+        let sequence = [0; 12].map(|_| philox.next_u32());
+        philox.seek(2);
+        assert_eq!(philox.next_u32(), sequence[8]);
+        assert_eq!(
+            philox.next_u64(),
+            u64::from(sequence[10]) << 32 | u64::from(sequence[9])
+        );
+    }
+}
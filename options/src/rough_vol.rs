@@ -0,0 +1,258 @@
+// Rough Bergomi (Bayer, Friz and Gatheral, 2016): variance is the exponential of a Riemann-Liouville
+// fractional Brownian motion with Hurst exponent H well below one half, scaled onto today's
+// forward variance curve. The variance driver is not Markov, so each path keeps its Brownian
+// history and the Volterra integral is simulated with the hybrid scheme of Bennedsen, Lunde and
+// Pakkanen: exact on the latest step, a Riemann sum at optimal points before it. Experimental,
+// behind the `rough_vol` feature and outside the semver guarantee.
+use crate::Options;
+use crate::error::PricingError;
+use crate::math::float::{exp, powf, sqrt};
+use crate::mc::{McResult, MonteCarloEngine};
+use crate::vol::term::forward_variance;
+
+/// Forward variance ξ₀(t) seen today, piecewise flat between pillars and flat after the last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardVarianceCurve {
+    /// (end of period, forward variance over the period), ascending
+    pillars: Vec<(f64, f64)>,
+}
+
+impl ForwardVarianceCurve {
+    pub fn flat(variance: f64) -> Self {
+        ForwardVarianceCurve {
+            pillars: vec![(f64::INFINITY, variance)],
+        }
+    }
+    /// Curve from (period end, forward variance) pillars. Fails on unsorted periods or a
+    /// negative variance
+    pub fn new(pillars: Vec<(f64, f64)>) -> Result<Self, PricingError> {
+        if pillars.is_empty()
+            || !pillars.windows(2).all(|w| w[1].0 > w[0].0)
+            || pillars.iter().any(|&(t, v)| !(t > 0.0 && v >= 0.0))
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "forward variance pillars {:?} are not ascending with non-negative variances",
+                pillars
+            )));
+        }
+        Ok(ForwardVarianceCurve { pillars })
+    }
+    /// Curve repricing a term structure of (expiry, implied vol), each period's forward variance
+    /// the increase in total variance over it. Fails on a calendar arbitrage
+    pub fn from_term_structure(term: &[(f64, f64)]) -> Result<Self, PricingError> {
+        let mut pillars = Vec::with_capacity(term.len());
+        let (mut start, mut start_vol) = (0.0, 0.0);
+        for &(expiry, vol) in term {
+            let variance = forward_variance(start_vol, start, vol, expiry)?;
+            pillars.push((expiry, variance / (expiry - start)));
+            (start, start_vol) = (expiry, vol);
+        }
+        ForwardVarianceCurve::new(pillars)
+    }
+    pub fn pillars(&self) -> &[(f64, f64)] {
+        &self.pillars
+    }
+    /// ξ₀(t)
+    pub fn variance(&self, t: f64) -> f64 {
+        let last = self.pillars[self.pillars.len() - 1];
+        self.pillars
+            .iter()
+            .find(|&&(end, _)| t <= end)
+            .unwrap_or(&last)
+            .1
+    }
+    /// Total variance ∫₀ᵗ ξ₀(s) ds, the implied total variance of a variance swap to `t`
+    pub fn total_variance(&self, t: f64) -> f64 {
+        let mut start = 0.0;
+        let mut total = 0.0;
+        for &(end, variance) in &self.pillars {
+            total += variance * (end.min(t) - start).max(0.0);
+            start = end;
+        }
+        let last = self.pillars[self.pillars.len() - 1];
+        total + last.1 * (t - last.0).max(0.0)
+    }
+}
+
+/// Rough Bergomi model of the spot and its instantaneous variance.
+///
+/// Formula: V_t = ξ₀(t) exp(η √(2H) ∫₀ᵗ (t - s)^(H - ½) dW_s - ½η²t^(2H)),
+/// dS/S = (r - q)dt + √V_t (ρ dW_t + √(1 - ρ²) dW⊥_t)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoughBergomi {
+    /// Hurst exponent H in (0, ½], around 0.1 on equity indices
+    pub hurst: f64,
+    /// Volatility of variance η
+    pub eta: f64,
+    /// Correlation ρ of spot and variance drivers
+    pub rho: f64,
+    pub forward_variance: ForwardVarianceCurve,
+}
+
+impl RoughBergomi {
+    pub fn validate(&self) -> Result<(), PricingError> {
+        if !(self.hurst > 0.0 && self.hurst <= 0.5) {
+            return Err(PricingError::InvalidInput(format!(
+                "Hurst exponent {} is outside (0, 0.5]",
+                self.hurst
+            )));
+        }
+        if !(self.eta >= 0.0 && self.eta.is_finite() && (-1.0..=1.0).contains(&self.rho)) {
+            return Err(PricingError::InvalidInput(format!(
+                "vol of vol {} must be non-negative and correlation {} inside [-1, 1]",
+                self.eta, self.rho
+            )));
+        }
+        Ok(())
+    }
+    /// Discounted expectation of a path-dependent `payoff`, which receives the spot at every
+    /// step of `engine`'s grid including the start. Each step draws the variance driver's
+    /// increment, its Volterra integral over the step and an independent spot driver
+    pub fn price<P>(
+        &self,
+        engine: &MonteCarloEngine,
+        spot: f64,
+        rate: f64,
+        dividend_yield: Option<f64>,
+        maturity: f64,
+        payoff: P,
+    ) -> Result<McResult, PricingError>
+    where
+        P: Fn(&[f64]) -> f64 + Sync,
+    {
+        self.validate()?;
+        if !(spot > 0.0 && maturity > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "spot {} and maturity {} must be positive",
+                spot, maturity
+            )));
+        }
+        let steps = engine.config.steps.max(1);
+        let dt = maturity / steps as f64;
+        let sqrt_dt = sqrt(dt);
+        let alpha = self.hurst - 0.5;
+        // Riemann weights (b_k dt)^α of the increments k steps back, b_k the optimal points
+        let kernel: Vec<f64> = (0..=steps)
+            .map(|k| match k {
+                0 | 1 => 0.0,
+                _ if alpha == 0.0 => 1.0,
+                _ => {
+                    let k = k as f64;
+                    let b = powf(
+                        (powf(k, alpha + 1.0) - powf(k - 1.0, alpha + 1.0)) / (alpha + 1.0),
+                        1.0 / alpha,
+                    );
+                    powf(b * dt, alpha)
+                }
+            })
+            .collect();
+        // the latest step's integral ∫(t - s)^α dW_s regressed on its increment
+        let covariance = powf(dt, alpha + 1.0) / (alpha + 1.0);
+        let exact_variance = powf(dt, 2.0 * alpha + 1.0) / (2.0 * alpha + 1.0);
+        let beta = covariance / dt;
+        let residual = sqrt((exact_variance - beta * covariance).max(0.0));
+        let scale = self.eta * sqrt(2.0 * self.hurst);
+        // forward variance at each step's midpoint, so grid steps share the curve's periods
+        let variances: Vec<f64> = (0..steps)
+            .map(|i| self.forward_variance.variance((i as f64 + 0.5) * dt))
+            .collect();
+        let compensators: Vec<f64> = (0..steps)
+            .map(|i| 0.5 * self.eta * self.eta * powf(i as f64 * dt, 2.0 * self.hurst))
+            .collect();
+        let drift = rate - dividend_yield.unwrap_or(0.0);
+        let orthogonal = sqrt(1.0 - self.rho * self.rho);
+        let scratch = || (vec![0.0; steps], Vec::with_capacity(steps + 1));
+        let simulate = |buffers: &mut (Vec<f64>, Vec<f64>), z: &[f64]| {
+            let (dw, path) = buffers;
+            path.clear();
+            path.push(spot);
+            let (mut log_spot, mut latest) = (0.0, 0.0);
+            for (i, z) in z.chunks_exact(3).enumerate() {
+                // Volterra driver at t_i: the last step's exact integral plus the Riemann sum
+                let volterra = latest + (2..=i).map(|k| kernel[k] * dw[i - k]).sum::<f64>();
+                let variance = variances[i] * exp(scale * volterra - compensators[i]);
+                dw[i] = sqrt_dt * z[0];
+                latest = beta * dw[i] + residual * z[2];
+                let dz = self.rho * dw[i] + orthogonal * sqrt_dt * z[1];
+                log_spot += (drift - 0.5 * variance) * dt + sqrt(variance) * dz;
+                path.push(spot * exp(log_spot));
+            }
+            payoff(path)
+        };
+        Ok(engine.run(steps * 3, exp(-rate * maturity), scratch, simulate))
+    }
+    /// European price of `option` under the model; the option's own volatility is ignored
+    pub fn price_european(
+        &self,
+        engine: &MonteCarloEngine,
+        option: &Options,
+    ) -> Result<McResult, PricingError> {
+        let contract = *option;
+        self.price(
+            engine,
+            option.spot_price(),
+            option.risk_free_rate(),
+            option.dividend_yield(),
+            option.time_to_maturity(),
+            |path: &[f64]| contract.payout(path[path.len() - 1]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::black_scholes::implied_volatility;
+    use crate::mc::McConfig;
+
+    #[test]
+    fn test_forward_variance_curve() {
+        let curve = ForwardVarianceCurve::from_term_structure(&[(0.5, 0.2), (1.0, 0.25)]).unwrap();
+        assert!((curve.total_variance(0.5) - 0.02).abs() < 1e-15);
+        assert!((curve.total_variance(1.0) - 0.0625).abs() < 1e-15);
+        assert!((curve.variance(0.75) - 0.085).abs() < 1e-15);
+        // flat beyond the last pillar
+        assert!((curve.total_variance(2.0) - 0.0625 - 0.085).abs() < 1e-15);
+        assert_eq!(ForwardVarianceCurve::flat(0.04).total_variance(2.0), 0.08);
+        assert!(ForwardVarianceCurve::from_term_structure(&[(0.5, 0.3), (1.0, 0.2)]).is_err());
+        assert!(ForwardVarianceCurve::new(vec![(1.0, 0.04), (0.5, 0.04)]).is_err());
+    }
+
+    #[test]
+    fn test_rough_bergomi_prices() {
+        let engine = MonteCarloEngine::new(McConfig::new(20_000, 50, 3));
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.03, 0.5, Some(0.01));
+        // no vol of vol leaves Black-Scholes at the forward variance
+        let flat = RoughBergomi {
+            hurst: 0.1,
+            eta: 0.0,
+            rho: -0.9,
+            forward_variance: ForwardVarianceCurve::flat(0.04),
+        };
+        let result = flat.price_european(&engine, &call).unwrap();
+        assert!((result.price - call.bs_pricing()).abs() < 3.0 * result.std_error);
+
+        // a rough, strongly anticorrelated variance keeps the forward and skews the smile
+        let rough = RoughBergomi { eta: 1.9, ..flat };
+        let forward = rough
+            .price(&engine, 100.0, 0.03, Some(0.01), 0.5, |path| path[50])
+            .unwrap();
+        let expected = 100.0 * (-0.01f64 * 0.5).exp();
+        assert!((forward.price - expected).abs() < 3.0 * forward.std_error);
+        let implied = |option: Options| {
+            let price = rough.price_european(&engine, &option).unwrap().price;
+            implied_volatility(option, price).unwrap()
+        };
+        let put = Options::new_put(90.0, 100.0, 0.2, 0.03, 0.5, Some(0.01));
+        let wing = Options::new_call(110.0, 100.0, 0.2, 0.03, 0.5, Some(0.01));
+        assert!(implied(put) > implied(call) + 0.01 && implied(call) > implied(wing) + 0.01);
+        assert!(
+            RoughBergomi {
+                hurst: 0.7,
+                ..rough
+            }
+            .price_european(&engine, &call)
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use crate::error::PricingError;
+use crate::market_data::MarketDataProvider;
+use crate::math::sum::KahanSum;
+use crate::portfolio::{CashPosition, Portfolio, Position};
+use crate::vol::dynamics::StickyRule;
+
+// Floors keeping shocked inputs inside the domain of the pricing formulas
+const MIN_SPOT: f64 = 1e-8;
+const MIN_VOL: f64 = 1e-8;
+
+/// A single shock applied to a market input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shock {
+    /// Adds the value to the base input (e.g. +0.05 for five vol points)
+    Absolute(f64),
+    /// Scales the base input by (1 + value) (e.g. -0.1 for a 10% spot drop)
+    Relative(f64),
+    /// Adds the value in basis points (e.g. 25.0 for +0.25%)
+    BasisPoints(f64),
+}
+
+impl Shock {
+    pub fn apply(&self, base: f64) -> f64 {
+        match *self {
+            Shock::Absolute(shift) => base + shift,
+            Shock::Relative(shift) => base * (1.0 + shift),
+            Shock::BasisPoints(bp) => base + bp / 10_000.0,
+        }
+    }
+    /// Builds `steps` evenly spaced shocks from `from` to `to` inclusive, wrapped by `kind`
+    ///
+    /// e.g. `Shock::ladder(-0.2, 0.2, 9, Shock::Relative)` gives a ±20% spot ladder in 5% steps
+    pub fn ladder(from: f64, to: f64, steps: usize, kind: fn(f64) -> Shock) -> Vec<Shock> {
+        match steps {
+            0 => Vec::new(),
+            1 => vec![kind(from)],
+            _ => (0..steps)
+                .map(|i| kind(from + (to - from) * i as f64 / (steps - 1) as f64))
+                .collect(),
+        }
+    }
+}
+
+/// Spot × vol × rate shock definitions revalued jointly.
+///
+/// Every shock is applied to each position's own inputs, so all underlyings move together
+#[derive(Debug, Clone)]
+pub struct ScenarioGrid {
+    pub spot_shocks: Vec<Shock>,
+    pub vol_shocks: Vec<Shock>,
+    pub rate_shocks: Vec<Shock>,
+}
+
+impl ScenarioGrid {
+    pub fn new(spot_shocks: Vec<Shock>, vol_shocks: Vec<Shock>, rate_shocks: Vec<Shock>) -> Self {
+        ScenarioGrid {
+            spot_shocks,
+            vol_shocks,
+            rate_shocks,
+        }
+    }
+    /// Revalues the portfolio under every combination of shocks and returns the P&L cube
+    pub fn run(&self, portfolio: &Portfolio) -> ScenarioCube {
+        let vols: Vec<f64> = portfolio
+            .positions()
+            .iter()
+            .map(|position| position.option.volatility())
+            .collect();
+        let vols = vec![vols; self.spot_shocks.len()];
+        self.revalue(portfolio, portfolio.value(), &vols)
+    }
+    /// Revalues the portfolio with each option's vol read from `provider`'s surface for its
+    /// underlying, moved with every spot shock as `rule` says; vol shocks apply on top. The
+    /// surface is taken as quoted at each position's own spot, and the base value is repriced
+    /// on it, so the unshocked scenario has no P&L. Fails if the provider lacks an underlying
+    pub fn run_on_surface(
+        &self,
+        portfolio: &Portfolio,
+        provider: &dyn MarketDataProvider,
+        rule: StickyRule,
+    ) -> Result<ScenarioCube, PricingError> {
+        let surface_vol = |position: &Position, spot: f64| {
+            let option = position.option;
+            let strike = rule.surface_strike(
+                option.strike_price(),
+                option.spot_price(),
+                spot.max(MIN_SPOT),
+            );
+            provider.volatility(&position.underlying, strike, option.time_to_maturity())
+        };
+        let vols = self
+            .spot_shocks
+            .iter()
+            .map(|shock| {
+                portfolio
+                    .positions()
+                    .iter()
+                    .map(|position| {
+                        surface_vol(position, shock.apply(position.option.spot_price()))
+                    })
+                    .collect::<Result<Vec<f64>, PricingError>>()
+            })
+            .collect::<Result<Vec<Vec<f64>>, PricingError>>()?;
+        // today's surface vol is the spot-unshocked one, the same under either rule
+        let mut base_value = portfolio.value();
+        for position in portfolio.positions() {
+            let option = position.option;
+            let vol = surface_vol(position, option.spot_price())?;
+            let on_surface = option.with_market(option.spot_price(), vol, option.risk_free_rate());
+            base_value += position.units() * (on_surface.bs_pricing() - option.bs_pricing());
+        }
+        Ok(self.revalue(portfolio, base_value, &vols))
+    }
+    // P&L cube against `base_value`, with vols[spot shock][position] the unshocked vol of each
+    // option in each spot scenario
+    fn revalue(&self, portfolio: &Portfolio, base_value: f64, vols: &[Vec<f64>]) -> ScenarioCube {
+        let mut pnl = Vec::with_capacity(
+            self.spot_shocks.len() * self.vol_shocks.len() * self.rate_shocks.len(),
+        );
+        for (spot_shock, spot_vols) in self.spot_shocks.iter().zip(vols) {
+            for vol_shock in &self.vol_shocks {
+                for rate_shock in &self.rate_shocks {
+                    let options =
+                        portfolio
+                            .positions()
+                            .iter()
+                            .zip(spot_vols)
+                            .map(|(position, &vol)| {
+                                let option = position.option;
+                                let shocked = option.with_market(
+                                    spot_shock.apply(option.spot_price()).max(MIN_SPOT),
+                                    vol_shock.apply(vol).max(MIN_VOL),
+                                    rate_shock.apply(option.risk_free_rate()),
+                                );
+                                position.units() * shocked.bs_pricing()
+                            });
+                    let stocks = portfolio
+                        .stocks()
+                        .iter()
+                        .map(|stock| stock.quantity * spot_shock.apply(stock.spot).max(MIN_SPOT));
+                    let cash = portfolio.cash().iter().map(|cash| CashPosition {
+                        risk_free_rate: rate_shock.apply(cash.risk_free_rate),
+                        ..*cash
+                    });
+                    let value: f64 = options
+                        .chain(stocks)
+                        .chain(cash.map(|cash| cash.value()))
+                        .sum::<KahanSum>()
+                        .value();
+                    pnl.push(value - base_value);
+                }
+            }
+        }
+        ScenarioCube {
+            base_value,
+            shape: (
+                self.spot_shocks.len(),
+                self.vol_shocks.len(),
+                self.rate_shocks.len(),
+            ),
+            pnl,
+        }
+    }
+}
+
+/// P&L of a portfolio over a scenario grid, indexed by (spot, vol, rate) shock position.
+#[derive(Debug, Clone)]
+pub struct ScenarioCube {
+    pub base_value: f64,
+    shape: (usize, usize, usize),
+    pnl: Vec<f64>,
+}
+
+impl ScenarioCube {
+    /// Number of (spot, vol, rate) shocks along each axis
+    pub fn shape(&self) -> (usize, usize, usize) {
+        self.shape
+    }
+    pub fn pnl(&self, spot: usize, vol: usize, rate: usize) -> f64 {
+        let (_, n_vol, n_rate) = self.shape;
+        self.pnl[(spot * n_vol + vol) * n_rate + rate]
+    }
+    /// Spot × vol P&L matrix for a fixed rate shock
+    pub fn spot_vol_slice(&self, rate: usize) -> Vec<Vec<f64>> {
+        let (n_spot, n_vol, _) = self.shape;
+        (0..n_spot)
+            .map(|i| (0..n_vol).map(|j| self.pnl(i, j, rate)).collect())
+            .collect()
+    }
+    /// Most negative P&L over all scenarios, zero if no scenario loses money
+    pub fn worst_loss(&self) -> f64 {
+        self.pnl.iter().copied().fold(0.0, f64::min)
+    }
+    pub fn values(&self) -> &[f64] {
+        &self.pnl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    #[test]
+    fn test_shock_application() {
+        assert!((Shock::Absolute(0.05).apply(0.2) - 0.25).abs() < 1e-12);
+        assert!((Shock::Relative(-0.1).apply(100.0) - 90.0).abs() < 1e-12);
+        assert!((Shock::BasisPoints(25.0).apply(0.05) - 0.0525).abs() < 1e-12);
+        let ladder = Shock::ladder(-0.2, 0.2, 5, Shock::Relative);
+        assert_eq!(ladder.len(), 5);
+        assert_eq!(ladder[2], Shock::Relative(0.0));
+    }
+
+    #[test]
+    fn test_scenario_cube() {
+        let mut portfolio = Portfolio::new();
+        portfolio.add(Position::new(
+            "SPX",
+            Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None),
+            1.0,
+        ));
+        let grid = ScenarioGrid::new(
+            Shock::ladder(-0.1, 0.1, 3, Shock::Relative),
+            vec![Shock::Absolute(0.0), Shock::Absolute(0.05)],
+            vec![Shock::BasisPoints(0.0)],
+        );
+        let cube = grid.run(&portfolio);
+        assert_eq!(cube.shape(), (3, 2, 1));
+        // unshocked scenario has no P&L
+        assert!(cube.pnl(1, 0, 0).abs() < 1e-12);
+        // long call gains on spot up and vol up, loses on spot down
+        assert!(cube.pnl(2, 0, 0) > 0.0);
+        assert!(cube.pnl(1, 1, 0) > 0.0);
+        assert!(cube.pnl(0, 0, 0) < 0.0);
+        assert_eq!(cube.worst_loss(), cube.pnl(0, 0, 0));
+    }
+
+    #[test]
+    fn test_scenarios_on_a_moving_smile() {
+        use crate::curves::RateCurve;
+        use crate::market_data::{StaticMarketData, SymbolData, VolData};
+
+        let mut market = StaticMarketData::new();
+        market
+            .insert(
+                "SPX",
+                SymbolData {
+                    spot: 100.0,
+                    rate_curve: RateCurve::new(vec![(1.0, 0.05)]),
+                    dividend_yield: None,
+                    vol: VolData::Grid {
+                        expiries: vec![1.0],
+                        strikes: vec![80.0, 100.0, 120.0],
+                        vols: vec![vec![0.3, 0.2, 0.15]],
+                    },
+                },
+            )
+            .unwrap();
+        let mut portfolio = Portfolio::new();
+        let call = Options::new_call(100.0, 100.0, 0.5, 0.05, 1.0, None);
+        portfolio.add(Position::new("SPX", call, 1.0));
+        let grid = ScenarioGrid::new(
+            vec![Shock::Relative(0.0), Shock::Relative(0.25)],
+            vec![Shock::Absolute(0.0)],
+            vec![Shock::Absolute(0.0)],
+        );
+        let sticky_strike = grid
+            .run_on_surface(&portfolio, &market, StickyRule::StickyStrike)
+            .unwrap();
+        let sticky_moneyness = grid
+            .run_on_surface(&portfolio, &market, StickyRule::StickyMoneyness)
+            .unwrap();
+        // the base is repriced on the surface, not at the position's stale 50% vol
+        let on_surface = call.with_market(100.0, 0.2, 0.05).bs_pricing();
+        assert!((sticky_strike.base_value - on_surface).abs() < 1e-12);
+        assert!(sticky_strike.pnl(0, 0, 0).abs() < 1e-12);
+        let rallied = |vol: f64| call.with_market(125.0, vol, 0.05).bs_pricing() - on_surface;
+        assert!((sticky_strike.pnl(1, 0, 0) - rallied(0.2)).abs() < 1e-12);
+        // the 100 strike now trades at 80% moneyness, up the skew at 30% vol
+        assert!((sticky_moneyness.pnl(1, 0, 0) - rallied(0.3)).abs() < 1e-12);
+        assert!(
+            grid.run_on_surface(
+                &portfolio,
+                &StaticMarketData::new(),
+                StickyRule::StickyStrike
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// Contract selection by Greeks. Systematic option sellers choose the strike with a target delta,
+// or the expiry at which theta reaches a level, rather than a fixed strike or date. Each helper
+// returns the contract on the same side and market with the solved term, searched with the
+// solver's bracketing root finders where no closed form applies.
+use crate::Options;
+use crate::conventions::ThetaConvention;
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, sqrt};
+use crate::moneyness::DeltaConvention;
+use crate::solver::{DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE, brent};
+use alloc::format;
+
+// Strikes searched on a smile span this many standard deviations either side of the forward
+const MAX_STRIKE_SD: f64 = 10.0;
+
+impl Options {
+    fn with_strike(&self, strike: f64, volatility: f64) -> Options {
+        Options::new_with_type(
+            self.option_type(),
+            strike,
+            self.spot_price(),
+            volatility,
+            self.risk_free_rate(),
+            self.time_to_maturity(),
+            self.dividend_yield(),
+        )
+    }
+    /// The contract at the strike whose spot delta is `target`, e.g. -0.25 for the 25-delta put,
+    /// at the contract's volatility. Fails when no strike has the delta
+    pub fn find_strike_for_delta(&self, target: f64) -> Result<Options, PricingError> {
+        let strike = self.strike_from_delta(target, DeltaConvention::Spot)?;
+        Ok(self.with_strike(strike, self.volatility()))
+    }
+    /// The contract at the strike whose spot delta is `target` with each strike priced at its
+    /// own volatility `smile(strike)`, the contract's volatility replaced. The delta must be
+    /// monotone in the strike over the smile, as it is for any smile free of arbitrage
+    pub fn find_strike_for_delta_on_smile<V: Fn(f64) -> f64>(
+        &self,
+        target: f64,
+        smile: V,
+    ) -> Result<Options, PricingError> {
+        let forward = self.forward();
+        let span = MAX_STRIKE_SD * smile(forward) * sqrt(self.time_to_maturity());
+        let delta_at = |log_strike: f64| {
+            let strike = exp(log_strike);
+            self.with_strike(strike, smile(strike)).delta() - target
+        };
+        let (lo, hi) = (ln(forward) - span, ln(forward) + span);
+        if !(span > 0.0 && delta_at(lo) * delta_at(hi) < 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "no strike on the smile has delta {}",
+                target
+            )));
+        }
+        let strike = exp(brent(
+            delta_at,
+            lo,
+            hi,
+            DEFAULT_TOLERANCE,
+            DEFAULT_MAX_ITERATIONS,
+        )?);
+        Ok(self.with_strike(strike, smile(strike)))
+    }
+    /// The listed strike whose contract has the spot delta closest to `target`, None for no
+    /// strikes
+    pub fn closest_strike_by_delta(&self, strikes: &[f64], target: f64) -> Option<Options> {
+        strikes
+            .iter()
+            .map(|&strike| self.with_strike(strike, self.volatility()))
+            .min_by(|a, b| {
+                (a.delta() - target)
+                    .abs()
+                    .total_cmp(&(b.delta() - target).abs())
+            })
+    }
+    /// The contract at the expiry in [`shortest`, `longest`] years whose theta, quoted in
+    /// `convention`, is `target`. Theta is not monotone in expiry away from the money, so the
+    /// bracket must contain a single crossing; fails when it contains none
+    pub fn find_expiry_for_theta(
+        &self,
+        target: f64,
+        convention: ThetaConvention,
+        shortest: f64,
+        longest: f64,
+    ) -> Result<Options, PricingError> {
+        let theta_at = |t: f64| convention.scale(self.with_time_to_maturity(t).theta()) - target;
+        if !(shortest > 0.0 && longest > shortest && theta_at(shortest) * theta_at(longest) < 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "no expiry between {} and {} years has theta {}",
+                shortest, longest, target
+            )));
+        }
+        let t = brent(
+            theta_at,
+            shortest,
+            longest,
+            DEFAULT_TOLERANCE,
+            DEFAULT_MAX_ITERATIONS,
+        )?;
+        Ok(self.with_time_to_maturity(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_by_greeks() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.03, 45.0 / 365.0, Some(0.01));
+        let sold = put.find_strike_for_delta(-0.16).unwrap();
+        assert!((sold.delta() + 0.16).abs() < 1e-10 && sold.strike_price() < 100.0);
+        let listed: Vec<f64> = (80..=100).map(f64::from).collect();
+        let nearest = put.closest_strike_by_delta(&listed, -0.16).unwrap();
+        assert!((nearest.strike_price() - sold.strike_price()).abs() <= 0.5);
+        assert!(put.closest_strike_by_delta(&[], -0.16).is_none());
+
+        // a put skew lifts the vol of low strikes, moving the 16-delta put further out of the
+        // money to a lower strike than at flat vol
+        let skew = |k: f64| 0.2 + 0.5 * (100.0 / k).ln().max(0.0);
+        let skewed = put.find_strike_for_delta_on_smile(-0.16, skew).unwrap();
+        assert!((skewed.delta() + 0.16).abs() < 1e-9);
+        assert!((skewed.volatility() - skew(skewed.strike_price())).abs() < 1e-15);
+        assert!(skewed.strike_price() < sold.strike_price());
+        assert!(put.find_strike_for_delta_on_smile(0.3, skew).is_err());
+
+        // the expiry at which an at-the-money put decays 5 cents a calendar day
+        let atm = Options::new_put(100.0, 100.0, 0.2, 0.0, 0.25, None);
+        let chosen = atm
+            .find_expiry_for_theta(-0.05, ThetaConvention::PerCalendarDay, 1.0 / 365.0, 2.0)
+            .unwrap();
+        let daily = ThetaConvention::PerCalendarDay.scale(chosen.theta());
+        assert!((daily + 0.05).abs() < 1e-9);
+        assert!(
+            atm.find_expiry_for_theta(-10.0, ThetaConvention::PerCalendarDay, 1.0 / 365.0, 2.0)
+                .is_err()
+        );
+    }
+}
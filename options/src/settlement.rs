@@ -0,0 +1,186 @@
+// Expiry-day settlement conventions of listed options. Index options like SPX settle in cash,
+// most of them AM against the special opening quotation, so their life ends at the open of the
+// expiry day rather than its close; equity options settle PM into shares, with holders allowed
+// to exercise until a cutoff after the close. For a 0DTE contract those hours are most of its
+// remaining life, so the time to expiry is counted in trading minutes to the settlement print.
+use crate::Options;
+use crate::calendar::{Date, DateTime, TimeOfDay, TradingHours};
+use crate::clock::TradingCalendar;
+use crate::error::PricingError;
+
+/// What changes hands at expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SettlementType {
+    /// The intrinsic value in cash, as for index options
+    Cash,
+    /// Delivery of the underlying at the strike, as for equity options
+    #[default]
+    Physical,
+}
+
+/// Which print on the expiry day sets the settlement price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExpirySession {
+    /// Opening prices of the expiry day; the contract stops trading the session before
+    Am,
+    /// The expiry day's close
+    #[default]
+    Pm,
+}
+
+/// Settlement terms of a listed contract, PM physical by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Settlement {
+    pub settlement_type: SettlementType,
+    pub session: ExpirySession,
+    /// Latest time on the expiry day a holder may submit an exercise notice, None if exercise
+    /// is automatic
+    pub exercise_cutoff: Option<TimeOfDay>,
+}
+
+impl Default for Settlement {
+    fn default() -> Self {
+        Settlement::pm_physical()
+    }
+}
+
+impl Settlement {
+    /// Cash-settled off the opening quotation, as for standard monthly SPX options
+    pub fn am_cash() -> Self {
+        Settlement {
+            settlement_type: SettlementType::Cash,
+            session: ExpirySession::Am,
+            exercise_cutoff: None,
+        }
+    }
+    /// Cash-settled off the close, as for SPX weeklies and 0DTE
+    pub fn pm_cash() -> Self {
+        Settlement {
+            settlement_type: SettlementType::Cash,
+            session: ExpirySession::Pm,
+            exercise_cutoff: None,
+        }
+    }
+    /// Delivered off the close with exercise notices accepted until 17:30, as for US equity
+    /// options
+    pub fn pm_physical() -> Self {
+        Settlement {
+            settlement_type: SettlementType::Physical,
+            session: ExpirySession::Pm,
+            exercise_cutoff: Some(TimeOfDay::new(17, 30).expect("17:30 is a time of day")),
+        }
+    }
+    /// Time on the expiry day the settlement price is struck: the open for AM, the close for PM
+    pub fn settles_at(&self, hours: &TradingHours) -> TimeOfDay {
+        match self.session {
+            ExpirySession::Am => hours.open,
+            ExpirySession::Pm => hours.close,
+        }
+    }
+    /// Whether an exercise notice at `time` on the expiry day is still accepted
+    pub fn accepts_exercise_at(&self, time: TimeOfDay) -> bool {
+        self.exercise_cutoff.is_none_or(|cutoff| time <= cutoff)
+    }
+    /// Exchange-local time of the settlement print of a contract expiring on `expiry`
+    pub fn expires_at(&self, hours: &TradingHours, expiry: Date) -> DateTime {
+        DateTime::new(expiry, self.settles_at(hours))
+    }
+    /// Trading-time years from `now` in exchange-local time to the settlement print on
+    /// `expiry`. Fails once the settlement print has passed
+    pub fn time_to_expiry(
+        &self,
+        hours: &TradingHours,
+        now: DateTime,
+        expiry: Date,
+    ) -> Result<f64, PricingError> {
+        let settles = self.expires_at(hours, expiry);
+        if settles <= now {
+            return Err(PricingError::InvalidInput(format!(
+                "contract settled at {}",
+                settles
+            )));
+        }
+        Ok(hours.year_fraction(now, settles))
+    }
+}
+
+impl Options {
+    /// The contract with its time to maturity running from `now` in exchange-local time to the
+    /// settlement print on `expiry` under `settlement`. Fails once settled
+    pub fn with_settlement(
+        &self,
+        settlement: &Settlement,
+        hours: &TradingHours,
+        now: DateTime,
+        expiry: Date,
+    ) -> Result<Self, PricingError> {
+        Ok(self.with_time_to_maturity(settlement.time_to_expiry(hours, now, expiry)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_dte_time_to_expiry() {
+        let hours = TradingHours::default();
+        let friday = Date::new(2025, 6, 20).unwrap();
+        let thursday = Date::new(2025, 6, 19).unwrap();
+        let at = |text: &str| text.parse::<TimeOfDay>().unwrap();
+        // at 15:00 on expiry day a PM contract has an hour left; an AM one settled this morning
+        let pm = Settlement::pm_cash()
+            .time_to_expiry(&hours, DateTime::new(friday, at("15:00")), friday)
+            .unwrap();
+        assert!((pm - 60.0 / (390.0 * 252.0)).abs() < 1e-15);
+        assert!(
+            Settlement::am_cash()
+                .time_to_expiry(&hours, DateTime::new(friday, at("15:00")), friday)
+                .is_err()
+        );
+        // the day before, the AM contract loses the whole expiry session the PM one keeps
+        let am = Settlement::am_cash()
+            .time_to_expiry(&hours, DateTime::new(thursday, at("15:00")), friday)
+            .unwrap();
+        let pm = Settlement::pm_cash()
+            .time_to_expiry(&hours, DateTime::new(thursday, at("15:00")), friday)
+            .unwrap();
+        assert!((pm - am - 1.0 / 252.0).abs() < 1e-15);
+
+        // rounding the last hour up to a calendar day doubles the price of the call
+        let price = |t: f64| Options::new_call(100.0, 100.0, 0.2, 0.0, t, None).bs_pricing();
+        assert!(price(1.0 / 365.0) > 2.0 * price(pm - 1.0 / 252.0));
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.0, 1.0 / 365.0, None)
+            .with_settlement(
+                &Settlement::am_cash(),
+                &hours,
+                DateTime::new(thursday, at("15:00")),
+                friday,
+            )
+            .unwrap();
+        assert_eq!(call.time_to_maturity(), am);
+
+        let equity = Settlement::pm_physical();
+        assert_eq!(equity.settlement_type, SettlementType::Physical);
+        assert_eq!(Settlement::default(), equity);
+        assert!(
+            equity.accepts_exercise_at(at("17:30")) && !equity.accepts_exercise_at(at("17:31"))
+        );
+        assert!(Settlement::am_cash().accepts_exercise_at(at("23:59")));
+    }
+}
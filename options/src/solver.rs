@@ -0,0 +1,236 @@
+use crate::error::PricingError;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Default absolute tolerance of the root finders
+pub const DEFAULT_TOLERANCE: f64 = 1e-10;
+/// Default iteration cap of the root finders and minimizers
+pub const DEFAULT_MAX_ITERATIONS: usize = 200;
+
+/// Finds a root of `f` in [lo, hi] by bisection. The bracket must change sign
+pub fn bisection<F: Fn(f64) -> f64>(
+    f: F,
+    mut lo: f64,
+    mut hi: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<f64, PricingError> {
+    let mut f_lo = f(lo);
+    if f_lo * f(hi) > 0.0 {
+        return Err(PricingError::InvalidInput(
+            "root is not bracketed".to_string(),
+        ));
+    }
+    for _ in 0..max_iterations {
+        let mid = 0.5 * (lo + hi);
+        let f_mid = f(mid);
+        if f_mid == 0.0 || 0.5 * (hi - lo) < tolerance {
+            return Ok(mid);
+        }
+        if f_lo * f_mid < 0.0 {
+            hi = mid;
+        } else {
+            lo = mid;
+            f_lo = f_mid;
+        }
+    }
+    Err(PricingError::NoConvergence {
+        iterations: max_iterations,
+    })
+}
+
+/// Finds a root of `f` in [lo, hi] with Brent's method (inverse quadratic interpolation with
+/// bisection fallback). The bracket must change sign
+pub fn brent<F: Fn(f64) -> f64>(
+    f: F,
+    lo: f64,
+    hi: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<f64, PricingError> {
+    let (mut a, mut b) = (lo, hi);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa * fb > 0.0 {
+        return Err(PricingError::InvalidInput(
+            "root is not bracketed".to_string(),
+        ));
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    for _ in 0..max_iterations {
+        if fb * fc > 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tol = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let m = 0.5 * (c - b);
+        if m.abs() <= tol || fb == 0.0 {
+            return Ok(b);
+        }
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * m * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * m * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol { d } else { tol.copysign(m) };
+        fb = f(b);
+    }
+    Err(PricingError::NoConvergence {
+        iterations: max_iterations,
+    })
+}
+
+/// Newton-Raphson iteration from `x0` using the derivative `df`
+pub fn newton<F: Fn(f64) -> f64, D: Fn(f64) -> f64>(
+    f: F,
+    df: D,
+    x0: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<f64, PricingError> {
+    let mut x = x0;
+    for _ in 0..max_iterations {
+        let slope = df(x);
+        if slope == 0.0 || !slope.is_finite() {
+            break;
+        }
+        let step = f(x) / slope;
+        x -= step;
+        if step.abs() < tolerance {
+            return Ok(x);
+        }
+    }
+    Err(PricingError::NoConvergence {
+        iterations: max_iterations,
+    })
+}
+
+/// Minimizes `f` with the Nelder-Mead simplex method, starting from `x0` with an initial simplex
+/// of edge `step`. Returns the best point found and its objective value
+pub fn nelder_mead<F: Fn(&[f64]) -> f64>(
+    f: F,
+    x0: &[f64],
+    step: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> (Vec<f64>, f64) {
+    let n = x0.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((x0.to_vec(), f(x0)));
+    for i in 0..n {
+        let mut point = x0.to_vec();
+        point[i] += step;
+        let value = f(&point);
+        simplex.push((point, value));
+    }
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[n].1 - simplex[0].1).abs() < tolerance {
+            break;
+        }
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(p, _)| p[j]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |t: f64| -> Vec<f64> {
+            centroid
+                .iter()
+                .zip(&simplex[n].0)
+                .map(|(c, w)| c + t * (w - c))
+                .collect()
+        };
+        let reflected = towards(-1.0);
+        let f_reflected = f(&reflected);
+        if f_reflected < simplex[0].1 {
+            let expanded = towards(-2.0);
+            let f_expanded = f(&expanded);
+            simplex[n] = if f_expanded < f_reflected {
+                (expanded, f_expanded)
+            } else {
+                (reflected, f_reflected)
+            };
+        } else if f_reflected < simplex[n - 1].1 {
+            simplex[n] = (reflected, f_reflected);
+        } else {
+            let contracted = towards(0.5);
+            let f_contracted = f(&contracted);
+            if f_contracted < simplex[n].1 {
+                simplex[n] = (contracted, f_contracted);
+            } else {
+                let best = simplex[0].0.clone();
+                for vertex in simplex.iter_mut().skip(1) {
+                    let shrunk: Vec<f64> = best
+                        .iter()
+                        .zip(&vertex.0)
+                        .map(|(b, v)| b + 0.5 * (v - b))
+                        .collect();
+                    let value = f(&shrunk);
+                    *vertex = (shrunk, value);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_finders() {
+        let f = |x: f64| x * x - 2.0;
+        let sqrt2 = 2f64.sqrt();
+        let root = bisection(f, 0.0, 2.0, 1e-12, 200).unwrap();
+        assert!((root - sqrt2).abs() < 1e-10);
+        let root = brent(f, 0.0, 2.0, 1e-12, 100).unwrap();
+        assert!((root - sqrt2).abs() < 1e-10);
+        let root = newton(f, |x| 2.0 * x, 1.0, 1e-12, 50).unwrap();
+        assert!((root - sqrt2).abs() < 1e-10);
+        assert!(brent(f, 2.0, 3.0, 1e-12, 100).is_err());
+    }
+
+    #[test]
+    fn test_nelder_mead_rosenbrock() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+        let (best, value) = nelder_mead(rosenbrock, &[-1.2, 1.0], 0.5, 1e-14, 5_000);
+        assert!(value < 1e-8);
+        assert!((best[0] - 1.0).abs() < 1e-3 && (best[1] - 1.0).abs() < 1e-3);
+    }
+}
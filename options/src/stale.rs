@@ -0,0 +1,126 @@
+// Pricing off a spot that stopped updating because the underlying market is closed.
+use crate::Options;
+
+/// Hours in the year used to convert spot ages into year fractions
+pub const HOURS_PER_YEAR: f64 = 24.0 * 365.0;
+
+/// Last traded price of an underlying and how long ago it printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotSnapshot {
+    pub spot: f64,
+    /// Hours since the last trade
+    pub age_hours: f64,
+    /// Whether the underlying market is trading, so the spot keeps updating
+    pub market_open: bool,
+}
+
+impl SpotSnapshot {
+    pub fn live(spot: f64) -> Self {
+        SpotSnapshot {
+            spot,
+            age_hours: 0.0,
+            market_open: true,
+        }
+    }
+    /// Spot from a closed market that last traded `age_hours` ago
+    pub fn closed(spot: f64, age_hours: f64) -> Self {
+        SpotSnapshot {
+            spot,
+            age_hours,
+            market_open: false,
+        }
+    }
+    pub fn is_stale(&self) -> bool {
+        !self.market_open
+    }
+}
+
+/// Extra variance for the move the underlying may have made since its last trade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OvernightAdjustment {
+    None,
+    /// Total log-variance to add, e.g. from an overnight futures or ADR move model
+    Variance(f64),
+    /// Annualized volatility of the closed hours, accrued over the spot's age
+    Volatility(f64),
+}
+
+/// Price and Greeks computed off a possibly stale spot, with the staleness that went into them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StalePricing {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub stale: bool,
+    pub spot_age_hours: f64,
+    /// Log-variance added for the unobserved move, zero without an adjustment
+    pub added_variance: f64,
+    /// Volatility actually used: sqrt(σ² + added variance / T)
+    pub effective_volatility: f64,
+}
+
+impl Options {
+    /// Prices off `snapshot`, widening the volatility by the overnight variance when the spot is
+    /// stale.
+    ///
+    /// An unobserved lognormal move with variance v convolves with the diffusion, so European
+    /// prices equal Black-Scholes at total variance σ²T + v. Live spots are never adjusted
+    pub fn price_with_spot(
+        &self,
+        snapshot: &SpotSnapshot,
+        adjustment: OvernightAdjustment,
+    ) -> StalePricing {
+        let added_variance = match (snapshot.is_stale(), adjustment) {
+            (false, _) | (true, OvernightAdjustment::None) => 0.0,
+            (true, OvernightAdjustment::Variance(variance)) => variance.max(0.0),
+            (true, OvernightAdjustment::Volatility(vol)) => {
+                vol * vol * snapshot.age_hours.max(0.0) / HOURS_PER_YEAR
+            }
+        };
+        let vol = self.volatility();
+        let effective_volatility = (vol * vol + added_variance / self.time_to_maturity()).sqrt();
+        let adjusted = self.with_market(snapshot.spot, effective_volatility, self.risk_free_rate());
+        StalePricing {
+            price: adjusted.bs_pricing(),
+            delta: adjusted.delta(),
+            gamma: adjusted.gamma(),
+            vega: adjusted.vega(),
+            stale: snapshot.is_stale(),
+            spot_age_hours: snapshot.age_hours,
+            added_variance,
+            effective_volatility,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_spot_adjustment() {
+        let option = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.25, None);
+        let live = option.price_with_spot(
+            &SpotSnapshot::live(101.0),
+            OvernightAdjustment::Variance(0.01),
+        );
+        assert!(!live.stale);
+        assert_eq!(live.added_variance, 0.0);
+        assert_eq!(
+            live.price,
+            option.with_market(101.0, 0.2, 0.05).bs_pricing()
+        );
+
+        // 0.0004 of variance over a quarter lifts the vol from 0.2 to sqrt(0.04 + 0.0016)
+        let closed = SpotSnapshot::closed(101.0, 16.0);
+        let adjusted = option.price_with_spot(&closed, OvernightAdjustment::Variance(0.0004));
+        assert!(adjusted.stale);
+        assert!((adjusted.effective_volatility - 0.0416f64.sqrt()).abs() < 1e-12);
+        assert!(adjusted.price > live.price);
+
+        let accrued = option.price_with_spot(&closed, OvernightAdjustment::Volatility(0.3));
+        let variance = 0.09 * 16.0 / HOURS_PER_YEAR;
+        assert!((accrued.added_variance - variance).abs() < 1e-15);
+    }
+}
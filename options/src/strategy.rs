@@ -0,0 +1,489 @@
+use crate::error::PricingError;
+use crate::math::float::{exp, ln};
+use crate::quote::OptionQuote;
+use crate::{OptionType, Options};
+use std::ops::RangeInclusive;
+
+// Expiries, in years, and strikes this close are matched into one structure
+const MATCH_TOLERANCE: f64 = 1e-9;
+
+/// One option of a strategy, with a signed contract count (negative when short).
+#[derive(Debug, Clone, Copy)]
+pub struct Leg {
+    pub option: Options,
+    pub quantity: f64,
+}
+
+/// Combination of options on a single underlying, treated as one payoff.
+#[derive(Debug, Clone, Default)]
+pub struct Strategy {
+    legs: Vec<Leg>,
+}
+
+impl Strategy {
+    pub fn new(legs: Vec<Leg>) -> Self {
+        Strategy { legs }
+    }
+    pub fn legs(&self) -> &[Leg] {
+        &self.legs
+    }
+    pub fn add(&mut self, option: Options, quantity: f64) {
+        self.legs.push(Leg { option, quantity });
+    }
+    /// Long call and long put at the same strike
+    pub fn straddle(call: Options, put: Options) -> Self {
+        Strategy::new(vec![
+            Leg {
+                option: call,
+                quantity: 1.0,
+            },
+            Leg {
+                option: put,
+                quantity: 1.0,
+            },
+        ])
+    }
+    /// Long the first option and short the second, e.g. a bull call or bear put spread
+    pub fn vertical(long: Options, short: Options) -> Self {
+        Strategy::new(vec![
+            Leg {
+                option: long,
+                quantity: 1.0,
+            },
+            Leg {
+                option: short,
+                quantity: -1.0,
+            },
+        ])
+    }
+    /// Model cost of entering the strategy, positive for a net debit
+    pub fn premium(&self) -> f64 {
+        self.legs
+            .iter()
+            .map(|leg| leg.quantity * leg.option.bs_pricing())
+            .sum()
+    }
+    /// Value of all legs at expiry for a terminal spot
+    pub fn payoff(&self, spot: f64) -> f64 {
+        self.legs
+            .iter()
+            .map(|leg| leg.quantity * leg.option.payout(spot))
+            .sum()
+    }
+    /// Expiry payoff sampled on `n_points` evenly spaced spots
+    pub fn payoff_curve(
+        &self,
+        spot_range: RangeInclusive<f64>,
+        n_points: usize,
+    ) -> Vec<(f64, f64)> {
+        spot_grid(spot_range, n_points)
+            .map(|spot| (spot, self.payoff(spot)))
+            .collect()
+    }
+    /// Expiry P&L net of the premium paid for the strategy
+    pub fn pnl_curve(
+        &self,
+        spot_range: RangeInclusive<f64>,
+        n_points: usize,
+        premium: f64,
+    ) -> Vec<(f64, f64)> {
+        spot_grid(spot_range, n_points)
+            .map(|spot| (spot, self.payoff(spot) - premium))
+            .collect()
+    }
+    /// Terminal spots where the expiry P&L crosses zero, in increasing order.
+    ///
+    /// The payoff is piecewise linear with kinks at the strikes, so each segment is solved exactly.
+    /// Segments on which the P&L is identically zero are skipped
+    pub fn break_evens(&self, premium: f64) -> Vec<f64> {
+        let mut strikes: Vec<f64> = self
+            .legs
+            .iter()
+            .map(|leg| leg.option.strike_price())
+            .collect();
+        strikes.sort_by(f64::total_cmp);
+        strikes.dedup();
+        if strikes.is_empty() {
+            return Vec::new();
+        }
+        let pnl = |spot: f64| self.payoff(spot) - premium;
+        let mut points = Vec::new();
+        // below the lowest strike only puts pay, each with slope -1
+        let left_slope: f64 = self
+            .legs
+            .iter()
+            .filter(|leg| matches!(leg.option, Options::Put(_)))
+            .map(|leg| -leg.quantity)
+            .sum();
+        let low = strikes[0];
+        if left_slope != 0.0 {
+            let root = low - pnl(low) / left_slope;
+            if (0.0..low).contains(&root) {
+                points.push(root);
+            }
+        }
+        for pair in strikes.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (pa, pb) = (pnl(a), pnl(b));
+            if pa == 0.0 && pb != 0.0 {
+                points.push(a);
+            } else if pa * pb < 0.0 {
+                points.push(a + (b - a) * pa / (pa - pb));
+            }
+        }
+        let high = strikes[strikes.len() - 1];
+        let right_slope: f64 = self
+            .legs
+            .iter()
+            .filter(|leg| matches!(leg.option, Options::Call(_)))
+            .map(|leg| leg.quantity)
+            .sum();
+        let p_high = pnl(high);
+        if p_high == 0.0 && right_slope != 0.0 {
+            points.push(high);
+        } else if right_slope != 0.0 {
+            let root = high - p_high / right_slope;
+            if root > high {
+                points.push(root);
+            }
+        }
+        points
+    }
+}
+
+fn spot_grid(spot_range: RangeInclusive<f64>, n_points: usize) -> impl Iterator<Item = f64> {
+    let (low, high) = spot_range.into_inner();
+    let n = n_points.max(2);
+    (0..n).map(move |i| low + (high - low) * i as f64 / (n - 1) as f64)
+}
+
+impl Options {
+    /// Expiry payoff of one contract sampled on `n_points` evenly spaced spots
+    pub fn payoff_curve(
+        &self,
+        spot_range: RangeInclusive<f64>,
+        n_points: usize,
+    ) -> Vec<(f64, f64)> {
+        spot_grid(spot_range, n_points)
+            .map(|spot| (spot, self.payout(spot)))
+            .collect()
+    }
+    /// Expiry P&L of one long contract bought for `premium`
+    pub fn pnl_curve(
+        &self,
+        spot_range: RangeInclusive<f64>,
+        n_points: usize,
+        premium: f64,
+    ) -> Vec<(f64, f64)> {
+        spot_grid(spot_range, n_points)
+            .map(|spot| (spot, self.payout(spot) - premium))
+            .collect()
+    }
+    /// Terminal spot at which a long contract bought for `premium` breaks even
+    ///
+    /// Formula: K + premium for calls, K - premium for puts
+    pub fn break_even(&self, premium: f64) -> f64 {
+        match self {
+            Options::Call(_) => self.strike_price() + premium,
+            Options::Put(_) => self.strike_price() - premium,
+        }
+    }
+}
+
+/// Parity structure locking in a fixed amount at expiry whatever the underlying does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParityStructure {
+    /// Long the low call and high put, short the high call and low put: receives K_high - K_low
+    LongBox,
+    /// The long box reversed: owes K_high - K_low
+    ShortBox,
+    /// Long stock and put, short call at one strike: receives K and the stock's dividends
+    Conversion,
+    /// Short stock and put, long call: owes K and the dividends
+    Reversal,
+}
+
+/// A parity structure entered at executable prices: asks when buying, bids when selling.
+/// The underlying trades at spot on both sides and dividends are the quote's continuous yield.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityTrade {
+    pub structure: ParityStructure,
+    pub expiry: f64,
+    /// (low, high) strikes, equal for conversions and reversals
+    pub strikes: (f64, f64),
+    /// Paid to enter, negative for a credit
+    pub cost: f64,
+    /// Present value of what the structure locks in at the quoted rate
+    pub theoretical: f64,
+    /// Continuously compounded rate the entry price lends at (long box, conversion) or borrows
+    /// at (short box, reversal). Long and short boxes bracket the rate options imply
+    pub implied_rate: f64,
+}
+
+impl ParityTrade {
+    /// Profit today before costs
+    ///
+    /// Formula: theoretical - cost
+    pub fn edge(&self) -> f64 {
+        self.theoretical - self.cost
+    }
+    /// Whether the edge pays more than `costs`, e.g. commissions and borrow on the stock
+    pub fn is_exploitable(&self, costs: f64) -> bool {
+        self.edge() > costs
+    }
+}
+
+fn check_pair(
+    first: &OptionQuote,
+    second: &OptionQuote,
+    types: [OptionType; 2],
+) -> Result<(), PricingError> {
+    if first.option_type != types[0] || second.option_type != types[1] {
+        return Err(PricingError::InvalidInput(format!(
+            "expected a {:?} and a {:?}, got a {:?} and a {:?}",
+            types[0], types[1], first.option_type, second.option_type
+        )));
+    }
+    if (first.expiry - second.expiry).abs() > MATCH_TOLERANCE {
+        return Err(PricingError::InvalidInput(format!(
+            "legs expire at {} and {}",
+            first.expiry, second.expiry
+        )));
+    }
+    Ok(())
+}
+
+/// Long and short box spreads on the strikes of the low and high call-put pairs.
+///
+/// Formula: box = (C_low - C_high) + (P_high - P_low), worth (K_high - K_low) e^(-rT)
+pub fn box_spreads(
+    low: (&OptionQuote, &OptionQuote),
+    high: (&OptionQuote, &OptionQuote),
+) -> Result<[ParityTrade; 2], PricingError> {
+    let (low_call, low_put) = low;
+    let (high_call, high_put) = high;
+    for (call, put) in [low, high] {
+        check_pair(call, put, [OptionType::Call, OptionType::Put])?;
+        if (call.strike - put.strike).abs() > MATCH_TOLERANCE {
+            return Err(PricingError::InvalidInput(format!(
+                "call and put strikes {} and {} differ",
+                call.strike, put.strike
+            )));
+        }
+    }
+    check_pair(low_call, high_call, [OptionType::Call, OptionType::Call])?;
+    let width = high_call.strike - low_call.strike;
+    if width <= 0.0 {
+        return Err(PricingError::InvalidInput(format!(
+            "box strikes {} and {} are not increasing",
+            low_call.strike, high_call.strike
+        )));
+    }
+    let t = low_call.expiry;
+    let present = width * exp(-low_call.risk_free_rate * t);
+    let bought = low_call.ask - high_call.bid + high_put.ask - low_put.bid;
+    let sold = low_call.bid - high_call.ask + high_put.bid - low_put.ask;
+    let trade = |structure, cost: f64, theoretical, financed: f64| ParityTrade {
+        structure,
+        expiry: t,
+        strikes: (low_call.strike, high_call.strike),
+        cost,
+        theoretical,
+        implied_rate: -ln(financed / width) / t,
+    };
+    Ok([
+        trade(ParityStructure::LongBox, bought, present, bought),
+        trade(ParityStructure::ShortBox, -sold, -present, sold),
+    ])
+}
+
+/// Conversion and reversal on a call and put of one strike and expiry.
+///
+/// Formula: S e^(-qT) - C + P = K e^(-rT)
+pub fn conversions(
+    call: &OptionQuote,
+    put: &OptionQuote,
+) -> Result<[ParityTrade; 2], PricingError> {
+    check_pair(call, put, [OptionType::Call, OptionType::Put])?;
+    if (call.strike - put.strike).abs() > MATCH_TOLERANCE {
+        return Err(PricingError::InvalidInput(format!(
+            "call and put strikes {} and {} differ",
+            call.strike, put.strike
+        )));
+    }
+    let (t, strike, spot) = (call.expiry, call.strike, call.spot);
+    let dividends = spot * (1.0 - exp(-call.dividend_yield.unwrap_or(0.0) * t));
+    let present = strike * exp(-call.risk_free_rate * t) + dividends;
+    let conversion = spot - call.bid + put.ask;
+    let reversal = -spot + call.ask - put.bid;
+    let trade = |structure, cost: f64, theoretical, financed: f64| ParityTrade {
+        structure,
+        expiry: t,
+        strikes: (strike, strike),
+        cost,
+        theoretical,
+        implied_rate: -ln(financed / strike) / t,
+    };
+    Ok([
+        trade(
+            ParityStructure::Conversion,
+            conversion,
+            present,
+            conversion - dividends,
+        ),
+        trade(
+            ParityStructure::Reversal,
+            reversal,
+            -present,
+            -reversal - dividends,
+        ),
+    ])
+}
+
+/// Every box, conversion and reversal in a chain whose edge exceeds `costs`, most profitable
+/// first. Boxes are formed on every pair of strikes quoted on both sides within an expiry
+pub fn parity_arbitrage(quotes: &[OptionQuote], costs: f64) -> Vec<ParityTrade> {
+    let mut pairs: Vec<(&OptionQuote, &OptionQuote)> = quotes
+        .iter()
+        .filter(|quote| quote.option_type == OptionType::Call)
+        .filter_map(|call| {
+            let put = quotes.iter().find(|quote| {
+                quote.option_type == OptionType::Put
+                    && (quote.strike - call.strike).abs() < MATCH_TOLERANCE
+                    && (quote.expiry - call.expiry).abs() < MATCH_TOLERANCE
+            })?;
+            Some((call, put))
+        })
+        .collect();
+    pairs.sort_by(|a, b| {
+        a.0.expiry
+            .total_cmp(&b.0.expiry)
+            .then(a.0.strike.total_cmp(&b.0.strike))
+    });
+    let mut trades = Vec::new();
+    for expiry in pairs.chunk_by(|a, b| (b.0.expiry - a.0.expiry).abs() < MATCH_TOLERANCE) {
+        for (i, &(call, put)) in expiry.iter().enumerate() {
+            trades.extend(conversions(call, put).into_iter().flatten());
+            for &high in &expiry[i + 1..] {
+                trades.extend(box_spreads((call, put), high).into_iter().flatten());
+            }
+        }
+    }
+    trades.retain(|trade| trade.is_exploitable(costs));
+    trades.sort_by(|a, b| b.edge().total_cmp(&a.edge()));
+    trades
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_break_evens() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let straddle = Strategy::straddle(call, put);
+        let premium = straddle.premium();
+        let points = straddle.break_evens(premium);
+        assert_eq!(points.len(), 2);
+        assert!((points[0] - (100.0 - premium)).abs() < 1e-10);
+        assert!((points[1] - (100.0 + premium)).abs() < 1e-10);
+
+        // bull call spread breaks even between its strikes
+        let spread =
+            Strategy::vertical(call, Options::new_call(110.0, 100.0, 0.2, 0.05, 0.5, None));
+        let debit = spread.premium();
+        assert_eq!(spread.break_evens(debit), vec![100.0 + debit]);
+        assert_eq!(call.break_even(6.0), 106.0);
+        assert_eq!(put.break_even(6.0), 94.0);
+    }
+
+    #[test]
+    fn test_payoff_curves() {
+        let put = Options::new_put(100.0, 100.0, 0.2, 0.05, 0.5, None);
+        let curve = put.pnl_curve(80.0..=120.0, 5, 4.0);
+        assert_eq!(
+            curve,
+            vec![
+                (80.0, 16.0),
+                (90.0, 6.0),
+                (100.0, -4.0),
+                (110.0, -4.0),
+                (120.0, -4.0)
+            ]
+        );
+        let strategy = Strategy::new(vec![Leg {
+            option: put,
+            quantity: -2.0,
+        }]);
+        assert_eq!(strategy.payoff_curve(80.0..=120.0, 3)[0], (80.0, -40.0));
+    }
+
+    fn chain(spread: f64) -> Vec<OptionQuote> {
+        let mut quotes = Vec::new();
+        for strike in [90.0, 100.0, 110.0] {
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let option =
+                    Options::new_with_type(option_type, strike, 100.0, 0.25, 0.04, 0.5, Some(0.02));
+                let mid = option.bs_pricing();
+                quotes.push(OptionQuote::new(
+                    option_type,
+                    strike,
+                    0.5,
+                    mid - 0.5 * spread,
+                    mid + 0.5 * spread,
+                    100.0,
+                    0.04,
+                    Some(0.02),
+                ));
+            }
+        }
+        quotes
+    }
+
+    #[test]
+    fn test_box_and_conversion_rates() {
+        let quotes = chain(0.0);
+        let [long, short] =
+            box_spreads((&quotes[0], &quotes[1]), (&quotes[4], &quotes[5])).unwrap();
+        assert_eq!(long.strikes, (90.0, 110.0));
+        assert!((long.implied_rate - 0.04).abs() < 1e-10 && long.edge().abs() < 1e-10);
+        assert!((short.implied_rate - 0.04).abs() < 1e-10 && short.edge().abs() < 1e-10);
+        let [conversion, reversal] = conversions(&quotes[2], &quotes[3]).unwrap();
+        assert!((conversion.implied_rate - 0.04).abs() < 1e-10);
+        assert!(conversion.edge().abs() < 1e-10 && reversal.edge().abs() < 1e-10);
+        // wide markets lend below and borrow above the fair rate, at a loss both ways
+        let wide = chain(0.2);
+        let [long, short] = box_spreads((&wide[0], &wide[1]), (&wide[2], &wide[3])).unwrap();
+        assert!(long.implied_rate < 0.04 && short.implied_rate > 0.04);
+        assert!(long.edge() < 0.0 && short.edge() < 0.0);
+        assert!(conversions(&wide[3], &wide[2]).is_err());
+        assert!(box_spreads((&wide[2], &wide[3]), (&wide[0], &wide[1])).is_err());
+    }
+
+    #[test]
+    fn test_parity_arbitrage_flags_mispricing() {
+        let mut quotes = chain(0.1);
+        assert!(parity_arbitrage(&quotes, 0.0).is_empty());
+        // the 100 put offered half a point cheap makes a conversion and both boxes buying it pay
+        quotes[3].bid -= 0.5;
+        quotes[3].ask -= 0.5;
+        let trades = parity_arbitrage(&quotes, 0.05);
+        assert_eq!(trades[0].structure, ParityStructure::Conversion);
+        assert!((trades[0].edge() - 0.4).abs() < 1e-9);
+        assert!(trades[0].implied_rate > 0.04);
+        let boxes: Vec<_> = trades[1..]
+            .iter()
+            .map(|t| (t.structure, t.strikes))
+            .collect();
+        assert_eq!(
+            boxes,
+            [
+                (ParityStructure::ShortBox, (100.0, 110.0)),
+                (ParityStructure::LongBox, (90.0, 100.0))
+            ]
+        );
+        // but not once costs eat the edge
+        assert!(parity_arbitrage(&quotes, 0.5).is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+// Structured deposits and participation notes, valued as the sum of their parts: a zero-coupon
+// bond returning the protected share of notional, funded at the issuer's spread, plus option legs
+// on the underlying's performance. The bond's discount leaves a budget for the options, which
+// fixes the participation a note issued at par can offer.
+use crate::Options;
+use crate::display::Percent;
+use crate::error::PricingError;
+use crate::lattice::ExerciseStyle;
+use crate::lattice::barrier::{Barrier, BarrierEngine, BarrierType};
+use crate::lattice::trinomial::TrinomialTree;
+use crate::math::float::exp;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Option on the underlying's performance S(T) / S(0), strikes and levels as fractions of the
+/// initial level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum NoteOption {
+    /// Formula: max(S(T)/S(0) - k, 0)
+    Call { strike: f64 },
+    /// Upside capped at `cap`, a call spread
+    ///
+    /// Formula: max(min(S(T)/S(0), c) - k, 0)
+    CappedCall { strike: f64, cap: f64 },
+    /// Call extinguished the first time the underlying reaches `barrier`, a shark fin
+    KnockOutCall { strike: f64, barrier: f64 },
+}
+
+/// An option leg paying `participation` times its payoff on the note's notional.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NoteLeg {
+    pub option: NoteOption,
+    pub participation: f64,
+}
+
+/// A note paying the protected share of notional at maturity plus its option legs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StructuredNote {
+    pub notional: f64,
+    /// Years to maturity
+    pub maturity: f64,
+    /// Share of notional repaid at maturity whatever the underlying does
+    pub protection: f64,
+    /// Issuer funding spread over the risk-free rate, discounting the protected amount
+    pub funding_spread: f64,
+    pub legs: Vec<NoteLeg>,
+}
+
+/// Value of a note and of each of its components, the bond first, in the order they were added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteValuation {
+    pub value: f64,
+    pub components: Vec<(String, f64)>,
+}
+
+impl StructuredNote {
+    /// Fully protected note without option legs, funded at the risk-free rate
+    pub fn new(notional: f64, maturity: f64) -> Self {
+        StructuredNote {
+            notional,
+            maturity,
+            protection: 1.0,
+            funding_spread: 0.0,
+            legs: Vec::new(),
+        }
+    }
+    pub fn with_protection(self, protection: f64) -> Self {
+        StructuredNote { protection, ..self }
+    }
+    pub fn with_funding_spread(self, funding_spread: f64) -> Self {
+        StructuredNote {
+            funding_spread,
+            ..self
+        }
+    }
+    pub fn with_leg(mut self, option: NoteOption, participation: f64) -> Self {
+        self.legs.push(NoteLeg {
+            option,
+            participation,
+        });
+        self
+    }
+    pub fn with_call(self, strike: f64, participation: f64) -> Self {
+        self.with_leg(NoteOption::Call { strike }, participation)
+    }
+    pub fn with_capped_call(self, strike: f64, cap: f64, participation: f64) -> Self {
+        self.with_leg(NoteOption::CappedCall { strike, cap }, participation)
+    }
+    pub fn with_knock_out_call(self, strike: f64, barrier: f64, participation: f64) -> Self {
+        self.with_leg(NoteOption::KnockOutCall { strike, barrier }, participation)
+    }
+    /// Present value of the protected amount
+    ///
+    /// Formula: N p e^(-(r + s)T)
+    pub fn bond_value(&self, rate: f64) -> f64 {
+        self.notional * self.protection * exp(-(rate + self.funding_spread) * self.maturity)
+    }
+    /// Values the note at the spot, volatility, rate and dividend yield of `market`, whose
+    /// strike, type and maturity are ignored. Vanilla legs are priced with Black-Scholes and
+    /// knock-outs on a barrier-aligned trinomial tree
+    pub fn price(&self, market: &Options) -> Result<NoteValuation, PricingError> {
+        let mut components = vec![(
+            format!("ZERO-COUPON {}", Percent(self.protection)),
+            self.bond_value(market.risk_free_rate()),
+        )];
+        for leg in &self.legs {
+            let value = leg.participation * self.unit_value(&leg.option, market)?;
+            components.push((leg.to_string(), value));
+        }
+        Ok(NoteValuation {
+            value: components.iter().map(|(_, value)| value).sum(),
+            components,
+        })
+    }
+    /// Participation, applied to every leg in place of its own, that prices the note at par
+    /// given its protection and funding spread. A wider funding spread cheapens the bond and
+    /// buys more participation. Fails when the bond alone costs par or the legs are worthless
+    pub fn implied_participation(&self, market: &Options) -> Result<f64, PricingError> {
+        let budget = self.notional - self.bond_value(market.risk_free_rate());
+        let mut options = 0.0;
+        for leg in &self.legs {
+            options += self.unit_value(&leg.option, market)?;
+        }
+        if !(budget > 0.0 && options > 0.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "option budget {} buys no participation in legs worth {}",
+                budget, options
+            )));
+        }
+        Ok(budget / options)
+    }
+    // Value of one leg at full participation on the note's notional
+    fn unit_value(&self, option: &NoteOption, market: &Options) -> Result<f64, PricingError> {
+        let spot = market.spot_price();
+        let call = |strike: f64| {
+            Options::new_call(
+                strike * spot,
+                spot,
+                market.volatility(),
+                market.risk_free_rate(),
+                self.maturity,
+                market.dividend_yield(),
+            )
+        };
+        // performance options are calls on the spot scaled by N / S(0)
+        let scale = self.notional / spot;
+        let value = match *option {
+            NoteOption::Call { strike } => call(strike).bs_pricing(),
+            NoteOption::CappedCall { strike, cap } => {
+                call(strike).bs_pricing() - call(cap.max(strike)).bs_pricing()
+            }
+            NoteOption::KnockOutCall { strike, barrier } => TrinomialTree::default()
+                .barrier_price(
+                    &call(strike),
+                    &Barrier::new(BarrierType::UpAndOut, barrier * spot),
+                    ExerciseStyle::European,
+                )?,
+        };
+        Ok(scale * value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participation_note() {
+        let market = Options::new_call(100.0, 100.0, 0.2, 0.04, 1.0, Some(0.01));
+        let note = StructuredNote::new(1000.0, 3.0).with_call(1.0, 1.0);
+        let participation = note.implied_participation(&market).unwrap();
+        let fair = StructuredNote::new(1000.0, 3.0).with_call(1.0, participation);
+        let valuation = fair.price(&market).unwrap();
+        assert!((valuation.value - 1000.0).abs() < 1e-9);
+        let bond = 1000.0 * (-0.12f64).exp();
+        assert_eq!(valuation.components[0].0, "ZERO-COUPON 100%");
+        assert!((valuation.components[0].1 - bond).abs() < 1e-9);
+        // the call leg spends the rest of par
+        assert!((valuation.components[1].1 - (1000.0 - bond)).abs() < 1e-9);
+
+        // issuer funding and lower protection both enlarge the option budget
+        let funded = note.clone().with_funding_spread(0.01);
+        let protected_95 = note.clone().with_protection(0.95);
+        assert!(funded.implied_participation(&market).unwrap() > participation);
+        assert!(protected_95.implied_participation(&market).unwrap() > participation);
+        // a fully protected note funded below zero rates leaves no budget
+        let negative = Options::new_call(100.0, 100.0, 0.2, -0.01, 1.0, None);
+        assert!(note.implied_participation(&negative).is_err());
+    }
+
+    #[test]
+    fn test_capped_and_knock_out_legs() {
+        let market = Options::new_call(100.0, 100.0, 0.2, 0.03, 1.0, None);
+        let note = StructuredNote::new(100.0, 1.0)
+            .with_protection(0.9)
+            .with_call(1.0, 1.0)
+            .with_capped_call(1.0, 1.3, 1.0)
+            .with_knock_out_call(1.0, 1.3, 1.0);
+        let valuation = note.price(&market).unwrap();
+        let names: Vec<&str> = valuation
+            .components
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "ZERO-COUPON 90%",
+                "100% x CALL 100%",
+                "100% x CALL 100%, cap 130%",
+                "100% x CALL 100%, up-and-out 130%"
+            ]
+        );
+        let [call, capped, knock_out] = [1, 2, 3].map(|i| valuation.components[i].1);
+        assert!((call - market.bs_pricing()).abs() < 1e-9);
+        // the cap gives up the upside beyond it; the knock-out also loses everything below it
+        assert!(knock_out < capped && capped < call && knock_out > 0.0);
+        let total: f64 = valuation.components.iter().map(|(_, v)| v).sum();
+        assert_eq!(valuation.value, total);
+    }
+}
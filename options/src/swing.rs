@@ -0,0 +1,184 @@
+// Swing options, the flexible-volume contracts of gas and power supply: on each date of a
+// schedule the holder may take a fixed volume at the strike, with the total taken over the
+// contract held between a minimum and a maximum. The exercise decision depends on how many
+// rights remain, so the contract is rolled back on a binomial tree carrying one value per
+// count of rights used, exercising where today's payoff plus the value with one right fewer
+// beats holding on.
+use crate::OptionType;
+use crate::error::PricingError;
+use crate::market::MarketQuote;
+use crate::math::float::{exp, sqrt};
+
+// Slack when turning volumes into whole numbers of exercises
+const VOLUME_TOLERANCE: f64 = 1e-9;
+
+/// A swing contract on one underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwingOption {
+    /// Call to buy the volume at the strike, put to sell it
+    pub option_type: OptionType,
+    pub strike: f64,
+    /// Exercise dates in years, increasing
+    pub exercise_dates: Vec<f64>,
+    /// Volume taken on each exercise
+    pub volume: f64,
+    /// Least total volume the holder must take; a shortfall is not allowed
+    pub min_volume: f64,
+    pub max_volume: f64,
+}
+
+impl SwingOption {
+    /// Fewest and most exercises the volume limits allow, the minimum rounded up and the maximum
+    /// down to whole exercises
+    pub fn exercise_limits(&self) -> Result<(usize, usize), PricingError> {
+        let volume_ok = self.volume > 0.0 && self.volume.is_finite();
+        if !volume_ok || self.min_volume < 0.0 || self.max_volume < self.min_volume {
+            return Err(PricingError::InvalidInput(format!(
+                "swing volume {} must be positive with 0 <= min {} <= max {}",
+                self.volume, self.min_volume, self.max_volume
+            )));
+        }
+        let dates = self.exercise_dates.len();
+        let min = (self.min_volume / self.volume - VOLUME_TOLERANCE).ceil() as usize;
+        let max = ((self.max_volume / self.volume + VOLUME_TOLERANCE).floor() as usize).min(dates);
+        if min > max {
+            return Err(PricingError::InvalidInput(format!(
+                "minimum volume {} cannot be taken in {} exercises of {}",
+                self.min_volume, max, self.volume
+            )));
+        }
+        Ok((min, max))
+    }
+    /// Value on a binomial tree of `steps` steps to the last exercise date, each date moved to
+    /// its nearest step. `carry_yield` is the underlying's dividend or convenience yield. Fails
+    /// on dates that are not positive and increasing, two dates sharing a step, or volume
+    /// limits the schedule cannot meet
+    pub fn price(
+        &self,
+        market: &MarketQuote,
+        carry_yield: f64,
+        steps: usize,
+    ) -> Result<f64, PricingError> {
+        let (min_rights, max_rights) = self.exercise_limits()?;
+        let increasing = self.exercise_dates.windows(2).all(|w| w[0] < w[1]);
+        let Some(&maturity) = self.exercise_dates.last() else {
+            return Err(PricingError::InvalidInput(
+                "swing contract without exercise dates".to_string(),
+            ));
+        };
+        if self.exercise_dates[0] <= 0.0 || !increasing {
+            return Err(PricingError::InvalidInput(
+                "swing exercise dates must be positive and increasing".to_string(),
+            ));
+        }
+        let n = steps.max(1);
+        let dt = maturity / n as f64;
+        let mut exercise_step = vec![false; n + 1];
+        for &t in &self.exercise_dates {
+            let step = ((t / dt).round() as usize).min(n);
+            if step == 0 || exercise_step[step] {
+                return Err(PricingError::InvalidInput(format!(
+                    "swing exercise date {} shares a tree step; use more than {} steps",
+                    t, n
+                )));
+            }
+            exercise_step[step] = true;
+        }
+
+        let phi = match self.option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let (r, jump) = (market.risk_free_rate, market.volatility * sqrt(dt));
+        let drift = (r - carry_yield) * dt;
+        let (up, down) = (exp(drift + jump), exp(drift - jump));
+        let p = (exp(drift) - down) / (up - down);
+        let discount = exp(-r * dt);
+        let node = |step: usize, j: usize| {
+            market.spot * exp(drift * step as f64 + jump * (2.0 * j as f64 - step as f64))
+        };
+
+        // values[k][j]: k rights used so far, j up moves; finishing short of the minimum is
+        // ruled out
+        let mut values: Vec<Vec<f64>> = (0..=max_rights)
+            .map(|k| {
+                let terminal = if k >= min_rights {
+                    0.0
+                } else {
+                    f64::NEG_INFINITY
+                };
+                vec![terminal; n + 1]
+            })
+            .collect();
+        for step in (0..=n).rev() {
+            if step < n {
+                for row in values.iter_mut() {
+                    for j in 0..=step {
+                        row[j] = discount * (p * row[j + 1] + (1.0 - p) * row[j]);
+                    }
+                    row.truncate(step + 1);
+                }
+            }
+            if exercise_step[step] {
+                for k in 0..max_rights {
+                    let (held, fewer) = values.split_at_mut(k + 1);
+                    for (j, value) in held[k].iter_mut().enumerate() {
+                        let payoff = self.volume * phi * (node(step, j) - self.strike);
+                        *value = value.max(payoff + fewer[0][j]);
+                    }
+                }
+            }
+        }
+        Ok(values[0][0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    #[test]
+    fn test_swing_limits() {
+        let market = MarketQuote {
+            spot: 30.0,
+            volatility: 0.5,
+            risk_free_rate: 0.03,
+        };
+        let monthly: Vec<f64> = (1..=12).map(|m| m as f64 / 12.0).collect();
+        let swing = |min_volume: f64, max_volume: f64| SwingOption {
+            option_type: OptionType::Call,
+            strike: 32.0,
+            exercise_dates: monthly.clone(),
+            volume: 10.0,
+            min_volume,
+            max_volume,
+        };
+        let europeans: Vec<f64> = monthly
+            .iter()
+            .map(|&t| 10.0 * Options::new_call(32.0, 30.0, 0.5, 0.03, t, None).bs_pricing())
+            .collect();
+        // unconstrained, every right is a separate European
+        let strip = swing(0.0, 120.0).price(&market, 0.0, 1200).unwrap();
+        let total: f64 = europeans.iter().sum();
+        assert!((strip - total).abs() < 0.01 * total);
+        // a single right is a Bermudan, worth at least the longest European
+        let one = swing(0.0, 10.0).price(&market, 0.0, 1200).unwrap();
+        assert!(one >= europeans[11] && one < strip);
+        let four = swing(0.0, 40.0).price(&market, 0.0, 1200).unwrap();
+        assert!(one < four && four < strip);
+        // taking the full volume every month is a strip of forwards
+        let forced = swing(120.0, 120.0).price(&market, 0.0, 1200).unwrap();
+        let forwards: f64 = monthly
+            .iter()
+            .map(|&t| 10.0 * (30.0 - 32.0 * (-0.03 * t).exp()))
+            .sum();
+        assert!((forced - forwards).abs() < 1e-6 * forwards.abs());
+        // a minimum take costs the holder optionality
+        assert!(swing(60.0, 120.0).price(&market, 0.0, 1200).unwrap() < strip);
+
+        assert!(swing(130.0, 130.0).price(&market, 0.0, 1200).is_err());
+        assert!(swing(50.0, 40.0).price(&market, 0.0, 1200).is_err());
+        assert!(swing(0.0, 40.0).price(&market, 0.0, 6).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+// Serializable product descriptions, used by the fixture-driven regression suite.
+use crate::calendar::{Date, DateTime, TradingHours};
+use crate::error::PricingError;
+use crate::exotics::ConvertibleBond;
+use crate::lattice::ExerciseStyle;
+use crate::lattice::binomial::BinomialTree;
+use crate::settlement::Settlement;
+use crate::{OptionType, Options};
+use serde::{Deserialize, Serialize};
+
+/// Contract terms and market inputs of one product, tagged by `product`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "product", rename_all = "snake_case")]
+pub enum Termsheet {
+    Vanilla {
+        option_type: OptionType,
+        strike: f64,
+        spot: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        /// Time to expiry in years
+        expiry: f64,
+        #[serde(default)]
+        dividend_yield: Option<f64>,
+        #[serde(default = "european")]
+        exercise: ExerciseStyle,
+        /// Expiry-day settlement terms, PM physical unless given
+        #[serde(default)]
+        settlement: Settlement,
+    },
+    ConvertibleBond(ConvertibleBond),
+}
+
+fn european() -> ExerciseStyle {
+    ExerciseStyle::European
+}
+
+impl Termsheet {
+    /// The underlying vanilla contract, if the product is one
+    pub fn option(&self) -> Option<Options> {
+        match *self {
+            Termsheet::Vanilla {
+                option_type,
+                strike,
+                spot,
+                volatility,
+                risk_free_rate,
+                expiry,
+                dividend_yield,
+                ..
+            } => Some(Options::new_with_type(
+                option_type,
+                strike,
+                spot,
+                volatility,
+                risk_free_rate,
+                expiry,
+                dividend_yield,
+            )),
+            Termsheet::ConvertibleBond(_) => None,
+        }
+    }
+    /// Expiry-day settlement terms of a vanilla
+    pub fn settlement(&self) -> Option<Settlement> {
+        match *self {
+            Termsheet::Vanilla { settlement, .. } => Some(settlement),
+            Termsheet::ConvertibleBond(_) => None,
+        }
+    }
+    /// The vanilla with its expiry reset to the trading-time years from `now` in
+    /// exchange-local time to its settlement print on `expiry`, so an AM contract loses the
+    /// expiry-day session a PM one keeps. Fails once settled or for a product without
+    /// settlement terms
+    pub fn with_expiry_date(
+        self,
+        hours: &TradingHours,
+        now: DateTime,
+        expiry: Date,
+    ) -> Result<Self, PricingError> {
+        match self {
+            Termsheet::Vanilla {
+                option_type,
+                strike,
+                spot,
+                volatility,
+                risk_free_rate,
+                dividend_yield,
+                exercise,
+                settlement,
+                ..
+            } => Ok(Termsheet::Vanilla {
+                option_type,
+                strike,
+                spot,
+                volatility,
+                risk_free_rate,
+                expiry: settlement.time_to_expiry(hours, now, expiry)?,
+                dividend_yield,
+                exercise,
+                settlement,
+            }),
+            Termsheet::ConvertibleBond(_) => Err(PricingError::InvalidInput(
+                "convertible bond termsheets carry no settlement terms".to_string(),
+            )),
+        }
+    }
+    pub fn price(&self) -> f64 {
+        match self {
+            Termsheet::Vanilla { exercise, .. } => {
+                let option = self.option().unwrap();
+                match exercise {
+                    ExerciseStyle::European => option.bs_pricing(),
+                    ExerciseStyle::American => BinomialTree::default().american_price(&option),
+                }
+            }
+            Termsheet::ConvertibleBond(bond) => bond.bs_pricing(),
+        }
+    }
+    /// Named measure of the product: `price`, or a Black-Scholes Greek of a European vanilla
+    pub fn measure(&self, name: &str) -> Option<f64> {
+        if name == "price" {
+            return Some(self.price());
+        }
+        let Termsheet::Vanilla {
+            exercise: ExerciseStyle::European,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let option = self.option()?;
+        match name {
+            "delta" => Some(option.delta()),
+            "gamma" => Some(option.gamma()),
+            "vega" => Some(option.vega()),
+            "theta" => Some(option.theta()),
+            "rho" => Some(option.rho()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vanilla_measures() {
+        let termsheet: Termsheet = serde_json::from_str(
+            r#"{"product": "vanilla", "option_type": "call", "strike": 100, "spot": 100,
+                "volatility": 0.2, "risk_free_rate": 0.05, "expiry": 1}"#,
+        )
+        .unwrap();
+        assert!((termsheet.price() - 10.450584).abs() < 1e-6);
+        assert!(termsheet.measure("delta").is_some());
+        assert!(termsheet.measure("vanna").is_none());
+        assert_eq!(termsheet.settlement(), Some(Settlement::pm_physical()));
+
+        let index: Termsheet = serde_json::from_str(
+            r#"{"product": "vanilla", "option_type": "put", "strike": 5000, "spot": 5000,
+                "volatility": 0.15, "risk_free_rate": 0.05, "expiry": 0.01,
+                "settlement": {"settlement_type": "cash", "session": "am",
+                               "exercise_cutoff": null}}"#,
+        )
+        .unwrap();
+        assert_eq!(index.settlement(), Some(Settlement::am_cash()));
+        // the day before an AM expiry only the rest of today's session is left
+        let hours = TradingHours::default();
+        let friday = Date::new(2025, 6, 20).unwrap();
+        let now = DateTime::new(Date::new(2025, 6, 19).unwrap(), "15:00".parse().unwrap());
+        let dated = index.with_expiry_date(&hours, now, friday).unwrap();
+        let Termsheet::Vanilla { expiry, .. } = dated else {
+            panic!("vanilla termsheet");
+        };
+        assert!((expiry - 60.0 / (390.0 * 252.0)).abs() < 1e-15);
+        assert!(dated.price() < index.price());
+    }
+
+    #[test]
+    fn test_settlement_defaults_agree() {
+        let empty: Settlement = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, Settlement::default());
+        let cash: Settlement = serde_json::from_str(r#"{"settlement_type": "cash"}"#).unwrap();
+        assert_eq!(
+            cash.exercise_cutoff,
+            Settlement::pm_physical().exercise_cutoff
+        );
+    }
+}
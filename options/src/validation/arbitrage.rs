@@ -0,0 +1,366 @@
+// Static arbitrage in option quotes. Undiscounted call prices must fall with the strike, by no
+// more than the strike step (vertical), be convex in the strike (butterfly), and rise with the
+// expiry at a fixed forward moneyness (calendar). Puts enter through parity, so a chain is checked
+// on one call curve per expiry. Strike violations are repaired by projecting each expiry's curve
+// onto the arbitrage-free set, moving wide quotes more than tight ones.
+use crate::OptionType;
+use crate::math::float::exp;
+use crate::quote::OptionQuote;
+#[cfg(feature = "calibration")]
+use crate::{Options, vol::surface::VolSurface};
+
+// Expiries, in years, and strikes this close are treated as equal
+const MATCH_TOLERANCE: f64 = 1e-9;
+// Violations smaller than this, in price, are rounding rather than arbitrage
+const PRICE_TOLERANCE: f64 = 1e-10;
+// Floor on a quote's bid-ask spread, so mid-only quotes weigh equally
+const MIN_SPREAD: f64 = 1e-8;
+// The projection stops once a sweep moves no price by more than this
+const PROJECTION_TOLERANCE: f64 = 1e-13;
+const MAX_SWEEPS: usize = 100_000;
+
+/// Which no-arbitrage bound a set of quotes breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArbitrageKind {
+    /// Call prices rise with the strike, or fall faster than the strike step
+    Vertical,
+    /// Call prices are not convex in the strike
+    Butterfly,
+    /// A later expiry is cheaper than an earlier one at the same forward moneyness
+    Calendar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub kind: ArbitrageKind,
+    /// Expiry of the offending quotes, the later one for calendar violations
+    pub expiry: f64,
+    /// Strikes spanning the violation: two for vertical, three for butterfly, one for calendar
+    pub strikes: Vec<f64>,
+    /// Size of the violation in undiscounted price, the profit of the arbitrage per unit
+    pub amount: f64,
+}
+
+/// Violations found in a set of quotes, with the quotes repaired to remove the strike ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitrageReport {
+    pub violations: Vec<Violation>,
+    /// The input quotes in their order, bid and ask shifted by the repair of their strike.
+    /// Calendar violations are reported but not repaired
+    pub repaired: Vec<OptionQuote>,
+}
+
+impl ArbitrageReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// One expiry's undiscounted call prices, sorted by strike
+struct CallCurve {
+    expiry: f64,
+    forward: f64,
+    strikes: Vec<f64>,
+    calls: Vec<f64>,
+}
+
+/// Scans quotes of any number of expiries for vertical, butterfly and calendar arbitrage.
+///
+/// Each strike is checked on its out-of-the-money side when both are quoted. The repair is the
+/// spread-weighted least squares projection of each expiry's call curve onto decreasing convex
+/// curves with slope at least -1, applied to every quote at the strike
+pub fn scan_quotes(quotes: &[OptionQuote]) -> ArbitrageReport {
+    let mut order: Vec<usize> = (0..quotes.len()).collect();
+    order.sort_by(|&a, &b| {
+        quotes[a]
+            .expiry
+            .total_cmp(&quotes[b].expiry)
+            .then(quotes[a].strike.total_cmp(&quotes[b].strike))
+    });
+    let mut violations = Vec::new();
+    let mut repaired = quotes.to_vec();
+    let mut previous: Option<CallCurve> = None;
+    for expiry in order.chunk_by(|&a, &b| quotes[b].expiry - quotes[a].expiry < MATCH_TOLERANCE) {
+        let first = &quotes[expiry[0]];
+        let forward = first.forward();
+        let discount = exp(-first.risk_free_rate * first.expiry);
+        let mut curve = CallCurve {
+            expiry: first.expiry,
+            forward,
+            strikes: Vec::new(),
+            calls: Vec::new(),
+        };
+        let mut weights = Vec::new();
+        let mut members = Vec::new();
+        for strike in
+            expiry.chunk_by(|&a, &b| quotes[b].strike - quotes[a].strike < MATCH_TOLERANCE)
+        {
+            let preferred = if quotes[strike[0]].strike < forward {
+                OptionType::Put
+            } else {
+                OptionType::Call
+            };
+            let quote = strike
+                .iter()
+                .map(|&i| &quotes[i])
+                .find(|quote| quote.option_type == preferred)
+                .unwrap_or(&quotes[strike[0]]);
+            let call = match quote.option_type {
+                OptionType::Call => quote.mid() / discount,
+                // C = P + D (F - K)
+                OptionType::Put => quote.mid() / discount + forward - quote.strike,
+            };
+            let spread = (quote.spread() / discount).max(MIN_SPREAD);
+            curve.strikes.push(quote.strike);
+            curve.calls.push(call);
+            weights.push(1.0 / (spread * spread));
+            members.push(strike);
+        }
+        check_strikes(&curve, &mut violations);
+        if let Some(earlier) = &previous {
+            check_calendar(earlier, &curve, &mut violations);
+        }
+        let projected = convexity_projection(&curve.strikes, &curve.calls, &weights);
+        for ((strike, call), fixed) in members.iter().zip(&curve.calls).zip(&projected) {
+            let shift = discount * (fixed - call);
+            for &i in strike.iter() {
+                repaired[i].bid += shift;
+                repaired[i].ask += shift;
+            }
+        }
+        previous = Some(curve);
+    }
+    ArbitrageReport {
+        violations,
+        repaired,
+    }
+}
+
+/// Scans a fitted surface on a grid of strikes, given as fractions of each slice's forward.
+///
+/// Every slice is priced on the same forward moneyness, so calendar checks compare like with like
+#[cfg(feature = "calibration")]
+pub fn scan_surface(surface: &VolSurface, relative_strikes: &[f64]) -> Vec<Violation> {
+    let mut relative = relative_strikes.to_vec();
+    relative.sort_by(|a, b| a.total_cmp(b));
+    let mut violations = Vec::new();
+    let mut previous: Option<CallCurve> = None;
+    for slice in surface.slices() {
+        let strikes: Vec<f64> = relative.iter().map(|m| m * slice.forward).collect();
+        // undiscounted Black prices: a spot at the forward with no carry
+        let calls = strikes
+            .iter()
+            .map(|&strike| {
+                let vol = slice.smile.vol(strike).vol;
+                Options::new_call(strike, slice.forward, vol, 0.0, slice.expiry, None).bs_pricing()
+            })
+            .collect();
+        let curve = CallCurve {
+            expiry: slice.expiry,
+            forward: slice.forward,
+            strikes,
+            calls,
+        };
+        check_strikes(&curve, &mut violations);
+        if let Some(earlier) = &previous {
+            check_calendar(earlier, &curve, &mut violations);
+        }
+        previous = Some(curve);
+    }
+    violations
+}
+
+/// Nearest call curve, in `weights`-weighted least squares, that is free of vertical and
+/// butterfly arbitrage: non-negative, decreasing with slope at least -1, and convex.
+///
+/// Dykstra's alternating projections onto the half-space of each bound, which converge to the
+/// exact projection. `strikes` must be increasing
+pub fn convexity_projection(strikes: &[f64], calls: &[f64], weights: &[f64]) -> Vec<f64> {
+    let n = calls.len();
+    // bounds a·c <= b, as the terms of a and b
+    let mut bounds: Vec<(Vec<(usize, f64)>, f64)> = Vec::new();
+    for i in 1..n {
+        bounds.push((vec![(i, 1.0), (i - 1, -1.0)], 0.0));
+        bounds.push((vec![(i - 1, 1.0), (i, -1.0)], strikes[i] - strikes[i - 1]));
+    }
+    for i in 1..n.saturating_sub(1) {
+        let weight = (strikes[i] - strikes[i - 1]) / (strikes[i + 1] - strikes[i - 1]);
+        bounds.push((vec![(i, 1.0), (i - 1, weight - 1.0), (i + 1, -weight)], 0.0));
+    }
+    if n > 0 {
+        bounds.push((vec![(n - 1, -1.0)], 0.0));
+    }
+    let mut fitted = calls.to_vec();
+    let mut multipliers = vec![0.0; bounds.len()];
+    for _ in 0..MAX_SWEEPS {
+        let mut moved: f64 = 0.0;
+        for ((terms, bound), multiplier) in bounds.iter().zip(multipliers.iter_mut()) {
+            // restore this bound's previous correction, then project onto its half-space in the
+            // weighted norm
+            let norm: f64 = terms.iter().map(|&(i, a)| a * a / weights[i]).sum();
+            let value: f64 = terms.iter().map(|&(i, a)| a * fitted[i]).sum::<f64>();
+            let next = (*multiplier + (value - bound) / norm).max(0.0);
+            let step = next - *multiplier;
+            for &(i, a) in terms {
+                let change = step * a / weights[i];
+                fitted[i] -= change;
+                moved = moved.max(change.abs());
+            }
+            *multiplier = next;
+        }
+        if moved < PROJECTION_TOLERANCE {
+            break;
+        }
+    }
+    fitted
+}
+
+fn check_strikes(curve: &CallCurve, violations: &mut Vec<Violation>) {
+    let (strikes, calls) = (&curve.strikes, &curve.calls);
+    for i in 1..strikes.len() {
+        let drop = calls[i - 1] - calls[i];
+        let step = strikes[i] - strikes[i - 1];
+        let amount = (-drop).max(drop - step);
+        if amount > PRICE_TOLERANCE {
+            violations.push(Violation {
+                kind: ArbitrageKind::Vertical,
+                expiry: curve.expiry,
+                strikes: vec![strikes[i - 1], strikes[i]],
+                amount,
+            });
+        }
+    }
+    for i in 1..strikes.len().saturating_sub(1) {
+        let weight = (strikes[i] - strikes[i - 1]) / (strikes[i + 1] - strikes[i - 1]);
+        let chord = (1.0 - weight) * calls[i - 1] + weight * calls[i + 1];
+        if calls[i] - chord > PRICE_TOLERANCE {
+            violations.push(Violation {
+                kind: ArbitrageKind::Butterfly,
+                expiry: curve.expiry,
+                strikes: vec![strikes[i - 1], strikes[i], strikes[i + 1]],
+                amount: calls[i] - chord,
+            });
+        }
+    }
+}
+
+// Compares C / F at each later strike's moneyness K / F with the earlier curve, linearly
+// interpolated between its strikes; strikes outside the earlier range are not compared
+fn check_calendar(earlier: &CallCurve, later: &CallCurve, violations: &mut Vec<Violation>) {
+    let moneyness: Vec<f64> = earlier
+        .strikes
+        .iter()
+        .map(|k| k / earlier.forward)
+        .collect();
+    for (&strike, &call) in later.strikes.iter().zip(&later.calls) {
+        let m = strike / later.forward;
+        let i = moneyness.partition_point(|&x| x < m - MATCH_TOLERANCE);
+        let normalized = |j: usize| earlier.calls[j] / earlier.forward;
+        let bound = match i {
+            _ if i == moneyness.len() => continue,
+            _ if (moneyness[i] - m).abs() <= MATCH_TOLERANCE => normalized(i),
+            0 => continue,
+            _ => {
+                let weight = (m - moneyness[i - 1]) / (moneyness[i] - moneyness[i - 1]);
+                (1.0 - weight) * normalized(i - 1) + weight * normalized(i)
+            }
+        };
+        let amount = (bound - call / later.forward) * later.forward;
+        if amount > PRICE_TOLERANCE {
+            violations.push(Violation {
+                kind: ArbitrageKind::Calendar,
+                expiry: later.expiry,
+                strikes: vec![strike],
+                amount,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+
+    fn chain(expiry: f64, vol: f64) -> Vec<OptionQuote> {
+        [80.0, 90.0, 100.0, 110.0, 120.0]
+            .iter()
+            .flat_map(|&strike| {
+                [OptionType::Call, OptionType::Put].map(|option_type| {
+                    let option = Options::new_with_type(
+                        option_type,
+                        strike,
+                        100.0,
+                        vol,
+                        0.03,
+                        expiry,
+                        Some(0.01),
+                    );
+                    let mid = option.bs_pricing();
+                    OptionQuote::new(
+                        option_type,
+                        strike,
+                        expiry,
+                        mid - 0.05,
+                        mid + 0.05,
+                        100.0,
+                        0.03,
+                        Some(0.01),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_flags_and_repairs_quotes() {
+        let mut quotes = chain(0.5, 0.2);
+        quotes.extend(chain(1.0, 0.2));
+        assert!(scan_quotes(&quotes).is_clean());
+
+        // the 0.5y 110 call bid through the chord of its neighbours, with a wide market
+        quotes[6].bid += 5.0;
+        quotes[6].ask += 6.0;
+        let report = scan_quotes(&quotes);
+        let kinds: Vec<ArbitrageKind> = report.violations.iter().map(|v| v.kind).collect();
+        assert!(kinds.contains(&ArbitrageKind::Butterfly));
+        // the 1.0y 110 call now prices below the 0.5y one
+        assert!(report.violations.iter().any(|v| {
+            v.kind == ArbitrageKind::Calendar && v.expiry == 1.0 && v.strikes == [110.0]
+        }));
+        // the repair moves the wide quote most and clears the strike arbitrage
+        let shift = |i: usize| report.repaired[i].mid() - quotes[i].mid();
+        assert!(shift(6) < -1.0 && shift(4).abs() < 0.5 * shift(6).abs());
+        assert!((shift(7) - shift(6)).abs() < 1e-12);
+        let rescanned = scan_quotes(&report.repaired[..10]);
+        assert!(rescanned.is_clean(), "{:?}", rescanned.violations);
+    }
+
+    #[cfg(feature = "calibration")]
+    #[test]
+    fn test_scan_surface_calendar() {
+        use crate::vol::smile::{Smile, WingExtrapolation};
+        use crate::vol::surface::SurfaceSlice;
+        let slice = |expiry: f64, vol: f64| SurfaceSlice {
+            expiry,
+            forward: 100.0,
+            svi: None,
+            fit: None,
+            smile: Smile::new(
+                expiry,
+                100.0,
+                vec![(90.0, vol), (110.0, vol)],
+                WingExtrapolation::Flat,
+            )
+            .unwrap(),
+        };
+        let grid = [0.8, 0.9, 1.0, 1.1, 1.2];
+        let rising = VolSurface::from_slices(vec![slice(0.5, 0.25), slice(1.0, 0.2)]);
+        assert!(scan_surface(&rising, &grid).is_empty());
+        // total variance falls from 0.045 to 0.04
+        let inverted = VolSurface::from_slices(vec![slice(0.5, 0.3), slice(1.0, 0.2)]);
+        let violations = scan_surface(&inverted, &grid);
+        assert_eq!(violations.len(), grid.len());
+        assert!(violations.iter().all(|v| v.kind == ArbitrageKind::Calendar));
+    }
+}
@@ -0,0 +1,3 @@
+// Checks run on market inputs before they reach pricing or calibration, reporting what is wrong
+// with the data and how it could be repaired rather than failing outright.
+pub mod arbitrage;
@@ -0,0 +1,416 @@
+use crate::error::PricingError;
+#[cfg(feature = "mc")]
+use crate::math::linalg::cholesky;
+use crate::math::linalg::{Matrix, dot, mat_vec};
+use crate::math::normal::Normal;
+use crate::math::sum::compensated_sum;
+use crate::numerics::NumericsConfig;
+use crate::portfolio::{Portfolio, Position};
+#[cfg(feature = "mc")]
+use rand::SeedableRng;
+#[cfg(feature = "mc")]
+use rand::rngs::StdRng;
+
+/// Spot-return risk factors, one per underlying, with the covariance of their returns over the
+/// VaR horizon.
+#[derive(Debug, Clone)]
+pub struct RiskFactors {
+    pub names: Vec<String>,
+    pub covariance: Matrix,
+    returns: Option<Matrix>,
+}
+
+impl RiskFactors {
+    pub fn new(names: Vec<String>, covariance: Matrix) -> Result<Self, PricingError> {
+        if covariance.len() != names.len() || covariance.iter().any(|row| row.len() != names.len())
+        {
+            return Err(PricingError::InvalidInput(
+                "covariance must be square with one row per factor".to_string(),
+            ));
+        }
+        Ok(RiskFactors {
+            names,
+            covariance,
+            returns: None,
+        })
+    }
+    /// Builds factors from a historical series of relative spot returns, one row per observation
+    /// and one column per factor. The series is kept for historical simulation
+    pub fn from_returns(names: Vec<String>, returns: Matrix) -> Result<Self, PricingError> {
+        let n = names.len();
+        if returns.len() < 2 || returns.iter().any(|row| row.len() != n) {
+            return Err(PricingError::InvalidInput(
+                "need at least two observations with one return per factor".to_string(),
+            ));
+        }
+        let obs = returns.len() as f64;
+        let means: Vec<f64> = (0..n)
+            .map(|j| returns.iter().map(|row| row[j]).sum::<f64>() / obs)
+            .collect();
+        let covariance = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        returns
+                            .iter()
+                            .map(|row| (row[i] - means[i]) * (row[j] - means[j]))
+                            .sum::<f64>()
+                            / (obs - 1.0)
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(RiskFactors {
+            names,
+            covariance,
+            returns: Some(returns),
+        })
+    }
+    fn index_of(&self, name: &str) -> Result<usize, PricingError> {
+        self.names
+            .iter()
+            .position(|factor| factor == name)
+            .ok_or_else(|| PricingError::InvalidInput(format!("no risk factor for {}", name)))
+    }
+}
+
+/// Contribution of a single position to portfolio VaR.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionVar {
+    /// Change in VaR per share-equivalent unit of the position, contracts times multiplier
+    pub marginal: f64,
+    /// Share of total VaR allocated to the position; components sum to the total
+    pub component: f64,
+}
+
+/// Value-at-Risk and Expected Shortfall at a confidence level, both reported as positive losses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarReport {
+    pub confidence: f64,
+    pub var: f64,
+    pub expected_shortfall: f64,
+    /// Per-position contributions, option positions in portfolio order followed by stock
+    pub positions: Vec<PositionVar>,
+}
+
+impl Portfolio {
+    // Quantities in the order VaR reports allocate to: options, then stock
+    fn quantities(&self) -> impl Iterator<Item = f64> + '_ {
+        let options = self.positions().iter().map(Position::units);
+        options.chain(self.stocks().iter().map(|stock| stock.quantity))
+    }
+    /// Delta-gamma VaR with a Cornish-Fisher skew correction.
+    ///
+    /// Component VaR is the delta-normal Euler allocation rescaled to the delta-gamma total
+    pub fn parametric_var(
+        &self,
+        factors: &RiskFactors,
+        confidence: f64,
+    ) -> Result<VarReport, PricingError> {
+        self.parametric_var_with(factors, confidence, &NumericsConfig::default())
+    }
+    /// Parametric VaR with the expected shortfall averaged over the tail on
+    /// `numerics.integration_points` Cornish-Fisher quantiles
+    pub fn parametric_var_with(
+        &self,
+        factors: &RiskFactors,
+        confidence: f64,
+        numerics: &NumericsConfig,
+    ) -> Result<VarReport, PricingError> {
+        check_confidence(confidence)?;
+        let n = factors.names.len();
+        let mut dollar_delta = vec![0.0; n];
+        let mut dollar_gamma = vec![0.0; n];
+        let mut position_deltas = Vec::with_capacity(self.positions().len());
+        for position in self.positions() {
+            let f = factors.index_of(&position.underlying)?;
+            let spot = position.option.spot_price();
+            let delta = position.units() * position.option.delta() * spot;
+            dollar_delta[f] += delta;
+            dollar_gamma[f] += position.units() * position.option.gamma() * spot * spot;
+            position_deltas.push((f, delta));
+        }
+        for stock in self.stocks() {
+            let f = factors.index_of(&stock.underlying)?;
+            let delta = stock.quantity * stock.spot;
+            dollar_delta[f] += delta;
+            position_deltas.push((f, delta));
+        }
+        // Moments of P&L = δᵀr + ½ rᵀΓr with Γ diagonal across underlyings
+        let cov = &factors.covariance;
+        let gamma_cov: Matrix = (0..n)
+            .map(|i| (0..n).map(|j| dollar_gamma[i] * cov[i][j]).collect())
+            .collect();
+        let mean = 0.5 * (0..n).map(|i| gamma_cov[i][i]).sum::<f64>();
+        let cov_delta = mat_vec(cov, &dollar_delta);
+        let delta_variance = dot(&dollar_delta, &cov_delta);
+        let trace_sq: f64 = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| gamma_cov[i][j] * gamma_cov[j][i])
+            .sum();
+        let trace_cube: f64 = (0..n)
+            .flat_map(|i| (0..n).flat_map(move |j| (0..n).map(move |k| (i, j, k))))
+            .map(|(i, j, k)| gamma_cov[i][j] * gamma_cov[j][k] * gamma_cov[k][i])
+            .sum();
+        let cov_gamma_cov_delta: f64 = (0..n)
+            .map(|i| cov_delta[i] * dollar_gamma[i] * cov_delta[i])
+            .sum();
+        let variance = delta_variance + 0.5 * trace_sq;
+        let sigma = variance.sqrt();
+        let skew = if sigma > 0.0 {
+            (3.0 * cov_gamma_cov_delta + trace_cube) / (sigma * sigma * sigma)
+        } else {
+            0.0
+        };
+        let std_norm = Normal::new(0.0, 1.0).unwrap();
+        let loss_at = |level: f64| {
+            let z = std_norm.inverse_cdf(1.0 - level);
+            let z_cf = z + (z * z - 1.0) * skew / 6.0;
+            -(mean + sigma * z_cf)
+        };
+        let var = loss_at(confidence);
+        let points = numerics.integration_points.max(1);
+        let expected_shortfall = (0..points)
+            .map(|i| loss_at(confidence + (1.0 - confidence) * (i as f64 + 0.5) / points as f64))
+            .sum::<f64>()
+            / points as f64;
+        let positions = self
+            .quantities()
+            .zip(position_deltas)
+            .map(|(quantity, (f, delta))| {
+                let component = if delta_variance > 0.0 {
+                    var * delta * cov_delta[f] / delta_variance
+                } else {
+                    0.0
+                };
+                PositionVar {
+                    marginal: marginal(component, quantity),
+                    component,
+                }
+            })
+            .collect();
+        Ok(VarReport {
+            confidence,
+            var,
+            expected_shortfall,
+            positions,
+        })
+    }
+    /// Full-revaluation Monte Carlo VaR over correlated normal factor returns.
+    ///
+    /// Components are tail-conditional (Euler ES) allocations rescaled to the VaR total
+    #[cfg(feature = "mc")]
+    pub fn monte_carlo_var(
+        &self,
+        factors: &RiskFactors,
+        confidence: f64,
+        paths: usize,
+        seed: u64,
+    ) -> Result<VarReport, PricingError> {
+        check_confidence(confidence)?;
+        let chol = cholesky(&factors.covariance).ok_or_else(|| {
+            PricingError::InvalidInput("covariance is not positive definite".to_string())
+        })?;
+        let std_norm = Normal::new(0.0, 1.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let scenarios: Vec<Vec<f64>> = (0..paths)
+            .map(|_| {
+                let z: Vec<f64> = (0..factors.names.len())
+                    .map(|_| rand::distributions::Distribution::sample(&std_norm, &mut rng))
+                    .collect();
+                mat_vec(&chol, &z)
+            })
+            .collect();
+        self.simulated_var(factors, &scenarios, confidence)
+    }
+    /// Historical-simulation VaR replaying the return series the factors were built from
+    pub fn historical_var(
+        &self,
+        factors: &RiskFactors,
+        confidence: f64,
+    ) -> Result<VarReport, PricingError> {
+        check_confidence(confidence)?;
+        let returns = factors.returns.as_ref().ok_or_else(|| {
+            PricingError::InvalidInput("risk factors carry no return history".to_string())
+        })?;
+        self.simulated_var(factors, returns, confidence)
+    }
+    fn simulated_var(
+        &self,
+        factors: &RiskFactors,
+        scenarios: &[Vec<f64>],
+        confidence: f64,
+    ) -> Result<VarReport, PricingError> {
+        if scenarios.is_empty() {
+            return Err(PricingError::InvalidInput(
+                "no scenarios to simulate".to_string(),
+            ));
+        }
+        let factor_index: Vec<usize> = self
+            .positions()
+            .iter()
+            .map(|position| factors.index_of(&position.underlying))
+            .collect::<Result<_, _>>()?;
+        let stock_index: Vec<usize> = self
+            .stocks()
+            .iter()
+            .map(|stock| factors.index_of(&stock.underlying))
+            .collect::<Result<_, _>>()?;
+        let base: Vec<f64> = self
+            .positions()
+            .iter()
+            .map(|position| position.option.bs_pricing())
+            .collect();
+        // Loss per scenario and per position under full revaluation
+        let losses: Vec<Vec<f64>> = scenarios
+            .iter()
+            .map(|returns| {
+                self.positions()
+                    .iter()
+                    .zip(&factor_index)
+                    .zip(&base)
+                    .map(|((position, &f), &base_price)| {
+                        let option = position.option;
+                        let shocked = option.with_market(
+                            option.spot_price() * (1.0 + returns[f]).max(0.0),
+                            option.volatility(),
+                            option.risk_free_rate(),
+                        );
+                        -position.units() * (shocked.bs_pricing() - base_price)
+                    })
+                    .chain(
+                        self.stocks()
+                            .iter()
+                            .zip(&stock_index)
+                            .map(|(stock, &f)| -stock.quantity * stock.spot * returns[f].max(-1.0)),
+                    )
+                    .collect()
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..losses.len()).collect();
+        let totals: Vec<f64> = losses
+            .iter()
+            .map(|row| compensated_sum(row.iter().copied()))
+            .collect();
+        order.sort_by(|&a, &b| totals[a].total_cmp(&totals[b]));
+        let cutoff = ((confidence * losses.len() as f64).ceil() as usize)
+            .saturating_sub(1)
+            .min(losses.len() - 1);
+        let var = totals[order[cutoff]];
+        let tail = &order[cutoff..];
+        let tail_size = tail.len() as f64;
+        let expected_shortfall = compensated_sum(tail.iter().map(|&i| totals[i])) / tail_size;
+        let positions = self
+            .quantities()
+            .enumerate()
+            .map(|(p, quantity)| {
+                let tail_loss = compensated_sum(tail.iter().map(|&i| losses[i][p])) / tail_size;
+                let component = if expected_shortfall != 0.0 {
+                    tail_loss * var / expected_shortfall
+                } else {
+                    0.0
+                };
+                PositionVar {
+                    marginal: marginal(component, quantity),
+                    component,
+                }
+            })
+            .collect();
+        Ok(VarReport {
+            confidence,
+            var,
+            expected_shortfall,
+            positions,
+        })
+    }
+}
+
+fn check_confidence(confidence: f64) -> Result<(), PricingError> {
+    if confidence > 0.0 && confidence < 1.0 {
+        Ok(())
+    } else {
+        Err(PricingError::InvalidInput(format!(
+            "confidence {} must lie in (0, 1)",
+            confidence
+        )))
+    }
+}
+
+// Euler allocation: VaR is homogeneous in quantities, so marginal VaR is component / quantity
+fn marginal(component: f64, quantity: f64) -> f64 {
+    if quantity != 0.0 {
+        component / quantity
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Options;
+    use crate::portfolio::Position;
+
+    fn book() -> Portfolio {
+        let mut portfolio = Portfolio::new();
+        portfolio.add(Position::new(
+            "AAA",
+            Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None),
+            10.0,
+        ));
+        portfolio.add(Position::new(
+            "BBB",
+            Options::new_put(50.0, 50.0, 0.3, 0.05, 0.5, None),
+            -20.0,
+        ));
+        portfolio
+    }
+
+    fn factors() -> RiskFactors {
+        // daily vols of 1% and 2% with 0.5 correlation
+        RiskFactors::new(
+            vec!["AAA".to_string(), "BBB".to_string()],
+            vec![vec![0.0001, 0.0001], vec![0.0001, 0.0004]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parametric_var() {
+        let report = book().parametric_var(&factors(), 0.99).unwrap();
+        assert!(report.var > 0.0);
+        assert!(report.expected_shortfall > report.var);
+        let total: f64 = report.positions.iter().map(|p| p.component).sum();
+        assert!((total - report.var).abs() < 1e-9);
+        assert!(book().parametric_var(&factors(), 1.5).is_err());
+    }
+
+    #[cfg(feature = "mc")]
+    #[test]
+    fn test_monte_carlo_matches_parametric() {
+        let parametric = book().parametric_var(&factors(), 0.99).unwrap();
+        let mc = book()
+            .monte_carlo_var(&factors(), 0.99, 20_000, 42)
+            .unwrap();
+        assert!((mc.var - parametric.var).abs() / parametric.var < 0.1);
+        assert!(mc.expected_shortfall >= mc.var);
+        let total: f64 = mc.positions.iter().map(|p| p.component).sum();
+        assert!((total - mc.var).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_historical_var() {
+        let returns = vec![
+            vec![0.01, 0.02],
+            vec![-0.02, -0.03],
+            vec![0.005, -0.01],
+            vec![-0.01, 0.01],
+        ];
+        let factors =
+            RiskFactors::from_returns(vec!["AAA".to_string(), "BBB".to_string()], returns).unwrap();
+        let report = book().historical_var(&factors, 0.75).unwrap();
+        // worst scenario is the joint sell-off
+        assert!(report.var > 0.0);
+        assert!(report.expected_shortfall >= report.var);
+    }
+}
@@ -0,0 +1,273 @@
+use super::smile::Smile;
+use super::surface::VolSurface;
+use crate::black_scholes::{d_minus, d_plus};
+use crate::error::PricingError;
+use crate::math::interp::linear;
+use crate::math::normal::Normal;
+
+/// Post-processing applied to the raw Breeden-Litzenberger density.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DensityParams {
+    /// Half-width, in grid points, of the moving average run over the density; 0 disables it
+    pub smoothing: usize,
+    /// Floor negative densities, which come from noise or arbitrage in the price curve, at zero
+    pub clip_negative: bool,
+}
+
+impl Default for DensityParams {
+    fn default() -> Self {
+        DensityParams {
+            smoothing: 0,
+            clip_negative: true,
+        }
+    }
+}
+
+/// Risk-neutral distribution of the terminal spot implied by call prices across strikes.
+///
+/// f(K) = ∂²C/∂K² / DF and F(K) = 1 + ∂C/∂K / DF, evaluated by finite differences on the
+/// interior strikes of the grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskNeutralDensity {
+    strikes: Vec<f64>,
+    pdf: Vec<f64>,
+    cdf: Vec<f64>,
+}
+
+impl RiskNeutralDensity {
+    /// Density from call prices quoted on strictly increasing strikes
+    pub fn from_call_prices(
+        strikes: &[f64],
+        prices: &[f64],
+        discount_factor: f64,
+        params: DensityParams,
+    ) -> Result<Self, PricingError> {
+        if strikes.len() != prices.len() || strikes.len() < 3 {
+            return Err(PricingError::InvalidInput(
+                "density needs at least three strikes with one price each".to_string(),
+            ));
+        }
+        if strikes.windows(2).any(|w| w[1] <= w[0]) || discount_factor <= 0.0 {
+            return Err(PricingError::InvalidInput(
+                "strikes must be strictly increasing and the discount factor positive".to_string(),
+            ));
+        }
+        let n = strikes.len();
+        let mut pdf = Vec::with_capacity(n - 2);
+        let mut cdf = Vec::with_capacity(n - 2);
+        for i in 1..n - 1 {
+            let h_lo = strikes[i] - strikes[i - 1];
+            let h_hi = strikes[i + 1] - strikes[i];
+            let slope_lo = (prices[i] - prices[i - 1]) / h_lo;
+            let slope_hi = (prices[i + 1] - prices[i]) / h_hi;
+            pdf.push(2.0 * (slope_hi - slope_lo) / (h_lo + h_hi) / discount_factor);
+            let slope = (prices[i + 1] - prices[i - 1]) / (h_lo + h_hi);
+            cdf.push(1.0 + slope / discount_factor);
+        }
+        let pdf = smooth(&pdf, params.smoothing)
+            .into_iter()
+            .map(|f| if params.clip_negative { f.max(0.0) } else { f })
+            .collect();
+        // keep the distribution function a valid one even on noisy prices
+        let mut running = 0.0f64;
+        let cdf = cdf
+            .into_iter()
+            .map(|c| {
+                running = running.max(c.clamp(0.0, 1.0));
+                running
+            })
+            .collect();
+        Ok(RiskNeutralDensity {
+            strikes: strikes[1..n - 1].to_vec(),
+            pdf,
+            cdf,
+        })
+    }
+    /// Density implied by a smile, priced with Black-76 on `points` strikes spanning [low, high]
+    pub fn from_smile(
+        smile: &Smile,
+        discount_factor: f64,
+        low: f64,
+        high: f64,
+        points: usize,
+        params: DensityParams,
+    ) -> Result<Self, PricingError> {
+        RiskNeutralDensity::from_vol_curve(
+            smile.forward,
+            smile.expiry,
+            discount_factor,
+            &strike_grid(low, high, points),
+            |strike| smile.vol(strike).vol,
+            params,
+        )
+    }
+    /// Density of the terminal spot at an expiry read off a surface
+    pub fn from_surface(
+        surface: &VolSurface,
+        expiry: f64,
+        discount_factor: f64,
+        low: f64,
+        high: f64,
+        points: usize,
+        params: DensityParams,
+    ) -> Result<Self, PricingError> {
+        RiskNeutralDensity::from_vol_curve(
+            surface.forward(expiry),
+            expiry,
+            discount_factor,
+            &strike_grid(low, high, points),
+            |strike| surface.vol(strike, expiry).vol,
+            params,
+        )
+    }
+    fn from_vol_curve<V: Fn(f64) -> f64>(
+        forward: f64,
+        expiry: f64,
+        discount_factor: f64,
+        strikes: &[f64],
+        vol: V,
+        params: DensityParams,
+    ) -> Result<Self, PricingError> {
+        let std_norm = Normal::new(0.0, 1.0).unwrap();
+        let prices: Vec<f64> = strikes
+            .iter()
+            .map(|&strike| {
+                let sigma = vol(strike);
+                let d1 = d_plus(expiry, 0.0, None, sigma, forward, strike);
+                let d2 = d_minus(expiry, 0.0, None, sigma, forward, strike);
+                discount_factor * (forward * std_norm.cdf(d1) - strike * std_norm.cdf(d2))
+            })
+            .collect();
+        RiskNeutralDensity::from_call_prices(strikes, &prices, discount_factor, params)
+    }
+    pub fn strikes(&self) -> &[f64] {
+        &self.strikes
+    }
+    pub fn pdf_values(&self) -> &[f64] {
+        &self.pdf
+    }
+    /// Density at a terminal spot, linearly interpolated and zero off the grid
+    pub fn pdf(&self, spot: f64) -> f64 {
+        if spot < self.strikes[0] || spot > self.strikes[self.strikes.len() - 1] {
+            return 0.0;
+        }
+        linear(&self.strikes, &self.pdf, spot)
+    }
+    /// Probability that the terminal spot ends at or below the given level
+    pub fn cdf(&self, spot: f64) -> f64 {
+        linear(&self.strikes, &self.cdf, spot)
+    }
+    /// Probability mass captured by the strike grid
+    pub fn mass(&self) -> f64 {
+        self.integrate(|_| 1.0)
+    }
+    /// Mean of the terminal spot over the grid, which should recover the forward
+    pub fn mean(&self) -> f64 {
+        self.integrate(|x| x) / self.mass()
+    }
+    /// Trapezoidal integral of g(x) f(x) over the grid
+    pub fn integrate<G: Fn(f64) -> f64>(&self, g: G) -> f64 {
+        self.strikes
+            .windows(2)
+            .zip(self.pdf.windows(2))
+            .map(|(x, f)| 0.5 * (x[1] - x[0]) * (g(x[0]) * f[0] + g(x[1]) * f[1]))
+            .sum()
+    }
+}
+
+fn strike_grid(low: f64, high: f64, points: usize) -> Vec<f64> {
+    let points = points.max(3);
+    (0..points)
+        .map(|i| low + (high - low) * i as f64 / (points - 1) as f64)
+        .collect()
+}
+
+// Centred moving average, shrinking the window at the ends of the grid
+fn smooth(values: &[f64], half_width: usize) -> Vec<f64> {
+    if half_width == 0 {
+        return values.to_vec();
+    }
+    (0..values.len())
+        .map(|i| {
+            let lo = i.saturating_sub(half_width);
+            let hi = (i + half_width).min(values.len() - 1);
+            values[lo..=hi].iter().sum::<f64>() / (hi - lo + 1) as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vol::smile::WingExtrapolation;
+
+    #[test]
+    fn test_flat_smile_recovers_lognormal() {
+        let (forward, expiry, vol) = (100.0, 0.5, 0.25);
+        let flat = vec![(90.0, vol), (110.0, vol)];
+        let smile = Smile::new(expiry, forward, flat, WingExtrapolation::Flat).unwrap();
+        let density = RiskNeutralDensity::from_smile(
+            &smile,
+            (-0.03f64 * expiry).exp(),
+            20.0,
+            300.0,
+            1401,
+            DensityParams::default(),
+        )
+        .unwrap();
+        let sd = vol * expiry.sqrt();
+        // terminal spot is lognormal: ln S_T ~ N(ln F - sd²/2, sd)
+        let log_spot = Normal::new(forward.ln() - 0.5 * sd * sd, sd).unwrap();
+        for spot in [70.0f64, 100.0, 130.0] {
+            assert!((density.pdf(spot) - log_spot.pdf(spot.ln()) / spot).abs() < 1e-5);
+            assert!((density.cdf(spot) - log_spot.cdf(spot.ln())).abs() < 1e-4);
+        }
+        assert!((density.mass() - 1.0).abs() < 1e-3);
+        assert!((density.mean() - forward).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_noisy_prices_are_smoothed() {
+        let strikes: Vec<f64> = (0..41).map(|i| 80.0 + i as f64).collect();
+        let flat = vec![(90.0, 0.2), (110.0, 0.2)];
+        let smile = Smile::new(1.0, 100.0, flat, WingExtrapolation::Flat).unwrap();
+        let clean =
+            RiskNeutralDensity::from_smile(&smile, 1.0, 80.0, 120.0, 41, DensityParams::default())
+                .unwrap();
+        // alternate the price error so the raw second difference swings negative
+        let prices: Vec<f64> = strikes
+            .iter()
+            .enumerate()
+            .map(|(i, &k)| {
+                let d1 = d_plus(1.0, 0.0, None, 0.2, 100.0, k);
+                let d2 = d_minus(1.0, 0.0, None, 0.2, 100.0, k);
+                let n = Normal::new(0.0, 1.0).unwrap();
+                100.0 * n.cdf(d1) - k * n.cdf(d2) + if i % 2 == 0 { 0.005 } else { -0.005 }
+            })
+            .collect();
+        let raw = RiskNeutralDensity::from_call_prices(
+            &strikes,
+            &prices,
+            1.0,
+            DensityParams {
+                smoothing: 0,
+                clip_negative: false,
+            },
+        )
+        .unwrap();
+        assert!(raw.pdf_values().iter().any(|&f| f < 0.0));
+        let smoothed = RiskNeutralDensity::from_call_prices(
+            &strikes,
+            &prices,
+            1.0,
+            DensityParams {
+                smoothing: 2,
+                clip_negative: true,
+            },
+        )
+        .unwrap();
+        assert!(smoothed.pdf_values().iter().all(|&f| f >= 0.0));
+        assert!((smoothed.pdf(100.0) - clean.pdf(100.0)).abs() < 0.01);
+        assert!(smoothed.cdf(110.0) >= smoothed.cdf(90.0));
+    }
+}
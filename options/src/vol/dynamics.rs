@@ -0,0 +1,173 @@
+use crate::Options;
+use crate::numerics::NumericsConfig;
+use crate::vol::smile::Smile;
+
+/// Assumption on how implied vol moves when spot moves, used for hedge ratios.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmileDynamics {
+    /// Each strike keeps its vol, so the hedge ratio is plain BS delta
+    StickyStrike,
+    /// Vol is a function of K/S, so dσ/dS = -(K/S) * dσ/dK given the smile slope dσ/dK at the strike
+    StickyMoneyness { skew_slope: f64 },
+    /// Empirical spot-vol regression dσ/dS = beta * dσ/dK, given the smile slope dσ/dK at the strike.
+    /// beta = 0 is sticky strike and beta = -K/S is sticky moneyness
+    SkewRegression { skew_slope: f64, beta: f64 },
+    /// Hull-White (2017) fit: expected vol change per spot return is (a + bΔ + cΔ²) / √T
+    HullWhite { a: f64, b: f64, c: f64 },
+}
+
+/// How the vol surface is carried along when spot is bumped in Greeks and scenarios.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StickyRule {
+    /// Each strike keeps its implied vol
+    #[default]
+    StickyStrike,
+    /// Implied vol is a function of K/S and moves with spot. At a fixed expiry this is also
+    /// sticky delta, since delta depends on the strike only through K/S and the vol itself
+    StickyMoneyness,
+}
+
+impl StickyRule {
+    /// Strike whose vol on today's surface applies to `strike` once spot has moved from
+    /// `base_spot` to `spot`
+    pub fn surface_strike(&self, strike: f64, base_spot: f64, spot: f64) -> f64 {
+        match self {
+            StickyRule::StickyStrike => strike,
+            StickyRule::StickyMoneyness => strike * base_spot / spot,
+        }
+    }
+}
+
+impl SmileDynamics {
+    /// Sticky-moneyness dynamics with the slope read off a smile at the option's strike
+    pub fn sticky_moneyness_from(smile: &Smile, strike: f64) -> Self {
+        SmileDynamics::StickyMoneyness {
+            skew_slope: smile.skew_slope(strike),
+        }
+    }
+    /// Expected change in the option's implied vol per unit change in spot
+    pub fn vol_spot_sensitivity(&self, option: &Options) -> f64 {
+        let spot = option.spot_price();
+        let strike = option.strike_price();
+        match *self {
+            SmileDynamics::StickyStrike => 0.0,
+            SmileDynamics::StickyMoneyness { skew_slope } => -(strike / spot) * skew_slope,
+            SmileDynamics::SkewRegression { skew_slope, beta } => beta * skew_slope,
+            SmileDynamics::HullWhite { a, b, c } => {
+                let delta = option.delta();
+                (a + b * delta + c * delta * delta) / (spot * option.time_to_maturity().sqrt())
+            }
+        }
+    }
+}
+
+impl Options {
+    /// Minimum-variance delta: BS delta plus the vega exposure to the vol move expected under the
+    /// smile dynamics, δ_MV = δ_BS + ν * dσ/dS
+    pub fn min_variance_delta(&self, dynamics: &SmileDynamics) -> f64 {
+        self.delta() + self.vega() * dynamics.vol_spot_sensitivity(self)
+    }
+}
+
+impl Options {
+    /// Delta and gamma with the implied vol re-read from `vol(strike, expiry)` at each bumped
+    /// spot as `rule` moves the surface; the option's own volatility is ignored
+    pub fn smile_delta_gamma<V>(&self, vol: V, rule: StickyRule) -> (f64, f64)
+    where
+        V: Fn(f64, f64) -> f64,
+    {
+        self.smile_delta_gamma_with(vol, rule, &NumericsConfig::default())
+    }
+    /// Smile delta and gamma with spot bumped by the relative bump of `numerics`
+    pub fn smile_delta_gamma_with<V>(
+        &self,
+        vol: V,
+        rule: StickyRule,
+        numerics: &NumericsConfig,
+    ) -> (f64, f64)
+    where
+        V: Fn(f64, f64) -> f64,
+    {
+        let (spot, strike, expiry) = (
+            self.spot_price(),
+            self.strike_price(),
+            self.time_to_maturity(),
+        );
+        let price = |bumped: f64| {
+            let sigma = vol(rule.surface_strike(strike, spot, bumped), expiry);
+            self.with_market(bumped, sigma, self.risk_free_rate())
+                .bs_pricing()
+        };
+        let h = spot * numerics.relative_bump;
+        let (down, base, up) = (price(spot - h), price(spot), price(spot + h));
+        ((up - down) / (2.0 * h), (up - 2.0 * base + down) / (h * h))
+    }
+}
+
+impl Smile {
+    /// Slope of the smile dσ/dK at a strike, by central difference on the interpolated vols
+    pub fn skew_slope(&self, strike: f64) -> f64 {
+        self.skew_slope_with(strike, &NumericsConfig::default())
+    }
+    /// Smile slope with the strike bumped by the relative bump of `numerics`
+    pub fn skew_slope_with(&self, strike: f64, numerics: &NumericsConfig) -> f64 {
+        let h = strike * numerics.relative_bump;
+        (self.vol(strike + h).vol - self.vol(strike - h).vol) / (2.0 * h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vol::smile::WingExtrapolation;
+
+    #[test]
+    fn test_min_variance_delta_under_negative_skew() {
+        let call = Options::new_call(100.0, 100.0, 0.2, 0.05, 1.0, None);
+        let smile = Smile::new(
+            1.0,
+            100.0,
+            vec![(90.0, 0.22), (100.0, 0.20), (110.0, 0.18)],
+            WingExtrapolation::Flat,
+        )
+        .unwrap();
+        assert!((smile.skew_slope(100.0) + 0.002).abs() < 1e-4);
+
+        let sticky_strike = call.min_variance_delta(&SmileDynamics::StickyStrike);
+        assert!((sticky_strike - call.delta()).abs() < 1e-12);
+        // under sticky moneyness a negative skew lifts vol as spot rises
+        let sticky_moneyness =
+            call.min_variance_delta(&SmileDynamics::sticky_moneyness_from(&smile, 100.0));
+        assert!(sticky_moneyness > call.delta());
+        // the empirical regime for equity index options pulls delta below BS
+        let regression = call.min_variance_delta(&SmileDynamics::SkewRegression {
+            skew_slope: smile.skew_slope(100.0),
+            beta: 1.5,
+        });
+        assert!(regression < call.delta());
+        let hull_white = call.min_variance_delta(&SmileDynamics::HullWhite {
+            a: -0.25,
+            b: 0.5,
+            c: -0.5,
+        });
+        assert!(hull_white < call.delta());
+    }
+
+    #[test]
+    fn test_smile_greeks_under_sticky_rules() {
+        let call = Options::new_call(100.0, 100.0, 0.3, 0.05, 1.0, None);
+        let skew = |strike: f64, _expiry: f64| 0.2 - 0.5 * (strike / 100.0).ln();
+        let flat = call.with_market(100.0, 0.2, 0.05);
+        let (delta, gamma) = call.smile_delta_gamma(skew, StickyRule::StickyStrike);
+        assert!((delta - flat.delta()).abs() < 1e-8 && (gamma - flat.gamma()).abs() < 1e-5);
+        // sticky moneyness is the minimum-variance delta of a smile sloping -0.005 per strike
+        let (delta, _) = call.smile_delta_gamma(skew, StickyRule::StickyMoneyness);
+        let expected =
+            flat.min_variance_delta(&SmileDynamics::StickyMoneyness { skew_slope: -0.005 });
+        assert!((delta - expected).abs() < 1e-6);
+        assert_eq!(
+            StickyRule::StickyMoneyness.surface_strike(100.0, 100.0, 125.0),
+            80.0
+        );
+    }
+}
@@ -0,0 +1,322 @@
+// Realized volatility from price history, the input implied vols are compared against. Range
+// estimators use each bar's high and low as well as its close and need far fewer bars than
+// close-to-close for the same accuracy; Yang-Zhang also captures the overnight gaps that
+// Parkinson, Garman-Klass and Rogers-Satchell miss.
+use crate::conventions::TRADING_DAYS_PER_YEAR;
+use crate::error::PricingError;
+use crate::math::float::{ln, powi, sqrt};
+use core::f64::consts::LN_2;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Open, high, low and close of one period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PriceBar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl PriceBar {
+    pub fn new(open: f64, high: f64, low: f64, close: f64) -> Self {
+        PriceBar {
+            open,
+            high,
+            low,
+            close,
+        }
+    }
+    fn validate(&self) -> Result<(), PricingError> {
+        if !(self.low > 0.0
+            && self.low <= self.open.min(self.close)
+            && self.high >= self.open.max(self.close))
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "bar {:?} is not positive with its open and close inside its range",
+                self
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Variance estimator applied to each period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Estimator {
+    /// Sample variance of log close-to-close returns
+    CloseToClose,
+    /// Formula: σ² = (ln H/L)² / (4 ln 2), assuming no drift and no overnight gap
+    Parkinson,
+    /// Formula: σ² = ½(ln H/L)² - (2 ln 2 - 1)(ln C/O)², assuming no drift and no overnight gap
+    GarmanKlass,
+    /// Formula: σ² = ln(H/C) ln(H/O) + ln(L/C) ln(L/O), unbiased under drift
+    RogersSatchell,
+    /// Overnight, open-to-close and Rogers-Satchell variances combined
+    ///
+    /// Formula: σ² = σ_o² + k σ_c² + (1 - k) σ_rs², k = 0.34 / (1.34 + (n + 1)/(n - 1))
+    YangZhang,
+}
+
+/// How the periods of the series are weighted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Weighting {
+    Equal,
+    /// Exponentially weighted, each period weighing `decay` times the one after it, so the
+    /// estimate reacts to recent moves; RiskMetrics uses 0.94 on daily data
+    Ewma {
+        decay: f64,
+    },
+}
+
+impl Weighting {
+    /// Weight of each of `n` periods, oldest first, summing to one
+    pub fn weights(&self, n: usize) -> Vec<f64> {
+        let raw: Vec<f64> = match *self {
+            Weighting::Equal => vec![1.0; n],
+            Weighting::Ewma { decay } => (0..n).map(|i| powi(decay, (n - 1 - i) as i32)).collect(),
+        };
+        let total: f64 = raw.iter().sum();
+        raw.into_iter().map(|w| w / total).collect()
+    }
+    pub(crate) fn validate(&self) -> Result<(), PricingError> {
+        if let Weighting::Ewma { decay } = *self
+            && !(decay > 0.0 && decay <= 1.0)
+        {
+            return Err(PricingError::InvalidInput(format!(
+                "EWMA decay {} is outside (0, 1]",
+                decay
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Annualized historical volatility estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HistoricalVol {
+    pub estimator: Estimator,
+    pub weighting: Weighting,
+    /// Bars in a year, trading days by default
+    pub periods_per_year: f64,
+}
+
+impl HistoricalVol {
+    /// Equally weighted daily estimate
+    pub fn new(estimator: Estimator) -> Self {
+        HistoricalVol {
+            estimator,
+            weighting: Weighting::Equal,
+            periods_per_year: TRADING_DAYS_PER_YEAR,
+        }
+    }
+    pub fn with_ewma(self, decay: f64) -> Self {
+        HistoricalVol {
+            weighting: Weighting::Ewma { decay },
+            ..self
+        }
+    }
+    pub fn with_periods_per_year(self, periods_per_year: f64) -> Self {
+        HistoricalVol {
+            periods_per_year,
+            ..self
+        }
+    }
+    /// Annualized volatility of `bars`, oldest first. Close-to-close and Yang-Zhang need three
+    /// bars, the first only supplying a previous close; the other estimators need one
+    pub fn estimate(&self, bars: &[PriceBar]) -> Result<f64, PricingError> {
+        self.weighting.validate()?;
+        if !(self.periods_per_year > 0.0 && self.periods_per_year.is_finite()) {
+            return Err(PricingError::InvalidInput(format!(
+                "{} periods per year is not positive",
+                self.periods_per_year
+            )));
+        }
+        for bar in bars {
+            bar.validate()?;
+        }
+        let needed = match self.estimator {
+            Estimator::CloseToClose | Estimator::YangZhang => 3,
+            _ => 1,
+        };
+        if bars.len() < needed {
+            return Err(PricingError::InvalidInput(format!(
+                "{:?} needs at least {} bars, got {}",
+                self.estimator,
+                needed,
+                bars.len()
+            )));
+        }
+        let log_ratio = |a: f64, b: f64| ln(a / b);
+        let rogers_satchell = |bar: &PriceBar| {
+            log_ratio(bar.high, bar.close) * log_ratio(bar.high, bar.open)
+                + log_ratio(bar.low, bar.close) * log_ratio(bar.low, bar.open)
+        };
+        let variance = match self.estimator {
+            Estimator::CloseToClose => {
+                let returns: Vec<f64> = bars
+                    .windows(2)
+                    .map(|w| log_ratio(w[1].close, w[0].close))
+                    .collect();
+                self.variance(&returns)
+            }
+            Estimator::Parkinson => self.mean(
+                bars.iter()
+                    .map(|bar| powi(log_ratio(bar.high, bar.low), 2) / (4.0 * LN_2)),
+            ),
+            Estimator::GarmanKlass => self.mean(bars.iter().map(|bar| {
+                0.5 * powi(log_ratio(bar.high, bar.low), 2)
+                    - (2.0 * LN_2 - 1.0) * powi(log_ratio(bar.close, bar.open), 2)
+            })),
+            Estimator::RogersSatchell => self.mean(bars.iter().map(rogers_satchell)),
+            Estimator::YangZhang => {
+                let overnight: Vec<f64> = bars
+                    .windows(2)
+                    .map(|w| log_ratio(w[1].open, w[0].close))
+                    .collect();
+                let intraday: Vec<f64> = bars[1..]
+                    .iter()
+                    .map(|bar| log_ratio(bar.close, bar.open))
+                    .collect();
+                let n = overnight.len() as f64;
+                let k = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+                self.variance(&overnight)
+                    + k * self.variance(&intraday)
+                    + (1.0 - k) * self.mean(bars[1..].iter().map(rogers_satchell))
+            }
+        };
+        Ok(sqrt(variance.max(0.0) * self.periods_per_year))
+    }
+    /// Annualized close-to-close volatility of a series of closes, oldest first
+    pub fn estimate_closes(&self, closes: &[f64]) -> Result<f64, PricingError> {
+        let bars: Vec<PriceBar> = closes
+            .iter()
+            .map(|&close| PriceBar::new(close, close, close, close))
+            .collect();
+        HistoricalVol {
+            estimator: Estimator::CloseToClose,
+            ..*self
+        }
+        .estimate(&bars)
+    }
+    fn mean(&self, values: impl ExactSizeIterator<Item = f64>) -> f64 {
+        let weights = self.weighting.weights(values.len());
+        values.zip(weights).map(|(v, w)| v * w).sum()
+    }
+    // Variance about the weighted mean, with Bessel's correction for its effective number of
+    // observations 1 / Σw², which is n under equal weights
+    fn variance(&self, values: &[f64]) -> f64 {
+        let weights = self.weighting.weights(values.len());
+        let mean: f64 = values.iter().zip(&weights).map(|(v, w)| v * w).sum();
+        let squares: f64 = values
+            .iter()
+            .zip(&weights)
+            .map(|(v, w)| w * (v - mean) * (v - mean))
+            .sum();
+        let concentration: f64 = weights.iter().map(|w| w * w).sum();
+        squares / (1.0 - concentration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bars opening at the previous close and swinging `range` either side of the open
+    fn bars(closes: &[f64], range: f64) -> Vec<PriceBar> {
+        let mut open = closes[0];
+        closes
+            .iter()
+            .map(|&close| {
+                let bar = PriceBar::new(
+                    open,
+                    open.max(close) * range.exp(),
+                    open.min(close) * (-range).exp(),
+                    close,
+                );
+                open = close;
+                bar
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimators() {
+        // alternating daily returns of ±1% are a 1% sample standard deviation scaled by √(n/(n-1))
+        let closes: Vec<f64> = (0..21)
+            .map(|i| 100.0 * (0.01 * (i % 2) as f64).exp())
+            .collect();
+        let daily = HistoricalVol::new(Estimator::CloseToClose).with_periods_per_year(1.0);
+        let vol = daily.estimate_closes(&closes).unwrap();
+        assert!((vol - 0.01 * (20.0f64 / 19.0).sqrt()).abs() < 1e-12);
+        let annual = HistoricalVol::new(Estimator::CloseToClose)
+            .estimate_closes(&closes)
+            .unwrap();
+        assert!((annual - vol * 252f64.sqrt()).abs() < 1e-12);
+
+        // flat bars spanning ±h: ln(H/L) = 2h, so Parkinson reads h²/ln 2 and the others 2h²
+        let flat = bars(&[100.0; 10], 0.01);
+        let estimate = |estimator| {
+            HistoricalVol::new(estimator)
+                .with_periods_per_year(1.0)
+                .estimate(&flat)
+                .unwrap()
+        };
+        let parkinson = estimate(Estimator::Parkinson);
+        assert!((parkinson - (1e-4 / LN_2).sqrt()).abs() < 1e-12);
+        for estimator in [Estimator::GarmanKlass, Estimator::RogersSatchell] {
+            assert!((estimate(estimator) - 2e-4f64.sqrt()).abs() < 1e-12);
+        }
+        // without gaps or open-to-close moves Yang-Zhang keeps the (1 - k) Rogers-Satchell share
+        let k: f64 = 0.34 / (1.34 + 10.0 / 8.0);
+        let yang_zhang = estimate(Estimator::YangZhang);
+        assert!((yang_zhang - (2e-4 * (1.0 - k)).sqrt()).abs() < 1e-12);
+
+        assert!(daily.estimate_closes(&closes[..2]).is_err());
+        let crossed = [PriceBar::new(100.0, 99.0, 98.0, 100.0)];
+        assert!(
+            HistoricalVol::new(Estimator::Parkinson)
+                .estimate(&crossed)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ewma_reacts_to_recent_moves() {
+        // twenty calm days of ±0.5% followed by five of ±3%
+        let mut closes = vec![100.0];
+        for i in 0..25 {
+            let size: f64 = if i < 20 { 0.005 } else { 0.03 };
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            closes.push(closes[i] * (sign * size).exp());
+        }
+        let equal = HistoricalVol::new(Estimator::CloseToClose);
+        let ewma = equal.with_ewma(0.94);
+        assert!(ewma.estimate_closes(&closes).unwrap() > equal.estimate_closes(&closes).unwrap());
+        // no decay is equal weighting
+        let undecayed = equal.with_ewma(1.0).estimate_closes(&closes).unwrap();
+        assert!((undecayed - equal.estimate_closes(&closes).unwrap()).abs() < 1e-12);
+        assert!(equal.with_ewma(1.5).estimate_closes(&closes).is_err());
+
+        let range = HistoricalVol::new(Estimator::YangZhang).with_ewma(0.9);
+        let series = bars(&closes, 0.004);
+        assert!(
+            range.estimate(&series).unwrap()
+                > HistoricalVol::new(Estimator::YangZhang)
+                    .estimate(&series)
+                    .unwrap()
+        );
+    }
+}
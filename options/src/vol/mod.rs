@@ -0,0 +1,12 @@
+// Implied volatility objects: single-expiry smiles, surfaces and the tools built on them.
+#[cfg(all(feature = "unstable", feature = "calibration"))]
+pub mod density;
+#[cfg(all(not(feature = "unstable"), feature = "calibration"))]
+#[allow(dead_code)]
+mod density;
+pub mod dynamics;
+pub mod historical;
+pub mod smile;
+#[cfg(feature = "calibration")]
+pub mod surface;
+pub mod term;
@@ -0,0 +1,239 @@
+use crate::error::PricingError;
+use crate::moneyness;
+
+// Lee's moment formula caps the asymptotic slope of total implied variance in log-moneyness at 2
+const LEE_MAX_SLOPE: f64 = 2.0;
+
+/// Raw SVI parameterization of total implied variance:
+/// w(k) = a + b * (ρ(k - m) + √((k - m)² + σ²))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SviParams {
+    pub a: f64,
+    pub b: f64,
+    pub rho: f64,
+    pub m: f64,
+    pub sigma: f64,
+}
+
+impl SviParams {
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        self.a + self.b * (self.rho * x + (x * x + self.sigma * self.sigma).sqrt())
+    }
+    /// Asymptotic (left, right) slopes of total variance: b(1 - ρ) and b(1 + ρ)
+    pub fn wing_slopes(&self) -> (f64, f64) {
+        (self.b * (1.0 - self.rho), self.b * (1.0 + self.rho))
+    }
+}
+
+/// How implied vol is extended beyond the quoted strike range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WingExtrapolation {
+    /// Edge vol held constant, which at a fixed expiry is flat in both strike and delta
+    Flat,
+    /// Total variance continued linearly from the last two quotes, slope clipped to [0, max_slope]
+    /// with max_slope itself capped at Lee's bound of 2
+    Lee { max_slope: f64 },
+    /// Total variance follows the SVI wing shape, anchored to the edge quotes for continuity
+    Svi(SviParams),
+}
+
+impl WingExtrapolation {
+    /// Whether the wing slopes respect Lee's moment bounds, so extrapolated prices stay arbitrage-free
+    pub fn is_arbitrage_free(&self) -> bool {
+        match self {
+            WingExtrapolation::Flat => true,
+            WingExtrapolation::Lee { max_slope } => (0.0..=LEE_MAX_SLOPE).contains(max_slope),
+            WingExtrapolation::Svi(params) => {
+                let (left, right) = params.wing_slopes();
+                params.b >= 0.0
+                    && (0.0..=LEE_MAX_SLOPE).contains(&left)
+                    && (0.0..=LEE_MAX_SLOPE).contains(&right)
+            }
+        }
+    }
+}
+
+/// Implied vol read from a smile, flagged when it comes from the extrapolated wings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmileVol {
+    pub vol: f64,
+    pub extrapolated: bool,
+}
+
+/// Implied vols quoted across strikes for a single expiry.
+///
+/// Interpolates linearly in total variance against log-moneyness ln(K/F) inside the quoted range,
+/// and applies the configured wing extrapolation outside it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Smile {
+    pub expiry: f64,
+    pub forward: f64,
+    pub wings: WingExtrapolation,
+    strikes: Vec<f64>,
+    vols: Vec<f64>,
+}
+
+impl Smile {
+    /// Builds a smile from (strike, implied vol) quotes; quotes are sorted by strike. Fails on
+    /// fewer than two quotes, a repeated strike, or a strike or vol that is not positive and
+    /// finite
+    pub fn new(
+        expiry: f64,
+        forward: f64,
+        mut quotes: Vec<(f64, f64)>,
+        wings: WingExtrapolation,
+    ) -> Result<Self, PricingError> {
+        if quotes.len() < 2 {
+            return Err(PricingError::InvalidInput(format!(
+                "a smile needs at least two quotes, got {}",
+                quotes.len()
+            )));
+        }
+        let positive = |x: f64| x > 0.0 && x.is_finite();
+        if let Some(&(strike, vol)) = quotes.iter().find(|&&(k, v)| !(positive(k) && positive(v))) {
+            return Err(PricingError::InvalidInput(format!(
+                "smile quote ({}, {}) needs a positive, finite strike and vol",
+                strike, vol
+            )));
+        }
+        quotes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(pair) = quotes.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(PricingError::InvalidInput(format!(
+                "smile strike {} is quoted twice",
+                pair[0].0
+            )));
+        }
+        let (strikes, vols) = quotes.into_iter().unzip();
+        Ok(Smile {
+            expiry,
+            forward,
+            wings,
+            strikes,
+            vols,
+        })
+    }
+    pub fn strikes(&self) -> &[f64] {
+        &self.strikes
+    }
+    pub fn vols(&self) -> &[f64] {
+        &self.vols
+    }
+    /// Implied vol at a strike, with the extrapolation flag set outside the quoted range
+    pub fn vol(&self, strike: f64) -> SmileVol {
+        let n = self.strikes.len();
+        let k = self.log_moneyness(strike);
+        if strike < self.strikes[0] {
+            let w = self.wing_variance(k, 0, 1);
+            return self.extrapolated(w);
+        }
+        if strike > self.strikes[n - 1] {
+            let w = self.wing_variance(k, n - 1, n - 2);
+            return self.extrapolated(w);
+        }
+        let i = self
+            .strikes
+            .partition_point(|&s| s < strike)
+            .clamp(1, n - 1);
+        let (k0, k1) = (
+            self.log_moneyness(self.strikes[i - 1]),
+            self.log_moneyness(self.strikes[i]),
+        );
+        let (w0, w1) = (self.total_variance_at(i - 1), self.total_variance_at(i));
+        let w = w0 + (w1 - w0) * (k - k0) / (k1 - k0);
+        SmileVol {
+            vol: (w / self.expiry).sqrt(),
+            extrapolated: false,
+        }
+    }
+    fn log_moneyness(&self, strike: f64) -> f64 {
+        moneyness::log_moneyness(strike, self.forward)
+    }
+    fn total_variance_at(&self, i: usize) -> f64 {
+        self.vols[i] * self.vols[i] * self.expiry
+    }
+    fn extrapolated(&self, total_variance: f64) -> SmileVol {
+        SmileVol {
+            vol: (total_variance.max(0.0) / self.expiry).sqrt(),
+            extrapolated: true,
+        }
+    }
+    // Total variance beyond the quote at `edge`, using `inner` as its neighbour for slope estimates
+    fn wing_variance(&self, k: f64, edge: usize, inner: usize) -> f64 {
+        let k_edge = self.log_moneyness(self.strikes[edge]);
+        let w_edge = self.total_variance_at(edge);
+        match self.wings {
+            WingExtrapolation::Flat => w_edge,
+            WingExtrapolation::Lee { max_slope } => {
+                let k_inner = self.log_moneyness(self.strikes[inner]);
+                let slope = (w_edge - self.total_variance_at(inner)) / (k_edge - k_inner).abs();
+                let slope = slope.clamp(0.0, max_slope.clamp(0.0, LEE_MAX_SLOPE));
+                w_edge + slope * (k - k_edge).abs()
+            }
+            WingExtrapolation::Svi(params) => {
+                w_edge + params.total_variance(k) - params.total_variance(k_edge)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotes() -> Vec<(f64, f64)> {
+        vec![(80.0, 0.30), (90.0, 0.25), (100.0, 0.20), (110.0, 0.19)]
+    }
+
+    #[test]
+    fn test_smile_interpolation_and_flags() {
+        let smile = Smile::new(1.0, 100.0, quotes(), WingExtrapolation::Flat).unwrap();
+        let atm = smile.vol(100.0);
+        assert!((atm.vol - 0.20).abs() < 1e-12);
+        assert!(!atm.extrapolated);
+        let inner = smile.vol(95.0);
+        assert!(inner.vol < 0.25 && inner.vol > 0.20 && !inner.extrapolated);
+        let wing = smile.vol(60.0);
+        assert!(wing.extrapolated);
+        assert!((wing.vol - 0.30).abs() < 1e-12);
+
+        let flat = WingExtrapolation::Flat;
+        assert!(Smile::new(1.0, 100.0, vec![(100.0, 0.2)], flat).is_err());
+        assert!(Smile::new(1.0, 100.0, vec![(90.0, 0.2), (100.0, 0.0)], flat).is_err());
+        assert!(Smile::new(1.0, 100.0, vec![(f64::NAN, 0.2), (100.0, 0.2)], flat).is_err());
+        assert!(Smile::new(1.0, 100.0, vec![(100.0, 0.2), (100.0, 0.3)], flat).is_err());
+    }
+
+    #[test]
+    fn test_lee_and_svi_wings() {
+        let lee = Smile::new(
+            1.0,
+            100.0,
+            quotes(),
+            WingExtrapolation::Lee { max_slope: 2.0 },
+        )
+        .unwrap();
+        // skew keeps rising into the left wing but the right wing slope is clipped at zero
+        assert!(lee.vol(60.0).vol > 0.30);
+        assert!((lee.vol(150.0).vol - 0.19).abs() < 1e-12);
+        // asymptotically total variance grows no faster than 2|k|
+        let far = lee.vol(1e-6);
+        let k = (1e-6f64 / 100.0).ln().abs();
+        assert!(far.vol * far.vol <= 2.0 * k + 0.09);
+
+        let svi = SviParams {
+            a: 0.02,
+            b: 0.4,
+            rho: -0.5,
+            m: 0.0,
+            sigma: 0.2,
+        };
+        assert!(WingExtrapolation::Svi(svi).is_arbitrage_free());
+        assert!(!WingExtrapolation::Svi(SviParams { b: 2.0, ..svi }).is_arbitrage_free());
+        let svi_smile = Smile::new(1.0, 100.0, quotes(), WingExtrapolation::Svi(svi)).unwrap();
+        let edge = svi_smile.vol(110.0);
+        let just_outside = svi_smile.vol(110.01);
+        assert!(just_outside.extrapolated);
+        assert!((just_outside.vol - edge.vol).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,581 @@
+use crate::calibration_report::{CalibrationReport, Parameter};
+use crate::error::PricingError;
+use crate::math::float::{exp, ln, powi};
+use crate::quote::{OptionQuote, QuoteSide};
+use crate::solver::nelder_mead;
+use crate::vol::smile::{Smile, SmileVol, SviParams, WingExtrapolation};
+use crate::vol::term;
+use crate::{OptionType, Options};
+use rayon::prelude::*;
+
+// Quotes whose expiries differ by less than this share a slice
+const EXPIRY_TOLERANCE: f64 = 1e-9;
+// Slices with fewer quotes than this keep their raw nodes instead of an SVI fit
+const MIN_SVI_QUOTES: usize = 5;
+// SVI parameters a, b, ρ, m, σ and the intervals keeping the smile well defined
+const SVI_PARAMETERS: &[Parameter] = &[
+    Parameter::unbounded("a"),
+    Parameter::positive("b"),
+    Parameter::new("rho", -1.0, 1.0),
+    Parameter::unbounded("m"),
+    Parameter::positive("sigma"),
+];
+
+/// Weight of each quote's squared total variance miss in a slice's SVI fit, scaled to average one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitWeighting {
+    #[default]
+    Uniform,
+    /// Proportional to Black-Scholes vega, so the fit is tightest where vol moves the price most
+    Vega,
+    /// Inversely proportional to the bid/ask spread, trusting tight markets over wide ones;
+    /// quotes without a spread weigh as much as the tightest quoted one
+    Spread,
+}
+
+/// Why a quote was left out of the surface.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RejectReason {
+    /// No implied vol reproduces the price on the side being fitted
+    NoImpliedVol(PricingError),
+    /// Call prices increase with strike, or fall faster than the strike step
+    Vertical,
+    /// Call prices are not convex in strike
+    Butterfly,
+    /// ATM total variance decreases from the previous expiry
+    Calendar,
+    /// The expiry's remaining quotes do not form a smile, such as a lone strike
+    Smile(PricingError),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedQuote {
+    pub quote: OptionQuote,
+    pub reason: RejectReason,
+}
+
+/// Fitted smile of one expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfaceSlice {
+    pub expiry: f64,
+    pub forward: f64,
+    /// Fitted SVI parameters, None when the slice had too few quotes to fit
+    pub svi: Option<SviParams>,
+    /// Report of the SVI fit in total variance, None alongside `svi`
+    pub fit: Option<CalibrationReport>,
+    pub smile: Smile,
+}
+
+/// Implied volatility surface across strikes and expiries.
+///
+/// Interpolates total variance linearly in expiry at fixed log-moneyness between slices
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolSurface {
+    slices: Vec<SurfaceSlice>,
+    rejected: Vec<RejectedQuote>,
+}
+
+impl VolSurface {
+    /// Builds a surface from listed quotes: solves mid implied vols in parallel, keeps the
+    /// out-of-the-money side per strike, drops quotes violating vertical, butterfly or calendar
+    /// arbitrage, then fits an SVI smile per expiry
+    pub fn from_chain(quotes: &[OptionQuote]) -> Result<Self, PricingError> {
+        VolSurface::from_chain_with(quotes, QuoteSide::Mid, FitWeighting::Uniform)
+    }
+    /// Builds a surface as `from_chain` does, from the implied vols of one side of the quotes
+    /// and with the SVI fits weighted by `weighting`
+    pub fn from_chain_with(
+        quotes: &[OptionQuote],
+        side: QuoteSide,
+        weighting: FitWeighting,
+    ) -> Result<Self, PricingError> {
+        let solved: Vec<Result<f64, PricingError>> = quotes
+            .par_iter()
+            .map(|quote| quote.side_implied_volatility(side))
+            .collect();
+        let mut rejected = Vec::new();
+        let mut nodes: Vec<(OptionQuote, f64)> = Vec::new();
+        for (quote, vol) in quotes.iter().zip(solved) {
+            match vol {
+                Ok(vol) => nodes.push((*quote, vol)),
+                Err(err) => rejected.push(RejectedQuote {
+                    quote: *quote,
+                    reason: RejectReason::NoImpliedVol(err),
+                }),
+            }
+        }
+        nodes.sort_by(|a, b| a.0.expiry.total_cmp(&b.0.expiry));
+
+        let mut slices: Vec<SurfaceSlice> = Vec::new();
+        let mut start = 0;
+        while start < nodes.len() {
+            let expiry = nodes[start].0.expiry;
+            let end = start
+                + nodes[start..]
+                    .iter()
+                    .take_while(|(q, _)| q.expiry - expiry < EXPIRY_TOLERANCE)
+                    .count();
+            let slice_nodes = out_of_the_money(&nodes[start..end]);
+            let clean = filter_strike_arbitrage(slice_nodes, &mut rejected);
+            start = end;
+            if clean.is_empty() {
+                continue;
+            }
+            let slice = match fit_slice(&clean, weighting) {
+                Ok(slice) => slice,
+                Err(err) => {
+                    rejected.extend(clean.into_iter().map(|(quote, _)| RejectedQuote {
+                        quote,
+                        reason: RejectReason::Smile(err.clone()),
+                    }));
+                    continue;
+                }
+            };
+            // calendar check on ATM total variance against the last accepted slice
+            if let Some(previous) = slices.last() {
+                let atm = |s: &SurfaceSlice| powi(s.smile.vol(s.forward).vol, 2) * s.expiry;
+                if atm(&slice) < atm(previous) {
+                    rejected.extend(clean.into_iter().map(|(quote, _)| RejectedQuote {
+                        quote,
+                        reason: RejectReason::Calendar,
+                    }));
+                    continue;
+                }
+            }
+            slices.push(slice);
+        }
+        if slices.is_empty() {
+            return Err(PricingError::InvalidInput(
+                "no usable quotes to build a surface".to_string(),
+            ));
+        }
+        Ok(VolSurface { slices, rejected })
+    }
+    /// Builds a surface directly from fitted slices, sorted by expiry
+    pub fn from_slices(mut slices: Vec<SurfaceSlice>) -> Self {
+        slices.sort_by(|a, b| a.expiry.total_cmp(&b.expiry));
+        VolSurface {
+            slices,
+            rejected: Vec::new(),
+        }
+    }
+    pub fn slices(&self) -> &[SurfaceSlice] {
+        &self.slices
+    }
+    /// Quotes dropped while building the surface, with the reason
+    pub fn rejected(&self) -> &[RejectedQuote] {
+        &self.rejected
+    }
+    /// Forward at an expiry, log-linearly interpolated between slices
+    pub fn forward(&self, expiry: f64) -> f64 {
+        let (lower, upper, weight) = self.bracket(expiry);
+        exp(ln(lower.forward) + weight * (ln(upper.forward) - ln(lower.forward)))
+    }
+    /// Implied vol at a strike and expiry, flagged when outside the quoted strikes or expiries
+    pub fn vol(&self, strike: f64, expiry: f64) -> SmileVol {
+        let first = &self.slices[0];
+        let last = &self.slices[self.slices.len() - 1];
+        let k = ln(strike / self.forward(expiry));
+        let slice_vol = |slice: &SurfaceSlice| slice.smile.vol(slice.forward * exp(k));
+        if expiry < first.expiry || expiry > last.expiry {
+            let edge = if expiry < first.expiry { first } else { last };
+            return SmileVol {
+                vol: slice_vol(edge).vol,
+                extrapolated: true,
+            };
+        }
+        let (lower, upper, weight) = self.bracket(expiry);
+        let (v0, v1) = (slice_vol(lower), slice_vol(upper));
+        let w = powi(v0.vol, 2) * lower.expiry
+            + weight * (powi(v1.vol, 2) * upper.expiry - powi(v0.vol, 2) * lower.expiry);
+        SmileVol {
+            vol: (w.max(0.0) / expiry).sqrt(),
+            extrapolated: v0.extrapolated || v1.extrapolated,
+        }
+    }
+    /// Total implied variance σ²T at a log-moneyness k = ln(K / F) and expiry
+    pub fn total_variance(&self, log_moneyness: f64, expiry: f64) -> f64 {
+        let vol = self
+            .vol(self.forward(expiry) * exp(log_moneyness), expiry)
+            .vol;
+        vol * vol * expiry
+    }
+    /// Forward variance between two expiries at a fixed log-moneyness, each expiry read at its
+    /// own forward. Fails on a calendar arbitrage, total variance falling between the expiries
+    pub fn forward_variance(
+        &self,
+        log_moneyness: f64,
+        start: f64,
+        end: f64,
+    ) -> Result<f64, PricingError> {
+        let vol = |t: f64| self.vol(self.forward(t) * exp(log_moneyness), t).vol;
+        term::forward_variance(vol(start), start, vol(end), end)
+    }
+    /// Annualized forward vol between two expiries at a fixed log-moneyness, zero reading each
+    /// expiry at the money forward
+    pub fn forward_vol(
+        &self,
+        log_moneyness: f64,
+        start: f64,
+        end: f64,
+    ) -> Result<f64, PricingError> {
+        let variance = self.forward_variance(log_moneyness, start, end)?;
+        Ok((variance / (end - start)).sqrt())
+    }
+    // Slices surrounding an expiry and the interpolation weight of the upper one
+    fn bracket(&self, expiry: f64) -> (&SurfaceSlice, &SurfaceSlice, f64) {
+        let i = self.slices.partition_point(|s| s.expiry < expiry);
+        if i == 0 {
+            return (&self.slices[0], &self.slices[0], 0.0);
+        }
+        if i == self.slices.len() {
+            let last = &self.slices[i - 1];
+            return (last, last, 0.0);
+        }
+        let (lower, upper) = (&self.slices[i - 1], &self.slices[i]);
+        (
+            lower,
+            upper,
+            (expiry - lower.expiry) / (upper.expiry - lower.expiry),
+        )
+    }
+}
+
+/// Surfaces fitted separately to the bid, mid and ask of the same quotes, for pricing to a
+/// chosen side of the market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketSurfaces {
+    pub bid: VolSurface,
+    pub mid: VolSurface,
+    pub ask: VolSurface,
+}
+
+impl MarketSurfaces {
+    /// Fits all three sides of `quotes` with the same weighting. Fails if any side has no
+    /// usable quotes, for instance a chain with zero bids throughout
+    pub fn from_chain(
+        quotes: &[OptionQuote],
+        weighting: FitWeighting,
+    ) -> Result<Self, PricingError> {
+        Ok(MarketSurfaces {
+            bid: VolSurface::from_chain_with(quotes, QuoteSide::Bid, weighting)?,
+            mid: VolSurface::from_chain_with(quotes, QuoteSide::Mid, weighting)?,
+            ask: VolSurface::from_chain_with(quotes, QuoteSide::Ask, weighting)?,
+        })
+    }
+    pub fn side(&self, side: QuoteSide) -> &VolSurface {
+        match side {
+            QuoteSide::Bid => &self.bid,
+            QuoteSide::Mid => &self.mid,
+            QuoteSide::Ask => &self.ask,
+        }
+    }
+}
+
+// Keeps puts below the forward and calls above it, falling back to whichever side is quoted
+fn out_of_the_money(nodes: &[(OptionQuote, f64)]) -> Vec<(OptionQuote, f64)> {
+    let mut kept: Vec<(OptionQuote, f64)> = Vec::new();
+    for &(quote, vol) in nodes {
+        let preferred = if quote.strike < quote.forward() {
+            OptionType::Put
+        } else {
+            OptionType::Call
+        };
+        match kept.iter_mut().find(|(q, _)| q.strike == quote.strike) {
+            Some(existing)
+                if existing.0.option_type != preferred && quote.option_type == preferred =>
+            {
+                *existing = (quote, vol);
+            }
+            Some(_) => {}
+            None => kept.push((quote, vol)),
+        }
+    }
+    kept.sort_by(|a, b| a.0.strike.total_cmp(&b.0.strike));
+    kept
+}
+
+// Removes quotes until undiscounted call prices are decreasing, no steeper than -1 and convex in strike
+fn filter_strike_arbitrage(
+    mut nodes: Vec<(OptionQuote, f64)>,
+    rejected: &mut Vec<RejectedQuote>,
+) -> Vec<(OptionQuote, f64)> {
+    loop {
+        let calls: Vec<f64> = nodes
+            .iter()
+            .map(|(quote, vol)| {
+                let call = Options::new_call(
+                    quote.strike,
+                    quote.spot,
+                    *vol,
+                    quote.risk_free_rate,
+                    quote.expiry,
+                    quote.dividend_yield,
+                );
+                call.bs_pricing() * exp(quote.risk_free_rate * quote.expiry)
+            })
+            .collect();
+        let strikes: Vec<f64> = nodes.iter().map(|(q, _)| q.strike).collect();
+        let vertical = (1..nodes.len()).find(|&i| {
+            let drop = calls[i - 1] - calls[i];
+            drop < -1e-10 || drop > strikes[i] - strikes[i - 1] + 1e-10
+        });
+        let violation = vertical.map(|i| (i, RejectReason::Vertical)).or_else(|| {
+            (1..nodes.len().saturating_sub(1))
+                .find(|&i| {
+                    let weight = (strikes[i] - strikes[i - 1]) / (strikes[i + 1] - strikes[i - 1]);
+                    calls[i] > (1.0 - weight) * calls[i - 1] + weight * calls[i + 1] + 1e-10
+                })
+                .map(|i| (i, RejectReason::Butterfly))
+        });
+        match violation {
+            Some((i, reason)) => {
+                let (quote, _) = nodes.remove(i);
+                rejected.push(RejectedQuote { quote, reason });
+            }
+            None => return nodes,
+        }
+    }
+}
+
+fn fit_slice(
+    nodes: &[(OptionQuote, f64)],
+    weighting: FitWeighting,
+) -> Result<SurfaceSlice, PricingError> {
+    let expiry = nodes[0].0.expiry;
+    let forward = nodes[0].0.forward();
+    let points: Vec<(f64, f64)> = nodes
+        .iter()
+        .map(|(q, vol)| (ln(q.strike / forward), vol * vol * expiry))
+        .collect();
+    let weights = fit_weights(nodes, weighting);
+    let svi = (nodes.len() >= MIN_SVI_QUOTES).then(|| fit_svi(&points, &weights));
+    let quotes = match svi {
+        Some(params) => nodes
+            .iter()
+            .zip(&points)
+            .map(|((q, _), &(k, _))| {
+                (
+                    q.strike,
+                    (params.total_variance(k).max(0.0) / expiry).sqrt(),
+                )
+            })
+            .collect(),
+        None => nodes.iter().map(|(q, vol)| (q.strike, *vol)).collect(),
+    };
+    let fit = svi.map(|params| {
+        let targets: Vec<f64> = points.iter().map(|&(_, w)| w).collect();
+        let values = [params.a, params.b, params.rho, params.m, params.sigma];
+        CalibrationReport::from_weighted_fit(SVI_PARAMETERS, &values, &targets, &weights, |v| {
+            let params = svi_params(v);
+            points
+                .iter()
+                .map(|&(k, _)| params.total_variance(k))
+                .collect()
+        })
+    });
+    let wings = svi.map_or(WingExtrapolation::Flat, WingExtrapolation::Svi);
+    Ok(SurfaceSlice {
+        expiry,
+        forward,
+        svi,
+        fit,
+        smile: Smile::new(expiry, forward, quotes, wings)?,
+    })
+}
+
+fn fit_weights(nodes: &[(OptionQuote, f64)], weighting: FitWeighting) -> Vec<f64> {
+    let raw: Vec<f64> = match weighting {
+        FitWeighting::Uniform => vec![1.0; nodes.len()],
+        FitWeighting::Vega => nodes
+            .iter()
+            .map(|(q, vol)| q.to_option(*vol).vega())
+            .collect(),
+        FitWeighting::Spread => {
+            let tightest = nodes
+                .iter()
+                .map(|(q, _)| q.spread())
+                .filter(|&spread| spread > 0.0)
+                .fold(f64::INFINITY, f64::min);
+            nodes
+                .iter()
+                .map(|(q, _)| match q.spread() {
+                    spread if spread > 0.0 => 1.0 / spread,
+                    _ if tightest.is_finite() => 1.0 / tightest,
+                    _ => 1.0,
+                })
+                .collect()
+        }
+    };
+    let mean = raw.iter().sum::<f64>() / raw.len() as f64;
+    if mean > 0.0 && mean.is_finite() {
+        raw.iter().map(|w| w / mean).collect()
+    } else {
+        vec![1.0; nodes.len()]
+    }
+}
+
+fn svi_params(values: &[f64]) -> SviParams {
+    SviParams {
+        a: values[0],
+        b: values[1],
+        rho: values[2],
+        m: values[3],
+        sigma: values[4],
+    }
+}
+
+// Weighted least-squares SVI fit to (log-moneyness, total variance) points
+pub(crate) fn fit_svi(points: &[(f64, f64)], weights: &[f64]) -> SviParams {
+    let to_params = |x: &[f64]| {
+        let values: Vec<f64> = SVI_PARAMETERS
+            .iter()
+            .zip(x)
+            .map(|(parameter, &x)| parameter.from_unconstrained(x))
+            .collect();
+        svi_params(&values)
+    };
+    let objective = |x: &[f64]| {
+        let params = to_params(x);
+        points
+            .iter()
+            .zip(weights)
+            .map(|(&(k, w), weight)| weight * powi(params.total_variance(k) - w, 2))
+            .sum::<f64>()
+    };
+    let min_variance = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let start: Vec<f64> = SVI_PARAMETERS
+        .iter()
+        .zip([0.5 * min_variance, 0.1, -0.3, 0.0, 0.1])
+        .map(|(parameter, value)| parameter.to_unconstrained(value))
+        .collect();
+    let (best, _) = nelder_mead(objective, &start, 0.1, 1e-16, 4_000);
+    to_params(&best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Vec<OptionQuote> {
+        let (spot, rate): (f64, f64) = (100.0, 0.03);
+        let mut quotes = Vec::new();
+        for expiry in [0.25, 0.5, 1.0] {
+            for strike in [70.0, 80.0, 90.0, 95.0, 100.0, 105.0, 110.0, 120.0, 130.0] {
+                // skewed smile with a term structure
+                let k: f64 = (strike / spot).ln();
+                let vol = 0.2 - 0.1 * k + 0.2 * k * k + 0.02 * expiry;
+                for option_type in [OptionType::Call, OptionType::Put] {
+                    let mid =
+                        Options::new_with_type(option_type, strike, spot, vol, rate, expiry, None)
+                            .bs_pricing();
+                    quotes.push(OptionQuote::new(
+                        option_type,
+                        strike,
+                        expiry,
+                        mid * 0.99,
+                        mid * 1.01,
+                        spot,
+                        rate,
+                        None,
+                    ));
+                }
+            }
+        }
+        quotes
+    }
+
+    #[test]
+    fn test_surface_from_chain() {
+        let surface = VolSurface::from_chain(&chain()).unwrap();
+        assert_eq!(surface.slices().len(), 3);
+        assert!(surface.rejected().is_empty());
+        let atm = surface.vol(100.0 * (0.03f64 * 0.5).exp(), 0.5);
+        assert!(!atm.extrapolated);
+        assert!((atm.vol - 0.21).abs() < 0.005);
+        // between slices and outside the quoted range
+        let mid_expiry = surface.vol(95.0, 0.75);
+        assert!(!mid_expiry.extrapolated && mid_expiry.vol > 0.2);
+        assert!(surface.vol(50.0, 0.5).extrapolated);
+        assert!(surface.vol(100.0, 2.0).extrapolated);
+        // the SVI fit reprices every total variance of the slice
+        let fit = surface.slices()[1].fit.as_ref().unwrap();
+        assert_eq!(fit.residuals.len(), 9);
+        assert!(fit.max_abs_residual() < 1e-3);
+    }
+
+    #[test]
+    fn test_surface_rejects_bad_quotes() {
+        let mut quotes = chain();
+        // a call priced above the spot has no implied vol
+        quotes[0].bid = 150.0;
+        quotes[0].ask = 151.0;
+        // an inflated 105 call in the 1y slice breaks convexity
+        let index = quotes
+            .iter()
+            .position(|q| q.expiry == 1.0 && q.strike == 105.0 && q.option_type == OptionType::Call)
+            .unwrap();
+        quotes[index].bid *= 1.15;
+        quotes[index].ask *= 1.15;
+        let surface = VolSurface::from_chain(&quotes).unwrap();
+        let reasons: Vec<&RejectReason> = surface.rejected().iter().map(|r| &r.reason).collect();
+        assert!(
+            reasons
+                .iter()
+                .any(|r| matches!(r, RejectReason::NoImpliedVol(_)))
+        );
+        assert!(reasons.contains(&&RejectReason::Butterfly));
+        assert!(VolSurface::from_chain(&[]).is_err());
+    }
+
+    #[test]
+    fn test_bid_ask_surfaces() {
+        let surfaces = MarketSurfaces::from_chain(&chain(), FitWeighting::Uniform).unwrap();
+        let atm = 100.0 * (0.03f64 * 0.5).exp();
+        let vol = |side: QuoteSide| surfaces.side(side).vol(atm, 0.5).vol;
+        assert!(vol(QuoteSide::Bid) < vol(QuoteSide::Mid) - 1e-3);
+        assert!(vol(QuoteSide::Mid) < vol(QuoteSide::Ask) - 1e-3);
+        // weighted fits still reprice the slice, most closely where they weigh most
+        for weighting in [FitWeighting::Vega, FitWeighting::Spread] {
+            let surface = VolSurface::from_chain_with(&chain(), QuoteSide::Mid, weighting).unwrap();
+            let fit = surface.slices()[1].fit.as_ref().unwrap();
+            assert!(fit.max_abs_residual() < 1e-3);
+        }
+        let uniform = surfaces.mid.slices()[1].fit.as_ref().unwrap();
+        let vega =
+            VolSurface::from_chain_with(&chain(), QuoteSide::Mid, FitWeighting::Vega).unwrap();
+        let atm_miss = |report: &CalibrationReport| report.residuals[4].residual.abs();
+        assert!(atm_miss(vega.slices()[1].fit.as_ref().unwrap()) < atm_miss(uniform));
+    }
+
+    #[test]
+    fn test_surface_forward_vol() {
+        let surface = VolSurface::from_chain(&chain()).unwrap();
+        // the ATM term structure rises by 2 vol points a year, so forward vols sit above spot vols
+        let forward = surface.forward_vol(0.0, 0.5, 1.0).unwrap();
+        let (short, long) = (
+            surface.vol(surface.forward(0.5), 0.5),
+            surface.vol(surface.forward(1.0), 1.0),
+        );
+        assert!(forward > long.vol && long.vol > short.vol);
+        let w = |t: f64| surface.total_variance(0.0, t);
+        let variance = surface.forward_variance(0.0, 0.5, 1.0).unwrap();
+        assert!((variance - (w(1.0) - w(0.5))).abs() < 1e-14);
+        // slices with falling total variance are rejected
+        let slice = |expiry: f64, vol: f64| SurfaceSlice {
+            expiry,
+            forward: 100.0,
+            svi: None,
+            fit: None,
+            smile: Smile::new(
+                expiry,
+                100.0,
+                vec![(90.0, vol), (110.0, vol)],
+                WingExtrapolation::Flat,
+            )
+            .unwrap(),
+        };
+        let inverted = VolSurface::from_slices(vec![slice(0.5, 0.3), slice(1.0, 0.2)]);
+        assert!(inverted.forward_vol(0.0, 0.5, 1.0).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+// Forward volatility between two expiries. Total implied variance w = σ²T adds up over time, so
+// the variance the market prices from T₁ to T₂ is w(T₂) - w(T₁), the quantity forward-starting
+// options and variance forwards are priced on. Total variance falling with expiry is a calendar
+// arbitrage and is rejected rather than turned into a negative variance.
+use crate::error::PricingError;
+
+/// Total implied variance between `start` and `end` from the implied vols to each.
+///
+/// Formula: σ₂²T₂ - σ₁²T₁
+pub fn forward_variance(
+    start_vol: f64,
+    start: f64,
+    end_vol: f64,
+    end: f64,
+) -> Result<f64, PricingError> {
+    if !(start >= 0.0 && end > start) {
+        return Err(PricingError::InvalidInput(format!(
+            "forward period from {} to {} does not run forward",
+            start, end
+        )));
+    }
+    let (w_start, w_end) = (start_vol * start_vol * start, end_vol * end_vol * end);
+    if w_end < w_start {
+        return Err(PricingError::InvalidInput(format!(
+            "calendar arbitrage: total variance falls from {} at {} to {} at {}",
+            w_start, start, w_end, end
+        )));
+    }
+    Ok(w_end - w_start)
+}
+
+/// Annualized volatility between `start` and `end`, the vol a forward-starting option over the
+/// period is priced at.
+///
+/// Formula: σ_fwd = √((σ₂²T₂ - σ₁²T₁) / (T₂ - T₁))
+pub fn forward_vol(
+    start_vol: f64,
+    start: f64,
+    end_vol: f64,
+    end: f64,
+) -> Result<f64, PricingError> {
+    let variance = forward_variance(start_vol, start, end_vol, end)?;
+    Ok((variance / (end - start)).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_vol() {
+        // 20% to six months and 25% to a year leave 29.15% for the second half
+        let vol = forward_vol(0.2, 0.5, 0.25, 1.0).unwrap();
+        assert!((vol - (0.0425f64 / 0.5).sqrt()).abs() < 1e-12);
+        assert_eq!(forward_vol(0.2, 0.0, 0.2, 1.0).unwrap(), 0.2);
+        // a flat term structure forwards at itself
+        assert!((forward_vol(0.3, 0.25, 0.3, 2.0).unwrap() - 0.3).abs() < 1e-12);
+        // total variance falling from 0.045 to 0.04 has no forward vol
+        assert!(forward_variance(0.3, 0.5, 0.2, 1.0).is_err());
+        assert!(forward_variance(0.2, 1.0, 0.2, 1.0).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+// Weather derivatives on degree days, an illustration of the Monte Carlo engine on an underlying
+// that is not traded. Each day below a base temperature (65°F, or 18°C) adds heating degree days
+// and each day above it cooling degree days; options pay a tick per degree day of the season's
+// total beyond the strike. With nothing to hedge there is no risk-neutral drift, so values are
+// discounted expected payouts under one of two views of the weather:
+// - burn analysis: the payout the contract would have made in each past season, averaged
+// - a temperature model: a seasonal mean, sinusoidal over the year with a linear warming trend,
+//   plus an Ornstein-Uhlenbeck anomaly pulled back to it, fitted to a daily history
+use crate::OptionType;
+use crate::conventions::CALENDAR_DAYS_PER_YEAR;
+use crate::error::PricingError;
+use crate::math::float::{cos, exp, ln, powi, sin, sqrt};
+use crate::math::linalg::{cholesky, cholesky_solve};
+use crate::mc::{McConfig, McResult, MonteCarloEngine};
+use crate::processes::OrnsteinUhlenbeck;
+use core::f64::consts::PI;
+
+/// Degree-day index a contract settles on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DegreeDayIndex {
+    /// Heating degree days, max(base - T, 0) a day
+    Heating,
+    /// Cooling degree days, max(T - base, 0) a day
+    Cooling,
+}
+
+impl DegreeDayIndex {
+    /// Degree days of one day at average temperature `temperature`
+    pub fn daily(&self, base: f64, temperature: f64) -> f64 {
+        match self {
+            DegreeDayIndex::Heating => (base - temperature).max(0.0),
+            DegreeDayIndex::Cooling => (temperature - base).max(0.0),
+        }
+    }
+    /// Degree days accumulated over daily average temperatures
+    pub fn accumulate(&self, base: f64, temperatures: &[f64]) -> f64 {
+        temperatures.iter().map(|&t| self.daily(base, t)).sum()
+    }
+}
+
+/// Option on the degree days accumulated over a season at one station.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherOption {
+    pub index: DegreeDayIndex,
+    /// Base temperature, in the units of the temperature series
+    pub base: f64,
+    pub option_type: OptionType,
+    /// Strike in degree days
+    pub strike: f64,
+    /// Payout per degree day beyond the strike
+    pub tick: f64,
+    /// Largest payout, as most listed and OTC contracts have
+    pub cap: Option<f64>,
+    /// Days in the season, starting the day after the pricing date, when the contract is
+    /// valued as an `Instrument`
+    pub season_days: Option<usize>,
+}
+
+/// Past seasons' payouts of a contract and their discounted mean.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurnAnalysis {
+    /// Payout in each season, in the order given
+    pub payouts: Vec<f64>,
+    pub price: f64,
+    /// Sample standard deviation of the payouts, undiscounted
+    pub std_dev: f64,
+}
+
+impl WeatherOption {
+    /// Uncapped option paying `tick` per degree day beyond `strike`
+    pub fn new(
+        index: DegreeDayIndex,
+        base: f64,
+        option_type: OptionType,
+        strike: f64,
+        tick: f64,
+    ) -> Self {
+        WeatherOption {
+            index,
+            base,
+            option_type,
+            strike,
+            tick,
+            cap: None,
+            season_days: None,
+        }
+    }
+    pub fn with_cap(self, cap: f64) -> Self {
+        WeatherOption {
+            cap: Some(cap),
+            ..self
+        }
+    }
+    pub fn with_season(self, days: usize) -> Self {
+        WeatherOption {
+            season_days: Some(days),
+            ..self
+        }
+    }
+    /// Formula: min(tick · max(φ(I - K), 0), cap)
+    pub fn payout(&self, degree_days: f64) -> f64 {
+        let phi = match self.option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let payout = self.tick * (phi * (degree_days - self.strike)).max(0.0);
+        self.cap.map_or(payout, |cap| payout.min(cap))
+    }
+    /// Payout over the daily temperatures of a season
+    pub fn season_payout(&self, temperatures: &[f64]) -> f64 {
+        self.payout(self.index.accumulate(self.base, temperatures))
+    }
+    /// Burn analysis over the daily temperatures of past seasons, the mean payout discounted at
+    /// `rate` over `maturity`. Fails without a season
+    pub fn burn_analysis(
+        &self,
+        seasons: &[Vec<f64>],
+        rate: f64,
+        maturity: f64,
+    ) -> Result<BurnAnalysis, PricingError> {
+        if seasons.is_empty() {
+            return Err(PricingError::InvalidInput(
+                "burn analysis needs at least one season".to_string(),
+            ));
+        }
+        let payouts: Vec<f64> = seasons.iter().map(|s| self.season_payout(s)).collect();
+        let n = payouts.len() as f64;
+        let mean = payouts.iter().sum::<f64>() / n;
+        let variance = if payouts.len() > 1 {
+            payouts.iter().map(|p| (p - mean) * (p - mean)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Ok(BurnAnalysis {
+            payouts,
+            price: exp(-rate * maturity) * mean,
+            std_dev: sqrt(variance),
+        })
+    }
+}
+
+/// A station's temperature model with the weather observed on the pricing date, day `today` of
+/// the model's history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherMarket {
+    pub model: TemperatureModel,
+    pub today: usize,
+    /// Today's temperature less the seasonal mean
+    pub anomaly: f64,
+}
+
+/// Daily average temperature as a seasonal mean plus a mean-reverting anomaly, with day 0 the
+/// first day of the history it was fitted to.
+///
+/// Formula: T(d) = a + b d/365 + c sin(2πd/365) + e cos(2πd/365) + X(d), dX = -κX dt + σ dW
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureModel {
+    pub level: f64,
+    /// Warming per year
+    pub trend: f64,
+    pub sine: f64,
+    pub cosine: f64,
+    /// Mean reversion of the anomaly per year
+    pub kappa: f64,
+    /// Annualized volatility of the anomaly
+    pub sigma: f64,
+}
+
+impl TemperatureModel {
+    /// Fits the seasonal mean by least squares and the anomaly as an AR(1) of the residuals,
+    /// from daily averages over at least two years. Fails on a shorter history or residuals that
+    /// do not revert
+    pub fn fit(daily: &[f64]) -> Result<Self, PricingError> {
+        if daily.len() < 2 * CALENDAR_DAYS_PER_YEAR as usize {
+            return Err(PricingError::InvalidInput(format!(
+                "{} days of temperatures are too few to fit a season, two years are needed",
+                daily.len()
+            )));
+        }
+        let basis = |day: f64| {
+            let phase = 2.0 * PI * day / CALENDAR_DAYS_PER_YEAR;
+            [1.0, day / CALENDAR_DAYS_PER_YEAR, sin(phase), cos(phase)]
+        };
+        let mut normal = vec![vec![0.0; 4]; 4];
+        let mut rhs = vec![0.0; 4];
+        for (day, &temperature) in daily.iter().enumerate() {
+            let x = basis(day as f64);
+            for i in 0..4 {
+                rhs[i] += x[i] * temperature;
+                for j in 0..4 {
+                    normal[i][j] += x[i] * x[j];
+                }
+            }
+        }
+        let factor = cholesky(&normal).ok_or_else(|| {
+            PricingError::InvalidInput("temperature history cannot fit a season".to_string())
+        })?;
+        let c = cholesky_solve(&factor, &rhs);
+        let mut model = TemperatureModel {
+            level: c[0],
+            trend: c[1],
+            sine: c[2],
+            cosine: c[3],
+            kappa: 0.0,
+            sigma: 0.0,
+        };
+        let residuals: Vec<f64> = daily
+            .iter()
+            .enumerate()
+            .map(|(day, &t)| t - model.seasonal_mean(day as f64))
+            .collect();
+        let (lagged, cross) = residuals.windows(2).fold((0.0, 0.0), |(lagged, cross), w| {
+            (lagged + w[0] * w[0], cross + w[0] * w[1])
+        });
+        let persistence = cross / lagged;
+        if !(persistence > 0.0 && persistence < 1.0) {
+            return Err(PricingError::InvalidInput(format!(
+                "temperature anomalies with daily persistence {} do not mean revert",
+                persistence
+            )));
+        }
+        let shocks = residuals
+            .windows(2)
+            .map(|w| powi(w[1] - persistence * w[0], 2))
+            .sum::<f64>()
+            / (residuals.len() - 2) as f64;
+        // the exact OU step over a day has variance σ²(1 - φ²) / 2κ
+        model.kappa = -ln(persistence) * CALENDAR_DAYS_PER_YEAR;
+        model.sigma = sqrt(shocks * 2.0 * model.kappa / (1.0 - persistence * persistence));
+        Ok(model)
+    }
+    /// Seasonal mean temperature on day `day` of the fitted history, fractional days allowed
+    pub fn seasonal_mean(&self, day: f64) -> f64 {
+        let phase = 2.0 * PI * day / CALENDAR_DAYS_PER_YEAR;
+        self.level
+            + self.trend * day / CALENDAR_DAYS_PER_YEAR
+            + self.sine * sin(phase)
+            + self.cosine * cos(phase)
+    }
+    /// Simulated value of `option` on a season of `days` days starting the day after `today`,
+    /// with today's anomaly `anomaly` over the seasonal mean, discounted at `rate` to the
+    /// season's end. `engine`'s path count and seed are used with one step a day
+    pub fn price(
+        &self,
+        option: &WeatherOption,
+        engine: &MonteCarloEngine,
+        today: usize,
+        anomaly: f64,
+        days: usize,
+        rate: f64,
+    ) -> McResult {
+        let anomalies = OrnsteinUhlenbeck {
+            initial: anomaly,
+            kappa: self.kappa,
+            theta: 0.0,
+            sigma: self.sigma,
+        };
+        let daily = MonteCarloEngine::new(McConfig {
+            steps: days,
+            ..engine.config
+        });
+        let season = days as f64 / CALENDAR_DAYS_PER_YEAR;
+        daily.price_process(&anomalies, rate, season, |path: &[f64]| {
+            let degree_days: f64 = path[1..]
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| {
+                    let mean = self.seasonal_mean((today + 1 + i) as f64);
+                    option.index.daily(option.base, mean + x)
+                })
+                .sum();
+            option.payout(degree_days)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_degree_day_options() {
+        let hdd = WeatherOption::new(DegreeDayIndex::Heating, 65.0, OptionType::Call, 100.0, 20.0)
+            .with_cap(1000.0);
+        let season = [60.0, 50.0, 70.0, 30.0];
+        assert_eq!(hdd.index.accumulate(65.0, &season), 55.0);
+        assert_eq!(DegreeDayIndex::Cooling.accumulate(65.0, &season), 5.0);
+        assert_eq!(hdd.payout(130.0), 600.0);
+        assert_eq!(hdd.payout(500.0), 1000.0);
+        assert_eq!(hdd.payout(90.0), 0.0);
+
+        // two cold seasons and a mild one
+        let seasons = vec![vec![30.0; 5], vec![40.0; 5], vec![55.0; 5]];
+        let burn = hdd.burn_analysis(&seasons, 0.0, 0.5).unwrap();
+        assert_eq!(burn.payouts, vec![1000.0, 500.0, 0.0]);
+        assert_eq!(burn.price, 500.0);
+        assert_eq!(burn.std_dev, 500.0);
+        assert!(hdd.burn_analysis(&[], 0.0, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_temperature_model() {
+        let truth = TemperatureModel {
+            level: 55.0,
+            trend: 0.0,
+            sine: 5.0,
+            cosine: -20.0,
+            kappa: 60.0,
+            sigma: 60.0,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let persistence = (-truth.kappa / 365.0).exp();
+        let step_sd =
+            truth.sigma * ((1.0 - persistence * persistence) / (2.0 * truth.kappa)).sqrt();
+        let mut anomaly = 0.0;
+        let history: Vec<f64> = (0..20 * 365)
+            .map(|day| {
+                anomaly = persistence * anomaly
+                    + step_sd * rng.sample::<f64, _>(rand_distr::StandardNormal);
+                truth.seasonal_mean(day as f64) + anomaly
+            })
+            .collect();
+        let model = TemperatureModel::fit(&history).unwrap();
+        assert!(
+            (model.level - 55.0).abs() < 1.0
+                && (model.cosine + 20.0).abs() < 0.5
+                && model.trend.abs() < 0.2
+        );
+        assert!((model.kappa / 60.0 - 1.0).abs() < 0.1);
+        assert!((model.sigma / 60.0 - 1.0).abs() < 0.05);
+        assert!(TemperatureModel::fit(&history[..400]).is_err());
+
+        // a January HDD call priced on the model agrees with burn analysis of past Januaries
+        let hdd = WeatherOption::new(DegreeDayIndex::Heating, 65.0, OptionType::Call, 900.0, 1.0);
+        let engine = MonteCarloEngine::new(McConfig::new(20_000, 1, 3));
+        let today = 20 * 365 - 1;
+        let simulated = model.price(&hdd, &engine, today, 0.0, 31, 0.0);
+        let seasons: Vec<Vec<f64>> = (0..19)
+            .map(|year| history[year * 365..year * 365 + 31].to_vec())
+            .collect();
+        let burn = hdd.burn_analysis(&seasons, 0.0, 0.0).unwrap();
+        assert!(simulated.price > 0.0);
+        assert!((simulated.price - burn.price).abs() < 3.0 * burn.std_dev / 19f64.sqrt());
+    }
+}
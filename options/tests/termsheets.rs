@@ -0,0 +1,81 @@
+// Prices every termsheet under tests/fixtures/termsheets and checks it against the expected
+// values stored next to it. To contribute a validated product, add `<name>.json` holding a
+// termsheet and `<name>.expected.json` mapping measure names (price, delta, gamma, vega, theta,
+// rho) to their reference values, with an optional absolute `tolerance` (default 1e-6).
+use options::termsheet::Termsheet;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/termsheets");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.ends_with(".json") && !name.ends_with(".expected.json")
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn read_json(path: &Path) -> Value {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+    serde_json::from_str(&text).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+// Checks one fixture, returning a description of every mismatching measure
+fn check(path: &Path) -> Vec<String> {
+    let termsheet: Termsheet = serde_json::from_value(read_json(path))
+        .unwrap_or_else(|err| panic!("{}: invalid termsheet: {}", path.display(), err));
+    let expected_path = path.with_extension("expected.json");
+    let Value::Object(mut expected) = read_json(&expected_path) else {
+        panic!(
+            "{}: expected values must be an object",
+            expected_path.display()
+        );
+    };
+    let tolerance = expected
+        .remove("tolerance")
+        .and_then(|value| value.as_f64())
+        .unwrap_or(DEFAULT_TOLERANCE);
+    let name = path.file_stem().unwrap().to_string_lossy();
+    compare(&name, &termsheet, &expected, tolerance)
+}
+
+fn compare(
+    name: &str,
+    termsheet: &Termsheet,
+    expected: &Map<String, Value>,
+    tolerance: f64,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    for (measure, value) in expected {
+        let Some(reference) = value.as_f64() else {
+            failures.push(format!("{}: {} is not a number", name, measure));
+            continue;
+        };
+        match termsheet.measure(measure) {
+            Some(actual) if (actual - reference).abs() <= tolerance => {}
+            Some(actual) => failures.push(format!(
+                "{}: {} = {} but expected {} ± {}",
+                name, measure, actual, reference, tolerance
+            )),
+            None => failures.push(format!("{}: unsupported measure {}", name, measure)),
+        }
+    }
+    failures
+}
+
+#[test]
+fn test_termsheet_fixtures() {
+    let paths = fixtures();
+    assert!(!paths.is_empty(), "no termsheet fixtures found");
+    let failures: Vec<String> = paths.iter().flat_map(|path| check(path)).collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
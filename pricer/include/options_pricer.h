@@ -0,0 +1,169 @@
+#ifndef OPTIONS_PRICER_H
+#define OPTIONS_PRICER_H
+
+/* Generated by cbindgen from pricer/src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Version of the C ABI, bumped whenever a signature or struct layout changes
+ */
+#define OP_ABI_VERSION 1
+
+/**
+ * Result of every FFI call; anything but `OP_STATUS_OK` leaves the out-pointers untouched.
+ */
+typedef enum {
+  OP_STATUS_OK = 0,
+  OP_STATUS_NULL_POINTER = 1,
+  OP_STATUS_INVALID_INPUT = 2,
+  OP_STATUS_NO_CONVERGENCE = 3,
+  /**
+   * The engine panicked; this is a bug and should be reported
+   */
+  OP_STATUS_INTERNAL = 4,
+} OpStatus;
+
+/**
+ * Side of a contract passed across the boundary, as the value of `OpContract::option_type`.
+ */
+typedef enum {
+  OP_OPTION_TYPE_CALL = 0,
+  OP_OPTION_TYPE_PUT = 1,
+} OpOptionType;
+
+/**
+ * A vanilla contract; rates and yields are continuously compounded and `expiry` is in years.
+ */
+typedef struct {
+  /**
+   * An `OpOptionType` value. Kept as a plain integer because hosts can store any value in it,
+   * which an enum field could not soundly hold; anything else is rejected as invalid input
+   */
+  uint32_t option_type;
+  double strike;
+  double spot;
+  double volatility;
+  double rate;
+  double expiry;
+  double dividend_yield;
+} OpContract;
+
+/**
+ * Black-Scholes Greeks, with theta per year and vega and rho per unit move.
+ */
+typedef struct {
+  double delta;
+  double gamma;
+  double vega;
+  double theta;
+  double rho;
+} OpGreeks;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * ABI version the library was built with, to check against `OP_ABI_VERSION` at load time
+ */
+uint32_t op_abi_version(void);
+
+/**
+ * Black-Scholes price of a call, with `dividend_yield` zero for none
+ *
+ * # Safety
+ * `out` must be null or point to a writable `double`.
+ */
+OpStatus op_price_call(double strike,
+                       double spot,
+                       double volatility,
+                       double rate,
+                       double expiry,
+                       double dividend_yield,
+                       double *out);
+
+/**
+ * Black-Scholes price of a put, with `dividend_yield` zero for none
+ *
+ * # Safety
+ * `out` must be null or point to a writable `double`.
+ */
+OpStatus op_price_put(double strike,
+                      double spot,
+                      double volatility,
+                      double rate,
+                      double expiry,
+                      double dividend_yield,
+                      double *out);
+
+/**
+ * Black-Scholes price of `contract`
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+ */
+OpStatus op_price(const OpContract *contract, double *out);
+
+/**
+ * Price of `contract` with early exercise on the default binomial tree
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+ */
+OpStatus op_american_price(const OpContract *contract, double *out);
+
+/**
+ * Black-Scholes Greeks of `contract`
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `OpGreeks`.
+ */
+OpStatus op_greeks(const OpContract *contract, OpGreeks *out);
+
+/**
+ * Black-Scholes implied volatility of `price`; the contract's own volatility is ignored
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+ */
+OpStatus op_implied_vol(const OpContract *contract, double price, double *out);
+
+/**
+ * Bachelier price of `contract`, its volatility read in price units
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+ */
+OpStatus op_bachelier_price(const OpContract *contract, double *out);
+
+/**
+ * Bachelier Greeks of `contract`, vega per unit of normal vol
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `OpGreeks`.
+ */
+OpStatus op_bachelier_greeks(const OpContract *contract, OpGreeks *out);
+
+/**
+ * Bachelier implied normal volatility of `price`; the contract's own volatility is ignored
+ *
+ * # Safety
+ * `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+ */
+OpStatus op_bachelier_implied_vol(const OpContract *contract, double price, double *out);
+
+/**
+ * Static, NUL-terminated description of `status`; never free the returned pointer
+ */
+const char *op_status_message(int status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OPTIONS_PRICER_H */
@@ -0,0 +1,423 @@
+// C ABI for embedding the pricers in C, C++ and C# hosts. Every entry point validates its inputs,
+// writes results through out-pointers and reports failures as status codes; panics never cross
+// the boundary. The header in `include/options_pricer.h` is generated from this file by cbindgen.
+use options::bachelier::implied_volatility;
+use options::error::PricingError;
+use options::lattice::binomial::BinomialTree;
+use options::{OptionType, Options};
+use std::ffi::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Version of the C ABI, bumped whenever a signature or struct layout changes
+pub const OP_ABI_VERSION: u32 = 1;
+
+/// Result of every FFI call; anything but `OP_STATUS_OK` leaves the out-pointers untouched.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidInput = 2,
+    NoConvergence = 3,
+    /// The engine panicked; this is a bug and should be reported
+    Internal = 4,
+}
+
+/// Side of a contract passed across the boundary, as the value of `OpContract::option_type`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpOptionType {
+    Call = 0,
+    Put = 1,
+}
+
+/// A vanilla contract; rates and yields are continuously compounded and `expiry` is in years.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpContract {
+    /// An `OpOptionType` value. Kept as a plain integer because hosts can store any value in it,
+    /// which an enum field could not soundly hold; anything else is rejected as invalid input
+    pub option_type: u32,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    pub rate: f64,
+    pub expiry: f64,
+    pub dividend_yield: f64,
+}
+
+/// Black-Scholes Greeks, with theta per year and vega and rho per unit move.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OpGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+impl From<&PricingError> for OpStatus {
+    fn from(err: &PricingError) -> Self {
+        match err {
+            PricingError::InvalidInput(_) => OpStatus::InvalidInput,
+            PricingError::NoConvergence { .. } => OpStatus::NoConvergence,
+        }
+    }
+}
+
+impl OpContract {
+    // The contract as an `Options`, rejecting inputs the closed forms are undefined for. The
+    // volatility is only checked when the call prices with it
+    fn to_options(self, needs_volatility: bool) -> Result<Options, OpStatus> {
+        let positive = [self.strike, self.spot, self.expiry];
+        let finite = [self.rate, self.dividend_yield];
+        if positive.iter().any(|x| !(x.is_finite() && *x > 0.0))
+            || finite.iter().any(|x| !x.is_finite())
+            || (needs_volatility && !(self.volatility.is_finite() && self.volatility > 0.0))
+        {
+            return Err(OpStatus::InvalidInput);
+        }
+        let option_type = match self.option_type {
+            t if t == OpOptionType::Call as u32 => OptionType::Call,
+            t if t == OpOptionType::Put as u32 => OptionType::Put,
+            _ => return Err(OpStatus::InvalidInput),
+        };
+        let volatility = if needs_volatility {
+            self.volatility
+        } else {
+            0.2
+        };
+        let dividend_yield = (self.dividend_yield != 0.0).then_some(self.dividend_yield);
+        Ok(Options::new_with_type(
+            option_type,
+            self.strike,
+            self.spot,
+            volatility,
+            self.rate,
+            self.expiry,
+            dividend_yield,
+        ))
+    }
+}
+
+// Runs `compute` behind a panic guard and writes its result to `out` on success
+fn guarded<T>(out: *mut T, compute: impl FnOnce() -> Result<T, OpStatus>) -> OpStatus {
+    if out.is_null() {
+        return OpStatus::NullPointer;
+    }
+    match panic::catch_unwind(AssertUnwindSafe(compute)) {
+        Ok(Ok(value)) => {
+            // SAFETY: `out` is non-null and callers guarantee it points to writable memory
+            unsafe { out.write(value) };
+            OpStatus::Ok
+        }
+        Ok(Err(status)) => status,
+        Err(_) => OpStatus::Internal,
+    }
+}
+
+// Reads a contract the caller passed by pointer
+unsafe fn read_contract(contract: *const OpContract) -> Result<OpContract, OpStatus> {
+    // SAFETY: forwarded from the entry points' contracts
+    unsafe { contract.as_ref() }
+        .copied()
+        .ok_or(OpStatus::NullPointer)
+}
+
+/// ABI version the library was built with, to check against `OP_ABI_VERSION` at load time
+#[unsafe(no_mangle)]
+pub extern "C" fn op_abi_version() -> u32 {
+    OP_ABI_VERSION
+}
+
+/// Black-Scholes price of a call, with `dividend_yield` zero for none
+///
+/// # Safety
+/// `out` must be null or point to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_price_call(
+    strike: f64,
+    spot: f64,
+    volatility: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: f64,
+    out: *mut f64,
+) -> OpStatus {
+    let contract = OpContract {
+        option_type: OpOptionType::Call as u32,
+        strike,
+        spot,
+        volatility,
+        rate,
+        expiry,
+        dividend_yield,
+    };
+    guarded(out, || Ok(contract.to_options(true)?.bs_pricing()))
+}
+
+/// Black-Scholes price of a put, with `dividend_yield` zero for none
+///
+/// # Safety
+/// `out` must be null or point to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_price_put(
+    strike: f64,
+    spot: f64,
+    volatility: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: f64,
+    out: *mut f64,
+) -> OpStatus {
+    let contract = OpContract {
+        option_type: OpOptionType::Put as u32,
+        strike,
+        spot,
+        volatility,
+        rate,
+        expiry,
+        dividend_yield,
+    };
+    guarded(out, || Ok(contract.to_options(true)?.bs_pricing()))
+}
+
+/// Black-Scholes price of `contract`
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_price(contract: *const OpContract, out: *mut f64) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(true)?;
+        Ok(option.bs_pricing())
+    })
+}
+
+/// Price of `contract` with early exercise on the default binomial tree
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_american_price(contract: *const OpContract, out: *mut f64) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(true)?;
+        Ok(BinomialTree::default().american_price(&option))
+    })
+}
+
+/// Black-Scholes Greeks of `contract`
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `OpGreeks`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_greeks(contract: *const OpContract, out: *mut OpGreeks) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(true)?;
+        Ok(OpGreeks {
+            delta: option.delta(),
+            gamma: option.gamma(),
+            vega: option.vega(),
+            theta: option.theta(),
+            rho: option.rho(),
+        })
+    })
+}
+
+/// Black-Scholes implied volatility of `price`; the contract's own volatility is ignored
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_implied_vol(
+    contract: *const OpContract,
+    price: f64,
+    out: *mut f64,
+) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(false)?;
+        option
+            .implied_volatility(price)
+            .map_err(|err| OpStatus::from(&err))
+    })
+}
+
+/// Bachelier price of `contract`, its volatility read in price units
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_bachelier_price(
+    contract: *const OpContract,
+    out: *mut f64,
+) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(true)?;
+        Ok(option.bachelier().price())
+    })
+}
+
+/// Bachelier Greeks of `contract`, vega per unit of normal vol
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `OpGreeks`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_bachelier_greeks(
+    contract: *const OpContract,
+    out: *mut OpGreeks,
+) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let normal = unsafe { read_contract(contract) }?
+            .to_options(true)?
+            .bachelier();
+        Ok(OpGreeks {
+            delta: normal.delta(),
+            gamma: normal.gamma(),
+            vega: normal.vega(),
+            theta: normal.theta(),
+            rho: normal.rho(),
+        })
+    })
+}
+
+/// Bachelier implied normal volatility of `price`; the contract's own volatility is ignored
+///
+/// # Safety
+/// `contract` must be null or point to a valid `OpContract`, and `out` to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn op_bachelier_implied_vol(
+    contract: *const OpContract,
+    price: f64,
+    out: *mut f64,
+) -> OpStatus {
+    guarded(out, || {
+        // SAFETY: upheld by the caller
+        let option = unsafe { read_contract(contract) }?.to_options(false)?;
+        implied_volatility(&option, price).map_err(|err| OpStatus::from(&err))
+    })
+}
+
+/// Static, NUL-terminated description of `status`; never free the returned pointer
+#[unsafe(no_mangle)]
+pub extern "C" fn op_status_message(status: c_int) -> *const c_char {
+    let message: &'static [u8] = match status {
+        0 => b"ok\0",
+        1 => b"null pointer argument\0",
+        2 => b"invalid input\0",
+        3 => b"solver did not converge\0",
+        4 => b"internal error\0",
+        _ => b"unknown status\0",
+    };
+    message.as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    #[test]
+    fn test_status_codes_instead_of_panics() {
+        let mut price = f64::NAN;
+        let status = unsafe { op_price_call(100.0, 100.0, 0.2, 0.05, 1.0, 0.0, &mut price) };
+        assert_eq!(status, OpStatus::Ok);
+        assert!((price - 10.450584).abs() < 1e-6);
+
+        let mut contract = OpContract {
+            option_type: OpOptionType::Put as u32,
+            strike: 100.0,
+            spot: 100.0,
+            volatility: 0.2,
+            rate: 0.05,
+            expiry: 1.0,
+            dividend_yield: 0.0,
+        };
+        let mut greeks = OpGreeks::default();
+        assert_eq!(unsafe { op_greeks(&contract, &mut greeks) }, OpStatus::Ok);
+        assert!((greeks.delta + 0.363169).abs() < 1e-6);
+
+        let mut vol = 0.0;
+        let put_price = 5.573526;
+        assert_eq!(
+            unsafe { op_implied_vol(&contract, put_price, &mut vol) },
+            OpStatus::Ok
+        );
+        assert!((vol - 0.2).abs() < 1e-6);
+        assert_eq!(
+            unsafe { op_implied_vol(&contract, 200.0, &mut vol) },
+            OpStatus::InvalidInput
+        );
+
+        // a side the enum has no variant for
+        let mut untouched = 7.0;
+        contract.option_type = 7;
+        assert_eq!(
+            unsafe { op_price(&contract, &mut untouched) },
+            OpStatus::InvalidInput
+        );
+        contract.option_type = OpOptionType::Put as u32;
+
+        contract.expiry = -1.0;
+        assert_eq!(
+            unsafe { op_price(&contract, &mut untouched) },
+            OpStatus::InvalidInput
+        );
+        assert_eq!(untouched, 7.0);
+        assert_eq!(
+            unsafe { op_price(ptr::null(), &mut untouched) },
+            OpStatus::NullPointer
+        );
+        assert_eq!(
+            unsafe { op_greeks(&contract, ptr::null_mut()) },
+            OpStatus::NullPointer
+        );
+        let message = unsafe { CStr::from_ptr(op_status_message(OpStatus::InvalidInput as c_int)) };
+        assert_eq!(message.to_str().unwrap(), "invalid input");
+    }
+
+    #[test]
+    fn test_bachelier_entry_points() {
+        let mut contract = OpContract {
+            option_type: OpOptionType::Call as u32,
+            strike: 100.0,
+            spot: 100.0,
+            volatility: 20.0,
+            rate: 0.0,
+            expiry: 1.0,
+            dividend_yield: 0.0,
+        };
+        // at the money with no carry the normal price is σ√T / √(2π)
+        let mut price = f64::NAN;
+        assert_eq!(
+            unsafe { op_bachelier_price(&contract, &mut price) },
+            OpStatus::Ok
+        );
+        assert!((price - 20.0 / (2.0 * std::f64::consts::PI).sqrt()).abs() < 1e-12);
+        let mut greeks = OpGreeks::default();
+        assert_eq!(
+            unsafe { op_bachelier_greeks(&contract, &mut greeks) },
+            OpStatus::Ok
+        );
+        assert!((greeks.delta - 0.5).abs() < 1e-12);
+        let mut vol = 0.0;
+        assert_eq!(
+            unsafe { op_bachelier_implied_vol(&contract, price, &mut vol) },
+            OpStatus::Ok
+        );
+        assert!((vol - 20.0).abs() < 1e-7);
+
+        contract.volatility = f64::NAN;
+        assert_eq!(
+            unsafe { op_bachelier_price(&contract, &mut price) },
+            OpStatus::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Facade over the workspace crates, and the only entry point covered by semver.
+//!
+//! Everything re-exported from [`prelude`] and the stable modules below follows semantic
+//! versioning: breaking changes only land in a new major version (a new minor version while
+//! below 1.0). Modules reached through [`unstable`] require the `unstable` feature and may
+//! change in any release. Internal crate layout is not part of the API, so prefer these paths
+//! over depending on the workspace members directly.
+
+pub use options::prelude;
+
+pub use options::{
+    analytics, bachelier, black_scholes, bonds, bounds, calendar, calibration_report, chain, clock,
+    commodity, conventions, correlation, credit, crypto, curves, decay, display, engine, error,
+    exotics, explain, generic, lattice, margin, market, math, moneyness,
+};
+pub use options::{
+    exposure, fourier, futures, hedging, instrument, market_data, numerics, parity, payoff,
+    perpetual, portfolio, quote, rates, risk_slide, scenario, selection, settlement, solver, stale,
+    strategy, structured, swing, validation, var, vol,
+};
+
+#[cfg(feature = "decimal")]
+pub use options::decimal;
+#[cfg(feature = "io")]
+pub use options::fpml;
+#[cfg(feature = "io")]
+pub use options::io;
+#[cfg(feature = "mc")]
+pub use options::mc;
+#[cfg(feature = "mc")]
+pub use options::model_risk;
+#[cfg(feature = "mc")]
+pub use options::multi_asset;
+#[cfg(feature = "mc")]
+pub use options::processes;
+#[cfg(feature = "report")]
+pub use options::report;
+#[cfg(feature = "mc")]
+pub use options::rng;
+#[cfg(feature = "serde")]
+pub use options::termsheet;
+#[cfg(feature = "mc")]
+pub use options::weather;
+
+/// Experimental models whose API may change in any release.
+#[cfg(feature = "unstable")]
+pub mod unstable {
+    #[cfg(all(feature = "mc", feature = "calibration"))]
+    pub use options::local_vol;
+    #[cfg(feature = "rough_vol")]
+    pub use options::rough_vol;
+    #[cfg(feature = "calibration")]
+    pub use options::vol::density;
+}
+
+#[cfg(feature = "server")]
+pub use server;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
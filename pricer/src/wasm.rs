@@ -0,0 +1,150 @@
+// JavaScript bindings over the closed-form pricers. Only this module knows about wasm-bindgen;
+// the pricing crates stay target-independent.
+use options::{OptionType, Options};
+use wasm_bindgen::prelude::*;
+
+/// A vanilla contract built from JavaScript numbers.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Contract {
+    inner: Options,
+}
+
+/// Black-Scholes Greeks of a contract, exposed as plain numeric fields.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+fn option_type(kind: &str) -> Option<OptionType> {
+    match kind.to_ascii_lowercase().as_str() {
+        "call" => Some(OptionType::Call),
+        "put" => Some(OptionType::Put),
+        _ => None,
+    }
+}
+
+#[wasm_bindgen]
+impl Contract {
+    /// `kind` is "call" or "put"; `expiry` is in years and `dividend_yield` may be undefined
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        kind: &str,
+        strike: f64,
+        spot: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        expiry: f64,
+        dividend_yield: Option<f64>,
+    ) -> Result<Contract, JsError> {
+        let option_type = option_type(kind)
+            .ok_or_else(|| JsError::new(&format!("unknown option kind {}", kind)))?;
+        for (name, value) in [
+            ("strike", strike),
+            ("spot", spot),
+            ("volatility", volatility),
+            ("expiry", expiry),
+        ] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(JsError::new(&format!("{} must be positive", name)));
+            }
+        }
+        Ok(Contract {
+            inner: Options::new_with_type(
+                option_type,
+                strike,
+                spot,
+                volatility,
+                risk_free_rate,
+                expiry,
+                dividend_yield,
+            ),
+        })
+    }
+    pub fn price(&self) -> f64 {
+        self.inner.bs_pricing()
+    }
+    /// Price with early exercise on the default binomial tree
+    #[wasm_bindgen(js_name = americanPrice)]
+    pub fn american_price(&self) -> f64 {
+        options::lattice::binomial::BinomialTree::default().american_price(&self.inner)
+    }
+    pub fn greeks(&self) -> Greeks {
+        Greeks {
+            delta: self.inner.delta(),
+            gamma: self.inner.gamma(),
+            vega: self.inner.vega(),
+            theta: self.inner.theta(),
+            rho: self.inner.rho(),
+        }
+    }
+    /// Volatility reproducing `price`; the contract's own volatility is ignored
+    #[wasm_bindgen(js_name = impliedVolatility)]
+    pub fn implied_volatility(&self, price: f64, american: bool) -> Result<f64, JsError> {
+        let solved = if american {
+            self.inner.american_implied_volatility(price)
+        } else {
+            self.inner.implied_volatility(price)
+        };
+        solved.map_err(|err| JsError::new(&err.to_string()))
+    }
+    /// Bachelier price, the contract's volatility read in price units
+    #[wasm_bindgen(js_name = bachelierPrice)]
+    pub fn bachelier_price(&self) -> f64 {
+        self.inner.bachelier().price()
+    }
+    /// Bachelier Greeks, vega per unit of normal vol
+    #[wasm_bindgen(js_name = bachelierGreeks)]
+    pub fn bachelier_greeks(&self) -> Greeks {
+        let normal = self.inner.bachelier();
+        Greeks {
+            delta: normal.delta(),
+            gamma: normal.gamma(),
+            vega: normal.vega(),
+            theta: normal.theta(),
+            rho: normal.rho(),
+        }
+    }
+    /// Normal volatility reproducing `price`; the contract's own volatility is ignored
+    #[wasm_bindgen(js_name = bachelierImpliedVolatility)]
+    pub fn bachelier_implied_volatility(&self, price: f64) -> Result<f64, JsError> {
+        options::bachelier::implied_volatility(&self.inner, price)
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+    /// Same contract at another spot, for redrawing charts as inputs move
+    #[wasm_bindgen(js_name = withSpot)]
+    pub fn with_spot(&self, spot: f64) -> Contract {
+        let inner = &self.inner;
+        Contract {
+            inner: inner.with_market(spot, inner.volatility(), inner.risk_free_rate()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_bindings() {
+        let call = Contract::new("Call", 100.0, 100.0, 0.2, 0.05, 1.0, None).unwrap();
+        assert!((call.price() - 10.450584).abs() < 1e-6);
+        assert!((call.greeks().delta - 0.636831).abs() < 1e-6);
+        let iv = call.implied_volatility(call.price(), false).unwrap();
+        assert!((iv - 0.2).abs() < 1e-8);
+        assert!(call.with_spot(110.0).price() > call.price());
+        assert_eq!(option_type("straddle"), None);
+
+        let normal = Contract::new("call", 100.0, 100.0, 20.0, 0.0, 1.0, None).unwrap();
+        let price = normal.bachelier_price();
+        assert!((price - 20.0 / (2.0 * std::f64::consts::PI).sqrt()).abs() < 1e-12);
+        assert!((normal.bachelier_greeks().delta - 0.5).abs() < 1e-12);
+        let vol = normal.bachelier_implied_volatility(price).unwrap();
+        assert!((vol - 20.0).abs() < 1e-7);
+    }
+}
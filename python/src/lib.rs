@@ -0,0 +1,551 @@
+// Python bindings: `import options_pricer_py` after `maturin develop` in this directory.
+use numpy::{PyArray1, PyReadonlyArray1};
+use options::bachelier::PricingModel;
+use options::error::PricingError;
+use options::lattice::binomial::BinomialTree;
+use options::{OptionType, Options};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+fn value_error(err: PricingError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn option_type(kind: &str) -> PyResult<OptionType> {
+    match kind.to_ascii_lowercase().as_str() {
+        "call" => Ok(OptionType::Call),
+        "put" => Ok(OptionType::Put),
+        _ => Err(PyValueError::new_err(format!(
+            "kind must be 'call' or 'put', got {:?}",
+            kind
+        ))),
+    }
+}
+
+// The contract, or a ValueError naming the first input outside its domain
+fn contract(
+    option_type: OptionType,
+    strike: f64,
+    spot: f64,
+    volatility: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: Option<f64>,
+) -> PyResult<Options> {
+    for (name, value) in [
+        ("strike", strike),
+        ("spot", spot),
+        ("volatility", volatility),
+        ("expiry", expiry),
+    ] {
+        if !(value.is_finite() && value > 0.0) {
+            return Err(PyValueError::new_err(format!(
+                "{} must be positive and finite, got {}",
+                name, value
+            )));
+        }
+    }
+    for (name, value) in [
+        ("rate", rate),
+        ("dividend_yield", dividend_yield.unwrap_or(0.0)),
+    ] {
+        if !value.is_finite() {
+            return Err(PyValueError::new_err(format!(
+                "{} must be finite, got {}",
+                name, value
+            )));
+        }
+    }
+    Ok(Options::new_with_type(
+        option_type,
+        strike,
+        spot,
+        volatility,
+        rate,
+        expiry,
+        dividend_yield,
+    ))
+}
+
+// "black_scholes" or "bachelier"; under the latter volatilities are in price units
+fn pricing_model(model: &str) -> PyResult<PricingModel> {
+    match model.to_ascii_lowercase().as_str() {
+        "black_scholes" => Ok(PricingModel::BlackScholes),
+        "bachelier" => Ok(PricingModel::Bachelier),
+        _ => Err(PyValueError::new_err(format!(
+            "model must be 'black_scholes' or 'bachelier', got {:?}",
+            model
+        ))),
+    }
+}
+
+type Greek = fn(&Options) -> f64;
+
+fn black_scholes_greeks() -> [(&'static str, Greek); 5] {
+    [
+        ("delta", Options::delta),
+        ("gamma", Options::gamma),
+        ("vega", Options::vega),
+        ("theta", Options::theta),
+        ("rho", Options::rho),
+    ]
+}
+
+fn bachelier_greeks() -> [(&'static str, Greek); 5] {
+    [
+        ("delta", |option| option.bachelier().delta()),
+        ("gamma", |option| option.bachelier().gamma()),
+        ("vega", |option| option.bachelier().vega()),
+        ("theta", |option| option.bachelier().theta()),
+        ("rho", |option| option.bachelier().rho()),
+    ]
+}
+
+fn model_greeks(model: PricingModel) -> [(&'static str, Greek); 5] {
+    match model {
+        PricingModel::Bachelier => bachelier_greeks(),
+        _ => black_scholes_greeks(),
+    }
+}
+
+fn greeks_of(option: &Options, model: PricingModel) -> HashMap<&'static str, f64> {
+    model_greeks(model)
+        .into_iter()
+        .map(|(name, greek)| (name, greek(option)))
+        .collect()
+}
+
+// Methods shared by the Call and Put classes, which only differ in their option type
+macro_rules! vanilla_class {
+    ($name:ident, $py_name:literal, $option_type:expr) => {
+        #[pyclass(name = $py_name, frozen, skip_from_py_object)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name {
+            inner: Options,
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = (strike, spot, volatility, rate, expiry, dividend_yield=None))]
+            fn new(
+                strike: f64,
+                spot: f64,
+                volatility: f64,
+                rate: f64,
+                expiry: f64,
+                dividend_yield: Option<f64>,
+            ) -> PyResult<Self> {
+                Ok($name {
+                    inner: contract(
+                        $option_type,
+                        strike,
+                        spot,
+                        volatility,
+                        rate,
+                        expiry,
+                        dividend_yield,
+                    )?,
+                })
+            }
+            /// Price under `model`, "black_scholes" or "bachelier"
+            #[pyo3(signature = (model="black_scholes"))]
+            fn price(&self, model: &str) -> PyResult<f64> {
+                Ok(self.inner.model_price(pricing_model(model)?))
+            }
+            /// Price with early exercise on a binomial tree
+            fn american_price(&self) -> f64 {
+                BinomialTree::default().american_price(&self.inner)
+            }
+            /// Dict of delta, gamma, vega, theta and rho under `model`
+            #[pyo3(signature = (model="black_scholes"))]
+            fn greeks(&self, model: &str) -> PyResult<HashMap<&'static str, f64>> {
+                Ok(greeks_of(&self.inner, pricing_model(model)?))
+            }
+            /// Volatility reproducing `price` under `model`; the contract's own volatility is
+            /// ignored. American inversion is Black-Scholes on a tree
+            #[pyo3(signature = (price, american=false, model="black_scholes"))]
+            fn implied_vol(&self, price: f64, american: bool, model: &str) -> PyResult<f64> {
+                let model = pricing_model(model)?;
+                if american {
+                    if model != PricingModel::BlackScholes {
+                        return Err(PyValueError::new_err(
+                            "american implied vols are only solved under black_scholes",
+                        ));
+                    }
+                    self.inner.american_implied_volatility(price)
+                } else {
+                    self.inner.model_implied_volatility(model, price)
+                }
+                .map_err(value_error)
+            }
+            #[getter]
+            fn strike(&self) -> f64 {
+                self.inner.strike_price()
+            }
+            #[getter]
+            fn spot(&self) -> f64 {
+                self.inner.spot_price()
+            }
+            #[getter]
+            fn volatility(&self) -> f64 {
+                self.inner.volatility()
+            }
+            #[getter]
+            fn expiry(&self) -> f64 {
+                self.inner.time_to_maturity()
+            }
+            fn __repr__(&self) -> String {
+                format!(
+                    "{}(strike={}, spot={}, volatility={}, rate={}, expiry={})",
+                    $py_name,
+                    self.inner.strike_price(),
+                    self.inner.spot_price(),
+                    self.inner.volatility(),
+                    self.inner.risk_free_rate(),
+                    self.inner.time_to_maturity()
+                )
+            }
+        }
+    };
+}
+
+vanilla_class!(Call, "Call", OptionType::Call);
+vanilla_class!(Put, "Put", OptionType::Put);
+
+/// Price of a `kind` ("call" or "put") contract under `model`
+#[pyfunction]
+#[pyo3(signature = (kind, strike, spot, volatility, rate, expiry, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn price(
+    kind: &str,
+    strike: f64,
+    spot: f64,
+    volatility: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<f64> {
+    let model = pricing_model(model)?;
+    let option_type = option_type(kind)?;
+    let option = contract(
+        option_type,
+        strike,
+        spot,
+        volatility,
+        rate,
+        expiry,
+        dividend_yield,
+    )?;
+    Ok(option.model_price(model))
+}
+
+/// Dict of Greeks of a `kind` contract under `model`
+#[pyfunction]
+#[pyo3(signature = (kind, strike, spot, volatility, rate, expiry, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn greeks(
+    kind: &str,
+    strike: f64,
+    spot: f64,
+    volatility: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<HashMap<&'static str, f64>> {
+    let model = pricing_model(model)?;
+    let option_type = option_type(kind)?;
+    let option = contract(
+        option_type,
+        strike,
+        spot,
+        volatility,
+        rate,
+        expiry,
+        dividend_yield,
+    )?;
+    Ok(greeks_of(&option, model))
+}
+
+/// Implied volatility of a quoted premium under `model`
+#[pyfunction]
+#[pyo3(signature = (kind, price, strike, spot, rate, expiry, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn implied_vol(
+    kind: &str,
+    price: f64,
+    strike: f64,
+    spot: f64,
+    rate: f64,
+    expiry: f64,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<f64> {
+    let model = pricing_model(model)?;
+    let option_type = option_type(kind)?;
+    contract(option_type, strike, spot, 0.2, rate, expiry, dividend_yield)?
+        .model_implied_volatility(model, price)
+        .map_err(value_error)
+}
+
+// Contracts of one kind from equally long input columns, failing on the first invalid row
+fn column_options(
+    kind: &str,
+    columns: [&[f64]; 5],
+    dividend_yield: Option<f64>,
+) -> PyResult<Vec<Options>> {
+    let option_type = option_type(kind)?;
+    let [strikes, spots, vols, rates, expiries] = columns;
+    let n = strikes.len();
+    if columns.iter().any(|column| column.len() != n) {
+        return Err(PyValueError::new_err(
+            "input arrays must have equal lengths",
+        ));
+    }
+    (0..n)
+        .map(|i| {
+            contract(
+                option_type,
+                strikes[i],
+                spots[i],
+                vols[i],
+                rates[i],
+                expiries[i],
+                dividend_yield,
+            )
+        })
+        .collect()
+}
+
+/// Prices arrays of contracts of one kind under `model`, returning a NumPy array of prices
+#[pyfunction]
+#[pyo3(signature = (kind, strikes, spots, volatilities, rates, expiries, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn price_batch<'py>(
+    py: Python<'py>,
+    kind: &str,
+    strikes: PyReadonlyArray1<'py, f64>,
+    spots: PyReadonlyArray1<'py, f64>,
+    volatilities: PyReadonlyArray1<'py, f64>,
+    rates: PyReadonlyArray1<'py, f64>,
+    expiries: PyReadonlyArray1<'py, f64>,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let model = pricing_model(model)?;
+    let contracts = column_options(
+        kind,
+        [
+            strikes.as_slice()?,
+            spots.as_slice()?,
+            volatilities.as_slice()?,
+            rates.as_slice()?,
+            expiries.as_slice()?,
+        ],
+        dividend_yield,
+    )?;
+    let prices: Vec<f64> = py.detach(|| {
+        contracts
+            .iter()
+            .map(|option| option.model_price(model))
+            .collect()
+    });
+    Ok(PyArray1::from_vec(py, prices))
+}
+
+/// Greeks of arrays of contracts under `model`, as a dict of NumPy arrays keyed by Greek name
+#[pyfunction]
+#[pyo3(signature = (kind, strikes, spots, volatilities, rates, expiries, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn greeks_batch<'py>(
+    py: Python<'py>,
+    kind: &str,
+    strikes: PyReadonlyArray1<'py, f64>,
+    spots: PyReadonlyArray1<'py, f64>,
+    volatilities: PyReadonlyArray1<'py, f64>,
+    rates: PyReadonlyArray1<'py, f64>,
+    expiries: PyReadonlyArray1<'py, f64>,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<HashMap<&'static str, Bound<'py, PyArray1<f64>>>> {
+    let model = pricing_model(model)?;
+    let contracts = column_options(
+        kind,
+        [
+            strikes.as_slice()?,
+            spots.as_slice()?,
+            volatilities.as_slice()?,
+            rates.as_slice()?,
+            expiries.as_slice()?,
+        ],
+        dividend_yield,
+    )?;
+    Ok(model_greeks(model)
+        .into_iter()
+        .map(|(name, greek)| {
+            let values: Vec<f64> = py.detach(|| contracts.iter().map(greek).collect());
+            (name, PyArray1::from_vec(py, values))
+        })
+        .collect())
+}
+
+/// Implied volatilities of arrays of quoted premiums under `model`; NaN where a premium has no solution
+#[pyfunction]
+#[pyo3(signature = (kind, prices, strikes, spots, rates, expiries, dividend_yield=None, model="black_scholes"))]
+#[allow(clippy::too_many_arguments)]
+fn implied_vol_batch<'py>(
+    py: Python<'py>,
+    kind: &str,
+    prices: PyReadonlyArray1<'py, f64>,
+    strikes: PyReadonlyArray1<'py, f64>,
+    spots: PyReadonlyArray1<'py, f64>,
+    rates: PyReadonlyArray1<'py, f64>,
+    expiries: PyReadonlyArray1<'py, f64>,
+    dividend_yield: Option<f64>,
+    model: &str,
+) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let prices = prices.as_slice()?;
+    // the solver ignores the contracts' volatility
+    let unused_vols = vec![0.2; prices.len()];
+    let model = pricing_model(model)?;
+    let contracts = column_options(
+        kind,
+        [
+            strikes.as_slice()?,
+            spots.as_slice()?,
+            &unused_vols,
+            rates.as_slice()?,
+            expiries.as_slice()?,
+        ],
+        dividend_yield,
+    )?;
+    let vols: Vec<f64> = py.detach(|| {
+        contracts
+            .iter()
+            .zip(prices)
+            .map(|(option, &price)| {
+                option
+                    .model_implied_volatility(model, price)
+                    .unwrap_or(f64::NAN)
+            })
+            .collect()
+    });
+    Ok(PyArray1::from_vec(py, vols))
+}
+
+#[pymodule]
+fn options_pricer_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Call>()?;
+    m.add_class::<Put>()?;
+    m.add_function(wrap_pyfunction!(price, m)?)?;
+    m.add_function(wrap_pyfunction!(greeks, m)?)?;
+    m.add_function(wrap_pyfunction!(implied_vol, m)?)?;
+    m.add_function(wrap_pyfunction!(price_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(greeks_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(implied_vol_batch, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_entry_points() {
+        let call = price("call", 100.0, 100.0, 0.2, 0.05, 1.0, None, "black_scholes").unwrap();
+        assert!((call - 10.450584).abs() < 1e-6);
+        let vol =
+            implied_vol("CALL", call, 100.0, 100.0, 0.05, 1.0, None, "black_scholes").unwrap();
+        assert!((vol - 0.2).abs() < 1e-8);
+        let put = Put::new(100.0, 100.0, 0.2, 0.05, 1.0, None).unwrap();
+        assert!((put.greeks("black_scholes").unwrap()["delta"] + 0.363169).abs() < 1e-6);
+        assert!(
+            price(
+                "straddle",
+                100.0,
+                100.0,
+                0.2,
+                0.05,
+                1.0,
+                None,
+                "black_scholes"
+            )
+            .is_err()
+        );
+
+        let columns: [&[f64]; 5] = [
+            &[100.0, 110.0],
+            &[100.0, 100.0],
+            &[0.2],
+            &[0.05; 2],
+            &[1.0; 2],
+        ];
+        assert!(column_options("put", columns, None).is_err());
+    }
+
+    #[test]
+    fn test_bachelier_model() {
+        // at the money with no carry the normal price is σ√T / √(2π)
+        let expected = 20.0 / (2.0 * std::f64::consts::PI).sqrt();
+        let normal = price("call", 100.0, 100.0, 20.0, 0.0, 1.0, None, "bachelier").unwrap();
+        assert!((normal - expected).abs() < 1e-12);
+        let call = Call::new(100.0, 100.0, 20.0, 0.0, 1.0, None).unwrap();
+        assert_eq!(call.price("bachelier").unwrap(), normal);
+        assert!((call.greeks("bachelier").unwrap()["delta"] - 0.5).abs() < 1e-12);
+        let vol = call.implied_vol(normal, false, "bachelier").unwrap();
+        assert!((vol - 20.0).abs() < 1e-7);
+        assert!(call.implied_vol(normal, true, "bachelier").is_err());
+        let vol = implied_vol("call", normal, 100.0, 100.0, 0.0, 1.0, None, "Bachelier").unwrap();
+        assert!((vol - 20.0).abs() < 1e-7);
+        let greeks = greeks("put", 100.0, 100.0, 20.0, 0.0, 1.0, None, "bachelier").unwrap();
+        assert!((greeks["delta"] + 0.5).abs() < 1e-12);
+        assert!(price("call", 100.0, 100.0, 0.2, 0.0, 1.0, None, "heston").is_err());
+    }
+
+    #[test]
+    fn test_domain_validation() {
+        assert!(Call::new(-100.0, 100.0, 0.2, 0.05, 1.0, None).is_err());
+        assert!(Put::new(100.0, 100.0, 0.0, 0.05, 1.0, None).is_err());
+        assert!(Call::new(100.0, 100.0, 0.2, f64::NAN, 1.0, None).is_err());
+        assert!(
+            price(
+                "call",
+                100.0,
+                f64::NAN,
+                0.2,
+                0.05,
+                1.0,
+                None,
+                "black_scholes"
+            )
+            .is_err()
+        );
+        assert!(greeks("put", 100.0, 100.0, 0.2, 0.05, 0.0, None, "black_scholes").is_err());
+        assert!(
+            greeks(
+                "put",
+                100.0,
+                100.0,
+                0.2,
+                0.05,
+                1.0,
+                Some(f64::INFINITY),
+                "black_scholes"
+            )
+            .is_err()
+        );
+        assert!(implied_vol("call", 10.0, 100.0, -1.0, 0.05, 1.0, None, "black_scholes").is_err());
+
+        let columns: [&[f64]; 5] = [
+            &[100.0, 110.0],
+            &[100.0, 100.0],
+            &[0.2, f64::NAN],
+            &[0.05; 2],
+            &[1.0; 2],
+        ];
+        assert!(column_options("call", columns, None).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+use crate::validation::{RequestLimits, ValidationError};
+use options::lattice::binomial::BinomialTree;
+use options::mc::{McConfig, MonteCarloEngine};
+use options::{OptionType, Options};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// Paths simulated when a Monte Carlo request does not specify them
+pub const DEFAULT_PATHS: usize = 50_000;
+/// Time steps per path when a Monte Carlo request does not specify them
+pub const DEFAULT_STEPS: usize = 1;
+
+/// Pricing engines a client can select per item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    #[default]
+    BlackScholes,
+    /// Normal model, the volatility read in price units
+    Bachelier,
+    /// American exercise on a binomial tree
+    Binomial,
+    MonteCarlo,
+}
+
+impl Engine {
+    /// Name used in engine whitelists and metric labels
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::BlackScholes => "black_scholes",
+            Engine::Bachelier => "bachelier",
+            Engine::Binomial => "binomial",
+            Engine::MonteCarlo => "monte_carlo",
+        }
+    }
+    /// Prices a contract, returning the Monte Carlo standard error when the engine has one.
+    ///
+    /// Unset simulation parameters fall back to the engine defaults
+    pub fn price(
+        &self,
+        option: &Options,
+        paths: Option<usize>,
+        steps: Option<usize>,
+        seed: Option<u64>,
+    ) -> (f64, Option<f64>) {
+        match self {
+            Engine::BlackScholes => (option.bs_pricing(), None),
+            Engine::Bachelier => (option.bachelier().price(), None),
+            Engine::Binomial => {
+                let tree = steps.map(BinomialTree::new).unwrap_or_default();
+                (tree.american_price(option), None)
+            }
+            Engine::MonteCarlo => {
+                let config = McConfig::new(
+                    paths.unwrap_or(DEFAULT_PATHS),
+                    steps.unwrap_or(DEFAULT_STEPS),
+                    seed.unwrap_or(0),
+                );
+                let result = MonteCarloEngine::new(config).price_european(option);
+                (result.price, Some(result.std_error))
+            }
+        }
+    }
+}
+
+/// One contract of a batch request, sent as one NDJSON line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchItem {
+    pub id: String,
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    pub risk_free_rate: f64,
+    /// Time to expiry in years
+    pub expiry: f64,
+    #[serde(default)]
+    pub dividend_yield: Option<f64>,
+    #[serde(default)]
+    pub engine: Engine,
+    #[serde(default)]
+    pub paths: Option<usize>,
+    #[serde(default)]
+    pub steps: Option<usize>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl BatchItem {
+    pub fn to_option(&self) -> Options {
+        Options::new_with_type(
+            self.option_type,
+            self.strike,
+            self.spot,
+            self.volatility,
+            self.risk_free_rate,
+            self.expiry,
+            self.dividend_yield,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Ok,
+    /// The line could not be parsed or failed validation
+    Rejected,
+    /// The engine failed to produce a price
+    Failed,
+}
+
+/// Outcome of one batch item, streamed back as one NDJSON line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Zero-based line number of the item in the request body
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub status: ItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub std_error: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    fn rejected(line: usize, id: Option<String>, error: String) -> Self {
+        BatchResult {
+            line,
+            id,
+            status: ItemStatus::Rejected,
+            price: None,
+            std_error: None,
+            error: Some(error),
+        }
+    }
+    pub fn to_ndjson(&self) -> String {
+        serde_json::to_string(self).expect("batch result is always serializable")
+    }
+}
+
+/// Prices NDJSON batches item by item so results can be streamed while the rest is computed.
+#[derive(Debug, Clone, Default)]
+pub struct BatchPricer {
+    pub limits: RequestLimits,
+}
+
+impl BatchPricer {
+    pub fn new(limits: RequestLimits) -> Self {
+        BatchPricer { limits }
+    }
+    fn validate(&self, item: &BatchItem) -> Result<(), ValidationError> {
+        self.limits.check_engine(item.engine.name())?;
+        self.limits.check_contract(
+            item.strike,
+            item.spot,
+            item.volatility,
+            item.risk_free_rate,
+            item.expiry,
+        )?;
+        if item.engine == Engine::MonteCarlo {
+            self.limits.check_simulation(
+                item.paths.unwrap_or(DEFAULT_PATHS),
+                item.steps.unwrap_or(DEFAULT_STEPS),
+            )?;
+        }
+        if let Some(steps) = item.steps
+            && item.engine == Engine::Binomial
+        {
+            self.limits.check_simulation(0, steps)?;
+        }
+        Ok(())
+    }
+    /// Validates and prices a single item
+    pub fn price_item(&self, line: usize, item: &BatchItem) -> BatchResult {
+        let id = Some(item.id.clone());
+        if let Err(err) = self.validate(item) {
+            return BatchResult::rejected(line, id, err.to_string());
+        }
+        let (price, std_error) =
+            item.engine
+                .price(&item.to_option(), item.paths, item.steps, item.seed);
+        if !price.is_finite() {
+            return BatchResult {
+                line,
+                id,
+                status: ItemStatus::Failed,
+                price: None,
+                std_error: None,
+                error: Some(format!(
+                    "{} returned a non-finite price",
+                    item.engine.name()
+                )),
+            };
+        }
+        BatchResult {
+            line,
+            id,
+            status: ItemStatus::Ok,
+            price: Some(price),
+            std_error,
+            error: None,
+        }
+    }
+    /// Lazily prices every non-blank line of an NDJSON body.
+    ///
+    /// Items past the batch limit are rejected individually instead of failing the whole batch
+    pub fn stream<'a>(&'a self, body: &'a str) -> impl Iterator<Item = BatchResult> + 'a {
+        body.lines()
+            .enumerate()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .enumerate()
+            .map(move |(index, (line, text))| {
+                let item: BatchItem = match serde_json::from_str(text) {
+                    Ok(item) => item,
+                    Err(err) => return BatchResult::rejected(line, None, err.to_string()),
+                };
+                if let Err(err) = self.limits.check_batch(index + 1) {
+                    return BatchResult::rejected(line, Some(item.id), err.to_string());
+                }
+                self.price_item(line, &item)
+            })
+    }
+    /// Writes one NDJSON result line per item, flushing after each so clients see progress
+    pub fn write_ndjson<W: Write>(&self, body: &str, mut out: W) -> io::Result<usize> {
+        let mut count = 0;
+        for result in self.stream(body) {
+            writeln!(out, "{}", result.to_ndjson())?;
+            out.flush()?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_per_item_status() {
+        let limits = RequestLimits {
+            max_batch: 4,
+            ..RequestLimits::default()
+        }
+        .with_engines(&["black_scholes", "binomial"]);
+        let pricer = BatchPricer::new(limits);
+        let body = r#"{"id":"a","option_type":"call","strike":100,"spot":105,"volatility":0.2,"risk_free_rate":0.05,"expiry":1}
+not json
+
+{"id":"b","option_type":"put","strike":100,"spot":100,"volatility":0.2,"risk_free_rate":0.05,"expiry":1,"engine":"monte_carlo"}
+{"id":"c","option_type":"put","strike":100,"spot":100,"volatility":0.2,"risk_free_rate":0.05,"expiry":1,"engine":"binomial"}
+{"id":"d","option_type":"put","strike":100,"spot":100,"volatility":0.2,"risk_free_rate":0.05,"expiry":1}"#;
+        let results: Vec<BatchResult> = pricer.stream(body).collect();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].status, ItemStatus::Ok);
+        assert!((results[0].price.unwrap() - 13.8579).abs() < 1e-4);
+        assert_eq!(results[1].status, ItemStatus::Rejected);
+        assert_eq!(results[1].line, 1);
+        // engine not whitelisted
+        assert_eq!(results[2].status, ItemStatus::Rejected);
+        assert_eq!(results[2].id.as_deref(), Some("b"));
+        assert_eq!(results[3].status, ItemStatus::Ok);
+        // fifth line is over the batch limit
+        assert_eq!(results[4].status, ItemStatus::Rejected);
+
+        let mut out = Vec::new();
+        assert_eq!(pricer.write_ndjson(body, &mut out).unwrap(), 5);
+        let text = String::from_utf8(out).unwrap();
+        let first: BatchResult = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first, results[0]);
+
+        let bachelier = r#"{"id":"n","option_type":"call","strike":100,"spot":100,"volatility":20,"risk_free_rate":0.0,"expiry":1,"engine":"bachelier"}"#;
+        let result = BatchPricer::default().stream(bachelier).next().unwrap();
+        // at the money the normal price is σ√T / √(2π)
+        let expected = 20.0 / (2.0 * std::f64::consts::PI).sqrt();
+        assert!((result.price.unwrap() - expected).abs() < 1e-9);
+        assert_eq!(
+            pricer.stream(bachelier).next().unwrap().status,
+            ItemStatus::Rejected
+        );
+    }
+}
@@ -0,0 +1,860 @@
+// REST front-end: JSON endpoints over the pricing library, rate limited and instrumented.
+use crate::batch::{BatchPricer, DEFAULT_PATHS, DEFAULT_STEPS, Engine};
+#[cfg(feature = "jobs")]
+use crate::jobs::{Job, JobError, JobStore, RISK_JOB, RiskJob};
+use crate::metrics::Metrics;
+use crate::rate_limit::RateLimiter;
+use crate::validation::{RequestLimits, ValidationError};
+use axum::body::Body;
+#[cfg(feature = "jobs")]
+use axum::extract::Path;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::stream;
+use options::Options;
+use options::bachelier::PricingModel;
+use options::error::PricingError;
+use options::portfolio::Portfolio;
+use options::quote::OptionQuote;
+use options::report::GreeksRecord;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "jobs")]
+use serde_json::Value;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Header identifying the client for rate limiting. Only keys the server was configured with are
+/// honoured; other requests are keyed by peer address
+pub const CLIENT_HEADER: &str = "x-api-key";
+// Priced batch lines held for a slow client before pricing waits for it
+const BATCH_BUFFER: usize = 64;
+
+/// State shared by every request handler.
+pub struct AppState {
+    pub metrics: Metrics,
+    pub limiter: RateLimiter,
+    pub batch: BatchPricer,
+    /// API keys that get their own rate limit bucket
+    pub api_keys: BTreeSet<String>,
+    /// Queue behind the `/jobs` endpoints, which are only routed when a store is attached
+    #[cfg(feature = "jobs")]
+    pub jobs: Option<JobStore>,
+}
+
+impl AppState {
+    pub fn new(limits: RequestLimits, limiter: RateLimiter) -> Self {
+        AppState {
+            metrics: Metrics::new(),
+            limiter,
+            batch: BatchPricer::new(limits),
+            api_keys: BTreeSet::new(),
+            #[cfg(feature = "jobs")]
+            jobs: None,
+        }
+    }
+    pub fn with_api_keys<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        self.api_keys = keys.into_iter().map(Into::into).collect();
+        self
+    }
+    #[cfg(feature = "jobs")]
+    pub fn with_jobs(mut self, store: JobStore) -> Self {
+        self.jobs = Some(store);
+        self
+    }
+    fn limits(&self) -> &RequestLimits {
+        &self.batch.limits
+    }
+    // Rate limit key: a known API key, else the peer address
+    fn client(&self, request: &Request) -> String {
+        let key = request
+            .headers()
+            .get(CLIENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|key| self.api_keys.contains(*key));
+        match key {
+            Some(key) => format!("key:{}", key),
+            None => request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+                .unwrap_or_else(|| "anonymous".to_string()),
+        }
+    }
+    fn check_contract(&self, option: &Options) -> Result<(), ValidationError> {
+        self.limits().check_contract(
+            option.strike_price(),
+            option.spot_price(),
+            option.volatility(),
+            option.risk_free_rate(),
+            option.time_to_maturity(),
+        )
+    }
+    // Every option, stock and cash line counts towards the batch limit; quantities and amounts
+    // only need to be finite, since books are long and short
+    fn check_portfolio(&self, portfolio: &Portfolio) -> Result<(), ValidationError> {
+        let lines = portfolio.positions().len() + portfolio.stocks().len() + portfolio.cash().len();
+        self.limits().check_batch(lines)?;
+        let finite = |field, value: f64| {
+            if value.is_finite() {
+                Ok(())
+            } else {
+                Err(ValidationError::InvalidField(field))
+            }
+        };
+        for position in portfolio.positions() {
+            self.check_contract(&position.option)?;
+            finite("quantity", position.quantity)?;
+            if !(position.multiplier > 0.0 && position.multiplier.is_finite()) {
+                return Err(ValidationError::InvalidField("multiplier"));
+            }
+        }
+        for stock in portfolio.stocks() {
+            if !(stock.spot > 0.0 && stock.spot.is_finite()) {
+                return Err(ValidationError::InvalidField("spot"));
+            }
+            finite("quantity", stock.quantity)?;
+        }
+        for cash in portfolio.cash() {
+            finite("amount", cash.amount)?;
+            finite("risk_free_rate", cash.risk_free_rate)?;
+            if !(cash.maturity >= 0.0 && cash.maturity.is_finite()) {
+                return Err(ValidationError::InvalidField("maturity"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Failure of a request, rendered as a JSON body `{"error": ..., "kind": ...}`.
+#[derive(Debug)]
+pub enum ApiError {
+    Validation(ValidationError),
+    Pricing(PricingError),
+    /// The request body is well-formed JSON but not a request this server understands
+    BadRequest(String),
+    NotFound(String),
+    /// The client exhausted its rate limit and may retry after the given delay
+    RateLimited(Duration),
+    Internal(String),
+}
+
+impl ApiError {
+    /// Label of the error in the `pricing_errors_total` metric
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::Validation(_) => "validation",
+            ApiError::Pricing(PricingError::InvalidInput(_)) => "invalid_input",
+            ApiError::Pricing(PricingError::NoConvergence { .. }) => "no_convergence",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Validation(_) | ApiError::Pricing(PricingError::InvalidInput(_)) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Pricing(PricingError::NoConvergence { .. }) | ApiError::Internal(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Validation(err) => write!(f, "{}", err),
+            ApiError::Pricing(err) => write!(f, "{}", err),
+            ApiError::BadRequest(reason) => write!(f, "bad request: {}", reason),
+            ApiError::NotFound(what) => write!(f, "{} not found", what),
+            ApiError::RateLimited(wait) => {
+                write!(
+                    f,
+                    "rate limit exceeded, retry in {:.3}s",
+                    wait.as_secs_f64()
+                )
+            }
+            ApiError::Internal(reason) => write!(f, "internal error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ValidationError> for ApiError {
+    fn from(err: ValidationError) -> Self {
+        ApiError::Validation(err)
+    }
+}
+
+impl From<PricingError> for ApiError {
+    fn from(err: PricingError) -> Self {
+        ApiError::Pricing(err)
+    }
+}
+
+#[cfg(feature = "jobs")]
+impl From<JobError> for ApiError {
+    fn from(err: JobError) -> Self {
+        match err {
+            JobError::NotFound(id) => ApiError::NotFound(format!("job {}", id)),
+            other => ApiError::Internal(other.to_string()),
+        }
+    }
+}
+
+// Attached to error responses so the middleware can count them by kind
+#[derive(Debug, Clone, Copy)]
+struct ErrorKind(&'static str);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let kind = self.kind();
+        let body = Json(json!({ "error": self.to_string(), "kind": kind }));
+        let mut response = (self.status(), body).into_response();
+        if let ApiError::RateLimited(wait) = self {
+            let seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response.extensions_mut().insert(ErrorKind(kind));
+        response
+    }
+}
+
+// Runs CPU-bound pricing off the async workers
+async fn blocking<T, F>(work: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|err| ApiError::Internal(err.to_string()))?
+}
+
+/// Body of `POST /price`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceRequest {
+    pub contract: Options,
+    #[serde(default)]
+    pub engine: Engine,
+    #[serde(default)]
+    pub paths: Option<usize>,
+    #[serde(default)]
+    pub steps: Option<usize>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceResponse {
+    pub price: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub std_error: Option<f64>,
+    pub engine: Engine,
+}
+
+/// Body of `POST /greeks`: a contract and the closed-form model its Greeks come from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreeksRequest {
+    #[serde(flatten)]
+    pub contract: Options,
+    /// `black_scholes` or `bachelier`
+    #[serde(default)]
+    pub engine: Engine,
+}
+
+/// Body of `POST /implied-vol`: a quote whose mid is inverted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpliedVolRequest {
+    #[serde(flatten)]
+    pub quote: OptionQuote,
+    /// Invert through the binomial pricer, for American listings
+    #[serde(default)]
+    pub american: bool,
+    /// `black_scholes` or `bachelier` for European listings, the latter giving a normal vol
+    #[serde(default)]
+    pub engine: Engine,
+}
+
+// Closed-form model behind an engine, for the endpoints that have no tree or simulation
+fn closed_form(engine: Engine, endpoint: &str) -> Result<PricingModel, ApiError> {
+    match engine {
+        Engine::BlackScholes => Ok(PricingModel::BlackScholes),
+        Engine::Bachelier => Ok(PricingModel::Bachelier),
+        other => Err(ApiError::BadRequest(format!(
+            "{} is not available for {}",
+            other.name(),
+            endpoint
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpliedVolResponse {
+    pub implied_volatility: f64,
+}
+
+/// Response of `POST /portfolio/value`: value and aggregated Black-Scholes Greeks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioValuation {
+    pub value: f64,
+    #[serde(flatten)]
+    pub greeks: GreeksRecord,
+}
+
+async fn price(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<PriceRequest>,
+) -> Result<Json<PriceResponse>, ApiError> {
+    let limits = state.limits();
+    limits.check_engine(request.engine.name())?;
+    state.check_contract(&request.contract)?;
+    match request.engine {
+        Engine::BlackScholes | Engine::Bachelier => {}
+        Engine::Binomial => limits.check_simulation(0, request.steps.unwrap_or(0))?,
+        Engine::MonteCarlo => limits.check_simulation(
+            request.paths.unwrap_or(DEFAULT_PATHS),
+            request.steps.unwrap_or(DEFAULT_STEPS),
+        )?,
+    }
+    let started = Instant::now();
+    let engine = request.engine;
+    let (price, std_error) = blocking(move || {
+        Ok(engine.price(
+            &request.contract,
+            request.paths,
+            request.steps,
+            request.seed,
+        ))
+    })
+    .await?;
+    state
+        .metrics
+        .observe_latency(engine.name(), started.elapsed());
+    if !price.is_finite() {
+        return Err(ApiError::Internal(format!(
+            "{} returned a non-finite price",
+            engine.name()
+        )));
+    }
+    Ok(Json(PriceResponse {
+        price,
+        std_error,
+        engine,
+    }))
+}
+
+async fn greeks(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<GreeksRequest>,
+) -> Result<Json<GreeksRecord>, ApiError> {
+    let model = closed_form(request.engine, "greeks")?;
+    state.limits().check_engine(request.engine.name())?;
+    state.check_contract(&request.contract)?;
+    let started = Instant::now();
+    let greeks = GreeksRecord::of_model(&request.contract, model);
+    state
+        .metrics
+        .observe_latency(request.engine.name(), started.elapsed());
+    Ok(Json(greeks))
+}
+
+async fn implied_vol(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ImpliedVolRequest>,
+) -> Result<Json<ImpliedVolResponse>, ApiError> {
+    let quote = request.quote;
+    // volatility is the unknown, so only the observed inputs are range-checked
+    state.limits().check_contract(
+        quote.strike,
+        quote.spot,
+        1.0,
+        quote.risk_free_rate,
+        quote.expiry,
+    )?;
+    let model = closed_form(request.engine, "implied volatility")?;
+    let engine = match (request.american, model) {
+        (true, PricingModel::BlackScholes) => Engine::Binomial,
+        (true, _) => {
+            return Err(ApiError::BadRequest(format!(
+                "american quotes cannot be inverted with {}",
+                request.engine.name()
+            )));
+        }
+        (false, _) => request.engine,
+    };
+    state.limits().check_engine(engine.name())?;
+    let started = Instant::now();
+    let implied_volatility = blocking(move || {
+        Ok(if request.american {
+            quote.american_implied_volatility()?
+        } else {
+            quote
+                .to_option(0.2)
+                .model_implied_volatility(model, quote.mid())?
+        })
+    })
+    .await?;
+    state
+        .metrics
+        .observe_latency(engine.name(), started.elapsed());
+    Ok(Json(ImpliedVolResponse { implied_volatility }))
+}
+
+async fn portfolio_value(
+    State(state): State<Arc<AppState>>,
+    Json(portfolio): Json<Portfolio>,
+) -> Result<Json<PortfolioValuation>, ApiError> {
+    state.check_portfolio(&portfolio)?;
+    let started = Instant::now();
+    let valuation = blocking(move || {
+        Ok(PortfolioValuation {
+            value: portfolio.value(),
+            greeks: GreeksRecord {
+                delta: portfolio.delta(),
+                gamma: portfolio.gamma(),
+                vega: portfolio.vega(),
+                theta: portfolio.theta(),
+                rho: portfolio.rho(),
+            },
+        })
+    })
+    .await?;
+    state
+        .metrics
+        .observe_latency(Engine::BlackScholes.name(), started.elapsed());
+    Ok(Json(valuation))
+}
+
+// Streams one NDJSON line per item as soon as it is priced
+async fn batch(State(state): State<Arc<AppState>>, body: String) -> Response {
+    let started = Instant::now();
+    let (sender, receiver) = mpsc::channel::<String>(BATCH_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        for result in state.batch.stream(&body) {
+            if sender.blocking_send(result.to_ndjson() + "\n").is_err() {
+                // the client went away, so the rest of the batch is not worth pricing
+                return;
+            }
+        }
+        state.metrics.observe_latency("batch", started.elapsed());
+    });
+    let lines = stream::unfold(receiver, |mut receiver| async move {
+        let line = receiver.recv().await?;
+        Some((Ok::<_, Infallible>(line), receiver))
+    });
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+/// Body of `POST /jobs`.
+#[cfg(feature = "jobs")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRequest {
+    pub kind: String,
+    pub payload: Value,
+}
+
+#[cfg(feature = "jobs")]
+fn job_store(state: &AppState) -> Result<&JobStore, ApiError> {
+    state
+        .jobs
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("job queue".to_string()))
+}
+
+#[cfg(feature = "jobs")]
+async fn submit_job(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<JobRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    job_store(&state)?;
+    match request.kind.as_str() {
+        RISK_JOB => {
+            let job: RiskJob = serde_json::from_value(request.payload.clone())
+                .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+            job.check(state.limits())?;
+        }
+        other => {
+            return Err(ApiError::BadRequest(format!("unknown job kind {}", other)));
+        }
+    }
+    let worker = state.clone();
+    let (id, depth) = blocking(move || {
+        let store = job_store(&worker)?;
+        let id = store.submit(&request.kind, request.payload)?;
+        Ok((id, store.queue_depth()?))
+    })
+    .await?;
+    state.metrics.set_queue_depth(depth as i64);
+    Ok((StatusCode::ACCEPTED, Json(json!({ "id": id }))))
+}
+
+#[cfg(feature = "jobs")]
+async fn job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<Job>, ApiError> {
+    let job = blocking(move || Ok(job_store(&state)?.get(id)?)).await?;
+    Ok(Json(job))
+}
+
+// Rate limits each client, then counts error responses by kind
+async fn instrument(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let client = state.client(&request);
+    let response = match state.limiter.check(&client) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => ApiError::RateLimited(wait).into_response(),
+    };
+    if let Some(ErrorKind(kind)) = response.extensions().get::<ErrorKind>() {
+        state.metrics.record_error(kind);
+    }
+    response
+}
+
+/// Routes of the pricing API, with rate limiting and metrics applied to every endpoint
+pub fn router(state: Arc<AppState>) -> Router {
+    let router = Router::new()
+        .route("/price", post(price))
+        .route("/greeks", post(greeks))
+        .route("/implied-vol", post(implied_vol))
+        .route("/portfolio/value", post(portfolio_value))
+        .route("/batch", post(batch))
+        .route("/metrics", get(metrics));
+    #[cfg(feature = "jobs")]
+    let router = router
+        .route("/jobs", post(submit_job))
+        .route("/jobs/{id}", get(job));
+    router
+        .layer(middleware::from_fn_with_state(state.clone(), instrument))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use futures_util::StreamExt;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    fn state(burst: u32) -> AppState {
+        AppState::new(
+            RequestLimits::default().with_engines(&["black_scholes", "bachelier", "binomial"]),
+            RateLimiter::new(burst, 0.001).unwrap(),
+        )
+        .with_api_keys(["test"])
+    }
+
+    fn app(burst: u32) -> (Arc<AppState>, Router) {
+        let state = Arc::new(state(burst));
+        (state.clone(), router(state))
+    }
+
+    async fn post_json(router: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+        post_as(router, uri, "test", body).await
+    }
+
+    // Posts from a fixed peer address with the given API key
+    async fn post_as(router: &Router, uri: &str, key: &str, body: Value) -> (StatusCode, Value) {
+        let mut request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(CLIENT_HEADER, key)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let peer: SocketAddr = "192.0.2.1:40000".parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(peer));
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn contract() -> Value {
+        json!({
+            "option_type": "call", "strike_price": 100.0, "spot_price": 100.0, "volatility": 0.2,
+            "risk_free_rate": 0.05, "time_to_maturity": 1.0
+        })
+    }
+
+    #[tokio::test]
+    async fn test_pricing_endpoints() {
+        let (state, router) = app(100);
+        let (status, body) = post_json(&router, "/price", json!({ "contract": contract() })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!((body["price"].as_f64().unwrap() - 10.450584).abs() < 1e-6);
+
+        let (_, body) = post_json(&router, "/greeks", contract()).await;
+        assert!((body["delta"].as_f64().unwrap() - 0.636831).abs() < 1e-6);
+
+        let quote = json!({
+            "option_type": "call", "strike": 100.0, "expiry": 1.0, "bid": 10.4, "ask": 10.5,
+            "spot": 100.0, "risk_free_rate": 0.05, "dividend_yield": null
+        });
+        let (_, body) = post_json(&router, "/implied-vol", quote).await;
+        assert!((body["implied_volatility"].as_f64().unwrap() - 0.2).abs() < 1e-3);
+
+        let book = json!({ "positions": [
+            { "underlying": "SPX", "option": contract(), "quantity": 2.0 },
+        ]});
+        let (_, body) = post_json(&router, "/portfolio/value", book).await;
+        assert!((body["value"].as_f64().unwrap() - 20.901167).abs() < 1e-6);
+        assert!(
+            state
+                .metrics
+                .render()
+                .contains("pricing_request_duration_seconds_count{engine=\"black_scholes\"} 4")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejections_are_counted() {
+        let (state, router) = app(2);
+        let mut bad = contract();
+        bad["volatility"] = json!(-0.2);
+        let (status, body) = post_json(&router, "/greeks", bad).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["kind"], "validation");
+
+        let request = json!({ "contract": contract(), "engine": "monte_carlo" });
+        let (status, _) = post_json(&router, "/price", request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, body) = post_json(&router, "/price", json!({ "contract": contract() })).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["kind"], "rate_limited");
+
+        let text = state.metrics.render();
+        assert!(text.contains("pricing_errors_total{kind=\"validation\"} 2"));
+        assert!(text.contains("pricing_errors_total{kind=\"rate_limited\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_bachelier_engine() {
+        let (state, router) = app(100);
+        let mut normal = contract();
+        normal["volatility"] = json!(20.0);
+        normal["risk_free_rate"] = json!(0.0);
+        let expected = 20.0 / (2.0 * std::f64::consts::PI).sqrt();
+        let request = json!({ "contract": normal, "engine": "bachelier" });
+        let (status, body) = post_json(&router, "/price", request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!((body["price"].as_f64().unwrap() - expected).abs() < 1e-9);
+        assert_eq!(body["engine"], "bachelier");
+
+        let mut request = normal.clone();
+        request["engine"] = json!("bachelier");
+        let (_, body) = post_json(&router, "/greeks", request).await;
+        assert!((body["delta"].as_f64().unwrap() - 0.5).abs() < 1e-12);
+        let mut request = normal.clone();
+        request["engine"] = json!("monte_carlo");
+        let (status, _) = post_json(&router, "/greeks", request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let quote = json!({
+            "option_type": "call", "strike": 100.0, "expiry": 1.0, "bid": expected - 0.05,
+            "ask": expected + 0.05, "spot": 100.0, "risk_free_rate": 0.0,
+            "dividend_yield": null, "engine": "bachelier"
+        });
+        let (_, body) = post_json(&router, "/implied-vol", quote).await;
+        assert!((body["implied_volatility"].as_f64().unwrap() - 20.0).abs() < 1e-6);
+        assert!(
+            state
+                .metrics
+                .render()
+                .contains("pricing_request_duration_seconds_count{engine=\"bachelier\"} 3")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_implied_vol_honours_the_engine_whitelist() {
+        let state = AppState::new(
+            RequestLimits::default().with_engines(&["black_scholes"]),
+            RateLimiter::new(100, 0.001).unwrap(),
+        )
+        .with_api_keys(["test"]);
+        let router = router(Arc::new(state));
+        let quote = |american: bool| {
+            json!({
+                "option_type": "put", "strike": 100.0, "expiry": 1.0, "bid": 6.0, "ask": 6.2,
+                "spot": 100.0, "risk_free_rate": 0.05, "dividend_yield": null,
+                "american": american
+            })
+        };
+        let (status, _) = post_json(&router, "/implied-vol", quote(false)).await;
+        assert_eq!(status, StatusCode::OK);
+        // American quotes are inverted on a tree, which this server does not run
+        let (status, body) = post_json(&router, "/implied-vol", quote(true)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["kind"], "validation");
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_lines_are_all_checked() {
+        use options::portfolio::{CashPosition, Position, StockPosition};
+        let limits = RequestLimits {
+            max_batch: 2,
+            ..RequestLimits::default()
+        };
+        let state =
+            AppState::new(limits, RateLimiter::new(100, 0.001).unwrap()).with_api_keys(["test"]);
+        let option: Options = serde_json::from_value(contract()).unwrap();
+        let book = |quantity: f64, spot: f64, amount: f64| {
+            let mut book = Portfolio::new();
+            book.add(Position::new("SPX", option, quantity));
+            book.add_stock(StockPosition::new("SPX", spot, -100.0));
+            book.add_cash(CashPosition::new(amount, 0.05, 1.0));
+            book
+        };
+        // stock and cash lines count towards the batch limit alongside the options
+        assert!(matches!(
+            state.check_portfolio(&book(2.0, 100.0, 1e4)),
+            Err(ValidationError::BatchTooLarge { .. })
+        ));
+        let state = AppState::new(
+            RequestLimits::default(),
+            RateLimiter::new(100, 0.001).unwrap(),
+        );
+        assert!(state.check_portfolio(&book(2.0, 100.0, 1e4)).is_ok());
+        for (bad, field) in [
+            (book(f64::NAN, 100.0, 1e4), "quantity"),
+            (book(2.0, f64::INFINITY, 1e4), "spot"),
+            (book(2.0, -100.0, 1e4), "spot"),
+            (book(2.0, 100.0, f64::NAN), "amount"),
+        ] {
+            assert!(matches!(
+                state.check_portfolio(&bad),
+                Err(ValidationError::InvalidField(name)) if name == field
+            ));
+        }
+
+        let router = router(Arc::new(state));
+        let book = json!({
+            "positions": [{ "underlying": "SPX", "option": contract(), "quantity": 2.0 }],
+            "stocks": [{ "underlying": "SPX", "spot": 100.0, "quantity": -100.0 }],
+            "cash": [{ "amount": 1e4, "risk_free_rate": 0.05, "maturity": 0.0 }]
+        });
+        let (status, body) = post_json(&router, "/portfolio/value", book).await;
+        assert_eq!(status, StatusCode::OK);
+        let expected = 2.0 * option.bs_pricing() - 1e4 + 1e4;
+        assert!((body["value"].as_f64().unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_batch_streams_lines_as_priced() {
+        let (state, router) = app(100);
+        let item = |id: &str, engine: &str, steps: usize| {
+            json!({
+                "id": id, "option_type": "put", "strike": 100.0, "spot": 100.0, "volatility": 0.2,
+                "risk_free_rate": 0.05, "expiry": 1.0, "engine": engine, "steps": steps
+            })
+            .to_string()
+        };
+        // a quick first item followed by slow deep trees
+        let mut lines = vec![item("fast", "black_scholes", 0)];
+        lines.extend((0..4).map(|i| item(&format!("slow-{}", i), "binomial", 2_000)));
+        let request = Request::post("/batch")
+            .header(CLIENT_HEADER, "test")
+            .body(Body::from(lines.join("\n")))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut body = response.into_body().into_data_stream();
+        let first = body.next().await.unwrap().unwrap();
+        let first: Value = serde_json::from_slice(&first).unwrap();
+        assert_eq!(first["id"], "fast");
+        // the batch latency is only observed once every item is priced
+        let batches = "pricing_request_duration_seconds_count{engine=\"batch\"}";
+        assert!(!state.metrics.render().contains(batches));
+        let mut rest = 0;
+        while let Some(chunk) = body.next().await {
+            let line: Value = serde_json::from_slice(&chunk.unwrap()).unwrap();
+            assert_eq!(line["status"], "ok");
+            rest += 1;
+        }
+        assert_eq!(rest, 4);
+        assert!(state.metrics.render().contains(batches));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_keys_share_the_peer_bucket() {
+        let (_, router) = app(2);
+        let request = || json!({ "contract": contract() });
+        let (status, _) = post_as(&router, "/price", "fresh-1", request()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_as(&router, "/price", "fresh-2", request()).await;
+        assert_eq!(status, StatusCode::OK);
+        // rotating the header does not buy a new bucket
+        let (status, _) = post_as(&router, "/price", "fresh-3", request()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        // a configured key has its own
+        let (status, _) = post_json(&router, "/price", request()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[cfg(feature = "jobs")]
+    #[tokio::test]
+    async fn test_jobs_are_validated_on_submit() {
+        let state = Arc::new(state(100).with_jobs(JobStore::temporary().unwrap()));
+        let router = router(state.clone());
+        let risk = |paths: usize| {
+            json!({ "kind": "risk", "payload": {
+                "positions": [{
+                    "underlying": "SPX", "option_type": "call", "strike": 100.0, "spot": 100.0,
+                    "volatility": 0.2, "risk_free_rate": 0.05, "expiry": 1.0, "quantity": 1.0
+                }],
+                "factor_names": ["SPX"], "covariance": [[0.0004]], "confidence": 0.99,
+                "methods": ["monte_carlo"], "paths": paths
+            }})
+        };
+        let (status, body) = post_json(&router, "/jobs", risk(10_000)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body["id"].is_u64());
+
+        let (status, body) = post_json(&router, "/jobs", risk(1_000_000_000)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["kind"], "validation");
+        let unknown = json!({ "kind": "xva", "payload": {} });
+        let (status, body) = post_json(&router, "/jobs", unknown).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["kind"], "bad_request");
+        let malformed = json!({ "kind": "risk", "payload": { "positions": 3 } });
+        let (status, _) = post_json(&router, "/jobs", malformed).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(state.jobs.as_ref().unwrap().queue_depth().unwrap(), 1);
+    }
+}
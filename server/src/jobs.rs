@@ -0,0 +1,400 @@
+use crate::validation::{RequestLimits, ValidationError};
+use options::OptionType;
+use options::Options;
+use options::error::PricingError;
+use options::portfolio::{Portfolio, Position};
+use options::report::{RiskReport, VarMethod};
+use options::var::RiskFactors;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A submitted job with its payload and, once finished, its outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u64,
+    /// Selects the handler that runs the job
+    pub kind: String,
+    pub payload: Value,
+    pub status: JobStatus,
+    /// Unix timestamps in seconds
+    pub submitted_at: u64,
+    pub finished_at: Option<u64>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum JobError {
+    Storage(sled::Error),
+    Encoding(serde_json::Error),
+    NotFound(u64),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::Storage(err) => write!(f, "job store error: {}", err),
+            JobError::Encoding(err) => write!(f, "job encoding error: {}", err),
+            JobError::NotFound(id) => write!(f, "job {} not found", id),
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
+impl From<sled::Error> for JobError {
+    fn from(err: sled::Error) -> Self {
+        JobError::Storage(err)
+    }
+}
+
+impl From<serde_json::Error> for JobError {
+    fn from(err: serde_json::Error) -> Self {
+        JobError::Encoding(err)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Job queue persisted in a sled database, so submitted work survives process restarts.
+pub struct JobStore {
+    db: sled::Db,
+    jobs: sled::Tree,
+    /// Ids of queued jobs, oldest first, so claiming and counting never decode the whole store
+    queued: sled::Tree,
+}
+
+impl JobStore {
+    /// Opens or creates the store at the given directory.
+    ///
+    /// Jobs left running by a previous process are put back in the queue, and the queue index is
+    /// rebuilt from the jobs themselves
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, JobError> {
+        JobStore::from_db(sled::open(path)?)
+    }
+    /// Store that lives only as long as the process, for tests and ephemeral servers
+    pub fn temporary() -> Result<Self, JobError> {
+        JobStore::from_db(sled::Config::new().temporary(true).open()?)
+    }
+    fn from_db(db: sled::Db) -> Result<Self, JobError> {
+        let store = JobStore {
+            jobs: db.open_tree("jobs")?,
+            queued: db.open_tree("queued")?,
+            db,
+        };
+        store.queued.clear()?;
+        for mut job in store.list()? {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                store.put(&job)?;
+            }
+            if job.status == JobStatus::Queued {
+                store.queued.insert(job.id.to_be_bytes(), &[])?;
+            }
+        }
+        Ok(store)
+    }
+    fn put(&self, job: &Job) -> Result<(), JobError> {
+        self.jobs
+            .insert(job.id.to_be_bytes(), serde_json::to_vec(job)?)?;
+        Ok(())
+    }
+    /// Queues a job and returns its id
+    pub fn submit(&self, kind: &str, payload: Value) -> Result<u64, JobError> {
+        let job = Job {
+            id: self.db.generate_id()?,
+            kind: kind.to_string(),
+            payload,
+            status: JobStatus::Queued,
+            submitted_at: now(),
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        self.put(&job)?;
+        self.queued.insert(job.id.to_be_bytes(), &[])?;
+        self.jobs.flush()?;
+        Ok(job.id)
+    }
+    pub fn get(&self, id: u64) -> Result<Job, JobError> {
+        match self.jobs.get(id.to_be_bytes())? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Err(JobError::NotFound(id)),
+        }
+    }
+    pub fn status(&self, id: u64) -> Result<JobStatus, JobError> {
+        Ok(self.get(id)?.status)
+    }
+    /// Every job in submission order
+    pub fn list(&self) -> Result<Vec<Job>, JobError> {
+        self.jobs
+            .iter()
+            .map(|entry| Ok(serde_json::from_slice(&entry?.1)?))
+            .collect()
+    }
+    /// Number of jobs still waiting, for the queue depth gauge
+    pub fn queue_depth(&self) -> Result<usize, JobError> {
+        Ok(self.queued.len())
+    }
+    /// Marks the oldest queued job as running and returns it
+    pub fn claim_next(&self) -> Result<Option<Job>, JobError> {
+        let Some((key, _)) = self.queued.pop_min()? else {
+            return Ok(None);
+        };
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&key);
+        let mut job = self.get(u64::from_be_bytes(bytes))?;
+        job.status = JobStatus::Running;
+        self.put(&job)?;
+        Ok(Some(job))
+    }
+    /// Records the outcome of a claimed job
+    pub fn finish(&self, id: u64, outcome: Result<Value, String>) -> Result<(), JobError> {
+        let mut job = self.get(id)?;
+        job.finished_at = Some(now());
+        match outcome {
+            Ok(result) => {
+                job.status = JobStatus::Completed;
+                job.result = Some(result);
+            }
+            Err(error) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(error);
+            }
+        }
+        self.put(&job)?;
+        self.jobs.flush()?;
+        Ok(())
+    }
+    /// Claims and runs the next queued job, returning its id if there was one
+    pub fn run_next<F>(&self, handler: F) -> Result<Option<u64>, JobError>
+    where
+        F: FnOnce(&Job) -> Result<Value, String>,
+    {
+        match self.claim_next()? {
+            Some(job) => {
+                let outcome = handler(&job);
+                self.finish(job.id, outcome)?;
+                Ok(Some(job.id))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Kind name of [`RiskJob`] payloads
+pub const RISK_JOB: &str = "risk";
+/// Monte Carlo VaR paths when a risk job does not specify them
+pub const DEFAULT_VAR_PATHS: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobPosition {
+    pub underlying: String,
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub spot: f64,
+    pub volatility: f64,
+    pub risk_free_rate: f64,
+    pub expiry: f64,
+    #[serde(default)]
+    pub dividend_yield: Option<f64>,
+    pub quantity: f64,
+    /// Underlying units per contract
+    #[serde(default = "unit_multiplier")]
+    pub multiplier: f64,
+}
+
+fn unit_multiplier() -> f64 {
+    1.0
+}
+
+/// Portfolio VaR run: prices the book and attaches VaR for each requested method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskJob {
+    pub positions: Vec<JobPosition>,
+    pub factor_names: Vec<String>,
+    /// Either a covariance matrix or a return history (one row per observation) is required
+    #[serde(default)]
+    pub covariance: Option<Vec<Vec<f64>>>,
+    #[serde(default)]
+    pub returns: Option<Vec<Vec<f64>>>,
+    pub confidence: f64,
+    pub methods: Vec<VarMethod>,
+    #[serde(default)]
+    pub paths: Option<usize>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl RiskJob {
+    /// Size and domain checks applied when the job is submitted, so an oversized run is refused
+    /// before it reaches the queue
+    pub fn check(&self, limits: &RequestLimits) -> Result<(), ValidationError> {
+        limits.check_batch(self.positions.len())?;
+        for position in &self.positions {
+            limits.check_contract(
+                position.strike,
+                position.spot,
+                position.volatility,
+                position.risk_free_rate,
+                position.expiry,
+            )?;
+            if !(position.multiplier > 0.0 && position.multiplier.is_finite()) {
+                return Err(ValidationError::InvalidField("multiplier"));
+            }
+        }
+        limits.check_simulation(self.paths.unwrap_or(DEFAULT_VAR_PATHS), 0)
+    }
+    pub fn run(&self) -> Result<RiskReport, PricingError> {
+        let portfolio: Portfolio = self
+            .positions
+            .iter()
+            .map(|position| {
+                let option = Options::new_with_type(
+                    position.option_type,
+                    position.strike,
+                    position.spot,
+                    position.volatility,
+                    position.risk_free_rate,
+                    position.expiry,
+                    position.dividend_yield,
+                );
+                Position::new(&position.underlying, option, position.quantity)
+                    .with_multiplier(position.multiplier)
+            })
+            .collect();
+        let names = self.factor_names.clone();
+        let factors = match (&self.returns, &self.covariance) {
+            (Some(returns), _) => RiskFactors::from_returns(names, returns.clone())?,
+            (None, Some(covariance)) => RiskFactors::new(names, covariance.clone())?,
+            (None, None) => {
+                return Err(PricingError::InvalidInput(
+                    "risk job needs a covariance matrix or return history".to_string(),
+                ));
+            }
+        };
+        let mut report = RiskReport::from_portfolio(&portfolio);
+        for method in &self.methods {
+            let var = match method {
+                VarMethod::Parametric => portfolio.parametric_var(&factors, self.confidence)?,
+                VarMethod::MonteCarlo => portfolio.monte_carlo_var(
+                    &factors,
+                    self.confidence,
+                    self.paths.unwrap_or(DEFAULT_VAR_PATHS),
+                    self.seed.unwrap_or(0),
+                )?,
+                VarMethod::Historical => portfolio.historical_var(&factors, self.confidence)?,
+            };
+            report = report.with_var(*method, &var);
+        }
+        Ok(report)
+    }
+}
+
+/// Handler for the job kinds this crate knows how to run
+pub fn run_job(job: &Job) -> Result<Value, String> {
+    match job.kind.as_str() {
+        RISK_JOB => {
+            let spec: RiskJob =
+                serde_json::from_value(job.payload.clone()).map_err(|err| err.to_string())?;
+            let report = spec.run().map_err(|err| err.to_string())?;
+            serde_json::to_value(report).map_err(|err| err.to_string())
+        }
+        other => Err(format!("unknown job kind {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_jobs_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("options-jobs-{}", std::process::id()));
+        let payload = json!({
+            "positions": [{
+                "underlying": "SPX", "option_type": "call", "strike": 100.0, "spot": 100.0,
+                "volatility": 0.2, "risk_free_rate": 0.05, "expiry": 1.0, "quantity": 10.0
+            }],
+            "factor_names": ["SPX"],
+            "covariance": [[0.0004]],
+            "confidence": 0.99,
+            "methods": ["parametric"]
+        });
+        let (risk, bogus) = {
+            // without sled's background flusher nothing else holds the database, so the file
+            // lock is released as soon as the store drops
+            let config = sled::Config::new().path(&dir).flush_every_ms(None);
+            let store = JobStore::from_db(config.open().unwrap()).unwrap();
+            let risk = store.submit(RISK_JOB, payload).unwrap();
+            let bogus = store.submit("xva", json!({})).unwrap();
+            // simulate a crash while the first job is running
+            store.claim_next().unwrap();
+            assert_eq!(store.status(risk).unwrap(), JobStatus::Running);
+            store.db.flush().unwrap();
+            (risk, bogus)
+        };
+
+        let store = JobStore::open(&dir).unwrap();
+        assert_eq!(store.status(risk).unwrap(), JobStatus::Queued);
+        assert_eq!(store.queue_depth().unwrap(), 2);
+        assert_eq!(store.run_next(run_job).unwrap(), Some(risk));
+        assert_eq!(store.queue_depth().unwrap(), 1);
+        assert_eq!(store.run_next(run_job).unwrap(), Some(bogus));
+        assert_eq!(store.run_next(run_job).unwrap(), None);
+        assert_eq!(store.queue_depth().unwrap(), 0);
+
+        let done = store.get(risk).unwrap();
+        assert_eq!(done.status, JobStatus::Completed);
+        let report: RiskReport = serde_json::from_value(done.result.unwrap()).unwrap();
+        assert_eq!(report.var.len(), 1);
+        assert!(report.var[0].var > 0.0);
+        assert_eq!(store.status(bogus).unwrap(), JobStatus::Failed);
+        assert!(matches!(store.get(12345), Err(JobError::NotFound(_))));
+
+        drop(store);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_risk_job_multiplier() {
+        let job = |multiplier: Option<f64>| {
+            let mut position = json!({
+                "underlying": "SPX", "option_type": "put", "strike": 100.0, "spot": 100.0,
+                "volatility": 0.2, "risk_free_rate": 0.05, "expiry": 1.0, "quantity": 2.0
+            });
+            if let Some(multiplier) = multiplier {
+                position["multiplier"] = json!(multiplier);
+            }
+            let spec: RiskJob = serde_json::from_value(json!({
+                "positions": [position], "factor_names": ["SPX"], "covariance": [[0.0004]],
+                "confidence": 0.99, "methods": ["parametric"]
+            }))
+            .unwrap();
+            spec.run().unwrap()
+        };
+        let single = job(None);
+        let listed = job(Some(100.0));
+        assert_eq!(listed.positions[0].multiplier, 100.0);
+        assert!((listed.totals.value - 100.0 * single.totals.value).abs() < 1e-9);
+        assert!((listed.var[0].var - 100.0 * single.var[0].var).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,9 @@
+// Building blocks of the pricing server: shared state and instrumentation used by the HTTP layer.
+pub mod batch;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "jobs")]
+pub mod jobs;
+pub mod metrics;
+pub mod rate_limit;
+pub mod validation;
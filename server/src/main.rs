@@ -0,0 +1,112 @@
+// Standalone REST pricing server, configured through environment variables:
+// OPTIONS_SERVER_ADDR (listen address), OPTIONS_SERVER_BURST and OPTIONS_SERVER_RATE (per-client
+// rate limit), OPTIONS_SERVER_API_KEYS (comma-separated keys given their own rate limit bucket)
+// and OPTIONS_SERVER_JOBS (job database path).
+use server::http::{AppState, router};
+#[cfg(feature = "jobs")]
+use server::jobs::{JobStore, run_job};
+use server::rate_limit::RateLimiter;
+use server::validation::RequestLimits;
+use std::env;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+const DEFAULT_BURST: u32 = 100;
+const DEFAULT_RATE: f64 = 50.0;
+#[cfg(feature = "jobs")]
+const DEFAULT_JOBS_PATH: &str = "jobs.db";
+// Idle wait of the job worker between polls of an empty queue
+#[cfg(feature = "jobs")]
+const JOB_POLL: Duration = Duration::from_millis(250);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+fn env_or<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("{} has an invalid value {:?}", name, value)),
+        Err(_) => Ok(default),
+    }
+}
+
+// Processes queued jobs one at a time until the process exits
+#[cfg(feature = "jobs")]
+fn work_jobs(state: Arc<AppState>) {
+    let Some(store) = state.jobs.as_ref() else {
+        return;
+    };
+    loop {
+        match store.run_next(run_job) {
+            Ok(Some(_)) => {}
+            Ok(None) => std::thread::sleep(JOB_POLL),
+            Err(err) => {
+                eprintln!("job worker: {}", err);
+                std::thread::sleep(JOB_POLL);
+            }
+        }
+        if let Ok(depth) = store.queue_depth() {
+            state.metrics.set_queue_depth(depth as i64);
+        }
+    }
+}
+
+async fn serve() -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = env_or("OPTIONS_SERVER_ADDR", DEFAULT_ADDR.parse()?)?;
+    let limiter = RateLimiter::new(
+        env_or("OPTIONS_SERVER_BURST", DEFAULT_BURST)?,
+        env_or("OPTIONS_SERVER_RATE", DEFAULT_RATE)?,
+    )?;
+    let api_keys = env::var("OPTIONS_SERVER_API_KEYS").unwrap_or_default();
+    let state = AppState::new(RequestLimits::default(), limiter).with_api_keys(
+        api_keys
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty()),
+    );
+    #[cfg(feature = "jobs")]
+    let state = state.with_jobs(JobStore::open(
+        env::var("OPTIONS_SERVER_JOBS").unwrap_or_else(|_| DEFAULT_JOBS_PATH.to_string()),
+    )?);
+    let state = Arc::new(state);
+
+    #[cfg(feature = "jobs")]
+    {
+        let worker = state.clone();
+        std::thread::spawn(move || work_jobs(worker));
+    }
+    let pruned = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            pruned.limiter.prune(Instant::now());
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("listening on {}", addr);
+    axum::serve(
+        listener,
+        router(state).into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async {
+        let _ = tokio::signal::ctrl_c().await;
+    })
+    .await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match serve().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
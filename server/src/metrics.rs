@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the request latency histogram buckets
+pub const LATENCY_BUCKETS: [f64; 10] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0,
+];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    counts: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(self.counts.iter_mut()) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Process-wide server metrics, rendered in the Prometheus text exposition format.
+#[derive(Debug)]
+pub struct Metrics {
+    latency: Mutex<BTreeMap<String, Histogram>>,
+    errors: Mutex<BTreeMap<String, u64>>,
+    queue_depth: AtomicI64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            latency: Mutex::new(BTreeMap::new()),
+            errors: Mutex::new(BTreeMap::new()),
+            queue_depth: AtomicI64::new(0),
+        }
+    }
+    /// Records the latency of one request served by the given engine
+    pub fn observe_latency(&self, engine: &str, elapsed: Duration) {
+        self.latency
+            .lock()
+            .unwrap()
+            .entry(engine.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+    /// Counts a failed pricing request, labelled by error kind
+    pub fn record_error(&self, kind: &str) {
+        *self
+            .errors
+            .lock()
+            .unwrap()
+            .entry(kind.to_string())
+            .or_insert(0) += 1;
+    }
+    pub fn set_queue_depth(&self, depth: i64) {
+        self.queue_depth.store(depth, Ordering::Relaxed);
+    }
+    /// Renders every metric for a `/metrics` scrape
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "# HELP pricing_request_duration_seconds Pricing request latency by engine.\n",
+        );
+        out.push_str("# TYPE pricing_request_duration_seconds histogram\n");
+        for (engine, histogram) in self.latency.lock().unwrap().iter() {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.counts) {
+                let _ = writeln!(
+                    out,
+                    "pricing_request_duration_seconds_bucket{{engine=\"{}\",le=\"{}\"}} {}",
+                    engine, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "pricing_request_duration_seconds_bucket{{engine=\"{}\",le=\"+Inf\"}} {}",
+                engine, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "pricing_request_duration_seconds_sum{{engine=\"{}\"}} {}",
+                engine, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "pricing_request_duration_seconds_count{{engine=\"{}\"}} {}",
+                engine, histogram.count
+            );
+        }
+        out.push_str("# HELP pricing_errors_total Failed pricing requests by error kind.\n");
+        out.push_str("# TYPE pricing_errors_total counter\n");
+        for (kind, count) in self.errors.lock().unwrap().iter() {
+            let _ = writeln!(out, "pricing_errors_total{{kind=\"{}\"}} {}", kind, count);
+        }
+        out.push_str("# HELP job_queue_depth Jobs waiting to be processed.\n");
+        out.push_str("# TYPE job_queue_depth gauge\n");
+        let _ = writeln!(
+            out,
+            "job_queue_depth {}",
+            self.queue_depth.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_exposition_format() {
+        let metrics = Metrics::new();
+        metrics.observe_latency("black_scholes", Duration::from_micros(300));
+        metrics.observe_latency("black_scholes", Duration::from_millis(20));
+        metrics.record_error("invalid_input");
+        metrics.set_queue_depth(2);
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "pricing_request_duration_seconds_bucket{engine=\"black_scholes\",le=\"0.0005\"} 1"
+        ));
+        // buckets are cumulative
+        assert!(text.contains(
+            "pricing_request_duration_seconds_bucket{engine=\"black_scholes\",le=\"0.025\"} 2"
+        ));
+        assert!(
+            text.contains("pricing_request_duration_seconds_count{engine=\"black_scholes\"} 2")
+        );
+        assert!(text.contains("pricing_errors_total{kind=\"invalid_input\"} 1"));
+        assert!(text.contains("job_queue_depth 2"));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token bucket: each client may burst up to `capacity` requests and is refilled at
+/// `refill_per_second`.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Rate limit configuration that cannot refill buckets or admit any request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitError {
+    ZeroCapacity,
+    /// The refill rate is zero, negative or not finite
+    InvalidRate(f64),
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitError::ZeroCapacity => write!(f, "rate limit burst must be at least 1"),
+            RateLimitError::InvalidRate(rate) => {
+                write!(
+                    f,
+                    "rate limit refill must be positive and finite, got {}",
+                    rate
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_per_second: f64) -> Result<Self, RateLimitError> {
+        if capacity == 0 {
+            return Err(RateLimitError::ZeroCapacity);
+        }
+        if !(refill_per_second > 0.0 && refill_per_second.is_finite()) {
+            return Err(RateLimitError::InvalidRate(refill_per_second));
+        }
+        Ok(RateLimiter {
+            capacity: capacity as f64,
+            refill_per_second,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+    /// Takes one token for the client, or returns how long to wait before retrying
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+    pub fn check_at(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_second))
+        }
+    }
+    /// Drops buckets of clients idle long enough to be full again
+    pub fn prune(&self, now: Instant) {
+        let full_after = self.capacity / self.refill_per_second;
+        self.buckets.lock().unwrap().retain(|_, bucket| {
+            now.saturating_duration_since(bucket.updated).as_secs_f64() < full_after
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_per_client() {
+        let limiter = RateLimiter::new(2, 1.0).unwrap();
+        let start = Instant::now();
+        assert!(limiter.check_at("a", start).is_ok());
+        assert!(limiter.check_at("a", start).is_ok());
+        let retry = limiter.check_at("a", start).unwrap_err();
+        assert!((retry.as_secs_f64() - 1.0).abs() < 1e-9);
+        // other clients have their own budget
+        assert!(limiter.check_at("b", start).is_ok());
+        // refilled after waiting
+        assert!(
+            limiter
+                .check_at("a", start + Duration::from_secs(1))
+                .is_ok()
+        );
+        limiter.prune(start + Duration::from_secs(10));
+        assert!(limiter.buckets.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_degenerate_limits() {
+        assert_eq!(
+            RateLimiter::new(0, 1.0).unwrap_err(),
+            RateLimitError::ZeroCapacity
+        );
+        for rate in [0.0, -1.0, f64::INFINITY] {
+            assert!(matches!(
+                RateLimiter::new(10, rate),
+                Err(RateLimitError::InvalidRate(_))
+            ));
+        }
+        assert!(RateLimiter::new(10, f64::NAN).is_err());
+    }
+}
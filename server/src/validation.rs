@@ -0,0 +1,158 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Bounds enforced on incoming requests before any pricing work is scheduled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLimits {
+    pub max_paths: usize,
+    pub max_steps: usize,
+    /// Largest number of contracts accepted in one batch request
+    pub max_batch: usize,
+    /// Engines clients may select; None allows every engine
+    pub allowed_engines: Option<BTreeSet<String>>,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        RequestLimits {
+            max_paths: 1_000_000,
+            max_steps: 10_000,
+            max_batch: 10_000,
+            allowed_engines: None,
+        }
+    }
+}
+
+/// Reason a request was refused by validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    TooManyPaths {
+        requested: usize,
+        limit: usize,
+    },
+    TooManySteps {
+        requested: usize,
+        limit: usize,
+    },
+    BatchTooLarge {
+        requested: usize,
+        limit: usize,
+    },
+    EngineNotAllowed(String),
+    /// A numeric field is NaN, infinite or outside its domain
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::TooManyPaths { requested, limit } => {
+                write!(f, "{} paths requested, limit is {}", requested, limit)
+            }
+            ValidationError::TooManySteps { requested, limit } => {
+                write!(f, "{} steps requested, limit is {}", requested, limit)
+            }
+            ValidationError::BatchTooLarge { requested, limit } => {
+                write!(f, "batch of {} contracts, limit is {}", requested, limit)
+            }
+            ValidationError::EngineNotAllowed(engine) => {
+                write!(f, "engine {} is not enabled on this server", engine)
+            }
+            ValidationError::InvalidField(field) => write!(f, "invalid value for {}", field),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl RequestLimits {
+    /// Limits that only accept the listed engines
+    pub fn with_engines(mut self, engines: &[&str]) -> Self {
+        self.allowed_engines = Some(engines.iter().map(|e| e.to_string()).collect());
+        self
+    }
+    pub fn check_engine(&self, engine: &str) -> Result<(), ValidationError> {
+        match &self.allowed_engines {
+            Some(allowed) if !allowed.contains(engine) => {
+                Err(ValidationError::EngineNotAllowed(engine.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Simulation size checks, preventing a single request from exhausting the server
+    pub fn check_simulation(&self, paths: usize, steps: usize) -> Result<(), ValidationError> {
+        if paths > self.max_paths {
+            return Err(ValidationError::TooManyPaths {
+                requested: paths,
+                limit: self.max_paths,
+            });
+        }
+        if steps > self.max_steps {
+            return Err(ValidationError::TooManySteps {
+                requested: steps,
+                limit: self.max_steps,
+            });
+        }
+        Ok(())
+    }
+    pub fn check_batch(&self, size: usize) -> Result<(), ValidationError> {
+        if size > self.max_batch {
+            return Err(ValidationError::BatchTooLarge {
+                requested: size,
+                limit: self.max_batch,
+            });
+        }
+        Ok(())
+    }
+    /// Domain checks on vanilla contract inputs
+    pub fn check_contract(
+        &self,
+        strike: f64,
+        spot: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        time_to_maturity: f64,
+    ) -> Result<(), ValidationError> {
+        let positive = [
+            ("strike", strike),
+            ("spot", spot),
+            ("volatility", volatility),
+            ("expiry", time_to_maturity),
+        ];
+        for (field, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(ValidationError::InvalidField(field));
+            }
+        }
+        if !risk_free_rate.is_finite() {
+            return Err(ValidationError::InvalidField("rate"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_limits() {
+        let limits = RequestLimits::default().with_engines(&["black_scholes"]);
+        assert!(limits.check_engine("black_scholes").is_ok());
+        assert_eq!(
+            limits.check_engine("heston_mc"),
+            Err(ValidationError::EngineNotAllowed("heston_mc".to_string()))
+        );
+        assert!(limits.check_simulation(10_000, 252).is_ok());
+        assert!(matches!(
+            limits.check_simulation(10_000_000, 252),
+            Err(ValidationError::TooManyPaths { .. })
+        ));
+        assert!(limits.check_batch(20_000).is_err());
+        assert!(limits.check_contract(100.0, 100.0, 0.2, 0.05, 1.0).is_ok());
+        assert_eq!(
+            limits.check_contract(100.0, 100.0, f64::NAN, 0.05, 1.0),
+            Err(ValidationError::InvalidField("volatility"))
+        );
+    }
+}